@@ -6,8 +6,9 @@ use std::{
 
 use anyhow::Result;
 use flux_bin::utils::{
-    get_flux_driver_path, get_rust_toolchain, get_rustc_driver_lib_path, prepend_path_to_env_var,
-    EXIT_ERR, LIB_PATH,
+    explain, explain_arg, get_bundled_fixpoint_path, get_flux_driver_path, get_rust_toolchain,
+    get_rustc_driver_lib_path, prepend_path_to_env_var, take_check_def_arg, EXIT_ERR,
+    FIXPOINT_PATH, FLUX_CHECK_DEF, LIB_PATH,
 };
 
 fn main() {
@@ -22,24 +23,36 @@ fn main() {
 }
 
 fn run() -> Result<i32> {
-    let flux_driver_path = get_flux_driver_path()?;
-    let rust_toolchain = get_rust_toolchain()?;
-    let ld_library_path = get_rustc_driver_lib_path(&rust_toolchain)?;
-    let extended_lib_path = prepend_path_to_env_var(LIB_PATH, ld_library_path)?;
-
     // Cargo can be called like `cargo [OPTIONS] flux`, so we skip all arguments until `flux` is
     // found.
-    let args = env::args()
+    let mut args = env::args()
         .skip_while(|arg| arg != "flux")
         .skip(1)
         .collect::<Vec<_>>();
 
+    if let Some(code) = explain_arg(&args) {
+        return Ok(explain(code));
+    }
+    let check_def = take_check_def_arg(&mut args);
+
+    let flux_driver_path = get_flux_driver_path()?;
+    let rust_toolchain = get_rust_toolchain()?;
+    let ld_library_path = get_rustc_driver_lib_path(&rust_toolchain)?;
+    let extended_lib_path = prepend_path_to_env_var(LIB_PATH, ld_library_path)?;
+
+    // Check all the members of the workspace unless some packages are selected explicitly.
+    // Members which are only dependencies of the selected packages are compiled to export their
+    // specs, but they are not checked.
+    if !args.iter().any(|arg| selects_packages(arg)) {
+        args.insert(0, "--workspace".to_string());
+    }
+
     let cargo_path = env::var("CARGO_PATH").unwrap_or_else(|_| "cargo".to_string());
     let cargo_target = env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
     let cargo_target = PathBuf::from_iter([cargo_target, "flux".to_string()]);
 
-    let exit_code = Command::new(cargo_path)
-        .arg("check")
+    let mut cmd = Command::new(cargo_path);
+    cmd.arg("check")
         .args(args)
         .env(LIB_PATH, extended_lib_path)
         // CODESYNC(build-sysroot, 5) Tell flux dependencies to build in flux mode.
@@ -49,9 +62,22 @@ fn run() -> Result<i32> {
         .env("RUST_TOOLCHAIN", rust_toolchain.clone())
         .env("RUSTUP_TOOLCHAIN", rust_toolchain)
         .env("RUSTC", flux_driver_path)
-        .env("CARGO_TARGET_DIR", cargo_target)
-        .status()?
-        .code();
+        .env("CARGO_TARGET_DIR", cargo_target);
+    if let Some(fixpoint_path) = get_bundled_fixpoint_path() {
+        cmd.env(FIXPOINT_PATH, fixpoint_path);
+    }
+    if let Some(filter) = check_def {
+        cmd.env(FLUX_CHECK_DEF, filter);
+    }
+    let exit_code = cmd.status()?.code();
 
     Ok(exit_code.unwrap_or(EXIT_ERR))
 }
+
+/// Whether `arg` is a flag selecting the packages to check, e.g., `-p foo` or `--package=foo`
+fn selects_packages(arg: &str) -> bool {
+    ["-p", "--package", "--workspace", "--all", "--manifest-path"]
+        .iter()
+        .any(|flag| arg == *flag || arg.starts_with(&format!("{flag}=")))
+        || (arg.starts_with("-p") && !arg.starts_with("--"))
+}
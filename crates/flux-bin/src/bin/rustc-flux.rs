@@ -5,8 +5,9 @@ use std::{
 
 use anyhow::Result;
 use flux_bin::utils::{
-    get_flux_driver_path, get_rust_toolchain, get_rustc_driver_lib_path, prepend_path_to_env_var,
-    sysroot_dir, EXIT_ERR, LIB_PATH,
+    get_bundled_fixpoint_path, get_flux_driver_path, get_rust_toolchain, get_rustc_driver_lib_path,
+    prepend_path_to_env_var, sysroot_dir, take_check_def_arg, EXIT_ERR, FIXPOINT_PATH,
+    FLUX_CHECK_DEF, LIB_PATH,
 };
 
 fn main() {
@@ -26,16 +27,24 @@ fn run() -> Result<i32> {
     let ld_library_path = get_rustc_driver_lib_path(&rust_toolchain)?;
     let extended_lib_path = prepend_path_to_env_var(LIB_PATH, ld_library_path)?;
 
-    let exit_code = Command::new(flux_driver_path)
-        // Skip the invocation of rustc-flux itself
-        .args(env::args().skip(1))
+    // Skip the invocation of rustc-flux itself
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    let check_def = take_check_def_arg(&mut args);
+
+    let mut cmd = Command::new(flux_driver_path);
+    cmd.args(args)
         .arg("-L")
         .arg(sysroot_dir())
         .arg("--extern")
         .arg("flux_rs")
-        .env(LIB_PATH, extended_lib_path)
-        .status()?
-        .code();
+        .env(LIB_PATH, extended_lib_path);
+    if let Some(fixpoint_path) = get_bundled_fixpoint_path() {
+        cmd.env(FIXPOINT_PATH, fixpoint_path);
+    }
+    if let Some(filter) = check_def {
+        cmd.env(FLUX_CHECK_DEF, filter);
+    }
+    let exit_code = cmd.status()?.code();
 
     Ok(exit_code.unwrap_or(EXIT_ERR))
 }
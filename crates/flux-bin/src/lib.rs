@@ -1 +1,5 @@
+// The table of error codes is shared with `flux-errors`, which can't be a dependency because it
+// requires `rustc_private`.
+#[path = "../../flux-errors/src/error_codes.rs"]
+pub mod error_codes;
 pub mod utils;
@@ -2,6 +2,8 @@ use std::{env, ffi::OsString, fs, path::PathBuf};
 
 use anyhow::{anyhow, Result};
 
+use crate::error_codes;
+
 #[cfg(target_os = "windows")]
 pub const LIB_PATH: &str = "PATH";
 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
@@ -38,6 +40,25 @@ pub fn get_flux_driver_path() -> Result<PathBuf> {
     Ok(path)
 }
 
+// CODESYNC(check-def-env) we must use the same env var in flux-config
+pub const FLUX_CHECK_DEF: &str = "FLUX_CHECK_DEF";
+
+// CODESYNC(fixpoint-path) we must use the same name used in xtask
+pub const FIXPOINT_PATH: &str = "FLUX_FIXPOINT_PATH";
+
+/// The fixpoint executable bundled in the sysroot if there's one. Setting [`FIXPOINT_PATH`]
+/// explicitly takes precedence over the bundled executable.
+pub fn get_bundled_fixpoint_path() -> Option<PathBuf> {
+    if env::var_os(FIXPOINT_PATH).is_some() {
+        return None;
+    }
+    let mut path = sysroot_dir().join("fixpoint");
+    if cfg!(target_os = "windows") {
+        path.set_extension("exe");
+    }
+    path.is_file().then_some(path)
+}
+
 pub fn get_rust_toolchain() -> Result<String> {
     let toolchain_str = include_str!("../../../rust-toolchain");
     let toolchain_file = rust_toolchain_file::toml::Parser::new(toolchain_str).parse()?;
@@ -82,3 +103,44 @@ pub fn prepend_path_to_env_var(var_name: &str, new_path: PathBuf) -> Result<OsSt
     paths.insert(0, new_path);
     env::join_paths(paths).map_err(anyhow::Error::from)
 }
+
+/// Returns the code passed with `--explain CODE` or `--explain=CODE`, if any
+pub fn explain_arg(args: &[String]) -> Option<&str> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == "--explain" {
+            args.get(i + 1).map(String::as_str)
+        } else {
+            arg.strip_prefix("--explain=")
+        }
+    })
+}
+
+/// Removes `--check-def FILTER` or `--check-def=FILTER` from the arguments and returns the filter,
+/// if any. The flag isn't understood by cargo or rustc, so it's passed to the driver through
+/// [`FLUX_CHECK_DEF`] instead.
+pub fn take_check_def_arg(args: &mut Vec<String>) -> Option<String> {
+    let i = args
+        .iter()
+        .position(|arg| arg == "--check-def" || arg.starts_with("--check-def="))?;
+    let arg = args.remove(i);
+    match arg.strip_prefix("--check-def=") {
+        Some(filter) => Some(filter.to_string()),
+        None if i < args.len() => Some(args.remove(i)),
+        None => None,
+    }
+}
+
+/// Prints the extended explanation of a flux error code, e.g., `EFLUX0003`, and returns the exit
+/// code of the process.
+pub fn explain(code: &str) -> i32 {
+    match error_codes::explanation(code) {
+        Some(explanation) => {
+            print!("{explanation}");
+            0
+        }
+        None => {
+            eprintln!("error: `{code}` is not a valid flux error code");
+            EXIT_ERR
+        }
+    }
+}
@@ -1,10 +1,20 @@
-use std::{fs::File, path::PathBuf};
+use std::{fs::File, path::PathBuf, sync::Mutex};
 
 use flux_config as config;
 use rustc_hash::FxHashMap;
 
+const CONSTRAINTS: &str = "constraints";
+const FNS: &str = "fns";
+/// Directory (inside the log directory) where the outputs of fixpoint are stored
+const RESULTS_DIR: &str = "fixpoint-results";
+
+/// The cache is shared by the threads solving fixpoint queries while functions are still being
+/// checked, so the tables are behind a lock.
 pub struct QueryCache {
-    entries: FxHashMap<String, u64>,
+    /// Hashes of the fixpoint constraints known to be safe
+    entries: Mutex<FxHashMap<String, u64>>,
+    /// Fingerprints of the functions that were checked successfully
+    fns: Mutex<FxHashMap<String, u64>>,
 }
 
 impl Default for QueryCache {
@@ -15,15 +25,62 @@ impl Default for QueryCache {
 
 impl QueryCache {
     pub fn new() -> Self {
-        QueryCache { entries: FxHashMap::default() }
+        QueryCache { entries: Mutex::default(), fns: Mutex::default() }
     }
 
-    pub fn insert(&mut self, key: String, constr_hash: u64) {
-        self.entries.insert(key, constr_hash);
+    pub fn insert(&self, key: String, constr_hash: u64) {
+        self.entries.lock().unwrap().insert(key, constr_hash);
     }
 
     pub fn is_safe(&self, key: &String, constr_hash: u64) -> bool {
-        config::is_cache_enabled() && self.entries.get(key).map_or(false, |h| *h == constr_hash)
+        config::is_cache_enabled()
+            && self
+                .entries
+                .lock()
+                .unwrap()
+                .get(key)
+                .map_or(false, |h| *h == constr_hash)
+    }
+
+    pub fn insert_fn(&self, key: String, fingerprint: u64) {
+        self.fns.lock().unwrap().insert(key, fingerprint);
+    }
+
+    pub fn is_fn_unchanged(&self, key: &String, fingerprint: u64) -> bool {
+        config::is_cache_enabled()
+            && self
+                .fns
+                .lock()
+                .unwrap()
+                .get(key)
+                .map_or(false, |h| *h == fingerprint)
+    }
+
+    /// Returns the output of fixpoint for the constraint with the given hash if it was stored on
+    /// disk by a previous run.
+    pub fn get_result(&self, constr_hash: u64) -> Option<Vec<u8>> {
+        if !config::is_cache_enabled() {
+            return None;
+        }
+        std::fs::read(Self::result_path(constr_hash)).ok()
+    }
+
+    /// Stores the output of fixpoint for the constraint with the given hash on disk.
+    pub fn insert_result(&self, constr_hash: u64, output: &[u8]) {
+        if !config::is_cache_enabled() {
+            return;
+        }
+        let path = Self::result_path(constr_hash);
+        if let Some(parent) = path.parent() {
+            // Failing to store a result only means it will be recomputed in the next run
+            let _ = std::fs::create_dir_all(parent).and_then(|_| std::fs::write(path, output));
+        }
+    }
+
+    fn result_path(constr_hash: u64) -> PathBuf {
+        config::log_dir()
+            .join(RESULTS_DIR)
+            .join(format!("{constr_hash:016x}.json"))
     }
 
     fn path() -> Result<PathBuf, std::io::Error> {
@@ -44,15 +101,24 @@ impl QueryCache {
     pub fn save(&self) -> Result<(), std::io::Error> {
         let path = Self::path()?;
         let mut file = File::create(path).unwrap();
-        serde_json::to_writer(&mut file, &self.entries).unwrap();
+        let entries = self.entries.lock().unwrap();
+        let fns = self.fns.lock().unwrap();
+        let mut tables = FxHashMap::default();
+        tables.insert(CONSTRAINTS, &*entries);
+        tables.insert(FNS, &*fns);
+        serde_json::to_writer(&mut file, &tables).unwrap();
         Ok(())
     }
 
     pub fn load() -> Self {
         if let Ok(path) = Self::path() {
             if let Ok(file) = File::open(path) {
-                if let Ok(entries) = serde_json::from_reader(file) {
-                    return QueryCache { entries };
+                if let Ok(mut tables) =
+                    serde_json::from_reader::<_, FxHashMap<String, FxHashMap<String, u64>>>(file)
+                {
+                    let entries = tables.remove(CONSTRAINTS).unwrap_or_default();
+                    let fns = tables.remove(FNS).unwrap_or_default();
+                    return QueryCache { entries: Mutex::new(entries), fns: Mutex::new(fns) };
                 }
             }
         }
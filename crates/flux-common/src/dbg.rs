@@ -30,6 +30,17 @@ pub fn dump_item_info<T: fmt::Debug>(
     writeln!(writer, "{val:#?}")
 }
 
+/// Writes `contents` to a file named after the item in [`config::emit_dir`], see `-Zflux-emit`
+pub fn emit_for_item(
+    tcx: TyCtxt,
+    def_id: impl Into<DefId>,
+    ext: impl AsRef<str>,
+    contents: &str,
+) -> io::Result<()> {
+    fs::create_dir_all(config::emit_dir())?;
+    fs::write(config::emit_dir().join(dump_base_name(tcx, def_id.into(), ext)), contents)
+}
+
 #[macro_export]
 macro_rules! _shape_mode_span {
     ($tcx:expr, $def_id:expr) => {{
@@ -15,6 +15,7 @@ pub mod format;
 pub mod index;
 pub mod iter;
 pub mod mir_storage;
+pub mod timings;
 
 pub mod bug;
 pub mod result;
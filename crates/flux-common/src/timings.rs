@@ -0,0 +1,118 @@
+//! Time spent in each phase of the analysis per item, enabled with `-Zflux-timings` (see
+//! [`config::timings`]).
+//!
+//! Phases can be nested, e.g., an item is desugared on demand the first time its signature is
+//! needed while checking another item. The time of a phase doesn't include the time of the phases
+//! nested inside it, so every instant is attributed to exactly one phase and item.
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    io::{self, Write},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use flux_config as config;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Phase {
+    Desugar,
+    WfCheck,
+    RefineCheck,
+    Encode,
+    Fixpoint,
+}
+
+impl Phase {
+    const ALL: [Phase; 5] =
+        [Phase::Desugar, Phase::WfCheck, Phase::RefineCheck, Phase::Encode, Phase::Fixpoint];
+
+    fn name(self) -> &'static str {
+        match self {
+            Phase::Desugar => "desugar",
+            Phase::WfCheck => "wf",
+            Phase::RefineCheck => "refineck",
+            Phase::Encode => "encode",
+            Phase::Fixpoint => "fixpoint",
+        }
+    }
+}
+
+/// Time spent in each [`Phase`] for an item
+#[derive(Default, Clone, Copy)]
+struct ItemTimings([Duration; Phase::ALL.len()]);
+
+impl ItemTimings {
+    fn total(&self) -> Duration {
+        self.0.iter().sum()
+    }
+
+    fn write_row(&self, w: &mut impl Write, name: &str) -> io::Result<()> {
+        for time in self.0 {
+            write!(w, "{:>10.3}", time.as_secs_f64())?;
+        }
+        writeln!(w, "{:>10.3}  {name}", self.total().as_secs_f64())
+    }
+}
+
+static TIMINGS: Mutex<BTreeMap<String, ItemTimings>> = Mutex::new(BTreeMap::new());
+
+thread_local! {
+    /// The time spent in nested phases for each phase currently running in this thread
+    static NESTED: RefCell<Vec<Duration>> = const { RefCell::new(vec![]) };
+}
+
+/// Runs `f` attributing the time it takes to `phase` for the item `def_id`
+pub fn time<R>(phase: Phase, tcx: TyCtxt, def_id: impl Into<DefId>, f: impl FnOnce() -> R) -> R {
+    if !config::timings() {
+        return f();
+    }
+    NESTED.with_borrow_mut(|nested| nested.push(Duration::ZERO));
+    let start = Instant::now();
+    let r = f();
+    let elapsed = start.elapsed();
+    let nested = NESTED.with_borrow_mut(|nested| {
+        let time = nested.pop().unwrap();
+        if let Some(parent) = nested.last_mut() {
+            *parent += elapsed;
+        }
+        time
+    });
+    record(phase, tcx.def_path_str(def_id.into()), elapsed.saturating_sub(nested));
+    r
+}
+
+/// Attributes `time` to `phase` for the item with path `def_path`. This is used for phases that
+/// don't run in a single block, e.g., the fixpoint queries which are solved in other threads.
+pub fn record(phase: Phase, def_path: String, time: Duration) {
+    if !config::timings() {
+        return;
+    }
+    let mut timings = TIMINGS.lock().unwrap();
+    timings.entry(def_path).or_default().0[phase as usize] += time;
+}
+
+/// Prints a table with the time spent in each phase per item, slowest items first.
+pub fn print(mut w: impl Write) -> io::Result<()> {
+    let timings = TIMINGS.lock().unwrap();
+    let mut items = timings.iter().collect::<Vec<_>>();
+    items.sort_by_key(|(_, timings)| std::cmp::Reverse(timings.total()));
+
+    let mut totals = ItemTimings::default();
+    for (_, timings) in &items {
+        for phase in Phase::ALL {
+            totals.0[phase as usize] += timings.0[phase as usize];
+        }
+    }
+
+    for phase in Phase::ALL {
+        write!(w, "{:>10}", phase.name())?;
+    }
+    writeln!(w, "{:>10}  item", "total")?;
+    for (def_path, timings) in items {
+        timings.write_row(&mut w, def_path)?;
+    }
+    totals.write_row(&mut w, "<total>")
+}
@@ -1,6 +1,6 @@
 #![feature(lazy_cell)]
 
-use std::{io::Read, path::PathBuf, sync::LazyLock};
+use std::{io::Read, path::PathBuf, str::FromStr, sync::LazyLock};
 
 use config::{Environment, File};
 use serde::Deserialize;
@@ -8,7 +8,18 @@ pub use toml::Value;
 
 const FLUX_ENV_VAR_PREFIX: &str = "FLUX";
 const FLUX_CONFIG_ENV_VAR: &str = "FLUX_CONFIG";
+pub const FLUX_UNSTABLE_FEATURES_ENV_VAR: &str = "FLUX_UNSTABLE_FEATURES";
+pub const FLUX_TIMINGS_ENV_VAR: &str = "FLUX_TIMINGS";
+pub const FLUX_EMIT_ENV_VAR: &str = "FLUX_EMIT";
+pub const FLUX_DUMP_REFINE_TREE_ENV_VAR: &str = "FLUX_DUMP_REFINE_TREE";
+pub const FLUX_TRACE_ENV_VAR: &str = "FLUX_TRACE";
+pub const FLUX_SHOW_INVARIANTS_ENV_VAR: &str = "FLUX_SHOW_INVARIANTS";
+pub const FLUX_SUGGEST_SIGS_ENV_VAR: &str = "FLUX_SUGGEST_SIGS";
+pub const FLUX_HOVER_ENV_VAR: &str = "FLUX_HOVER";
 
+/// Only check the definitions whose path contains this string, see `--check-def`. Specs are still
+/// collected and checked for well-formedness for the entire crate.
+// CODESYNC(check-def-env) `FLUX_CHECK_DEF` is set by `cargo-flux` and `rustc-flux`
 pub fn check_def() -> &'static str {
     &CONFIG.check_def
 }
@@ -17,6 +28,37 @@ pub fn dump_timings() -> bool {
     CONFIG.dump_timings
 }
 
+/// Whether to print the time spent in each phase of the analysis per item, see
+/// `-Zflux-timings`
+pub fn timings() -> bool {
+    CONFIG.timings
+}
+
+/// Functions whose path contains this string are traced step by step, see `-Zflux-trace`
+pub fn trace() -> Option<&'static str> {
+    if CONFIG.trace.is_empty() {
+        None
+    } else {
+        Some(&CONFIG.trace)
+    }
+}
+
+/// Position in the source, written as `path:line:column`, whose refined types are reported, see
+/// `-Zflux-hover`
+pub fn hover() -> Option<&'static str> {
+    if CONFIG.hover.is_empty() {
+        None
+    } else {
+        Some(&CONFIG.hover)
+    }
+}
+
+/// File with specs for items of the crate that can't be annotated directly. Defaults to the root of
+/// the crate with the extension `.flux`, e.g., `src/lib.flux`, if it exists.
+pub fn spec_file() -> Option<&'static PathBuf> {
+    CONFIG.spec_file.as_ref()
+}
+
 pub fn dump_checker_trace() -> bool {
     CONFIG.dump_checker_trace
 }
@@ -29,6 +71,22 @@ pub fn dump_constraint() -> bool {
     CONFIG.dump_constraint
 }
 
+/// Formats in which the constraint of each item (or the refined signatures of the crate) is written
+/// to [`emit_dir`], see `-Zflux-emit`
+pub fn emit() -> EmitFormats {
+    CONFIG.emit
+}
+
+/// Directory where constraints are emitted, defaults to [`log_dir`]
+pub fn emit_dir() -> &'static PathBuf {
+    CONFIG.emit_dir.as_ref().unwrap_or(&CONFIG.log_dir)
+}
+
+/// Format in which the refinement tree of each function is saved, see `-Zflux-dump-refine-tree`
+pub fn dump_refine_tree() -> Option<RefineTreeFormat> {
+    CONFIG.dump_refine_tree
+}
+
 pub fn dump_fhir() -> bool {
     CONFIG.dump_fhir
 }
@@ -45,6 +103,11 @@ pub fn log_dir() -> &'static PathBuf {
     &CONFIG.log_dir
 }
 
+/// Path of the fixpoint executable, by default it is looked up in `$PATH`
+pub fn fixpoint_path() -> &'static PathBuf {
+    &CONFIG.fixpoint_path
+}
+
 pub fn is_cache_enabled() -> bool {
     CONFIG.cache
 }
@@ -53,26 +116,113 @@ pub fn cache_path() -> PathBuf {
     log_dir().join(&CONFIG.cache_file)
 }
 
+/// Maximum number of fixpoint queries solved concurrently, `0` means one per available core
+pub fn solver_jobs() -> usize {
+    CONFIG.solver_jobs
+}
+
+/// Number of seconds after which solving the constraint of an item is aborted, `0` means no timeout
+pub fn timeout() -> u64 {
+    CONFIG.timeout
+}
+
+/// Whether to save per-item check times and constraint sizes in `FLUX_LOG_DIR/stats.json`
+pub fn stats() -> bool {
+    CONFIG.stats
+}
+
+pub fn summary() -> bool {
+    CONFIG.summary
+}
+
 pub fn check_overflow() -> bool {
     CONFIG.check_overflow
 }
 
+/// Whether to report the loop invariants inferred by fixpoint, see `-Zflux-show-invariants`
+pub fn show_invariants() -> bool {
+    CONFIG.show_invariants
+}
+
+/// Whether to suggest signatures for private functions without one, see `-Zflux-suggest-sigs`
+pub fn suggest_sigs() -> bool {
+    CONFIG.suggest_sigs
+}
+
 pub fn scrape_quals() -> bool {
     CONFIG.scrape_quals
 }
 
+pub fn mine_quals() -> bool {
+    CONFIG.mine_quals
+}
+
+pub fn auto_specs() -> bool {
+    CONFIG.auto_specs
+}
+
+pub fn check_panics() -> bool {
+    CONFIG.check_panics
+}
+
+pub fn warn_holes() -> bool {
+    CONFIG.warn_holes
+}
+
+pub fn unpack() -> UnpackPolicy {
+    CONFIG.unpack
+}
+
+pub fn solver() -> SmtSolver {
+    CONFIG.solver
+}
+
+pub fn solver_args() -> SolverArgs {
+    CONFIG.solver_args
+}
+
+pub fn unstable_features() -> UnstableFeatures {
+    let mut features = CONFIG.unstable_features;
+    // `FLUX_SMT_BACKEND=1` is a deprecated alias for enabling the `smt-backend` feature
+    if CONFIG.smt_backend {
+        features.enable(UnstableFeature::SmtBackend);
+    }
+    features
+}
+
+pub fn builtin_specs() -> bool {
+    CONFIG.builtin_specs
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct CrateConfig {
     pub check_overflow: bool,
     pub scrape_quals: bool,
+    pub mine_quals: bool,
+    pub auto_specs: bool,
+    pub check_panics: bool,
+    pub warn_holes: bool,
+    pub unpack: UnpackPolicy,
+    pub solver: SmtSolver,
+    pub solver_args: SolverArgs,
+    pub timeout: u64,
+    pub unstable_features: UnstableFeatures,
 }
 
 #[derive(Deserialize)]
 struct Config {
     log_dir: PathBuf,
+    fixpoint_path: PathBuf,
     dump_constraint: bool,
+    emit: EmitFormats,
+    emit_dir: Option<PathBuf>,
+    dump_refine_tree: Option<RefineTreeFormat>,
     dump_checker_trace: bool,
+    trace: String,
+    hover: String,
+    spec_file: Option<PathBuf>,
     dump_timings: bool,
+    timings: bool,
     dump_fhir: bool,
     dump_rty: bool,
     dump_mir: bool,
@@ -80,8 +230,24 @@ struct Config {
     check_def: String,
     cache: bool,
     cache_file: String,
+    solver_jobs: usize,
+    smt_backend: bool,
+    timeout: u64,
+    stats: bool,
+    summary: bool,
+    show_invariants: bool,
+    suggest_sigs: bool,
     check_overflow: bool,
     scrape_quals: bool,
+    mine_quals: bool,
+    auto_specs: bool,
+    check_panics: bool,
+    warn_holes: bool,
+    unpack: UnpackPolicy,
+    solver: SmtSolver,
+    solver_args: SolverArgs,
+    unstable_features: UnstableFeatures,
+    builtin_specs: bool,
 }
 
 #[derive(Copy, Clone, Deserialize)]
@@ -112,14 +278,249 @@ impl TryFrom<u8> for PointerWidth {
     }
 }
 
+/// How aggressively existential types are unpacked when they are added to the environment
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnpackPolicy {
+    /// Only unpack the outermost existential
+    Shallow,
+    /// Unpack through boxes, shared references and tuples, but not under mutable references
+    Default,
+    /// Also unpack under mutable references
+    Deep,
+}
+
+impl FromStr for UnpackPolicy {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shallow" => Ok(UnpackPolicy::Shallow),
+            "default" => Ok(UnpackPolicy::Default),
+            "deep" => Ok(UnpackPolicy::Deep),
+            _ => Err("unpack policy must be `shallow`, `default` or `deep`"),
+        }
+    }
+}
+
+/// The SMT solver used to discharge the constraints
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtSolver {
+    Z3,
+    Cvc5,
+}
+
+impl SmtSolver {
+    pub fn name(self) -> &'static str {
+        match self {
+            SmtSolver::Z3 => "z3",
+            SmtSolver::Cvc5 => "cvc5",
+        }
+    }
+}
+
+impl FromStr for SmtSolver {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "z3" => Ok(SmtSolver::Z3),
+            "cvc5" => Ok(SmtSolver::Cvc5),
+            _ => Err("solver must be `z3` or `cvc5`"),
+        }
+    }
+}
+
+/// Extra command line arguments for the SMT solver, written as a whitespace separated list. They are
+/// specific to each solver, e.g., `-T:10` sets a timeout in `z3`.
+///
+/// Settings are parsed a handful of times per run, so we leak the string to keep configurations
+/// [`Copy`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(from = "String")]
+pub struct SolverArgs(&'static str);
+
+impl SolverArgs {
+    pub fn iter(self) -> impl Iterator<Item = &'static str> {
+        self.0.split_whitespace()
+    }
+}
+
+impl From<String> for SolverArgs {
+    fn from(value: String) -> Self {
+        SolverArgs(Box::leak(value.into_boxed_str()))
+    }
+}
+
+impl FromStr for SolverArgs {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(SolverArgs::from(s.to_string()))
+    }
+}
+
+/// An experimental capability of the checker that has to be enabled explicitly
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UnstableFeature {
+    /// Checking constraints without kvars directly with the SMT solver instead of fixpoint
+    SmtBackend,
+    /// `match` expressions over reflected enums in refinements
+    MatchExprs,
+}
+
+impl UnstableFeature {
+    pub const ALL: &'static [UnstableFeature] =
+        &[UnstableFeature::SmtBackend, UnstableFeature::MatchExprs];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            UnstableFeature::SmtBackend => "smt-backend",
+            UnstableFeature::MatchExprs => "match-exprs",
+        }
+    }
+}
+
+/// The set of enabled unstable features, written as a comma separated list of feature names, e.g.,
+/// `-Zflux-unstable-features=smt-backend`
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct UnstableFeatures {
+    smt_backend: bool,
+    match_exprs: bool,
+}
+
+impl UnstableFeatures {
+    pub fn is_enabled(self, feature: UnstableFeature) -> bool {
+        match feature {
+            UnstableFeature::SmtBackend => self.smt_backend,
+            UnstableFeature::MatchExprs => self.match_exprs,
+        }
+    }
+
+    fn enable(&mut self, feature: UnstableFeature) {
+        match feature {
+            UnstableFeature::SmtBackend => self.smt_backend = true,
+            UnstableFeature::MatchExprs => self.match_exprs = true,
+        }
+    }
+}
+
+impl FromStr for UnstableFeatures {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut features = UnstableFeatures::default();
+        for name in s.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let Some(feature) = UnstableFeature::ALL.iter().find(|f| f.name() == name) else {
+                let known = UnstableFeature::ALL
+                    .iter()
+                    .map(|f| f.name())
+                    .collect::<Vec<_>>();
+                return Err(format!(
+                    "unknown unstable feature `{name}`, expected one of: {}",
+                    known.join(", ")
+                ));
+            };
+            features.enable(*feature);
+        }
+        Ok(features)
+    }
+}
+
+impl TryFrom<String> for UnstableFeatures {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Format of the dump of the refinement tree
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RefineTreeFormat {
+    /// The textual format also used by `dump_constraint` (`.fluxc`)
+    Text,
+    /// A graphviz graph (`.dot`)
+    Dot,
+}
+
+/// The formats in which constraints are emitted, written as a comma separated list, e.g.,
+/// `-Zflux-emit=smtlib,fixpoint`
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct EmitFormats {
+    /// The input format of fixpoint (`.fq`)
+    pub fixpoint: bool,
+    /// Constrained Horn clauses in SMT-LIB2 (`.smt2`)
+    pub smtlib: bool,
+    /// The refined signatures of all the items in the crate as json (`.refined-sigs.json`). Unlike
+    /// the other formats, this is a single file for the entire crate.
+    pub refined_sigs: bool,
+    /// Property tests of the functions in the crate generated from their signatures
+    /// (`.proptest.rs`), also a single file for the entire crate
+    pub proptest: bool,
+    /// Kani proof harnesses of the functions in the crate generated from their signatures
+    /// (`.kani.rs`), also a single file for the entire crate
+    pub kani: bool,
+}
+
+impl EmitFormats {
+    pub fn any(self) -> bool {
+        self.fixpoint || self.smtlib || self.refined_sigs || self.proptest || self.kani
+    }
+}
+
+impl FromStr for EmitFormats {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut formats = EmitFormats::default();
+        for name in s.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            match name {
+                "fixpoint" => formats.fixpoint = true,
+                "smtlib" => formats.smtlib = true,
+                "refined-sigs" => formats.refined_sigs = true,
+                "proptest" => formats.proptest = true,
+                "kani" => formats.kani = true,
+                _ => {
+                    return Err(format!(
+                        "unknown emit format `{name}`, expected one of: fixpoint, smtlib, \
+                         refined-sigs, proptest, kani"
+                    ))
+                }
+            }
+        }
+        Ok(formats)
+    }
+}
+
+impl TryFrom<String> for EmitFormats {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
 static CONFIG: LazyLock<Config> = LazyLock::new(|| {
     fn build() -> Result<Config, config::ConfigError> {
         let mut config_builder = config::Config::builder()
             .set_default("driver_path", None::<String>)?
             .set_default("log_dir", "./log/")?
+            .set_default("fixpoint_path", "fixpoint")?
             .set_default("dump_constraint", false)?
+            .set_default("emit", "")?
+            .set_default("emit_dir", None::<String>)?
+            .set_default("dump_refine_tree", None::<String>)?
             .set_default("dump_checker_trace", false)?
+            .set_default("trace", "")?
+            .set_default("hover", "")?
+            .set_default("spec_file", None::<String>)?
             .set_default("dump_timings", false)?
+            .set_default("timings", false)?
             .set_default("dump_mir", false)?
             .set_default("dump_fhir", false)?
             .set_default("dump_rty", false)?
@@ -128,8 +529,24 @@ static CONFIG: LazyLock<Config> = LazyLock::new(|| {
             .set_default("check_def", "")?
             .set_default("cache", false)?
             .set_default("cache_file", "cache.json")?
+            .set_default("solver_jobs", 0)?
+            .set_default("smt_backend", false)?
+            .set_default("timeout", 0)?
+            .set_default("stats", false)?
+            .set_default("summary", false)?
+            .set_default("show_invariants", false)?
+            .set_default("suggest_sigs", false)?
             .set_default("check_overflow", false)?
-            .set_default("scrape_quals", false)?;
+            .set_default("scrape_quals", false)?
+            .set_default("mine_quals", false)?
+            .set_default("auto_specs", false)?
+            .set_default("check_panics", false)?
+            .set_default("warn_holes", false)?
+            .set_default("unpack", "default")?
+            .set_default("solver", "z3")?
+            .set_default("solver_args", "")?
+            .set_default("unstable_features", "")?
+            .set_default("builtin_specs", true)?;
         // Config comes first, enviroment settings override it.
         if let Some(config_path) = CONFIG_PATH.as_ref() {
             config_builder = config_builder.add_source(File::from(config_path.clone()));
@@ -175,6 +592,18 @@ pub static CONFIG_FILE: LazyLock<Value> = LazyLock::new(|| {
 
 impl Default for CrateConfig {
     fn default() -> Self {
-        Self { check_overflow: check_overflow(), scrape_quals: scrape_quals() }
+        Self {
+            check_overflow: check_overflow(),
+            scrape_quals: scrape_quals(),
+            mine_quals: mine_quals(),
+            auto_specs: auto_specs(),
+            check_panics: check_panics(),
+            warn_holes: warn_holes(),
+            unpack: unpack(),
+            solver: solver(),
+            solver_args: solver_args(),
+            timeout: timeout(),
+            unstable_features: unstable_features(),
+        }
     }
 }
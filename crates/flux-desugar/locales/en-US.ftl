@@ -16,6 +16,14 @@ desugar_unexpected_literal =
 desugar_invalid_dot_var =
     unsupported field access in refinement
 
+desugar_invalid_match_arm =
+    invalid pattern in refinement `match`
+    .note = each arm must match a distinct variant of the same reflected enum
+
+desugar_non_exhaustive_match =
+    non-exhaustive `match` in refinement
+    .note = there must be one arm for each variant of the matched enum
+
 desugar_invalid_func_as_var =
     invalid use of function
     .label = function not supported in this position
@@ -54,6 +62,19 @@ desugar_unresolved_var =
     cannot find {$kind} `{$var}` in this scope
     .label = not found in this scope
 
+desugar_unstable_feature =
+    use of unstable feature `{$feature}`
+    .help = enable it with `-Zflux-unstable-features={$feature}` or `#![flux::cfg(unstable_features = "{$feature}")]`
+
+desugar_duplicate_flux_item =
+    the name `{$name}` is defined multiple times
+    .label = redefined here
+    .first_definition = previous definition of `{$name}` here
+
+desugar_unresolved_qualifier =
+    cannot find qualifier `{$name}` in this scope
+    .label = not found in this scope
+
 desugar_unresolved_sort =
     cannot find sort `{$sort}` in this scope
     .label = not found in this scope
@@ -333,7 +333,11 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
             else {
                 bug!("expected struct")
             };
-            debug_assert_eq!(struct_def.fields.len(), variant_data.fields().len());
+            // The dummy struct of an extern spec has an extra field for the external struct
+            debug_assert_eq!(
+                struct_def.fields.len() + struct_def.extern_id.is_some() as usize,
+                variant_data.fields().len()
+            );
             let fields = try_alloc_slice!(
                 self.genv,
                 iter::zip(&struct_def.fields, variant_data.fields()),
@@ -372,14 +376,23 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
         let ItemKind::Enum(hir_enum, _) = self.genv.hir().expect_item(def_id).kind else {
             bug!("expected enum");
         };
-        let variants = try_alloc_slice!(
-            self.genv,
-            iter::zip(&enum_def.variants, hir_enum.variants),
-            |(variant, hir_variant)| self.desugar_enum_variant_def(variant, hir_variant)
-        )?;
+        let variants = if enum_def.reflected {
+            let hir_variants = hir_enum.variants.iter().enumerate();
+            try_alloc_slice!(self.genv, hir_variants, |(idx, hir_variant)| {
+                self.desugar_reflected_variant(idx, hir_variant)
+            })?
+        } else {
+            try_alloc_slice!(
+                self.genv,
+                iter::zip(&enum_def.variants, hir_enum.variants),
+                |(variant, hir_variant)| self.desugar_enum_variant_def(variant, hir_variant)
+            )?
+        };
 
         let refined_by = if let Some(refined_by) = &enum_def.refined_by {
             self.desugar_refined_by(refined_by)?
+        } else if enum_def.reflected {
+            reflected_refined_by(self.genv.hir().expect_item(def_id).ident.span)
         } else {
             self.as_lift_cx().lift_refined_by()
         };
@@ -442,6 +455,28 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
         }
     }
 
+    /// The `i`-th variant of a reflected enum is indexed by `{ tag: i }`
+    fn desugar_reflected_variant(
+        &mut self,
+        idx: usize,
+        hir_variant: &hir::Variant,
+    ) -> Result<fhir::VariantDef<'genv>> {
+        let mut variant = self.as_lift_cx().lift_enum_variant(hir_variant)?;
+        let span = variant.ret.idx.span;
+        let tag = fhir::Expr {
+            kind: fhir::ExprKind::Literal(fhir::Lit::Int(idx as i128)),
+            span,
+            fhir_id: self.next_fhir_id(),
+        };
+        let tag = fhir::RefineArg {
+            kind: fhir::RefineArgKind::Expr(tag),
+            fhir_id: self.next_fhir_id(),
+            span,
+        };
+        variant.ret.idx.kind = fhir::RefineArgKind::Record(self.genv.alloc_slice(&[tag]));
+        Ok(variant)
+    }
+
     fn desugar_generics_for_adt(
         &mut self,
         generics: Option<&surface::Generics>,
@@ -508,7 +543,7 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
 
             // Bail out if there's an error in the arguments to avoid confusing error messages
             let args = try_alloc_slice!(self.genv, &fn_sig.args, |arg| {
-                self.desugar_fun_arg(arg, &mut requires)
+                self.desugar_fun_arg(arg, &fn_sig.output.ensures, &mut requires)
             })?;
 
             let output = self.desugar_fn_output(fn_sig.asyncness, &fn_sig.output)?;
@@ -530,6 +565,8 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
         let qual_names = fn_spec.qual_names.as_ref().map_or(&[][..], |it| &it.names);
         Ok(fhir::FnSig {
             trusted: fn_spec.trusted,
+            inline: fn_spec.inline,
+            should_fail: fn_spec.should_fail,
             qualifiers: self.genv.alloc_slice(qual_names),
             decl: self.genv.alloc(decl),
         })
@@ -615,8 +652,12 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
     fn desugar_fun_arg(
         &mut self,
         arg: &surface::Arg,
+        ensures: &[surface::Constraint],
         requires: &mut Vec<fhir::Constraint<'genv>>,
     ) -> Result<fhir::Ty<'genv>> {
+        if let Some((loc, ty, node_id)) = ensured_mut_ref(arg, ensures) {
+            return self.desugar_strg_ref(loc, ty, node_id, requires);
+        }
         match arg {
             surface::Arg::Constr(bind, path, pred, node_id) => {
                 let bty = self.desugar_path_to_bty(None, path)?;
@@ -634,18 +675,7 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
                 Ok(fhir::Ty { kind, span })
             }
             surface::Arg::StrgRef(loc, ty, node_id) => {
-                let span = loc.span;
-                let (id, kind) = self.resolve_implicit_param(*node_id).unwrap();
-                let path = fhir::PathExpr {
-                    segments: self.genv.alloc_slice(&[*loc]),
-                    res: ExprRes::Param(kind, id),
-                    fhir_id: self.next_fhir_id(),
-                    span: loc.span,
-                };
-                let ty = self.desugar_ty(ty)?;
-                requires.push(fhir::Constraint::Type(path, ty));
-                let kind = fhir::TyKind::Ptr(self.mk_lft_hole(), path);
-                Ok(fhir::Ty { kind, span })
+                self.desugar_strg_ref(*loc, ty, *node_id, requires)
             }
             surface::Arg::Ty(bind, ty, node_id) => {
                 if let Some(bind) = bind
@@ -666,6 +696,28 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
         }
     }
 
+    /// Desugars a strong reference `loc: &strg T` into a pointer to `loc` with the requirement
+    /// `loc: T` added to `requires`.
+    fn desugar_strg_ref(
+        &mut self,
+        loc: surface::Ident,
+        ty: &surface::Ty,
+        node_id: NodeId,
+        requires: &mut Vec<fhir::Constraint<'genv>>,
+    ) -> Result<fhir::Ty<'genv>> {
+        let (id, kind) = self.resolve_implicit_param(node_id).unwrap();
+        let path = fhir::PathExpr {
+            segments: self.genv.alloc_slice(&[loc]),
+            res: ExprRes::Param(kind, id),
+            fhir_id: self.next_fhir_id(),
+            span: loc.span,
+        };
+        let ty = self.desugar_ty(ty)?;
+        requires.push(fhir::Constraint::Type(path, ty));
+        let kind = fhir::TyKind::Ptr(self.mk_lft_hole(), path);
+        Ok(fhir::Ty { kind, span: loc.span })
+    }
+
     fn desugar_asyncness(
         &mut self,
         asyncness: surface::Async,
@@ -1035,6 +1087,17 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
                     fhir::ArrayLen { val: len.val, span: len.span },
                 )
             }
+            surface::TyKind::BareFn(bare_fn) => {
+                let inputs =
+                    try_alloc_slice!(self.genv(), &bare_fn.inputs, |ty| self.desugar_ty(ty))?;
+                let output = self.desugar_fn_ret_ty(&bare_fn.output)?;
+                let bare_fn = fhir::BareFnTy {
+                    bound_generic_params: &[],
+                    inputs,
+                    output: self.genv().alloc(output),
+                };
+                fhir::TyKind::BareFn(self.genv().alloc(bare_fn))
+            }
             surface::TyKind::ImplTrait(node_id, bounds) => {
                 self.desugar_impl_trait(*node_id, bounds)?
             }
@@ -1225,11 +1288,81 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
                     self.genv().alloc(e2?),
                 )
             }
+            surface::ExprKind::Match(box scrutinee, arms) => {
+                return self.desugar_match(expr.span, scrutinee, arms);
+            }
         };
 
         Ok(fhir::Expr { kind, span: expr.span, fhir_id: self.next_fhir_id() })
     }
 
+    /// Desugars `match e { V1 => e1, ..., Vn => en }` to `if e == V1 { e1 } else if .. { en }`. The
+    /// arms must match every variant of a reflected enum exactly once, so the last one doesn't need
+    /// a test.
+    fn desugar_match(
+        &mut self,
+        span: Span,
+        scrutinee: &surface::Expr,
+        arms: &[surface::MatchArm],
+    ) -> Result<fhir::Expr<'genv>> {
+        self.check_match_arms(span, arms)?;
+        let (last, rest) = arms.split_last().unwrap();
+        let mut expr = self.desugar_expr(&last.body)?;
+        for arm in rest.iter().rev() {
+            let scrutinee = self.desugar_expr(scrutinee)?;
+            let pat = fhir::Expr {
+                kind: self.desugar_var(&arm.pat)?,
+                span: arm.pat.span,
+                fhir_id: self.next_fhir_id(),
+            };
+            let cond = fhir::Expr {
+                kind: fhir::ExprKind::BinaryOp(
+                    fhir::BinOp::Eq,
+                    self.genv().alloc(scrutinee),
+                    self.genv().alloc(pat),
+                ),
+                span: arm.pat.span,
+                fhir_id: self.next_fhir_id(),
+            };
+            let body = self.desugar_expr(&arm.body)?;
+            expr = fhir::Expr {
+                kind: fhir::ExprKind::IfThenElse(
+                    self.genv().alloc(cond),
+                    self.genv().alloc(body),
+                    self.genv().alloc(expr),
+                ),
+                span: arm.span.to(last.span),
+                fhir_id: self.next_fhir_id(),
+            };
+        }
+        Ok(fhir::Expr { span, ..expr })
+    }
+
+    fn check_match_arms(&self, span: Span, arms: &[surface::MatchArm]) -> Result {
+        let tcx = self.genv().tcx();
+        let mut enum_def_id = None;
+        let mut matched = FxHashSet::default();
+        for arm in arms {
+            let res = self
+                .resolver_output()
+                .path_expr_res_map
+                .get(&arm.pat.node_id);
+            let Some(ExprRes::Variant(variant_def_id)) = res else {
+                return Err(self.emit_err(errors::InvalidMatchArm { span: arm.pat.span }));
+            };
+            let parent = tcx.parent(*variant_def_id);
+            if *enum_def_id.get_or_insert(parent) != parent || !matched.insert(*variant_def_id) {
+                return Err(self.emit_err(errors::InvalidMatchArm { span: arm.pat.span }));
+            }
+        }
+        match enum_def_id {
+            Some(enum_def_id) if tcx.adt_def(enum_def_id).variants().len() == matched.len() => {
+                Ok(())
+            }
+            _ => Err(self.emit_err(errors::NonExhaustiveMatch { span })),
+        }
+    }
+
     fn desugar_exprs(&mut self, exprs: &[surface::Expr]) -> Result<&'genv [fhir::Expr<'genv>]> {
         try_alloc_slice!(self.genv(), exprs, |e| self.desugar_expr(e))
     }
@@ -1277,6 +1410,36 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
     }
 }
 
+/// An `ensures` clause can mention an argument of type `&mut T` directly, e.g.,
+/// `fn(x: &mut i32[@n]) ensures x: i32[n + 1]`. The clause talks about the value behind the
+/// reference when the borrow ends (i.e., when the function returns). We support this by treating
+/// the argument as a strong reference `x: &strg i32[@n]`: the callee has to prove the ensured type
+/// on return, and a caller passing an ordinary `&mut` gets the referent checked against the type of
+/// its reference when the call finishes. Returns the name of the argument, the type `T` and the
+/// node id of the argument if `arg` is such an argument.
+pub(crate) fn ensured_mut_ref<'a>(
+    arg: &'a surface::Arg,
+    ensures: &[surface::Constraint],
+) -> Option<(surface::Ident, &'a surface::Ty, NodeId)> {
+    let surface::Arg::Ty(Some(bind), ty, node_id) = arg else { return None };
+    let surface::TyKind::Ref(surface::Mutability::Mut, ty) = &ty.kind else { return None };
+    let is_ensured = ensures
+        .iter()
+        .any(|cstr| matches!(cstr, surface::Constraint::Type(loc, ..) if loc.name == bind.name));
+    is_ensured.then_some((*bind, ty, *node_id))
+}
+
+/// A reflected enum is refined by the position of its variant, e.g., `Ordering[{ tag: 0 }]`
+fn reflected_refined_by<'genv>(span: Span) -> fhir::RefinedBy<'genv> {
+    let sort = fhir::Sort::Path(fhir::SortPath {
+        res: fhir::SortRes::PrimSort(fhir::PrimSort::Int),
+        segment: surface::Ident::new(SORTS.int, span),
+        args: &[],
+    });
+    let fields = [(Symbol::intern("tag"), sort)].into_iter().collect();
+    fhir::RefinedBy::new(fields, Default::default(), span)
+}
+
 fn desugar_sort<'genv>(
     genv: GlobalEnv<'genv, '_>,
     resolver_output: &ResolverOutput,
@@ -24,6 +24,22 @@ pub(super) struct InvalidDotVar {
     pub(super) span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(desugar_invalid_match_arm, code = E0999)]
+#[note]
+pub(super) struct InvalidMatchArm {
+    #[primary_span]
+    pub(super) span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(desugar_non_exhaustive_match, code = E0999)]
+#[note]
+pub(super) struct NonExhaustiveMatch {
+    #[primary_span]
+    pub(super) span: Span,
+}
+
 #[derive(Diagnostic)]
 #[diag(desugar_invalid_func_as_var, code = E0999)]
 pub(super) struct InvalidFuncAsVar {
@@ -47,6 +47,7 @@ fn try_resolve_crate(genv: GlobalEnv) -> Result<ResolverOutput> {
     let mut resolver = CrateResolver::new(genv, specs);
 
     resolver.collect_flux_global_items();
+    resolver.check_duplicate_flux_items();
 
     for qualifier in &specs.qualifs {
         collect_err!(resolver, resolver.resolve_qualifier(qualifier));
@@ -221,6 +222,10 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
             self.consts.insert(sym, did);
         }
 
+        for decl in self.genv.cstore().func_decls() {
+            self.func_decls.insert(decl.name, decl.kind);
+        }
+
         for defn in &self.specs.func_defs {
             let kind =
                 if defn.body.is_some() { fhir::SpecFuncKind::Def } else { fhir::SpecFuncKind::Uif };
@@ -233,6 +238,40 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
         }
     }
 
+    /// Reports qualifiers and spec functions with the same name. They are visible throughout the
+    /// crate regardless of the module declaring them, so their names must be unique in the crate.
+    fn check_duplicate_flux_items(&mut self) {
+        let mut defined: FxHashMap<Symbol, Ident> = FxHashMap::default();
+        let names = self
+            .specs
+            .qualifs
+            .iter()
+            .map(|qualifier| qualifier.name)
+            .chain(self.specs.func_defs.iter().map(|defn| defn.name));
+        for name in names {
+            if let Some(first) = defined.insert(name.name, name) {
+                self.err = Some(
+                    self.genv
+                        .sess()
+                        .emit_err(errors::DuplicateFluxItem::new(first, name)),
+                );
+            }
+        }
+    }
+
+    /// Whether `name` is a qualifier declared in this crate or in one of its dependencies
+    fn is_qualifier(&self, name: Symbol) -> bool {
+        self.specs
+            .qualifs
+            .iter()
+            .any(|qualifier| qualifier.name.name == name)
+            || self
+                .genv
+                .cstore()
+                .qualifiers()
+                .any(|qualifier| qualifier.name == name)
+    }
+
     fn push_rib(&mut self) {
         self.ribs.push(Rib::default());
     }
@@ -297,7 +336,18 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
     }
 
     fn resolve_fn_sig(&mut self, owner_id: OwnerId) -> Result {
-        if let Some(fn_sig) = &self.specs.fn_sigs[&owner_id].fn_sig {
+        let fn_spec = &self.specs.fn_sigs[&owner_id];
+        if let Some(qual_names) = &fn_spec.qual_names {
+            for qual in &qual_names.names {
+                if !self.is_qualifier(qual.name) {
+                    return Err(self
+                        .genv
+                        .sess()
+                        .emit_err(errors::UnresolvedQualifier::new(*qual)));
+                }
+            }
+        }
+        if let Some(fn_sig) = &fn_spec.fn_sig {
             ItemResolver::run(self, owner_id, |item_resolver| {
                 item_resolver.visit_fn_sig(fn_sig);
             })?;
@@ -730,4 +780,36 @@ mod errors {
             }
         }
     }
+
+    #[derive(Diagnostic)]
+    #[diag(desugar_duplicate_flux_item, code = E0999)]
+    pub(super) struct DuplicateFluxItem {
+        #[primary_span]
+        #[label]
+        span: Span,
+        name: Symbol,
+        #[label(desugar_first_definition)]
+        first_definition: Span,
+    }
+
+    impl DuplicateFluxItem {
+        pub(super) fn new(first: Ident, second: Ident) -> Self {
+            Self { span: second.span, name: second.name, first_definition: first.span }
+        }
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(desugar_unresolved_qualifier, code = E0999)]
+    pub(super) struct UnresolvedQualifier {
+        #[primary_span]
+        #[label]
+        span: Span,
+        name: Symbol,
+    }
+
+    impl UnresolvedQualifier {
+        pub(super) fn new(ident: Ident) -> Self {
+            Self { span: ident.span, name: ident.name }
+        }
+    }
 }
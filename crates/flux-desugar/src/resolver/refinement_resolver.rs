@@ -1,6 +1,7 @@
 use std::ops::ControlFlow;
 
 use flux_common::index::IndexGen;
+use flux_config::UnstableFeature;
 use flux_errors::Errors;
 use flux_middle::{
     fhir::{self, ExprRes},
@@ -17,9 +18,10 @@ use rustc_data_structures::{
 use rustc_hash::FxHashMap;
 use rustc_hir::{self as hir, def::DefKind, OwnerId};
 use rustc_middle::ty::TyCtxt;
-use rustc_span::{sym, symbol::kw, ErrorGuaranteed, Symbol};
+use rustc_span::{sym, symbol::kw, ErrorGuaranteed, Span, Symbol};
 
 use super::CrateResolver;
+use crate::desugar::ensured_mut_ref;
 
 type Result<T = ()> = std::result::Result<T, ErrorGuaranteed>;
 
@@ -70,6 +72,7 @@ pub(crate) trait ScopedVisitor: Sized {
     fn on_func(&mut self, _func: Ident, _node_id: NodeId) {}
     fn on_path(&mut self, _path: &surface::PathExpr) {}
     fn on_base_sort(&mut self, _sort: &surface::BaseSort) {}
+    fn on_match(&mut self, _span: Span) {}
 }
 
 pub(crate) struct ScopedVisitorWrapper<V>(V);
@@ -165,7 +168,21 @@ impl<V: ScopedVisitor> surface::visit::Visitor for ScopedVisitorWrapper<V> {
     fn visit_fn_sig(&mut self, fn_sig: &surface::FnSig) {
         self.with_scope(ScopeKind::FnInput, |this| {
             this.on_fn_sig(fn_sig);
-            surface::visit::walk_fn_sig(this, fn_sig);
+            this.visit_async(&fn_sig.asyncness);
+            this.visit_generics(&fn_sig.generics);
+            if let Some(requires) = &fn_sig.requires {
+                this.visit_expr(requires);
+            }
+            for (idx, arg) in fn_sig.args.iter().enumerate() {
+                if let Some((loc, ty, node_id)) = ensured_mut_ref(arg, &fn_sig.output.ensures) {
+                    this.on_implicit_param(loc, fhir::ParamKind::Loc(idx), node_id);
+                    this.visit_ident(loc);
+                    this.visit_ty(ty);
+                } else {
+                    this.visit_fun_arg(arg, idx);
+                }
+            }
+            this.visit_fn_output(&fn_sig.output);
         });
     }
 
@@ -255,7 +272,7 @@ impl<V: ScopedVisitor> surface::visit::Visitor for ScopedVisitorWrapper<V> {
                     surface::visit::walk_ty(this, ty);
                 });
             }
-            surface::TyKind::Array(..) => {
+            surface::TyKind::Array(..) | surface::TyKind::BareFn(..) => {
                 self.with_scope(ScopeKind::Misc, |this| {
                     surface::visit::walk_ty(this, ty);
                 });
@@ -286,6 +303,12 @@ impl<V: ScopedVisitor> surface::visit::Visitor for ScopedVisitorWrapper<V> {
                 self.on_func(*func, expr.node_id);
             }
             surface::ExprKind::Dot(path, _) => self.on_path(path),
+            surface::ExprKind::Match(_, arms) => {
+                self.on_match(expr.span);
+                for arm in arms {
+                    self.on_path(&arm.pat);
+                }
+            }
             _ => {}
         }
         surface::visit::walk_expr(self, expr);
@@ -564,6 +587,40 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
             .emit(errors::UnresolvedVar::from_ident(ident, "name"));
     }
 
+    /// Resolves a path `Enum::Variant` where `Enum` is a local enum marked with `#[flux::reflect]`
+    fn resolve_reflected_variant(&self, typ: Ident, name: Ident) -> Option<ExprRes> {
+        let hir::def::Res::Def(DefKind::Enum, enum_def_id) = self.resolver.resolve_ident(typ)?
+        else {
+            return None;
+        };
+        let owner_id = OwnerId { def_id: enum_def_id.as_local()? };
+        if !self.resolver.specs.enums.get(&owner_id)?.reflected {
+            return None;
+        }
+        let variant = self
+            .resolver
+            .genv
+            .tcx()
+            .adt_def(enum_def_id)
+            .variants()
+            .iter()
+            .find(|variant| variant.name == name.name)?;
+        Some(ExprRes::Variant(variant.def_id))
+    }
+
+    fn check_feature_enabled(&mut self, feature: UnstableFeature, span: Span) {
+        let features = self
+            .resolver
+            .genv
+            .crate_config()
+            .unwrap_or_default()
+            .unstable_features;
+        if !features.is_enabled(feature) {
+            self.errors
+                .emit(errors::UnstableFeature::new(feature, span));
+        }
+    }
+
     fn resolve_sort_path(&mut self, path: &surface::SortPath) {
         let segment = path.segment;
         let res = if segment.name == SORTS.int {
@@ -608,6 +665,7 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
                 ExprRes::Const(def_id) => ExprRes::Const(def_id),
                 ExprRes::NumConst(val) => ExprRes::NumConst(val),
                 ExprRes::GlobalFunc(kind, name) => ExprRes::GlobalFunc(kind, name),
+                ExprRes::Variant(def_id) => ExprRes::Variant(def_id),
             };
             self.resolver.output.path_expr_res_map.insert(node_id, res);
         }
@@ -726,7 +784,9 @@ impl<'genv> ScopedVisitor for RefinementResolver<'_, 'genv, '_> {
                 self.resolve_ident(*var, path.node_id);
             }
             [typ, name] => {
-                if let Some(res) = resolve_num_const(*typ, *name) {
+                if let Some(res) = resolve_num_const(*typ, *name)
+                    .or_else(|| self.resolve_reflected_variant(*typ, *name))
+                {
                     self.resolver
                         .output
                         .path_expr_res_map
@@ -751,6 +811,10 @@ impl<'genv> ScopedVisitor for RefinementResolver<'_, 'genv, '_> {
             surface::BaseSort::BitVec(_) => {}
         }
     }
+
+    fn on_match(&mut self, span: Span) {
+        self.check_feature_enabled(UnstableFeature::MatchExprs, span);
+    }
 }
 
 macro_rules! define_resolve_num_const {
@@ -873,6 +937,21 @@ mod errors {
         }
     }
 
+    #[derive(Diagnostic)]
+    #[diag(desugar_unstable_feature, code = E0999)]
+    #[help]
+    pub(super) struct UnstableFeature {
+        #[primary_span]
+        span: Span,
+        feature: &'static str,
+    }
+
+    impl UnstableFeature {
+        pub(super) fn new(feature: flux_config::UnstableFeature, span: Span) -> Self {
+            Self { span, feature: feature.name() }
+        }
+    }
+
     #[derive(Diagnostic)]
     #[diag(desugar_unresolved_sort, code = E0999)]
     pub(super) struct UnresolvedSort {
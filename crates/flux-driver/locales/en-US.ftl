@@ -4,9 +4,15 @@ driver_duplicated_attr =
 driver_invalid_attr =
     invalid flux attribute
 
+driver_invalid_intrinsic_sig =
+    the `{$intrinsic}` intrinsic must be declared as `{$expected}`
+
 driver_cfg_error =
     invalid flux configuration: {$message}
 
+driver_crate_config_file_error =
+    invalid flux configuration in `{$path}`: {$message}
+
 driver_syntax_err =
     syntax error: {$msg}
 
@@ -22,8 +28,40 @@ driver_missing_variant =
     .label = this variant doesn't have a refinement annotation
     .note = all variants in a refined enum must be annotated
 
+driver_invalid_reflect =
+    invalid reflected enum
+    .note = only enums whose variants have no fields and no refinement annotations can be reflected
+
 driver_malformed_extern_spec =
     malformed extern_spec, this should never happen if you are using the extern_spec macro. Did you accidentally use the internal flux::extern_spec attribute?
 
+driver_extern_struct_field_count_mismatch =
+    extern spec for `{$path}` has {$found} fields but `{$path}` has {$expected} fields
+    .note = the fields of an extern spec must be the same as the fields of the external struct
+
+driver_extern_struct_field_mismatch =
+    field doesn't match the corresponding field of `{$path}`
+    .label = expected `{$expected}`
+
 driver_missing_fn_sig_for_extern_spec =
     missing flux::sig attribute (functions declared as flux::extern_spec require a flux::sig)
+
+driver_extern_spec_for_local_fn =
+    cannot declare an extern spec for `{$path}`
+    .note = `{$path}` is defined in the current crate, add a `flux::sig` attribute to its definition instead
+
+driver_duplicate_extern_spec =
+    duplicate extern spec for `{$path}`
+    .label = `{$path}` already has an extern spec
+    .note = the previous extern spec is here
+
+driver_sidecar_unresolved_path =
+    cannot find item `{$path}` in this crate
+    .note = paths in a spec file are relative to the root of the crate and written without generic arguments
+
+driver_sidecar_ambiguous_path =
+    `{$path}` refers to more than one item in this crate
+    .note = items whose paths only differ in their generic arguments cannot be told apart in a spec file
+
+driver_sidecar_not_flux_attr =
+    only flux attributes can be used in a spec file
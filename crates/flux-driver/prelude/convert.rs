@@ -0,0 +1,130 @@
+// Built-in specs for conversions between integer types. Lossless conversions (`From`) preserve the
+// index and fallible ones (`TryFrom`) succeed exactly when the value fits in the target type, e.g.,
+// `u8::try_from(x)` on `x: usize{v: v < 256}` returns an `Ok`. Calls to `into` and `try_into` go
+// through these specs because they are resolved to `from` and `try_from` respectively.
+//
+// There are no specs for fallible conversions into `usize` and `isize` because their bounds depend
+// on the target. Conversions from them assume a pointer width of at most 64 bits.
+//
+// The specs are generated with macros because there is one impl for each pair of types.
+
+use core::num::TryFromIntError;
+
+macro_rules! from_specs {
+    ($($name:ident: $src:ident => $dst:ident,)*) => {$(
+        struct $name($dst);
+
+        #[flux::extern_spec]
+        impl $name {
+            #[flux::extern_spec]
+            #[flux::sig(fn($src[@n]) -> $dst[n])]
+            fn from(x: $src) -> $dst {
+                <$dst as From<$src>>::from(x)
+            }
+
+            #[flux::fake_impl]
+            fn __flux_extern_impl_fake_method<FluxFake: From<$src>>(x: $dst) {}
+        }
+    )*};
+}
+
+macro_rules! try_from_specs {
+    ($($name:ident: $src:ident => $dst:ident $fits:tt,)*) => {$(
+        struct $name($dst);
+
+        #[flux::extern_spec]
+        impl $name {
+            #[flux::extern_spec]
+            #[flux::sig(fn($src[@n]) -> Result<$dst[n], TryFromIntError>$fits)]
+            fn try_from(x: $src) -> Result<$dst, TryFromIntError> {
+                <$dst as TryFrom<$src>>::try_from(x)
+            }
+
+            #[flux::fake_impl]
+            fn __flux_extern_impl_fake_method<FluxFake: TryFrom<$src>>(x: $dst) {}
+        }
+    )*};
+}
+
+from_specs! {
+    __FluxExternImplStruct0Fromu8u16: u8 => u16,
+    __FluxExternImplStruct0Fromu8u32: u8 => u32,
+    __FluxExternImplStruct0Fromu16u32: u16 => u32,
+    __FluxExternImplStruct0Fromu8u64: u8 => u64,
+    __FluxExternImplStruct0Fromu16u64: u16 => u64,
+    __FluxExternImplStruct0Fromu32u64: u32 => u64,
+    __FluxExternImplStruct0Fromu8i16: u8 => i16,
+    __FluxExternImplStruct0Fromi8i16: i8 => i16,
+    __FluxExternImplStruct0Fromu8i32: u8 => i32,
+    __FluxExternImplStruct0Fromu16i32: u16 => i32,
+    __FluxExternImplStruct0Fromi8i32: i8 => i32,
+    __FluxExternImplStruct0Fromi16i32: i16 => i32,
+    __FluxExternImplStruct0Fromu8i64: u8 => i64,
+    __FluxExternImplStruct0Fromu16i64: u16 => i64,
+    __FluxExternImplStruct0Fromu32i64: u32 => i64,
+    __FluxExternImplStruct0Fromi8i64: i8 => i64,
+    __FluxExternImplStruct0Fromi16i64: i16 => i64,
+    __FluxExternImplStruct0Fromi32i64: i32 => i64,
+    __FluxExternImplStruct0Fromu8usize: u8 => usize,
+    __FluxExternImplStruct0Fromu16usize: u16 => usize,
+    __FluxExternImplStruct0Fromu8isize: u8 => isize,
+    __FluxExternImplStruct0Fromi8isize: i8 => isize,
+    __FluxExternImplStruct0Fromi16isize: i16 => isize,
+}
+
+try_from_specs! {
+    __FluxExternImplStruct0TryFromu16u8: u16 => u8 [n <= 255],
+    __FluxExternImplStruct0TryFromu32u8: u32 => u8 [n <= 255],
+    __FluxExternImplStruct0TryFromu64u8: u64 => u8 [n <= 255],
+    __FluxExternImplStruct0TryFromusizeu8: usize => u8 [n <= 255],
+    __FluxExternImplStruct0TryFromi8u8: i8 => u8 [0 <= n],
+    __FluxExternImplStruct0TryFromi16u8: i16 => u8 [0 <= n && n <= 255],
+    __FluxExternImplStruct0TryFromi32u8: i32 => u8 [0 <= n && n <= 255],
+    __FluxExternImplStruct0TryFromi64u8: i64 => u8 [0 <= n && n <= 255],
+    __FluxExternImplStruct0TryFromisizeu8: isize => u8 [0 <= n && n <= 255],
+    __FluxExternImplStruct0TryFromu32u16: u32 => u16 [n <= 65535],
+    __FluxExternImplStruct0TryFromu64u16: u64 => u16 [n <= 65535],
+    __FluxExternImplStruct0TryFromusizeu16: usize => u16 [n <= 65535],
+    __FluxExternImplStruct0TryFromi8u16: i8 => u16 [0 <= n],
+    __FluxExternImplStruct0TryFromi16u16: i16 => u16 [0 <= n],
+    __FluxExternImplStruct0TryFromi32u16: i32 => u16 [0 <= n && n <= 65535],
+    __FluxExternImplStruct0TryFromi64u16: i64 => u16 [0 <= n && n <= 65535],
+    __FluxExternImplStruct0TryFromisizeu16: isize => u16 [0 <= n && n <= 65535],
+    __FluxExternImplStruct0TryFromu64u32: u64 => u32 [n <= 4294967295],
+    __FluxExternImplStruct0TryFromusizeu32: usize => u32 [n <= 4294967295],
+    __FluxExternImplStruct0TryFromi8u32: i8 => u32 [0 <= n],
+    __FluxExternImplStruct0TryFromi16u32: i16 => u32 [0 <= n],
+    __FluxExternImplStruct0TryFromi32u32: i32 => u32 [0 <= n],
+    __FluxExternImplStruct0TryFromi64u32: i64 => u32 [0 <= n && n <= 4294967295],
+    __FluxExternImplStruct0TryFromisizeu32: isize => u32 [0 <= n && n <= 4294967295],
+    __FluxExternImplStruct0TryFromusizeu64: usize => u64 [true],
+    __FluxExternImplStruct0TryFromi8u64: i8 => u64 [0 <= n],
+    __FluxExternImplStruct0TryFromi16u64: i16 => u64 [0 <= n],
+    __FluxExternImplStruct0TryFromi32u64: i32 => u64 [0 <= n],
+    __FluxExternImplStruct0TryFromi64u64: i64 => u64 [0 <= n],
+    __FluxExternImplStruct0TryFromisizeu64: isize => u64 [0 <= n],
+    __FluxExternImplStruct0TryFromu8i8: u8 => i8 [n <= 127],
+    __FluxExternImplStruct0TryFromu16i8: u16 => i8 [n <= 127],
+    __FluxExternImplStruct0TryFromu32i8: u32 => i8 [n <= 127],
+    __FluxExternImplStruct0TryFromu64i8: u64 => i8 [n <= 127],
+    __FluxExternImplStruct0TryFromusizei8: usize => i8 [n <= 127],
+    __FluxExternImplStruct0TryFromi16i8: i16 => i8 [-128 <= n && n <= 127],
+    __FluxExternImplStruct0TryFromi32i8: i32 => i8 [-128 <= n && n <= 127],
+    __FluxExternImplStruct0TryFromi64i8: i64 => i8 [-128 <= n && n <= 127],
+    __FluxExternImplStruct0TryFromisizei8: isize => i8 [-128 <= n && n <= 127],
+    __FluxExternImplStruct0TryFromu16i16: u16 => i16 [n <= 32767],
+    __FluxExternImplStruct0TryFromu32i16: u32 => i16 [n <= 32767],
+    __FluxExternImplStruct0TryFromu64i16: u64 => i16 [n <= 32767],
+    __FluxExternImplStruct0TryFromusizei16: usize => i16 [n <= 32767],
+    __FluxExternImplStruct0TryFromi32i16: i32 => i16 [-32768 <= n && n <= 32767],
+    __FluxExternImplStruct0TryFromi64i16: i64 => i16 [-32768 <= n && n <= 32767],
+    __FluxExternImplStruct0TryFromisizei16: isize => i16 [-32768 <= n && n <= 32767],
+    __FluxExternImplStruct0TryFromu32i32: u32 => i32 [n <= 2147483647],
+    __FluxExternImplStruct0TryFromu64i32: u64 => i32 [n <= 2147483647],
+    __FluxExternImplStruct0TryFromusizei32: usize => i32 [n <= 2147483647],
+    __FluxExternImplStruct0TryFromi64i32: i64 => i32 [-2147483648 <= n && n <= 2147483647],
+    __FluxExternImplStruct0TryFromisizei32: isize => i32 [-2147483648 <= n && n <= 2147483647],
+    __FluxExternImplStruct0TryFromu64i64: u64 => i64 [n <= 9223372036854775807],
+    __FluxExternImplStruct0TryFromusizei64: usize => i64 [n <= 9223372036854775807],
+    __FluxExternImplStruct0TryFromisizei64: isize => i64 [true],
+}
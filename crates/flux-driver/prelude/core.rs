@@ -0,0 +1,18 @@
+//! Built-in specs for items in `core`.
+//!
+//! The prelude is compiled with flux into the crates `flux_prelude_core` (this file) and
+//! `flux_prelude_std` (`std.rs`), see `cargo xtask build-prelude`. Their metadata is installed next
+//! to the flux driver which loads the extern specs in it for every crate it checks, see
+//! `src/prelude.rs`. Each top-level module is a group of specs that is dropped if the crate being
+//! checked gives its own extern specs for the items in the group. The items are written as the
+//! expansion of the corresponding `extern_spec` macro so the prelude does not depend on `flux-rs`.
+#![no_std]
+#![feature(step_trait, try_trait_v2)]
+#![allow(dead_code, non_camel_case_types, unused)]
+
+mod convert;
+mod option;
+mod ordering;
+mod range;
+mod result;
+mod slice;
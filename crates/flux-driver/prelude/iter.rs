@@ -0,0 +1,89 @@
+// Built-in specs for iterating over a `Vec`. The iterators are indexed by the number of elements
+// left, so a `for` loop over a vector knows it yields an element exactly when there are some left.
+// The elements keep the refinements of the vector's element type.
+
+use std::{
+    alloc::{Allocator, Global},
+    slice::Iter,
+    vec::IntoIter,
+};
+
+#[flux::extern_spec]
+#[flux::refined_by(len: int)]
+#[flux::invariant(0 <= len)]
+struct __FluxExternStructIntoIter<T, A: Allocator = Global>(IntoIter<T, A>);
+
+#[flux::extern_spec]
+#[flux::refined_by(len: int)]
+#[flux::invariant(0 <= len)]
+struct __FluxExternStructIter<'a, T>(Iter<'a, T>);
+
+struct __FluxExternImplStruct2IntoIteratorVec<T, A: Allocator>(T, A, Vec<T, A>);
+
+#[flux::extern_spec]
+impl<T, A: Allocator> __FluxExternImplStruct2IntoIteratorVec<T, A> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(Vec<T, A>[@n]) -> IntoIter<T, A>[n])]
+    fn into_iter(v: Vec<T, A>) -> IntoIter<T, A> {
+        <Vec<T, A> as IntoIterator>::into_iter(v)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: IntoIterator>(x: Vec<T, A>) {}
+}
+
+struct __FluxExternImplStruct3IntoIteratorRefVec<'a, T, A: Allocator>(T, A, &'a Vec<T, A>);
+
+#[flux::extern_spec]
+impl<'a, T, A: Allocator> __FluxExternImplStruct3IntoIteratorRefVec<'a, T, A> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(&Vec<T, A>[@n]) -> Iter<T>[n])]
+    fn into_iter(v: &'a Vec<T, A>) -> Iter<'a, T> {
+        <&'a Vec<T, A> as IntoIterator>::into_iter(v)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: IntoIterator>(x: &'a Vec<T, A>) {}
+}
+
+struct __FluxExternImplStruct2IteratorIntoIter<T, A: Allocator>(T, A, IntoIter<T, A>);
+
+#[flux::extern_spec]
+impl<T, A: Allocator> __FluxExternImplStruct2IteratorIntoIter<T, A> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(self: &strg IntoIter<T, A>[@n]) -> Option<T>[n > 0]
+                ensures self: IntoIter<T, A>[if n > 0 { n - 1 } else { 0 }])]
+    fn next(it: &mut IntoIter<T, A>) -> Option<T> {
+        <IntoIter<T, A> as Iterator>::next(it)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(&IntoIter<T, A>[@n]) -> (usize[n], Option<usize[n]>[true]))]
+    fn size_hint(it: &IntoIter<T, A>) -> (usize, Option<usize>) {
+        <IntoIter<T, A> as Iterator>::size_hint(it)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: Iterator>(x: IntoIter<T, A>) {}
+}
+
+struct __FluxExternImplStruct2IteratorIter<'a, T>(T, Iter<'a, T>);
+
+#[flux::extern_spec]
+impl<'a, T> __FluxExternImplStruct2IteratorIter<'a, T> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(self: &strg Iter<T>[@n]) -> Option<&T>[n > 0]
+                ensures self: Iter<T>[if n > 0 { n - 1 } else { 0 }])]
+    fn next(it: &mut Iter<'a, T>) -> Option<&'a T> {
+        <Iter<'a, T> as Iterator>::next(it)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(&Iter<T>[@n]) -> (usize[n], Option<usize[n]>[true]))]
+    fn size_hint(it: &Iter<'a, T>) -> (usize, Option<usize>) {
+        <Iter<'a, T> as Iterator>::size_hint(it)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: Iterator>(x: Iter<'a, T>) {}
+}
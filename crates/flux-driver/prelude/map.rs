@@ -0,0 +1,155 @@
+// Built-in specs for `HashMap` and `BTreeMap`. A map is indexed by the set of its keys.
+//
+// Lookups take a borrowed form `Q` of the key type `K`. The associated refinement `owned` of
+// `Borrow` maps a borrowed key back to the owned one, so the membership of `k: &Q` can be stated
+// in terms of the set of keys. It is only defined for `K = Q` (where it is the identity); for any
+// other impl it is left uninterpreted.
+
+use std::{
+    alloc::{Allocator, Global},
+    borrow::Borrow,
+    collections::{hash_map::RandomState, BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+};
+
+#[flux::extern_spec]
+#[flux::generics(Self as base, Borrowed as base)]
+#[flux::assoc(fn owned(k: Borrowed) -> Self)]
+trait __FluxExternTraitBorrow<Borrowed>: Borrow<Borrowed>
+where
+    Borrowed: ?Sized,
+{
+}
+
+struct __FluxExternImplStruct1BorrowT<T>(T, T);
+
+#[flux::extern_spec]
+#[flux::generics(T as base)]
+#[flux::assoc(fn owned(k: T) -> T { k })]
+impl<T> __FluxExternImplStruct1BorrowT<T> {
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: Borrow<T>>(x: T) {}
+}
+
+#[flux::extern_spec]
+#[flux::refined_by(keys: Set<K>)]
+struct __FluxExternStructHashMap<K, V, S = RandomState>(HashMap<K, V, S>);
+
+struct __FluxExternImplStruct2HashMap<K, V>(K, V, HashMap<K, V>);
+
+#[flux::extern_spec]
+#[flux::generics(K as base)]
+impl<K, V> __FluxExternImplStruct2HashMap<K, V> {
+    #[flux::extern_spec]
+    #[flux::sig(fn() -> HashMap<K, V>[set_empty(0)])]
+    fn new() -> HashMap<K, V> {
+        <HashMap<K, V>>::new()
+    }
+}
+
+struct __FluxExternImplStruct3HashMap<K: Eq + Hash, V, S: BuildHasher>(K, V, S, HashMap<K, V, S>);
+
+#[flux::extern_spec]
+#[flux::generics(K as base)]
+impl<K: Eq + Hash, V, S: BuildHasher> __FluxExternImplStruct3HashMap<K, V, S> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(self: &strg HashMap<K, V, S>[@m], k: K, V) -> Option<V>[set_is_in(k, m.keys)]
+                ensures self: HashMap<K, V, S>[set_union(set_singleton(k), m.keys)])]
+    fn insert(m: &mut HashMap<K, V, S>, k: K, v: V) -> Option<V> {
+        <HashMap<K, V, S>>::insert(m, k, v)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn<Q as base>(&HashMap<K, V, S>[@m], &Q[@k])
+                -> Option<&V>[set_is_in(<K as Borrow<Q>>::owned(k), m.keys)])]
+    fn get<Q: ?Sized + Hash + Eq>(m: &HashMap<K, V, S>, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        <HashMap<K, V, S>>::get(m, k)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn<Q as base>(&HashMap<K, V, S>[@m], &Q[@k])
+                -> bool[set_is_in(<K as Borrow<Q>>::owned(k), m.keys)])]
+    fn contains_key<Q: ?Sized + Hash + Eq>(m: &HashMap<K, V, S>, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        <HashMap<K, V, S>>::contains_key(m, k)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn<Q as base>(self: &strg HashMap<K, V, S>[@m], &Q[@k])
+                -> Option<V>[set_is_in(<K as Borrow<Q>>::owned(k), m.keys)]
+                ensures self: HashMap<K, V, S>[set_difference(m.keys, set_singleton(<K as Borrow<Q>>::owned(k)))])]
+    fn remove<Q: ?Sized + Hash + Eq>(m: &mut HashMap<K, V, S>, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        <HashMap<K, V, S>>::remove(m, k)
+    }
+}
+
+#[flux::extern_spec]
+#[flux::refined_by(keys: Set<K>)]
+struct __FluxExternStructBTreeMap<K, V, A: Allocator + Clone = Global>(BTreeMap<K, V, A>);
+
+struct __FluxExternImplStruct2BTreeMap<K, V>(K, V, BTreeMap<K, V>);
+
+#[flux::extern_spec]
+#[flux::generics(K as base)]
+impl<K, V> __FluxExternImplStruct2BTreeMap<K, V> {
+    #[flux::extern_spec]
+    #[flux::sig(fn() -> BTreeMap<K, V>[set_empty(0)])]
+    fn new() -> BTreeMap<K, V> {
+        <BTreeMap<K, V>>::new()
+    }
+}
+
+struct __FluxExternImplStruct3BTreeMap<K, V, A: Allocator + Clone>(K, V, A, BTreeMap<K, V, A>);
+
+#[flux::extern_spec]
+#[flux::generics(K as base)]
+impl<K, V, A: Allocator + Clone> __FluxExternImplStruct3BTreeMap<K, V, A> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(self: &strg BTreeMap<K, V, A>[@m], k: K, V) -> Option<V>[set_is_in(k, m.keys)]
+                ensures self: BTreeMap<K, V, A>[set_union(set_singleton(k), m.keys)])]
+    fn insert(m: &mut BTreeMap<K, V, A>, k: K, v: V) -> Option<V>
+    where
+        K: Ord,
+    {
+        <BTreeMap<K, V, A>>::insert(m, k, v)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn<Q as base>(&BTreeMap<K, V, A>[@m], &Q[@k])
+                -> Option<&V>[set_is_in(<K as Borrow<Q>>::owned(k), m.keys)])]
+    fn get<Q: ?Sized + Ord>(m: &BTreeMap<K, V, A>, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q> + Ord,
+    {
+        <BTreeMap<K, V, A>>::get(m, k)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn<Q as base>(&BTreeMap<K, V, A>[@m], &Q[@k])
+                -> bool[set_is_in(<K as Borrow<Q>>::owned(k), m.keys)])]
+    fn contains_key<Q: ?Sized + Ord>(m: &BTreeMap<K, V, A>, k: &Q) -> bool
+    where
+        K: Borrow<Q> + Ord,
+    {
+        <BTreeMap<K, V, A>>::contains_key(m, k)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn<Q as base>(self: &strg BTreeMap<K, V, A>[@m], &Q[@k])
+                -> Option<V>[set_is_in(<K as Borrow<Q>>::owned(k), m.keys)]
+                ensures self: BTreeMap<K, V, A>[set_difference(m.keys, set_singleton(<K as Borrow<Q>>::owned(k)))])]
+    fn remove<Q: ?Sized + Ord>(m: &mut BTreeMap<K, V, A>, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q> + Ord,
+    {
+        <BTreeMap<K, V, A>>::remove(m, k)
+    }
+}
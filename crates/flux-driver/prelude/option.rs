@@ -0,0 +1,46 @@
+// Built-in specs for `Option`. An option is indexed by whether it is a `Some`.
+
+#[flux::extern_spec]
+#[flux::refined_by(is_some: bool)]
+enum __FluxExternEnumOption<T> {
+    #[flux::variant(Option<T>[false])]
+    None,
+    #[flux::variant({T} -> Option<T>[true])]
+    Some(T),
+    FluxExternEnumFake(Option<T>),
+}
+
+struct __FluxExternImplStruct1Option<T>(T, Option<T>);
+
+#[flux::extern_spec]
+impl<T> __FluxExternImplStruct1Option<T> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(&Option<T>[@b]) -> bool[b])]
+    fn is_some(opt: &Option<T>) -> bool {
+        <Option<T>>::is_some(opt)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(&Option<T>[@b]) -> bool[!b])]
+    fn is_none(opt: &Option<T>) -> bool {
+        <Option<T>>::is_none(opt)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(Option<T>[true]) -> T)]
+    fn unwrap(opt: Option<T>) -> T {
+        <Option<T>>::unwrap(opt)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(Option<T>, T) -> T)]
+    fn unwrap_or(opt: Option<T>, default: T) -> T {
+        <Option<T>>::unwrap_or(opt, default)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(Option<T>[@b], F) -> Option<U>[b])]
+    fn map<U, F: FnOnce(T) -> U>(opt: Option<T>, f: F) -> Option<U> {
+        <Option<T>>::map::<U, F>(opt, f)
+    }
+}
@@ -0,0 +1,16 @@
+// Built-in specs for `Ordering`. An ordering is indexed by its discriminant, i.e., `-1` for `Less`,
+// `0` for `Equal` and `1` for `Greater`.
+
+use core::cmp::Ordering;
+
+#[flux::extern_spec]
+#[flux::refined_by(v: int)]
+enum __FluxExternEnumOrdering {
+    #[flux::variant(Ordering[-1])]
+    Less,
+    #[flux::variant(Ordering[0])]
+    Equal,
+    #[flux::variant(Ordering[1])]
+    Greater,
+    FluxExternEnumFake(Ordering),
+}
@@ -0,0 +1,73 @@
+// Built-in specs for `Range` and `RangeInclusive`. A range is indexed by its bounds and iterating
+// over it yields values within them, e.g., the loop variable of `for i in 0..n` is in `[0, n)`.
+//
+// The element type of a range can be any type implementing `Step`, so the specs only compare
+// values of it (which doesn't require a numeric sort) and don't say exactly how far the start of
+// the range moves after each step.
+
+use core::{
+    iter::Step,
+    ops::{Range, RangeInclusive},
+};
+
+#[flux::extern_spec]
+#[flux::generics(Idx as base)]
+#[flux::refined_by(start: Idx, end: Idx)]
+struct __FluxExternStructRange<Idx> {
+    #[flux::field(Idx[start])]
+    start: Idx,
+    #[flux::field(Idx[end])]
+    end: Idx,
+    __flux_extern_struct: Range<Idx>,
+}
+
+#[flux::extern_spec]
+#[flux::generics(Idx as base)]
+#[flux::refined_by(start: Idx, end: Idx)]
+struct __FluxExternStructRangeInclusive<Idx>(RangeInclusive<Idx>);
+
+struct __FluxExternImplStruct1RangeInclusive<Idx>(Idx, RangeInclusive<Idx>);
+
+#[flux::extern_spec]
+#[flux::generics(Idx as base)]
+impl<Idx> __FluxExternImplStruct1RangeInclusive<Idx> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(start: Idx, end: Idx) -> RangeInclusive<Idx>[start, end])]
+    fn new(start: Idx, end: Idx) -> RangeInclusive<Idx> {
+        <RangeInclusive<Idx>>::new(start, end)
+    }
+}
+
+#[flux::generics(A as base)]
+struct __FluxExternImplStruct1IteratorRange<A: Step>(A, Range<A>);
+
+#[flux::extern_spec]
+#[flux::generics(A as base)]
+impl<A: Step> __FluxExternImplStruct1IteratorRange<A> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(self: &strg Range<A>[@lo, @hi]) -> Option<A{v: lo <= v && v < hi}>[lo < hi]
+                ensures self: Range<A>{r: lo <= r.start && r.end == hi})]
+    fn next(r: &mut Range<A>) -> Option<A> {
+        <Range<A> as Iterator>::next(r)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: Iterator>(x: Range<A>) {}
+}
+
+#[flux::generics(A as base)]
+struct __FluxExternImplStruct1IteratorRangeInclusive<A: Step>(A, RangeInclusive<A>);
+
+#[flux::extern_spec]
+#[flux::generics(A as base)]
+impl<A: Step> __FluxExternImplStruct1IteratorRangeInclusive<A> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(self: &strg RangeInclusive<A>[@lo, @hi]) -> Option<A{v: lo <= v && v <= hi}>
+                ensures self: RangeInclusive<A>{r: lo <= r.start && r.end == hi})]
+    fn next(r: &mut RangeInclusive<A>) -> Option<A> {
+        <RangeInclusive<A> as Iterator>::next(r)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: Iterator>(x: RangeInclusive<A>) {}
+}
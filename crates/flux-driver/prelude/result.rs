@@ -0,0 +1,122 @@
+// Built-in specs for `Result`. A result is indexed by whether it is an `Ok`. The specs for the
+// `Try` and `FromResidual` implementations (and for `ControlFlow`) make the index flow through the
+// `?` operator.
+
+use core::{
+    convert::Infallible,
+    fmt::Debug,
+    ops::{ControlFlow, FromResidual, Try},
+};
+
+#[flux::extern_spec]
+#[flux::refined_by(is_ok: bool)]
+enum __FluxExternEnumResult<T, E> {
+    #[flux::variant({T} -> Result<T, E>[true])]
+    Ok(T),
+    #[flux::variant({E} -> Result<T, E>[false])]
+    Err(E),
+    FluxExternEnumFake(Result<T, E>),
+}
+
+#[flux::extern_spec]
+#[flux::refined_by(is_break: bool)]
+enum __FluxExternEnumControlFlow<B, C = ()> {
+    #[flux::variant({C} -> ControlFlow<B, C>[false])]
+    Continue(C),
+    #[flux::variant({B} -> ControlFlow<B, C>[true])]
+    Break(B),
+    FluxExternEnumFake(ControlFlow<B, C>),
+}
+
+struct __FluxExternImplStruct2Result<T, E>(T, E, Result<T, E>);
+
+#[flux::extern_spec]
+impl<T, E> __FluxExternImplStruct2Result<T, E> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(&Result<T, E>[@ok]) -> bool[ok])]
+    fn is_ok(res: &Result<T, E>) -> bool {
+        <Result<T, E>>::is_ok(res)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(&Result<T, E>[@ok]) -> bool[!ok])]
+    fn is_err(res: &Result<T, E>) -> bool {
+        <Result<T, E>>::is_err(res)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(Result<T, E>[true]) -> T)]
+    fn unwrap(res: Result<T, E>) -> T
+    where
+        E: Debug,
+    {
+        <Result<T, E>>::unwrap(res)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(Result<T, E>[true], &str) -> T)]
+    fn expect(res: Result<T, E>, msg: &str) -> T
+    where
+        E: Debug,
+    {
+        <Result<T, E>>::expect(res, msg)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(Result<T, E>[false]) -> E)]
+    fn unwrap_err(res: Result<T, E>) -> E
+    where
+        T: Debug,
+    {
+        <Result<T, E>>::unwrap_err(res)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(Result<T, E>[@ok], F) -> Result<U, E>[ok])]
+    fn map<U, F: FnOnce(T) -> U>(res: Result<T, E>, f: F) -> Result<U, E> {
+        <Result<T, E>>::map::<U, F>(res, f)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(Result<T, E>[@ok], O) -> Result<T, F>[ok])]
+    fn map_err<F, O: FnOnce(E) -> F>(res: Result<T, E>, op: O) -> Result<T, F> {
+        <Result<T, E>>::map_err::<F, O>(res, op)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(Result<T, E>[@ok]) -> Option<T>[ok])]
+    fn ok(res: Result<T, E>) -> Option<T> {
+        <Result<T, E>>::ok(res)
+    }
+}
+
+struct __FluxExternImplStruct2TryResult<T, E>(T, E, Result<T, E>);
+
+#[flux::extern_spec]
+impl<T, E> __FluxExternImplStruct2TryResult<T, E> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(Result<T, E>[@ok]) -> ControlFlow<Result<Infallible, E>, T>[!ok])]
+    fn branch(res: Result<T, E>) -> ControlFlow<Result<Infallible, E>, T> {
+        <Result<T, E> as Try>::branch(res)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: Try>(x: Result<T, E>) {}
+}
+
+struct __FluxExternImplStruct3FromResidualResult<T, E, F: From<E>>(T, E, F, Result<T, F>);
+
+#[flux::extern_spec]
+impl<T, E, F: From<E>> __FluxExternImplStruct3FromResidualResult<T, E, F> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(Result<Infallible, E>) -> Result<T, F>[false])]
+    fn from_residual(residual: Result<Infallible, E>) -> Result<T, F> {
+        <Result<T, F> as FromResidual<Result<Infallible, E>>>::from_residual(residual)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: FromResidual<Result<Infallible, E>>>(
+        x: Result<T, F>,
+    ) {
+    }
+}
@@ -0,0 +1,13 @@
+// Built-in specs for slices. Slices are indexed by their length without any extern spec, but the
+// methods on them are not refined.
+
+struct __FluxExternImplStruct1SliceT<T>(T, [T]);
+
+#[flux::extern_spec]
+impl<T> __FluxExternImplStruct1SliceT<T> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(&[T][@n]) -> usize[n])]
+    fn len(s: &[T]) -> usize {
+        <[T]>::len(s)
+    }
+}
@@ -0,0 +1,7 @@
+//! Built-in specs for items in `std`. It uses the specs in `flux_prelude_core`, see `core.rs`.
+#![feature(allocator_api)]
+#![allow(dead_code, non_camel_case_types, unused)]
+
+mod iter;
+mod map;
+mod vec;
@@ -0,0 +1,142 @@
+// Built-in specs for `Vec`. A vector is indexed by its length.
+
+use std::{
+    alloc::{Allocator, Global},
+    ops::{Deref, DerefMut, Index, IndexMut},
+    slice::SliceIndex,
+};
+
+#[flux::extern_spec]
+#[flux::refined_by(len: int)]
+#[flux::invariant(0 <= len)]
+struct __FluxExternStructVec<T, A: Allocator = Global>(Vec<T, A>);
+
+#[flux::extern_spec]
+#[flux::generics(Self as base, T as base)]
+#[flux::assoc(fn in_bounds(idx: Self, v: T) -> bool)]
+trait __FluxExternTraitSliceIndex<T>: SliceIndex<T>
+where
+    T: ?Sized,
+{
+}
+
+struct __FluxExternImplStruct1SliceIndexusize<T>(T, usize);
+
+#[flux::extern_spec]
+#[flux::assoc(fn in_bounds(idx: int, len: int) -> bool { idx < len })]
+impl<T> __FluxExternImplStruct1SliceIndexusize<T> {
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: SliceIndex<[T]>>(x: usize) {}
+}
+
+#[flux::generics(I as base)]
+struct __FluxExternImplStruct3IndexVec<T, I: SliceIndex<[T]>, A: Allocator>(T, I, A, Vec<T, A>);
+
+#[flux::extern_spec]
+#[flux::generics(I as base)]
+impl<T, I: SliceIndex<[T]>, A: Allocator> __FluxExternImplStruct3IndexVec<T, I, A> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(&Vec<T, A>[@len], {I[@idx] | <I as SliceIndex<[T]>>::in_bounds(idx, len)}) -> _)]
+    fn index(v: &Vec<T, A>, index: I) -> &<I as SliceIndex<[T]>>::Output {
+        <Vec<T, A> as Index<I>>::index(v, index)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: Index<I>>(x: Vec<T, A>) {}
+}
+
+#[flux::generics(I as base)]
+struct __FluxExternImplStruct3IndexMutVec<T, I: SliceIndex<[T]>, A: Allocator>(
+    T,
+    I,
+    A,
+    Vec<T, A>,
+);
+
+#[flux::extern_spec]
+#[flux::generics(I as base)]
+impl<T, I: SliceIndex<[T]>, A: Allocator> __FluxExternImplStruct3IndexMutVec<T, I, A> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(&mut Vec<T, A>[@len], {I[@idx] | <I as SliceIndex<[T]>>::in_bounds(idx, len)}) -> _)]
+    fn index_mut(v: &mut Vec<T, A>, index: I) -> &mut <I as SliceIndex<[T]>>::Output {
+        <Vec<T, A> as IndexMut<I>>::index_mut(v, index)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: IndexMut<I>>(x: Vec<T, A>) {}
+}
+
+struct __FluxExternImplStruct2DerefVec<T, A: Allocator>(T, A, Vec<T, A>);
+
+#[flux::extern_spec]
+impl<T, A: Allocator> __FluxExternImplStruct2DerefVec<T, A> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(&Vec<T, A>[@n]) -> &[T][n])]
+    fn deref(v: &Vec<T, A>) -> &[T] {
+        <Vec<T, A> as Deref>::deref(v)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: Deref>(x: Vec<T, A>) {}
+}
+
+struct __FluxExternImplStruct2DerefMutVec<T, A: Allocator>(T, A, Vec<T, A>);
+
+#[flux::extern_spec]
+impl<T, A: Allocator> __FluxExternImplStruct2DerefMutVec<T, A> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(&mut Vec<T, A>[@n]) -> &mut [T][n])]
+    fn deref_mut(v: &mut Vec<T, A>) -> &mut [T] {
+        <Vec<T, A> as DerefMut>::deref_mut(v)
+    }
+
+    #[flux::fake_impl]
+    fn __flux_extern_impl_fake_method<FluxFake: DerefMut>(x: Vec<T, A>) {}
+}
+
+struct __FluxExternImplStruct1Vec<T>(T, Vec<T>);
+
+#[flux::extern_spec]
+impl<T> __FluxExternImplStruct1Vec<T> {
+    #[flux::extern_spec]
+    #[flux::sig(fn() -> Vec<T>[0])]
+    fn new() -> Vec<T> {
+        <Vec<T>>::new()
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(usize) -> Vec<T>[0])]
+    fn with_capacity(capacity: usize) -> Vec<T> {
+        <Vec<T>>::with_capacity(capacity)
+    }
+}
+
+struct __FluxExternImplStruct2Vec<T, A: Allocator>(T, A, Vec<T, A>);
+
+#[flux::extern_spec]
+impl<T, A: Allocator> __FluxExternImplStruct2Vec<T, A> {
+    #[flux::extern_spec]
+    #[flux::sig(fn(self: &strg Vec<T, A>[@n], T) ensures self: Vec<T, A>[n + 1])]
+    fn push(v: &mut Vec<T, A>, value: T) {
+        <Vec<T, A>>::push(v, value)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(self: &strg Vec<T, A>[@n]) -> Option<T>
+                ensures self: Vec<T, A>[if n > 0 { n - 1 } else { 0 }])]
+    fn pop(v: &mut Vec<T, A>) -> Option<T> {
+        <Vec<T, A>>::pop(v)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(&Vec<T, A>[@n]) -> usize[n])]
+    fn len(v: &Vec<T, A>) -> usize {
+        <Vec<T, A>>::len(v)
+    }
+
+    #[flux::extern_spec]
+    #[flux::sig(fn(&Vec<T, A>[@n]) -> bool[n == 0])]
+    fn is_empty(v: &Vec<T, A>) -> bool {
+        <Vec<T, A>>::is_empty(v)
+    }
+}
@@ -0,0 +1,89 @@
+//! Entry point to embed flux in other tools without running the `flux-driver` binary.
+//!
+//! A tool driving rustc itself calls [`configure`] from [`Callbacks::config`] and [`verify`] once
+//! the crate has been analyzed, e.g., from [`Callbacks::after_analysis`]. Diagnostics are still
+//! emitted as usual, but the outcome of checking each item is also returned as a [`CrateResults`].
+//!
+//! [`Callbacks::config`]: rustc_driver::Callbacks::config
+//! [`Callbacks::after_analysis`]: rustc_driver::Callbacks::after_analysis
+use flux_middle::queries::Providers;
+pub use flux_refineck::ConstrReason;
+use rustc_driver::Callbacks as _;
+use rustc_errors::ErrorGuaranteed;
+use rustc_hir::def_id::LocalDefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::{Span, Symbol};
+
+use crate::callbacks::{self, FluxCallbacks};
+
+/// The outcome of checking the items of a crate, in the order they were checked
+#[derive(Debug, Default)]
+pub struct CrateResults {
+    pub items: Vec<ItemResult>,
+}
+
+#[derive(Debug)]
+pub struct ItemResult {
+    pub def_id: LocalDefId,
+    /// The path of the item, e.g., `foo::bar`
+    pub path: String,
+    pub status: Status,
+    /// The refinements that failed to be proved. This is empty for items other than functions,
+    /// whose errors are only reported as diagnostics.
+    pub errors: Vec<VerificationError>,
+    /// Loop invariants inferred by fixpoint. Empty unless [`flux_config::show_invariants`] is set.
+    pub invariants: Vec<Invariant>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Status {
+    /// All the refinements of the item were proved
+    Safe,
+    /// Some refinement couldn't be proved
+    Unsafe,
+    /// The item is marked with `#[flux::trusted]`, or nested in a function marked with
+    /// `#[flux::trusted(deep)]`, and wasn't checked
+    Trusted {
+        /// The reason given with `#[flux::trusted(reason = "..")]`, if any
+        reason: Option<Symbol>,
+    },
+    /// The solver didn't finish within the timeout of the crate
+    Timeout,
+    /// The item couldn't be checked, e.g., because its spec is ill-formed
+    Error,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct VerificationError {
+    pub span: Span,
+    pub reason: ConstrReason,
+}
+
+#[derive(Clone, Debug)]
+pub struct Invariant {
+    pub span: Span,
+    pub pred: String,
+}
+
+/// Configures the compiler to keep the information flux needs, it must be called before the
+/// crate is analyzed.
+pub fn configure(config: &mut rustc_interface::interface::Config) {
+    FluxCallbacks { verify: true, ..Default::default() }.config(config);
+}
+
+/// The providers used by the `flux-driver` binary. Tools can replace some of them before passing
+/// them to [`verify`].
+pub fn default_providers() -> Providers {
+    let mut providers = Providers::default();
+    flux_desugar::provide(&mut providers);
+    flux_fhir_analysis::provide(&mut providers);
+    flux_refineck::provide(&mut providers);
+    providers.collect_specs = callbacks::collect_specs;
+    providers
+}
+
+/// Checks the current crate. Returns an error if the crate couldn't be checked at all, e.g.,
+/// because its specs are ill-formed.
+pub fn verify(tcx: TyCtxt, providers: Providers) -> Result<CrateResults, ErrorGuaranteed> {
+    callbacks::enter(tcx, providers, callbacks::check_crate)
+}
@@ -10,14 +10,68 @@ use std::{
     process::exit,
 };
 
+use flux_config as config;
 use flux_driver::callbacks::FluxCallbacks;
 use rustc_driver::{catch_with_exit_code, RunCompiler};
 
 mod logger;
 
+/// Flag to enable unstable features, e.g., `-Zflux-unstable-features=smt-backend`
+const UNSTABLE_FEATURES_FLAG: &str = "-Zflux-unstable-features";
+
+/// Flag to print the time spent in each phase per item, similar to rustc's `-Ztime-passes`
+const TIMINGS_FLAG: &str = "-Zflux-timings";
+
+/// Flag to write the constraint of each item in the given formats, e.g., `-Zflux-emit=smtlib`
+const EMIT_FLAG: &str = "-Zflux-emit";
+
+/// Flag to save the refinement tree of each item, e.g., `-Zflux-dump-refine-tree=dot`
+const DUMP_REFINE_TREE_FLAG: &str = "-Zflux-dump-refine-tree";
+
+/// Flag to trace the checker step by step in the functions matching a filter, e.g.,
+/// `-Zflux-trace=my_mod::foo`
+const TRACE_FLAG: &str = "-Zflux-trace";
+
+/// Flag to report the refined types of the variables in scope at a position in the source, e.g.,
+/// `-Zflux-hover=src/lib.rs:10:5`
+const HOVER_FLAG: &str = "-Zflux-hover";
+
+/// Flag to report the loop invariants inferred by fixpoint
+const SHOW_INVARIANTS_FLAG: &str = "-Zflux-show-invariants";
+
+/// Flag to suggest signatures for private functions without one
+const SUGGEST_SIGS_FLAG: &str = "-Zflux-suggest-sigs";
+
 fn main() -> io::Result<()> {
     let original_args = env::args().collect::<Vec<_>>();
 
+    // Unstable features are read from the environment like the rest of the configuration, so this
+    // has to happen before the configuration is first accessed.
+    if let Some(features) = arg_value(&original_args, UNSTABLE_FEATURES_FLAG, |_| true) {
+        env::set_var(config::FLUX_UNSTABLE_FEATURES_ENV_VAR, features);
+    }
+    if original_args.iter().any(|arg| arg == TIMINGS_FLAG) {
+        env::set_var(config::FLUX_TIMINGS_ENV_VAR, "1");
+    }
+    if let Some(formats) = arg_value(&original_args, EMIT_FLAG, |_| true) {
+        env::set_var(config::FLUX_EMIT_ENV_VAR, formats);
+    }
+    if let Some(format) = arg_value(&original_args, DUMP_REFINE_TREE_FLAG, |_| true) {
+        env::set_var(config::FLUX_DUMP_REFINE_TREE_ENV_VAR, format);
+    }
+    if let Some(filter) = arg_value(&original_args, TRACE_FLAG, |_| true) {
+        env::set_var(config::FLUX_TRACE_ENV_VAR, filter);
+    }
+    if let Some(position) = arg_value(&original_args, HOVER_FLAG, |_| true) {
+        env::set_var(config::FLUX_HOVER_ENV_VAR, position);
+    }
+    if original_args.iter().any(|arg| arg == SHOW_INVARIANTS_FLAG) {
+        env::set_var(config::FLUX_SHOW_INVARIANTS_ENV_VAR, "1");
+    }
+    if original_args.iter().any(|arg| arg == SUGGEST_SIGS_FLAG) {
+        env::set_var(config::FLUX_SUGGEST_SIGS_ENV_VAR, "1");
+    }
+
     let resolve_logs = logger::install()?;
 
     let context = Context::new(&original_args);
@@ -32,7 +86,18 @@ fn main() -> io::Result<()> {
     let mut args = vec![];
     let mut is_codegen = false;
     for arg in env::args() {
-        if arg.starts_with("-C") || arg.starts_with("--codegen") {
+        if arg.starts_with(UNSTABLE_FEATURES_FLAG)
+            || arg == TIMINGS_FLAG
+            || arg.starts_with(EMIT_FLAG)
+            || arg.starts_with(DUMP_REFINE_TREE_FLAG)
+            || arg.starts_with(TRACE_FLAG)
+            || arg.starts_with(HOVER_FLAG)
+            || arg == SHOW_INVARIANTS_FLAG
+            || arg == SUGGEST_SIGS_FLAG
+        {
+            // Not a rustc flag
+            continue;
+        } else if arg.starts_with("-C") || arg.starts_with("--codegen") {
             is_codegen = true;
         } else if is_codegen && arg.starts_with("incremental=") {
             is_codegen = false;
@@ -53,8 +118,11 @@ fn main() -> io::Result<()> {
     args.push("-Zcrate-attr=register_tool(flux_tool)".to_string());
     args.push("--cfg=flux".to_string());
 
-    let mut callbacks =
-        FluxCallbacks { full_compilation: context.full_compilation(), verify: context.verify() };
+    let mut callbacks = FluxCallbacks {
+        full_compilation: context.full_compilation(),
+        verify: context.verify(),
+        export_only: context.export_only(),
+    };
 
     let exit_code = catch_with_exit_code(move || RunCompiler::new(&args, &mut callbacks).run());
     resolve_logs()?;
@@ -123,7 +191,13 @@ impl FluxMetadata {
 
 /// The context in which `flux-driver` is being called.
 enum Context {
-    CargoFlux { build_script_build: bool, metadata: Option<FluxMetadata> },
+    CargoFlux {
+        build_script_build: bool,
+        /// Whether the crate belongs to one of the packages selected in the command line (e.g.,
+        /// with `-p`) as opposed to being a dependency of them
+        primary: bool,
+        metadata: Option<FluxMetadata>,
+    },
     RustcFlux,
 }
 
@@ -133,7 +207,8 @@ impl Context {
         if env::var("FLUX_CARGO").is_ok() {
             let build_script_build =
                 arg_value(args, "--crate-name", |val| val == "build_script_build").is_some();
-            Context::CargoFlux { build_script_build, metadata: FluxMetadata::read() }
+            let primary = env::var("CARGO_PRIMARY_PACKAGE").is_ok();
+            Context::CargoFlux { build_script_build, primary, metadata: FluxMetadata::read() }
         } else {
             Context::RustcFlux
         }
@@ -141,7 +216,7 @@ impl Context {
 
     fn be_rustc(&self) -> bool {
         match self {
-            Context::CargoFlux { build_script_build, metadata: manifest } => {
+            Context::CargoFlux { build_script_build, metadata: manifest, .. } => {
                 *build_script_build || manifest.is_none()
             }
             Context::RustcFlux => false,
@@ -158,6 +233,15 @@ impl Context {
         }
     }
 
+    /// Whether to only save the specs of the target crate for its dependents. We do this for
+    /// dependencies with flux enabled of the packages being checked.
+    fn export_only(&self) -> bool {
+        match self {
+            Context::CargoFlux { primary, .. } => !*primary,
+            Context::RustcFlux => false,
+        }
+    }
+
     /// When called from cargo we do a full compilation to generate artifacts needed for proc macro
     /// dependencies.
     fn full_compilation(&self) -> bool {
@@ -1,4 +1,15 @@
-use flux_common::{cache::QueryCache, dbg, iter::IterExt, result::ResultExt};
+use std::{
+    io, thread,
+    time::{Duration, Instant},
+};
+
+use flux_common::{
+    cache::QueryCache,
+    dbg,
+    iter::IterExt,
+    result::ResultExt,
+    timings::{self, Phase},
+};
 use flux_config as config;
 use flux_errors::FluxSession;
 use flux_fhir_analysis::compare_impl_item;
@@ -10,7 +21,11 @@ use flux_middle::{
     Specs,
 };
 use flux_refineck as refineck;
-use refineck::CheckerConfig;
+use refineck::{
+    incremental::Fingerprinter,
+    stats::{CheckStats, ItemStats, ObligationStats},
+    CheckerConfig, FnSolver,
+};
 use rustc_borrowck::consumers::ConsumerOptions;
 use rustc_driver::{Callbacks, Compilation};
 use rustc_errors::ErrorGuaranteed;
@@ -19,12 +34,20 @@ use rustc_interface::{interface::Compiler, Queries};
 use rustc_middle::{query, ty::TyCtxt};
 use rustc_session::config::OutputType;
 
-use crate::{collector::SpecCollector, DEFAULT_LOCALE_RESOURCES};
+use crate::{
+    api::{self, CrateResults, Invariant, ItemResult, Status, VerificationError},
+    collector::SpecCollector,
+    harness, prelude, refined_sigs, DEFAULT_LOCALE_RESOURCES,
+};
 
 #[derive(Default)]
 pub struct FluxCallbacks {
     pub full_compilation: bool,
     pub verify: bool,
+    /// Only check the specs and save them in the metadata without checking any function. This is
+    /// used for dependencies of the crates being checked, so their specs are available downstream
+    /// but no errors are reported for their code.
+    pub export_only: bool,
 }
 
 impl Callbacks for FluxCallbacks {
@@ -60,50 +83,138 @@ impl FluxCallbacks {
         }
 
         queries.global_ctxt().unwrap().enter(|tcx| {
-            let sess = FluxSession::new(
-                &tcx.sess.opts,
-                tcx.sess.psess.clone_source_map(),
-                rustc_errors::fallback_fluent_bundle(DEFAULT_LOCALE_RESOURCES.to_vec(), false),
-            );
-
-            let mut providers = Providers::default();
-            flux_desugar::provide(&mut providers);
-            flux_fhir_analysis::provide(&mut providers);
-            providers.collect_specs = collect_specs;
-
-            let cstore = CStore::load(tcx, &sess);
-            let arena = fhir::Arena::new();
-            GlobalEnv::enter(tcx, &sess, Box::new(cstore), &arena, providers, |genv| {
-                let _ = check_crate(genv);
+            enter(tcx, api::default_providers(), |genv| {
+                if self.export_only {
+                    let _ = export_crate(genv);
+                } else {
+                    let _ = check_crate(genv);
+                }
+                genv.sess().finish_diagnostics();
             });
-            sess.finish_diagnostics();
         });
     }
 }
 
-fn check_crate(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
+/// Creates a [`GlobalEnv`] for the current crate and calls `f` with it
+pub(crate) fn enter<'tcx, R>(
+    tcx: TyCtxt<'tcx>,
+    providers: Providers,
+    f: impl for<'genv> FnOnce(GlobalEnv<'genv, 'tcx>) -> R,
+) -> R {
+    let sess = FluxSession::new(
+        &tcx.sess.opts,
+        tcx.sess.psess.clone_source_map(),
+        rustc_errors::fallback_fluent_bundle(DEFAULT_LOCALE_RESOURCES.to_vec(), false),
+    );
+    let mut cstore = CStore::load(tcx, &sess, &prelude::metadata_paths(tcx));
+    // Extern specs in dependencies take precedence over the built-in ones
+    let overridden = prelude::overridden_groups(tcx, cstore.extern_spec_ids());
+    cstore.drop_prelude_groups(|group| overridden.contains(&group));
+    let arena = fhir::Arena::new();
+    GlobalEnv::enter(tcx, &sess, Box::new(cstore), &arena, providers, f)
+}
+
+/// Checks all the items in the crate. Errors found while checking an item are reported and
+/// recorded in the results of the item, we only return an error if the crate couldn't be checked.
+pub(crate) fn check_crate(genv: GlobalEnv) -> Result<CrateResults, ErrorGuaranteed> {
     tracing::info_span!("check_crate").in_scope(move || {
         tracing::info!("Callbacks::check_wf");
 
         flux_fhir_analysis::check_crate_wf(genv)?;
-        let mut ck = CrateChecker::new(genv);
+        if config::emit().refined_sigs {
+            refined_sigs::emit(genv).unwrap_or(());
+        }
+        if config::emit().proptest {
+            harness::emit(genv, harness::Backend::Proptest).unwrap_or(());
+        }
+        if config::emit().kani {
+            harness::emit(genv, harness::Backend::Kani).unwrap_or(());
+        }
+        let cache = QueryCache::load();
+        // The fixpoint queries of functions are solved in background threads while the rest of
+        // the crate is checked.
+        let results = thread::scope(|s| {
+            let mut ck = CrateChecker::new(genv, &cache, FnSolver::new(s, &cache));
 
-        let crate_items = genv.tcx().hir_crate_items(());
+            let crate_items = genv.tcx().hir_crate_items(());
 
-        let result = crate_items
-            .definitions()
-            .try_for_each_exhaust(|def_id| ck.check_def(def_id));
+            let _ = crate_items.definitions().try_for_each_exhaust(|def_id| {
+                let result = ck.check_def(def_id);
+                let _ = ck.report_solved(false);
+                result
+            });
+            let _ = ck.report_solved(true);
 
-        ck.cache.save().unwrap_or(());
+            if config::summary() {
+                eprint!("{}", ck.stats);
+                print_trusted(&ck.results);
+                ck.stats.save().unwrap_or(());
+            }
+            if config::stats() {
+                ck.check_stats.save().unwrap_or(());
+            }
+            ck.results
+        });
+
+        cache.save().unwrap_or(());
+        if config::timings() {
+            timings::print(io::stderr()).unwrap_or(());
+        }
 
         tracing::info!("Callbacks::check_crate");
         save_metadata(&genv);
 
-        result
+        Ok(CrateResults { items: results })
     })
 }
 
-fn collect_specs(genv: GlobalEnv) -> Specs {
+/// Prints the items that weren't checked because they are trusted, together with the reason they
+/// are trusted, so trusted code can be audited from the summary
+fn print_trusted(results: &[ItemResult]) {
+    let mut trusted = results
+        .iter()
+        .filter_map(|item| {
+            if let Status::Trusted { reason } = item.status {
+                Some((&item.path, reason))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    trusted.sort_by_key(|(path, _)| *path);
+    eprintln!("trusted: {} items", trusted.len());
+    for (path, reason) in trusted {
+        match reason {
+            Some(reason) => eprintln!("  {path}: {reason}"),
+            None => eprintln!("  {path}"),
+        }
+    }
+}
+
+/// Whether checking a function produces output besides its errors, e.g., hover information or
+/// emitted constraints. Unchanged functions can't be skipped in that case because the output would
+/// be missing for them.
+fn reports_per_fn(checker_config: &CheckerConfig) -> bool {
+    config::hover().is_some()
+        || config::trace().is_some()
+        || config::emit().any()
+        || config::show_invariants()
+        || config::suggest_sigs()
+        || config::dump_constraint()
+        || config::dump_checker_trace()
+        || config::dump_refine_tree().is_some()
+        || checker_config.warn_holes
+}
+
+fn export_crate(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
+    tracing::info_span!("export_crate").in_scope(move || {
+        flux_fhir_analysis::check_crate_wf(genv)?;
+        save_metadata(&genv);
+        Ok(())
+    })
+}
+
+pub(crate) fn collect_specs(genv: GlobalEnv) -> Specs {
     match SpecCollector::collect(genv.tcx(), genv.sess()) {
         Ok(specs) => specs,
         Err(err) => {
@@ -125,20 +236,129 @@ fn save_metadata(genv: &GlobalEnv) {
     }
 }
 
-struct CrateChecker<'genv, 'tcx> {
+struct CrateChecker<'ck, 'genv, 'tcx> {
     genv: GlobalEnv<'genv, 'tcx>,
-    cache: QueryCache,
+    cache: &'ck QueryCache,
+    stats: ObligationStats,
+    check_stats: CheckStats,
     checker_config: CheckerConfig,
+    /// Used to skip functions that haven't changed since the last run when caching is enabled
+    fingerprinter: Option<Fingerprinter>,
+    /// Solves the fixpoint queries of functions concurrently with checking the rest of the crate
+    solver: FnSolver,
+    /// The functions whose queries were submitted to `solver`, indexed by the index of the query
+    submitted: Vec<SubmittedFn>,
+    results: Vec<ItemResult>,
 }
 
-impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
-    fn new(genv: GlobalEnv<'genv, 'tcx>) -> Self {
+struct SubmittedFn {
+    def_id: LocalDefId,
+    /// The path of the function and its fingerprint if it should be recorded in the cache
+    fingerprint: Option<(String, u64)>,
+    /// The path of the function and the time it took to generate its query
+    check_time: (String, Duration),
+}
+
+impl<'ck, 'genv, 'tcx> CrateChecker<'ck, 'genv, 'tcx> {
+    fn new(genv: GlobalEnv<'genv, 'tcx>, cache: &'ck QueryCache, solver: FnSolver) -> Self {
         let crate_config = genv.crate_config().unwrap_or_default();
         let checker_config = CheckerConfig {
             check_overflow: crate_config.check_overflow,
             scrape_quals: crate_config.scrape_quals,
+            mine_quals: crate_config.mine_quals,
+            auto_specs: crate_config.auto_specs,
+            check_panics: crate_config.check_panics,
+            warn_holes: crate_config.warn_holes,
+            unpack: crate_config.unpack,
+            solver: crate_config.solver,
+            solver_args: crate_config.solver_args,
+            smt_backend: crate_config
+                .unstable_features
+                .is_enabled(config::UnstableFeature::SmtBackend),
+            timeout: crate_config.timeout,
+        };
+        let fingerprinter = if config::is_cache_enabled() && !reports_per_fn(&checker_config) {
+            Fingerprinter::new(genv, &checker_config).ok()
+        } else {
+            None
         };
-        CrateChecker { genv, cache: QueryCache::load(), checker_config }
+        CrateChecker {
+            genv,
+            cache,
+            stats: ObligationStats::new(),
+            check_stats: CheckStats::new(),
+            checker_config,
+            fingerprinter,
+            solver,
+            submitted: vec![],
+            results: vec![],
+        }
+    }
+
+    /// Reports the outcome of the queries solved so far. If `wait` is true, this waits until all
+    /// the submitted queries are solved. Errors are reported in the order the functions were
+    /// checked, so the output doesn't depend on the order in which queries finish.
+    fn report_solved(&mut self, wait: bool) -> Result<(), ErrorGuaranteed> {
+        let submitted = &self.submitted;
+        let cache = self.cache;
+        let genv = self.genv;
+        let check_stats = &mut self.check_stats;
+        let results = &mut self.results;
+        let mut result = Ok(());
+        self.solver.poll(wait, |idx, outcome, solve_stats| {
+            let SubmittedFn { def_id, fingerprint, check_time: (key, check_time) } =
+                &submitted[idx];
+            let stats = ItemStats {
+                check_time: *check_time,
+                solve_time: solve_stats.time,
+                kvars: solve_stats.kvars,
+                constraints: solve_stats.constraints,
+            };
+            check_stats.insert(key.clone(), stats);
+            timings::record(Phase::Fixpoint, key.clone(), solve_stats.time);
+            refineck::report_invariants(genv, &outcome.invariants);
+            refineck::report_suggested_sig(genv, outcome.suggested_sig.as_ref());
+            let (status, errors) = match &outcome.errors {
+                Ok(errors) if errors.is_empty() => (Status::Safe, vec![]),
+                Ok(errors) => {
+                    let errors = errors
+                        .iter()
+                        .map(|err| {
+                            VerificationError { span: err.tag.src_span, reason: err.tag.reason }
+                        })
+                        .collect();
+                    (Status::Unsafe, errors)
+                }
+                Err(_) => (Status::Timeout, vec![]),
+            };
+            results.push(ItemResult {
+                def_id: *def_id,
+                path: key.clone(),
+                status,
+                errors,
+                invariants: outcome
+                    .invariants
+                    .iter()
+                    .map(|inv| Invariant { span: inv.span, pred: inv.pred.clone() })
+                    .collect(),
+            });
+            match refineck::report_fn_errors(genv, *def_id, outcome.errors) {
+                Ok(()) => {
+                    if let Some((key, fingerprint)) = fingerprint {
+                        cache.insert_fn(key.clone(), *fingerprint);
+                    }
+                }
+                Err(err) => result = Err(err),
+            }
+        });
+        result
+    }
+
+    /// Records the result of checking an item without a fixpoint query to solve
+    fn record(&mut self, def_id: LocalDefId, status: Status) {
+        let path = self.genv.tcx().def_path_str(def_id.to_def_id());
+        self.results
+            .push(ItemResult { def_id, path, status, errors: vec![], invariants: vec![] });
     }
 
     fn matches_check_def(&self, def_id: LocalDefId) -> bool {
@@ -150,6 +370,12 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
         if self.genv.ignored(def_id) == Ignored::Yes || !self.matches_check_def(def_id) {
             return Ok(());
         }
+        if let Some(trusted) = self.genv.deep_trusted_ancestor(def_id) {
+            if matches!(self.genv.def_kind(def_id), DefKind::Fn | DefKind::AssocFn) {
+                self.record(def_id, Status::Trusted { reason: trusted.reason });
+            }
+            return Ok(());
+        }
 
         match self.genv.def_kind(def_id) {
             DefKind::Fn | DefKind::AssocFn => {
@@ -161,7 +387,42 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
                     .body_id()
                     .is_some()
                 {
-                    refineck::check_fn(self.genv, &mut self.cache, def_id, self.checker_config)?;
+                    let key = self.genv.tcx().def_path_str(def_id.to_def_id());
+                    let fingerprint = self
+                        .fingerprinter
+                        .as_ref()
+                        .and_then(|fp| fp.fingerprint(self.genv, def_id).ok());
+                    if let Some(fingerprint) = fingerprint
+                        && self.cache.is_fn_unchanged(&key, fingerprint)
+                    {
+                        self.record(def_id, Status::Safe);
+                        return Ok(());
+                    }
+                    let start = Instant::now();
+                    let query =
+                        refineck::fn_query(self.genv, &mut self.stats, def_id, self.checker_config)
+                            .inspect_err(|_| self.record(def_id, Status::Error))?;
+                    let check_time = (key.clone(), start.elapsed());
+                    match query {
+                        Some(query) => {
+                            let fingerprint = fingerprint.map(|fingerprint| (key, fingerprint));
+                            self.solver.submit(query);
+                            self.submitted
+                                .push(SubmittedFn { def_id, fingerprint, check_time });
+                        }
+                        None => {
+                            if let Some(fingerprint) = fingerprint {
+                                self.cache.insert_fn(key, fingerprint);
+                            }
+                            let status = if self.genv.map().is_trusted(def_id).unwrap_or(false) {
+                                let reason = self.genv.trusted(def_id).and_then(|t| t.reason);
+                                Status::Trusted { reason }
+                            } else {
+                                Status::Safe
+                            };
+                            self.record(def_id, status);
+                        }
+                    }
                 }
                 Ok(())
             }
@@ -173,14 +434,17 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
                     .expect_item(def_id)
                     .emit(&self.genv)?
                     .expect_enum();
-                refineck::invariants::check_invariants(
+                let result = refineck::invariants::check_invariants(
                     self.genv,
-                    &mut self.cache,
+                    &self.cache,
+                    &mut self.stats,
                     def_id,
                     enum_def.invariants,
                     &adt_def,
                     self.checker_config,
-                )
+                );
+                self.record(def_id, if result.is_ok() { Status::Safe } else { Status::Unsafe });
+                result
             }
             DefKind::Struct => {
                 let adt_def = self.genv.adt_def(def_id.to_def_id()).emit(&self.genv)?;
@@ -193,14 +457,17 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
                 if struct_def.is_opaque() {
                     return Ok(());
                 }
-                refineck::invariants::check_invariants(
+                let result = refineck::invariants::check_invariants(
                     self.genv,
-                    &mut self.cache,
+                    &self.cache,
+                    &mut self.stats,
                     def_id,
                     struct_def.invariants,
                     &adt_def,
                     self.checker_config,
-                )
+                );
+                self.record(def_id, if result.is_ok() { Status::Safe } else { Status::Unsafe });
+                result
             }
             DefKind::Impl { of_trait } => {
                 if of_trait {
@@ -1,9 +1,15 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap, iter};
 
 use flux_common::{iter::IterExt, result::ResultExt};
-use flux_config::{self as config, CrateConfig};
+use flux_config::{
+    self as config, CrateConfig, SmtSolver, SolverArgs, UnpackPolicy, UnstableFeatures,
+};
 use flux_errors::FluxSession;
-use flux_middle::{fhir::Ignored, rustc::lowering::resolve_trait_ref_impl_id, Specs};
+use flux_middle::{
+    fhir::{Ignored, Intrinsic, Trusted},
+    rustc::lowering::resolve_trait_ref_impl_id,
+    Opts, Specs,
+};
 use flux_syntax::{surface, ParseResult, ParseSess};
 use itertools::Itertools;
 use rustc_ast::{
@@ -14,18 +20,25 @@ use rustc_errors::{Diagnostic, ErrorGuaranteed};
 use rustc_hir::{
     def::DefKind,
     def_id::{DefId, LocalDefId, CRATE_DEF_ID},
-    AssocItemKind, EnumDef, GenericBounds, ImplItemKind, ImplItemRef, Item, ItemKind, OwnerId,
-    VariantData,
+    intravisit, AssocItemKind, EnumDef, GenericBounds, HirId, ImplItemKind, ImplItemRef, Item,
+    ItemKind, OwnerId, Stmt, TraitFn, TraitItemKind, VariantData,
 };
 use rustc_middle::ty::{TraitPredicate, TyCtxt};
 use rustc_span::{Span, Symbol, SyntaxContext};
 
+use crate::{
+    manifest::CrateConfigFile,
+    prelude,
+    sidecar::{self, SidecarSpecs},
+};
+
 type Result<T = ()> = std::result::Result<T, ErrorGuaranteed>;
 
 pub(crate) struct SpecCollector<'tcx, 'a> {
     tcx: TyCtxt<'tcx>,
     parse_sess: ParseSess,
     specs: Specs,
+    sidecar: SidecarSpecs,
     sess: &'a FluxSession,
     error_guaranteed: Option<ErrorGuaranteed>,
 }
@@ -44,57 +57,170 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
             parse_sess: ParseSess::default(),
             sess,
             specs: Specs::default(),
+            sidecar: SidecarSpecs::load(tcx, sess)?,
             error_guaranteed: None,
         };
 
+        collector.parse_crate_config_file()?;
         collector.parse_crate_spec(tcx.hir().krate_attrs())?;
 
-        let crate_items = tcx.hir_crate_items(());
+        collector.collect_items()?;
+        collector.collect_stmt_annots();
+
+        // The user's own extern specs take precedence over the built-in ones
+        collector.specs.dropped_prelude_groups =
+            prelude::overridden_groups(tcx, collector.specs.extern_specs.keys().copied());
+
+        if let Some(e) = collector.error_guaranteed {
+            Err(e)
+        } else {
+            Ok(collector.specs)
+        }
+    }
+
+    /// Collects the specs of all the items in the crate
+    fn collect_items(&mut self) -> Result {
+        let crate_items = self.tcx.hir_crate_items(());
 
         for item_id in crate_items.free_items() {
-            let item = tcx.hir().item(item_id);
-            let hir_id = item.hir_id();
-            let attrs = tcx.hir().attrs(hir_id);
+            let item = self.tcx.hir().item(item_id);
             let owner_id = item.owner_id;
+            let attrs = self.attrs(item.hir_id(), owner_id.def_id);
+            let attrs = &*attrs;
             let _ = match &item.kind {
-                ItemKind::Fn(..) => collector.parse_fn_spec(owner_id, attrs, DefKind::Fn),
-                ItemKind::Struct(data, ..) => collector.parse_struct_def(owner_id, attrs, data),
-                ItemKind::Enum(def, ..) => collector.parse_enum_def(owner_id, attrs, def),
-                ItemKind::Mod(..) => collector.parse_mod_spec(owner_id.def_id, attrs),
-                ItemKind::TyAlias(..) => collector.parse_tyalias_spec(owner_id, attrs),
-                ItemKind::Const(..) => collector.parse_const_spec(owner_id.def_id, item, attrs),
-                ItemKind::Impl(impl_) => collector.parse_impl_specs(owner_id, attrs, impl_),
+                ItemKind::Fn(..) => self.parse_fn_spec(owner_id, attrs, DefKind::Fn),
+                ItemKind::Struct(data, ..) => self.parse_struct_def(owner_id, attrs, data),
+                ItemKind::Enum(def, ..) => self.parse_enum_def(owner_id, attrs, def),
+                ItemKind::Mod(..) => self.parse_mod_spec(owner_id.def_id, attrs),
+                ItemKind::TyAlias(..) => self.parse_tyalias_spec(owner_id, attrs),
+                ItemKind::Const(..) => self.parse_const_spec(owner_id.def_id, item, attrs),
+                ItemKind::Impl(impl_) => self.parse_impl_specs(owner_id, attrs, impl_),
                 ItemKind::Trait(_, _, _, bounds, _) => {
-                    collector.parse_trait_specs(owner_id, attrs, bounds)
+                    self.parse_trait_specs(owner_id, attrs, bounds)
                 }
                 _ => Ok(()),
             };
         }
 
         for trait_item_id in crate_items.trait_items() {
-            let attrs = tcx.hir().attrs(trait_item_id.hir_id());
-            if let rustc_hir::TraitItemKind::Fn(_, _) = tcx.hir().trait_item(trait_item_id).kind {
-                collector.parse_fn_spec(trait_item_id.owner_id, attrs, DefKind::AssocFn)?;
+            let attrs = self.attrs(trait_item_id.hir_id(), trait_item_id.owner_id.def_id);
+            if let rustc_hir::TraitItemKind::Fn(_, _) =
+                self.tcx.hir().trait_item(trait_item_id).kind
+            {
+                self.parse_fn_spec(trait_item_id.owner_id, &attrs, DefKind::AssocFn)?;
             }
         }
 
         for impl_item_id in crate_items.impl_items() {
-            let impl_item = tcx.hir().impl_item(impl_item_id);
+            let impl_item = self.tcx.hir().impl_item(impl_item_id);
             let owner_id = impl_item.owner_id;
             if let ImplItemKind::Fn(..) = &impl_item.kind {
-                let hir_id = impl_item.hir_id();
-                let attrs = tcx.hir().attrs(hir_id);
-                let _ = collector.parse_fn_spec(owner_id, attrs, DefKind::AssocFn);
+                let attrs = self.attrs(impl_item.hir_id(), owner_id.def_id);
+                let _ = self.parse_fn_spec(owner_id, &attrs, DefKind::AssocFn);
             }
         }
 
-        if let Some(e) = collector.error_guaranteed {
-            Err(e)
+        Ok(())
+    }
+
+    /// Collects the `#[flux::assert(..)]` and `#[flux::assume(..)]` annotations on the statements
+    /// in the bodies of the crate. Other flux attributes on statements are ignored.
+    fn collect_stmt_annots(&mut self) {
+        let hir = self.tcx.hir();
+        for def_id in hir.body_owners() {
+            let body = hir.body(hir.body_owned_by(def_id));
+            let mut finder = StmtAnnotFinder { collector: self, annots: vec![] };
+            intravisit::walk_body(&mut finder, body);
+            let annots = finder.annots;
+            if !annots.is_empty() {
+                self.specs.stmt_annots.insert(def_id, annots);
+            }
+        }
+    }
+
+    fn parse_stmt_annot(
+        &mut self,
+        attr: &Attribute,
+        stmt_span: Span,
+    ) -> Option<surface::StmtAnnot> {
+        let AttrKind::Normal(attr_item, ..) = &attr.kind else { return None };
+        let attr_item = &attr_item.item;
+        let [first, segment] = &attr_item.path.segments[..] else { return None };
+        if !matches!(first.ident.as_str(), "flux" | "flux_tool") {
+            return None;
+        }
+        let kind = match segment.ident.as_str() {
+            "assert" => surface::StmtAnnotKind::Assert,
+            "assume" => surface::StmtAnnotKind::Assume,
+            _ => return None,
+        };
+        let AttrArgs::Delimited(dargs) = &attr_item.args else {
+            self.emit_err(errors::InvalidAttr { span: attr_item.span() });
+            return None;
+        };
+        let entire = dargs.dspan.entire().with_ctxt(SyntaxContext::root());
+        match self.parse_sess.parse_expr(&dargs.tokens, entire) {
+            Ok(pred) => Some(surface::StmtAnnot { kind, pred, span: attr.span, stmt_span }),
+            Err(err) => {
+                self.emit_err(errors::SyntaxErr::from(err));
+                None
+            }
+        }
+    }
+
+    /// The attributes of an item in the source followed by the ones in the spec file of the crate
+    /// (see [`SidecarSpecs`])
+    fn attrs(&self, hir_id: HirId, def_id: LocalDefId) -> Cow<'tcx, [Attribute]> {
+        let attrs = self.tcx.hir().attrs(hir_id);
+        let sidecar = self.sidecar.attrs(def_id);
+        if sidecar.is_empty() {
+            Cow::Borrowed(attrs)
         } else {
-            Ok(collector.specs)
+            Cow::Owned(attrs.iter().chain(sidecar).cloned().collect())
         }
     }
 
+    /// Reads the settings of the crate from its manifest (see [`CrateConfigFile`]). They are
+    /// overridden by `#![flux::cfg(..)]` and the `ignore` globs by `#[flux::ignore]` attributes.
+    fn parse_crate_config_file(&mut self) -> Result {
+        let file = match CrateConfigFile::find() {
+            Ok(Some(file)) => file,
+            Ok(None) => return Ok(()),
+            Err((path, message)) => {
+                return Err(self.emit_err(errors::CrateConfigFileError {
+                    path: path.display().to_string(),
+                    message,
+                }));
+            }
+        };
+        let mut cfg = FluxAttrCFG { map: HashMap::new() };
+        for (name, value) in &file.settings {
+            let setting = CFGSetting { setting: Symbol::intern(value), span: rustc_span::DUMMY_SP };
+            cfg.map.insert(name.clone(), setting);
+        }
+        let crate_config = cfg
+            .read_crate_cfg(CrateConfig::default())
+            .and_then(|crate_config| {
+                cfg.check_no_settings_left()?;
+                Ok(crate_config)
+            })
+            .map_err(|err| {
+                self.emit_err(errors::CrateConfigFileError {
+                    path: file.path.display().to_string(),
+                    message: err.message,
+                })
+            })?;
+        self.specs.crate_config = Some(crate_config);
+
+        for def_id in self.tcx.hir_crate_items(()).definitions() {
+            let def_path = sidecar::strip_generic_args(&self.tcx.def_path_str(def_id.to_def_id()));
+            if file.is_ignored(&def_path) {
+                self.specs.check_item.insert(def_id, Ignored::Yes);
+            }
+        }
+        Ok(())
+    }
+
     fn parse_crate_spec(&mut self, attrs: &[Attribute]) -> Result {
         // TODO(atgeller) error if non-crate attributes
         // TODO(atgeller) error if >1 cfg attributes
@@ -105,11 +231,15 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
         if let Some(ignored) = attrs.ignore() {
             self.specs.check_item.insert(CRATE_DEF_ID, ignored);
         }
+        if let Some(opts) = attrs.opts() {
+            self.specs.opts.insert(CRATE_DEF_ID, opts);
+        }
 
         self.specs.extend_items(attrs.items());
 
-        let crate_config = attrs.crate_config();
-        self.specs.crate_config = crate_config;
+        if let Some(crate_config) = attrs.crate_config() {
+            self.specs.crate_config = Some(crate_config);
+        }
         Ok(())
     }
 
@@ -120,6 +250,9 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
         if let Some(ignored) = attrs.ignore() {
             self.specs.check_item.insert(def_id, ignored);
         }
+        if let Some(opts) = attrs.opts() {
+            self.specs.opts.insert(def_id, opts);
+        }
 
         self.specs.extend_items(attrs.items());
         Ok(())
@@ -150,6 +283,12 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
     ) -> Result {
         let mut attrs = self.parse_flux_attrs(attrs, DefKind::Trait)?;
         self.report_dups(&attrs)?;
+        if let Some(ignored) = attrs.ignore() {
+            self.specs.check_item.insert(owner_id.def_id, ignored);
+        }
+        if let Some(opts) = attrs.opts() {
+            self.specs.opts.insert(owner_id.def_id, opts);
+        }
 
         let generics = attrs.generics();
         let assoc_refinements = attrs.trait_assoc_refts();
@@ -176,6 +315,12 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
         let def_kind = DefKind::Impl { of_trait: impl_.of_trait.is_some() };
         let mut attrs = self.parse_flux_attrs(attrs, def_kind)?;
         self.report_dups(&attrs)?;
+        if let Some(ignored) = attrs.ignore() {
+            self.specs.check_item.insert(owner_id.def_id, ignored);
+        }
+        if let Some(opts) = attrs.opts() {
+            self.specs.opts.insert(owner_id.def_id, opts);
+        }
 
         let generics = attrs.generics();
         let assoc_refinements = attrs.impl_assoc_refts();
@@ -199,6 +344,9 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
 
     fn parse_tyalias_spec(&mut self, owner_id: OwnerId, attrs: &[Attribute]) -> Result {
         let mut attrs = self.parse_flux_attrs(attrs, DefKind::TyAlias)?;
+        if let Some(ignored) = attrs.ignore() {
+            self.specs.check_item.insert(owner_id.def_id, ignored);
+        }
         self.specs.ty_aliases.insert(owner_id, attrs.ty_alias());
         Ok(())
     }
@@ -211,6 +359,9 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
     ) -> Result {
         let mut attrs = self.parse_flux_attrs(attrs, DefKind::Struct)?;
         self.report_dups(&attrs)?;
+        if let Some(ignored) = attrs.ignore() {
+            self.specs.check_item.insert(owner_id.def_id, ignored);
+        }
         // TODO(nilehmann) error if it has non-struct attrs
 
         let mut opaque = attrs.opaque();
@@ -219,8 +370,22 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
 
         let generics = attrs.generics();
 
-        let fields = data
-            .fields()
+        // The last field of an extern_spec dummy struct is the external struct it is meant to
+        // represent. The fields before it (if any) mirror the fields of the external struct.
+        let struct_fields = if attrs.extern_spec() {
+            let fields = data
+                .fields()
+                .split_last()
+                .map_or(&[][..], |(_, fields)| fields);
+            if fields.is_empty() {
+                opaque = true;
+            }
+            fields
+        } else {
+            data.fields()
+        };
+
+        let fields = struct_fields
             .iter()
             .map(|field| self.parse_field_spec(field, opaque))
             .try_collect_exhaust()?;
@@ -228,11 +393,11 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
         let invariants = attrs.invariants();
 
         let extern_id = if attrs.extern_spec() {
-            // extern_spec dummy structs are always opaque because they contain
-            // one field: the external struct they are meant to represent.
-            opaque = true;
             let extern_id =
                 self.extract_extern_def_id_from_extern_spec_struct(owner_id.def_id, data)?;
+            if !struct_fields.is_empty() {
+                self.check_extern_struct_fields(owner_id.def_id, extern_id, data)?;
+            }
             self.specs.extern_specs.insert(extern_id, owner_id.def_id);
             Some(extern_id)
         } else {
@@ -260,8 +425,8 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
         field: &rustc_hir::FieldDef,
         opaque: bool,
     ) -> Result<Option<surface::Ty>> {
-        let attrs = self.tcx.hir().attrs(field.hir_id);
-        let mut attrs = self.parse_flux_attrs(attrs, DefKind::Field)?;
+        let attrs = self.attrs(field.hir_id, field.def_id);
+        let mut attrs = self.parse_flux_attrs(&attrs, DefKind::Field)?;
         self.report_dups(&attrs)?;
         let field_attr = attrs.field();
 
@@ -285,6 +450,9 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
     ) -> Result {
         let mut attrs = self.parse_flux_attrs(attrs, DefKind::Enum)?;
         self.report_dups(&attrs)?;
+        if let Some(ignored) = attrs.ignore() {
+            self.specs.check_item.insert(owner_id.def_id, ignored);
+        }
 
         let generics = attrs.generics();
 
@@ -303,6 +471,11 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
 
         let invariants = attrs.invariants();
 
+        let reflected = attrs.reflect();
+        if reflected {
+            self.check_reflected_enum(owner_id, enum_def, &refined_by, &variants, &invariants)?;
+        }
+
         let extern_id = if attrs.extern_spec() {
             let extern_id =
                 self.extract_extern_def_id_from_extern_spec_enum(owner_id.def_id, enum_def)?;
@@ -321,18 +494,43 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
                 invariants,
                 node_id: self.parse_sess.next_node_id(),
                 extern_id,
+                reflected,
             },
         );
         Ok(())
     }
 
+    /// A reflected enum is indexed by its variants, so it can't have fields or be refined any other
+    /// way.
+    fn check_reflected_enum(
+        &mut self,
+        owner_id: OwnerId,
+        enum_def: &EnumDef,
+        refined_by: &Option<surface::RefinedBy>,
+        variants: &[Option<surface::VariantDef>],
+        invariants: &[surface::Expr],
+    ) -> Result {
+        if let Some(variant) = enum_def
+            .variants
+            .iter()
+            .find(|variant| !variant.data.fields().is_empty())
+        {
+            return Err(self.emit_err(errors::InvalidReflect::new(variant.span)));
+        }
+        if refined_by.is_some() || variants.iter().any(Option::is_some) || !invariants.is_empty() {
+            let span = self.tcx.def_span(owner_id);
+            return Err(self.emit_err(errors::InvalidReflect::new(span)));
+        }
+        Ok(())
+    }
+
     fn parse_variant(
         &mut self,
         hir_variant: &rustc_hir::Variant,
         has_refined_by: bool,
     ) -> Result<Option<surface::VariantDef>> {
-        let attrs = self.tcx.hir().attrs(hir_variant.hir_id);
-        let mut attrs = self.parse_flux_attrs(attrs, DefKind::Variant)?;
+        let attrs = self.attrs(hir_variant.hir_id, hir_variant.def_id);
+        let mut attrs = self.parse_flux_attrs(&attrs, DefKind::Variant)?;
         self.report_dups(&attrs)?;
 
         let variant = attrs.variant();
@@ -353,8 +551,33 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
         let mut attrs = self.parse_flux_attrs(attrs, def_kind)?;
         self.report_dups(&attrs)?;
         // TODO(nilehmann) error if it has non-fun attrs
+        if let Some(ignored) = attrs.ignore() {
+            self.specs.check_item.insert(owner_id.def_id, ignored);
+        }
 
-        let mut trusted = attrs.trusted();
+        let mut trusted = false;
+        if let Some(trusted_attr) = attrs.trusted() {
+            self.specs.trusted.insert(owner_id.def_id, trusted_attr);
+            trusted = true;
+        }
+        let inline = attrs.inline();
+        let should_fail = attrs.should_fail();
+        if let Some(intrinsic) = attrs.intrinsic() {
+            self.check_intrinsic_sig(owner_id, intrinsic)?;
+            self.specs.intrinsics.insert(owner_id.def_id, intrinsic);
+            // The meaning of an intrinsic is built into the checker, so there's nothing to check
+            trusted = true;
+        }
+        if let Some(policy) = attrs.unpack_policy() {
+            self.specs.unpack_policies.insert(owner_id.def_id, policy);
+        }
+        let mut opts = attrs.opts();
+        if let Some(solver) = attrs.solver() {
+            opts.get_or_insert_with(Opts::default).solver = Some(solver);
+        }
+        if let Some(opts) = opts {
+            self.specs.opts.insert(owner_id.def_id, opts);
+        }
         let fn_sig = attrs.fn_sig();
         let qual_names: Option<surface::QualNames> = attrs.qual_names();
         let extern_id = if attrs.extern_spec() {
@@ -364,6 +587,9 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
                 }));
             }
             let extern_def_id = self.extract_extern_def_id_from_extern_spec_fn(owner_id.def_id)?;
+            if self.tcx.def_kind(owner_id) == DefKind::Fn {
+                self.check_extern_fn_target(owner_id.def_id, extern_def_id)?;
+            }
             self.specs
                 .extern_specs
                 .insert(extern_def_id, owner_id.def_id);
@@ -373,9 +599,48 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
         } else {
             None
         };
-        self.specs
-            .fn_sigs
-            .insert(owner_id, surface::FnSpec { fn_sig, trusted, qual_names, extern_id });
+        self.specs.fn_sigs.insert(
+            owner_id,
+            surface::FnSpec { fn_sig, trusted, inline, should_fail, qual_names, extern_id },
+        );
+        Ok(())
+    }
+
+    /// Intrinsics that aren't checked, e.g., `assume`, must be `unsafe` to make it explicit at call
+    /// sites.
+    fn check_intrinsic_sig(&mut self, owner_id: OwnerId, intrinsic: Intrinsic) -> Result {
+        let fn_sig = self
+            .tcx
+            .fn_sig(owner_id)
+            .instantiate_identity()
+            .skip_binder();
+        let (valid, expected) = match intrinsic {
+            Intrinsic::Assume => {
+                let valid = fn_sig.unsafety == rustc_hir::Unsafety::Unsafe
+                    && fn_sig.inputs() == [self.tcx.types.bool]
+                    && fn_sig.output().is_unit();
+                (valid, "unsafe fn(bool)")
+            }
+            Intrinsic::Assert => {
+                let valid = fn_sig.unsafety == rustc_hir::Unsafety::Normal
+                    && fn_sig.inputs() == [self.tcx.types.bool]
+                    && fn_sig.output().is_unit();
+                (valid, "fn(bool)")
+            }
+            Intrinsic::Pack | Intrinsic::Unpack => {
+                let valid = fn_sig.unsafety == rustc_hir::Unsafety::Normal
+                    && matches!(fn_sig.inputs(), [input] if input.is_ref())
+                    && fn_sig.output().is_unit();
+                (valid, "fn(&T)")
+            }
+        };
+        if !valid {
+            return Err(self.emit_err(errors::InvalidIntrinsicSig {
+                span: self.tcx.def_span(owner_id),
+                intrinsic: intrinsic.name(),
+                expected,
+            }));
+        }
         Ok(())
     }
 
@@ -459,10 +724,18 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
             ("invariant", AttrArgs::Delimited(dargs)) => {
                 self.parse(dargs, ParseSess::parse_expr, FluxAttrKind::Invariant)?
             }
+            ("opts", AttrArgs::Delimited(..)) => {
+                let opts = FluxAttrCFG::parse_cfg(attr_item)
+                    .emit(self.sess)?
+                    .try_into_opts()
+                    .emit(self.sess)?;
+                FluxAttrKind::Opts(opts)
+            }
             ("cfg", AttrArgs::Delimited(..)) => {
+                let base = self.specs.crate_config.unwrap_or_default();
                 let crate_cfg = FluxAttrCFG::parse_cfg(attr_item)
                     .emit(self.sess)?
-                    .try_into_crate_cfg()
+                    .try_into_crate_cfg(base)
                     .emit(self.sess)?;
                 FluxAttrKind::CrateConfig(crate_cfg)
             }
@@ -479,7 +752,37 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
                 }
             }
             ("opaque", AttrArgs::Empty) => FluxAttrKind::Opaque,
-            ("trusted", AttrArgs::Empty) => FluxAttrKind::Trusted,
+            ("reflect", AttrArgs::Empty) => FluxAttrKind::Reflect,
+            ("trusted", AttrArgs::Empty) => FluxAttrKind::Trusted(Trusted::default()),
+            ("trusted", AttrArgs::Delimited(..)) => {
+                FluxAttrKind::Trusted(self.parse_trusted(attr_item)?)
+            }
+            ("inline", AttrArgs::Empty) => FluxAttrKind::Inline,
+            ("should_fail", AttrArgs::Empty) => FluxAttrKind::ShouldFail,
+            ("unpack_policy", AttrArgs::Delimited(dargs)) => {
+                let val = tts_to_string(&dargs.tokens);
+                match val.parse() {
+                    Ok(policy) => FluxAttrKind::UnpackPolicy(policy),
+                    Err(_) => Err(self.emit_err(errors::InvalidAttr { span: attr_item.span() }))?,
+                }
+            }
+            ("solver", AttrArgs::Delimited(dargs)) => {
+                let val = tts_to_string(&dargs.tokens);
+                match val.parse() {
+                    Ok(solver) => FluxAttrKind::Solver(solver),
+                    Err(_) => Err(self.emit_err(errors::InvalidAttr { span: attr_item.span() }))?,
+                }
+            }
+            ("intrinsic", AttrArgs::Delimited(dargs)) => {
+                let val = tts_to_string(&dargs.tokens);
+                match &val[..] {
+                    "assume" => FluxAttrKind::Intrinsic(Intrinsic::Assume),
+                    "assert" => FluxAttrKind::Intrinsic(Intrinsic::Assert),
+                    "pack" => FluxAttrKind::Intrinsic(Intrinsic::Pack),
+                    "unpack" => FluxAttrKind::Intrinsic(Intrinsic::Unpack),
+                    _ => Err(self.emit_err(errors::InvalidAttr { span: attr_item.span() }))?,
+                }
+            }
             ("fake_impl", AttrArgs::Empty) => FluxAttrKind::FakeImpl,
             ("extern_spec", AttrArgs::Empty) => FluxAttrKind::ExternSpec,
             _ => return Err(self.emit_err(errors::InvalidAttr { span: attr_item.span() })),
@@ -487,6 +790,43 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
         Ok(FluxAttr { kind, span: attr_item.span() })
     }
 
+    /// Parses the arguments of `#[flux::trusted(..)]`: `shallow` (the default) or `deep`, and an
+    /// optional `reason = "..."`
+    fn parse_trusted(&mut self, attr_item: &AttrItem) -> Result<Trusted> {
+        let span = attr_item.span();
+        let Some(MetaItemKind::List(items)) = attr_item.meta_kind() else {
+            return Err(self.emit_err(errors::InvalidAttr { span }));
+        };
+        let mut trusted = Trusted::default();
+        for item in &items {
+            let Some(item) = item.meta_item() else {
+                return Err(self.emit_err(errors::InvalidAttr { span }));
+            };
+            match (item.name_or_empty().as_str(), item.value_str()) {
+                ("shallow", None) if item.is_word() => trusted.deep = false,
+                ("deep", None) if item.is_word() => trusted.deep = true,
+                ("reason", Some(reason)) => trusted.reason = Some(reason),
+                _ => return Err(self.emit_err(errors::InvalidAttr { span: item.span })),
+            }
+        }
+        Ok(trusted)
+    }
+
+    /// Checks that the target of an extern spec for a free function is defined in another crate and
+    /// doesn't already have an extern spec. The signature of a local function should be given directly on its definition.
+    fn check_extern_fn_target(&mut self, def_id: LocalDefId, extern_def_id: DefId) -> Result {
+        let span = self.tcx.def_span(def_id);
+        let path = self.tcx.def_path_str(extern_def_id);
+        if extern_def_id.is_local() {
+            return Err(self.emit_err(errors::ExternSpecForLocalFn { span, path }));
+        }
+        if let Some(prev) = self.specs.extern_specs.get(&extern_def_id) {
+            let prev = self.tcx.def_span(*prev);
+            return Err(self.emit_err(errors::DuplicateExternSpec { span, prev, path }));
+        }
+        Ok(())
+    }
+
     // In Prusti they suggested looking into doing this instead of using a Visitor...
     // it seems more brittle but I guess conversely their version is a little permissive.
     fn extract_extern_def_id_from_extern_spec_fn(&mut self, def_id: LocalDefId) -> Result<DefId> {
@@ -527,6 +867,20 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
                 return Ok(def_id);
             }
         }
+        // trait methods
+        if let Node::TraitItem(i) = self.tcx.hir_node_by_def_id(def_id)
+            && let TraitItemKind::Fn(_, TraitFn::Provided(body_id)) = &i.kind
+            && let Node::Expr(e) = self.tcx.hir_node(body_id.hir_id)
+            && let ExprKind::Block(b, _) = e.kind
+            && let Some(e) = b.expr
+            && let ExprKind::Call(callee, _) = &e.kind
+            && let ExprKind::Path(qself) = &callee.kind
+        {
+            let typeck_result = self.tcx.typeck(def_id);
+            if let def::Res::Def(_, def_id) = typeck_result.qpath_res(qself, callee.hir_id) {
+                return Ok(def_id);
+            }
+        }
         Err(self.emit_err(errors::MalformedExternSpec { span: self.tcx.def_span(def_id) }))
     }
 
@@ -535,7 +889,7 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
         def_id: LocalDefId,
         data: &VariantData,
     ) -> Result<DefId> {
-        if let Some(extern_field) = data.fields().first() {
+        if let Some(extern_field) = data.fields().last() {
             let ty = self.tcx.type_of(extern_field.def_id);
             if let Some(adt_def) = ty.skip_binder().ty_adt_def() {
                 return Ok(adt_def.did());
@@ -544,6 +898,52 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
         Err(self.emit_err(errors::MalformedExternSpec { span: self.tcx.def_span(def_id) }))
     }
 
+    /// Checks that the fields listed in an extern spec for a struct are the same as the fields of
+    /// the external struct, i.e., they have the same names and types and are in the same order.
+    fn check_extern_struct_fields(
+        &mut self,
+        def_id: LocalDefId,
+        extern_id: DefId,
+        data: &VariantData,
+    ) -> Result {
+        let tcx = self.tcx;
+        let Some((extern_field, fields)) = data.fields().split_last() else {
+            return Err(self.emit_err(errors::MalformedExternSpec { span: tcx.def_span(def_id) }));
+        };
+        let path = tcx.def_path_str(extern_id);
+        let adt_def = tcx.adt_def(extern_id);
+        if !adt_def.is_struct() {
+            return Err(self.emit_err(errors::MalformedExternSpec { span: tcx.def_span(def_id) }));
+        }
+        let expected_fields = &adt_def.non_enum_variant().fields;
+        if fields.len() != expected_fields.len() {
+            return Err(self.emit_err(errors::ExternStructFieldCountMismatch {
+                span: tcx.def_span(def_id),
+                path,
+                found: fields.len(),
+                expected: expected_fields.len(),
+            }));
+        }
+        // The type of the last field is the external struct instantiated with the generics of the
+        // dummy struct, so we use its arguments to instantiate the types of the external fields.
+        let rustc_middle::ty::Adt(_, args) = tcx.type_of(extern_field.def_id).skip_binder().kind()
+        else {
+            return Err(self.emit_err(errors::MalformedExternSpec { span: tcx.def_span(def_id) }));
+        };
+        iter::zip(fields, expected_fields.iter()).try_for_each_exhaust(|(field, expected)| {
+            let found_ty = tcx.type_of(field.def_id).instantiate_identity();
+            let expected_ty = tcx.type_of(expected.did).instantiate(tcx, args);
+            if field.ident.name != expected.name || found_ty != expected_ty {
+                return Err(self.emit_err(errors::ExternStructFieldMismatch {
+                    span: field.span,
+                    path: path.clone(),
+                    expected: format!("{}: {expected_ty}", expected.name),
+                }));
+            }
+            Ok(())
+        })
+    }
+
     fn extract_extern_def_id_from_extern_spec_enum(
         &mut self,
         def_id: LocalDefId,
@@ -715,6 +1115,22 @@ impl<'tcx, 'a> SpecCollector<'tcx, 'a> {
     }
 }
 
+struct StmtAnnotFinder<'c, 'tcx, 'a> {
+    collector: &'c mut SpecCollector<'tcx, 'a>,
+    annots: Vec<surface::StmtAnnot>,
+}
+
+impl<'tcx> intravisit::Visitor<'tcx> for StmtAnnotFinder<'_, 'tcx, '_> {
+    fn visit_stmt(&mut self, stmt: &'tcx Stmt<'tcx>) {
+        for attr in self.collector.tcx.hir().attrs(stmt.hir_id) {
+            if let Some(annot) = self.collector.parse_stmt_annot(attr, stmt.span) {
+                self.annots.push(annot);
+            }
+        }
+        intravisit::walk_stmt(self, stmt);
+    }
+}
+
 #[derive(Debug)]
 struct FluxAttrs {
     map: HashMap<&'static str, Vec<FluxAttr>>,
@@ -728,8 +1144,11 @@ struct FluxAttr {
 
 #[derive(Debug)]
 enum FluxAttrKind {
-    Trusted,
+    Trusted(Trusted),
+    Inline,
+    ShouldFail,
     Opaque,
+    Reflect,
     FnSig(surface::FnSig),
     TraitAssocReft(surface::TraitAssocReft),
     ImplAssocReft(surface::ImplAssocReft),
@@ -744,6 +1163,10 @@ enum FluxAttrKind {
     CrateConfig(config::CrateConfig),
     Invariant(surface::Expr),
     Ignore(Ignored),
+    Intrinsic(Intrinsic),
+    UnpackPolicy(UnpackPolicy),
+    Solver(SmtSolver),
+    Opts(Opts),
     FakeImpl,
     ExternSpec,
 }
@@ -790,14 +1213,38 @@ impl FluxAttrs {
             .map(|(name, attrs)| (*name, &attrs[1..]))
     }
 
-    fn trusted(&mut self) -> bool {
-        read_flag!(self, Trusted)
+    fn trusted(&mut self) -> Option<Trusted> {
+        read_attr!(self, Trusted)
+    }
+
+    fn inline(&mut self) -> bool {
+        read_flag!(self, Inline)
+    }
+
+    fn should_fail(&mut self) -> bool {
+        read_flag!(self, ShouldFail)
     }
 
     fn ignore(&mut self) -> Option<Ignored> {
         read_attr!(self, Ignore)
     }
 
+    fn intrinsic(&mut self) -> Option<Intrinsic> {
+        read_attr!(self, Intrinsic)
+    }
+
+    fn unpack_policy(&mut self) -> Option<UnpackPolicy> {
+        read_attr!(self, UnpackPolicy)
+    }
+
+    fn solver(&mut self) -> Option<SmtSolver> {
+        read_attr!(self, Solver)
+    }
+
+    fn opts(&mut self) -> Option<Opts> {
+        read_attr!(self, Opts)
+    }
+
     fn fake_impl(&mut self) -> bool {
         read_flag!(self, FakeImpl)
     }
@@ -806,6 +1253,10 @@ impl FluxAttrs {
         read_flag!(self, Opaque)
     }
 
+    fn reflect(&mut self) -> bool {
+        read_flag!(self, Reflect)
+    }
+
     fn items(&mut self) -> impl Iterator<Item = surface::Item> {
         read_attrs!(self, Items).into_iter().flatten()
     }
@@ -866,8 +1317,11 @@ impl FluxAttrs {
 impl FluxAttrKind {
     fn name(&self) -> &'static str {
         match self {
-            FluxAttrKind::Trusted => attr_name!(Trusted),
+            FluxAttrKind::Trusted(_) => attr_name!(Trusted),
+            FluxAttrKind::Inline => attr_name!(Inline),
+            FluxAttrKind::ShouldFail => attr_name!(ShouldFail),
             FluxAttrKind::Opaque => attr_name!(Opaque),
+            FluxAttrKind::Reflect => attr_name!(Reflect),
             FluxAttrKind::FnSig(_) => attr_name!(FnSig),
             FluxAttrKind::TraitAssocReft(_) => attr_name!(TraitAssocReft),
             FluxAttrKind::ImplAssocReft(_) => attr_name!(ImplAssocReft),
@@ -881,6 +1335,10 @@ impl FluxAttrKind {
             FluxAttrKind::TypeAlias(_) => attr_name!(TypeAlias),
             FluxAttrKind::CrateConfig(_) => attr_name!(CrateConfig),
             FluxAttrKind::Ignore(_) => attr_name!(Ignore),
+            FluxAttrKind::Intrinsic(_) => attr_name!(Intrinsic),
+            FluxAttrKind::UnpackPolicy(_) => attr_name!(UnpackPolicy),
+            FluxAttrKind::Solver(_) => attr_name!(Solver),
+            FluxAttrKind::Opts(_) => attr_name!(Opts),
             FluxAttrKind::Invariant(_) => attr_name!(Invariant),
             FluxAttrKind::ExternSpec => attr_name!(ExternSpec),
             FluxAttrKind::FakeImpl => attr_name!(FakeImpl),
@@ -900,11 +1358,11 @@ struct FluxAttrCFG {
 }
 
 macro_rules! try_read_setting {
-    ($self:expr, $setting:ident, $type:ident, $cfg:expr) => {
+    (@read $self:expr, $setting:ident, $type:ident, |$val:ident| $assign:expr) => {
         if let Some(CFGSetting { setting, span }) = $self.map.remove(stringify!($setting)) {
             let parse_result = setting.as_str().parse::<$type>();
-            if let Ok(val) = parse_result {
-                $cfg.$setting = val;
+            if let Ok($val) = parse_result {
+                $assign;
             } else {
                 return Err(errors::CFGError {
                     span,
@@ -917,6 +1375,12 @@ macro_rules! try_read_setting {
             }
         }
     };
+    ($self:expr, $setting:ident, Option<$type:ident>, $cfg:expr) => {
+        try_read_setting!(@read $self, $setting, $type, |val| $cfg.$setting = Some(val))
+    };
+    ($self:expr, $setting:ident, $type:ident, $cfg:expr) => {
+        try_read_setting!(@read $self, $setting, $type, |val| $cfg.$setting = val)
+    };
 }
 
 type CFGResult<T = ()> = std::result::Result<T, errors::CFGError>;
@@ -977,19 +1441,61 @@ impl FluxAttrCFG {
         }
     }
 
-    fn try_into_crate_cfg(&mut self) -> CFGResult<config::CrateConfig> {
-        let mut crate_config = CrateConfig::default();
+    fn try_into_crate_cfg(&mut self, base: CrateConfig) -> CFGResult<config::CrateConfig> {
+        let crate_config = self.read_crate_cfg(base)?;
+        self.check_no_settings_left()?;
+        Ok(crate_config)
+    }
+
+    /// Overrides the settings in `base` with the ones in the map, leaving unknown settings in it
+    fn read_crate_cfg(&mut self, base: CrateConfig) -> CFGResult<config::CrateConfig> {
+        let mut crate_config = base;
         try_read_setting!(self, check_overflow, bool, crate_config);
         try_read_setting!(self, scrape_quals, bool, crate_config);
+        try_read_setting!(self, mine_quals, bool, crate_config);
+        try_read_setting!(self, auto_specs, bool, crate_config);
+        try_read_setting!(self, check_panics, bool, crate_config);
+        try_read_setting!(self, warn_holes, bool, crate_config);
+        try_read_setting!(self, unpack, UnpackPolicy, crate_config);
+        try_read_setting!(self, solver, SmtSolver, crate_config);
+        try_read_setting!(self, solver_args, SolverArgs, crate_config);
+        try_read_setting!(self, timeout, u64, crate_config);
+        try_read_setting!(self, unstable_features, UnstableFeatures, crate_config);
+        Ok(crate_config)
+    }
 
+    fn check_no_settings_left(&self) -> CFGResult {
         if let Some((name, setting)) = self.map.iter().next() {
             return Err(errors::CFGError {
                 span: setting.span,
                 message: format!("invalid crate cfg keyword `{name}`"),
             });
         }
+        Ok(())
+    }
 
-        Ok(crate_config)
+    fn try_into_opts(&mut self) -> CFGResult<Opts> {
+        let mut opts = Opts::default();
+        // Arithmetic is modeled differently with and without overflow checking, so values flowing
+        // between functions checked with different settings could break the integer invariants.
+        if let Some(setting) = self.map.get("check_overflow") {
+            return Err(errors::CFGError {
+                span: setting.span,
+                message: "`check_overflow` can only be set for the whole crate with `flux::cfg`"
+                    .to_string(),
+            });
+        }
+        try_read_setting!(self, solver, Option<SmtSolver>, opts);
+        try_read_setting!(self, timeout, Option<u64>, opts);
+
+        if let Some((name, setting)) = self.map.iter().next() {
+            return Err(errors::CFGError {
+                span: setting.span,
+                message: format!("invalid option `{name}`"),
+            });
+        }
+
+        Ok(opts)
     }
 }
 
@@ -1013,6 +1519,15 @@ mod errors {
         pub span: Span,
     }
 
+    #[derive(Diagnostic)]
+    #[diag(driver_invalid_intrinsic_sig, code = E0999)]
+    pub(super) struct InvalidIntrinsicSig {
+        #[primary_span]
+        pub span: Span,
+        pub intrinsic: &'static str,
+        pub expected: &'static str,
+    }
+
     #[derive(Diagnostic)]
     #[diag(driver_cfg_error, code = E0999)]
     pub(super) struct CFGError {
@@ -1021,6 +1536,13 @@ mod errors {
         pub message: String,
     }
 
+    #[derive(Diagnostic)]
+    #[diag(driver_crate_config_file_error, code = E0999)]
+    pub(super) struct CrateConfigFileError {
+        pub path: String,
+        pub message: String,
+    }
+
     #[derive(Diagnostic)]
     #[diag(driver_syntax_err, code = E0999)]
     pub(super) struct SyntaxErr {
@@ -1036,6 +1558,27 @@ mod errors {
         pub span: Span,
     }
 
+    #[derive(Diagnostic)]
+    #[diag(driver_extern_struct_field_count_mismatch, code = E0999)]
+    #[note]
+    pub(super) struct ExternStructFieldCountMismatch {
+        #[primary_span]
+        pub span: Span,
+        pub path: String,
+        pub found: usize,
+        pub expected: usize,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_extern_struct_field_mismatch, code = E0999)]
+    pub(super) struct ExternStructFieldMismatch {
+        #[primary_span]
+        #[label]
+        pub span: Span,
+        pub path: String,
+        pub expected: String,
+    }
+
     #[derive(Diagnostic)]
     #[diag(driver_missing_fn_sig_for_extern_spec, code = E0999)]
     pub(super) struct MissingFnSigForExternSpec {
@@ -1043,6 +1586,26 @@ mod errors {
         pub span: Span,
     }
 
+    #[derive(Diagnostic)]
+    #[diag(driver_extern_spec_for_local_fn, code = E0999)]
+    #[note]
+    pub(super) struct ExternSpecForLocalFn {
+        #[primary_span]
+        pub span: Span,
+        pub path: String,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_duplicate_extern_spec, code = E0999)]
+    pub(super) struct DuplicateExternSpec {
+        #[primary_span]
+        #[label]
+        pub span: Span,
+        #[note]
+        pub prev: Span,
+        pub path: String,
+    }
+
     #[derive(Diagnostic)]
     #[diag(driver_attr_on_opaque, code = E0999)]
     pub(super) struct AttrOnOpaque {
@@ -1074,6 +1637,20 @@ mod errors {
         }
     }
 
+    #[derive(Diagnostic)]
+    #[diag(driver_invalid_reflect, code = E0999)]
+    #[note]
+    pub(super) struct InvalidReflect {
+        #[primary_span]
+        span: Span,
+    }
+
+    impl InvalidReflect {
+        pub(super) fn new(span: Span) -> Self {
+            Self { span }
+        }
+    }
+
     impl From<flux_syntax::ParseError> for SyntaxErr {
         fn from(err: flux_syntax::ParseError) -> Self {
             use flux_syntax::ParseErrorKind;
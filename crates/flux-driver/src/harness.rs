@@ -0,0 +1,445 @@
+//! Tests and proof harnesses generated from signatures, enabled with `-Zflux-emit=proptest` and
+//! `-Zflux-emit=kani` (see [`config::emit`]).
+//!
+//! For every function whose arguments and return value are integers or booleans, we generate a
+//! harness that picks arguments, discards the ones that don't satisfy the precondition, calls the
+//! function and asserts the postcondition. This is a cheap way to cross-check specs that flux
+//! takes for granted, e.g., the ones of trusted functions, against their implementation:
+//!
+//! ```ignore
+//! #[flux::trusted]
+//! #[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > x})]
+//! fn inc(x: i32) -> i32 { .. }
+//! ```
+//!
+//! becomes a [proptest] test that samples the arguments
+//!
+//! ```ignore
+//! #[test]
+//! #[allow(unused_variables)]
+//! fn inc(arg0 in any::<i32>()) {
+//!     let x = arg0 as i128;
+//!     prop_assume!(x > 0);
+//!     let ret = crate::inc(arg0);
+//!     {
+//!         let v = ret as i128;
+//!         prop_assert!(v > x);
+//!     }
+//! }
+//! ```
+//!
+//! or a [Kani] proof harness that checks the function for all of them with bounded model checking
+//!
+//! ```ignore
+//! #[kani::proof]
+//! #[allow(unused_variables)]
+//! fn inc() {
+//!     let arg0: i32 = kani::any();
+//!     let x = arg0 as i128;
+//!     kani::assume(x > 0);
+//!     let ret = crate::inc(arg0);
+//!     {
+//!         let v = ret as i128;
+//!         assert!(v > x);
+//!     }
+//! }
+//! ```
+//!
+//! As with the contracts of `flux-rs`, integers are compared as `i128` so they can be mixed as in
+//! flux, and predicates can only mention the values of arguments. A function is skipped if part of
+//! its precondition can't be translated, otherwise we would test it on inputs it doesn't accept,
+//! while a postcondition that can't be translated is just not asserted.
+//!
+//! [proptest]: https://docs.rs/proptest
+//! [Kani]: https://model-checking.github.io/kani
+use std::{fmt::Write as _, fs, io};
+
+use flux_config as config;
+use flux_middle::{fhir::Ignored, global_env::GlobalEnv};
+use flux_syntax::surface::{self, BinOp, BindKind, LitKind, UnOp};
+use rustc_hir::{
+    def::DefKind,
+    def_id::{LocalDefId, CRATE_DEF_ID, LOCAL_CRATE},
+    OwnerId,
+};
+use rustc_middle::ty;
+
+#[derive(Clone, Copy)]
+pub(crate) enum Backend {
+    Proptest,
+    Kani,
+}
+
+impl Backend {
+    fn name(self) -> &'static str {
+        match self {
+            Backend::Proptest => "proptest",
+            Backend::Kani => "kani",
+        }
+    }
+
+    /// The indentation of the harnesses, the ones of proptest are inside the `proptest!` macro
+    fn indent(self) -> &'static str {
+        match self {
+            Backend::Proptest => "    ",
+            Backend::Kani => "",
+        }
+    }
+
+    /// The macro or function used to discard inputs that don't satisfy a precondition
+    fn assume(self) -> &'static str {
+        match self {
+            Backend::Proptest => "prop_assume!",
+            Backend::Kani => "kani::assume",
+        }
+    }
+
+    fn assert(self) -> &'static str {
+        match self {
+            Backend::Proptest => "prop_assert!",
+            Backend::Kani => "assert!",
+        }
+    }
+}
+
+/// Writes the harnesses for the functions of the crate to `{crate}.proptest.rs` or
+/// `{crate}.kani.rs` in [`config::emit_dir`]. The file is meant to be included in a module at the
+/// root of the crate, which needs `proptest` as a dev-dependency for property tests.
+pub(crate) fn emit(genv: GlobalEnv, backend: Backend) -> io::Result<()> {
+    let tcx = genv.tcx();
+    let crate_name = tcx.crate_name(LOCAL_CRATE);
+    let mut out = String::new();
+    match backend {
+        Backend::Proptest => {
+            writeln!(
+                out,
+                "// Property tests generated by flux from the signatures of `{crate_name}`"
+            )
+            .unwrap();
+            writeln!(out, "use proptest::prelude::*;\n").unwrap();
+            writeln!(out, "proptest! {{").unwrap();
+        }
+        Backend::Kani => {
+            writeln!(
+                out,
+                "// Kani harnesses generated by flux from the signatures of `{crate_name}`"
+            )
+            .unwrap();
+        }
+    }
+    for def_id in tcx.hir_crate_items(()).definitions() {
+        if genv.ignored(def_id) == Ignored::Yes
+            || !matches!(genv.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+        {
+            continue;
+        }
+        let fn_spec = genv.collect_specs().fn_sigs.get(&OwnerId { def_id });
+        if let Some(fn_sig) = fn_spec.and_then(|fn_spec| fn_spec.fn_sig.as_ref())
+            && let Some(harness) = fn_harness(genv, def_id, fn_sig, backend)
+        {
+            out.push_str(&harness);
+        }
+    }
+    if let Backend::Proptest = backend {
+        writeln!(out, "}}").unwrap();
+    }
+
+    fs::create_dir_all(config::emit_dir())?;
+    fs::write(config::emit_dir().join(format!("{crate_name}.{}.rs", backend.name())), out)
+}
+
+/// The harness of the function `def_id` or `None` if it can't be tested
+fn fn_harness(
+    genv: GlobalEnv,
+    def_id: LocalDefId,
+    fn_sig: &surface::FnSig,
+    backend: Backend,
+) -> Option<String> {
+    let tcx = genv.tcx();
+    let path = fn_path(genv, def_id)?;
+    if tcx.generics_of(def_id).count() > 0 || matches!(fn_sig.asyncness, surface::Async::Yes { .. })
+    {
+        return None;
+    }
+    let rust_sig = tcx.fn_sig(def_id).instantiate_identity().skip_binder();
+    if rust_sig.unsafety == rustc_hir::Unsafety::Unsafe
+        || rust_sig.inputs().len() != fn_sig.args.len()
+        || !(rust_sig.output().is_unit() || scalar(rust_sig.output()).is_some())
+    {
+        return None;
+    }
+
+    let mut test = Test::default();
+    let mut inputs = vec![];
+    for (i, (arg, rust_ty)) in fn_sig.args.iter().zip(rust_sig.inputs()).enumerate() {
+        let scalar = scalar(*rust_ty)?;
+        let value = match scalar {
+            Scalar::Int => format!("arg{i} as i128"),
+            Scalar::Bool => format!("arg{i}"),
+        };
+        match arg {
+            surface::Arg::Constr(name, _, pred, _) => {
+                test.lets.push((name.to_string(), value));
+                test.pre.push(Cond { pred, local: None, index: false });
+            }
+            surface::Arg::Ty(name, ty, _) => {
+                if let Some(name) = name {
+                    test.lets.push((name.to_string(), value.clone()));
+                }
+                test.refinements(ty, value, false);
+            }
+            surface::Arg::StrgRef(..) => return None,
+        }
+        inputs.push((format!("arg{i}"), rust_ty.to_string()));
+    }
+    if let Some(requires) = &fn_sig.requires {
+        test.pre
+            .push(Cond { pred: requires, local: None, index: false });
+    }
+    let ret_value = match scalar(rust_sig.output()) {
+        Some(Scalar::Int) => "ret as i128",
+        _ => "ret",
+    };
+    if let surface::FnRetTy::Ty(ty) = &fn_sig.output.returns {
+        test.refinements(ty, ret_value.to_string(), true);
+    }
+    for constraint in &fn_sig.output.ensures {
+        if let surface::Constraint::Pred(pred) = constraint {
+            test.post.push(Cond { pred, local: None, index: false });
+        }
+    }
+
+    let indent = backend.indent();
+    let mut out = String::new();
+    let name = tcx.def_path_str(def_id.to_def_id()).replace("::", "_");
+    match backend {
+        Backend::Proptest => writeln!(out, "{indent}#[test]").unwrap(),
+        Backend::Kani => writeln!(out, "{indent}#[kani::proof]").unwrap(),
+    }
+    writeln!(out, "{indent}#[allow(unused_variables)]").unwrap();
+    match backend {
+        Backend::Proptest => {
+            let params = inputs
+                .iter()
+                .map(|(arg, ty)| format!("{arg} in any::<{ty}>()"))
+                .collect::<Vec<_>>();
+            writeln!(out, "{indent}fn {name}({}) {{", params.join(", ")).unwrap();
+        }
+        Backend::Kani => {
+            writeln!(out, "{indent}fn {name}() {{").unwrap();
+            for (arg, ty) in &inputs {
+                writeln!(out, "{indent}    let {arg}: {ty} = kani::any();").unwrap();
+            }
+        }
+    }
+    for (name, value) in &test.lets {
+        writeln!(out, "{indent}    let {name} = {value};").unwrap();
+    }
+    for cond in &test.pre {
+        writeln!(out, "{}", test.cond_to_rust(cond, backend.assume(), indent)?).unwrap();
+    }
+    let args = inputs.iter().map(|(arg, _)| &arg[..]).collect::<Vec<_>>();
+    writeln!(out, "{indent}    let ret = crate::{path}({});", args.join(", ")).unwrap();
+    let post = test
+        .post
+        .iter()
+        .filter_map(|cond| test.cond_to_rust(cond, backend.assert(), indent))
+        .collect::<Vec<_>>();
+    // There's nothing to check without a postcondition
+    if post.is_empty() {
+        return None;
+    }
+    for cond in post {
+        writeln!(out, "{cond}").unwrap();
+    }
+    writeln!(out, "{indent}}}").unwrap();
+    Some(out)
+}
+
+#[derive(Clone, Copy)]
+enum Scalar {
+    Int,
+    Bool,
+}
+
+fn scalar(ty: ty::Ty) -> Option<Scalar> {
+    if ty.is_integral() {
+        Some(Scalar::Int)
+    } else if ty.is_bool() {
+        Some(Scalar::Bool)
+    } else {
+        None
+    }
+}
+
+#[derive(Default)]
+struct Test<'a> {
+    /// Variables bound by the arguments and the expression computing their value
+    lets: Vec<(String, String)>,
+    pre: Vec<Cond<'a>>,
+    post: Vec<Cond<'a>>,
+}
+
+struct Cond<'a> {
+    pred: &'a surface::Expr,
+    /// A variable bound only for this predicate, e.g., `v` in `i32{v: v > 0}`
+    local: Option<(String, String)>,
+    /// Whether `pred` is an index, e.g., `n + 1` in `i32[n + 1]`, which has to be equal to the
+    /// value of the local variable
+    index: bool,
+}
+
+impl<'a> Test<'a> {
+    /// Collects the variables bound by a type and the predicates it asserts about `value`
+    fn refinements(&mut self, ty: &'a surface::Ty, value: String, post: bool) {
+        match &ty.kind {
+            surface::TyKind::Indexed { indices, .. } => {
+                let [index] = &indices.indices[..] else { return };
+                match index {
+                    surface::RefineArg::Bind(name, BindKind::At | BindKind::Pound, ..) => {
+                        if !post {
+                            self.lets.push((name.to_string(), value));
+                        }
+                    }
+                    surface::RefineArg::Expr(expr) => {
+                        let local = Some(("value".to_string(), value));
+                        self.push(Cond { pred: expr, local, index: true }, post);
+                    }
+                    surface::RefineArg::Abs(..) => {}
+                }
+            }
+            surface::TyKind::Exists { bind, pred, .. } => {
+                self.push(
+                    Cond { pred, local: Some((bind.to_string(), value)), index: false },
+                    post,
+                );
+            }
+            surface::TyKind::GeneralExists { params, pred: Some(pred), .. } => {
+                // The type is indexed by the parameter, e.g., `{v. i32[v] | v > 0}`
+                if let [param] = &params[..] {
+                    let local = Some((param.name.to_string(), value));
+                    self.push(Cond { pred, local, index: false }, post);
+                }
+            }
+            surface::TyKind::Constr(pred, ty) => {
+                self.refinements(ty, value, post);
+                self.push(Cond { pred, local: None, index: false }, post);
+            }
+            _ => {}
+        }
+    }
+
+    fn push(&mut self, cond: Cond<'a>, post: bool) {
+        if post {
+            self.post.push(cond);
+        } else {
+            self.pre.push(cond);
+        }
+    }
+
+    /// A block checking the condition with `check`, e.g., `prop_assert!`, or `None` if it can't be
+    /// translated
+    fn cond_to_rust(&self, cond: &Cond, check: &str, indent: &str) -> Option<String> {
+        let in_scope = |name: &str| {
+            cond.local.as_ref().is_some_and(|(local, _)| local == name)
+                || self.lets.iter().any(|(bound, _)| bound == name)
+        };
+        let mut pred = expr_to_rust(cond.pred, &in_scope)?;
+        if cond.index
+            && let Some((name, _)) = &cond.local
+        {
+            pred = format!("{name} == {pred}");
+        }
+        let mut out = String::new();
+        writeln!(out, "{indent}    {{").unwrap();
+        if let Some((name, value)) = &cond.local {
+            writeln!(out, "{indent}        let {name} = {value};").unwrap();
+        }
+        writeln!(out, "{indent}        {check}({pred});").unwrap();
+        write!(out, "{indent}    }}").unwrap();
+        Some(out)
+    }
+}
+
+/// Translates a predicate to a Rust expression. Returns `None` if it mentions anything that isn't
+/// a variable in scope or a literal, e.g., a field or a function.
+fn expr_to_rust(expr: &surface::Expr, in_scope: &impl Fn(&str) -> bool) -> Option<String> {
+    let s = match &expr.kind {
+        surface::ExprKind::Path(path) => {
+            let [name] = &path.segments[..] else { return None };
+            let name = name.to_string();
+            if !in_scope(&name) {
+                return None;
+            }
+            name
+        }
+        surface::ExprKind::Literal(lit) => {
+            match lit.kind {
+                LitKind::Integer if lit.suffix.is_none() => lit.symbol.to_string(),
+                LitKind::Bool => lit.symbol.to_string(),
+                _ => return None,
+            }
+        }
+        surface::ExprKind::BinaryOp(op, box [e1, e2]) => {
+            let e1 = expr_to_rust(e1, in_scope)?;
+            let e2 = expr_to_rust(e2, in_scope)?;
+            match op {
+                BinOp::Iff => format!("({e1} == {e2})"),
+                BinOp::Imp => format!("(!{e1} || {e2})"),
+                // Flux's `mod` is the euclidean remainder
+                BinOp::Mod => format!("({e1}).rem_euclid({e2})"),
+                _ => format!("({e1} {op:?} {e2})"),
+            }
+        }
+        surface::ExprKind::UnaryOp(op, e) => {
+            let e = expr_to_rust(e, in_scope)?;
+            match op {
+                UnOp::Not => format!("!{e}"),
+                UnOp::Neg => format!("-{e}"),
+            }
+        }
+        surface::ExprKind::IfThenElse(box [p, e1, e2]) => {
+            let p = expr_to_rust(p, in_scope)?;
+            let e1 = expr_to_rust(e1, in_scope)?;
+            let e2 = expr_to_rust(e2, in_scope)?;
+            format!("(if {p} {{ {e1} }} else {{ {e2} }})")
+        }
+        surface::ExprKind::Dot(..)
+        | surface::ExprKind::App(..)
+        | surface::ExprKind::Alias(..)
+        | surface::ExprKind::Match(..) => {
+            return None;
+        }
+    };
+    Some(s)
+}
+
+/// The path of a function relative to the root of the crate, or `None` if it isn't accessible from
+/// there, i.e., a test at the root of the crate couldn't call it.
+fn fn_path(genv: GlobalEnv, def_id: LocalDefId) -> Option<String> {
+    let tcx = genv.tcx();
+    let root = CRATE_DEF_ID.to_def_id();
+    let mut item = def_id.to_def_id();
+    loop {
+        if !tcx.visibility(item).is_accessible_from(root, tcx) {
+            return None;
+        }
+        let parent = tcx.parent(item);
+        match tcx.def_kind(parent) {
+            DefKind::Mod if parent == root => break,
+            DefKind::Mod => item = parent,
+            // A method of an inherent impl is accessible if the type is
+            DefKind::Impl { of_trait: false } => {
+                let ty::Adt(adt_def, _) = tcx.type_of(parent).instantiate_identity().kind() else {
+                    return None;
+                };
+                if !adt_def.did().is_local() {
+                    return None;
+                }
+                item = adt_def.did();
+            }
+            _ => return None,
+        }
+    }
+    Some(tcx.def_path_str(def_id.to_def_id()))
+}
@@ -3,16 +3,25 @@
 extern crate rustc_ast;
 extern crate rustc_ast_pretty;
 extern crate rustc_borrowck;
+extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_errors;
 extern crate rustc_hir;
 extern crate rustc_interface;
 extern crate rustc_middle;
+extern crate rustc_parse;
 extern crate rustc_session;
 extern crate rustc_span;
+extern crate serde_json;
 
+pub mod api;
 pub mod callbacks;
 mod collector;
+mod harness;
+mod manifest;
+mod prelude;
+mod refined_sigs;
+mod sidecar;
 
 use flux_macros::fluent_messages;
 
@@ -0,0 +1,128 @@
+//! Crate-level settings written in a file instead of a `#![flux::cfg(..)]` attribute, which can't
+//! be used, e.g., on a stable toolchain.
+//!
+//! The settings of a crate are read from the `[package.metadata.flux]` table of its `Cargo.toml`
+//! or, if there isn't one, from the `[crate]` table of a `flux.toml` next to it:
+//!
+//! ```toml
+//! [package.metadata.flux]
+//! check_overflow = true
+//! solver = "cvc5"
+//! timeout = 30
+//! ignore = ["generated::*"]
+//! ```
+//!
+//! The keys are the ones accepted by `#![flux::cfg(..)]`, which takes precedence over the file,
+//! plus `ignore`, a list of globs over the paths of the items that shouldn't be checked. Any other
+//! key is an error. A `flux.toml` may also contain options for the driver (see `flux_config`),
+//! which is why the settings of the crate go in their own table.
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use toml::{Table, Value};
+
+/// The only setting that isn't accepted by `#![flux::cfg(..)]`
+const IGNORE_KEY: &str = "ignore";
+
+/// The table of a `flux.toml` with the settings of the crate
+const CRATE_TABLE: &str = "crate";
+
+pub(crate) struct CrateConfigFile {
+    pub(crate) path: PathBuf,
+    /// The value of each setting as it would be written in `#![flux::cfg(..)]`
+    pub(crate) settings: Vec<(String, String)>,
+    pub(crate) ignore: Vec<String>,
+}
+
+impl CrateConfigFile {
+    /// Reads the settings of the crate being compiled. Cargo tells us where its manifest is with
+    /// `CARGO_MANIFEST_DIR`, so there are no settings when running flux on a single file.
+    pub(crate) fn find() -> Result<Option<CrateConfigFile>, (PathBuf, String)> {
+        let Some(dir) = env::var_os("CARGO_MANIFEST_DIR") else { return Ok(None) };
+        let dir = Path::new(&dir);
+
+        let manifest = dir.join("Cargo.toml");
+        if let Some(table) = read_table(&manifest)?
+            && let Some(Value::Table(flux)) = table
+                .get("package")
+                .and_then(|package| package.get("metadata"))
+                .and_then(|metadata| metadata.get("flux"))
+        {
+            return CrateConfigFile::from_table(manifest, flux).map(Some);
+        }
+
+        let flux_toml = dir.join("flux.toml");
+        let Some(table) = read_table(&flux_toml)? else { return Ok(None) };
+        match table.get(CRATE_TABLE) {
+            Some(Value::Table(settings)) => {
+                CrateConfigFile::from_table(flux_toml, settings).map(Some)
+            }
+            Some(_) => Err((flux_toml, format!("`{CRATE_TABLE}` must be a table"))),
+            None => Ok(None),
+        }
+    }
+
+    fn from_table(path: PathBuf, table: &Table) -> Result<CrateConfigFile, (PathBuf, String)> {
+        let mut settings = vec![];
+        let mut ignore = vec![];
+        for (key, value) in table {
+            if key == IGNORE_KEY {
+                let globs = value.as_array().and_then(|globs| {
+                    globs
+                        .iter()
+                        .map(|glob| glob.as_str().map(str::to_string))
+                        .collect::<Option<Vec<_>>>()
+                });
+                let Some(globs) = globs else {
+                    return Err((path, format!("`{IGNORE_KEY}` must be a list of strings")));
+                };
+                ignore = globs;
+                continue;
+            }
+            let value = match value {
+                Value::String(s) => s.clone(),
+                Value::Integer(n) => n.to_string(),
+                Value::Boolean(b) => b.to_string(),
+                _ => return Err((path, format!("unsupported value for setting `{key}`"))),
+            };
+            settings.push((key.clone(), value));
+        }
+        Ok(CrateConfigFile { path, settings, ignore })
+    }
+
+    /// Whether the item with path `def_path` (as printed by `def_path_str` without generic
+    /// arguments) matches one of the `ignore` globs
+    pub(crate) fn is_ignored(&self, def_path: &str) -> bool {
+        self.ignore.iter().any(|glob| glob_matches(glob, def_path))
+    }
+}
+
+fn read_table(path: &Path) -> Result<Option<Table>, (PathBuf, String)> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(path).map_err(|err| (path.to_path_buf(), err.to_string()))?;
+    let table = contents
+        .parse::<Table>()
+        .map_err(|err| (path.to_path_buf(), err.to_string()))?;
+    Ok(Some(table))
+}
+
+/// Matches a path against a glob where `*` stands for any sequence of characters, including `::`,
+/// e.g., `generated::*` matches all the items in the module `generated`.
+fn glob_matches(glob: &str, path: &str) -> bool {
+    let mut parts = glob.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else { return false };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else { return rest.is_empty() };
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
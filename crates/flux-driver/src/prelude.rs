@@ -0,0 +1,155 @@
+//! Built-in specs for commonly used types in the standard library.
+//!
+//! The specs are written as extern specs in regular Rust source (see the `prelude` directory in the
+//! root of this crate). They are compiled with flux into the crates listed in [`PRELUDE_CRATES`]
+//! whose metadata is installed next to the driver. The extern specs saved in the metadata are then
+//! loaded for every crate being checked as if it depended on the prelude crates. The prelude is
+//! split into groups, each one a top-level module specifying a set of types and traits. A group
+//! is dropped if the crate (or one of its dependencies) provides its own extern specs for any of
+//! them.
+
+use std::{env, path::PathBuf};
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::{
+    def::DefKind,
+    def_id::{DefId, LOCAL_CRATE},
+    LangItem,
+};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::{sym, Symbol};
+
+/// The crates the prelude is compiled into together with whether they need `std`. A crate can use
+/// the specs in the crates before it.
+// CODESYNC(prelude-crates) must match the crates built by `cargo xtask build-prelude`
+const PRELUDE_CRATES: &[(&str, bool)] = &[("flux_prelude_core", false), ("flux_prelude_std", true)];
+
+struct Group {
+    name: &'static str,
+    /// Diagnostic items (or lang items) of the types, traits and methods the group specifies or
+    /// whose specs it depends on
+    specifies: &'static [Symbol],
+}
+
+static GROUPS: &[Group] = &[
+    Group { name: "vec", specifies: &[sym::Vec, sym::SliceIndex] },
+    Group {
+        name: "slice",
+        // Inherent impls on slices have no diagnostic items, but `len` is a lang item
+        specifies: &[sym::slice_len_fn],
+    },
+    Group { name: "option", specifies: &[sym::Option] },
+    Group {
+        name: "ordering",
+        // `Ordering` is a lang item
+        specifies: &[sym::Ordering],
+    },
+    Group {
+        name: "result",
+        // `Result::ok` mentions the index of `Option`
+        specifies: &[sym::Result, sym::Option],
+    },
+    Group {
+        name: "iter",
+        // `vec::IntoIter` is not a diagnostic item, so we conservatively give up on the group if
+        // the crate has specs for any impl of `Iterator`
+        specifies: &[sym::Vec, sym::SliceIter, sym::IntoIterator, sym::Iterator, sym::Option],
+    },
+    Group {
+        name: "range",
+        // `Range` and `RangeInclusive` are lang items
+        specifies: &[sym::Range, sym::RangeInclusive, sym::Option],
+    },
+    Group {
+        name: "map",
+        // `get` and `remove` mention the index of `Option`
+        specifies: &[sym::HashMap, sym::BTreeMap, sym::Borrow, sym::Option],
+    },
+    Group {
+        name: "convert",
+        // `try_from` mentions the index of `Result`
+        specifies: &[sym::From, sym::TryFrom, sym::Result],
+    },
+];
+
+/// Returns the paths of the metadata files with the built-in specs for the crate being compiled.
+/// The specs mentioning items in `std` are skipped for crates that do not link to it.
+pub(crate) fn metadata_paths(tcx: TyCtxt) -> Vec<PathBuf> {
+    if !flux_config::builtin_specs() {
+        return vec![];
+    }
+    let Some(dir) = env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.to_path_buf()))
+    else {
+        return vec![];
+    };
+    let crate_name = tcx.crate_name(LOCAL_CRATE);
+    let has_std = tcx
+        .crates(())
+        .iter()
+        .any(|krate| tcx.crate_name(*krate) == sym::std);
+    PRELUDE_CRATES
+        .iter()
+        .take_while(|(name, _)| crate_name.as_str() != *name)
+        .filter(|(_, needs_std)| has_std || !needs_std)
+        .map(|(name, _)| dir.join(format!("lib{name}.fluxmeta")))
+        .collect()
+}
+
+/// Whether `def_id` is defined in one of the crates the prelude is compiled into. This is the case
+/// for every item of the crate being compiled while building the prelude.
+pub(crate) fn contains(tcx: TyCtxt, def_id: DefId) -> bool {
+    let crate_name = tcx.crate_name(def_id.krate);
+    PRELUDE_CRATES
+        .iter()
+        .any(|(name, _)| crate_name.as_str() == *name)
+}
+
+/// Returns the groups specifying a type or trait for which there's already an extern spec in
+/// `extern_specs`.
+pub(crate) fn overridden_groups(
+    tcx: TyCtxt,
+    extern_specs: impl IntoIterator<Item = DefId>,
+) -> FxHashSet<Symbol> {
+    let mut specified = FxHashSet::default();
+    for def_id in extern_specs {
+        specified_items(tcx, def_id, &mut specified);
+    }
+    GROUPS
+        .iter()
+        .filter(|group| {
+            group.specifies.iter().any(|name| {
+                item_by_name(tcx, *name).is_some_and(|def_id| specified.contains(&def_id))
+            })
+        })
+        .map(|group| Symbol::intern(group.name))
+        .collect()
+}
+
+fn item_by_name(tcx: TyCtxt, name: Symbol) -> Option<DefId> {
+    tcx.get_diagnostic_item(name)
+        .or_else(|| tcx.lang_items().get(LangItem::from_name(name)?))
+}
+
+/// Collects the types, traits and methods refined by an extern spec for `def_id`
+fn specified_items(tcx: TyCtxt, def_id: DefId, specified: &mut FxHashSet<DefId>) {
+    match tcx.def_kind(def_id) {
+        DefKind::Struct | DefKind::Enum | DefKind::Trait => {
+            specified.insert(def_id);
+        }
+        DefKind::AssocFn => {
+            specified.insert(def_id);
+            specified_items(tcx, tcx.parent(def_id), specified);
+        }
+        DefKind::Impl { .. } => {
+            if let Some(adt_def) = tcx.type_of(def_id).instantiate_identity().ty_adt_def() {
+                specified.insert(adt_def.did());
+            }
+            if let Some(trait_id) = tcx.trait_id_of_impl(def_id) {
+                specified.insert(trait_id);
+            }
+        }
+        _ => {}
+    }
+}
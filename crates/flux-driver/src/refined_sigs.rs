@@ -0,0 +1,152 @@
+//! Refined signatures of a crate as json, enabled with `-Zflux-emit=refined-sigs` (see
+//! [`config::emit`]).
+//!
+//! The file is meant for external tools, e.g., to diff the specs of two versions of a crate or to
+//! audit which functions are trusted and why (see `#[flux::trusted(reason = "..")]`). Its schema
+//! doesn't follow the internal representation of signatures, which changes often. Instead,
+//! signatures and sorts are printed in the syntax of `#[flux::sig]` and `#[flux::refined_by]`, and
+//! items are sorted by path so the output only changes when the specs do. Any incompatible change
+//! to the schema must bump [`SCHEMA_VERSION`].
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "crate": "mycrate",
+//!   "fns": [
+//!     {
+//!       "path": "rvec::RVec::<T>::push",
+//!       "span": "src/rvec.rs:42:5",
+//!       "trusted": true,
+//!       "trusted_reason": "the length is tracked by the allocator",
+//!       "sig": "fn(self: &strg RVec<T>[@n], T) ensures self: RVec<T>[n + 1]"
+//!     }
+//!   ],
+//!   "adts": [
+//!     {
+//!       "path": "rvec::RVec",
+//!       "span": "src/rvec.rs:10:1",
+//!       "refined_by": [{ "name": "len", "sort": "int" }]
+//!     }
+//!   ]
+//! }
+//! ```
+use std::{fs, io};
+
+use flux_config as config;
+use flux_middle::{fhir::Ignored, global_env::GlobalEnv, pretty};
+use rustc_hir::{
+    def::DefKind,
+    def_id::{LocalDefId, LOCAL_CRATE},
+};
+use rustc_span::Span;
+use serde_json::{Map, Value};
+
+use crate::prelude;
+
+const SCHEMA_VERSION: u64 = 1;
+
+/// Writes the refined signatures of the crate to `{crate}.refined-sigs.json` in
+/// [`config::emit_dir`]. Items whose signature has errors are skipped, the errors are reported
+/// when checking them.
+pub(crate) fn emit(genv: GlobalEnv) -> io::Result<()> {
+    let tcx = genv.tcx();
+    let mut fns = vec![];
+    let mut adts = vec![];
+    for def_id in tcx.hir_crate_items(()).definitions() {
+        if genv.ignored(def_id) == Ignored::Yes
+            || prelude::contains(tcx, def_id.to_def_id())
+            || is_extern_spec(genv, def_id)
+        {
+            continue;
+        }
+        match genv.def_kind(def_id) {
+            DefKind::Fn | DefKind::AssocFn => {
+                if let Some(json) = fn_to_json(genv, def_id) {
+                    fns.push(json);
+                }
+            }
+            DefKind::Struct | DefKind::Enum => {
+                if let Some(json) = adt_to_json(genv, def_id) {
+                    adts.push(json);
+                }
+            }
+            _ => {}
+        }
+    }
+    fns.sort_by(|a, b| a["path"].as_str().cmp(&b["path"].as_str()));
+    adts.sort_by(|a, b| a["path"].as_str().cmp(&b["path"].as_str()));
+
+    let crate_name = tcx.crate_name(LOCAL_CRATE);
+    let mut map = Map::new();
+    map.insert("version".to_string(), Value::from(SCHEMA_VERSION));
+    map.insert("crate".to_string(), Value::from(crate_name.as_str()));
+    map.insert("fns".to_string(), Value::Array(fns));
+    map.insert("adts".to_string(), Value::Array(adts));
+
+    fs::create_dir_all(config::emit_dir())?;
+    let file =
+        fs::File::create(config::emit_dir().join(format!("{crate_name}.refined-sigs.json")))?;
+    serde_json::to_writer_pretty(file, &Value::Object(map))?;
+    Ok(())
+}
+
+/// Whether `def_id` is a dummy item holding an extern spec or is nested in one, e.g., a method in
+/// an extern impl. The spec belongs to the extern item and not to the crate.
+fn is_extern_spec(genv: GlobalEnv, def_id: LocalDefId) -> bool {
+    let tcx = genv.tcx();
+    let is_extern = |def_id| matches!(genv.map().extern_id_of(def_id), Ok(Some(_)));
+    is_extern(def_id)
+        || tcx.opt_local_parent(def_id).is_some_and(|parent| {
+            matches!(tcx.def_kind(parent), DefKind::Impl { .. }) && is_extern(parent)
+        })
+}
+
+fn fn_to_json(genv: GlobalEnv, def_id: LocalDefId) -> Option<Value> {
+    let fn_sig = genv.fn_sig(def_id).ok()?;
+    let mut map = item_to_json(genv, def_id);
+    let trusted = genv.map().is_trusted(def_id).unwrap_or(false);
+    map.insert("trusted".to_string(), Value::from(trusted));
+    if let Some(reason) = genv.trusted(def_id).and_then(|trusted| trusted.reason) {
+        map.insert("trusted_reason".to_string(), Value::from(reason.as_str()));
+    }
+    let sig = pretty::to_surface_string(genv.tcx(), &fn_sig.skip_binder());
+    map.insert("sig".to_string(), Value::from(sig));
+    Some(Value::Object(map))
+}
+
+fn adt_to_json(genv: GlobalEnv, def_id: LocalDefId) -> Option<Value> {
+    let sort_def = genv.adt_sort_def_of(def_id).ok()?;
+    let sorts = sort_def.sorts(&sort_def.identity_args());
+    let fields = sort_def
+        .field_names()
+        .iter()
+        .zip(sorts.iter())
+        .map(|(name, sort)| {
+            let mut field = Map::new();
+            field.insert("name".to_string(), Value::from(name.as_str()));
+            field.insert(
+                "sort".to_string(),
+                Value::from(pretty::to_surface_string(genv.tcx(), sort)),
+            );
+            Value::Object(field)
+        })
+        .collect();
+    let mut map = item_to_json(genv, def_id);
+    map.insert("refined_by".to_string(), Value::Array(fields));
+    Some(Value::Object(map))
+}
+
+/// The fields shared by all items: their path and where they are defined
+fn item_to_json(genv: GlobalEnv, def_id: LocalDefId) -> Map<String, Value> {
+    let tcx = genv.tcx();
+    let mut map = Map::new();
+    map.insert("path".to_string(), Value::from(tcx.def_path_str(def_id.to_def_id())));
+    map.insert("span".to_string(), Value::from(span_to_string(genv, tcx.def_span(def_id))));
+    map
+}
+
+/// Formats a span as `file:line:column`, both 1-based
+fn span_to_string(genv: GlobalEnv, span: Span) -> String {
+    let loc = genv.tcx().sess.source_map().lookup_char_pos(span.lo());
+    format!("{}:{}:{}", loc.file.name.prefer_local(), loc.line, loc.col.0 + 1)
+}
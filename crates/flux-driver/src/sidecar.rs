@@ -0,0 +1,246 @@
+//! Specs written in a separate file, for items that can't be annotated directly, e.g., generated
+//! code or code vendored from another crate.
+//!
+//! The file sits next to the root of the crate with the extension `.flux`, e.g., `src/lib.flux` for
+//! `src/lib.rs`, unless [`config::spec_file`] says otherwise. It contains a list of flux
+//! attributes, each group followed by the path of the item they are attached to:
+//!
+//! ```ignore
+//! #[flux::sig(fn(x: i32{x > 0}) -> i32[x + 1])]
+//! generated::inc;
+//!
+//! #[flux::field(i32{v: v >= 0})]
+//! generated::Counter::count;
+//! ```
+//!
+//! Paths are relative to the root of the crate and written without generic arguments, e.g.,
+//! `rvec::RVec::push` for a method in an inherent impl, `<rvec::RVec as Clone>::clone` for a method
+//! in a trait impl or `Counter::count` for a field. A path that refers to more than one item, e.g.,
+//! methods with the same name in impls that only differ in their generic arguments, is an error.
+//! The attributes are added to the ones written in the source, so an item can't have, e.g., a
+//! signature in both places.
+use std::{collections::HashMap, path::PathBuf};
+
+use flux_config as config;
+use flux_errors::FluxSession;
+use rustc_ast::{
+    attr,
+    token::{self, Delimiter},
+    AttrKind, AttrStyle, Attribute,
+};
+use rustc_ast_pretty::pprust;
+use rustc_errors::{ErrorGuaranteed, PResult};
+use rustc_hir::{def::DefKind, def_id::LocalDefId};
+use rustc_middle::ty::TyCtxt;
+use rustc_parse::parser::{Parser, PathStyle};
+use rustc_span::{symbol::kw, Span, Symbol};
+
+type Result<T = ()> = std::result::Result<T, ErrorGuaranteed>;
+
+#[derive(Default)]
+pub(crate) struct SidecarSpecs {
+    attrs: HashMap<LocalDefId, Vec<Attribute>>,
+}
+
+impl SidecarSpecs {
+    /// Parses the spec file of the crate, if there's one, and resolves the paths in it
+    pub(crate) fn load(tcx: TyCtxt, sess: &FluxSession) -> Result<SidecarSpecs> {
+        let Some(path) = spec_file(tcx) else { return Ok(SidecarSpecs::default()) };
+        // Editing the spec file must make cargo check the crate again
+        tcx.sess
+            .psess
+            .file_depinfo
+            .borrow_mut()
+            .insert(Symbol::intern(&path.to_string_lossy()));
+        let mut parser = rustc_parse::new_parser_from_file(&sess.parse_sess, &path, None);
+        let mut entries = vec![];
+        while parser.token != token::Eof {
+            entries.push(parse_entry(sess, &mut parser).map_err(|err| err.emit())?);
+        }
+
+        let mut items: HashMap<String, Vec<LocalDefId>> = HashMap::new();
+        for def_id in tcx.hir_crate_items(()).definitions() {
+            if matches!(tcx.def_kind(def_id), DefKind::Ctor(..)) {
+                continue;
+            }
+            items
+                .entry(item_key(&tcx.def_path_str(def_id.to_def_id())))
+                .or_default()
+                .push(def_id);
+        }
+
+        let mut specs = SidecarSpecs::default();
+        let mut err = None;
+        for (path, span, attrs) in entries {
+            match items.get(&item_key(&path)).map(Vec::as_slice) {
+                Some([def_id]) => specs.attrs.entry(*def_id).or_default().extend(attrs),
+                Some(_) => err = Some(sess.emit_err(errors::AmbiguousPath { span, path })),
+                None => err = Some(sess.emit_err(errors::UnresolvedPath { span, path })),
+            }
+        }
+        if let Some(err) = err {
+            Err(err)
+        } else {
+            Ok(specs)
+        }
+    }
+
+    /// The attributes attached to `def_id` in the spec file
+    pub(crate) fn attrs(&self, def_id: LocalDefId) -> &[Attribute] {
+        self.attrs.get(&def_id).map_or(&[], Vec::as_slice)
+    }
+}
+
+fn spec_file(tcx: TyCtxt) -> Option<PathBuf> {
+    if let Some(path) = config::spec_file() {
+        return Some(path.clone());
+    }
+    let path = tcx.sess.local_crate_source_file()?.with_extension("flux");
+    path.exists().then_some(path)
+}
+
+/// Parses a list of attributes followed by the path they are attached to, e.g.,
+/// `#[flux::trusted] foo::bar;`. The path is returned as written together with its span.
+fn parse_entry<'a>(
+    sess: &'a FluxSession,
+    parser: &mut Parser<'a>,
+) -> PResult<'a, (String, Span, Vec<Attribute>)> {
+    let mut attrs = vec![];
+    while parser.token == token::Pound {
+        let lo = parser.token.span;
+        parser.bump();
+        parser.expect(&token::OpenDelim(Delimiter::Bracket))?;
+        let item = parser.parse_attr_item(false)?;
+        parser.expect(&token::CloseDelim(Delimiter::Bracket))?;
+        let span = lo.to(parser.prev_token.span);
+        let attr = attr::mk_attr_from_item(
+            &sess.parse_sess.attr_id_generator,
+            item,
+            None,
+            AttrStyle::Outer,
+            span,
+        );
+        if !is_flux_attr(&attr) {
+            return Err(sess.dcx().create_err(errors::NotFluxAttr { span }));
+        }
+        attrs.push(attr);
+    }
+    let lo = parser.token.span;
+    let path = parse_entry_path(parser)?;
+    let span = lo.to(parser.prev_token.span);
+    parser.expect(&token::Semi)?;
+    Ok((path, span, attrs))
+}
+
+/// Parses a path, e.g., `foo::bar`, or a qualified path for an item in a trait impl, e.g.,
+/// `<Foo as Trait>::bar`
+fn parse_entry_path<'a>(parser: &mut Parser<'a>) -> PResult<'a, String> {
+    if !parser.eat(&token::Lt) {
+        return Ok(pprust::path_to_string(&parser.parse_path(PathStyle::Mod)?));
+    }
+    let self_ty = parser.parse_ty()?;
+    if !parser.eat_keyword(kw::As) {
+        return parser.unexpected();
+    }
+    let trait_path = parser.parse_path(PathStyle::Type)?;
+    parser.expect(&token::Gt)?;
+    // The rest of the path starts with `::` so it's parsed as a global path
+    let rest = parser.parse_path(PathStyle::Mod)?;
+    Ok(format!(
+        "<{} as {}>{}",
+        pprust::ty_to_string(&self_ty),
+        pprust::path_to_string(&trait_path),
+        pprust::path_to_string(&rest)
+    ))
+}
+
+fn is_flux_attr(attr: &Attribute) -> bool {
+    let AttrKind::Normal(attr_item) = &attr.kind else { return false };
+    attr_item
+        .item
+        .path
+        .segments
+        .first()
+        .is_some_and(|segment| matches!(segment.ident.as_str(), "flux" | "flux_tool"))
+}
+
+/// The key used to match a path in the spec file with the path of an item printed by
+/// [`TyCtxt::def_path_str`]: the path without generic arguments and without a leading `crate`.
+fn item_key(path: &str) -> String {
+    let key = strip_generic_args(path)
+        .replace("<crate::", "<")
+        .replace(" as crate::", " as ");
+    if let Some(key) = key.strip_prefix("crate::") {
+        return key.to_string();
+    }
+    key
+}
+
+/// Removes the generic arguments of a path printed by [`TyCtxt::def_path_str`], e.g.,
+/// `RVec::<T>::push` becomes `RVec::push` and `<RVec<T> as Clone>::clone` becomes
+/// `<RVec as Clone>::clone`.
+pub(crate) fn strip_generic_args(path: &str) -> String {
+    let mut stripped = String::with_capacity(path.len());
+    // Whether each of the enclosing `<` starts a qualified path, which is kept, or generic
+    // arguments, which are removed
+    let mut open = vec![];
+    let mut prev = None;
+    for c in path.chars() {
+        let in_args = open.contains(&false);
+        match c {
+            '<' => {
+                let qualified = !in_args && matches!(prev, None | Some('<'));
+                if qualified {
+                    stripped.push(c);
+                }
+                open.push(qualified);
+            }
+            // The arrow of a function type, e.g., in `<fn() -> i32 as Trait>::m`
+            '>' if prev == Some('-') => {
+                if !in_args {
+                    stripped.push(c);
+                }
+            }
+            '>' => {
+                if open.pop() == Some(true) {
+                    stripped.push(c);
+                }
+            }
+            _ if !in_args => stripped.push(c),
+            _ => {}
+        }
+        prev = Some(c);
+    }
+    stripped.replace("::::", "::")
+}
+
+mod errors {
+    use flux_errors::E0999;
+    use flux_macros::Diagnostic;
+    use rustc_span::Span;
+
+    #[derive(Diagnostic)]
+    #[diag(driver_sidecar_unresolved_path, code = E0999)]
+    #[note]
+    pub(super) struct UnresolvedPath {
+        #[primary_span]
+        pub span: Span,
+        pub path: String,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_sidecar_ambiguous_path, code = E0999)]
+    #[note]
+    pub(super) struct AmbiguousPath {
+        #[primary_span]
+        pub span: Span,
+        pub path: String,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_sidecar_not_flux_attr, code = E0999)]
+    pub(super) struct NotFluxAttr {
+        #[primary_span]
+        pub span: Span,
+    }
+}
@@ -0,0 +1,49 @@
+//! Stable codes of the errors reported by flux together with their extended explanations, which
+//! can be printed with `cargo flux --explain EFLUX0003`.
+//!
+//! This module doesn't depend on rustc so `flux-bin` can include it as well. The diagnostics
+//! encode a code `EFLUXn` as the rustc error code `OFFSET + n` (see `flux_errors::EFLUX0001`)
+//! because rustc can only print codes of the form `E0000`.
+
+/// Offset of flux error codes when encoded as rustc error codes
+pub const OFFSET: u32 = 9000;
+
+macro_rules! error_codes {
+    ($($code:ident,)*) => {
+        /// Every flux error code paired with its extended explanation
+        pub static ERROR_CODES: &[(&str, &str)] = &[
+            $((stringify!($code), include_str!(concat!("error_codes/", stringify!($code), ".md"))),)*
+        ];
+    };
+}
+
+error_codes! {
+    EFLUX0001,
+    EFLUX0002,
+    EFLUX0003,
+    EFLUX0004,
+    EFLUX0005,
+    EFLUX0006,
+    EFLUX0007,
+    EFLUX0008,
+    EFLUX0009,
+    EFLUX0010,
+}
+
+/// Returns the extended explanation of `code`, e.g., `EFLUX0003`.
+pub fn explanation(code: &str) -> Option<&'static str> {
+    ERROR_CODES
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, explanation)| *explanation)
+}
+
+/// Returns the name of the flux error code encoded as the rustc error code `n`, if any
+pub fn name(n: u32) -> Option<&'static str> {
+    let n = n.checked_sub(OFFSET)?;
+    let name = format!("EFLUX{n:04}");
+    ERROR_CODES
+        .iter()
+        .find(|(code, _)| *code == name)
+        .map(|(code, _)| *code)
+}
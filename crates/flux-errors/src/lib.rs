@@ -5,6 +5,8 @@ extern crate rustc_errors;
 extern crate rustc_session;
 extern crate rustc_span;
 
+pub mod error_codes;
+
 use std::{cell::Cell, io, sync::Arc};
 
 use flux_common::result::{ErrorCollector, ErrorEmitter};
@@ -15,7 +17,7 @@ use rustc_errors::{
     emitter::{stderr_destination, Emitter, HumanEmitter, HumanReadableErrorType},
     json::JsonEmitter,
     registry::Registry,
-    Diagnostic, ErrCode, FatalAbort, FatalError, LazyFallbackBundle,
+    Diag, Diagnostic, EmissionGuarantee, ErrCode, FatalAbort, FatalError, LazyFallbackBundle,
 };
 use rustc_session::{
     config::{self, ErrorOutputType},
@@ -30,6 +32,27 @@ pub struct FluxSession {
 // FIXME(nilehmann) We probably need to move out of this error reporting
 pub const E0999: ErrCode = ErrCode::from_u32(999);
 
+/// A precondition of a function call cannot be proved
+pub const EFLUX0001: ErrCode = ErrCode::from_u32(error_codes::OFFSET + 1);
+/// A postcondition of a function cannot be proved
+pub const EFLUX0002: ErrCode = ErrCode::from_u32(error_codes::OFFSET + 2);
+/// The invariant of a type may not hold when a place is folded
+pub const EFLUX0003: ErrCode = ErrCode::from_u32(error_codes::OFFSET + 3);
+/// An arithmetic operation may overflow
+pub const EFLUX0004: ErrCode = ErrCode::from_u32(error_codes::OFFSET + 4);
+/// The refinement parameters of a function call cannot be inferred
+pub const EFLUX0005: ErrCode = ErrCode::from_u32(error_codes::OFFSET + 5);
+/// The types inferred for a join point may not hold when jumping to it
+pub const EFLUX0006: ErrCode = ErrCode::from_u32(error_codes::OFFSET + 6);
+/// An assignment through a reference may not respect the type of the reference
+pub const EFLUX0007: ErrCode = ErrCode::from_u32(error_codes::OFFSET + 7);
+/// The divisor of a division may be zero
+pub const EFLUX0008: ErrCode = ErrCode::from_u32(error_codes::OFFSET + 8);
+/// The divisor of a remainder operation may be zero
+pub const EFLUX0009: ErrCode = ErrCode::from_u32(error_codes::OFFSET + 9);
+/// An assertion inserted by the compiler may fail
+pub const EFLUX0010: ErrCode = ErrCode::from_u32(error_codes::OFFSET + 10);
+
 impl FluxSession {
     pub fn new(
         opts: &config::Options,
@@ -47,7 +70,33 @@ impl FluxSession {
 
     #[track_caller]
     pub fn emit_err<'a>(&'a self, err: impl Diagnostic<'a>) -> ErrorGuaranteed {
-        self.parse_sess.dcx.emit_err(err)
+        let mut diag = self.parse_sess.dcx.create_err(err);
+        explain_code(&mut diag);
+        diag.emit()
+    }
+
+    /// Like [`FluxSession::emit_err`] but overriding the code of the diagnostic. This is useful
+    /// when the same diagnostic is used for errors of different classes.
+    #[track_caller]
+    pub fn emit_err_with_code<'a>(
+        &'a self,
+        err: impl Diagnostic<'a>,
+        code: ErrCode,
+    ) -> ErrorGuaranteed {
+        let mut diag = self.parse_sess.dcx.create_err(err);
+        diag.code(code);
+        explain_code(&mut diag);
+        diag.emit()
+    }
+
+    #[track_caller]
+    pub fn emit_warn<'a>(&'a self, warning: impl Diagnostic<'a, ()>) {
+        self.parse_sess.dcx.emit_warn(warning);
+    }
+
+    #[track_caller]
+    pub fn emit_note<'a>(&'a self, note: impl Diagnostic<'a, ()>) {
+        self.parse_sess.dcx.emit_note(note);
     }
 
     #[track_caller]
@@ -74,6 +123,16 @@ impl FluxSession {
     }
 }
 
+/// Replaces a flux error code (see [`error_codes`]) with a help pointing to its explanation.
+/// Otherwise, rustc would print it as a regular error code (e.g., `E9003`) and suggest running
+/// `rustc --explain` on it.
+fn explain_code<G: EmissionGuarantee>(diag: &mut Diag<'_, G>) {
+    let Some(code) = diag.code else { return };
+    let Some(name) = error_codes::name(code.as_u32()) else { return };
+    diag.code = None;
+    diag.help(format!("for more information about this error, try `cargo flux --explain {name}`"));
+}
+
 fn emitter(
     opts: &config::Options,
     source_map: Arc<SourceMap>,
@@ -105,6 +105,7 @@ fhir_analysis_invalid_refinement =
     .label = expected a refinement of `{$expected_ty}`
     .expected_label = must be a valid refinement of this type
     .note = {$note}
+    .position_note = the refined signature is out of sync with the rust signature in {$position}
 
 fhir_analysis_fun_arg_count_mismatch =
     argument count mismatch
@@ -116,6 +117,7 @@ fhir_analysis_fun_arg_count_mismatch =
         [one] argument
         *[other] arguments
     }
+    .note = the signatures are out of sync at: {$positions}
 
 fhir_analysis_generic_argument_count_mismatch =
     this {$def_descr} must take {$expected} generic {$expected ->
@@ -174,3 +176,8 @@ fhir_analysis_incompatible_sort =
 
 fhir_analysis_invalid_assoc_reft =
     associated refinement `{$name}` is not a member of trait `{$trait_}`
+
+# Unused items
+
+fhir_analysis_unused_item =
+    {$kind} `{$name}` is never used
@@ -7,7 +7,7 @@
 //! [`fhir`]: flux_middle::fhir
 use std::iter;
 
-use flux_common::{bug, index::IndexGen, iter::IterExt, span_bug};
+use flux_common::{bug, index::IndexGen, iter::IterExt, result::ResultExt, span_bug};
 use flux_errors::ErrorGuaranteed;
 use flux_middle::{
     fhir::{
@@ -20,7 +20,8 @@ use flux_middle::{
 };
 use rustc_data_structures::unord::UnordMap;
 use rustc_errors::Diagnostic;
-use rustc_hir::OwnerId;
+use rustc_hir::{def::DefKind, OwnerId};
+use rustc_span::symbol::{kw, Ident};
 
 pub(crate) fn check_node<'genv>(
     genv: GlobalEnv<'genv, '_>,
@@ -91,7 +92,8 @@ pub(crate) fn check_fn_sig<'genv>(
     }
     let self_ty = lift::lift_self_ty(genv, owner_id)?;
     let expected_fn_decl = &lift::lift_fn_decl(genv, owner_id)?.0;
-    Zipper::new(genv, wfckresults, self_ty).zip_fn_decl(fn_sig.decl, expected_fn_decl)
+    let arg_names = genv.tcx().fn_arg_names(owner_id.def_id);
+    Zipper::new(genv, wfckresults, self_ty).zip_fn_decl(fn_sig.decl, expected_fn_decl, arg_names)
 }
 
 pub(crate) fn check_ty_alias<'genv>(
@@ -153,6 +155,9 @@ struct Zipper<'zip, 'genv, 'tcx> {
     wfckresults: &'zip mut WfckResults<'genv>,
     locs: LocsMap<'genv>,
     self_ty: Option<fhir::Ty<'genv>>,
+    /// The position in a function signature being zipped, e.g., "argument `x`" or "the return
+    /// type". It is used to point at the position that is out of sync in error messages.
+    position: Option<String>,
 }
 
 type LocsMap<'genv> = UnordMap<fhir::ParamId, fhir::Ty<'genv>>;
@@ -163,7 +168,7 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
         wfckresults: &'zip mut WfckResults<'genv>,
         self_ty: Option<fhir::Ty<'genv>>,
     ) -> Self {
-        Self { genv, wfckresults, locs: LocsMap::default(), self_ty }
+        Self { genv, wfckresults, locs: LocsMap::default(), self_ty, position: None }
     }
 
     fn zip_enum_variant(
@@ -187,14 +192,27 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
         &mut self,
         fn_decl: &fhir::FnDecl,
         expected_fn_sig: &fhir::FnDecl<'genv>,
+        arg_names: &[Ident],
     ) -> Result<(), ErrorGuaranteed> {
         if fn_decl.args.len() != expected_fn_sig.args.len() {
-            return Err(self.emit_err(errors::FunArgCountMismatch::new(fn_decl, expected_fn_sig)));
+            return Err(self.emit_err(errors::FunArgCountMismatch::new(
+                fn_decl,
+                expected_fn_sig,
+                arg_names,
+            )));
         }
-        self.zip_tys(fn_decl.args, expected_fn_sig.args)?;
+        iter::zip(fn_decl.args, expected_fn_sig.args)
+            .enumerate()
+            .try_for_each_exhaust(|(i, (ty, expected))| {
+                self.position = Some(arg_position(arg_names, i));
+                self.zip_ty(ty, expected)
+            })?;
+        self.position = Some("a `requires` clause".to_string());
         self.zip_constraints(fn_decl.requires)?;
 
+        self.position = Some("the return type".to_string());
         self.zip_ty(&fn_decl.output.ret, &expected_fn_sig.output.ret)?;
+        self.position = Some("an `ensures` clause".to_string());
         self.zip_constraints(fn_decl.output.ensures)
     }
 
@@ -226,6 +244,9 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
         ty: &fhir::Ty,
         expected_ty: &fhir::Ty<'genv>,
     ) -> Result<(), ErrorGuaranteed> {
+        if let Some(alias_ty) = self.unfold_alias(ty, expected_ty)? {
+            return self.zip_ty(&alias_ty, expected_ty);
+        }
         match (ty.kind, expected_ty.kind) {
             (fhir::TyKind::Constr(_, ty) | fhir::TyKind::Exists(.., ty), _) => {
                 self.zip_ty(ty, expected_ty)
@@ -243,7 +264,7 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
                     self.locs.insert(id, *expected_mut_ty.ty);
                     Ok(())
                 } else {
-                    Err(self.emit_err(
+                    Err(self.emit_invalid_refinement(
                         errors::InvalidRefinement::from_tys(ty, expected_ty).with_note(
                             "only mutable reference can be refined with a strong reference",
                         ),
@@ -252,7 +273,7 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
             }
             (fhir::TyKind::Ref(lft, mut_ty), fhir::TyKind::Ref(expected_lft, expected_mut_ty)) => {
                 if mut_ty.mutbl != expected_mut_ty.mutbl {
-                    return Err(self.emit_err(
+                    return Err(self.emit_invalid_refinement(
                         errors::InvalidRefinement::from_tys(ty, expected_ty)
                             .with_note("types differ in mutability"),
                     ));
@@ -262,7 +283,7 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
             }
             (fhir::TyKind::Tuple(tys), fhir::TyKind::Tuple(expected_tys)) => {
                 if tys.len() != expected_tys.len() {
-                    return Err(self.emit_err(
+                    return Err(self.emit_invalid_refinement(
                         errors::InvalidRefinement::from_tys(ty, expected_ty)
                             .with_note("tuples have different length"),
                     ));
@@ -280,13 +301,23 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
                 fhir::TyKind::RawPtr(expected_ty, expected_mutbl),
             ) => {
                 if mutbl != expected_mutbl {
-                    return Err(self.emit_err(
+                    return Err(self.emit_invalid_refinement(
                         errors::InvalidRefinement::from_tys(ty, expected_ty)
                             .with_note("types differ in mutability"),
                     ));
                 }
                 self.zip_ty(ty, expected_ty)
             }
+            (fhir::TyKind::BareFn(bare_fn), fhir::TyKind::BareFn(expected_bare_fn)) => {
+                if bare_fn.inputs.len() != expected_bare_fn.inputs.len() {
+                    return Err(self.emit_invalid_refinement(
+                        errors::InvalidRefinement::from_tys(ty, expected_ty)
+                            .with_note("function pointers have different number of arguments"),
+                    ));
+                }
+                self.zip_tys(bare_fn.inputs, expected_bare_fn.inputs)?;
+                self.zip_ty(bare_fn.output, expected_bare_fn.output)
+            }
             (fhir::TyKind::Never, fhir::TyKind::Never) => Ok(()),
             (fhir::TyKind::Hole(fhir_id), _) => {
                 self.wfckresults
@@ -299,13 +330,13 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
                 fhir::TyKind::OpaqueDef(exp_item_id, exp_args, _, _),
             ) => {
                 if item_id != exp_item_id {
-                    return Err(self.emit_err(
+                    return Err(self.emit_invalid_refinement(
                         errors::InvalidRefinement::from_tys(ty, expected_ty)
                             .with_note("impl trait: types differ in impl id!"),
                     ));
                 }
                 if args.len() != exp_args.len() {
-                    return Err(self.emit_err(
+                    return Err(self.emit_invalid_refinement(
                         errors::InvalidRefinement::from_tys(ty, expected_ty)
                             .with_note("impl trait: types differ in number of args!"),
                     ));
@@ -316,8 +347,46 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
                 Ok(())
             }
 
-            _ => Err(self.emit_err(errors::InvalidRefinement::from_tys(ty, expected_ty))),
+            _ => {
+                Err(self
+                    .emit_invalid_refinement(errors::InvalidRefinement::from_tys(ty, expected_ty)))
+            }
+        }
+    }
+
+    /// If `ty` is a flux alias used where the rust declaration has the type the alias stands for,
+    /// e.g., `Vec<Nat>` for a `Vec<i32>`, returns the definition of the alias to be zipped instead.
+    /// Only local aliases without generics are unfolded.
+    fn unfold_alias(
+        &self,
+        ty: &fhir::Ty,
+        expected_ty: &fhir::Ty,
+    ) -> Result<Option<fhir::Ty<'genv>>, ErrorGuaranteed> {
+        let (fhir::TyKind::BaseTy(bty) | fhir::TyKind::Indexed(bty, _)) = ty.kind else {
+            return Ok(None);
+        };
+        let fhir::BaseTyKind::Path(fhir::QPath::Resolved(None, path)) = bty.kind else {
+            return Ok(None);
+        };
+        let Res::Def(DefKind::TyAlias, def_id) = path.res else { return Ok(None) };
+        if let fhir::TyKind::BaseTy(expected_bty) = expected_ty.kind
+            && let fhir::BaseTyKind::Path(fhir::QPath::Resolved(None, expected_path)) =
+                expected_bty.kind
+            && self.is_same_res(path.res, expected_path.res)
+        {
+            return Ok(None);
+        }
+        let Some(def_id) = def_id.as_local() else { return Ok(None) };
+        let alias = self
+            .genv
+            .map()
+            .expect_item(def_id)
+            .emit(&self.genv)?
+            .expect_type_alias();
+        if !alias.generics.params.is_empty() || !path.last_segment().args.is_empty() {
+            return Ok(None);
         }
+        Ok(Some(alias.ty))
     }
 
     fn zip_generic_arg(
@@ -362,7 +431,12 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
             (fhir::BaseTyKind::Slice(ty), fhir::BaseTyKind::Slice(expected_ty)) => {
                 self.zip_ty(ty, expected_ty)
             }
-            _ => Err(self.emit_err(errors::InvalidRefinement::from_btys(bty, expected_bty))),
+            _ => {
+                Err(self.emit_invalid_refinement(errors::InvalidRefinement::from_btys(
+                    bty,
+                    expected_bty,
+                )))
+            }
         }
     }
 
@@ -382,7 +456,12 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
                 self.zip_ty(self_ty, expected_self_ty)?;
                 self.zip_path(path, expected_path)
             }
-            _ => Err(self.emit_err(errors::InvalidRefinement::from_qpaths(qpath, expected_qpath))),
+            _ => {
+                Err(self.emit_invalid_refinement(errors::InvalidRefinement::from_qpaths(
+                    qpath,
+                    expected_qpath,
+                )))
+            }
         }
     }
 
@@ -413,7 +492,10 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
             {
                 return self.zip_path(path, &expected_path);
             }
-            return Err(self.emit_err(errors::InvalidRefinement::from_paths(path, expected_path)));
+            return Err(self.emit_invalid_refinement(errors::InvalidRefinement::from_paths(
+                path,
+                expected_path,
+            )));
         }
         // TODO(nilehmann) we should check all segments here
         if path.last_segment().args.len() != expected_path.last_segment().args.len() {
@@ -424,17 +506,33 @@ impl<'zip, 'genv, 'tcx> Zipper<'zip, 'genv, 'tcx> {
             .try_for_each_exhaust(|(arg, expected)| self.zip_generic_arg(arg, expected))
     }
 
+    #[track_caller]
+    fn emit_invalid_refinement(&self, err: errors::InvalidRefinement) -> ErrorGuaranteed {
+        self.emit_err(err.with_position(self.position.clone()))
+    }
+
     #[track_caller]
     fn emit_err<'a>(&'a self, err: impl Diagnostic<'a>) -> ErrorGuaranteed {
         self.genv.sess().emit_err(err)
     }
 }
 
+/// Describes the `i`-th argument of a function for error messages
+fn arg_position(arg_names: &[Ident], i: usize) -> String {
+    match arg_names.get(i) {
+        Some(name) if !matches!(name.name, kw::Empty | kw::Underscore) => {
+            format!("argument `{name}`")
+        }
+        _ => format!("argument {}", i + 1),
+    }
+}
+
 mod errors {
     use flux_errors::E0999;
     use flux_macros::Diagnostic;
     use flux_middle::fhir;
-    use rustc_span::Span;
+    use itertools::Itertools;
+    use rustc_span::{symbol::Ident, Span};
 
     #[derive(Diagnostic)]
     #[diag(fhir_analysis_invalid_refinement, code = E0999)]
@@ -448,6 +546,9 @@ mod errors {
         #[note]
         has_note: Option<()>,
         note: String,
+        #[note(fhir_analysis_position_note)]
+        has_position: Option<()>,
+        position: String,
     }
 
     impl InvalidRefinement {
@@ -458,6 +559,8 @@ mod errors {
                 expected_ty: format!("{expected_ty:?}"),
                 has_note: None,
                 note: String::new(),
+                has_position: None,
+                position: String::new(),
             }
         }
 
@@ -468,6 +571,8 @@ mod errors {
                 expected_ty: format!("{expected_path:?}"),
                 has_note: None,
                 note: String::new(),
+                has_position: None,
+                position: String::new(),
             }
         }
 
@@ -478,6 +583,8 @@ mod errors {
                 expected_ty: format!("{expected_bty:?}"),
                 has_note: None,
                 note: String::new(),
+                has_position: None,
+                position: String::new(),
             }
         }
 
@@ -488,16 +595,26 @@ mod errors {
                 expected_ty: format!("{expected_qpath:?}"),
                 has_note: None,
                 note: String::new(),
+                has_position: None,
+                position: String::new(),
             }
         }
 
         pub(super) fn with_note(self, note: impl ToString) -> Self {
             Self { has_note: Some(()), note: note.to_string(), ..self }
         }
+
+        pub(super) fn with_position(self, position: Option<String>) -> Self {
+            match position {
+                Some(position) => Self { has_position: Some(()), position, ..self },
+                None => self,
+            }
+        }
     }
 
     #[derive(Diagnostic)]
     #[diag(fhir_analysis_fun_arg_count_mismatch, code = E0999)]
+    #[note]
     pub(super) struct FunArgCountMismatch {
         #[primary_span]
         #[label]
@@ -506,15 +623,30 @@ mod errors {
         #[label(fhir_analysis_expected_label)]
         expected_span: Span,
         expected_args: usize,
+        positions: String,
     }
 
     impl FunArgCountMismatch {
-        pub(super) fn new(decl: &fhir::FnDecl, expected_decl: &fhir::FnDecl) -> Self {
+        pub(super) fn new(
+            decl: &fhir::FnDecl,
+            expected_decl: &fhir::FnDecl,
+            arg_names: &[Ident],
+        ) -> Self {
+            let (min, max) = if decl.args.len() < expected_decl.args.len() {
+                (decl.args.len(), expected_decl.args.len())
+            } else {
+                (expected_decl.args.len(), decl.args.len())
+            };
+            let positions = (min..max)
+                .map(|i| super::arg_position(arg_names, i))
+                .format(", ")
+                .to_string();
             Self {
                 span: decl.span,
                 args: decl.args.len(),
                 expected_span: expected_decl.span,
                 expected_args: expected_decl.args.len(),
+                positions,
             }
         }
     }
@@ -41,6 +41,17 @@ pub struct ConvCtxt<'a, 'genv, 'tcx> {
 pub(crate) struct Env {
     layers: Vec<Layer>,
     early_bound: FxIndexMap<fhir::ParamId, (Symbol, rty::Sort)>,
+    /// The function pointer types we are currently inside of, innermost last.
+    fn_ptr_binders: Vec<FnPtrBinder>,
+}
+
+/// The binder introduced by a function pointer type, e.g., `for<'a> fn(&'a i32)`. Function pointer
+/// types can only bind late-bound regions. We collect them as we find them while converting the
+/// signature because the desugared version of the type doesn't have its bound generic params.
+struct FnPtrBinder {
+    /// The index of the (empty) [`Layer`] pushed for the binder
+    layer: usize,
+    regions: Vec<Option<rty::BoundRegionKind>>,
 }
 
 #[derive(Debug, Clone)]
@@ -734,6 +745,7 @@ impl<'a, 'genv, 'tcx> ConvCtxt<'a, 'genv, 'tcx> {
                     rty::Expr::unit(),
                 ))
             }
+            fhir::TyKind::BareFn(bare_fn) => self.conv_bare_fn(env, bare_fn),
             fhir::TyKind::Hole(fhir_id) => {
                 let ty = self
                     .wfckresults
@@ -755,6 +767,34 @@ impl<'a, 'genv, 'tcx> ConvCtxt<'a, 'genv, 'tcx> {
         }
     }
 
+    fn conv_bare_fn(&self, env: &mut Env, bare_fn: &fhir::BareFnTy) -> QueryResult<rty::Ty> {
+        env.push_layer(Layer::list(self, 0, &[], true)?);
+        env.fn_ptr_binders
+            .push(FnPtrBinder { layer: env.depth() - 1, regions: vec![] });
+
+        let args: Vec<_> = bare_fn
+            .inputs
+            .iter()
+            .map(|ty| self.conv_ty(env, ty))
+            .try_collect()?;
+        let output = fhir::FnOutput { params: &[], ret: *bare_fn.output, ensures: &[] };
+        let output = self.conv_fn_output(env, &output)?;
+
+        env.pop_layer();
+        let vars = env
+            .fn_ptr_binders
+            .pop()
+            .unwrap()
+            .regions
+            .into_iter()
+            .map(|kind| {
+                rty::BoundVariableKind::Region(kind.unwrap_or(rty::BoundRegionKind::BrAnon))
+            })
+            .collect();
+        let fn_sig = rty::PolyFnSig::new(rty::FnSig::new(List::empty(), args, output), vars);
+        Ok(rty::Ty::indexed(rty::BaseTy::FnPtr(fn_sig), rty::Expr::unit()))
+    }
+
     fn conv_base_ty(&self, env: &mut Env, bty: &fhir::BaseTy) -> QueryResult<rty::Ty> {
         match &bty.kind {
             fhir::BaseTyKind::Path(fhir::QPath::Resolved(self_ty, path)) => {
@@ -814,7 +854,7 @@ impl<'a, 'genv, 'tcx> ConvCtxt<'a, 'genv, 'tcx> {
         }
     }
 
-    fn conv_lifetime(&self, env: &Env, lft: fhir::Lifetime) -> rty::Region {
+    fn conv_lifetime(&self, env: &mut Env, lft: fhir::Lifetime) -> rty::Region {
         let res = match lft {
             fhir::Lifetime::Hole(fhir_id) => {
                 *self
@@ -834,10 +874,21 @@ impl<'a, 'genv, 'tcx> ConvCtxt<'a, 'genv, 'tcx> {
                 let name = lifetime_name(def_id.expect_local());
                 rty::ReEarlyBound(rty::EarlyParamRegion { def_id, index, name })
             }
-            ResolvedArg::LateBound(_, index, def_id) => {
-                let depth = env.depth().checked_sub(1).unwrap();
+            ResolvedArg::LateBound(debruijn, index, def_id) => {
                 let name = lifetime_name(def_id.expect_local());
                 let kind = rty::BoundRegionKind::BrNamed(def_id, name);
+                // Regions bound by an enclosing function pointer type are resolved to the layer of
+                // that type. Otherwise, the region is bound by the item's signature at the outermost
+                // layer.
+                let depth = env.depth().checked_sub(1).unwrap();
+                let depth = if let Some(binder) =
+                    env.fn_ptr_binders.iter_mut().rev().nth(debruijn.as_usize())
+                {
+                    binder.record_region(index, kind);
+                    depth - binder.layer
+                } else {
+                    depth
+                };
                 let var = BoundVar::from_u32(index);
                 let bound_region = rty::BoundRegion { var, kind };
                 rty::ReLateBound(rustc::ty::DebruijnIndex::from_usize(depth), bound_region)
@@ -1007,6 +1058,16 @@ impl<'a, 'genv, 'tcx> ConvCtxt<'a, 'genv, 'tcx> {
     }
 }
 
+impl FnPtrBinder {
+    fn record_region(&mut self, index: u32, kind: rty::BoundRegionKind) {
+        let index = index as usize;
+        if self.regions.len() <= index {
+            self.regions.resize(index + 1, None);
+        }
+        self.regions[index] = Some(kind);
+    }
+}
+
 impl Env {
     pub(crate) fn new(
         genv: GlobalEnv,
@@ -1020,7 +1081,7 @@ impl Env {
                 Ok((param.id, (param.name, sort)))
             })
             .try_collect()?;
-        Ok(Self { layers: vec![], early_bound })
+        Ok(Self { layers: vec![], early_bound, fn_ptr_binders: vec![] })
     }
 
     fn depth(&self) -> usize {
@@ -1081,6 +1142,7 @@ impl ConvCtxt<'_, '_, '_> {
                     ExprRes::NumConst(num) => {
                         rty::Expr::constant_at(rty::Constant::from(num), espan)
                     }
+                    ExprRes::Variant(def_id) => self.conv_variant(def_id, espan),
                     ExprRes::GlobalFunc(..) => {
                         span_bug!(var.span, "unexpected func in var position")
                     }
@@ -1154,6 +1216,15 @@ impl ConvCtxt<'_, '_, '_> {
         self.add_coercions(expr, func.fhir_id)
     }
 
+    /// The `i`-th variant of a reflected enum is the record `{ tag: i }`
+    fn conv_variant(&self, def_id: DefId, espan: Option<ESpan>) -> rty::Expr {
+        let tcx = self.genv.tcx();
+        let enum_def_id = tcx.parent(def_id);
+        let idx = tcx.adt_def(enum_def_id).variant_index_with_id(def_id);
+        let tag = rty::Expr::constant_at(rty::Constant::from(idx.as_usize()), espan);
+        rty::Expr::adt(enum_def_id, List::singleton(tag))
+    }
+
     fn conv_exprs(&self, env: &mut Env, exprs: &[fhir::Expr]) -> QueryResult<List<rty::Expr>> {
         exprs.iter().map(|e| self.conv_expr(env, e)).collect()
     }
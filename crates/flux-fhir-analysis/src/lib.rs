@@ -11,6 +11,7 @@ extern crate rustc_type_ir;
 mod annot_check;
 pub mod compare_impl_item;
 mod conv;
+mod unused;
 mod wf;
 
 use std::rc::Rc;
@@ -61,6 +62,9 @@ fn adt_sort_def_of(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<rty::AdtS
 
 fn spec_func_decls(genv: GlobalEnv) -> QueryResult<FxHashMap<Symbol, rty::SpecFuncDecl>> {
     let mut func_decls = FxHashMap::default();
+    for func in genv.cstore().func_decls() {
+        func_decls.insert(func.name, func.clone());
+    }
     for func in genv.map().spec_funcs() {
         func_decls.insert(func.name, conv::conv_func_decl(genv, func)?);
     }
@@ -77,7 +81,12 @@ fn spec_func_decls(genv: GlobalEnv) -> QueryResult<FxHashMap<Symbol, rty::SpecFu
 }
 
 fn spec_func_defns(genv: GlobalEnv) -> QueryResult<rty::SpecFuncDefns> {
+    // Definitions from upstream crates are already normalized, but they are needed to expand
+    // the ones in this crate using them.
     let mut defns = FxHashMap::default();
+    for defn in genv.cstore().func_defns() {
+        defns.insert(defn.name, defn.clone());
+    }
     for func in genv.map().spec_funcs() {
         let wfckresults = genv.check_wf(FluxLocalDefId::Flux(func.name))?;
         if let Some(defn) = conv::conv_defn(genv, func, &wfckresults)? {
@@ -410,7 +419,12 @@ fn check_wf<'genv>(
 pub fn check_crate_wf(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
     let mut errors = Errors::new(genv.sess());
 
-    let qualifiers = genv.map().qualifiers().map(|q| q.name).collect();
+    let qualifiers = genv
+        .map()
+        .qualifiers()
+        .map(|q| q.name)
+        .chain(genv.cstore().qualifiers().map(|q| q.name))
+        .collect();
 
     for def_id in genv.tcx().hir_crate_items(()).definitions() {
         if genv.ignored(def_id) == Ignored::Yes {
@@ -449,7 +463,13 @@ pub fn check_crate_wf(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
             .ok();
     }
 
-    errors.into_result()
+    // Uses in specs that failed to desugar or are ill-formed may be missed, so we only look for
+    // unused items if everything is well-formed
+    let result = errors.into_result();
+    if result.is_ok() {
+        unused::check_unused(genv);
+    }
+    result
 }
 
 fn normalize<T: TypeFoldable>(genv: GlobalEnv, t: T) -> QueryResult<T> {
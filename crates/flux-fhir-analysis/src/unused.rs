@@ -0,0 +1,143 @@
+//! Warnings for flux items that are declared but never used.
+//!
+//! We report local qualifiers that are not listed in any `#[flux::qualifiers(...)]` attribute, spec
+//! functions that are not mentioned in any spec, and type aliases annotated with `#[flux::alias]`
+//! that are not mentioned in any signature. Stale items are easy to miss because they don't cause
+//! errors, but they still have to be desugared and checked. Global qualifiers are used by every
+//! function in their scope, so they are never reported. Items marked as `pub` may be used by other
+//! crates and aren't reported either.
+use flux_middle::{
+    fhir::{self, visit::Visitor, ExprRes, Ignored},
+    global_env::GlobalEnv,
+};
+use rustc_hash::FxHashSet;
+use rustc_hir::{def::DefKind, def_id::DefId, OwnerId};
+use rustc_span::Symbol;
+
+pub(crate) fn check_unused(genv: GlobalEnv) {
+    let mut collector = UsageCollector::default();
+    for def_id in genv.tcx().hir_crate_items(()).definitions() {
+        if genv.ignored(def_id) == Ignored::Yes {
+            continue;
+        }
+        if matches!(
+            genv.def_kind(def_id),
+            DefKind::TyAlias { .. }
+                | DefKind::Struct
+                | DefKind::Enum
+                | DefKind::Fn
+                | DefKind::AssocFn
+                | DefKind::OpaqueTy
+                | DefKind::Trait
+                | DefKind::Impl { .. }
+        ) && let Ok(node) = genv.map().node(def_id)
+        {
+            collector.visit_node(&node);
+        }
+    }
+    for qualifier in genv.map().qualifiers() {
+        collector.visit_expr(&qualifier.expr);
+    }
+    for defn in genv.map().spec_funcs() {
+        if let Some(body) = &defn.body {
+            collector.visit_expr(body);
+        }
+    }
+
+    let specs = genv.collect_specs();
+    for qualifier in &specs.qualifs {
+        if !qualifier.global
+            && !qualifier.public
+            && !collector.qualifiers.contains(&qualifier.name.name)
+        {
+            genv.sess().emit_warn(errors::UnusedItem::new(
+                qualifier.name.span,
+                "qualifier",
+                qualifier.name.name,
+            ));
+        }
+    }
+    for defn in &specs.func_defs {
+        if !defn.public && !collector.funcs.contains(&defn.name.name) {
+            genv.sess().emit_warn(errors::UnusedItem::new(
+                defn.name.span,
+                "spec function",
+                defn.name.name,
+            ));
+        }
+    }
+    for def_id in genv.tcx().hir_crate_items(()).definitions() {
+        if let Some(Some(ty_alias)) = specs.ty_aliases.get(&OwnerId { def_id })
+            && genv.ignored(def_id) == Ignored::No
+            && !genv.tcx().visibility(def_id).is_public()
+            && !collector.aliases.contains(&def_id.to_def_id())
+        {
+            genv.sess().emit_warn(errors::UnusedItem::new(
+                ty_alias.ident.span,
+                "type alias",
+                ty_alias.ident.name,
+            ));
+        }
+    }
+}
+
+#[derive(Default)]
+struct UsageCollector {
+    qualifiers: FxHashSet<Symbol>,
+    funcs: FxHashSet<Symbol>,
+    aliases: FxHashSet<DefId>,
+}
+
+impl UsageCollector {
+    fn use_func(&mut self, res: ExprRes) {
+        if let ExprRes::GlobalFunc(_, name) = res {
+            self.funcs.insert(name);
+        }
+    }
+}
+
+impl Visitor for UsageCollector {
+    fn visit_fn_sig(&mut self, sig: &fhir::FnSig) {
+        self.qualifiers
+            .extend(sig.qualifiers.iter().map(|qual| qual.name));
+        fhir::visit::walk_fn_sig(self, sig);
+    }
+
+    fn visit_path(&mut self, path: &fhir::Path) {
+        if let fhir::Res::Def(DefKind::TyAlias { .. }, def_id) = path.res {
+            self.aliases.insert(def_id);
+        }
+        fhir::visit::walk_path(self, path);
+    }
+
+    fn visit_expr(&mut self, expr: &fhir::Expr) {
+        if let fhir::ExprKind::App(func, _) = expr.kind {
+            self.use_func(func.res);
+        }
+        fhir::visit::walk_expr(self, expr);
+    }
+
+    fn visit_path_expr(&mut self, path: &fhir::PathExpr) {
+        self.use_func(path.res);
+    }
+}
+
+mod errors {
+    use flux_macros::Diagnostic;
+    use rustc_span::{Span, Symbol};
+
+    #[derive(Diagnostic)]
+    #[diag(fhir_analysis_unused_item)]
+    pub(super) struct UnusedItem {
+        #[primary_span]
+        span: Span,
+        kind: &'static str,
+        name: Symbol,
+    }
+
+    impl UnusedItem {
+        pub(super) fn new(span: Span, kind: &'static str, name: Symbol) -> Self {
+            Self { span, kind, name }
+        }
+    }
+}
@@ -147,7 +147,7 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
     }
 
     pub(super) fn check_loc(&mut self, loc: &fhir::PathExpr) -> Result {
-        let found = self.synth_var(loc);
+        let found = self.synth_var(loc)?;
         if found == rty::Sort::Loc {
             Ok(())
         } else {
@@ -157,7 +157,7 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
 
     fn synth_expr(&mut self, expr: &fhir::Expr) -> Result<rty::Sort> {
         match &expr.kind {
-            fhir::ExprKind::Var(var, _) => Ok(self.synth_var(var)),
+            fhir::ExprKind::Var(var, _) => self.synth_var(var),
             fhir::ExprKind::Literal(lit) => Ok(synth_lit(*lit)),
             fhir::ExprKind::BinaryOp(op, e1, e2) => self.synth_binary_op(expr, *op, e1, e2),
             fhir::ExprKind::UnaryOp(op, e) => self.synth_unary_op(*op, e),
@@ -188,15 +188,22 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
         }
     }
 
-    fn synth_var(&mut self, path: &fhir::PathExpr) -> rty::Sort {
-        match path.res {
+    fn synth_var(&mut self, path: &fhir::PathExpr) -> Result<rty::Sort> {
+        let sort = match path.res {
             ExprRes::Param(_, id) => self.param_sort(id),
             ExprRes::Const(_) => rty::Sort::Int, // TODO: generalize const sorts
             ExprRes::NumConst(_) => rty::Sort::Int,
+            ExprRes::Variant(def_id) => {
+                let enum_def_id = self.genv.tcx().parent(def_id);
+                let sort_def = self.genv.adt_sort_def_of(enum_def_id).emit(&self.genv)?;
+                let args = sort_def.identity_args();
+                rty::Sort::App(rty::SortCtor::Adt(sort_def), args)
+            }
             ExprRes::GlobalFunc(_, _) => {
                 span_bug!(path.span, "unexpected func in var position")
             }
-        }
+        };
+        Ok(sort)
     }
 
     fn synth_binary_op(
@@ -6,10 +6,10 @@ use rustc_macros::{Decodable, Encodable};
 ///
 /// In the logic, we work with natural numbers so we could represent them with arbitrary precision
 /// integers. We instead take the simpler approach of using a fixed size representation that allows
-/// us to store any Rust literal, i.e., we can reprent both `i128::MIN` and `u128::MAX`. Since we
-/// never do arithmetic, this representation doesn't present any problems. We may choose to change
-/// the representation in the future (and use arbitrary precision integers) if this ever become a
-/// problem, e.g., if we want to do (precise) arithmetic during constant folding.
+/// us to store any Rust literal, i.e., we can reprent both `i128::MIN` and `u128::MAX`. We only do
+/// arithmetic during constant folding, where we give up if the result doesn't fit in an `i128`, so
+/// this representation doesn't present any problems. We may choose to change the representation in
+/// the future (and use arbitrary precision integers) if this ever become a problem.
 #[derive(Clone, Debug, Copy, PartialEq, Eq, Hash, Encodable, Decodable, PartialOrd, Ord)]
 pub struct BigInt {
     sign: Sign,
@@ -44,6 +44,29 @@ impl BigInt {
     pub fn uint_max(bit_width: u32) -> BigInt {
         (u128::MAX >> (128 - bit_width)).into()
     }
+
+    pub fn checked_add(self, other: BigInt) -> Option<BigInt> {
+        Some(self.to_i128()?.checked_add(other.to_i128()?)?.into())
+    }
+
+    pub fn checked_sub(self, other: BigInt) -> Option<BigInt> {
+        Some(self.to_i128()?.checked_sub(other.to_i128()?)?.into())
+    }
+
+    pub fn checked_mul(self, other: BigInt) -> Option<BigInt> {
+        Some(self.to_i128()?.checked_mul(other.to_i128()?)?.into())
+    }
+
+    pub fn checked_neg(self) -> Option<BigInt> {
+        Some(self.to_i128()?.checked_neg()?.into())
+    }
+
+    fn to_i128(self) -> Option<i128> {
+        match self.sign {
+            Sign::NonNegative => i128::try_from(self.val).ok(),
+            Sign::Negative => 0i128.checked_sub_unsigned(self.val),
+        }
+    }
 }
 
 impl From<usize> for BigInt {
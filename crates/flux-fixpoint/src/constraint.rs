@@ -542,6 +542,7 @@ impl Constant {
     pub const ZERO: Constant = Constant::Int(BigInt::ZERO);
     pub const ONE: Constant = Constant::Int(BigInt::ONE);
     pub const TRUE: Constant = Constant::Bool(true);
+    pub const FALSE: Constant = Constant::Bool(false);
 
     fn to_bool(self) -> Option<bool> {
         match self {
@@ -601,6 +602,28 @@ impl Constant {
         Some(Constant::Bool(n1 >= n2))
     }
 
+    /// Returns `None` if the constants are not integers or the result overflows an `i128`. The
+    /// same goes for [`Constant::sub`], [`Constant::mul`] and [`Constant::neg`].
+    pub fn add(&self, other: &Constant) -> Option<Constant> {
+        Some(Constant::Int(self.to_int()?.checked_add(other.to_int()?)?))
+    }
+
+    pub fn sub(&self, other: &Constant) -> Option<Constant> {
+        Some(Constant::Int(self.to_int()?.checked_sub(other.to_int()?)?))
+    }
+
+    pub fn mul(&self, other: &Constant) -> Option<Constant> {
+        Some(Constant::Int(self.to_int()?.checked_mul(other.to_int()?)?))
+    }
+
+    pub fn neg(&self) -> Option<Constant> {
+        Some(Constant::Int(self.to_int()?.checked_neg()?))
+    }
+
+    pub fn not(&self) -> Option<Constant> {
+        Some(Constant::Bool(!self.to_bool()?))
+    }
+
     /// See [`BigInt::int_min`]
     pub fn int_min(bit_width: u32) -> Constant {
         Constant::Int(BigInt::int_min(bit_width))
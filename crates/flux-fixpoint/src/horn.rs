@@ -0,0 +1,311 @@
+//! Encoding of a [`Task`] as constrained Horn clauses in SMT-LIB2 (see [`Task::to_smtlib`]).
+//!
+//! The output is meant to inspect constraints or replay them with external Horn solvers (e.g.,
+//! `z3` with the Spacer engine). Kvars are declared as uninterpreted relations and every head in
+//! the constraint becomes a clause universally quantified over the binders in scope. A head that
+//! is not a kvar is encoded as a query, i.e., a clause with head `false` whose body includes the
+//! negation of the predicate.
+//!
+//! Sets and maps are encoded as arrays and the theory functions of fixpoint that have a direct
+//! counterpart in SMT-LIB are translated. The remaining ones (e.g., `Set_empty`) are emitted with
+//! their fixpoint names and must be defined by hand to replay the script. Clauses binding variables
+//! of a sort that cannot be encoded (i.e., functions) are replaced by a comment.
+
+use std::fmt::Write as _;
+
+use itertools::Itertools;
+
+use crate::{
+    BinOp, BinRel, Constant, Constraint, DataDecl, Expr, Pred, Sort, SortCtor, Task, Types,
+};
+
+pub(crate) fn encode<T: Types>(task: &Task<T>) -> String {
+    let mut encoder = Encoder { text: String::new(), binders: vec![], hyps: vec![] };
+    encoder.line(format_args!("(set-logic HORN)"));
+    for comment in &task.comments {
+        encoder.line(format_args!("; {comment}"));
+    }
+    for data_decl in &task.data_decls {
+        encoder.data_decl(data_decl);
+    }
+    for cinfo in &task.constants {
+        match fun_sort(&cinfo.sort) {
+            Some((inputs, output)) => {
+                encoder.line(format_args!(
+                    "(declare-fun {} ({}) {output})",
+                    cinfo.name,
+                    inputs.join(" ")
+                ));
+            }
+            None => encoder.line(format_args!("; constant {} of sort {}", cinfo.name, cinfo.sort)),
+        }
+    }
+    for kvar in &task.kvars {
+        let sorts = kvar.sorts.iter().map(sort).collect::<Option<Vec<_>>>();
+        match sorts {
+            Some(sorts) => {
+                encoder.line(format_args!(
+                    "(declare-fun ${} ({}) Bool) ; {}",
+                    kvar.kvid,
+                    sorts.join(" "),
+                    kvar.comment
+                ));
+            }
+            None => encoder.line(format_args!("; {kvar}")),
+        }
+    }
+    encoder.constraint(&task.constraint);
+    encoder.line(format_args!("(check-sat)"));
+    encoder.text
+}
+
+struct Encoder {
+    text: String,
+    /// Names and sorts of the binders in scope, or `None` if the sort of a binder can't be encoded
+    binders: Vec<Option<(String, String)>>,
+    /// Hypotheses in scope
+    hyps: Vec<String>,
+}
+
+impl Encoder {
+    fn line(&mut self, args: std::fmt::Arguments) {
+        writeln!(self.text, "{args}").unwrap();
+    }
+
+    fn data_decl<T: Types>(&mut self, data_decl: &DataDecl<T>) {
+        let ctors = data_decl
+            .ctors
+            .iter()
+            .map(|ctor| {
+                let fields = ctor
+                    .fields
+                    .iter()
+                    .map(|field| Some(format!("({} {})", field.name, sort(&field.sort)?)))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(format!("({} {})", ctor.name, fields.join(" ")))
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some(ctors) = ctors else {
+            self.line(format_args!("; {data_decl}"));
+            return;
+        };
+        let ctors = ctors.join(" ");
+        let name = &data_decl.name;
+        let vars = data_decl.vars;
+        if vars == 0 {
+            self.line(format_args!("(declare-datatypes (({name} 0)) (({ctors})))"));
+        } else {
+            let params = (0..vars).map(|i| format!("T{i}")).join(" ");
+            self.line(format_args!(
+                "(declare-datatypes (({name} {vars})) ((par ({params}) ({ctors}))))"
+            ));
+        }
+    }
+
+    fn constraint<T: Types>(&mut self, cstr: &Constraint<T>) {
+        match cstr {
+            Constraint::Conj(cstrs) => {
+                for cstr in cstrs {
+                    self.constraint(cstr);
+                }
+            }
+            Constraint::ForAll(bind, head) => {
+                self.binders
+                    .push(sort(&bind.sort).map(|sort| (bind.name.to_string(), sort)));
+                let n = self.hyps.len();
+                self.hyp(&bind.pred);
+                self.constraint(head);
+                self.hyps.truncate(n);
+                self.binders.pop();
+            }
+            Constraint::Pred(pred, tag) => self.head(pred, tag.as_ref()),
+        }
+    }
+
+    fn hyp<T: Types>(&mut self, pred: &Pred<T>) {
+        match pred {
+            Pred::And(preds) => {
+                for pred in preds {
+                    self.hyp(pred);
+                }
+            }
+            _ if pred.is_trivially_true() => {}
+            _ => {
+                let pred = self.pred(pred);
+                self.hyps.push(pred);
+            }
+        }
+    }
+
+    fn head<T: Types>(&mut self, pred: &Pred<T>, tag: Option<&T::Tag>) {
+        match pred {
+            Pred::And(preds) => {
+                for pred in preds {
+                    self.head(pred, tag);
+                }
+            }
+            Pred::KVar(..) => {
+                let body = nary("and", "true", &self.hyps);
+                let head = self.pred(pred);
+                self.clause(body, head, None);
+            }
+            Pred::Expr(expr) => {
+                if pred.is_trivially_true() {
+                    return;
+                }
+                let negated = format!("(not {})", self.expr(expr));
+                let body = nary("and", "true", self.hyps.iter().chain([&negated]));
+                self.clause(body, "false".to_string(), tag.map(ToString::to_string));
+            }
+        }
+    }
+
+    fn clause(&mut self, body: String, head: String, tag: Option<String>) {
+        if let Some(tag) = tag {
+            self.line(format_args!("; tag {tag}"));
+        }
+        let Some(binders) = self.binders.iter().cloned().collect::<Option<Vec<_>>>() else {
+            self.line(format_args!(
+                "; clause omitted: a variable in scope has an unsupported sort"
+            ));
+            return;
+        };
+        let imp = format!("(=> {body} {head})");
+        if binders.is_empty() {
+            self.line(format_args!("(assert {imp})"));
+        } else {
+            let binders = binders
+                .iter()
+                .format_with(" ", |(name, sort), f| f(&format_args!("({name} {sort})")));
+            self.line(format_args!("(assert (forall ({binders}) {imp}))"));
+        }
+    }
+
+    fn pred<T: Types>(&self, pred: &Pred<T>) -> String {
+        match pred {
+            Pred::And(preds) => nary("and", "true", preds.iter().map(|pred| self.pred(pred))),
+            Pred::KVar(kvid, args) => {
+                if args.is_empty() {
+                    format!("${kvid}")
+                } else {
+                    format!("(${kvid} {})", args.iter().join(" "))
+                }
+            }
+            Pred::Expr(expr) => self.expr(expr),
+        }
+    }
+
+    fn expr<T: Types>(&self, expr: &Expr<T>) -> String {
+        match expr {
+            Expr::Constant(c) => constant(c),
+            Expr::Var(x) => x.to_string(),
+            Expr::App(func, args) => app(&func.to_string(), self.exprs(args)),
+            Expr::Neg(e) => format!("(- {})", self.expr(e)),
+            Expr::BinaryOp(op, box [e1, e2]) => {
+                let op = match op {
+                    BinOp::Add => "+",
+                    BinOp::Sub => "-",
+                    BinOp::Mul => "*",
+                    // The encoding is not sort-directed, so we assume integer division
+                    BinOp::Div => "div",
+                    BinOp::Mod => "mod",
+                };
+                format!("({op} {} {})", self.expr(e1), self.expr(e2))
+            }
+            Expr::IfThenElse(box [p, e1, e2]) => {
+                format!("(ite {} {} {})", self.expr(p), self.expr(e1), self.expr(e2))
+            }
+            Expr::And(exprs) => nary("and", "true", self.exprs(exprs)),
+            Expr::Or(exprs) => nary("or", "false", self.exprs(exprs)),
+            Expr::Not(e) => format!("(not {})", self.expr(e)),
+            Expr::Imp(box [e1, e2]) => format!("(=> {} {})", self.expr(e1), self.expr(e2)),
+            Expr::Iff(box [e1, e2]) => format!("(= {} {})", self.expr(e1), self.expr(e2)),
+            Expr::Atom(rel, box [e1, e2]) => {
+                let (e1, e2) = (self.expr(e1), self.expr(e2));
+                match rel {
+                    BinRel::Eq => format!("(= {e1} {e2})"),
+                    BinRel::Ne => format!("(not (= {e1} {e2}))"),
+                    BinRel::Gt => format!("(> {e1} {e2})"),
+                    BinRel::Ge => format!("(>= {e1} {e2})"),
+                    BinRel::Lt => format!("(< {e1} {e2})"),
+                    BinRel::Le => format!("(<= {e1} {e2})"),
+                }
+            }
+        }
+    }
+
+    fn exprs<T: Types>(&self, exprs: &[Expr<T>]) -> Vec<String> {
+        exprs.iter().map(|e| self.expr(e)).collect()
+    }
+}
+
+/// Translates applications of the theory functions of fixpoint with a counterpart in SMT-LIB
+fn app(func: &str, args: Vec<String>) -> String {
+    match (func, &args[..]) {
+        ("Set_mem", [elem, set]) => format!("(select {set} {elem})"),
+        ("Map_select", [map, key]) => format!("(select {map} {key})"),
+        ("Map_store", [map, key, val]) => format!("(store {map} {key} {val})"),
+        (_, []) => func.to_string(),
+        _ => format!("({func} {})", args.join(" ")),
+    }
+}
+
+fn nary(op: &str, unit: &str, args: impl IntoIterator<Item = impl ToString>) -> String {
+    let args = args.into_iter().map(|arg| arg.to_string()).collect_vec();
+    match &args[..] {
+        [] => unit.to_string(),
+        [arg] => arg.clone(),
+        _ => format!("({op} {})", args.join(" ")),
+    }
+}
+
+fn constant(c: &Constant) -> String {
+    let (n, suffix) = match c {
+        Constant::Int(n) => (n.to_string(), ""),
+        Constant::Real(r) => (r.to_string(), ".0"),
+        Constant::Bool(b) => return b.to_string(),
+    };
+    match n.strip_prefix('-') {
+        Some(abs) => format!("(- {abs}{suffix})"),
+        None => format!("{n}{suffix}"),
+    }
+}
+
+fn sort<T: Types>(sort: &Sort<T>) -> Option<String> {
+    let s = match sort {
+        Sort::Int => "Int".to_string(),
+        Sort::Bool => "Bool".to_string(),
+        Sort::Real => "Real".to_string(),
+        Sort::BitVec(size) => format!("(_ BitVec {size})"),
+        Sort::Var(i) => format!("T{i}"),
+        Sort::App(SortCtor::Set, args) => {
+            let [elem] = &args[..] else { return None };
+            format!("(Array {} Bool)", self::sort(elem)?)
+        }
+        Sort::App(SortCtor::Map, args) => {
+            let [key, val] = &args[..] else { return None };
+            format!("(Array {} {})", self::sort(key)?, self::sort(val)?)
+        }
+        Sort::App(SortCtor::Data(name), args) => {
+            if args.is_empty() {
+                name.to_string()
+            } else {
+                let args = args.iter().map(self::sort).collect::<Option<Vec<_>>>()?;
+                format!("({name} {})", args.join(" "))
+            }
+        }
+        Sort::Func(..) | Sort::Abs(..) => return None,
+    };
+    Some(s)
+}
+
+/// Splits a monomorphic function sort into the sorts of its inputs and output. Other sorts are
+/// treated as functions without inputs.
+fn fun_sort<T: Types>(mut curr: &Sort<T>) -> Option<(Vec<String>, String)> {
+    let mut inputs = vec![];
+    while let Sort::Func(box [input, output]) = curr {
+        inputs.push(sort(input)?);
+        curr = output;
+    }
+    Some((inputs, sort(curr)?))
+}
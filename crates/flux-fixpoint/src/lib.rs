@@ -6,14 +6,20 @@ extern crate rustc_span;
 
 pub mod big_int;
 mod constraint;
+mod horn;
+mod smt;
 
 use std::{
     collections::hash_map::DefaultHasher,
     fmt::{self, Write as FmtWrite},
     hash::{Hash, Hasher},
-    io::{self, BufWriter, Write as IOWrite},
-    process::{Command, Stdio},
+    io::{self, BufWriter, Read, Write as IOWrite},
+    num::NonZeroUsize,
+    process::{Child, Command, Stdio},
     str::FromStr,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
 };
 
 pub use constraint::{
@@ -22,21 +28,23 @@ pub use constraint::{
 };
 use derive_where::derive_where;
 use flux_common::{cache::QueryCache, format::PadAdapter};
-use flux_config as config;
+use flux_config::{self as config, SmtSolver, SolverArgs};
 use itertools::Itertools;
 use serde::{de, Deserialize};
 
 use crate::constraint::DEFAULT_QUALIFIERS;
 
-pub trait Symbol: fmt::Display + Hash + Clone {}
+/// Symbols must be [`Send`] and [`Sync`] because tasks are encoded and checked in background
+/// threads (see [`TaskPool`]).
+pub trait Symbol: fmt::Display + Hash + Clone + Send + Sync {}
 
-impl<T: fmt::Display + Hash + Clone> Symbol for T {}
+impl<T: fmt::Display + Hash + Clone + Send + Sync> Symbol for T {}
 
 pub trait Types {
     type Sort: Symbol;
     type KVar: Symbol;
     type Var: Symbol;
-    type Tag: fmt::Display + Hash + FromStr;
+    type Tag: fmt::Display + Hash + FromStr + Send + Sync;
 }
 
 #[macro_export]
@@ -96,6 +104,17 @@ pub struct Task<T: Types> {
     pub constraint: Constraint<T>,
     pub qualifiers: Vec<Qualifier<T>>,
     pub scrape_quals: bool,
+    pub solver: SmtSolver,
+    /// Only passed to the solver when checking the task directly with it (see [`smt`])
+    pub solver_args: SolverArgs,
+    /// Whether to check the task directly with the solver if it has no kvars (see [`smt`]). Both
+    /// backends agree on the result, so it's not hashed.
+    #[derive_where(skip)]
+    pub smt_backend: bool,
+    /// The solver is killed if it doesn't finish within this time. It doesn't change the result
+    /// of a successful run, so it's not hashed.
+    #[derive_where(skip)]
+    pub timeout: Option<Duration>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -106,6 +125,23 @@ pub enum FixpointResult<Tag> {
     Crash(CrashInfo),
 }
 
+/// The result of checking a [`Task`] together with the solution found for its kvars, if any
+#[derive(Debug)]
+pub struct Response<Tag> {
+    pub result: FixpointResult<Tag>,
+    /// Only reported by fixpoint (never by the SMT backend or the cache) and only if the task is
+    /// safe, see [`config::show_invariants`] and [`config::suggest_sigs`].
+    pub solution: Vec<KVarBind>,
+}
+
+/// The predicate fixpoint inferred for a kvar, written in fixpoint's syntax over the parameters
+/// of the kvar.
+#[derive(Deserialize, Debug, Clone)]
+pub struct KVarBind {
+    pub kvar: String,
+    pub val: String,
+}
+
 #[derive(Debug)]
 pub struct Error<Tag> {
     pub id: i32,
@@ -143,54 +179,58 @@ impl<T: Types> Task<T> {
     pub fn check_with_cache(
         &self,
         key: String,
-        cache: &mut QueryCache,
-    ) -> io::Result<FixpointResult<T::Tag>> {
+        cache: &QueryCache,
+    ) -> io::Result<Response<T::Tag>> {
         let hash = self.hash_with_default();
+        // Cached results don't include the solution of the kvars
+        let use_cache =
+            config::is_cache_enabled() && !config::show_invariants() && !config::suggest_sigs();
 
-        if config::is_cache_enabled() && cache.is_safe(&key, hash) {
-            return Ok(FixpointResult::Safe(Default::default()));
+        if use_cache && cache.is_safe(&key, hash) {
+            return Ok(Response::without_solution(FixpointResult::Safe(Default::default())));
         }
 
-        let result = self.check();
-
-        if config::is_cache_enabled() {
-            if let Ok(FixpointResult::Safe(_)) = result {
-                cache.insert(key, hash);
+        if self.smt_backend {
+            if let Some(result) = smt::check(self)? {
+                if let FixpointResult::Safe(_) = result {
+                    cache.insert(key, hash);
+                }
+                return Ok(Response::without_solution(result));
             }
         }
-        result
-    }
 
-    fn check(&self) -> io::Result<FixpointResult<T::Tag>> {
-        let mut child = Command::new("fixpoint")
-            .arg("-q")
-            .arg("--stdin")
-            .arg("--json")
-            .arg("--nosmthorn")
-            .arg("--allowho")
-            .arg("--allowhoqs")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::null())
-            .spawn()
-            .unwrap();
-        let mut stdin = None;
-        std::mem::swap(&mut stdin, &mut child.stdin);
-        {
-            let mut w = BufWriter::new(stdin.unwrap());
-            writeln!(w, "{self}")?;
+        let input = self.to_string();
+
+        if !use_cache {
+            return Ok(parse_response(&run_fixpoint(&input, self.timeout)?)?);
         }
-        let out = child.wait_with_output()?;
 
-        let result = serde_json::from_slice(&out.stdout)?;
+        // Results are stored on disk keyed by the hash of the encoded constraint. In contrast to
+        // the table of safe queries, this survives changes to the item's path and is shared by all
+        // items producing the same constraint.
+        let stable_hash = stable_hash(&input);
+        if let Some(output) = cache.get_result(stable_hash) {
+            if let Ok(response) = parse_response(&output) {
+                return Ok(response);
+            }
+        }
 
-        Ok(result)
+        let output = run_fixpoint(&input, self.timeout)?;
+        let response = parse_response(&output)?;
+        match response.result {
+            FixpointResult::Safe(_) => {
+                cache.insert(key, hash);
+                cache.insert_result(stable_hash, &output);
+            }
+            FixpointResult::Unsafe(..) => cache.insert_result(stable_hash, &output),
+            FixpointResult::Crash(_) => {}
+        }
+        Ok(response)
     }
-}
 
-impl<T: Types> KVar<T> {
-    pub fn new(kvid: T::KVar, sorts: Vec<Sort<T>>, comment: String) -> Self {
-        Self { kvid, sorts, comment }
+    /// Encodes the task as constrained Horn clauses in SMT-LIB2 (see [`horn`]).
+    pub fn to_smtlib(&self) -> String {
+        horn::encode(self)
     }
 }
 
@@ -199,6 +239,9 @@ impl<T: Types> fmt::Display for Task<T> {
         if self.scrape_quals {
             writeln!(f, "(fixpoint \"--scrape=both\")")?;
         }
+        if self.solver != SmtSolver::Z3 {
+            writeln!(f, "(fixpoint \"--solver={}\")", self.solver.name())?;
+        }
         for line in &self.comments {
             writeln!(f, "// {line}")?;
         }
@@ -231,6 +274,188 @@ impl<T: Types> fmt::Display for Task<T> {
     }
 }
 
+impl<Tag> Response<Tag> {
+    fn without_solution(result: FixpointResult<Tag>) -> Self {
+        Response { result, solution: vec![] }
+    }
+}
+
+/// Parses the output of fixpoint. Versions of fixpoint that report the solution of the kvars wrap
+/// the result in an object with a `status` and a `solution` field, older versions output the
+/// result alone.
+fn parse_response<Tag: FromStr>(output: &[u8]) -> serde_json::Result<Response<Tag>> {
+    let mut value: serde_json::Value = serde_json::from_slice(output)?;
+    let solution = match value.get_mut("solution") {
+        Some(solution) => serde_json::from_value(solution.take())?,
+        None => vec![],
+    };
+    let status = if value.get("status").is_some() { value["status"].take() } else { value };
+    Ok(Response { result: serde_json::from_value(status)?, solution })
+}
+
+impl<T: Types> KVar<T> {
+    pub fn new(kvid: T::KVar, sorts: Vec<Sort<T>>, comment: String) -> Self {
+        Self { kvid, sorts, comment }
+    }
+}
+
+/// A task that finished checking together with its index, its result and the time it took
+type Finished<Tag> = (usize, io::Result<Response<Tag>>, Duration);
+
+/// A pool of threads checking tasks in the background, so the tasks of some items can be solved
+/// while the tasks of others are still being generated. At most [`config::solver_jobs`] tasks are
+/// checked at the same time. Tasks are handed to threads one at a time because the time fixpoint
+/// takes to solve them varies widely. A task is also encoded in fixpoint's format by the thread
+/// checking it, so the tasks of different items are encoded in parallel. The encoding of a single
+/// task is sequential, but it doesn't depend on the thread because kvars and variables are
+/// numbered when the task is created.
+pub struct TaskPool<T: Types> {
+    tasks: mpsc::Sender<(usize, String, Task<T>)>,
+    results: mpsc::Receiver<Finished<T::Tag>>,
+    /// Number of tasks submitted so far, i.e., the index of the next task
+    submitted: usize,
+    /// Number of submitted tasks whose result hasn't been received yet
+    pending: usize,
+}
+
+impl<T: Types> TaskPool<T>
+where
+    Task<T>: Send,
+{
+    /// Spawns the threads of the pool in `scope`. The threads finish when the pool is dropped,
+    /// which must happen before the end of `scope`.
+    pub fn new<'scope, 'env>(
+        scope: &'scope thread::Scope<'scope, 'env>,
+        cache: &'env QueryCache,
+    ) -> Self
+    where
+        T: 'scope,
+    {
+        let jobs = match config::solver_jobs() {
+            0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            jobs => jobs,
+        };
+        let (tasks, task_receiver) = mpsc::channel::<(usize, String, Task<T>)>();
+        let (result_sender, results) = mpsc::channel();
+        let task_receiver = Arc::new(Mutex::new(task_receiver));
+        for _ in 0..jobs {
+            let task_receiver = Arc::clone(&task_receiver);
+            let result_sender = result_sender.clone();
+            scope.spawn(move || {
+                loop {
+                    let Ok((idx, key, task)) = task_receiver.lock().unwrap().recv() else { break };
+                    let start = Instant::now();
+                    let result = task.check_with_cache(key, cache);
+                    if result_sender.send((idx, result, start.elapsed())).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        TaskPool { tasks, results, submitted: 0, pending: 0 }
+    }
+
+    /// Queues `task` to be checked by the next available thread and returns its index. Tasks are
+    /// numbered in the order they are submitted.
+    pub fn submit(&mut self, key: String, task: Task<T>) -> usize {
+        let idx = self.submitted;
+        self.submitted += 1;
+        self.pending += 1;
+        // The threads only stop when the pool is dropped
+        self.tasks.send((idx, key, task)).unwrap();
+        idx
+    }
+
+    /// Calls `on_result` with the index of each task that finished since the last call, its result
+    /// and the time it took to check it. If `wait` is true, this blocks until all the submitted
+    /// tasks are finished.
+    pub fn poll(
+        &mut self,
+        wait: bool,
+        mut on_result: impl FnMut(usize, io::Result<Response<T::Tag>>, Duration),
+    ) {
+        while self.pending > 0 {
+            let finished =
+                if wait { self.results.recv().ok() } else { self.results.try_recv().ok() };
+            let Some((idx, result, time)) = finished else { break };
+            self.pending -= 1;
+            on_result(idx, result, time);
+        }
+    }
+}
+
+fn run_fixpoint(input: &str, timeout: Option<Duration>) -> io::Result<Vec<u8>> {
+    let mut child = Command::new(config::fixpoint_path())
+        .arg("-q")
+        .arg("--stdin")
+        .arg("--json")
+        .arg("--nosmthorn")
+        .arg("--allowho")
+        .arg("--allowhoqs")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| {
+            let path = config::fixpoint_path();
+            io::Error::new(err.kind(), format!("cannot execute `{}`: {err}", path.display()))
+        })?;
+    let mut stdin = None;
+    std::mem::swap(&mut stdin, &mut child.stdin);
+    {
+        let mut w = BufWriter::new(stdin.unwrap());
+        writeln!(w, "{input}")?;
+    }
+    wait_with_timeout(child, timeout)
+}
+
+/// Waits for `child` to finish and returns its standard output. If it doesn't finish within
+/// `timeout`, the child is killed and an error of kind [`io::ErrorKind::TimedOut`] is returned.
+pub(crate) fn wait_with_timeout(
+    mut child: Child,
+    timeout: Option<Duration>,
+) -> io::Result<Vec<u8>> {
+    let Some(timeout) = timeout else { return Ok(child.wait_with_output()?.stdout) };
+
+    // The output is read in a separate thread, otherwise the child could block writing to a full
+    // pipe and never finish.
+    let mut stdout = child.stdout.take().unwrap();
+    let reader = thread::spawn(move || {
+        let mut buf = vec![];
+        stdout.read_to_end(&mut buf).map(|_| buf)
+    });
+
+    let deadline = Instant::now() + timeout;
+    while child.try_wait()?.is_none() {
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("the solver didn't finish within {}s", timeout.as_secs()),
+            ));
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    reader.join().unwrap()
+}
+
+/// A hash of an encoded task that doesn't depend on the comments in it, which mention spans and
+/// other details that don't affect the result.
+fn stable_hash(input: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for line in input.lines() {
+        let line = line
+            .split_once("//")
+            .map_or(line, |(code, _)| code)
+            .trim_end();
+        if !line.is_empty() {
+            line.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
 impl<T: Types> fmt::Display for KVar<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
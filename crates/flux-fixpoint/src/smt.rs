@@ -0,0 +1,290 @@
+//! A backend that checks constraints without kvars directly with an SMT solver (`z3` or `cvc5`).
+//!
+//! A constraint without kvars is valid iff every head is implied by the hypotheses in scope, so
+//! there's nothing to infer and we don't need fixpoint. The constraint is encoded as a single
+//! incremental SMT-LIB script that follows the structure of the constraint: each binder opens a
+//! scope declaring the variable and asserting its predicate, and each head asserts its negation
+//! in a nested scope followed by a `(check-sat)`. A head is valid iff the solver answers `unsat`.
+//!
+//! Only a subset of the constraints flux generates can be encoded: variables must be integers or
+//! booleans and functions must be monomorphic functions between them. [`check`] returns `None` for
+//! tasks outside this subset, which must be checked with fixpoint.
+
+use std::{
+    collections::HashSet,
+    fmt::Write as _,
+    io::{self, Write as _},
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use flux_config::{SmtSolver, SolverArgs};
+
+use crate::{
+    wait_with_timeout, BinOp, BinRel, Constant, Constraint, Error, Expr, FixpointResult, Pred,
+    Sort, Stats, Task, Types,
+};
+
+/// Checks `task` with the SMT solver, or returns `None` if the task cannot be encoded.
+pub(crate) fn check<T: Types>(task: &Task<T>) -> io::Result<Option<FixpointResult<T::Tag>>> {
+    let Some(script) = Script::encode(task) else { return Ok(None) };
+    let output = run_solver(task.solver, task.solver_args, task.timeout, &script.text)?;
+    let mut answers = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+
+    let mut errors = vec![];
+    for (id, tag) in script.heads.iter().enumerate() {
+        match answers.next() {
+            Some("unsat") => {}
+            Some("sat" | "unknown") => {
+                let Ok(tag) = tag.parse() else { return Ok(None) };
+                errors.push(Error { id: id as i32, tag });
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("unexpected output from the SMT solver: {output}"),
+                ))
+            }
+        }
+    }
+
+    let num_chck = script.heads.len() as i32;
+    let stats = Stats {
+        num_cstr: num_chck,
+        num_iter: 0,
+        num_chck,
+        num_vald: num_chck - errors.len() as i32,
+    };
+    if errors.is_empty() {
+        Ok(Some(FixpointResult::Safe(stats)))
+    } else {
+        Ok(Some(FixpointResult::Unsafe(stats, errors)))
+    }
+}
+
+fn run_solver(
+    solver: SmtSolver,
+    args: SolverArgs,
+    timeout: Option<Duration>,
+    script: &str,
+) -> io::Result<String> {
+    let mut cmd = Command::new(solver.name());
+    match solver {
+        SmtSolver::Z3 => cmd.arg("-in"),
+        SmtSolver::Cvc5 => cmd.arg("--incremental").arg("--lang=smt2"),
+    };
+    let mut child = cmd
+        .args(args.iter())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    // The solver answers each `(check-sat)` as soon as it reads it, so the script is written in a
+    // separate thread. Otherwise we could block writing to a full pipe while the solver blocks
+    // writing its answers to another.
+    thread::scope(|s| {
+        let writer = s.spawn(move || stdin.write_all(script.as_bytes()));
+        let out = wait_with_timeout(child, timeout)?;
+        writer.join().unwrap()?;
+        Ok(String::from_utf8_lossy(&out).into_owned())
+    })
+}
+
+struct Script {
+    text: String,
+    /// The tags of the heads in the order they are checked in the script
+    heads: Vec<String>,
+}
+
+impl Script {
+    fn encode<T: Types>(task: &Task<T>) -> Option<Script> {
+        let mut encoder = Encoder { text: String::new(), heads: vec![], scope: HashSet::new() };
+        encoder.line(format_args!("(set-logic ALL)"));
+        for cinfo in &task.constants {
+            // Constants of unsupported sorts are skipped. We give up if they are used.
+            if let Some((inputs, output)) = fun_sort(&cinfo.sort) {
+                let name = cinfo.name.to_string();
+                encoder.line(format_args!("(declare-fun {name} ({}) {output})", inputs.join(" ")));
+                encoder.scope.insert(name);
+            }
+        }
+        encoder.constraint(&task.constraint)?;
+        Some(Script { text: encoder.text, heads: encoder.heads })
+    }
+}
+
+struct Encoder {
+    text: String,
+    heads: Vec<String>,
+    /// Names of the variables and functions declared in the current scope
+    scope: HashSet<String>,
+}
+
+impl Encoder {
+    fn line(&mut self, args: std::fmt::Arguments) {
+        writeln!(self.text, "{args}").unwrap();
+    }
+
+    fn constraint<T: Types>(&mut self, cstr: &Constraint<T>) -> Option<()> {
+        match cstr {
+            Constraint::Conj(cstrs) => {
+                for cstr in cstrs {
+                    self.constraint(cstr)?;
+                }
+            }
+            Constraint::ForAll(bind, head) => {
+                let name = bind.name.to_string();
+                let sort = sort(&bind.sort)?;
+                self.line(format_args!("(push 1)"));
+                self.line(format_args!("(declare-const {name} {sort})"));
+                let fresh = self.scope.insert(name.clone());
+                let pred = self.pred(&bind.pred)?;
+                self.line(format_args!("(assert {pred})"));
+                self.constraint(head)?;
+                self.line(format_args!("(pop 1)"));
+                if fresh {
+                    self.scope.remove(&name);
+                }
+            }
+            Constraint::Pred(pred, tag) => {
+                self.head(pred, tag.as_ref())?;
+            }
+        }
+        Some(())
+    }
+
+    fn head<T: Types>(&mut self, pred: &Pred<T>, tag: Option<&T::Tag>) -> Option<()> {
+        match pred {
+            Pred::And(preds) => {
+                for pred in preds {
+                    self.head(pred, tag)?;
+                }
+            }
+            Pred::KVar(..) => return None,
+            Pred::Expr(expr) => {
+                if pred.is_trivially_true() {
+                    return Some(());
+                }
+                let expr = self.expr(expr)?;
+                self.heads.push(tag?.to_string());
+                self.line(format_args!("(push 1)"));
+                self.line(format_args!("(assert (not {expr}))"));
+                self.line(format_args!("(check-sat)"));
+                self.line(format_args!("(pop 1)"));
+            }
+        }
+        Some(())
+    }
+
+    fn pred<T: Types>(&self, pred: &Pred<T>) -> Option<String> {
+        match pred {
+            Pred::And(preds) => {
+                let preds = preds
+                    .iter()
+                    .map(|pred| self.pred(pred))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(nary("and", "true", preds))
+            }
+            Pred::KVar(..) => None,
+            Pred::Expr(expr) => self.expr(expr),
+        }
+    }
+
+    fn expr<T: Types>(&self, expr: &Expr<T>) -> Option<String> {
+        let s = match expr {
+            Expr::Constant(c) => constant(c)?,
+            Expr::Var(x) => self.var(x)?,
+            Expr::App(func, args) => {
+                let func = self.var(func)?;
+                format!("({func} {})", self.exprs(args)?.join(" "))
+            }
+            Expr::Neg(e) => format!("(- {})", self.expr(e)?),
+            Expr::BinaryOp(op, box [e1, e2]) => {
+                let op = match op {
+                    BinOp::Add => "+",
+                    BinOp::Sub => "-",
+                    BinOp::Mul => "*",
+                    // Reals are not supported, so this is always integer division
+                    BinOp::Div => "div",
+                    BinOp::Mod => "mod",
+                };
+                format!("({op} {} {})", self.expr(e1)?, self.expr(e2)?)
+            }
+            Expr::IfThenElse(box [p, e1, e2]) => {
+                format!("(ite {} {} {})", self.expr(p)?, self.expr(e1)?, self.expr(e2)?)
+            }
+            Expr::And(exprs) => nary("and", "true", self.exprs(exprs)?),
+            Expr::Or(exprs) => nary("or", "false", self.exprs(exprs)?),
+            Expr::Not(e) => format!("(not {})", self.expr(e)?),
+            Expr::Imp(box [e1, e2]) => format!("(=> {} {})", self.expr(e1)?, self.expr(e2)?),
+            Expr::Iff(box [e1, e2]) => format!("(= {} {})", self.expr(e1)?, self.expr(e2)?),
+            Expr::Atom(rel, box [e1, e2]) => {
+                let (e1, e2) = (self.expr(e1)?, self.expr(e2)?);
+                match rel {
+                    BinRel::Eq => format!("(= {e1} {e2})"),
+                    BinRel::Ne => format!("(not (= {e1} {e2}))"),
+                    BinRel::Gt => format!("(> {e1} {e2})"),
+                    BinRel::Ge => format!("(>= {e1} {e2})"),
+                    BinRel::Lt => format!("(< {e1} {e2})"),
+                    BinRel::Le => format!("(<= {e1} {e2})"),
+                }
+            }
+        };
+        Some(s)
+    }
+
+    fn exprs<T: Types>(&self, exprs: &[Expr<T>]) -> Option<Vec<String>> {
+        exprs.iter().map(|e| self.expr(e)).collect()
+    }
+
+    /// Variables that are not declared are either of an unsupported sort or interpreted by
+    /// fixpoint (e.g., set operations), so we give up on them.
+    fn var(&self, var: &impl std::fmt::Display) -> Option<String> {
+        let name = var.to_string();
+        self.scope.contains(&name).then_some(name)
+    }
+}
+
+fn nary(op: &str, unit: &str, args: Vec<String>) -> String {
+    match &args[..] {
+        [] => unit.to_string(),
+        [arg] => arg.clone(),
+        _ => format!("({op} {})", args.join(" ")),
+    }
+}
+
+fn constant(c: &Constant) -> Option<String> {
+    match c {
+        Constant::Int(n) => {
+            let n = n.to_string();
+            match n.strip_prefix('-') {
+                Some(abs) => Some(format!("(- {abs})")),
+                None => Some(n),
+            }
+        }
+        Constant::Bool(b) => Some(b.to_string()),
+        Constant::Real(_) => None,
+    }
+}
+
+fn sort<T: Types>(sort: &Sort<T>) -> Option<&'static str> {
+    match sort {
+        Sort::Int => Some("Int"),
+        Sort::Bool => Some("Bool"),
+        _ => None,
+    }
+}
+
+fn fun_sort<T: Types>(mut curr: &Sort<T>) -> Option<(Vec<&'static str>, &'static str)> {
+    let mut inputs = vec![];
+    while let Sort::Func(box [input, output]) = curr {
+        inputs.push(sort(input)?);
+        curr = output;
+    }
+    Some((inputs, sort(curr)?))
+}
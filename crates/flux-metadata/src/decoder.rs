@@ -15,12 +15,14 @@ use rustc_middle::{
 };
 use rustc_serialize::{opaque::MemDecoder, Decodable, Decoder as _};
 use rustc_span::{
-    def_id::{CrateNum, DefIndex},
-    SpanDecoder,
+    def_id::{CrateNum, DefIndex, DefPathHash},
+    SpanDecoder, Symbol,
 };
 use rustc_type_ir::TyDecoder;
 
-use crate::{CrateMetadata, METADATA_HEADER};
+use crate::{
+    CrateMetadata, ExternSpecs, METADATA_HEADER, SYMBOL_OFFSET, SYMBOL_PREINTERNED, SYMBOL_STR,
+};
 
 struct DecodeContext<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
@@ -31,7 +33,26 @@ pub(super) fn decode_crate_metadata(
     tcx: TyCtxt,
     sess: &FluxSession,
     path: &Path,
-) -> Option<CrateMetadata> {
+) -> Option<(ExternSpecs, CrateMetadata)> {
+    let buf = read_metadata_file(sess, path)?;
+    let mut decoder = DecodeContext { tcx, opaque: MemDecoder::new(&buf, METADATA_HEADER.len()) };
+    let extern_specs = ExternSpecs::decode(&mut decoder);
+    Some((extern_specs, CrateMetadata::decode(&mut decoder)))
+}
+
+/// Decodes only the extern specs saved in a metadata file. This is used to load the built-in specs
+/// which are saved as the extern specs of a crate the crate being checked does not depend on.
+pub(super) fn decode_extern_specs(
+    tcx: TyCtxt,
+    sess: &FluxSession,
+    path: &Path,
+) -> Option<ExternSpecs> {
+    let buf = read_metadata_file(sess, path)?;
+    let mut decoder = DecodeContext { tcx, opaque: MemDecoder::new(&buf, METADATA_HEADER.len()) };
+    Some(ExternSpecs::decode(&mut decoder))
+}
+
+fn read_metadata_file(sess: &FluxSession, path: &Path) -> Option<Vec<u8>> {
     let mut file = match fs::File::open(path) {
         Ok(file) => file,
         Err(err) if let io::ErrorKind::NotFound = err.kind() => return None,
@@ -44,9 +65,7 @@ pub(super) fn decode_crate_metadata(
     if !buf.starts_with(METADATA_HEADER) {
         panic!("incompatible metadata version")
     }
-
-    let mut decoder = DecodeContext { tcx, opaque: MemDecoder::new(&buf, METADATA_HEADER.len()) };
-    Some(CrateMetadata::decode(&mut decoder))
+    Some(buf)
 }
 
 implement_ty_decoder!(DecodeContext<'a, 'tcx>);
@@ -56,8 +75,16 @@ impl<'a, 'tcx> SpanDecoder for DecodeContext<'a, 'tcx> {
         self.opaque.decode_span()
     }
 
-    fn decode_symbol(&mut self) -> rustc_span::Symbol {
-        self.opaque.decode_symbol()
+    fn decode_symbol(&mut self) -> Symbol {
+        match self.read_u8() {
+            SYMBOL_STR => Symbol::intern(self.read_str()),
+            SYMBOL_OFFSET => {
+                let pos = self.read_usize();
+                self.with_position(pos, |d| Symbol::intern(d.read_str()))
+            }
+            SYMBOL_PREINTERNED => Symbol::new_from_decoded(self.read_u32()),
+            tag => bug!("invalid symbol tag `{tag}`"),
+        }
     }
 
     fn decode_expn_id(&mut self) -> rustc_span::ExpnId {
@@ -77,7 +104,9 @@ impl<'a, 'tcx> SpanDecoder for DecodeContext<'a, 'tcx> {
     }
 
     fn decode_def_id(&mut self) -> DefId {
-        DefId { krate: Decodable::decode(self), index: Decodable::decode(self) }
+        let def_path_hash = DefPathHash::decode(self);
+        self.tcx
+            .def_path_hash_to_def_id(def_path_hash, &"failed to decode `DefId` in flux metadata")
     }
 
     fn decode_attr_id(&mut self) -> rustc_ast::AttrId {
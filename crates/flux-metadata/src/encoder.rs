@@ -3,7 +3,7 @@ use std::collections::hash_map::Entry;
 use flux_middle::global_env::GlobalEnv;
 use rustc_data_structures::{fx::FxIndexSet, sync::Lrc};
 use rustc_hash::FxHashMap;
-use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_hir::def_id::{DefId, LocalDefId, LOCAL_CRATE};
 use rustc_metadata::errors::FailCreateFileEncoder;
 use rustc_middle::{
     bug,
@@ -19,8 +19,8 @@ use rustc_span::{
 use rustc_type_ir::TyEncoder;
 
 use crate::{
-    CrateMetadata, SpanKind, SpanTag, METADATA_HEADER, SYMBOL_OFFSET, SYMBOL_PREINTERNED,
-    SYMBOL_STR,
+    CrateMetadata, ExternSpecs, SpanKind, SpanTag, METADATA_HEADER, SYMBOL_OFFSET,
+    SYMBOL_PREINTERNED, SYMBOL_STR,
 };
 
 struct EncodeContext<'a, 'tcx> {
@@ -34,6 +34,8 @@ struct EncodeContext<'a, 'tcx> {
     is_proc_macro: bool,
     hygiene_ctxt: &'a HygieneEncodeContext,
     symbol_table: FxHashMap<Symbol, usize>, // interpret_allocs: FxIndexSet<interpret::AllocId>,
+    /// Maps the items declared with an extern spec to the extern item they refine
+    extern_ids: FxHashMap<LocalDefId, DefId>,
 }
 
 pub fn encode_metadata(genv: &GlobalEnv, path: &std::path::Path) {
@@ -46,7 +48,8 @@ pub fn encode_metadata(genv: &GlobalEnv, path: &std::path::Path) {
 
     encoder.emit_raw_bytes(METADATA_HEADER);
 
-    let crate_root = CrateMetadata::new(genv);
+    let extern_specs = ExternSpecs::new(*genv);
+    let crate_root = CrateMetadata::new(*genv);
 
     let source_map_files = genv.tcx().sess.source_map().files();
     let source_file_cache = (source_map_files[0].clone(), 0);
@@ -65,8 +68,15 @@ pub fn encode_metadata(genv: &GlobalEnv, path: &std::path::Path) {
         is_proc_macro: genv.tcx().crate_types().contains(&CrateType::ProcMacro),
         hygiene_ctxt: &hygiene_ctxt,
         symbol_table: Default::default(),
+        extern_ids: genv
+            .collect_specs()
+            .extern_specs
+            .iter()
+            .map(|(extern_id, local_id)| (*local_id, *extern_id))
+            .collect(),
     };
 
+    extern_specs.encode(&mut ecx);
     crate_root.encode(&mut ecx);
 
     ecx.opaque.finish().unwrap();
@@ -85,8 +95,8 @@ impl<'a, 'tcx> SpanEncoder for EncodeContext<'a, 'tcx> {
     }
 
     fn encode_def_id(&mut self, def_id: DefId) {
-        def_id.krate.encode(self);
-        def_id.index.encode(self);
+        let def_id = self.resolve_extern_spec(def_id);
+        self.tcx.def_path_hash(def_id).encode(self);
     }
 
     fn encode_syntax_context(&mut self, syntax_context: SyntaxContext) {
@@ -163,6 +173,26 @@ impl<'a, 'tcx> SpanEncoder for EncodeContext<'a, 'tcx> {
     }
 }
 
+impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
+    /// The items declared to write an extern spec only exist in the crate being compiled. Uses of
+    /// them are replaced by the extern item they refine, and uses of their generic parameters by
+    /// the parameters of the extern item at the same index.
+    fn resolve_extern_spec(&self, def_id: DefId) -> DefId {
+        let tcx = self.tcx;
+        let Some(local_id) = def_id.as_local() else { return def_id };
+        if let Some(extern_id) = self.extern_ids.get(&local_id) {
+            return *extern_id;
+        }
+        let Some(parent) = tcx.opt_local_parent(local_id) else { return def_id };
+        let Some(extern_id) = self.extern_ids.get(&parent) else { return def_id };
+        let extern_generics = tcx.generics_of(*extern_id);
+        tcx.generics_of(parent)
+            .param_def_id_to_index(tcx, def_id)
+            .filter(|index| (*index as usize) < extern_generics.count())
+            .map_or(*extern_id, |index| extern_generics.param_at(index as usize, tcx).def_id)
+    }
+}
+
 impl<'a, 'tcx> TyEncoder for EncodeContext<'a, 'tcx> {
     const CLEAR_CROSS_CRATE: bool = true;
 
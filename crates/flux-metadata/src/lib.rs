@@ -1,5 +1,5 @@
 #![allow(incomplete_features)]
-#![feature(rustc_private, specialization, if_let_guard)]
+#![feature(rustc_private, specialization, if_let_guard, let_chains)]
 
 extern crate rustc_ast;
 extern crate rustc_data_structures;
@@ -17,13 +17,13 @@ extern crate rustc_type_ir;
 mod decoder;
 mod encoder;
 
-use std::path::PathBuf;
+use std::{hash::Hash, iter, path::PathBuf};
 
-use decoder::decode_crate_metadata;
+use decoder::{decode_crate_metadata, decode_extern_specs};
 use flux_errors::FluxSession;
 use flux_macros::fluent_messages;
 use flux_middle::{cstore::CrateStore, fhir::Ignored, global_env::GlobalEnv, intern::List, rty};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use rustc_hir::def::DefKind;
 use rustc_macros::{Decodable, Encodable, TyDecodable, TyEncodable};
 use rustc_middle::ty::TyCtxt;
@@ -31,25 +31,60 @@ use rustc_session::{
     config::{OutFileName, OutputType},
     utils::CanonicalizedPath,
 };
-use rustc_span::def_id::{CrateNum, DefId, DefIndex};
+use rustc_span::{
+    def_id::{CrateNum, DefId, DefIndex, CRATE_DEF_ID},
+    symbol::kw,
+    Symbol,
+};
 
 pub use crate::encoder::encode_metadata;
 
 fluent_messages! { "../locales/en-US.ftl" }
 
-const METADATA_VERSION: u8 = 0;
+const METADATA_VERSION: u8 = 2;
 const METADATA_HEADER: &[u8] = &[b'f', b'l', b'u', b'x', 0, 0, 0, METADATA_VERSION];
 
 pub struct CStore {
     meta: FxHashMap<CrateNum, CrateMetadata>,
+    /// The extern specs of dependencies in the order their crates are loaded
+    extern_specs: Vec<ExternSpecs>,
+    /// The built-in specs. They are only used for items without extern specs in dependencies.
+    prelude: Vec<ExternSpecs>,
 }
 
+/// The refinements of items declared in the crate the metadata is saved for. The tables are keyed
+/// by [`DefIndex`] because they are always looked up with the crate of the item.
 #[derive(TyEncodable, TyDecodable)]
 pub struct CrateMetadata {
-    fn_sigs: FxHashMap<DefIndex, rty::EarlyBinder<rty::PolyFnSig>>,
-    adts: FxHashMap<DefIndex, AdtMetadata>,
-    /// For now it only store type of aliases
-    type_of: FxHashMap<DefIndex, rty::EarlyBinder<rty::TyCtor>>,
+    local_tables: Tables<DefIndex>,
+    func_decls: Vec<rty::SpecFuncDecl>,
+    func_defns: Vec<rty::SpecFunc>,
+    qualifiers: Vec<rty::Qualifier>,
+}
+
+/// The refinements given with extern specs to items declared in other crates. They are encoded
+/// before the [`CrateMetadata`] such that they can be decoded alone, which is how the built-in
+/// specs are loaded.
+#[derive(TyEncodable, TyDecodable)]
+pub struct ExternSpecs {
+    tables: Tables<DefId>,
+    /// The name of the top-level module containing the extern spec of each item. For the built-in
+    /// specs this is the name of the group the spec belongs to.
+    modules: FxHashMap<DefId, Symbol>,
+}
+
+#[derive(TyEncodable, TyDecodable)]
+struct Tables<K: Eq + Hash> {
+    fn_sig: FxHashMap<K, rty::EarlyBinder<rty::PolyFnSig>>,
+    adt: FxHashMap<K, AdtMetadata>,
+    /// Only saved for local structs, enums and type aliases
+    type_of: FxHashMap<K, rty::EarlyBinder<rty::TyCtor>>,
+    generics_of: FxHashMap<K, rty::Generics>,
+    refinement_generics_of: FxHashMap<K, rty::RefinementGenerics>,
+    predicates_of: FxHashMap<K, rty::EarlyBinder<rty::GenericPredicates>>,
+    assoc_refinements_of: FxHashMap<K, rty::AssocRefinements>,
+    assoc_refinement_def: FxHashMap<(K, Symbol), rty::EarlyBinder<rty::Lambda>>,
+    sort_of_assoc_reft: FxHashMap<(K, Symbol), Option<rty::EarlyBinder<rty::FuncSort>>>,
 }
 
 #[derive(TyEncodable, TyDecodable)]
@@ -58,32 +93,71 @@ struct AdtMetadata {
     variants: rty::Opaqueness<rty::EarlyBinder<List<rty::PolyVariant>>>,
 }
 
+/// Looks up `$def_id` in the table `$table` of the crate declaring it, falling back to the extern
+/// specs in dependencies and then to the built-in specs.
+macro_rules! lookup {
+    ($cstore:expr, $table:ident, $def_id:expr) => {
+        lookup!($cstore, $table, $def_id, $def_id.index, $def_id)
+    };
+    ($cstore:expr, $table:ident, $def_id:expr, $local_key:expr, $extern_key:expr) => {{
+        let cstore: &CStore = $cstore;
+        cstore
+            .meta
+            .get(&$def_id.krate)
+            .and_then(|meta| meta.local_tables.$table.get(&$local_key))
+            .or_else(|| {
+                cstore
+                    .extern_specs
+                    .iter()
+                    .chain(&cstore.prelude)
+                    .find_map(|specs| specs.tables.$table.get(&$extern_key))
+            })
+    }};
+}
+
 impl CStore {
-    pub fn load(tcx: TyCtxt, sess: &FluxSession) -> Self {
-        let meta = tcx
-            .crates(())
+    /// Loads the metadata of the dependencies of the crate and the built-in specs saved in the
+    /// files in `prelude`.
+    pub fn load(tcx: TyCtxt, sess: &FluxSession, prelude: &[PathBuf]) -> Self {
+        let mut meta = FxHashMap::default();
+        let mut extern_specs = vec![];
+        for crate_num in tcx.crates(()) {
+            let Some(path) = flux_metadata_extern_location(tcx, *crate_num) else { continue };
+            let Some((specs, crate_meta)) = decode_crate_metadata(tcx, sess, path.as_path()) else {
+                continue;
+            };
+            extern_specs.push(specs);
+            meta.insert(*crate_num, crate_meta);
+        }
+        let prelude = prelude
             .iter()
-            .filter_map(|crate_num| {
-                let path = flux_metadata_extern_location(tcx, *crate_num)?;
-                let meta = decode_crate_metadata(tcx, sess, path.as_path())?;
-                Some((*crate_num, meta))
-            })
+            .filter_map(|path| decode_extern_specs(tcx, sess, path))
             .collect();
-        Self { meta }
+        Self { meta, extern_specs, prelude }
+    }
+
+    /// The items with extern specs in dependencies
+    pub fn extern_spec_ids(&self) -> impl Iterator<Item = DefId> + '_ {
+        self.extern_specs
+            .iter()
+            .flat_map(|specs| specs.modules.keys().copied())
+    }
+
+    /// Removes the built-in specs in the groups for which `dropped` returns `true`
+    pub fn drop_prelude_groups(&mut self, dropped: impl Fn(Symbol) -> bool) {
+        for specs in &mut self.prelude {
+            specs.retain(|group| !dropped(group));
+        }
     }
 
     fn adt(&self, def_id: DefId) -> Option<&AdtMetadata> {
-        self.meta.get(&def_id.krate)?.adts.get(&def_id.index)
+        lookup!(self, adt, def_id)
     }
 }
 
 impl CrateStore for CStore {
     fn fn_sig(&self, def_id: DefId) -> Option<rty::EarlyBinder<rty::PolyFnSig>> {
-        self.meta
-            .get(&def_id.krate)?
-            .fn_sigs
-            .get(&def_id.index)
-            .cloned()
+        lookup!(self, fn_sig, def_id).cloned()
     }
 
     fn adt_def(&self, def_id: DefId) -> Option<&rty::AdtDef> {
@@ -99,43 +173,225 @@ impl CrateStore for CStore {
     }
 
     fn type_of(&self, def_id: DefId) -> Option<&rty::EarlyBinder<rty::TyCtor>> {
-        self.meta.get(&def_id.krate)?.type_of.get(&def_id.index)
+        lookup!(self, type_of, def_id)
+    }
+
+    fn generics_of(&self, def_id: DefId) -> Option<&rty::Generics> {
+        lookup!(self, generics_of, def_id)
+    }
+
+    fn refinement_generics_of(&self, def_id: DefId) -> Option<&rty::RefinementGenerics> {
+        lookup!(self, refinement_generics_of, def_id)
+    }
+
+    fn predicates_of(&self, def_id: DefId) -> Option<&rty::EarlyBinder<rty::GenericPredicates>> {
+        lookup!(self, predicates_of, def_id)
+    }
+
+    fn assoc_refinements_of(&self, def_id: DefId) -> Option<&rty::AssocRefinements> {
+        lookup!(self, assoc_refinements_of, def_id)
+    }
+
+    fn assoc_refinement_def(
+        &self,
+        impl_id: DefId,
+        name: Symbol,
+    ) -> Option<&rty::EarlyBinder<rty::Lambda>> {
+        lookup!(self, assoc_refinement_def, impl_id, (impl_id.index, name), (impl_id, name))
+    }
+
+    fn sort_of_assoc_reft(
+        &self,
+        def_id: DefId,
+        name: Symbol,
+    ) -> Option<&Option<rty::EarlyBinder<rty::FuncSort>>> {
+        lookup!(self, sort_of_assoc_reft, def_id, (def_id.index, name), (def_id, name))
+    }
+
+    fn prelude_group(&self, def_id: DefId) -> Option<Symbol> {
+        if self
+            .extern_specs
+            .iter()
+            .any(|specs| specs.modules.contains_key(&def_id))
+        {
+            return None;
+        }
+        self.prelude
+            .iter()
+            .find_map(|specs| specs.modules.get(&def_id).copied())
+    }
+
+    fn func_decls(&self) -> Box<dyn Iterator<Item = &rty::SpecFuncDecl> + '_> {
+        Box::new(self.meta.values().flat_map(|meta| &meta.func_decls))
+    }
+
+    fn func_defns(&self) -> Box<dyn Iterator<Item = &rty::SpecFunc> + '_> {
+        Box::new(self.meta.values().flat_map(|meta| &meta.func_defns))
+    }
+
+    fn qualifiers(&self) -> Box<dyn Iterator<Item = &rty::Qualifier> + '_> {
+        Box::new(self.meta.values().flat_map(|meta| &meta.qualifiers))
     }
 }
 
 impl CrateMetadata {
-    fn new(genv: &GlobalEnv) -> Self {
+    fn new(genv: GlobalEnv) -> Self {
         let tcx = genv.tcx();
-        let mut fn_sigs = FxHashMap::default();
-        let mut adts = FxHashMap::default();
-        let mut type_of = FxHashMap::default();
+        let extern_specs = genv
+            .collect_specs()
+            .extern_specs
+            .values()
+            .copied()
+            .collect::<FxHashSet<_>>();
+        let mut local_tables = Tables::default();
 
         for local_id in tcx.iter_local_def_id() {
             if genv.ignored(local_id) == Ignored::Yes {
                 continue;
             }
+            // The refinements of extern specs are saved in `ExternSpecs` under the extern item
+            if extern_specs.contains(&local_id) {
+                continue;
+            }
 
             let def_id = local_id.to_def_id();
-            let def_kind = tcx.def_kind(local_id);
+            local_tables.insert(genv, def_id.index, def_id);
+            if let DefKind::Struct | DefKind::Enum | DefKind::TyAlias { .. } = tcx.def_kind(def_id)
+                && let Ok(ty) = genv.type_of(def_id)
+            {
+                local_tables.type_of.insert(def_id.index, ty);
+            }
+        }
 
-            match def_kind {
-                DefKind::Fn | DefKind::AssocFn => {
-                    fn_sigs.insert(def_id.index, genv.fn_sig(def_id).unwrap());
-                }
-                DefKind::Enum | DefKind::Struct => {
-                    let adt_def = genv.adt_def(def_id).unwrap();
-                    let variants = genv.variants_of(def_id).unwrap();
-                    let meta = AdtMetadata { adt_def, variants };
-                    adts.insert(def_id.index, meta);
-                    type_of.insert(def_id.index, genv.type_of(def_id).unwrap());
+        let mut func_decls = vec![];
+        let mut func_defns = vec![];
+        let defns = genv.spec_func_defns().unwrap();
+        for func in genv.map().spec_funcs() {
+            func_decls.push(genv.func_decl(func.name).unwrap());
+            if let Some(defn) = defns.func_defn(&func.name) {
+                func_defns.push(defn.clone());
+            }
+        }
+        let qualifiers = genv.local_qualifiers().unwrap().to_vec();
+
+        Self { local_tables, func_decls, func_defns, qualifiers }
+    }
+}
+
+impl ExternSpecs {
+    fn new(genv: GlobalEnv) -> Self {
+        let tcx = genv.tcx();
+        let mut tables = Tables::default();
+        let mut modules = FxHashMap::default();
+        for (extern_id, local_id) in &genv.collect_specs().extern_specs {
+            if genv.ignored(*local_id) == Ignored::Yes {
+                continue;
+            }
+            // Queries on the extern item are answered with the refinements of the extern spec
+            tables.insert(genv, *extern_id, *extern_id);
+
+            let module = iter::successors(Some(*local_id), |id| tcx.opt_local_parent(*id))
+                .find(|id| tcx.opt_local_parent(*id) == Some(CRATE_DEF_ID))
+                .filter(|id| tcx.def_kind(*id) == DefKind::Mod)
+                .map_or(kw::Empty, |id| tcx.item_name(id.to_def_id()));
+            modules.insert(*extern_id, module);
+        }
+        Self { tables, modules }
+    }
+
+    /// Keeps only the specs in the modules for which `keep_module` returns `true`
+    fn retain(&mut self, keep_module: impl Fn(Symbol) -> bool) {
+        let modules = &self.modules;
+        let keep = |def_id: &DefId| {
+            modules
+                .get(def_id)
+                .is_some_and(|module| keep_module(*module))
+        };
+        let tables = &mut self.tables;
+        tables.fn_sig.retain(|def_id, _| keep(def_id));
+        tables.adt.retain(|def_id, _| keep(def_id));
+        tables.type_of.retain(|def_id, _| keep(def_id));
+        tables.generics_of.retain(|def_id, _| keep(def_id));
+        tables
+            .refinement_generics_of
+            .retain(|def_id, _| keep(def_id));
+        tables.predicates_of.retain(|def_id, _| keep(def_id));
+        tables.assoc_refinements_of.retain(|def_id, _| keep(def_id));
+        tables
+            .assoc_refinement_def
+            .retain(|(def_id, _), _| keep(def_id));
+        tables
+            .sort_of_assoc_reft
+            .retain(|(def_id, _), _| keep(def_id));
+        self.modules.retain(|_, module| keep_module(*module));
+    }
+}
+
+impl<K: Eq + Hash + Copy> Tables<K> {
+    /// Saves the refinements of `def_id` under `key`. Refinements that cannot be computed are
+    /// skipped, errors for them are reported when checking the crate.
+    fn insert(&mut self, genv: GlobalEnv, key: K, def_id: DefId) {
+        let def_kind = genv.def_kind(def_id);
+        if let DefKind::Fn | DefKind::AssocFn = def_kind {
+            if let Ok(fn_sig) = genv.fn_sig(def_id) {
+                self.fn_sig.insert(key, fn_sig);
+            }
+            if let Ok(generics) = genv.refinement_generics_of(def_id) {
+                self.refinement_generics_of.insert(key, generics);
+            }
+        }
+        if let DefKind::Struct | DefKind::Enum = def_kind
+            && let Ok(adt_def) = genv.adt_def(def_id)
+            && let Ok(variants) = genv.variants_of(def_id)
+        {
+            self.adt.insert(key, AdtMetadata { adt_def, variants });
+        }
+        if let DefKind::Fn
+        | DefKind::AssocFn
+        | DefKind::Struct
+        | DefKind::Enum
+        | DefKind::Trait
+        | DefKind::Impl { .. } = def_kind
+        {
+            if let Ok(generics) = genv.generics_of(def_id) {
+                self.generics_of.insert(key, generics);
+            }
+            if let Ok(predicates) = genv.predicates_of(def_id) {
+                self.predicates_of.insert(key, predicates);
+            }
+        }
+        if let DefKind::Trait | DefKind::Impl { .. } = def_kind
+            && let Ok(assoc_refinements) = genv.assoc_refinements_of(def_id)
+        {
+            for assoc_reft in &assoc_refinements.predicates {
+                let name = assoc_reft.name;
+                if let Ok(sort) = genv.sort_of_assoc_reft(def_id, name) {
+                    self.sort_of_assoc_reft.insert((key, name), sort);
                 }
-                DefKind::TyAlias { .. } => {
-                    type_of.insert(def_id.index, genv.type_of(def_id).unwrap());
+                if let DefKind::Impl { .. } = def_kind
+                    && let Ok(lambda) = genv.assoc_refinement_def(def_id, name)
+                {
+                    self.assoc_refinement_def.insert((key, name), lambda);
                 }
-                _ => {}
             }
+            self.assoc_refinements_of.insert(key, assoc_refinements);
+        }
+    }
+}
+
+impl<K: Eq + Hash> Default for Tables<K> {
+    fn default() -> Self {
+        Self {
+            fn_sig: Default::default(),
+            adt: Default::default(),
+            type_of: Default::default(),
+            generics_of: Default::default(),
+            refinement_generics_of: Default::default(),
+            predicates_of: Default::default(),
+            assoc_refinements_of: Default::default(),
+            assoc_refinement_def: Default::default(),
+            sort_of_assoc_reft: Default::default(),
         }
-        Self { fn_sigs, adts, type_of }
     }
 }
 
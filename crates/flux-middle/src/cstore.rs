@@ -1,4 +1,4 @@
-use rustc_span::def_id::DefId;
+use rustc_span::{def_id::DefId, Symbol};
 
 use crate::rty;
 
@@ -10,6 +10,28 @@ pub trait CrateStore {
         def_id: DefId,
     ) -> Option<rty::Opaqueness<rty::EarlyBinder<&[rty::PolyVariant]>>>;
     fn type_of(&self, def_id: DefId) -> Option<&rty::EarlyBinder<rty::TyCtor>>;
+    fn generics_of(&self, def_id: DefId) -> Option<&rty::Generics>;
+    fn refinement_generics_of(&self, def_id: DefId) -> Option<&rty::RefinementGenerics>;
+    fn predicates_of(&self, def_id: DefId) -> Option<&rty::EarlyBinder<rty::GenericPredicates>>;
+    fn assoc_refinements_of(&self, def_id: DefId) -> Option<&rty::AssocRefinements>;
+    fn assoc_refinement_def(
+        &self,
+        impl_id: DefId,
+        name: Symbol,
+    ) -> Option<&rty::EarlyBinder<rty::Lambda>>;
+    fn sort_of_assoc_reft(
+        &self,
+        def_id: DefId,
+        name: Symbol,
+    ) -> Option<&Option<rty::EarlyBinder<rty::FuncSort>>>;
+    /// The group of the built-in spec used for `def_id` if it has one
+    fn prelude_group(&self, def_id: DefId) -> Option<Symbol>;
+    /// Spec functions declared in upstream crates (both uninterpreted and defined)
+    fn func_decls(&self) -> Box<dyn Iterator<Item = &rty::SpecFuncDecl> + '_>;
+    /// Definitions of the spec functions with a body declared in upstream crates
+    fn func_defns(&self) -> Box<dyn Iterator<Item = &rty::SpecFunc> + '_>;
+    /// Qualifiers declared in upstream crates
+    fn qualifiers(&self) -> Box<dyn Iterator<Item = &rty::Qualifier> + '_>;
 }
 
 pub type CrateStoreDyn = dyn CrateStore;
@@ -57,6 +57,41 @@ impl Ignored {
     }
 }
 
+/// The arguments of `#[flux::trusted(..)]` on a function
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Trusted {
+    /// Whether the items nested in the body of the function, e.g., inner functions or impls, are
+    /// also trusted. Closures are always trusted because they are checked as part of the body
+    /// defining them.
+    pub deep: bool,
+    /// Why the function is trusted, e.g., `#[flux::trusted(reason = "uses unsafe pointers")]`
+    pub reason: Option<Symbol>,
+}
+
+/// A function whose meaning is built into the checker, declared with `#[flux::intrinsic(..)]`
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Intrinsic {
+    /// `unsafe fn(bool)`: assumes its argument holds at the call site without proving it
+    Assume,
+    /// `fn(bool)`: proves its argument holds at the call site
+    Assert,
+    /// `fn(&T)`: packs the type of the borrowed place into a type with inferred refinements
+    Pack,
+    /// `fn(&T)`: unpacks the type of the borrowed place, including under mutable references
+    Unpack,
+}
+
+impl Intrinsic {
+    pub fn name(self) -> &'static str {
+        match self {
+            Intrinsic::Assume => "assume",
+            Intrinsic::Assert => "assert",
+            Intrinsic::Pack => "pack",
+            Intrinsic::Unpack => "unpack",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Generics<'fhir> {
     pub params: &'fhir [GenericParam<'fhir>],
@@ -466,6 +501,11 @@ pub struct FnDecl<'fhir> {
 #[derive(Clone, Copy)]
 pub struct FnSig<'fhir> {
     pub trusted: bool,
+    /// Whether calls to this function are checked by inlining its body instead of using its
+    /// signature
+    pub inline: bool,
+    /// Whether the function is expected to fail verification, i.e., `#[flux::should_fail]`
+    pub should_fail: bool,
     //// List of local qualifiers for this function
     pub qualifiers: &'fhir [SurfaceIdent],
     pub decl: &'fhir FnDecl<'fhir>,
@@ -513,11 +553,26 @@ pub enum TyKind<'fhir> {
     Tuple(&'fhir [Ty<'fhir>]),
     Array(&'fhir Ty<'fhir>, ArrayLen),
     RawPtr(&'fhir Ty<'fhir>, Mutability),
+    BareFn(&'fhir BareFnTy<'fhir>),
     OpaqueDef(ItemId, &'fhir [GenericArg<'fhir>], &'fhir [RefineArg<'fhir>], bool),
     Never,
     Hole(FhirId),
 }
 
+/// A function pointer type. Function pointer types cannot have refinement parameters, `requires`
+/// or `ensures` clauses.
+#[derive(Clone, Copy)]
+pub struct BareFnTy<'fhir> {
+    /// The late-bound lifetimes of the type, e.g., `'a` in `for<'a> fn(&'a i32)`. This is only
+    /// populated for [lifted] types. We don't need them during conversion because lifetimes are
+    /// already resolved.
+    ///
+    /// [lifted]: lift::LiftCtxt::lift_ty
+    pub bound_generic_params: &'fhir [GenericParam<'fhir>],
+    pub inputs: &'fhir [Ty<'fhir>],
+    pub output: &'fhir Ty<'fhir>,
+}
+
 #[derive(Clone, Copy)]
 pub struct MutTy<'fhir> {
     pub ty: &'fhir Ty<'fhir>,
@@ -849,6 +904,8 @@ pub enum ExprRes<Id = ParamId> {
     Const(DefId),
     NumConst(i128),
     GlobalFunc(SpecFuncKind, Symbol),
+    /// A variant of a reflected enum, e.g., `Ordering::Less`
+    Variant(DefId),
 }
 
 impl<Id> ExprRes<Id> {
@@ -1196,6 +1253,9 @@ impl fmt::Debug for Ty<'_> {
             TyKind::Constr(pred, ty) => write!(f, "{{{ty:?} | {pred:?}}}"),
             TyKind::RawPtr(ty, Mutability::Not) => write!(f, "*const {ty:?}"),
             TyKind::RawPtr(ty, Mutability::Mut) => write!(f, "*mut {ty:?}"),
+            TyKind::BareFn(bare_fn) => {
+                write!(f, "fn({:?}) -> {:?}", bare_fn.inputs.iter().format(", "), bare_fn.output)
+            }
             TyKind::Hole(_) => write!(f, "_"),
             TyKind::OpaqueDef(def_id, args, refine_args, _) => {
                 write!(
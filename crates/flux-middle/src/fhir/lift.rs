@@ -384,6 +384,21 @@ impl<'a, 'genv, 'tcx> LiftCtxt<'a, 'genv, 'tcx> {
                 let ty = self.lift_ty(mut_ty.ty)?;
                 fhir::TyKind::RawPtr(self.genv.alloc(ty), mut_ty.mutbl)
             }
+            hir::TyKind::BareFn(bare_fn) => {
+                let bound_generic_params =
+                    try_alloc_slice!(self.genv, bare_fn.generic_params, |param| {
+                        self.lift_generic_param(param)
+                    })?;
+                let inputs =
+                    try_alloc_slice!(self.genv, bare_fn.decl.inputs, |ty| self.lift_ty(ty))?;
+                let output = self.lift_fn_ret_ty(&bare_fn.decl.output)?;
+                let bare_fn = fhir::BareFnTy {
+                    bound_generic_params,
+                    inputs,
+                    output: self.genv.alloc(output),
+                };
+                fhir::TyKind::BareFn(self.genv.alloc(bare_fn))
+            }
             hir::TyKind::OpaqueDef(item_id, args, in_trait_def) => {
                 let opaque_ty = self
                     .with_new_owner(item_id.owner_id, &IndexGen::new())
@@ -367,6 +367,10 @@ pub fn walk_ty<V: Visitor>(vis: &mut V, ty: &Ty) {
         TyKind::RawPtr(ty, _mtblt) => {
             vis.visit_ty(ty);
         }
+        TyKind::BareFn(bare_fn) => {
+            walk_list!(vis, visit_ty, bare_fn.inputs);
+            vis.visit_ty(bare_fn.output);
+        }
         TyKind::OpaqueDef(_item_id, generics, refine, _bool) => {
             walk_list!(vis, visit_generic_arg, generics);
             walk_list!(vis, visit_refine_arg, refine);
@@ -1,8 +1,9 @@
 use std::{alloc, ptr, rc::Rc, slice};
 
 use flux_common::{bug, result::ErrorEmitter};
-use flux_config::CrateConfig;
+use flux_config::{CrateConfig, UnpackPolicy};
 use flux_errors::FluxSession;
+use flux_syntax::surface;
 use rustc_data_structures::unord::UnordMap;
 use rustc_hash::FxHashSet;
 use rustc_hir::{
@@ -20,6 +21,7 @@ use crate::{
     queries::{Providers, Queries, QueryErr, QueryResult},
     rty::{self, normalize::SpecFuncDefns, refining::Refiner},
     rustc::{self, lowering, ty},
+    Opts,
 };
 
 #[derive(Clone, Copy)]
@@ -133,6 +135,7 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
     }
 
     /// Return all the qualifiers that apply to an item, including both global and local qualifiers.
+    /// Qualifiers declared in upstream crates apply in the same way as the ones in this crate.
     pub fn qualifiers_for(
         self,
         did: LocalDefId,
@@ -144,13 +147,17 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
             .map(|qual| qual.name)
             .collect();
         Ok(self
-            .inner
-            .queries
-            .qualifiers(self)?
+            .local_qualifiers()?
             .iter()
+            .chain(self.cstore().qualifiers())
             .filter(move |qualifier| qualifier.global || names.contains(&qualifier.name)))
     }
 
+    /// The qualifiers declared in this crate
+    pub fn local_qualifiers(self) -> QueryResult<&'genv [rty::Qualifier]> {
+        self.inner.queries.qualifiers(self)
+    }
+
     pub fn func_decls(self) -> QueryResult<impl Iterator<Item = &'genv rty::SpecFuncDecl>> {
         Ok(self.inner.queries.func_decls(self)?.values())
     }
@@ -283,6 +290,15 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
         self.inner.queries.fn_sig(self, def_id.into())
     }
 
+    /// A signature for `def_id` computed from its body, used at call sites instead of its declared
+    /// signature when `auto_specs` is enabled. Returns `None` if the function is not a candidate.
+    pub fn fn_summary(
+        self,
+        def_id: LocalDefId,
+    ) -> QueryResult<Option<rty::EarlyBinder<rty::PolyFnSig>>> {
+        self.inner.queries.fn_summary(self, def_id)
+    }
+
     pub fn variants_of(
         self,
         def_id: DefId,
@@ -345,7 +361,7 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
         Refiner::with_holes(self, generics).refine_ty(rustc_ty)
     }
 
-    pub(crate) fn cstore(self) -> &'genv CrateStoreDyn {
+    pub fn cstore(self) -> &'genv CrateStoreDyn {
         &*self.inner.cstore
     }
 
@@ -383,6 +399,62 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
         }
     }
 
+    /// Returns the intrinsic `def_id` was declared as, if any
+    pub fn intrinsic(self, def_id: DefId) -> Option<fhir::Intrinsic> {
+        self.collect_specs()
+            .intrinsics
+            .get(&def_id.as_local()?)
+            .copied()
+    }
+
+    /// Returns the `#[flux::assert(..)]` and `#[flux::assume(..)]` annotations on the statements in
+    /// the body of `def_id`
+    pub fn stmt_annots(self, def_id: LocalDefId) -> &'genv [surface::StmtAnnot] {
+        self.collect_specs()
+            .stmt_annots
+            .get(&def_id)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the unpack policy `def_id` was annotated with, if any
+    pub fn unpack_policy(self, def_id: LocalDefId) -> Option<UnpackPolicy> {
+        self.collect_specs().unpack_policies.get(&def_id).copied()
+    }
+
+    /// Returns the arguments of the `#[flux::trusted]` annotation on `def_id`, if any
+    pub fn trusted(self, def_id: LocalDefId) -> Option<fhir::Trusted> {
+        self.collect_specs().trusted.get(&def_id).copied()
+    }
+
+    /// Returns the `#[flux::trusted(deep)]` annotation of the function whose body `def_id` is
+    /// nested in, if any
+    pub fn deep_trusted_ancestor(self, mut def_id: LocalDefId) -> Option<fhir::Trusted> {
+        while let Some(parent) = self.tcx().opt_local_parent(def_id) {
+            if let Some(trusted) = self.trusted(parent)
+                && trusted.deep
+            {
+                return Some(trusted);
+            }
+            def_id = parent;
+        }
+        None
+    }
+
+    /// The options of `def_id` set with `#[flux::opts(..)]` on it or its ancestors. Each option is
+    /// resolved independently to the nearest item setting it, like [`GlobalEnv::ignored`].
+    pub fn opts(self, mut def_id: LocalDefId) -> Opts {
+        let mut opts = Opts::default();
+        loop {
+            if let Some(item_opts) = self.collect_specs().opts.get(&def_id) {
+                opts = opts.or(*item_opts);
+            }
+            match self.tcx().opt_local_parent(def_id) {
+                Some(parent) => def_id = parent,
+                None => break opts,
+            }
+        }
+    }
+
     fn ignores(self) -> &'genv UnordMap<LocalDefId, Ignored> {
         &self.collect_specs().check_item
     }
@@ -471,6 +543,16 @@ impl<'genv, 'tcx> Map<'genv, 'tcx> {
         Ok(self.node(def_id)?.fn_sig().unwrap().trusted)
     }
 
+    pub fn is_inline(self, def_id: LocalDefId) -> QueryResult<bool> {
+        Ok(self.node(def_id)?.fn_sig().unwrap().inline)
+    }
+
+    /// Whether the function is marked with `#[flux::should_fail]`, i.e., it is expected to fail
+    /// verification and it is an error if it verifies.
+    pub fn should_fail(self, def_id: LocalDefId) -> QueryResult<bool> {
+        Ok(self.node(def_id)?.fn_sig().unwrap().should_fail)
+    }
+
     pub fn fn_quals_for(self, def_id: LocalDefId) -> QueryResult<&'genv [fhir::SurfaceIdent]> {
         // This is called on adts when checking invariants
         if let Some(fn_sig) = self.node(def_id)?.fn_sig() {
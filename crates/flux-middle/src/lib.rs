@@ -136,6 +136,20 @@ pub fn theory_funcs() -> &'static [TheoryFunc] {
                     ),
                 ),
             },
+            TheoryFunc {
+                name: Symbol::intern("set_difference"),
+                fixpoint_name: Symbol::intern("Set_dif"),
+                sort: rty::PolyFuncSort::new(
+                    1,
+                    rty::FuncSort::new(
+                        vec![
+                            Sort::app(Set, vec![Var(ParamSort::from(0))]),
+                            Sort::app(Set, vec![Var(ParamSort::from(0))]),
+                        ],
+                        Sort::app(Set, vec![Var(ParamSort::from(0))]),
+                    ),
+                ),
+            },
             TheoryFunc {
                 name: Symbol::intern("set_is_in"),
                 fixpoint_name: Symbol::intern("Set_mem"),
@@ -195,6 +209,25 @@ pub fn theory_funcs() -> &'static [TheoryFunc] {
     })
 }
 
+/// Options set on a crate, module, impl, trait or function with `#[flux::opts(..)]`. Each option
+/// applies to the item and everything nested in it, unless an inner item sets it again (see
+/// [`GlobalEnv::opts`]).
+///
+/// [`GlobalEnv::opts`]: global_env::GlobalEnv::opts
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Opts {
+    pub solver: Option<config::SmtSolver>,
+    /// Number of seconds after which solving the constraint of a function is aborted
+    pub timeout: Option<u64>,
+}
+
+impl Opts {
+    /// Fills the options that aren't set with the ones in `outer`
+    pub fn or(self, outer: Opts) -> Opts {
+        Opts { solver: self.solver.or(outer.solver), timeout: self.timeout.or(outer.timeout) }
+    }
+}
+
 #[derive(Default)]
 pub struct Specs {
     pub fn_sigs: UnordMap<OwnerId, surface::FnSpec>,
@@ -207,9 +240,19 @@ pub struct Specs {
     pub sort_decls: Vec<surface::SortDecl>,
     pub ty_aliases: UnordMap<OwnerId, Option<surface::TyAlias>>,
     pub check_item: UnordMap<LocalDefId, fhir::Ignored>,
+    pub intrinsics: UnordMap<LocalDefId, fhir::Intrinsic>,
+    pub unpack_policies: UnordMap<LocalDefId, config::UnpackPolicy>,
+    /// The functions annotated with `#[flux::trusted]`
+    pub trusted: UnordMap<LocalDefId, fhir::Trusted>,
+    pub opts: UnordMap<LocalDefId, Opts>,
+    /// The `#[flux::assert(..)]` and `#[flux::assume(..)]` annotations on the statements of each
+    /// body
+    pub stmt_annots: UnordMap<LocalDefId, Vec<surface::StmtAnnot>>,
     pub consts: FxHashSet<LocalDefId>,
     pub crate_config: Option<config::CrateConfig>,
     pub extern_specs: FxHashMap<DefId, LocalDefId>,
+    /// The groups of built-in specs that are not used for the crate
+    pub dropped_prelude_groups: FxHashSet<Symbol>,
 }
 
 impl Specs {
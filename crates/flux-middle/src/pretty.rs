@@ -6,7 +6,7 @@ use rustc_hash::FxHashMap;
 use rustc_hir::def_id::DefId;
 use rustc_index::newtype_index;
 use rustc_middle::ty::TyCtxt;
-use rustc_span::{Pos, Span};
+use rustc_span::{Pos, Span, Symbol};
 use rustc_target::abi::FieldIdx;
 use rustc_type_ir::{DebruijnIndex, INNERMOST};
 
@@ -134,7 +134,7 @@ pub fn pprint_with_default_cx<T: Pretty>(
 }
 
 pub use crate::_impl_debug_with_default_cx as impl_debug_with_default_cx;
-use crate::rty::{BoundReft, BoundReftKind, BoundVariableKind};
+use crate::rty::{BoundReft, BoundReftKind, BoundVariableKind, Name};
 
 #[derive(Copy, Clone)]
 pub enum KVarArgs {
@@ -157,6 +157,8 @@ pub struct PrettyCx<'tcx> {
     pub hide_refinements: bool,
     pub hide_regions: bool,
     pub hide_binder: bool,
+    /// Print types in the syntax of `#[flux::sig]`, see [`PrettyCx::surface`]
+    pub surface: bool,
     env: RefCell<Env>,
 }
 
@@ -170,6 +172,9 @@ newtype_index! {
 struct Env {
     name_gen: IndexGen<BoundVarName>,
     layers: Vec<FxHashMap<u32, BoundVarName>>,
+    /// Surface names for free variables in scope. The second component is used to disambiguate
+    /// between variables in scope with the same surface name.
+    fvar_names: FxHashMap<Name, (Symbol, usize)>,
 }
 
 impl Env {
@@ -193,6 +198,15 @@ impl Env {
     fn pop_layer(&mut self) {
         self.layers.pop();
     }
+
+    fn insert_fvar_name(&mut self, name: Name, sym: Symbol) {
+        let shadowed = self
+            .fvar_names
+            .values()
+            .filter(|(other, _)| *other == sym)
+            .count();
+        self.fvar_names.insert(name, (sym, shadowed));
+    }
 }
 
 pub struct WithCx<'a, 'tcx, T> {
@@ -256,10 +270,23 @@ impl PrettyCx<'_> {
             hide_refinements: false,
             hide_regions: false,
             hide_binder: false,
+            surface: false,
             env: RefCell::new(Env::default()),
         }
     }
 
+    /// A context to print types in user-facing diagnostics. Types are printed as close as possible
+    /// to what the user would write in a `#[flux::sig]`, e.g., `i32{v: v > 0}` instead of
+    /// `∃b0. { i32[b0] | b0 > 0 }`, without regions or the binders of refinement parameters.
+    pub fn surface(tcx: TyCtxt) -> PrettyCx {
+        PrettyCx {
+            hide_regions: true,
+            kvar_args: KVarArgs::Hide,
+            surface: true,
+            ..Self::default(tcx)
+        }
+    }
+
     pub fn merge(&mut self, opts: &config::Value) {
         set_opts!(
             self,
@@ -277,6 +304,7 @@ impl PrettyCx<'_> {
                 hide_refinements,
                 hide_regions,
                 hide_binder,
+                surface,
             ]
         );
     }
@@ -288,6 +316,33 @@ impl PrettyCx<'_> {
         r
     }
 
+    /// Print free variables in `names` using their surface name (when formatting inside `f`)
+    pub fn with_fvar_names<R>(
+        &self,
+        names: impl IntoIterator<Item = (Name, Symbol)>,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        let mut inserted = vec![];
+        for (name, sym) in names {
+            self.env.borrow_mut().insert_fvar_name(name, sym);
+            inserted.push(name);
+        }
+        let r = f();
+        let mut env = self.env.borrow_mut();
+        for name in inserted {
+            env.fvar_names.remove(&name);
+        }
+        r
+    }
+
+    pub fn fmt_fvar(&self, name: Name, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.env.borrow().fvar_names.get(&name) {
+            Some((sym, 0)) => write!(f, "{sym}"),
+            Some((sym, n)) => write!(f, "{sym}#{n}"),
+            None => write!(f, "{name:?}"),
+        }
+    }
+
     pub fn fmt_bound_vars(
         &self,
         left: &str,
@@ -451,6 +506,11 @@ impl Pretty for DefId {
     }
 }
 
+/// Prints `t` in surface syntax, see [`PrettyCx::surface`].
+pub fn to_surface_string<T: Pretty>(tcx: TyCtxt, t: &T) -> String {
+    format!("{:?}", WithCx::new(&PrettyCx::surface(tcx), t))
+}
+
 pub fn def_id_to_string(def_id: DefId) -> String {
     rustc_middle::ty::tls::with(|tcx| format!("{:?}", WithCx::new(&PrettyCx::default(tcx), def_id)))
 }
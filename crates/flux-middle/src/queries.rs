@@ -3,7 +3,10 @@ use std::{
     rc::Rc,
 };
 
-use flux_common::iter::IterExt;
+use flux_common::{
+    iter::IterExt,
+    timings::{self, Phase},
+};
 use flux_errors::{ErrorGuaranteed, E0999};
 use itertools::Itertools;
 use rustc_data_structures::unord::{ExtendUnord, UnordMap};
@@ -18,6 +21,7 @@ use rustc_span::{Span, Symbol};
 use rustc_trait_selection::traits::NormalizeExt;
 
 use crate::{
+    cstore::CrateStoreDyn,
     fhir::{self, FluxLocalDefId},
     global_env::GlobalEnv,
     intern::List,
@@ -79,6 +83,8 @@ pub struct Providers {
         LocalDefId,
     ) -> QueryResult<rty::Opaqueness<rty::EarlyBinder<rty::PolyVariants>>>,
     pub fn_sig: fn(GlobalEnv, LocalDefId) -> QueryResult<rty::EarlyBinder<rty::PolyFnSig>>,
+    pub fn_summary:
+        fn(GlobalEnv, LocalDefId) -> QueryResult<Option<rty::EarlyBinder<rty::PolyFnSig>>>,
     pub generics_of: fn(GlobalEnv, LocalDefId) -> QueryResult<rty::Generics>,
     pub refinement_generics_of: fn(GlobalEnv, LocalDefId) -> QueryResult<rty::RefinementGenerics>,
     pub predicates_of:
@@ -113,6 +119,7 @@ impl Default for Providers {
             type_of: |_, _| empty_query!(),
             variants_of: |_, _| empty_query!(),
             fn_sig: |_, _| empty_query!(),
+            fn_summary: |_, _| empty_query!(),
             generics_of: |_, _| empty_query!(),
             refinement_generics_of: |_, _| empty_query!(),
             predicates_of: |_, _| empty_query!(),
@@ -152,6 +159,7 @@ pub struct Queries<'genv, 'tcx> {
     type_of: Cache<DefId, QueryResult<rty::EarlyBinder<rty::TyCtor>>>,
     variants_of: Cache<DefId, QueryResult<rty::Opaqueness<rty::EarlyBinder<rty::PolyVariants>>>>,
     fn_sig: Cache<DefId, QueryResult<rty::EarlyBinder<rty::PolyFnSig>>>,
+    fn_summary: Cache<LocalDefId, QueryResult<Option<rty::EarlyBinder<rty::PolyFnSig>>>>,
     lower_late_bound_vars: Cache<LocalDefId, QueryResult<List<rustc::ty::BoundVariableKind>>>,
 }
 
@@ -184,6 +192,7 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
             type_of: Default::default(),
             variants_of: Default::default(),
             fn_sig: Default::default(),
+            fn_summary: Default::default(),
             lower_late_bound_vars: Default::default(),
         }
     }
@@ -221,7 +230,10 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
         if let Some(v) = self.desugar.borrow().get(&def_id) {
             return v.clone();
         }
-        match (self.providers.desugar)(genv, def_id) {
+        let nodes = timings::time(Phase::Desugar, genv.tcx(), def_id, || {
+            (self.providers.desugar)(genv, def_id)
+        });
+        match nodes {
             Ok(nodes) => {
                 let mut cache = self.desugar.borrow_mut();
                 cache.extend_unord(nodes.into_items().map(|(def_id, node)| (def_id, Ok(node))));
@@ -338,7 +350,9 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
             let def_id = extern_id.unwrap_or(def_id);
             if let Some(local_id) = def_id.as_local() {
                 (self.providers.adt_sort_def_of)(genv, local_id)
-            } else if let Some(adt_def) = genv.cstore().adt_def(def_id) {
+            } else if let Some(adt_def) =
+                lookup_cstore(genv, def_id, |cstore| cstore.adt_def(def_id))
+            {
                 Ok(adt_def.sort_def().clone())
             } else {
                 Ok(rty::AdtSortDef::new(def_id, vec![], vec![]))
@@ -351,7 +365,16 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
         genv: GlobalEnv<'genv, '_>,
         flux_id: FluxLocalDefId,
     ) -> QueryResult<Rc<rty::WfckResults<'genv>>> {
-        run_with_cache(&self.check_wf, flux_id, || (self.providers.check_wf)(genv, flux_id))
+        run_with_cache(&self.check_wf, flux_id, || {
+            match flux_id {
+                FluxLocalDefId::Rust(def_id) => {
+                    timings::time(Phase::WfCheck, genv.tcx(), def_id, || {
+                        (self.providers.check_wf)(genv, flux_id)
+                    })
+                }
+                FluxLocalDefId::Flux(_) => (self.providers.check_wf)(genv, flux_id),
+            }
+        })
     }
 
     pub(crate) fn adt_def(&self, genv: GlobalEnv, def_id: DefId) -> QueryResult<rty::AdtDef> {
@@ -360,7 +383,9 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
             let def_id = extern_id.unwrap_or(def_id);
             if let Some(local_id) = def_id.as_local() {
                 (self.providers.adt_def)(genv, local_id)
-            } else if let Some(adt_def) = genv.cstore().adt_def(def_id) {
+            } else if let Some(adt_def) =
+                lookup_cstore(genv, def_id, |cstore| cstore.adt_def(def_id))
+            {
                 Ok(adt_def.clone())
             } else {
                 let adt_def = if let Some(extern_id) = extern_id {
@@ -378,6 +403,10 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
             let def_id = lookup_extern(genv, def_id).unwrap_or(def_id);
             if let Some(local_id) = def_id.as_local() {
                 (self.providers.generics_of)(genv, local_id)
+            } else if let Some(generics) =
+                lookup_cstore(genv, def_id, |cstore| cstore.generics_of(def_id))
+            {
+                Ok(generics.clone())
             } else {
                 let generics = genv.lower_generics_of(def_id)?;
                 refining::refine_generics(&generics)
@@ -394,6 +423,10 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
             let def_id = lookup_extern(genv, def_id).unwrap_or(def_id);
             if let Some(local_id) = def_id.as_local() {
                 (self.providers.refinement_generics_of)(genv, local_id)
+            } else if let Some(generics) =
+                lookup_cstore(genv, def_id, |cstore| cstore.refinement_generics_of(def_id))
+            {
+                Ok(generics.clone())
             } else {
                 let parent = genv.tcx().generics_of(def_id).parent;
                 Ok(rty::RefinementGenerics { parent, parent_count: 0, params: List::empty() })
@@ -434,6 +467,10 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
 
             if let Some(local_id) = def_id.as_local() {
                 (self.providers.predicates_of)(genv, local_id)
+            } else if let Some(predicates) =
+                lookup_cstore(genv, def_id, |cstore| cstore.predicates_of(def_id))
+            {
+                Ok(predicates.clone())
             } else {
                 let predicates = genv.lower_predicates_of(def_id)?;
                 let predicates = Refiner::default(genv, &genv.generics_of(def_id)?)
@@ -452,6 +489,10 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
             let def_id = lookup_extern(genv, def_id).unwrap_or(def_id);
             if let Some(local_id) = def_id.as_local() {
                 (self.providers.assoc_refinements_of)(genv, local_id)
+            } else if let Some(assoc_refinements) =
+                lookup_cstore(genv, def_id, |cstore| cstore.assoc_refinements_of(def_id))
+            {
+                Ok(assoc_refinements.clone())
             } else {
                 Ok(rty::AssocRefinements::default())
             }
@@ -468,8 +509,14 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
             let impl_id = lookup_extern(genv, impl_id).unwrap_or(impl_id);
             if let Some(local_id) = impl_id.as_local() {
                 (self.providers.assoc_refinement_def)(genv, local_id, name)
+            } else if let Some(lambda) =
+                lookup_cstore(genv, impl_id, |cstore| cstore.assoc_refinement_def(impl_id, name))
+            {
+                Ok(lambda.clone())
             } else {
-                todo!("implement for external crates")
+                flux_common::bug!(
+                    "no definition for associated refinement `{name}` in `{impl_id:?}`"
+                )
             }
         })
     }
@@ -484,8 +531,12 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
             let impl_id = lookup_extern(genv, def_id).unwrap_or(def_id);
             if let Some(local_id) = impl_id.as_local() {
                 (self.providers.sort_of_assoc_reft)(genv, local_id, name)
+            } else if let Some(sort) =
+                lookup_cstore(genv, impl_id, |cstore| cstore.sort_of_assoc_reft(impl_id, name))
+            {
+                Ok(sort.clone())
             } else {
-                todo!("implement for external crates")
+                Ok(None)
             }
         })
     }
@@ -498,7 +549,7 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
         run_with_cache(&self.type_of, def_id, || {
             if let Some(local_id) = def_id.as_local() {
                 (self.providers.type_of)(genv, local_id)
-            } else if let Some(ty) = genv.cstore().type_of(def_id) {
+            } else if let Some(ty) = lookup_cstore(genv, def_id, |cstore| cstore.type_of(def_id)) {
                 Ok(ty.clone())
             } else {
                 // If we're given a type parameter, provide the generics of the parent container.
@@ -526,7 +577,9 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
             };
             if let Some(local_id) = def_id.as_local() {
                 (self.providers.variants_of)(genv, local_id)
-            } else if let Some(variants) = genv.cstore().variants(def_id) {
+            } else if let Some(variants) =
+                lookup_cstore(genv, def_id, |cstore| cstore.variants(def_id))
+            {
                 Ok(variants.map(|variants| variants.map(List::from)))
             } else {
                 let variants = genv
@@ -560,7 +613,8 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
             let def_id = lookup_extern(genv, def_id).unwrap_or(def_id);
             if let Some(local_id) = def_id.as_local() {
                 (self.providers.fn_sig)(genv, local_id)
-            } else if let Some(fn_sig) = genv.cstore().fn_sig(def_id) {
+            } else if let Some(fn_sig) = lookup_cstore(genv, def_id, |cstore| cstore.fn_sig(def_id))
+            {
                 Ok(fn_sig)
             } else {
                 let fn_sig = genv.lower_fn_sig(def_id)?.skip_binder();
@@ -571,6 +625,14 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
         })
     }
 
+    pub(crate) fn fn_summary(
+        &self,
+        genv: GlobalEnv,
+        def_id: LocalDefId,
+    ) -> QueryResult<Option<rty::EarlyBinder<rty::PolyFnSig>>> {
+        run_with_cache(&self.fn_summary, def_id, || (self.providers.fn_summary)(genv, def_id))
+    }
+
     pub(crate) fn lower_late_bound_vars(
         &self,
         genv: GlobalEnv,
@@ -591,6 +653,21 @@ fn lookup_extern(genv: GlobalEnv, extern_def_id: DefId) -> Option<DefId> {
         .map(LocalDefId::to_def_id)
 }
 
+/// Looks up the refinements of `def_id` saved in the metadata of other crates, skipping the
+/// built-in specs in groups dropped because the crate gives its own extern specs.
+fn lookup_cstore<'genv, T>(
+    genv: GlobalEnv<'genv, '_>,
+    def_id: DefId,
+    f: impl FnOnce(&'genv CrateStoreDyn) -> Option<T>,
+) -> Option<T> {
+    if let Some(group) = genv.cstore().prelude_group(def_id)
+        && genv.collect_specs().dropped_prelude_groups.contains(&group)
+    {
+        return None;
+    }
+    f(genv.cstore())
+}
+
 fn run_with_cache<K, V>(cache: &Cache<K, V>, key: K, f: impl FnOnce() -> V) -> V
 where
     K: std::hash::Hash + Eq,
@@ -6,7 +6,10 @@ use itertools::Itertools;
 use rustc_hir::def_id::DefId;
 use rustc_index::newtype_index;
 use rustc_macros::{Decodable, Encodable, TyDecodable, TyEncodable};
-use rustc_middle::{mir::Local, ty::TyCtxt};
+use rustc_middle::{
+    mir::Local,
+    ty::{ParamConst, TyCtxt},
+};
 use rustc_span::{BytePos, Span, Symbol, SyntaxContext};
 use rustc_target::abi::FieldIdx;
 use rustc_type_ir::{DebruijnIndex, INNERMOST};
@@ -262,21 +265,23 @@ pub struct BoundReft {
     pub kind: BoundReftKind,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Encodable, Decodable)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, TyEncodable, TyDecodable)]
 pub enum Var {
     Free(Name),
     LateBound(DebruijnIndex, BoundReft),
     EarlyParam(EarlyReftParam),
     EVar(EVar),
+    /// The value of a const generic parameter, e.g., the `N` in `[T; N]`
+    ConstGeneric(ParamConst),
 }
 
-#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Encodable, Decodable)]
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, TyEncodable, TyDecodable)]
 pub struct Path {
     pub loc: Loc,
     projection: List<FieldIdx>,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Encodable, Decodable)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, TyEncodable, TyDecodable)]
 pub enum Loc {
     Local(Local),
     Var(Var),
@@ -317,6 +322,11 @@ impl Expr {
         }
     }
 
+    /// Returns the same expression attributed to `espan`, replacing its span if it already had one
+    pub fn at(&self, espan: ESpan) -> Expr {
+        self.kind().clone().intern_at(Some(espan))
+    }
+
     pub fn span(&self) -> Option<ESpan> {
         self.espan.as_ref().copied()
     }
@@ -422,6 +432,10 @@ impl Expr {
         Var::EarlyParam(EarlyReftParam { index, name }).to_expr()
     }
 
+    pub fn const_generic(param: ParamConst) -> Expr {
+        Var::ConstGeneric(param).to_expr()
+    }
+
     pub fn local(local: Local, espan: Option<ESpan>) -> Expr {
         ExprKind::Local(local).intern_at(espan)
     }
@@ -578,11 +592,53 @@ impl Expr {
         !matches!(self.kind, ExprKind::Abs(..) | ExprKind::BinaryOp(..))
     }
 
-    /// Simple syntactic check to see if the expression is a trivially true predicate. This is used
-    /// mostly for filtering predicates when pretty printing but also to simplify types in general.
+    /// Whether the expression is valid without calling the solver, i.e., it is literally `true`, a
+    /// reflexive relation, a ground expression evaluating to `true`, or a boolean combination of
+    /// these. This is used for filtering predicates when pretty printing, to simplify types, and to
+    /// discharge trivial obligations during constraint generation.
     pub fn is_trivially_true(&self) -> bool {
-        self.is_true()
-            || matches!(self.kind(), ExprKind::BinaryOp(BinOp::Eq | BinOp::Iff | BinOp::Imp, e1, e2) if e1 == e2)
+        match self.kind() {
+            ExprKind::BinaryOp(
+                BinOp::Eq | BinOp::Iff | BinOp::Imp | BinOp::Le(_) | BinOp::Ge(_),
+                e1,
+                e2,
+            ) if e1 == e2 => true,
+            ExprKind::BinaryOp(BinOp::And, e1, e2) => {
+                e1.is_trivially_true() && e2.is_trivially_true()
+            }
+            ExprKind::BinaryOp(BinOp::Or, e1, e2) => {
+                e1.is_trivially_true() || e2.is_trivially_true()
+            }
+            ExprKind::BinaryOp(BinOp::Imp, _, e2) if e2.is_trivially_true() => true,
+            _ => self.is_true() || self.eval_const() == Some(Constant::TRUE),
+        }
+    }
+
+    /// Whether the expression is literally `false` or a ground expression evaluating to `false`.
+    pub fn is_trivially_false(&self) -> bool {
+        self.eval_const() == Some(Constant::FALSE)
+    }
+
+    /// Evaluates a ground expression to a constant. Returns `None` if the expression contains
+    /// variables or operations we don't know how to evaluate.
+    fn eval_const(&self) -> Option<Constant> {
+        match self.kind() {
+            ExprKind::Constant(c) => Some(*c),
+            ExprKind::BinaryOp(op, e1, e2) => {
+                let c1 = e1.eval_const()?;
+                let c2 = e2.eval_const()?;
+                // Arithmetic is only folded here, `simplify` leaves it to the solver
+                match op {
+                    BinOp::Add => c1.add(&c2),
+                    BinOp::Sub => c1.sub(&c2),
+                    BinOp::Mul => c1.mul(&c2),
+                    _ => Expr::const_op(op, &c1, &c2),
+                }
+            }
+            ExprKind::UnaryOp(UnOp::Not, e) => e.eval_const()?.not(),
+            ExprKind::UnaryOp(UnOp::Neg, e) => e.eval_const()?.neg(),
+            _ => None,
+        }
     }
 
     /// Whether the expression is *literally* the constant true.
@@ -634,6 +690,21 @@ impl Expr {
                             }
                             (BinOp::And, ExprKind::Constant(Constant::Bool(true)), _) => e2,
                             (BinOp::And, _, ExprKind::Constant(Constant::Bool(true))) => e1,
+                            (BinOp::Or, ExprKind::Constant(Constant::Bool(true)), _) => {
+                                Expr::constant_at(Constant::Bool(true), e1_span)
+                            }
+                            (BinOp::Or, _, ExprKind::Constant(Constant::Bool(true))) => {
+                                Expr::constant_at(Constant::Bool(true), e2_span)
+                            }
+                            (BinOp::Or, ExprKind::Constant(Constant::Bool(false)), _) => e2,
+                            (BinOp::Or, _, ExprKind::Constant(Constant::Bool(false))) => e1,
+                            (BinOp::Imp, ExprKind::Constant(Constant::Bool(true)), _) => e2,
+                            (BinOp::Imp, ExprKind::Constant(Constant::Bool(false)), _) => {
+                                Expr::constant_at(Constant::Bool(true), e1_span)
+                            }
+                            (BinOp::Imp, _, ExprKind::Constant(Constant::Bool(true))) => {
+                                Expr::constant_at(Constant::Bool(true), e2_span)
+                            }
                             (op, ExprKind::Constant(c1), ExprKind::Constant(c2)) => {
                                 let e2_span = e2.span();
                                 match Expr::const_op(op, c1, c2) {
@@ -1018,8 +1089,9 @@ mod pretty {
             match self {
                 Var::LateBound(debruijn, var) => cx.fmt_bound_reft(*debruijn, *var, f),
                 Var::EarlyParam(var) => w!("{}", ^var.name),
-                Var::Free(name) => w!("{:?}", ^name),
+                Var::Free(name) => cx.fmt_fvar(*name, f),
                 Var::EVar(evar) => w!("{:?}", evar),
+                Var::ConstGeneric(param) => w!("{}", ^param.name),
             }
         }
     }
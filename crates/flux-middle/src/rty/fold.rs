@@ -14,11 +14,11 @@ use super::{
     normalize::{Normalizer, SpecFuncDefns},
     projections,
     subst::EVarSubstFolder,
-    AliasReft, AliasTy, BaseTy, BinOp, Binder, BoundVariableKind, Clause, ClauseKind, Constraint,
-    CoroutineObligPredicate, Expr, ExprKind, FnOutput, FnSig, FnTraitPredicate, FuncSort,
-    GenericArg, Invariant, KVar, Lambda, Name, Opaqueness, OutlivesPredicate, PolyFuncSort,
-    ProjectionPredicate, PtrKind, Qualifier, ReLateBound, Region, Sort, SubsetTy, TraitPredicate,
-    TraitRef, Ty, TyKind,
+    AliasReft, AliasTy, BaseTy, BinOp, Binder, BoundVariableKind, Clause, ClauseKind, Const,
+    Constraint, CoroutineObligPredicate, Expr, ExprKind, FnOutput, FnSig, FnTraitPredicate,
+    FuncSort, GenericArg, Invariant, KVar, Lambda, Name, Opaqueness, OutlivesPredicate,
+    PolyFuncSort, ProjectionPredicate, PtrKind, Qualifier, ReLateBound, Region, Sort, SubsetTy,
+    TraitPredicate, TraitRef, Ty, TyKind,
 };
 use crate::{
     global_env::GlobalEnv,
@@ -85,6 +85,10 @@ pub trait FallibleTypeFolder: Sized {
         Ok(*re)
     }
 
+    fn try_fold_const(&mut self, c: &Const) -> Result<Const, Self::Error> {
+        Ok(c.clone())
+    }
+
     fn try_fold_expr(&mut self, expr: &Expr) -> Result<Expr, Self::Error> {
         expr.try_super_fold_with(self)
     }
@@ -115,6 +119,10 @@ pub trait TypeFolder: FallibleTypeFolder<Error = !> {
         *re
     }
 
+    fn fold_const(&mut self, c: &Const) -> Const {
+        c.clone()
+    }
+
     fn fold_expr(&mut self, expr: &Expr) -> Expr {
         expr.super_fold_with(self)
     }
@@ -153,6 +161,10 @@ where
         Ok(self.fold_region(re))
     }
 
+    fn try_fold_const(&mut self, c: &Const) -> Result<Const, Self::Error> {
+        Ok(self.fold_const(c))
+    }
+
     fn try_fold_expr(&mut self, expr: &Expr) -> Result<Expr, Self::Error> {
         Ok(self.fold_expr(expr))
     }
@@ -816,11 +828,24 @@ impl TypeFoldable for Ty {
 
 impl TypeSuperFoldable for Ty {
     fn try_super_fold_with<F: FallibleTypeFolder>(&self, folder: &mut F) -> Result<Ty, F::Error> {
+        // See the comment in the implementation for `Expr`
         let ty = match self.kind() {
             TyKind::Indexed(bty, idxs) => {
-                Ty::indexed(bty.try_fold_with(folder)?, idxs.try_fold_with(folder)?)
+                let (bty1, idxs1) = (bty.try_fold_with(folder)?, idxs.try_fold_with(folder)?);
+                if bty1 == *bty && idxs1 == *idxs {
+                    self.clone()
+                } else {
+                    Ty::indexed(bty1, idxs1)
+                }
+            }
+            TyKind::Exists(exists) => {
+                let exists1 = exists.try_fold_with(folder)?;
+                if exists1 == *exists {
+                    self.clone()
+                } else {
+                    TyKind::Exists(exists1).intern()
+                }
             }
-            TyKind::Exists(exists) => TyKind::Exists(exists.try_fold_with(folder)?).intern(),
             TyKind::Ptr(pk, path) => {
                 let pk = match pk {
                     PtrKind::Shr(re) => PtrKind::Shr(re.try_fold_with(folder)?),
@@ -837,7 +862,12 @@ impl TypeSuperFoldable for Ty {
                 )
             }
             TyKind::Constr(pred, ty) => {
-                Ty::constr(pred.try_fold_with(folder)?, ty.try_fold_with(folder)?)
+                let (pred1, ty1) = (pred.try_fold_with(folder)?, ty.try_fold_with(folder)?);
+                if pred1 == *pred && ty1 == *ty {
+                    self.clone()
+                } else {
+                    Ty::constr(pred1, ty1)
+                }
             }
             TyKind::Downcast(adt, args, ty, variant, fields) => {
                 Ty::downcast(
@@ -868,6 +898,18 @@ impl TypeFoldable for Region {
     }
 }
 
+impl TypeVisitable for Const {
+    fn visit_with<V: TypeVisitor>(&self, _visitor: &mut V) -> ControlFlow<V::BreakTy> {
+        ControlFlow::Continue(())
+    }
+}
+
+impl TypeFoldable for Const {
+    fn try_fold_with<F: FallibleTypeFolder>(&self, folder: &mut F) -> Result<Self, F::Error> {
+        folder.try_fold_const(self)
+    }
+}
+
 impl TypeVisitable for BaseTy {
     fn visit_with<V: TypeVisitor>(&self, visitor: &mut V) -> ControlFlow<V::BreakTy> {
         visitor.visit_bty(self)
@@ -887,6 +929,7 @@ impl TypeSuperVisitable for BaseTy {
                 resume_ty.visit_with(visitor)?;
                 upvars.visit_with(visitor)
             }
+            BaseTy::FnPtr(fn_sig) => fn_sig.visit_with(visitor),
             BaseTy::Int(_)
             | BaseTy::Uint(_)
             | BaseTy::Bool
@@ -916,7 +959,9 @@ impl TypeSuperFoldable for BaseTy {
                 BaseTy::Ref(re.try_fold_with(folder)?, ty.try_fold_with(folder)?, *mutbl)
             }
             BaseTy::Tuple(tys) => BaseTy::Tuple(tys.try_fold_with(folder)?),
-            BaseTy::Array(ty, c) => BaseTy::Array(ty.try_fold_with(folder)?, c.clone()),
+            BaseTy::Array(ty, c) => {
+                BaseTy::Array(ty.try_fold_with(folder)?, c.try_fold_with(folder)?)
+            }
             BaseTy::Int(_)
             | BaseTy::Param(_)
             | BaseTy::Uint(_)
@@ -926,6 +971,7 @@ impl TypeSuperFoldable for BaseTy {
             | BaseTy::Char
             | BaseTy::Never => self.clone(),
             BaseTy::Closure(did, args) => BaseTy::Closure(*did, args.try_fold_with(folder)?),
+            BaseTy::FnPtr(fn_sig) => BaseTy::FnPtr(fn_sig.try_fold_with(folder)?),
             BaseTy::Coroutine(did, resume_ty, args) => {
                 BaseTy::Coroutine(
                     *did,
@@ -995,7 +1041,7 @@ impl TypeFoldable for GenericArg {
             GenericArg::Ty(ty) => GenericArg::Ty(ty.try_fold_with(folder)?),
             GenericArg::Base(sty) => GenericArg::Base(sty.try_fold_with(folder)?),
             GenericArg::Lifetime(re) => GenericArg::Lifetime(re.try_fold_with(folder)?),
-            GenericArg::Const(c) => GenericArg::Const(c.clone()),
+            GenericArg::Const(c) => GenericArg::Const(c.try_fold_with(folder)?),
         };
         Ok(arg)
     }
@@ -1079,7 +1125,9 @@ impl TypeVisitable for Var {
     fn visit_with<V: TypeVisitor>(&self, visitor: &mut V) -> ControlFlow<V::BreakTy> {
         match self {
             Var::Free(name) => visitor.visit_fvar(*name),
-            Var::LateBound(_, _) | Var::EarlyParam(_) | Var::EVar(_) => ControlFlow::Continue(()),
+            Var::LateBound(_, _) | Var::EarlyParam(_) | Var::EVar(_) | Var::ConstGeneric(_) => {
+                ControlFlow::Continue(())
+            }
         }
     }
 }
@@ -1100,42 +1148,83 @@ impl TypeFoldable for Expr {
 
 impl TypeSuperFoldable for Expr {
     fn try_super_fold_with<F: FallibleTypeFolder>(&self, folder: &mut F) -> Result<Self, F::Error> {
+        // Expressions are hash-consed, so we can check whether folding changed a subexpression by
+        // comparing pointers. If nothing changed, we return the expression itself to avoid
+        // interning a copy of it.
         let span = self.span();
         let expr = match self.kind() {
-            ExprKind::Var(var) => Expr::var(*var, span),
-            ExprKind::Local(local) => Expr::local(*local, span),
-            ExprKind::Constant(c) => Expr::constant_at(*c, span),
-            ExprKind::ConstDefId(did) => Expr::const_def_id(*did, span),
+            ExprKind::Var(_)
+            | ExprKind::Local(_)
+            | ExprKind::Constant(_)
+            | ExprKind::ConstDefId(_)
+            | ExprKind::GlobalFunc(..) => self.clone(),
             ExprKind::BinaryOp(op, e1, e2) => {
-                Expr::binary_op(
+                let (op1, e11, e21) = (
                     op.try_fold_with(folder)?,
                     e1.try_fold_with(folder)?,
                     e2.try_fold_with(folder)?,
-                    span,
-                )
+                );
+                if op1 == *op && e11 == *e1 && e21 == *e2 {
+                    self.clone()
+                } else {
+                    Expr::binary_op(op1, e11, e21, span)
+                }
+            }
+            ExprKind::UnaryOp(op, e) => {
+                let e1 = e.try_fold_with(folder)?;
+                if e1 == *e {
+                    self.clone()
+                } else {
+                    Expr::unary_op(*op, e1, span)
+                }
+            }
+            ExprKind::FieldProj(e, proj) => {
+                let e1 = e.try_fold_with(folder)?;
+                if e1 == *e {
+                    self.clone()
+                } else {
+                    Expr::field_proj(e1, *proj, span)
+                }
             }
-            ExprKind::UnaryOp(op, e) => Expr::unary_op(*op, e.try_fold_with(folder)?, span),
-            ExprKind::FieldProj(e, proj) => Expr::field_proj(e.try_fold_with(folder)?, *proj, span),
             ExprKind::Aggregate(kind, flds) => {
-                let flds = flds.iter().map(|e| e.try_fold_with(folder)).try_collect()?;
-                Expr::aggregate(*kind, flds)
+                let flds1 = flds.try_fold_with(folder)?;
+                if flds1 == *flds {
+                    self.clone()
+                } else {
+                    Expr::aggregate(*kind, flds1)
+                }
+            }
+            ExprKind::PathProj(e, field) => {
+                let e1 = e.try_fold_with(folder)?;
+                if e1 == *e {
+                    self.clone()
+                } else {
+                    Expr::path_proj(e1, *field)
+                }
             }
-            ExprKind::PathProj(e, field) => Expr::path_proj(e.try_fold_with(folder)?, *field),
             ExprKind::App(func, arg) => {
-                Expr::app(func.try_fold_with(folder)?, arg.try_fold_with(folder)?, span)
+                let (func1, arg1) = (func.try_fold_with(folder)?, arg.try_fold_with(folder)?);
+                if func1 == *func && arg1 == *arg {
+                    self.clone()
+                } else {
+                    Expr::app(func1, arg1, span)
+                }
             }
             ExprKind::IfThenElse(p, e1, e2) => {
-                Expr::ite(
+                let (p1, e11, e21) = (
                     p.try_fold_with(folder)?,
                     e1.try_fold_with(folder)?,
                     e2.try_fold_with(folder)?,
-                    span,
-                )
+                );
+                if p1 == *p && e11 == *e1 && e21 == *e2 {
+                    self.clone()
+                } else {
+                    Expr::ite(p1, e11, e21, span)
+                }
             }
             ExprKind::Hole(kind) => Expr::hole(kind.try_fold_with(folder)?),
             ExprKind::KVar(kvar) => Expr::kvar(kvar.try_fold_with(folder)?),
             ExprKind::Abs(lam) => Expr::abs(lam.try_fold_with(folder)?),
-            ExprKind::GlobalFunc(func, kind) => Expr::global_func(*func, *kind),
             ExprKind::Alias(alias, args) => {
                 let alias = alias.try_fold_with(folder)?;
                 let args = args.try_fold_with(folder)?;
@@ -1220,11 +1309,16 @@ where
 
 impl<T> TypeFoldable for List<T>
 where
-    T: TypeFoldable,
+    T: TypeFoldable + Eq,
     [T]: Internable,
 {
     fn try_fold_with<F: FallibleTypeFolder>(&self, folder: &mut F) -> Result<Self, F::Error> {
-        self.iter().map(|t| t.try_fold_with(folder)).try_collect()
+        let folded: Vec<T> = self.iter().map(|t| t.try_fold_with(folder)).try_collect()?;
+        if folded[..] == self[..] {
+            Ok(self.clone())
+        } else {
+            Ok(List::from_vec(folded))
+        }
     }
 }
 
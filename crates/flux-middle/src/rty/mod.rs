@@ -96,6 +96,10 @@ impl AdtSortDef {
         self.0.sorts.len()
     }
 
+    pub fn field_names(&self) -> &[Symbol] {
+        &self.0.field_names
+    }
+
     pub fn field_sort(&self, args: &[Sort], name: Symbol) -> Option<Sort> {
         let idx = self.field_index(name)?;
         Some(self.0.sorts[idx].fold_with(&mut SortSubst::new(args)))
@@ -122,27 +126,27 @@ impl AdtSortDef {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, TyEncodable, TyDecodable)]
 pub struct Generics {
     pub parent: Option<DefId>,
     pub parent_count: usize,
     pub params: List<GenericParamDef>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, TyEncodable, TyDecodable)]
 pub struct RefinementGenerics {
     pub parent: Option<DefId>,
     pub parent_count: usize,
     pub params: List<RefineParam>,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+#[derive(PartialEq, Eq, Debug, Clone, Hash, TyEncodable, TyDecodable)]
 pub struct RefineParam {
     pub sort: Sort,
     pub mode: InferMode,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
 pub struct GenericParamDef {
     pub kind: GenericParamDefKind,
     pub def_id: DefId,
@@ -150,7 +154,7 @@ pub struct GenericParamDef {
     pub name: Symbol,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Encodable, Decodable)]
 pub enum GenericParamDefKind {
     Type { has_default: bool },
     Base,
@@ -160,18 +164,18 @@ pub enum GenericParamDefKind {
 
 pub const SELF_PARAM_TY: ParamTy = ParamTy { index: 0, name: kw::SelfUpper };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, TyEncodable, TyDecodable)]
 pub struct GenericPredicates {
     pub parent: Option<DefId>,
     pub predicates: List<Clause>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, TyEncodable, TyDecodable)]
 pub struct Clause {
     kind: Binder<ClauseKind>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
 pub enum ClauseKind {
     FnTrait(FnTraitPredicate),
     Trait(TraitPredicate),
@@ -183,24 +187,24 @@ pub enum ClauseKind {
 
 pub type TypeOutlivesPredicate = OutlivesPredicate<Ty, Region>;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
 pub struct TraitPredicate {
     pub trait_ref: TraitRef,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
 pub struct TraitRef {
     pub def_id: DefId,
     pub args: GenericArgs,
 }
 
-#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, TyEncodable, TyDecodable)]
 pub struct ProjectionPredicate {
     pub projection_ty: AliasTy,
     pub term: Ty,
 }
 
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, TyEncodable, TyDecodable)]
 pub struct FnTraitPredicate {
     pub self_ty: Ty,
     pub tupled_args: Ty,
@@ -208,7 +212,7 @@ pub struct FnTraitPredicate {
     pub kind: ClosureKind,
 }
 
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, TyEncodable, TyDecodable)]
 pub struct CoroutineObligPredicate {
     pub def_id: DefId,
     pub resume_ty: Ty,
@@ -216,7 +220,7 @@ pub struct CoroutineObligPredicate {
     pub output: Ty,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, TyEncodable, TyDecodable)]
 pub struct AssocRefinements {
     pub predicates: List<AssocRefinement>,
 }
@@ -233,7 +237,7 @@ impl AssocRefinements {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, TyEncodable, TyDecodable)]
 pub struct AssocRefinement {
     /// [`DefId`] of the container, i.e., the impl block or trait.
     pub container_def_id: DefId,
@@ -506,14 +510,14 @@ pub struct EarlyBinder<T>(pub T);
 
 pub type PolyFnSig = Binder<FnSig>;
 
-#[derive(Clone, TyEncodable, TyDecodable)]
+#[derive(Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
 pub struct FnSig {
     requires: List<Constraint>,
     args: List<Ty>,
     output: Binder<FnOutput>,
 }
 
-#[derive(Clone, Debug, TyEncodable, TyDecodable)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
 pub struct FnOutput {
     pub ret: Ty,
     pub ensures: List<Constraint>,
@@ -532,19 +536,20 @@ pub enum Constraint {
     Pred(Expr),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, TyEncodable, TyDecodable)]
 pub struct Qualifier {
     pub name: Symbol,
     pub body: Binder<Expr>,
     pub global: bool,
 }
 
+#[derive(Debug, Clone, TyEncodable, TyDecodable)]
 pub struct SpecFunc {
     pub name: Symbol,
     pub expr: Binder<Expr>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, TyEncodable, TyDecodable)]
 pub struct SpecFuncDecl {
     pub name: Symbol,
     pub sort: PolyFuncSort,
@@ -836,6 +841,10 @@ pub enum BaseTy {
     Closure(DefId, /* upvar_tys */ List<Ty>),
     Coroutine(DefId, /*resume_ty: */ Ty, /* upvar_tys: */ List<Ty>),
     Param(ParamTy),
+    /// A function pointer. The signature is refined, e.g., a pointer of type
+    /// `fn(i32{v: v > 0}) -> i32{v: v > 0}` can only be created from functions whose signature
+    /// is a subtype of it.
+    FnPtr(PolyFnSig),
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug, TyEncodable, TyDecodable)]
@@ -1032,7 +1041,7 @@ impl GenericArg {
                 ty::GenericArg::from(ctor.as_ref().skip_binder().to_rustc(tcx))
             }
             GenericArg::Lifetime(re) => ty::GenericArg::from(re.to_rustc(tcx)),
-            GenericArg::Const(_) => todo!(),
+            GenericArg::Const(c) => ty::GenericArg::from(c.to_rustc(tcx)),
         }
     }
 }
@@ -1389,7 +1398,7 @@ where
     pub fn replace_bound_vars(
         &self,
         replace_region: impl FnMut(BoundRegion) -> Region,
-        mut replace_expr: impl FnMut(&Sort, InferMode) -> Expr,
+        mut replace_expr: impl FnMut(&Sort, InferMode, BoundReftKind) -> Expr,
     ) -> T {
         let mut exprs = UnordMap::default();
         let delegate = FnMutDelegate::new(
@@ -1397,8 +1406,8 @@ where
                 exprs
                     .entry(var.index)
                     .or_insert_with(|| {
-                        let (sort, mode, _) = self.vars[var.index as usize].expect_refine();
-                        replace_expr(sort, mode)
+                        let (sort, mode, kind) = self.vars[var.index as usize].expect_refine();
+                        replace_expr(sort, mode, kind)
                     })
                     .clone()
             },
@@ -1489,6 +1498,30 @@ impl EarlyBinder<GenericPredicates> {
         Ok(predicates)
     }
 
+    /// Like `instantiate_identity` but instantiating the predicates (and the ones of the parents)
+    /// with `args` and `refine_args`.
+    pub fn instantiate(
+        self,
+        genv: GlobalEnv,
+        args: &[GenericArg],
+        refine_args: &[Expr],
+    ) -> QueryResult<Vec<Clause>> {
+        let mut predicates = vec![];
+        if let Some(def_id) = self.0.parent {
+            predicates.extend(
+                genv.predicates_of(def_id)?
+                    .instantiate(genv, args, refine_args)?,
+            );
+        }
+        predicates.extend(
+            self.predicates()
+                .instantiate(args, refine_args)
+                .iter()
+                .cloned(),
+        );
+        Ok(predicates)
+    }
+
     fn instantiate_identity_into(
         self,
         genv: GlobalEnv,
@@ -1547,6 +1580,32 @@ impl FnSig {
     }
 }
 
+impl PolyFnSig {
+    /// Converts the signature back to an unrefined rustc signature. We don't track safety nor abi,
+    /// so we assume a safe signature with the Rust abi.
+    fn to_rustc<'tcx>(&self, tcx: TyCtxt<'tcx>) -> rustc_middle::ty::PolyFnSig<'tcx> {
+        use rustc_middle::ty;
+        let vars = tcx.mk_bound_variable_kinds_from_iter(self.vars().iter().filter_map(|var| {
+            if let BoundVariableKind::Region(kind) = var {
+                Some(ty::BoundVariableKind::Region(*kind))
+            } else {
+                None
+            }
+        }));
+        let fn_sig = self.as_ref().skip_binder();
+        let inputs = fn_sig.args().iter().map(|ty| ty.to_rustc(tcx));
+        let output = fn_sig.output().as_ref().skip_binder().ret.to_rustc(tcx);
+        let fn_sig = tcx.mk_fn_sig(
+            inputs,
+            output,
+            false,
+            rustc_hir::Unsafety::Normal,
+            rustc_target::spec::abi::Abi::Rust,
+        );
+        ty::Binder::bind_with_vars(fn_sig, vars)
+    }
+}
+
 impl FnOutput {
     pub fn new(ret: Ty, ensures: impl Into<List<Constraint>>) -> Self {
         Self { ret, ensures: ensures.into() }
@@ -1782,7 +1841,7 @@ impl BaseTy {
         }
     }
 
-    fn into_ty(self) -> Ty {
+    pub fn into_ty(self) -> Ty {
         let sort = self.sort();
         if sort.is_unit() {
             Ty::indexed(self, Expr::unit())
@@ -1806,6 +1865,7 @@ impl BaseTy {
             | BaseTy::Array(_, _)
             | BaseTy::Closure(_, _)
             | BaseTy::Coroutine(..)
+            | BaseTy::FnPtr(_)
             | BaseTy::Never => Sort::unit(),
         }
     }
@@ -1835,8 +1895,11 @@ impl BaseTy {
                 let ts = tys.iter().map(|ty| ty.to_rustc(tcx)).collect_vec();
                 ty::Ty::new_tup(tcx, &ts)
             }
-            BaseTy::Array(_, _) => todo!(),
+            BaseTy::Array(ty, c) => {
+                ty::Ty::new_array_with_const_len(tcx, ty.to_rustc(tcx), c.to_rustc(tcx))
+            }
             BaseTy::Never => tcx.types.never,
+            BaseTy::FnPtr(fn_sig) => ty::Ty::new_fn_ptr(tcx, fn_sig.to_rustc(tcx)),
             BaseTy::Closure(_, _) => todo!(),
             BaseTy::Coroutine(def_id, resume_ty, upvars) => {
                 todo!("Generator {def_id:?} {resume_ty:?} {upvars:?}")
@@ -102,7 +102,7 @@ impl SpecFuncDefns {
         Ok(exp_defns)
     }
 
-    fn func_defn(&self, f: &Symbol) -> Option<&SpecFunc> {
+    pub fn func_defn(&self, f: &Symbol) -> Option<&SpecFunc> {
         self.defns.get(f)
     }
 }
@@ -54,7 +54,7 @@ impl Pretty for PolyFnSig {
         define_scoped!(cx, f);
         let vars = &self.vars;
         cx.with_bound_vars(vars, || {
-            if !vars.is_empty() {
+            if !vars.is_empty() && !cx.surface {
                 cx.fmt_bound_vars("for<", vars, "> ", f)?;
             }
             w!("{:?}", &self.value)
@@ -152,6 +152,13 @@ impl Pretty for PolyFuncSort {
 impl Pretty for FnSig {
     fn fmt(&self, cx: &PrettyCx, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         define_scoped!(cx, f);
+        if cx.surface {
+            w!("fn({:?}) -> {:?}", join!(", ", &self.args), &self.output)?;
+            if !self.requires.is_empty() {
+                w!(" requires {:?}", join!(" && ", &self.requires))?;
+            }
+            return Ok(());
+        }
         w!("fn(")?;
         if !self.requires.is_empty() {
             w!("[{:?}] ", join!(", ", &self.requires))?;
@@ -171,7 +178,7 @@ impl Pretty for Binder<FnOutput> {
         define_scoped!(cx, f);
         let vars = &self.vars;
         cx.with_bound_vars(vars, || {
-            if !vars.is_empty() {
+            if !vars.is_empty() && !cx.surface {
                 cx.fmt_bound_vars("exists<", vars, "> ", f)?;
             }
             w!("{:?}", &self.value)
@@ -184,7 +191,11 @@ impl Pretty for FnOutput {
         define_scoped!(cx, f);
         w!("{:?}", &self.ret)?;
         if !self.ensures.is_empty() {
-            w!("; [{:?}]", join!(", ", &self.ensures))?;
+            if cx.surface {
+                w!(" ensures {:?}", join!(", ", &self.ensures))?;
+            } else {
+                w!("; [{:?}]", join!(", ", &self.ensures))?;
+            }
         }
         Ok(())
     }
@@ -221,7 +232,7 @@ impl Pretty for TyS {
                     return Ok(());
                 }
                 if idx.is_unit() {
-                    if bty.is_adt() {
+                    if bty.is_adt() && !cx.surface {
                         w!("[]")?;
                     }
                 } else {
@@ -231,7 +242,9 @@ impl Pretty for TyS {
             }
             TyKind::Exists(Binder { vars, value: ty }) => {
                 cx.with_bound_vars(vars, || {
-                    if cx.hide_refinements {
+                    if cx.surface {
+                        fmt_exists_surface(cx, vars, ty, f)
+                    } else if cx.hide_refinements {
                         w!("{:?}", ty)
                     } else {
                         cx.fmt_bound_vars("∃", vars, ". ", f)?;
@@ -297,6 +310,37 @@ impl Pretty for TyS {
     }
 }
 
+/// Prints an existential in surface syntax: `b` if the index is unconstrained, `b{v: p}` if it's
+/// constrained by `p`, and `{v. t | p}` for the general form.
+fn fmt_exists_surface(
+    cx: &PrettyCx,
+    vars: &[BoundVariableKind],
+    ty: &Ty,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    define_scoped!(cx, f);
+    let (pred, inner) = match ty.kind() {
+        TyKind::Constr(pred, inner) => (Some(pred), inner),
+        _ => (None, ty),
+    };
+    if let [_] = vars
+        && let TyKind::Indexed(bty, idx) = inner.kind()
+        && idx.is_nu()
+    {
+        w!("{:?}", bty)?;
+        if let Some(pred) = pred {
+            cx.fmt_bound_vars("{", vars, ": ", f)?;
+            w!("{:?}}}", pred)?;
+        }
+        return Ok(());
+    }
+    cx.fmt_bound_vars("{", vars, ". ", f)?;
+    match pred {
+        Some(pred) => w!("{:?} | {:?}}}", inner, pred),
+        None => w!("{:?}}}", inner),
+    }
+}
+
 impl Pretty for PtrKind {
     fn fmt(&self, cx: &PrettyCx, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         define_scoped!(cx, f);
@@ -385,6 +429,7 @@ impl Pretty for BaseTy {
             BaseTy::Closure(did, args) => {
                 w!("Closure {:?}<{:?}>", did, args)
             }
+            BaseTy::FnPtr(fn_sig) => w!("{:?}", fn_sig),
             BaseTy::Coroutine(did, resume_ty, upvars) => {
                 w!("Coroutine({:?}, {:?})", did, resume_ty)?;
                 if !upvars.is_empty() {
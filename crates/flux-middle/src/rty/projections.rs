@@ -12,13 +12,14 @@ use rustc_trait_selection::traits::SelectionContext;
 
 use super::{
     fold::{FallibleTypeFolder, TypeFoldable, TypeSuperFoldable},
-    AliasKind, AliasReft, AliasTy, BaseTy, Binder, Clause, ClauseKind, Expr, ExprKind, GenericArg,
-    ProjectionPredicate, RefineArgs, Region, SubsetTy, Ty, TyKind,
+    AliasKind, AliasReft, AliasTy, BaseTy, Binder, Clause, ClauseKind, Const, Expr, ExprKind,
+    GenericArg, ProjectionPredicate, RefineArgs, Region, SubsetTy, Ty, TyKind,
 };
 use crate::{
     global_env::GlobalEnv,
     queries::{QueryErr, QueryResult},
     rty::fold::TypeVisitable,
+    rustc::ty::ConstKind,
 };
 
 pub(crate) struct Normalizer<'genv, 'tcx, 'cx> {
@@ -84,12 +85,22 @@ impl<'genv, 'tcx, 'cx> Normalizer<'genv, 'tcx, 'cx> {
         if candidates.len() > 1 {
             bug!("ambiguity when resolving `{obligation:?}` in {:?}", self.def_id);
         }
-        self.confirm_candidate(candidates.pop().unwrap(), obligation)
+        match self.confirm_candidate(candidates.pop().unwrap(), obligation)? {
+            // The associated type may mention other projections, e.g., the item of
+            // `Enumerate<I>` is `(usize, <I as Iterator>::Item)`
+            Some(ty) => ty.try_fold_with(self),
+            None => Ok(Ty::alias(AliasKind::Projection, obligation.clone())),
+        }
     }
 
-    fn confirm_candidate(&self, candidate: Candidate, obligation: &AliasTy) -> QueryResult<Ty> {
+    /// Returns `None` if the type cannot be determined from the impl, see below.
+    fn confirm_candidate(
+        &self,
+        candidate: Candidate,
+        obligation: &AliasTy,
+    ) -> QueryResult<Option<Ty>> {
         match candidate {
-            Candidate::ParamEnv(pred) | Candidate::TraitDef(pred) => Ok(pred.term),
+            Candidate::ParamEnv(pred) | Candidate::TraitDef(pred) => Ok(Some(pred.term)),
             Candidate::UserDefinedImpl(impl_def_id) => {
                 // Given a projection obligation
                 //     <IntoIter<{v. i32[v] | v > 0}, Global> as Iterator>::Item
@@ -113,7 +124,10 @@ impl<'genv, 'tcx, 'cx> Normalizer<'genv, 'tcx, 'cx> {
                 for (a, b) in iter::zip(&impl_trait_ref.args, &obligation.args) {
                     subst.generic_args(a, b);
                 }
-                let args = subst.finish(self.tcx(), generics);
+                // Some parameters of the impl may not appear in the self type, e.g., `B` in
+                //     impl<B, I, F> Iterator for Map<I, F> where F: FnMut(I::Item) -> B
+                // in which case we leave the projection unnormalized.
+                let Some(args) = subst.try_finish() else { return Ok(None) };
 
                 // 2. Get the associated type in the impl block and apply the substitution to it
                 let assoc_type_id = self
@@ -124,11 +138,12 @@ impl<'genv, 'tcx, 'cx> Normalizer<'genv, 'tcx, 'cx> {
                     .map(|item| item.def_id)
                     .unwrap();
 
-                Ok(self
-                    .genv
-                    .type_of(assoc_type_id)?
-                    .instantiate(&args, &[])
-                    .to_ty())
+                Ok(Some(
+                    self.genv
+                        .type_of(assoc_type_id)?
+                        .instantiate(&args, &[])
+                        .to_ty(),
+                ))
             }
         }
     }
@@ -178,7 +193,18 @@ impl<'genv, 'tcx, 'cx> Normalizer<'genv, 'tcx, 'cx> {
             alias.to_rustc_trait_ref(self.tcx()),
         );
         match self.selcx.select(&trait_pred) {
-            Ok(Some(ImplSource::UserDefined(impl_data))) => Ok(Some(impl_data.impl_def_id)),
+            Ok(Some(ImplSource::UserDefined(impl_data))) => {
+                let impl_def_id = impl_data.impl_def_id;
+                // An impl in another crate without an extern spec doesn't define the refinement, so
+                // we leave the alias uninterpreted.
+                if impl_def_id.is_local()
+                    || self.genv.get_local_id_for_extern(impl_def_id).is_some()
+                {
+                    Ok(Some(impl_def_id))
+                } else {
+                    Ok(None)
+                }
+            }
             Ok(_) => Ok(None),
             Err(e) => bug!("error selecting {trait_pred:?}: {e:?}"),
         }
@@ -268,6 +294,10 @@ impl TVarSubst {
         Self { args: vec![None; generics.count()] }
     }
 
+    fn try_finish(self) -> Option<Vec<GenericArg>> {
+        self.args.into_iter().collect()
+    }
+
     fn finish<'tcx>(
         self,
         tcx: TyCtxt<'tcx>,
@@ -291,6 +321,7 @@ impl TVarSubst {
         match (a, b) {
             (GenericArg::Ty(a), GenericArg::Ty(b)) => self.tys(a, b),
             (GenericArg::Lifetime(a), GenericArg::Lifetime(b)) => self.regions(*a, *b),
+            (GenericArg::Const(a), GenericArg::Const(b)) => self.consts(a, b),
             (GenericArg::Base(a), GenericArg::Base(b)) => {
                 self.btys(a.as_bty_skipping_binder(), b.as_bty_skipping_binder());
             }
@@ -325,8 +356,9 @@ impl TVarSubst {
                     self.generic_args(a_arg, b_arg);
                 }
             }
-            (BaseTy::Array(a_ty, _), BaseTy::Array(b_ty, _)) => {
+            (BaseTy::Array(a_ty, a_len), BaseTy::Array(b_ty, b_len)) => {
                 self.tys(a_ty, b_ty);
+                self.consts(a_len, b_len);
             }
             (BaseTy::Tuple(a_tys), BaseTy::Tuple(b_tys)) => {
                 debug_assert_eq!(a_tys.len(), b_tys.len());
@@ -351,6 +383,12 @@ impl TVarSubst {
         }
     }
 
+    fn consts(&mut self, a: &Const, b: &Const) {
+        if let ConstKind::Param(param_const) = &a.kind {
+            self.insert_generic_arg(param_const.index, GenericArg::Const(b.clone()));
+        }
+    }
+
     fn insert_generic_arg(&mut self, idx: u32, arg: GenericArg) {
         if self.args[idx as usize].replace(arg).is_some() {
             bug!("duplicate insert");
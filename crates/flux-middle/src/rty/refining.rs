@@ -356,7 +356,9 @@ impl<'genv, 'tcx> Refiner<'genv, 'tcx> {
             rustc::ty::TyKind::Str => rty::BaseTy::Str,
             rustc::ty::TyKind::Slice(ty) => rty::BaseTy::Slice(self.refine_ty(ty)?),
             rustc::ty::TyKind::Char => rty::BaseTy::Char,
-            rustc::ty::TyKind::FnPtr(_) => todo!("refine_ty: FnSig"),
+            rustc::ty::TyKind::FnPtr(fn_sig) => {
+                rty::BaseTy::FnPtr(self.as_default().refine_poly_fn_sig(fn_sig)?)
+            }
             rustc::ty::TyKind::RawPtr(ty, mu) => {
                 rty::BaseTy::RawPtr(self.as_default().refine_ty(ty)?, *mu)
             }
@@ -242,7 +242,9 @@ impl TypeFolder for EVarSubstFolder<'_> {
 /// Substitution for generics, i.e., early bound types, lifetimes, const generics, and refinements.
 /// Note that a substitution for refinement parameters (a list of expressions) must always be
 /// specified, while the behavior of other generics parameters (types, lifetimes and consts) can be
-/// configured with [`GenericsSubstDelegate`].
+/// configured with [`GenericsSubstDelegate`]. Bound variables escaping an argument are shifted when
+/// substituted under a binder, e.g., when an alias is instantiated with `i32[n]` for a generic
+/// argument that appears under the binder of its index.
 pub(crate) struct GenericsSubstFolder<'a, D> {
     current_index: DebruijnIndex,
     delegate: D,
@@ -256,6 +258,8 @@ pub trait GenericsSubstDelegate {
     fn ty_for_param(&mut self, param_ty: ParamTy) -> Ty;
     fn ctor_for_param(&mut self, param_ty: ParamTy) -> SubsetTyCtor;
     fn region_for_param(&mut self, ebr: EarlyParamRegion) -> Region;
+    fn const_for_param(&mut self, param: &Const) -> Const;
+    fn expr_for_param_const(&mut self, param_const: ParamConst) -> Expr;
 }
 
 /// The identity substitution used when checking the body of a (polymorphic) function. For example,
@@ -284,6 +288,14 @@ impl GenericsSubstDelegate for IdentitySubstDelegate {
     fn region_for_param(&mut self, ebr: EarlyParamRegion) -> Region {
         ReEarlyBound(ebr)
     }
+
+    fn const_for_param(&mut self, param: &Const) -> Const {
+        param.clone()
+    }
+
+    fn expr_for_param_const(&mut self, param_const: ParamConst) -> Expr {
+        Expr::const_generic(param_const)
+    }
 }
 
 /// A substitution with an explicit list of generic arguments.
@@ -321,6 +333,36 @@ impl GenericsSubstDelegate for GenericArgsDelegate<'_> {
             None => bug!("region parameter out of range"),
         }
     }
+
+    fn const_for_param(&mut self, param: &Const) -> Const {
+        let ConstKind::Param(param_const) = &param.kind else {
+            bug!("expected const parameter, found `{param:?}`");
+        };
+        match self.0.get(param_const.index as usize) {
+            Some(GenericArg::Const(c)) => c.clone(),
+            Some(arg) => bug!("expected const for generic parameter, found `{arg:?}`"),
+            None => bug!("const parameter out of range"),
+        }
+    }
+
+    fn expr_for_param_const(&mut self, param_const: ParamConst) -> Expr {
+        match self.0.get(param_const.index as usize) {
+            Some(GenericArg::Const(c)) => {
+                match c.kind {
+                    ConstKind::Param(param_const) => Expr::const_generic(param_const),
+                    ConstKind::Value(_) => {
+                        // Const arguments of other types are rejected when they are lowered
+                        let Some(constant) = c.to_rty_constant() else {
+                            bug!("unsupported const generic argument `{c:?}`")
+                        };
+                        Expr::constant(constant)
+                    }
+                }
+            }
+            Some(arg) => bug!("expected const for generic parameter, found `{arg:?}`"),
+            None => bug!("const parameter out of range"),
+        }
+    }
 }
 
 /// A substitution meant to be used only for sorts. It'll panic if used on a type. This is used to
@@ -362,6 +404,14 @@ where
     fn region_for_param(&mut self, ebr: EarlyParamRegion) -> Region {
         bug!("unexpected region param {ebr:?}");
     }
+
+    fn const_for_param(&mut self, param: &Const) -> Const {
+        bug!("unexpected const param {param:?}");
+    }
+
+    fn expr_for_param_const(&mut self, param_const: ParamConst) -> Expr {
+        bug!("unexpected const param {param_const:?}");
+    }
 }
 
 impl<'a, D> GenericsSubstFolder<'a, D> {
@@ -390,12 +440,18 @@ impl<D: GenericsSubstDelegate> FallibleTypeFolder for GenericsSubstFolder<'_, D>
 
     fn try_fold_ty(&mut self, ty: &Ty) -> Result<Ty, D::Error> {
         match ty.kind() {
-            TyKind::Param(param_ty) => Ok(self.delegate.ty_for_param(*param_ty)),
+            TyKind::Param(param_ty) => {
+                Ok(self
+                    .delegate
+                    .ty_for_param(*param_ty)
+                    .shift_in_escaping(self.current_index.as_u32()))
+            }
             TyKind::Indexed(BaseTy::Param(param_ty), idx) => {
                 let idx = idx.try_fold_with(self)?;
                 Ok(self
                     .delegate
                     .ctor_for_param(*param_ty)
+                    .shift_in_escaping(self.current_index.as_u32())
                     .replace_bound_reft(&idx)
                     .to_ty())
             }
@@ -408,6 +464,7 @@ impl<D: GenericsSubstDelegate> FallibleTypeFolder for GenericsSubstFolder<'_, D>
             Ok(self
                 .delegate
                 .ctor_for_param(*param_ty)
+                .shift_in_escaping(self.current_index.as_u32())
                 .replace_bound_reft(&sty.idx)
                 .strengthen(&sty.pred))
         } else {
@@ -423,11 +480,21 @@ impl<D: GenericsSubstDelegate> FallibleTypeFolder for GenericsSubstFolder<'_, D>
         }
     }
 
-    fn try_fold_expr(&mut self, expr: &Expr) -> Result<Expr, D::Error> {
-        if let ExprKind::Var(Var::EarlyParam(var)) = expr.kind() {
-            Ok(self.expr_for_param(var.index))
+    fn try_fold_const(&mut self, c: &Const) -> Result<Const, D::Error> {
+        if let ConstKind::Param(_) = c.kind {
+            Ok(self.delegate.const_for_param(c))
         } else {
-            expr.try_super_fold_with(self)
+            Ok(c.clone())
+        }
+    }
+
+    fn try_fold_expr(&mut self, expr: &Expr) -> Result<Expr, D::Error> {
+        match expr.kind() {
+            ExprKind::Var(Var::EarlyParam(var)) => Ok(self.expr_for_param(var.index)),
+            ExprKind::Var(Var::ConstGeneric(param_const)) => {
+                Ok(self.delegate.expr_for_param_const(*param_const))
+            }
+            _ => expr.try_super_fold_with(self),
         }
     }
 }
@@ -13,7 +13,7 @@ use rustc_middle::{
         TyCtxt, ValTree,
     },
 };
-use rustc_span::{def_id::LocalDefId, Span};
+use rustc_span::{def_id::LocalDefId, sym, Span};
 use rustc_trait_selection::traits::SelectionContext;
 
 use super::{
@@ -112,7 +112,42 @@ fn resolve_call_query<'tcx>(
     let (impl_def_id, impl_args) = trait_ref_impl_id(tcx, selcx, param_env, trait_ref)?;
     let assoc_id = tcx.impl_item_implementor_ids(impl_def_id).get(&callee_id)?;
     let assoc_item = tcx.associated_item(assoc_id);
-    Some((assoc_item.def_id, impl_args))
+    // The arguments of the impl method are the arguments of the impl followed by the method's own
+    let args = args.rebase_onto(tcx, trait_id, impl_args);
+    Some((assoc_item.def_id, args))
+}
+
+/// Calls to `into` and `try_into` resolved to the blanket impls of `Into` and `TryInto` (which just
+/// call `from` and `try_from`) are turned into calls to `from` and `try_from` on the target type, so
+/// they can be further resolved to the corresponding impl of `From` or `TryFrom`.
+fn resolve_blanket_conversion<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    selcx: &mut SelectionContext<'_, 'tcx>,
+    param_env: ParamEnv<'tcx>,
+    callee_id: DefId,
+    args: rustc_middle::ty::GenericArgsRef<'tcx>,
+) -> Option<(DefId, rustc_middle::ty::GenericArgsRef<'tcx>)> {
+    let trait_id = tcx.trait_of_item(callee_id)?;
+    let from_trait_id = if Some(trait_id) == tcx.get_diagnostic_item(sym::Into) {
+        tcx.get_diagnostic_item(sym::From)?
+    } else if Some(trait_id) == tcx.get_diagnostic_item(sym::TryInto) {
+        tcx.get_diagnostic_item(sym::TryFrom)?
+    } else {
+        return None;
+    };
+    let trait_ref = rustc_ty::TraitRef::from_method(tcx, trait_id, args);
+    let (impl_def_id, _) = trait_ref_impl_id(tcx, selcx, param_env, trait_ref)?;
+    // The blanket impls are the only ones whose self type is a type parameter
+    let self_ty = tcx.type_of(impl_def_id).instantiate_identity();
+    if !self_ty.is_ty_param() {
+        return None;
+    }
+    let from_fn = tcx
+        .associated_items(from_trait_id)
+        .in_definition_order()
+        .find(|item| item.kind == rustc_ty::AssocKind::Fn)?;
+    // `<T as Into<U>>::into` becomes `<U as From<T>>::from`
+    Some((from_fn.def_id, tcx.mk_args(&[args[1], args[0]])))
 }
 
 pub fn resolve_call_from<'tcx>(
@@ -275,43 +310,61 @@ impl<'sess, 'tcx> LoweringCtxt<'_, 'sess, 'tcx> {
         let kind = match &terminator.kind {
             rustc_mir::TerminatorKind::Return => TerminatorKind::Return,
             rustc_mir::TerminatorKind::Call { func, args, destination, target, unwind, .. } => {
-                let (func, generic_args) = match func.ty(self.rustc_mir, self.tcx).kind() {
-                    rustc_middle::ty::TyKind::FnDef(fn_def, args) => {
-                        let lowered = lower_generic_args(self.tcx, args)
-                            .map_err(|_err| errors::UnsupportedMir::from(terminator))
-                            .emit(self.sess)?;
-                        (*fn_def, CallArgs { orig: args, lowered })
-                    }
-                    _ => Err(errors::UnsupportedMir::from(terminator)).emit(self.sess)?,
-                };
-
                 let destination = lower_place(destination)
                     .map_err(|reason| {
                         errors::UnsupportedMir::new(span, "terminator destination", reason)
                     })
                     .emit(self.sess)?;
-
-                let resolved_call = self
-                    .resolve_call(func, generic_args.orig)
-                    .map_err(|reason| errors::UnsupportedMir::new(span, "terminator call", reason))
+                let args: Vec<_> = args
+                    .iter()
+                    .map(|arg| {
+                        self.lower_operand(&arg.node).map_err(|reason| {
+                            errors::UnsupportedMir::new(span, "terminator args", reason)
+                        })
+                    })
+                    .try_collect()
                     .emit(self.sess)?;
 
-                TerminatorKind::Call {
-                    func,
-                    generic_args,
-                    destination,
-                    target: *target,
-                    args: args
-                        .iter()
-                        .map(|arg| {
-                            self.lower_operand(&arg.node).map_err(|reason| {
-                                errors::UnsupportedMir::new(span, "terminator args", reason)
+                match func.ty(self.rustc_mir, self.tcx).kind() {
+                    rustc_middle::ty::TyKind::FnDef(fn_def, fn_args) => {
+                        let lowered = lower_generic_args(self.tcx, fn_args)
+                            .map_err(|_err| errors::UnsupportedMir::from(terminator))
+                            .emit(self.sess)?;
+                        let generic_args = CallArgs { orig: fn_args, lowered };
+
+                        let resolved_call = self
+                            .resolve_call(*fn_def, generic_args.orig)
+                            .map_err(|reason| {
+                                errors::UnsupportedMir::new(span, "terminator call", reason)
                             })
-                        })
-                        .try_collect()
-                        .emit(self.sess)?,
-                    unwind: *unwind,
-                    resolved_call,
+                            .emit(self.sess)?;
+
+                        TerminatorKind::Call {
+                            func: *fn_def,
+                            generic_args,
+                            destination,
+                            target: *target,
+                            args,
+                            unwind: *unwind,
+                            resolved_call,
+                        }
+                    }
+                    rustc_middle::ty::TyKind::FnPtr(_) => {
+                        let func = self
+                            .lower_operand(func)
+                            .map_err(|reason| {
+                                errors::UnsupportedMir::new(span, "terminator call", reason)
+                            })
+                            .emit(self.sess)?;
+                        TerminatorKind::CallIndirect {
+                            func,
+                            args,
+                            destination,
+                            target: *target,
+                            unwind: *unwind,
+                        }
+                    }
+                    _ => Err(errors::UnsupportedMir::from(terminator)).emit(self.sess)?,
                 }
             }
             rustc_mir::TerminatorKind::SwitchInt { discr, targets, .. } => {
@@ -385,6 +438,9 @@ impl<'sess, 'tcx> LoweringCtxt<'_, 'sess, 'tcx> {
         callee_id: DefId,
         args: rustc_middle::ty::GenericArgsRef<'tcx>,
     ) -> Result<(DefId, CallArgs<'tcx>), UnsupportedReason> {
+        let (callee_id, args) =
+            resolve_blanket_conversion(self.tcx, &mut self.selcx, self.param_env, callee_id, args)
+                .unwrap_or((callee_id, args));
         let (resolved_id, resolved_args) =
             resolve_call_query(self.tcx, &mut self.selcx, self.param_env, callee_id, args)
                 .unwrap_or((callee_id, args));
@@ -431,12 +487,21 @@ impl<'sess, 'tcx> LoweringCtxt<'_, 'sess, 'tcx> {
                 let kind = self.lower_cast_kind(*kind).ok_or_else(|| {
                     UnsupportedReason::new(format!("unsupported cast `{kind:?}`"))
                 })?;
-                let op = self.lower_operand(op)?;
+                let op = if let CastKind::Pointer(PointerCast::ReifyFnPointer) = kind {
+                    self.lower_fn_def_operand(op)?
+                } else {
+                    self.lower_operand(op)?
+                };
                 let ty = lower_ty(self.tcx, *ty)?;
                 Ok(Rvalue::Cast(kind, op, ty))
             }
-            rustc_mir::Rvalue::Repeat(_, _)
-            | rustc_mir::Rvalue::ThreadLocalRef(_)
+            rustc_mir::Rvalue::Repeat(op, c) => {
+                let ty = lower_ty(self.tcx, op.ty(self.rustc_mir, self.tcx))?;
+                let op = self.lower_operand(op)?;
+                let c = lower_const(self.tcx, c.normalize(self.tcx, self.param_env))?;
+                Ok(Rvalue::Repeat(op, ty, c))
+            }
+            rustc_mir::Rvalue::ThreadLocalRef(_)
             | rustc_mir::Rvalue::AddressOf(_, _)
             | rustc_mir::Rvalue::NullaryOp(_, _)
             | rustc_mir::Rvalue::CopyForDeref(_)
@@ -470,6 +535,9 @@ impl<'sess, 'tcx> LoweringCtxt<'_, 'sess, 'tcx> {
             rustc_adjustment::PointerCoercion::Unsize => {
                 Some(crate::rustc::mir::PointerCast::Unsize)
             }
+            rustc_adjustment::PointerCoercion::ReifyFnPointer => {
+                Some(crate::rustc::mir::PointerCast::ReifyFnPointer)
+            }
             _ => None,
         }
     }
@@ -552,6 +620,22 @@ impl<'sess, 'tcx> LoweringCtxt<'_, 'sess, 'tcx> {
         }
     }
 
+    /// Function items are zero-sized constants whose type is the `FnDef` of the function. We only
+    /// support them as the operand of a cast to a function pointer.
+    fn lower_fn_def_operand(
+        &self,
+        op: &rustc_mir::Operand<'tcx>,
+    ) -> Result<Operand, UnsupportedReason> {
+        if let rustc_mir::Operand::Constant(c) = op
+            && let rustc_middle::ty::TyKind::FnDef(def_id, args) = c.ty().kind()
+        {
+            let args = lower_generic_args(self.tcx, args)?;
+            Ok(Operand::Constant(Constant::FnDef(*def_id, args)))
+        } else {
+            Err(UnsupportedReason::new(format!("unsupported function pointer cast `{op:?}`")))
+        }
+    }
+
     fn lower_constant(
         &self,
         constant: &rustc_mir::ConstOperand<'tcx>,
@@ -669,7 +753,11 @@ fn lower_const<'tcx>(
         rustc_type_ir::ConstKind::Param(param_const) => {
             ConstKind::Param(ParamConst { name: param_const.name, index: param_const.index })
         }
-        rustc_type_ir::ConstKind::Value(ValTree::Leaf(scalar_int)) => ConstKind::Value(scalar_int),
+        rustc_type_ir::ConstKind::Value(ValTree::Leaf(scalar_int))
+            if c.ty().is_integral() || c.ty().is_bool() || c.ty().is_char() =>
+        {
+            ConstKind::Value(scalar_int)
+        }
         _ => return Err(UnsupportedReason::new(format!("unsupported const {c:?}"))),
     };
     Ok(Const { kind, ty: lower_ty(tcx, c.ty())? })
@@ -9,7 +9,10 @@ use flux_common::{
 use itertools::Itertools;
 pub use rustc_borrowck::borrow_set::BorrowData;
 use rustc_borrowck::consumers::{BodyWithBorrowckFacts, BorrowIndex};
-use rustc_data_structures::{fx::FxIndexMap, graph::dominators::Dominators};
+use rustc_data_structures::{
+    fx::{FxHashMap, FxIndexMap},
+    graph::dominators::Dominators,
+};
 use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_index::IndexSlice;
 use rustc_infer::infer::TyCtxtInferExt;
@@ -28,7 +31,7 @@ pub use rustc_middle::{
 use rustc_span::{Span, Symbol};
 pub use rustc_target::abi::{FieldIdx, VariantIdx, FIRST_VARIANT};
 
-use super::ty::{GenericArg, GenericArgs, Region, Ty, TyKind};
+use super::ty::{Const, GenericArg, GenericArgs, Region, Ty, TyKind};
 use crate::{
     global_env::GlobalEnv, intern::List, pretty::def_id_to_string, queries::QueryResult,
     rustc::ty::region_to_string,
@@ -119,6 +122,14 @@ pub enum TerminatorKind<'tcx> {
         unwind: UnwindAction,
         resolved_call: (DefId, CallArgs<'tcx>),
     },
+    /// A call through a function pointer, i.e., a call whose callee is not statically known.
+    CallIndirect {
+        func: Operand,
+        args: Vec<Operand>,
+        destination: Place,
+        target: Option<BasicBlock>,
+        unwind: UnwindAction,
+    },
     SwitchInt {
         discr: Operand,
         targets: SwitchTargets,
@@ -192,6 +203,9 @@ pub enum Rvalue {
     Discriminant(Place),
     Len(Place),
     Cast(CastKind, Operand, Ty),
+    /// An array of a given length filled with copies of a value, i.e., `[x; N]`, together with the
+    /// type of the elements
+    Repeat(Operand, Ty, Const),
 }
 
 pub enum BorrowKind {
@@ -212,6 +226,7 @@ pub enum CastKind {
 pub enum PointerCast {
     MutToConstPointer,
     Unsize,
+    ReifyFnPointer,
 }
 
 #[derive(Debug)]
@@ -283,6 +298,8 @@ pub enum Constant {
     Unit,
     /// General catch-all for constants of a given Ty
     Opaque(Ty),
+    /// A function item. We only support these as the operand of a [`PointerCast::ReifyFnPointer`].
+    FnDef(DefId, GenericArgs),
 }
 
 pub enum FakeReadCause {
@@ -294,6 +311,32 @@ impl<'tcx> Terminator<'tcx> {
     pub fn is_return(&self) -> bool {
         matches!(self.kind, TerminatorKind::Return)
     }
+
+    pub fn is_unreachable(&self) -> bool {
+        matches!(self.kind, TerminatorKind::Unreachable)
+    }
+
+    /// The blocks control can flow to after the terminator, ignoring unwinding and imaginary edges.
+    pub fn successors(&self) -> Vec<BasicBlock> {
+        match &self.kind {
+            TerminatorKind::Return
+            | TerminatorKind::Unreachable
+            | TerminatorKind::CoroutineDrop
+            | TerminatorKind::UnwindResume => vec![],
+            TerminatorKind::Call { target, .. } | TerminatorKind::CallIndirect { target, .. } => {
+                target.iter().copied().collect()
+            }
+            TerminatorKind::SwitchInt { targets, .. } => targets.all_targets().to_vec(),
+            TerminatorKind::Goto { target }
+            | TerminatorKind::Drop { target, .. }
+            | TerminatorKind::Assert { target, .. } => vec![*target],
+            TerminatorKind::FalseEdge { real_target, .. }
+            | TerminatorKind::FalseUnwind { real_target, .. } => vec![*real_target],
+            TerminatorKind::Yield { resume, drop, .. } => {
+                std::iter::once(*resume).chain(*drop).collect()
+            }
+        }
+    }
 }
 
 impl Statement {
@@ -375,6 +418,23 @@ impl<'tcx> Body<'tcx> {
     pub fn local_kind(&self, local: Local) -> LocalKind {
         self.body_with_facts.body.local_kind(local)
     }
+
+    /// The names of the locals that correspond to a variable in the source
+    pub fn local_names(&self) -> FxHashMap<Local, Symbol> {
+        self.rustc_body()
+            .var_debug_info
+            .iter()
+            .filter_map(|info| {
+                if let mir::VarDebugInfoContents::Place(place) = info.value
+                    && place.projection.is_empty()
+                {
+                    Some((place.local, info.name))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
 }
 
 impl Place {
@@ -553,6 +613,15 @@ impl<'tcx> fmt::Debug for Terminator<'tcx> {
                     target = opt_bb_to_str(*target),
                 )
             }
+            TerminatorKind::CallIndirect { func, args, destination, target, unwind } => {
+                write!(f, "{destination:?} = call {func:?}")?;
+                write!(
+                    f,
+                    "({args:?}) -> [return: {target}, unwind: {unwind:?}]",
+                    args = args.iter().format(", "),
+                    target = opt_bb_to_str(*target),
+                )
+            }
             TerminatorKind::SwitchInt { discr, targets } => {
                 write!(
                     f,
@@ -684,6 +753,7 @@ impl fmt::Debug for Rvalue {
             }
             Rvalue::Len(place) => write!(f, "Len({place:?})"),
             Rvalue::Cast(kind, op, ty) => write!(f, "{op:?} as {ty:?} [{kind:?}]"),
+            Rvalue::Repeat(op, _, c) => write!(f, "[{op:?}; {c:?}]"),
         }
     }
 }
@@ -693,6 +763,7 @@ impl fmt::Debug for PointerCast {
         match self {
             PointerCast::MutToConstPointer => write!(f, "MutToConstPointer"),
             PointerCast::Unsize => write!(f, "Unsize"),
+            PointerCast::ReifyFnPointer => write!(f, "ReifyFnPointer"),
         }
     }
 }
@@ -730,6 +801,13 @@ impl fmt::Debug for Constant {
             Constant::Str => write!(f, "\"<opaque str>\""),
             Constant::Char => write!(f, "\"<opaque char>\""),
             Constant::Opaque(ty) => write!(f, "<opaque {:?}>", ty),
+            Constant::FnDef(def_id, args) => {
+                write!(f, "{}", def_id_to_string(*def_id))?;
+                if !args.is_empty() {
+                    write!(f, "::<{:?}>", args.iter().format(", "))?;
+                }
+                Ok(())
+            }
         }
     }
 }
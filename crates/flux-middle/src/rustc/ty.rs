@@ -24,6 +24,7 @@ use self::subst::Subst;
 use crate::{
     intern::{impl_internable, impl_slice_internable, Interned, List},
     pretty::def_id_to_string,
+    rty,
 };
 
 #[derive(Debug, Clone)]
@@ -206,6 +207,37 @@ impl Const {
             ty: Ty::mk_uint(UintTy::Usize),
         }
     }
+
+    pub fn to_rustc<'tcx>(&self, tcx: TyCtxt<'tcx>) -> rustc_middle::ty::Const<'tcx> {
+        use rustc_middle::ty;
+        let const_ty = match self.ty.kind() {
+            TyKind::Int(int_ty) => ty::Ty::new_int(tcx, *int_ty),
+            TyKind::Uint(uint_ty) => ty::Ty::new_uint(tcx, *uint_ty),
+            TyKind::Bool => tcx.types.bool,
+            TyKind::Char => tcx.types.char,
+            _ => bug!("unsupported type for const generic `{:?}`", self.ty),
+        };
+        match &self.kind {
+            ConstKind::Param(param_const) => ty::Const::new_param(tcx, *param_const, const_ty),
+            ConstKind::Value(scalar) => {
+                ty::Const::new_value(tcx, ty::ValTree::from_scalar_int(*scalar), const_ty)
+            }
+        }
+    }
+
+    /// The value of the constant as a refinement constant, interpreting its bits according to the
+    /// type of the constant. Returns `None` if the constant is a parameter or its type has no
+    /// refinement constants, e.g., `char`.
+    pub fn to_rty_constant(&self) -> Option<rty::Constant> {
+        let ConstKind::Value(scalar) = &self.kind else { return None };
+        let size = scalar.size();
+        match self.ty.kind() {
+            TyKind::Int(_) => Some(rty::Constant::from(scalar.try_to_int(size).ok()?)),
+            TyKind::Uint(_) => Some(rty::Constant::from(scalar.try_to_uint(size).ok()?)),
+            TyKind::Bool => Some(rty::Constant::from(scalar.try_to_bool().ok()?)),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
@@ -12,8 +12,23 @@ refineck_condition_span_note =
 refineck_call_span_note =
     inside this call
 
+refineck_fact_label =
+    assuming this condition holds
+
+refineck_precondition_suggestion =
+    consider adding `{$sugg}`
+
+refineck_blame_note =
+    cannot prove `{$pred}` required by `{$def}` at {$loc}
+
+refineck_signature_help =
+    `{$def}` has signature `{$sig}`
+
 refineck_refine_error =
-    refinement type error
+    refinement type error{$has_pred ->
+        [true] : cannot prove `{$pred}`
+        *[false] {""}
+    }
     .label = a {$cond} cannot be proved
 
 refineck_div_error =
@@ -31,6 +46,31 @@ refineck_param_inference_error =
 refineck_fold_error =
     type invariant may not hold (when place is folded)
 
+refineck_fold_field_note =
+    field `{$field}` of `{$adt}` may not have its declared type
+
+refineck_timeout_error =
+    refinement checking timed out after {$secs} seconds
+    .help = the timeout can be increased with `#[flux::opts(timeout = ...)]` or `FLUX_TIMEOUT`
+
+refineck_hole_warning =
+    this code path is not checked
+    .note = paths ending in `todo!()` or `unimplemented!()` are assumed to be safe
+
+refineck_hover =
+    refined types at this point
+    .note = {$env}
+
+refineck_inferred_invariant =
+    inferred loop invariant: `{$pred}`
+
+refineck_should_fail_error =
+    function was expected to fail verification
+    .note = the function verifies but is marked with `#[flux::should_fail]`
+
+refineck_suggested_sig =
+    suggested signature: `#[flux::sig({$sig})]`
+
 refineck_unknown_error =
     cannot prove this code safe
 
@@ -40,6 +80,38 @@ refineck_overflow_error =
 refineck_opaque_struct_error =
     cannot access fields of opaque struct `{$struct}`
 
+refineck_unrefined_fn_ptr_error =
+    cannot call function pointer without a refined signature
+
+refineck_invalid_pack_error =
+    only places owned by the function can be packed or unpacked
+
+refineck_fn_ptr_type_constraint_error =
+    functions with strong references cannot be used as function pointers
+
+refineck_unknown_annot_value_error =
+    cannot refer to `{$name}` at this point
+    .note = the variable is not initialized or its value is not tracked
+
+refineck_annot_never_checked =
+    condition is never checked
+    .note = the annotated statement doesn't execute any code
+
+refineck_annot_unresolved_var =
+    cannot find value `{$ident}` in this scope
+
+refineck_annot_unsupported_var =
+    `{$ident}` cannot be used in a condition
+    .note = only variables of integer or boolean type, or shared references to them, can be used
+
+refineck_annot_unsupported_expr =
+    unsupported expression in condition
+    .note = conditions can only use variables, literals, `if`, and arithmetic, comparison and logical operators
+
+refineck_annot_mismatched_sort =
+    mismatched sorts
+    .label = expected `{$expected}`, found `{$found}`
+
 refineck_unsupported_call =
     unsupported type in function call
     .function_definition = function defined here
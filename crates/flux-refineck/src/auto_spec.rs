@@ -0,0 +1,277 @@
+//! Automatic specifications for private helper functions.
+//!
+//! A function without a `flux::sig` annotation gets a default signature which doesn't say anything
+//! about the value it returns. When [`auto_specs`] is enabled, calls to small private *leaf*
+//! functions (i.e., functions that don't call other functions) whose arguments and return value are
+//! integers or booleans use a *summary* instead. The summary is a signature indexing the returned
+//! value by an expression over the arguments, e.g., for
+//!
+//! ```ignore
+//! fn max(a: i32, b: i32) -> i32 {
+//!     if a > b { a } else { b }
+//! }
+//! ```
+//!
+//! we compute `fn(i32[@a], i32[@b]) -> i32[if a > b { a } else { b }]`. The expression is computed
+//! by symbolically evaluating the (acyclic) mir of the function, so we don't need to infer kvars.
+//! Summaries are exact on the paths that return, and the function is still checked against its own
+//! default signature, thus using them at call sites is sound.
+//!
+//! [`auto_specs`]: crate::checker::CheckerConfig::auto_specs
+use flux_middle::{
+    global_env::GlobalEnv,
+    intern::List,
+    queries::QueryResult,
+    rty::{
+        self, fold::TypeFoldable, BaseTy, BinOp, Binder, BoundReftKind, BoundVariableKind,
+        EarlyBinder, Expr, FnOutput, FnSig, InferMode, PolyFnSig, Sort, Ty, INNERMOST,
+    },
+    rustc::{
+        mir::{
+            self, BasicBlock, Body, Constant, Local, Operand, Place, PlaceElem, Rvalue,
+            StatementKind, TerminatorKind, RETURN_PLACE, START_BLOCK,
+        },
+        ty,
+    },
+};
+use rustc_hash::FxHashMap;
+use rustc_hir::{
+    def::DefKind,
+    def_id::{DefId, LocalDefId},
+};
+use rustc_index::bit_set::BitSet;
+use rustc_middle::ty::GenericArgsRef;
+
+use crate::derived;
+
+/// Maximum number of basic blocks in the body of a function with a summary
+const MAX_BLOCKS: usize = 16;
+
+/// Returns the signature to use when calling `def_id`, i.e., its built-in signature if it is a
+/// derived method (see [`crate::derived`]), its summary if `auto_specs` is enabled and it has one,
+/// or its signature otherwise.
+pub(crate) fn fn_sig_for_call<'tcx>(
+    genv: GlobalEnv<'_, 'tcx>,
+    def_id: DefId,
+    args: GenericArgsRef<'tcx>,
+    auto_specs: bool,
+) -> QueryResult<EarlyBinder<PolyFnSig>> {
+    if let Some(fn_sig) = derived::fn_sig(genv, def_id, args)? {
+        Ok(fn_sig)
+    } else if auto_specs
+        && let Some(local_id) = def_id.as_local()
+        && let Some(fn_sig) = genv.fn_summary(local_id)?
+    {
+        Ok(fn_sig)
+    } else {
+        genv.fn_sig(def_id)
+    }
+}
+
+/// Computes the summary of `def_id` by evaluating its body. This is memoized through
+/// [`GlobalEnv::fn_summary`], so each function is evaluated at most once.
+pub(crate) fn summary(
+    genv: GlobalEnv,
+    def_id: LocalDefId,
+) -> QueryResult<Option<EarlyBinder<PolyFnSig>>> {
+    if !is_candidate(genv, def_id)? {
+        return Ok(None);
+    }
+    let body = genv.mir(def_id)?;
+    if body.basic_blocks.len() > MAX_BLOCKS {
+        return Ok(None);
+    }
+
+    let mut vars = vec![];
+    let mut inputs = vec![];
+    let mut locals = FxHashMap::default();
+    for (idx, local) in body.args_iter().enumerate() {
+        let Some((bty, sort)) = scalar_bty(&body.local_decls[local].ty) else { return Ok(None) };
+        let idx = Expr::late_bvar(INNERMOST, idx as u32, BoundReftKind::Annon);
+        vars.push(BoundVariableKind::Refine(sort, InferMode::EVar, BoundReftKind::Annon));
+        inputs.push(Ty::indexed(bty, idx.clone()));
+        locals.insert(local, Value::Scalar(idx));
+    }
+    let Some((ret_bty, _)) = scalar_bty(&body.local_decls[RETURN_PLACE].ty) else {
+        return Ok(None);
+    };
+
+    let mut evaluator =
+        Evaluator { body: &body, visiting: BitSet::new_empty(body.basic_blocks.len()) };
+    let Some(ret) = evaluator.eval_block(START_BLOCK, locals) else { return Ok(None) };
+
+    // The index of the returned value is under the binder of the output
+    let ret = Ty::indexed(ret_bty, ret.shift_in_escaping(1));
+    let output = Binder::new(FnOutput::new(ret, vec![]), List::empty());
+    let fn_sig = PolyFnSig::new(FnSig::new(vec![], inputs, output), List::from_vec(vars));
+    Ok(Some(EarlyBinder(fn_sig)))
+}
+
+/// Whether `def_id` is a private non-generic function without a signature
+pub(crate) fn is_candidate(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<bool> {
+    let tcx = genv.tcx();
+    if !matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+        || tcx.visibility(def_id).is_public()
+        || !tcx.is_mir_available(def_id)
+        || tcx.generics_of(def_id).count() > 0
+    {
+        return Ok(false);
+    }
+    let fn_sig = genv.map().node(def_id)?.fn_sig().unwrap();
+    Ok(fn_sig.decl.lifted && !fn_sig.trusted && !fn_sig.inline)
+}
+
+pub(crate) fn scalar_bty(ty: &ty::Ty) -> Option<(BaseTy, Sort)> {
+    match ty.kind() {
+        ty::TyKind::Int(int_ty) => Some((BaseTy::Int(*int_ty), Sort::Int)),
+        ty::TyKind::Uint(uint_ty) => Some((BaseTy::Uint(*uint_ty), Sort::Int)),
+        ty::TyKind::Bool => Some((BaseTy::Bool, Sort::Bool)),
+        _ => None,
+    }
+}
+
+/// The symbolic value of a local
+#[derive(Clone)]
+enum Value {
+    Scalar(Expr),
+    /// The result of a [`Rvalue::CheckedBinaryOp`]. Only the result of the operation can be read
+    /// from it because the function panics if the operation overflows.
+    Checked(Expr),
+}
+
+struct Evaluator<'a, 'tcx> {
+    body: &'a Body<'tcx>,
+    /// Basic blocks in the current path, used to reject functions with loops
+    visiting: BitSet<BasicBlock>,
+}
+
+impl Evaluator<'_, '_> {
+    /// Evaluates the value returned starting from `bb`. Returns `None` if the body uses a feature we
+    /// don't support.
+    fn eval_block(&mut self, bb: BasicBlock, mut locals: FxHashMap<Local, Value>) -> Option<Expr> {
+        if !self.visiting.insert(bb) {
+            return None;
+        }
+        let data = &self.body.basic_blocks[bb];
+        for stmt in &data.statements {
+            match &stmt.kind {
+                StatementKind::Assign(place, rvalue) if place.projection.is_empty() => {
+                    let value = self.eval_rvalue(&locals, rvalue)?;
+                    locals.insert(place.local, value);
+                }
+                StatementKind::FakeRead(_)
+                | StatementKind::AscribeUserType(..)
+                | StatementKind::PlaceMention(_)
+                | StatementKind::Nop => {}
+                _ => return None,
+            }
+        }
+        let ret = match &data.terminator.as_ref()?.kind {
+            TerminatorKind::Return => self.eval_place(&locals, Place::RETURN),
+            TerminatorKind::Goto { target }
+            | TerminatorKind::Assert { target, .. }
+            | TerminatorKind::FalseEdge { real_target: target, .. }
+            | TerminatorKind::FalseUnwind { real_target: target, .. } => {
+                self.eval_block(*target, locals)
+            }
+            TerminatorKind::SwitchInt { discr, targets } => {
+                if !is_bool_operand(self.body, discr) {
+                    return None;
+                }
+                let discr = self.eval_operand(&locals, discr)?;
+                let mut ret = self.eval_block(targets.otherwise(), locals.clone())?;
+                for (value, target) in targets.iter() {
+                    let cond = if value == 0 { discr.not() } else { discr.clone() };
+                    let then = self.eval_block(target, locals.clone())?;
+                    ret = Expr::ite(cond, then, ret, None);
+                }
+                Some(ret)
+            }
+            _ => None,
+        };
+        self.visiting.remove(bb);
+        ret
+    }
+
+    fn eval_rvalue(&self, locals: &FxHashMap<Local, Value>, rvalue: &Rvalue) -> Option<Value> {
+        match rvalue {
+            Rvalue::Use(op) => Some(Value::Scalar(self.eval_operand(locals, op)?)),
+            Rvalue::BinaryOp(bin_op, op1, op2) => {
+                Some(Value::Scalar(self.eval_bin_op(locals, *bin_op, op1, op2)?))
+            }
+            Rvalue::CheckedBinaryOp(bin_op, op1, op2) => {
+                Some(Value::Checked(self.eval_bin_op(locals, *bin_op, op1, op2)?))
+            }
+            Rvalue::UnaryOp(un_op, op) => {
+                let e = self.eval_operand(locals, op)?;
+                match un_op {
+                    mir::UnOp::Not if is_bool_operand(self.body, op) => {
+                        Some(Value::Scalar(e.not()))
+                    }
+                    mir::UnOp::Neg => Some(Value::Scalar(e.neg())),
+                    mir::UnOp::Not => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn eval_bin_op(
+        &self,
+        locals: &FxHashMap<Local, Value>,
+        bin_op: mir::BinOp,
+        op1: &Operand,
+        op2: &Operand,
+    ) -> Option<Expr> {
+        let op = match bin_op {
+            mir::BinOp::Add => BinOp::Add,
+            mir::BinOp::Sub => BinOp::Sub,
+            mir::BinOp::Mul => BinOp::Mul,
+            mir::BinOp::Div => BinOp::Div,
+            mir::BinOp::Eq => BinOp::Eq,
+            mir::BinOp::Ne => BinOp::Ne,
+            mir::BinOp::Gt => BinOp::Gt(Sort::Int),
+            mir::BinOp::Ge => BinOp::Ge(Sort::Int),
+            mir::BinOp::Lt => BinOp::Lt(Sort::Int),
+            mir::BinOp::Le => BinOp::Le(Sort::Int),
+            mir::BinOp::Rem
+            | mir::BinOp::BitAnd
+            | mir::BinOp::BitOr
+            | mir::BinOp::Shl
+            | mir::BinOp::Shr => return None,
+        };
+        let e1 = self.eval_operand(locals, op1)?;
+        let e2 = self.eval_operand(locals, op2)?;
+        Some(Expr::binary_op(op, e1, e2, None))
+    }
+
+    fn eval_operand(&self, locals: &FxHashMap<Local, Value>, op: &Operand) -> Option<Expr> {
+        match op {
+            Operand::Copy(place) | Operand::Move(place) => self.eval_place(locals, place),
+            Operand::Constant(Constant::Int(n, _)) => Some(Expr::constant(rty::Constant::from(*n))),
+            Operand::Constant(Constant::Uint(n, _)) => {
+                Some(Expr::constant(rty::Constant::from(*n)))
+            }
+            Operand::Constant(Constant::Bool(b)) => Some(Expr::constant(rty::Constant::from(*b))),
+            Operand::Constant(_) => None,
+        }
+    }
+
+    fn eval_place(&self, locals: &FxHashMap<Local, Value>, place: &Place) -> Option<Expr> {
+        match (locals.get(&place.local)?, &place.projection[..]) {
+            (Value::Scalar(e), []) => Some(e.clone()),
+            (Value::Checked(e), [PlaceElem::Field(f)]) if f.as_u32() == 0 => Some(e.clone()),
+            _ => None,
+        }
+    }
+}
+
+fn is_bool_operand(body: &Body, op: &Operand) -> bool {
+    match op {
+        Operand::Copy(place) | Operand::Move(place) => {
+            place.projection.is_empty()
+                && matches!(body.local_decls[place.local].ty.kind(), ty::TyKind::Bool)
+        }
+        Operand::Constant(c) => matches!(c, Constant::Bool(_)),
+    }
+}
@@ -1,16 +1,17 @@
 use std::{collections::hash_map::Entry, iter};
 
 use flux_common::{bug, dbg, index::IndexVec, tracked_span_bug};
-use flux_config as config;
+use flux_config::{self as config, SmtSolver, SolverArgs, UnpackPolicy};
 use flux_middle::{
+    fhir,
     global_env::GlobalEnv,
     intern::List,
     queries::QueryResult,
     rty::{
         self, fold::TypeFoldable, refining::Refiner, BaseTy, BinOp, Binder, Bool, Constraint,
-        CoroutineObligPredicate, EarlyBinder, Expr, Float, FnOutput, FnSig, FnTraitPredicate,
-        GenericArg, Generics, HoleKind, Int, IntTy, Mutability, PolyFnSig, Ref, Region::ReStatic,
-        Ty, TyKind, Uint, UintTy, VariantIdx,
+        CoroutineObligPredicate, ESpan, EarlyBinder, Expr, Float, FnOutput, FnSig,
+        FnTraitPredicate, GenericArg, Generics, HoleKind, Int, IntTy, Mutability, Path, PolyFnSig,
+        PtrKind, Ref, Region::ReStatic, Ty, TyKind, Uint, UintTy, VariantIdx,
     },
     rustc::{
         self,
@@ -22,7 +23,7 @@ use flux_middle::{
         ty::{self, ConstKind},
     },
 };
-use itertools::Itertools;
+use itertools::{izip, Itertools};
 use rustc_data_structures::{graph::dominators::Dominators, unord::UnordMap};
 use rustc_hash::{FxHashMap, FxHashSet};
 use rustc_hir::{
@@ -37,14 +38,19 @@ use rustc_middle::{
 };
 use rustc_span::{sym, Span};
 
-use self::errors::{CheckerError, ResultExt};
+use self::errors::{CheckerErrKind, CheckerError, ResultExt};
 use crate::{
+    auto_spec,
     constraint_gen::{ConstrGen, ConstrReason, Obligations},
     fixpoint_encoding::{self, KVarStore},
     ghost_statements::{GhostStatement, GhostStatements, Point},
+    holes::Holes,
+    hover::Hover,
+    liveness::Liveness,
     queue::WorkQueue,
     refine_tree::{RefineCtxt, RefineSubtree, RefineTree, Snapshot},
-    sigs,
+    sig_suggestion, sigs,
+    trace::{Step, Tracer},
     type_env::{BasicBlockEnv, BasicBlockEnvShape, TypeEnv},
 };
 
@@ -54,6 +60,26 @@ type Result<T = ()> = std::result::Result<T, CheckerError>;
 pub struct CheckerConfig {
     pub check_overflow: bool,
     pub scrape_quals: bool,
+    /// Whether to pass to fixpoint the qualifiers mined from the constraint of the function
+    pub mine_quals: bool,
+    pub auto_specs: bool,
+    /// Whether reaching a call to a panicking function (e.g., through `panic!`, `unreachable!` or a
+    /// failing `assert!`) should be proven impossible.
+    pub check_panics: bool,
+    /// Whether to warn about the code paths that are not checked because they end in `todo!()` or
+    /// `unimplemented!()`.
+    pub warn_holes: bool,
+    /// How aggressively to unpack the types added to the environment, i.e., at the entry of basic
+    /// blocks, in assignments and in the results of calls.
+    pub unpack: UnpackPolicy,
+    /// The SMT solver used to discharge the constraint and extra arguments to pass to it
+    pub solver: SmtSolver,
+    pub solver_args: SolverArgs,
+    /// Whether to check constraints without kvars directly with the SMT solver, see
+    /// [`config::UnstableFeature::SmtBackend`]
+    pub smt_backend: bool,
+    /// Number of seconds after which solving the constraint is aborted, `0` means no timeout
+    pub timeout: u64,
 }
 
 pub(crate) struct Checker<'ck, 'genv, 'tcx, M> {
@@ -71,6 +97,9 @@ pub(crate) struct Checker<'ck, 'genv, 'tcx, M> {
     /// of the terminator.
     snapshots: IndexVec<BasicBlock, Option<Snapshot>>,
     visited: BitSet<BasicBlock>,
+    liveness: Liveness,
+    /// Blocks that are not checked because they end in a `todo!()` or `unimplemented!()`
+    holes: Holes,
     queue: WorkQueue<'ck>,
 }
 
@@ -82,8 +111,25 @@ struct Inherited<'ck, M> {
     ghost_stmts: &'ck UnordMap<LocalDefId, GhostStatements>,
     mode: &'ck mut M,
     config: CheckerConfig,
+    /// [`LocalDefId`] of the top-level function
+    root: LocalDefId,
+    /// The chain of inlined calls leading to the body being checked, starting from the top-level
+    /// function. Each call is paired with the function inlined at it.
+    inlined_at: Vec<(Location, LocalDefId)>,
 }
 
+/// Identifies an instance of a body being checked. The body of a function inlined at several call
+/// sites is checked once per call site, so join points are inferred separately for each instance.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct BodyInstance {
+    def_id: LocalDefId,
+    /// Locations of the chain of inlined calls leading to the body, empty if it isn't inlined
+    inlined_at: Vec<Location>,
+}
+
+/// Maximum depth of nested inlined calls
+const MAX_INLINE_DEPTH: usize = 4;
+
 impl<'ck, M: Mode> Inherited<'ck, M> {
     fn new(
         genv: GlobalEnv,
@@ -101,7 +147,7 @@ impl<'ck, M: Mode> Inherited<'ck, M> {
             .collect_all_params(genv, |param| rcx.define_vars(&param.sort))
             .with_span(span)?;
 
-        Ok(Self { refine_params, ghost_stmts, mode, config })
+        Ok(Self { refine_params, ghost_stmts, mode, config, root: def_id, inlined_at: vec![] })
     }
 
     fn constr_gen<'a, 'genv, 'tcx>(
@@ -122,8 +168,18 @@ impl<'ck, M: Mode> Inherited<'ck, M> {
             ghost_stmts: self.ghost_stmts,
             mode: &mut *self.mode,
             config: self.config,
+            root: self.root,
+            inlined_at: self.inlined_at.clone(),
         }
     }
+
+    /// Whether a call to `callee` can be inlined. Recursive calls and calls exceeding the maximum
+    /// inlining depth are checked against the signature of the callee instead.
+    fn can_inline(&self, callee: LocalDefId) -> bool {
+        self.inlined_at.len() < MAX_INLINE_DEPTH
+            && callee != self.root
+            && self.inlined_at.iter().all(|(_, def_id)| *def_id != callee)
+    }
 }
 
 pub(crate) trait Mode: Sized {
@@ -152,19 +208,28 @@ pub(crate) trait Mode: Sized {
     ) -> Result<bool>;
 
     fn clear(ck: &mut Checker<Self>, bb: BasicBlock);
+
+    /// The tracer if the function is being traced step by step (see [`crate::trace`])
+    fn tracer(&mut self) -> Option<&mut Tracer>;
+
+    /// The hover if we are reporting the refined types at a position (see [`crate::hover`])
+    fn hover(&mut self) -> Option<&mut Hover>;
 }
 
 pub(crate) struct ShapeMode {
-    bb_envs: FxHashMap<LocalDefId, FxHashMap<BasicBlock, BasicBlockEnvShape>>,
+    bb_envs: FxHashMap<BodyInstance, FxHashMap<BasicBlock, BasicBlockEnvShape>>,
 }
 
 pub(crate) struct RefineMode {
-    bb_envs: FxHashMap<LocalDefId, FxHashMap<BasicBlock, BasicBlockEnv>>,
+    bb_envs: FxHashMap<BodyInstance, FxHashMap<BasicBlock, BasicBlockEnv>>,
     kvars: KVarStore,
+    tracer: Option<Tracer>,
+    hover: Option<Hover>,
 }
 
 /// The result of running the shape phase.
-pub(crate) struct ShapeResult(FxHashMap<LocalDefId, FxHashMap<BasicBlock, BasicBlockEnvShape>>);
+#[derive(Default)]
+pub(crate) struct ShapeResult(FxHashMap<BodyInstance, FxHashMap<BasicBlock, BasicBlockEnvShape>>);
 
 /// A `Guard` describes extra "control" information that holds at the start of a successor basic block
 enum Guard {
@@ -209,23 +274,68 @@ impl<'ck, 'genv, 'tcx> Checker<'ck, 'genv, 'tcx, RefineMode> {
         bb_env_shapes: ShapeResult,
         config: CheckerConfig,
     ) -> Result<(RefineTree, KVarStore)> {
-        let fn_sig = genv.fn_sig(def_id).with_span(genv.tcx().def_span(def_id))?;
-
+        let span = genv.tcx().def_span(def_id);
         let mut kvars = fixpoint_encoding::KVarStore::new();
+        let fn_sig = match sig_suggestion::template(genv, def_id, &mut kvars).with_span(span)? {
+            Some(fn_sig) => fn_sig,
+            None => genv.fn_sig(def_id).with_span(span)?,
+        };
         let mut refine_tree = RefineTree::new();
         let bb_envs = bb_env_shapes.into_bb_envs(&mut kvars);
 
         dbg::refine_mode_span!(genv.tcx(), def_id, bb_envs).in_scope(|| {
-            let mut mode = RefineMode { bb_envs, kvars };
+            let tracer = Tracer::new(genv.tcx(), def_id);
+            let hover = Hover::new(genv.tcx(), def_id);
+            let mut mode = RefineMode { bb_envs, kvars, tracer, hover };
             let mut rcx = refine_tree.refine_ctxt_at_root();
             let inherited = Inherited::new(genv, &mut rcx, def_id, &mut mode, ghost_stmts, config)?;
             Checker::run(genv, rcx.as_subtree(), def_id, inherited, fn_sig)?;
 
+            if config::show_invariants() {
+                mode.collect_loop_heads(genv);
+            }
+            if let Some(hover) = mode.hover.take() {
+                hover.report(genv);
+            }
+
             Ok((refine_tree, mode.kvars))
         })
     }
 }
 
+impl RefineMode {
+    /// Registers the kvar at the entry of every loop head so their solution can be reported back
+    /// to the user (see [`config::show_invariants`]).
+    fn collect_loop_heads(&mut self, genv: GlobalEnv) {
+        for (instance, bb_envs) in &self.bb_envs {
+            // The invariants of an inlined body only hold for the call it is inlined at
+            if !instance.inlined_at.is_empty() {
+                continue;
+            }
+            let Ok(body) = genv.mir(instance.def_id) else { continue };
+            let rustc_body = body.rustc_body();
+            let local_names = body.local_names();
+            let predecessors = rustc_body.basic_blocks.predecessors();
+            for (bb, bb_env) in bb_envs.iter().sorted_by_key(|(bb, _)| **bb) {
+                // A block is a loop head if it dominates one of its predecessors, i.e., it is the
+                // target of a back edge.
+                let is_loop_head = predecessors[*bb]
+                    .iter()
+                    .any(|pred| body.dominators().dominates(*bb, *pred));
+                if !is_loop_head {
+                    continue;
+                }
+                if let Some((kvid, arg_names)) = bb_env.invariant_kvar(&local_names) {
+                    let span = rustc_body
+                        .source_info(Location { block: *bb, statement_index: 0 })
+                        .span;
+                    self.kvars.add_loop_head(kvid, span, arg_names);
+                }
+            }
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
     fn run(
@@ -254,10 +364,11 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                     .next_nll_region_var(NllRegionVariableOrigin::FreeRegion);
                 rty::ReVar(re.as_var())
             },
-            |sort, _| rcx.define_vars(sort),
+            |sort, _, kind| rcx.define_bound_reft_var(sort, kind),
         );
 
         let env = init_env(&mut rcx, &body, &fn_sig, inherited.config);
+        let liveness = Liveness::new(genv.tcx(), &body, &rcx.scope());
 
         // (NOTE:YIELD) per https://doc.rust-lang.org/beta/nightly-rustc/rustc_middle/mir/enum.TerminatorKind.html#variant.Yield
         //   "execution of THIS function continues at the `resume` basic block, with THE SECOND ARGUMENT WRITTEN
@@ -275,6 +386,8 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             body: &body,
             resume_ty,
             visited: BitSet::new_empty(body.basic_blocks.len()),
+            liveness,
+            holes: Holes::new(genv.tcx(), &body),
             output: fn_sig.output().clone(),
             snapshots: IndexVec::from_fn_n(|_| None, body.basic_blocks.len()),
             queue: WorkQueue::empty(body.basic_blocks.len(), body.dominators()),
@@ -290,7 +403,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             let snapshot = ck.snapshot_at_dominator(bb);
             let mut rcx = refine_tree.refine_ctxt_at(snapshot).unwrap();
             let mut env = M::enter_basic_block(&mut ck, &mut rcx, bb);
-            env.unpack(&mut rcx, ck.config().check_overflow);
+            env.unpack(&mut rcx, ck.config());
             ck.check_basic_block(rcx, env, bb)?;
         }
 
@@ -304,6 +417,9 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         bb: BasicBlock,
     ) -> Result {
         dbg::basic_block_start!(bb, rcx, env);
+        if let Some(tracer) = self.inherited.mode.tracer() {
+            tracer.basic_block_start(bb, &env);
+        }
 
         self.visited.insert(bb);
         let data = &self.body.basic_blocks[bb];
@@ -312,10 +428,14 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         for stmt in &data.statements {
             let span = stmt.source_info.span;
             self.check_ghost_statements_at(&mut rcx, &mut env, Point::Location(location), span)?;
+            self.hover_at(span, &rcx, &env);
             bug::track_span(span, || {
                 dbg::statement!("start", stmt, rcx, env);
+                let step = self.trace_start(&rcx, &env);
                 self.check_statement(&mut rcx, &mut env, stmt)?;
                 dbg::statement!("end", stmt, rcx, env);
+                let step = step.map(|step| step.end(&env));
+                self.trace_step(step, location, stmt, span);
                 Ok(())
             })?;
             if !stmt.is_nop() {
@@ -327,20 +447,56 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         if let Some(terminator) = &data.terminator {
             let span = terminator.source_info.span;
             self.check_ghost_statements_at(&mut rcx, &mut env, Point::Location(location), span)?;
+            self.hover_at(span, &rcx, &env);
             bug::track_span(span, || {
                 dbg::terminator!("start", terminator, rcx, env);
-                let successors =
-                    self.check_terminator(&mut rcx, &mut env, terminator, last_stmt_span)?;
+                let step = self.trace_start(&rcx, &env);
+                let successors = self.check_terminator(
+                    &mut rcx,
+                    &mut env,
+                    terminator,
+                    location,
+                    last_stmt_span,
+                )?;
                 dbg::terminator!("end", terminator, rcx, env);
+                let step = step.map(|step| step.end(&env));
 
                 self.snapshots[bb] = Some(rcx.snapshot());
                 let term_span = last_stmt_span.unwrap_or(span);
-                self.check_successors(rcx, env, bb, term_span, successors)
+                // The constraints of the jumps to join points are added below the terminator, so
+                // the step is traced after checking the successors.
+                self.check_successors(rcx, env, bb, term_span, successors)?;
+                self.trace_step(step, location, terminator, span);
+                Ok(())
             })?;
         }
         Ok(())
     }
 
+    fn hover_at(&mut self, span: Span, rcx: &RefineCtxt, env: &TypeEnv) {
+        if let Some(hover) = self.inherited.mode.hover() {
+            hover.record(self.genv.tcx(), self.body, span, rcx, env);
+        }
+    }
+
+    fn trace_start(&mut self, rcx: &RefineCtxt, env: &TypeEnv) -> Option<Step> {
+        self.inherited.mode.tracer()?;
+        Some(Step::start(rcx, env))
+    }
+
+    fn trace_step(
+        &mut self,
+        step: Option<Step>,
+        location: Location,
+        what: impl std::fmt::Debug,
+        span: Span,
+    ) {
+        let tcx = self.genv.tcx();
+        if let (Some(step), Some(tracer)) = (step, self.inherited.mode.tracer()) {
+            tracer.step(tcx, step, location, what, span);
+        }
+    }
+
     fn check_assign_ty(
         &mut self,
         rcx: &mut RefineCtxt,
@@ -351,6 +507,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
     ) -> Result {
         let ty = rcx
             .unpacker()
+            .policy(self.config().unpack)
             .assume_invariants(self.check_overflow())
             .unpack(&ty);
         let gen = &mut self.constr_gen(rcx, source_info.span);
@@ -399,15 +556,34 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         is_no_op && is_ret
     }
 
+    /// Whether `bb` does nothing but reach an [`Unreachable`] terminator, e.g., the `otherwise`
+    /// branch rustc generates for an exhaustive `match`. The environment at such a block is
+    /// inconsistent so a jump to it is trivially safe and doesn't contribute to any join point.
+    ///
+    /// [`Unreachable`]: TerminatorKind::Unreachable
+    fn is_unreachable_block(&self, bb: BasicBlock) -> bool {
+        let data = &self.body.basic_blocks[bb];
+        let is_no_op = data.statements.iter().all(Statement::is_nop);
+        let is_unreachable = match &data.terminator {
+            None => false,
+            Some(term) => term.is_unreachable(),
+        };
+        is_no_op && is_unreachable
+    }
+
     /// For `check_terminator`, the output `Vec<BasicBlock, Guard>` denotes,
     /// - `BasicBlock` "successors" of the current terminator, and
     /// - `Guard` are extra control information from, e.g. the `SwitchInt` (or `Assert`)
     ///    you can assume when checking the corresponding successor.
+    ///
+    /// Terminators that diverge, i.e., `Unreachable` and calls to functions returning `!` (which
+    /// don't have a target), have no successors so they never contribute to a join point.
     fn check_terminator(
         &mut self,
         rcx: &mut RefineCtxt,
         env: &mut TypeEnv,
         terminator: &Terminator<'tcx>,
+        location: Location,
         last_stmt_span: Option<Span>,
     ) -> Result<Vec<(BasicBlock, Guard)>> {
         let source_info = terminator.source_info;
@@ -437,39 +613,120 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             TerminatorKind::SwitchInt { discr, targets } => {
                 let discr_ty = self.check_operand(rcx, env, terminator_span, discr)?;
                 if discr_ty.is_integral() || discr_ty.is_bool() {
-                    Ok(Self::check_if(&discr_ty, targets))
+                    Ok(Self::check_if(&discr_ty, targets, terminator_span))
                 } else {
                     Ok(Self::check_match(&discr_ty, targets))
                 }
             }
-            TerminatorKind::Call { args, destination, target, resolved_call, .. } => {
-                let actuals = self.check_operands(rcx, env, terminator_span, args)?;
+            TerminatorKind::Call {
+                func,
+                generic_args,
+                args,
+                destination,
+                target,
+                resolved_call,
+                ..
+            } => {
+                let mut actuals = self.check_operands(rcx, env, terminator_span, args)?;
+
+                let (func_id, call_args) =
+                    callee_of_call(self.genv, func, generic_args, resolved_call)
+                        .with_src_info(terminator.source_info)?;
+                if self.config().check_panics
+                    && target.is_none()
+                    && is_panic_fn(self.genv.tcx(), *func_id)
+                {
+                    self.constr_gen(rcx, terminator_span).check_pred(
+                        rcx,
+                        &Expr::ff(),
+                        ConstrReason::Assert("panic"),
+                    );
+                }
 
-                let (func_id, call_args) = resolved_call;
-                let fn_sig = self
-                    .genv
-                    .fn_sig(*func_id)
+                let ret = if let Some(intrinsic) = self.genv.intrinsic(*func_id) {
+                    self.check_intrinsic_call(rcx, terminator_span, intrinsic, &actuals)
+                } else if self.ghost_stmts().is_inlinable_call(location)
+                    && self.inherited.can_inline(func_id.expect_local())
+                {
+                    self.check_inlined_call(
+                        rcx,
+                        env,
+                        location,
+                        terminator_span,
+                        func_id.expect_local(),
+                        &actuals,
+                    )?
+                } else {
+                    let fn_sig = auto_spec::fn_sig_for_call(
+                        self.genv,
+                        *func_id,
+                        call_args.orig,
+                        self.config().auto_specs,
+                    )
                     .with_src_info(terminator.source_info)?;
 
-                let generic_args = instantiate_args_for_fun_call(
-                    self.genv,
-                    &self.generics,
-                    *func_id,
-                    &call_args.lowered,
-                )
-                .with_src_info(terminator.source_info)?;
+                    let generic_args = instantiate_args_for_fun_call(
+                        self.genv,
+                        &self.generics,
+                        *func_id,
+                        &call_args.lowered,
+                    )
+                    .with_src_info(terminator.source_info)?;
+
+                    let weak_args = open_weak_args(rcx, env, &fn_sig, args, &mut actuals);
+                    let ret = self.check_call(
+                        rcx,
+                        env,
+                        terminator_span,
+                        Some(*func_id),
+                        fn_sig,
+                        &generic_args,
+                        &actuals,
+                    )?;
+                    let mut gen = self.constr_gen(rcx, terminator_span);
+                    for (path, ty) in weak_args {
+                        env.close_weak_loc(rcx, &mut gen, &path, &ty)
+                            .with_span(terminator_span)?;
+                    }
+                    ret
+                };
+
+                let ret = rcx.unpacker().policy(self.config().unpack).unpack(&ret);
+                rcx.assume_invariants(&ret, self.check_overflow());
+                let mut gen = self.constr_gen(rcx, terminator_span);
+                env.assign(rcx, &mut gen, destination, ret)
+                    .with_span(terminator_span)?;
+
+                if let Some(target) = target {
+                    Ok(vec![(*target, Guard::None)])
+                } else {
+                    Ok(vec![])
+                }
+            }
+            TerminatorKind::CallIndirect { func, args, destination, target, .. } => {
+                let func_ty = self.check_operand(rcx, env, terminator_span, func)?;
+                let TyKind::Indexed(BaseTy::FnPtr(fn_sig), _) = func_ty.kind() else {
+                    tracked_span_bug!("expected function pointer, found `{func_ty:?}`");
+                };
+                if self
+                    .is_unrefined_fn_ptr(func, &func_ty)
+                    .with_span(terminator_span)?
+                {
+                    return Err(CheckerError::unrefined_fn_ptr(terminator_span));
+                }
+                let actuals = self.check_operands(rcx, env, terminator_span, args)?;
 
                 let ret = self.check_call(
                     rcx,
                     env,
                     terminator_span,
-                    Some(*func_id),
-                    fn_sig,
-                    &generic_args,
+                    None,
+                    EarlyBinder(fn_sig.clone()),
+                    &[],
                     &actuals,
                 )?;
 
-                let ret = rcx.unpack(&ret);
+                let ret = rcx.unpacker().policy(self.config().unpack).unpack(&ret);
                 rcx.assume_invariants(&ret, self.check_overflow());
                 let mut gen = self.constr_gen(rcx, terminator_span);
                 env.assign(rcx, &mut gen, destination, ret)
@@ -510,13 +767,13 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         generic_args: &[GenericArg],
         actuals: &[Ty],
     ) -> Result<Ty> {
-        let actuals = infer_under_mut_ref_hack(rcx, actuals, fn_sig.as_ref());
         let (output, obligs) = self
             .constr_gen(rcx, terminator_span)
-            .check_fn_call(rcx, env, did, fn_sig, generic_args, &actuals)
+            .check_fn_call(rcx, env, did, fn_sig, generic_args, actuals)
             .with_span(terminator_span)?;
 
-        let output = output.replace_bound_refts_with(|sort, _, _| rcx.define_vars(sort));
+        let output =
+            output.replace_bound_refts_with(|sort, _, kind| rcx.define_bound_reft_var(sort, kind));
 
         for constr in &output.ensures {
             match constr {
@@ -534,6 +791,93 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         Ok(output.ret)
     }
 
+    /// Whether the type of a function pointer is the default refinement of its unrefined type, i.e.,
+    /// the pointer doesn't come from a field, argument or cast with a refined signature.
+    fn is_unrefined_fn_ptr(&self, func: &Operand, func_ty: &Ty) -> QueryResult<bool> {
+        let rustc_ty = match func {
+            Operand::Copy(place) | Operand::Move(place) => {
+                place.ty(self.genv, &self.body.local_decls)?.ty
+            }
+            Operand::Constant(Constant::Opaque(ty)) => ty.clone(),
+            Operand::Constant(_) => return Ok(false),
+        };
+        Ok(&self.genv.refine_default(&self.generics, &rustc_ty)? == func_ty)
+    }
+
+    /// Intrinsics don't have a signature, the meaning of a call is built into the checker. Their
+    /// signature was already validated when collecting them.
+    fn check_intrinsic_call(
+        &mut self,
+        rcx: &mut RefineCtxt,
+        terminator_span: Span,
+        intrinsic: fhir::Intrinsic,
+        actuals: &[Ty],
+    ) -> Ty {
+        match intrinsic {
+            fhir::Intrinsic::Assume | fhir::Intrinsic::Assert => {
+                let ty = rcx.unpack(&actuals[0]);
+                let TyKind::Indexed(BaseTy::Bool, pred) = ty.kind() else {
+                    tracked_span_bug!("unexpected ty `{ty:?}`");
+                };
+                // The obligation is tagged with the span of the call, i.e., of the `flux_assert!`
+                if let fhir::Intrinsic::Assert = intrinsic {
+                    self.constr_gen(rcx, terminator_span).check_pred(
+                        rcx,
+                        pred,
+                        ConstrReason::Assert("the asserted condition may be false"),
+                    );
+                }
+                rcx.assume_pred(pred);
+            }
+            // The borrowed place is packed or unpacked by a ghost statement before the call
+            fhir::Intrinsic::Pack | fhir::Intrinsic::Unpack => {}
+        }
+        Ty::unit()
+    }
+
+    /// Checks a call by checking the body of the callee against the types of the actual arguments,
+    /// i.e., the call is inlined instead of using the signature of the callee. The return type is
+    /// the unrefined type of the return place of the callee with refinements inferred from the
+    /// inlined body.
+    fn check_inlined_call(
+        &mut self,
+        rcx: &mut RefineCtxt,
+        env: &mut TypeEnv,
+        location: Location,
+        terminator_span: Span,
+        callee: LocalDefId,
+        actuals: &[Ty],
+    ) -> Result<Ty> {
+        let genv = self.genv;
+        let span = genv.tcx().def_span(callee);
+        let generics = genv.generics_of(callee).with_span(span)?;
+        let body = genv.mir(callee).with_span(span)?;
+        let ret = genv
+            .refine_with_holes(&generics, &body.local_decls[RETURN_PLACE].ty)
+            .with_span(span)?;
+
+        let mut gen = self.constr_gen(rcx, terminator_span);
+        let args = gen
+            .pack_closure_operands(rcx, env, actuals)
+            .with_span(terminator_span)?;
+        let ret = gen.instantiate_inlined_output(&ret);
+
+        let output = Binder::new(FnOutput::new(ret.clone(), vec![]), List::empty());
+        let poly_sig = PolyFnSig::new(FnSig::new(vec![], args, output), List::empty());
+
+        let snapshot = rcx.snapshot();
+        let mut inherited = self.inherited.reborrow();
+        inherited.inlined_at.push((location, callee));
+        Checker::run(
+            genv,
+            rcx.subtree_at(&snapshot).unwrap(),
+            callee,
+            inherited,
+            EarlyBinder(poly_sig),
+        )?;
+        Ok(ret)
+    }
+
     fn check_oblig_generator_pred(
         &mut self,
         rcx: &mut RefineCtxt,
@@ -622,9 +966,12 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         Ok(Guard::Pred(pred))
     }
 
-    fn check_if(discr_ty: &Ty, targets: &SwitchTargets) -> Vec<(BasicBlock, Guard)> {
+    /// The guards are attributed to the span of the branch so errors can point at the conditions
+    /// that were assumed when proving an obligation.
+    fn check_if(discr_ty: &Ty, targets: &SwitchTargets, span: Span) -> Vec<(BasicBlock, Guard)> {
+        let espan = ESpan::new(span);
         let mk = |bits| {
-            match discr_ty.kind() {
+            let guard = match discr_ty.kind() {
                 TyKind::Indexed(BaseTy::Bool, idx) => {
                     if bits == 0 {
                         idx.not()
@@ -636,7 +983,8 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                     Expr::binary_op(BinOp::Eq, idx.clone(), Expr::from_bits(bty, bits), None)
                 }
                 _ => tracked_span_bug!("unexpected discr_ty {:?}", discr_ty),
-            }
+            };
+            guard.at(espan)
         };
 
         let mut successors = vec![];
@@ -644,7 +992,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         for (bits, bb) in targets.iter() {
             successors.push((bb, Guard::Pred(mk(bits))));
         }
-        let otherwise = Expr::and(targets.iter().map(|(bits, _)| mk(bits).not()));
+        let otherwise = Expr::and(targets.iter().map(|(bits, _)| mk(bits).not().at(espan)));
         successors.push((targets.otherwise(), Guard::Pred(otherwise)));
 
         successors
@@ -709,6 +1057,9 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         span: Span,
         target: BasicBlock,
     ) -> Result {
+        if self.is_unreachable_block(target) || self.holes.is_hole(target) {
+            return Ok(());
+        }
         self.check_ghost_statements_at(&mut rcx, &mut env, Point::Edge(from, target), span)?;
         if self.is_exit_block(target) {
             let location = self.body.terminator_loc(target);
@@ -778,7 +1129,19 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 gen.check_mk_array(rcx, env, &args, arr_ty)
                     .with_span(stmt_span)
             }
+            Rvalue::Repeat(operand, elem_ty, c) => {
+                let ty = self.check_operand(rcx, env, stmt_span, operand)?;
+                let elem_ty = self
+                    .genv
+                    .refine_with_holes(&self.generics, elem_ty)
+                    .with_span(stmt_span)?;
+                let mut gen = self.constr_gen(rcx, stmt_span);
+                gen.check_mk_repeat(rcx, env, ty, elem_ty, c.clone())
+                    .with_span(stmt_span)
+            }
             Rvalue::Aggregate(AggregateKind::Tuple, args) => {
+                // Tuples are not refined by themselves, the components are. We keep mutable
+                // pointers in the components so strong updates through them are still possible.
                 let tys = self.check_operands(rcx, env, stmt_span, args)?;
                 Ok(Ty::tuple(tys))
             }
@@ -805,6 +1168,11 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 Ok(Ty::discr(adt_def.clone(), place.clone()))
             }
             Rvalue::Len(place) => self.check_len(rcx, env, stmt_span, place),
+            Rvalue::Cast(
+                CastKind::Pointer(mir::PointerCast::ReifyFnPointer),
+                Operand::Constant(Constant::FnDef(def_id, args)),
+                _,
+            ) => self.check_reify_fn_ptr(rcx, stmt_span, *def_id, args),
             Rvalue::Cast(kind, op, to) => {
                 let from = self.check_operand(rcx, env, stmt_span, op)?;
                 self.check_cast(*kind, &from, to)
@@ -812,6 +1180,26 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         }
     }
 
+    /// A function pointer created from a function item has the signature of the function. The
+    /// signature is checked against the expected one when the pointer is used, e.g., when it is
+    /// stored in a field whose type is a refined function pointer.
+    fn check_reify_fn_ptr(
+        &mut self,
+        rcx: &mut RefineCtxt,
+        stmt_span: Span,
+        def_id: DefId,
+        args: &ty::GenericArgs,
+    ) -> Result<Ty> {
+        let fn_sig = self.genv.fn_sig(def_id).with_span(stmt_span)?;
+        let generic_args = instantiate_args_for_fun_call(self.genv, &self.generics, def_id, args)
+            .with_span(stmt_span)?;
+        let fn_sig = self
+            .constr_gen(rcx, stmt_span)
+            .instantiate_fn_ptr_sig(rcx, def_id, fn_sig, &generic_args)
+            .with_span(stmt_span)?;
+        Ok(Ty::indexed(BaseTy::FnPtr(fn_sig), Expr::unit()))
+    }
+
     fn check_aggregate_operands(
         &mut self,
         rcx: &mut RefineCtxt<'_>,
@@ -821,8 +1209,8 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
     ) -> Result<List<flux_middle::intern::Interned<rty::TyS>>> {
         let tys = self.check_operands(rcx, env, stmt_span, args)?;
         let mut gen = self.constr_gen(rcx, stmt_span);
-        let tys = gen.pack_closure_operands(env, &tys);
-        Ok(tys)
+        gen.pack_closure_operands(rcx, env, &tys)
+            .with_span(stmt_span)
     }
 
     fn check_len(
@@ -835,14 +1223,18 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         let ty = env
             .lookup_place(self.genv, rcx, place)
             .with_span(source_span)?;
+        // The type of a slice behind a mutable reference is not unpacked, but the length of the
+        // slice cannot change through the reference, so it is fine to unpack it here.
+        let ty = rcx.unpacker().shallow(true).unpack(&ty);
 
         let idx = match ty.kind() {
             TyKind::Indexed(BaseTy::Array(_, len), _) => {
-                if let ConstKind::Value(value) = &len.kind {
-                    let value = value.try_to_target_usize(self.genv.tcx()).unwrap() as u128;
-                    Expr::constant(rty::Constant::from(value))
-                } else {
-                    tracked_span_bug!("unexpected array length")
+                match &len.kind {
+                    ConstKind::Value(value) => {
+                        let value = value.try_to_target_usize(self.genv.tcx()).unwrap() as u128;
+                        Expr::constant(rty::Constant::from(value))
+                    }
+                    ConstKind::Param(param) => Expr::const_generic(*param),
                 }
             }
             TyKind::Indexed(BaseTy::Slice(_), idx) => idx.clone(),
@@ -955,19 +1347,26 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             CastKind::Pointer(mir::PointerCast::Unsize) => {
                 if let TyKind::Indexed(BaseTy::Ref(_, src_ty, src_mut), _) = from.kind()
                     && let TyKind::Indexed(BaseTy::Array(src_arr_ty, src_n), _) = src_ty.kind()
-                    && let ConstKind::Value(src_n) = &src_n.kind
                     && let rustc::ty::TyKind::Ref(dst_re, dst_ty, dst_mut) = to.kind()
                     && let rustc::ty::TyKind::Slice(_) = dst_ty.kind()
                     && src_mut == dst_mut
                 {
-                    let v = src_n.try_to_target_usize(self.genv.tcx()).unwrap() as u128;
-                    let expr = Expr::constant(rty::Constant::from(v));
-                    let dst_slice = Ty::indexed(BaseTy::Slice(src_arr_ty.clone()), expr);
+                    let dst_slice = BaseTy::Slice(src_arr_ty.clone());
+                    let dst_slice = match &src_n.kind {
+                        ConstKind::Value(src_n) => {
+                            let v = src_n.try_to_target_usize(self.genv.tcx()).unwrap() as u128;
+                            Ty::indexed(dst_slice, Expr::constant(rty::Constant::from(v)))
+                        }
+                        ConstKind::Param(_) => dst_slice.into_ty(),
+                    };
                     Ty::mk_ref(*dst_re, dst_slice, *dst_mut)
                 } else {
                     tracked_span_bug!("unsupported Unsize cast")
                 }
             }
+            CastKind::Pointer(mir::PointerCast::ReifyFnPointer) => {
+                tracked_span_bug!("unexpected operand in function pointer cast")
+            }
             CastKind::FloatToInt
             | CastKind::IntToFloat
             | CastKind::PtrToPtr
@@ -1034,6 +1433,9 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                     .refine_default(&self.generics, ty)
                     .with_span(self.body.span())
             }
+            Constant::FnDef(..) => {
+                tracked_span_bug!("function items are only supported in function pointer casts")
+            }
         }
     }
 
@@ -1074,11 +1476,84 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 let gen = &mut self.constr_gen(rcx, span);
                 env.ptr_to_borrow(rcx, gen, place).with_span(span)?;
             }
+            GhostStatement::Pack(place) => {
+                let gen = &mut self.constr_gen(rcx, span);
+                env.pack(rcx, gen, place).with_span(span)?;
+            }
+            GhostStatement::Unpack(place) => {
+                env.unpack_place(self.genv, rcx, place, self.check_overflow())
+                    .with_span(span)?;
+            }
+            GhostStatement::Assert(pred, annot_span) => {
+                let pred = self
+                    .instantiate_annot_pred(rcx, env, pred)
+                    .with_span(*annot_span)?;
+                self.constr_gen(rcx, *annot_span).check_pred(
+                    rcx,
+                    pred.clone(),
+                    ConstrReason::Assert("the asserted condition may be false"),
+                );
+                rcx.assume_pred(pred);
+            }
+            GhostStatement::Assume(pred, annot_span) => {
+                let pred = self
+                    .instantiate_annot_pred(rcx, env, pred)
+                    .with_span(*annot_span)?;
+                rcx.assume_pred(pred);
+            }
         }
         dbg::statement!("end", stmt, rcx, env);
         Ok(())
     }
 
+    /// Replaces the locals in the condition of a `#[flux::assert(..)]` or `#[flux::assume(..)]`
+    /// annotation with the index of their types in `env`. A local behind a shared reference stands
+    /// for the index of the referent.
+    fn instantiate_annot_pred(
+        &self,
+        rcx: &mut RefineCtxt,
+        env: &TypeEnv,
+        pred: &Expr,
+    ) -> std::result::Result<Expr, CheckerErrKind> {
+        use rty::fold::{FallibleTypeFolder, TypeSuperFoldable};
+
+        struct Instantiator<'a, 'b, 'rcx> {
+            rcx: &'a mut RefineCtxt<'rcx>,
+            env: &'a TypeEnv<'b>,
+        }
+
+        impl Instantiator<'_, '_, '_> {
+            fn index_of(&mut self, ty: &Ty) -> Option<Expr> {
+                let ty = self.rcx.unpack(ty);
+                match ty.kind() {
+                    TyKind::Indexed(BaseTy::Ref(_, ty, Mutability::Not), _) => self.index_of(ty),
+                    TyKind::Ptr(PtrKind::Shr(_), path) => {
+                        let ty = self.env.get(path);
+                        self.index_of(&ty)
+                    }
+                    TyKind::Indexed(_, idx) => Some(idx.clone()),
+                    _ => None,
+                }
+            }
+        }
+
+        impl FallibleTypeFolder for Instantiator<'_, '_, '_> {
+            type Error = mir::Local;
+
+            fn try_fold_expr(&mut self, expr: &Expr) -> std::result::Result<Expr, mir::Local> {
+                if let rty::ExprKind::Local(local) = expr.kind() {
+                    let ty = self.env.get(&Path::from(rty::Loc::Local(*local)));
+                    self.index_of(&ty).ok_or(*local)
+                } else {
+                    expr.try_super_fold_with(self)
+                }
+            }
+        }
+
+        pred.try_fold_with(&mut Instantiator { rcx, env })
+            .map_err(|local| CheckerErrKind::UnknownAnnotValue(self.body.local_names()[&local]))
+    }
+
     fn constr_gen(&mut self, rcx: &RefineCtxt, span: Span) -> ConstrGen<'_, 'genv, 'tcx> {
         self.inherited
             .constr_gen(self.genv, &self.body.infcx, self.def_id, rcx, span)
@@ -1101,11 +1576,51 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         self.inherited.config
     }
 
+    fn body_instance(&self) -> BodyInstance {
+        let inlined_at = self
+            .inherited
+            .inlined_at
+            .iter()
+            .map(|(loc, _)| *loc)
+            .collect();
+        BodyInstance { def_id: self.def_id, inlined_at }
+    }
+
     fn check_overflow(&self) -> bool {
         self.config().check_overflow
     }
 }
 
+/// A function expecting a strong reference `&strg T` can also be called with a mutable reference
+/// `&mut T`. For the duration of the call, the referent is moved into a fresh location and the
+/// argument is replaced by a pointer to it. Returns the opened locations together with the types
+/// of the original references, which must be restored after the call.
+fn open_weak_args(
+    rcx: &mut RefineCtxt,
+    env: &mut TypeEnv,
+    fn_sig: &EarlyBinder<PolyFnSig>,
+    args: &[Operand],
+    actuals: &mut [Ty],
+) -> Vec<(Path, Ty)> {
+    let formals = fn_sig.as_ref().skip_binder().as_ref().skip_binder().args();
+    let mut weak_args = vec![];
+    for (formal, arg, actual) in izip!(formals, args, actuals) {
+        let (Operand::Copy(place) | Operand::Move(place)) = arg else {
+            continue;
+        };
+        if let (TyKind::Ptr(PtrKind::Mut(_), _), Ref!(re, ty, Mutability::Mut)) =
+            (formal.kind(), actual.kind())
+        {
+            let mut place = place.clone();
+            place.projection.push(PlaceElem::Deref);
+            let path = env.open_weak_loc(rcx, place, ty);
+            weak_args.push((path.clone(), ty.clone()));
+            *actual = Ty::ptr(PtrKind::Mut(*re), path);
+        }
+    }
+    weak_args
+}
+
 fn init_env<'a>(
     rcx: &mut RefineCtxt,
     body: &'a Body,
@@ -1196,6 +1711,65 @@ fn instantiate_args_for_constructor(
         .collect()
 }
 
+/// Whether `def_id` is one of the functions that macros like `panic!`, `assert!` or `unreachable!`
+/// expand to. Most of them are lang or diagnostic items. The rest, e.g., `panic_explicit` or
+/// `assert_failed`, have no items, so we fall back to checking whether they are defined in
+/// `core::panicking`.
+fn is_panic_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    const PANIC_LANG_ITEMS: &[LangItem] = &[
+        LangItem::Panic,
+        LangItem::PanicFmt,
+        LangItem::ConstPanicFmt,
+        LangItem::PanicNounwind,
+        LangItem::BeginPanic,
+    ];
+    if let Some(item) = tcx.lang_items().from_def_id(def_id) {
+        return PANIC_LANG_ITEMS.contains(&item);
+    }
+    if let Some(name) = tcx.get_diagnostic_name(def_id) {
+        return name == sym::panic_str || name == sym::unreachable_display;
+    }
+    tcx.crate_name(def_id.krate) == sym::core
+        && tcx
+            .opt_parent(def_id)
+            .and_then(|parent| tcx.opt_item_name(parent))
+            .is_some_and(|name| name.as_str() == "panicking")
+}
+
+/// Returns the function whose signature should be used for a call. Calls resolved to an
+/// implementation of a method of a trait with an extern spec use the signature of the trait method
+/// unless the implementation has its own signature.
+fn callee_of_call<'a, 'tcx>(
+    genv: GlobalEnv<'_, 'tcx>,
+    func: &'a DefId,
+    generic_args: &'a rustc::mir::CallArgs<'tcx>,
+    resolved_call: &'a (DefId, rustc::mir::CallArgs<'tcx>),
+) -> QueryResult<(&'a DefId, &'a rustc::mir::CallArgs<'tcx>)> {
+    let (resolved_id, resolved_args) = resolved_call;
+    if resolved_id != func
+        && genv.get_local_id_for_extern(*func).is_some()
+        && !has_fn_spec(genv, *resolved_id)?
+    {
+        Ok((func, generic_args))
+    } else {
+        Ok((resolved_id, resolved_args))
+    }
+}
+
+/// Whether `def_id` has a signature given by the user, either on its definition or in an extern
+/// spec
+fn has_fn_spec(genv: GlobalEnv, def_id: DefId) -> QueryResult<bool> {
+    if genv.get_local_id_for_extern(def_id).is_some() {
+        return Ok(true);
+    }
+    if let Some(local_id) = def_id.as_local()
+        && let Some(fn_sig) = genv.map().node(local_id)?.fn_sig()
+    {
+        return Ok(!fn_sig.decl.lifted);
+    }
+    Ok(false)
+}
+
 fn collect_params_in_clauses(genv: GlobalEnv, def_id: DefId) -> FxHashSet<usize> {
     let tcx = genv.tcx();
     struct Collector {
@@ -1264,29 +1838,6 @@ fn all_predicates_of(
     .flatten()
 }
 
-/// HACK(nilehmann) This let us infer parameters under mutable references for the simple case
-/// where the formal argument is of the form `&mut B[@n]`, e.g., the type of the first argument
-/// to `RVec::get_mut` is `&mut RVec<T>[@n]`. We should remove this after we implement opening of
-/// mutable references.
-fn infer_under_mut_ref_hack(
-    rcx: &mut RefineCtxt,
-    actuals: &[Ty],
-    fn_sig: EarlyBinder<&PolyFnSig>,
-) -> Vec<Ty> {
-    iter::zip(actuals, fn_sig.as_ref().skip_binder().as_ref().skip_binder().args())
-        .map(|(actual, formal)| {
-            if let (Ref!(.., Mutability::Mut), Ref!(_, ty, Mutability::Mut)) =
-                (actual.kind(), formal.kind())
-                && let TyKind::Indexed(..) = ty.kind()
-            {
-                rcx.unpacker().unpack_inside_mut_ref(true).unpack(actual)
-            } else {
-                actual.clone()
-            }
-        })
-        .collect()
-}
-
 impl Mode for ShapeMode {
     fn constr_gen<'a, 'genv, 'tcx>(
         &'a mut self,
@@ -1312,7 +1863,7 @@ impl Mode for ShapeMode {
         _rcx: &mut RefineCtxt,
         bb: BasicBlock,
     ) -> TypeEnv<'a> {
-        ck.inherited.mode.bb_envs[&ck.def_id][&bb].enter(&ck.body.local_decls)
+        ck.inherited.mode.bb_envs[&ck.body_instance()][&bb].enter(&ck.body.local_decls)
     }
 
     fn check_goto_join_point(
@@ -1322,39 +1873,52 @@ impl Mode for ShapeMode {
         terminator_span: Span,
         target: BasicBlock,
     ) -> Result<bool> {
+        let instance = ck.body_instance();
         let bb_envs = &mut ck.inherited.mode.bb_envs;
-        let target_bb_env = bb_envs.entry(ck.def_id).or_default().get(&target);
+        let target_bb_env = bb_envs.entry(instance.clone()).or_default().get(&target);
         dbg::shape_goto_enter!(target, env, target_bb_env);
 
-        let modified = match bb_envs.entry(ck.def_id).or_default().entry(target) {
+        let modified = match bb_envs.entry(instance.clone()).or_default().entry(target) {
             Entry::Occupied(mut entry) => entry.get_mut().join(env).with_span(terminator_span)?,
             Entry::Vacant(entry) => {
                 let scope = snapshot_at_dominator(ck.body, &ck.snapshots, target)
                     .scope()
                     .unwrap();
-                entry.insert(env.into_infer(scope).with_span(terminator_span)?);
+                entry.insert(
+                    env.into_infer(scope, &ck.liveness, target)
+                        .with_span(terminator_span)?,
+                );
                 true
             }
         };
 
-        dbg::shape_goto_exit!(target, bb_envs[&ck.def_id].get(&target));
+        dbg::shape_goto_exit!(target, bb_envs[&instance].get(&target));
         Ok(modified)
     }
 
     fn clear(ck: &mut Checker<ShapeMode>, root: BasicBlock) {
+        let instance = ck.body_instance();
         ck.visited.remove(root);
         for bb in ck.body.basic_blocks.indices() {
             if bb != root && ck.dominators().dominates(root, bb) {
                 ck.inherited
                     .mode
                     .bb_envs
-                    .entry(ck.def_id)
+                    .entry(instance.clone())
                     .or_default()
                     .remove(&bb);
                 ck.visited.remove(bb);
             }
         }
     }
+
+    fn tracer(&mut self) -> Option<&mut Tracer> {
+        None
+    }
+
+    fn hover(&mut self) -> Option<&mut Hover> {
+        None
+    }
 }
 
 impl Mode for RefineMode {
@@ -1373,7 +1937,7 @@ impl Mode for RefineMode {
             infcx,
             def_id.into(),
             refparams,
-            move |sorts: &[_], encoding| self.kvars.fresh(sorts, &scope, encoding),
+            move |sorts: &[_], encoding| self.kvars.fresh(sorts, scope.iter(), encoding),
             span,
         )
     }
@@ -1383,7 +1947,7 @@ impl Mode for RefineMode {
         rcx: &mut RefineCtxt,
         bb: BasicBlock,
     ) -> TypeEnv<'ck> {
-        ck.inherited.mode.bb_envs[&ck.def_id][&bb].enter(rcx, &ck.body.local_decls)
+        ck.inherited.mode.bb_envs[&ck.body_instance()][&bb].enter(rcx, &ck.body.local_decls)
     }
 
     fn check_goto_join_point(
@@ -1393,7 +1957,7 @@ impl Mode for RefineMode {
         terminator_span: Span,
         target: BasicBlock,
     ) -> Result<bool> {
-        let bb_env = &ck.inherited.mode.bb_envs[&ck.def_id][&target];
+        let bb_env = &ck.inherited.mode.bb_envs[&ck.body_instance()][&target];
         debug_assert_eq!(&ck.snapshot_at_dominator(target).scope().unwrap(), bb_env.scope());
 
         dbg::refine_goto!(target, rcx, env, bb_env);
@@ -1407,7 +1971,7 @@ impl Mode for RefineMode {
                 ck.inherited
                     .mode
                     .kvars
-                    .fresh(sorts, bb_env.scope(), encoding)
+                    .fresh(sorts, bb_env.scope().iter(), encoding)
             },
             terminator_span,
         );
@@ -1420,6 +1984,14 @@ impl Mode for RefineMode {
     fn clear(_ck: &mut Checker<RefineMode>, _bb: BasicBlock) {
         bug!();
     }
+
+    fn tracer(&mut self) -> Option<&mut Tracer> {
+        self.tracer.as_mut()
+    }
+
+    fn hover(&mut self) -> Option<&mut Hover> {
+        self.hover.as_mut()
+    }
 }
 
 fn bool_int_cast(b: &Expr, int_ty: IntTy) -> Ty {
@@ -1470,15 +2042,15 @@ impl ShapeResult {
     fn into_bb_envs(
         self,
         kvar_store: &mut KVarStore,
-    ) -> FxHashMap<LocalDefId, FxHashMap<BasicBlock, BasicBlockEnv>> {
+    ) -> FxHashMap<BodyInstance, FxHashMap<BasicBlock, BasicBlockEnv>> {
         self.0
             .into_iter()
-            .map(|(def_id, shapes)| {
+            .map(|(instance, shapes)| {
                 let bb_envs = shapes
                     .into_iter()
                     .map(|(bb, shape)| (bb, shape.into_bb_env(kvar_store)))
                     .collect();
-                (def_id, bb_envs)
+                (instance, bb_envs)
             })
             .collect()
     }
@@ -1494,12 +2066,12 @@ fn snapshot_at_dominator<'a>(
 }
 
 pub(crate) mod errors {
-    use flux_errors::{ErrorGuaranteed, E0999};
+    use flux_errors::{ErrorGuaranteed, E0999, EFLUX0005};
     use flux_middle::{pretty, queries::QueryErr, rty::evars::UnsolvedEvar};
     use rustc_errors::Diagnostic;
     use rustc_hir::def_id::DefId;
     use rustc_middle::mir::SourceInfo;
-    use rustc_span::Span;
+    use rustc_span::{Span, Symbol};
 
     pub struct CheckerError {
         kind: CheckerErrKind,
@@ -1510,6 +2082,14 @@ pub(crate) mod errors {
     pub enum CheckerErrKind {
         Inference,
         OpaqueStruct(DefId),
+        UnrefinedFnPtr,
+        /// A `pack` or `unpack` of a place not owned by the function
+        InvalidPack,
+        /// A function with strong references in its signature used as a function pointer
+        FnPtrTypeConstraint,
+        /// A variable in the condition of a `#[flux::assert(..)]` or `#[flux::assume(..)]`
+        /// annotation whose type has no index at the annotated statement
+        UnknownAnnotValue(Symbol),
         Query(QueryErr),
     }
 
@@ -1517,6 +2097,10 @@ pub(crate) mod errors {
         pub fn opaque_struct(def_id: DefId, span: Span) -> Self {
             Self { kind: CheckerErrKind::OpaqueStruct(def_id), span }
         }
+
+        pub fn unrefined_fn_ptr(span: Span) -> Self {
+            Self { kind: CheckerErrKind::UnrefinedFnPtr, span }
+        }
     }
 
     impl<'a> Diagnostic<'a> for CheckerError {
@@ -1531,7 +2115,7 @@ pub(crate) mod errors {
                 CheckerErrKind::Inference => {
                     let mut diag =
                         dcx.struct_span_err(self.span, fluent::refineck_param_inference_error);
-                    diag.code(E0999);
+                    diag.code(EFLUX0005);
                     diag
                 }
                 CheckerErrKind::OpaqueStruct(def_id) => {
@@ -1541,6 +2125,32 @@ pub(crate) mod errors {
                     diag.code(E0999);
                     diag
                 }
+                CheckerErrKind::UnrefinedFnPtr => {
+                    let mut diag =
+                        dcx.struct_span_err(self.span, fluent::refineck_unrefined_fn_ptr_error);
+                    diag.code(E0999);
+                    diag
+                }
+                CheckerErrKind::InvalidPack => {
+                    let mut diag =
+                        dcx.struct_span_err(self.span, fluent::refineck_invalid_pack_error);
+                    diag.code(E0999);
+                    diag
+                }
+                CheckerErrKind::FnPtrTypeConstraint => {
+                    let mut diag = dcx
+                        .struct_span_err(self.span, fluent::refineck_fn_ptr_type_constraint_error);
+                    diag.code(E0999);
+                    diag
+                }
+                CheckerErrKind::UnknownAnnotValue(name) => {
+                    let mut diag =
+                        dcx.struct_span_err(self.span, fluent::refineck_unknown_annot_value_error);
+                    diag.arg("name", name);
+                    diag.note(fluent::_subdiag::note);
+                    diag.code(E0999);
+                    diag
+                }
                 CheckerErrKind::Query(err) => err.at(self.span).into_diag(dcx, level),
             }
         }
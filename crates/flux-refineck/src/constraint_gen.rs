@@ -10,7 +10,7 @@ use flux_middle::{
         fold::TypeFoldable,
         AliasTy, BaseTy, BinOp, Binder, Constraint, CoroutineObligPredicate, ESpan, EVarGen,
         EarlyBinder, Expr, ExprKind, FnOutput, GenericArg, HoleKind, InferMode, Lambda, Mutability,
-        Path, PolyFnSig, PolyVariant, PtrKind, Ref, Sort, Ty, TyKind, Var,
+        Path, PolyFnSig, PolyVariant, PtrKind, Ref, Sort, Ty, TyKind, Var, VariantIdx,
     },
     rustc::mir::{BasicBlock, Place},
 };
@@ -66,17 +66,24 @@ pub(crate) struct InferCtxt<'a, 'genv, 'tcx> {
 pub struct Tag {
     pub reason: ConstrReason,
     pub src_span: Span,
+    /// The span of the conjunct in the condition that has to be proven
     pub dst_span: Option<ESpan>,
+    /// The item whose precondition or postcondition the condition belongs to
+    pub required_by: Option<DefId>,
 }
 
 impl Tag {
     pub fn new(reason: ConstrReason, span: Span) -> Self {
-        Self { reason, src_span: span, dst_span: None }
+        Self { reason, src_span: span, dst_span: None, required_by: None }
     }
 
     pub fn with_dst(self, dst_span: Option<ESpan>) -> Self {
         Self { dst_span, ..self }
     }
+
+    pub fn required_by(self, def_id: Option<DefId>) -> Self {
+        Self { required_by: def_id, ..self }
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
@@ -84,7 +91,9 @@ pub enum ConstrReason {
     Call,
     Assign,
     Ret,
-    Fold,
+    /// Checking a field against its declared type when folding a place, with the [`DefId`] of the
+    /// field
+    Fold(DefId),
     Assert(&'static str),
     Div,
     Rem,
@@ -129,19 +138,31 @@ impl<'a, 'genv, 'tcx> ConstrGen<'a, 'genv, 'tcx> {
         rcx.replace_evars(&infcx.solve().unwrap());
     }
 
-    pub(crate) fn pack_closure_operands(&mut self, env: &mut TypeEnv, operands: &[Ty]) -> List<Ty> {
+    /// Packs the upvars of a closure (or coroutine) aggregate. Upvars keep their (unpacked) types,
+    /// so the closure captures refined values. Pointers, however, cannot escape into the body of
+    /// the closure, which is checked separately. Thus, mutable pointers are converted to mutable
+    /// borrows blocking the location they point to (see [`TypeEnv::ptr_to_ref`]).
+    pub(crate) fn pack_closure_operands(
+        &mut self,
+        rcx: &mut RefineCtxt,
+        env: &mut TypeEnv,
+        operands: &[Ty],
+    ) -> Result<List<Ty>> {
         operands
             .iter()
             .map(|ty| {
                 match ty.kind() {
                     TyKind::Ptr(PtrKind::Shr(region), path) => {
                         let ty = env.get(path);
-                        rty::Ty::mk_ref(*region, ty, Mutability::Not)
+                        Ok(rty::Ty::mk_ref(*region, ty, Mutability::Not))
                     }
-                    _ => ty.clone(),
+                    TyKind::Ptr(PtrKind::Mut(region), path) => {
+                        env.ptr_to_ref(rcx, self, *region, path)
+                    }
+                    _ => Ok(ty.clone()),
                 }
             })
-            .collect()
+            .try_collect()
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -158,6 +179,7 @@ impl<'a, 'genv, 'tcx> ConstrGen<'a, 'genv, 'tcx> {
         let span = self.span;
 
         let mut infcx = self.infcx(rcx, ConstrReason::Call);
+        infcx.tag = infcx.tag.required_by(callee_def_id);
         let snapshot = rcx.snapshot();
 
         // Replace holes in generic arguments with fresh inference variables
@@ -178,7 +200,7 @@ impl<'a, 'genv, 'tcx> ConstrGen<'a, 'genv, 'tcx> {
                     ));
                     rty::ReVar(re.as_var())
                 },
-                |sort, mode| infcx.fresh_infer_var(sort, mode),
+                |sort, mode, _| infcx.fresh_infer_var(sort, mode),
             )
             .normalize_projections(genv, infcx.region_infcx, infcx.def_id, infcx.refparams)?;
 
@@ -216,6 +238,19 @@ impl<'a, 'genv, 'tcx> ConstrGen<'a, 'genv, 'tcx> {
                     let ty = env.block_with(genv, path, bound.clone())?;
                     infcx.subtyping(rcx, &ty, bound)?;
                 }
+                (Ref!(_, ty, Mutability::Mut), Ref!(_, bound, Mutability::Mut))
+                    if matches!(bound.kind(), TyKind::Indexed(..)) =>
+                {
+                    // Open the mutable reference for the duration of the call, i.e., give a name
+                    // to the existential index of the referent so it can be used to instantiate
+                    // the index of the formal. The callee gets a reference to the opened type and
+                    // leaves behind a value of the formal type, which must be folded back into
+                    // the original (existential) type since that's the type the reference keeps
+                    // in the environment after the call.
+                    let opened = rcx.unpack(ty);
+                    infcx.subtyping(rcx, &opened, bound)?;
+                    infcx.subtyping(rcx, bound, ty)?;
+                }
                 _ => infcx.subtyping(rcx, actual, &formal)?,
             }
         }
@@ -252,6 +287,44 @@ impl<'a, 'genv, 'tcx> ConstrGen<'a, 'genv, 'tcx> {
         Ok((output, Obligations::new(obligs, snapshot)))
     }
 
+    /// Instantiates the signature of a function item to give a type to a function pointer created
+    /// from it. Holes in the generic arguments and refinement generics of the function are inferred.
+    pub(crate) fn instantiate_fn_ptr_sig(
+        &mut self,
+        rcx: &mut RefineCtxt,
+        def_id: DefId,
+        fn_sig: EarlyBinder<PolyFnSig>,
+        generic_args: &[GenericArg],
+    ) -> Result<PolyFnSig> {
+        let genv = self.genv;
+        let mut infcx = self.infcx(rcx, ConstrReason::Other);
+
+        let generic_args = infcx.instantiate_generic_args(generic_args);
+        let refine_args = infcx.instantiate_refine_args(genv, Some(def_id))?;
+        let fn_sig = fn_sig
+            .instantiate(&generic_args, &refine_args)
+            .normalize_projections(genv, infcx.region_infcx, infcx.def_id, infcx.refparams)?;
+        if has_type_constraints(&fn_sig) {
+            return Err(CheckerErrKind::FnPtrTypeConstraint);
+        }
+
+        let evars_sol = infcx.solve()?;
+        rcx.replace_evars(&evars_sol);
+        Ok(fn_sig.replace_evars(&evars_sol))
+    }
+
+    /// Instantiates the type returned by a call checked by inlining the body of the callee. Holes in
+    /// `ret` are replaced with fresh kvars to be inferred when checking the return of the inlined
+    /// body.
+    pub(crate) fn instantiate_inlined_output(&mut self, ret: &Ty) -> Ty {
+        ret.replace_holes(|binders, kind| {
+            match kind {
+                HoleKind::Pred => self.kvar_gen.fresh(binders, KVarEncoding::Conj),
+                HoleKind::Expr(_) => bug!("unexpected expr hole in inlined output `{ret:?}`"),
+            }
+        })
+    }
+
     pub(crate) fn check_ret(
         &mut self,
         rcx: &mut RefineCtxt,
@@ -261,6 +334,7 @@ impl<'a, 'genv, 'tcx> ConstrGen<'a, 'genv, 'tcx> {
         let ret_place_ty = env.lookup_place(self.genv, rcx, Place::RETURN)?;
 
         let mut infcx = self.infcx(rcx, ConstrReason::Ret);
+        infcx.tag = infcx.tag.required_by(Some(self.def_id));
 
         let output =
             output.replace_bound_refts_with(|sort, mode, _| infcx.fresh_infer_var(sort, mode));
@@ -278,15 +352,18 @@ impl<'a, 'genv, 'tcx> ConstrGen<'a, 'genv, 'tcx> {
         Ok(Obligations::new(obligs.into(), rcx.snapshot()))
     }
 
+    /// Checks the `fields` of a variant against its signature. We are only calling this when
+    /// folding, so the obligations for each field are tagged with [`ConstrReason::Fold`] and the
+    /// field, i.e., when folding nested places the error points at the innermost field that fails.
     pub(crate) fn check_constructor(
         &mut self,
         rcx: &mut RefineCtxt,
         variant: EarlyBinder<PolyVariant>,
+        variant_idx: VariantIdx,
         generic_args: &[GenericArg],
         fields: &[Ty],
     ) -> Result<Ty> {
-        // rn we are only calling `check_constructor` when folding so we mark this as a folding error.
-        let mut infcx = self.infcx(rcx, ConstrReason::Fold);
+        let mut infcx = self.infcx(rcx, ConstrReason::Other);
 
         // Replace holes in generic arguments with fresh inference variables
         let generic_args = infcx.instantiate_generic_args(generic_args);
@@ -296,7 +373,9 @@ impl<'a, 'genv, 'tcx> ConstrGen<'a, 'genv, 'tcx> {
             .replace_bound_refts_with(|sort, mode, _| infcx.fresh_infer_var(sort, mode));
 
         // Check arguments
-        for (actual, formal) in iter::zip(fields, variant.fields()) {
+        let field_defs = &variant.adt_def.variant(variant_idx).fields;
+        for (field_def, actual, formal) in izip!(field_defs, fields, variant.fields()) {
+            infcx.tag = Tag::new(ConstrReason::Fold(field_def.did), infcx.tag.src_span);
             infcx.subtyping(rcx, actual, formal)?;
         }
 
@@ -313,6 +392,33 @@ impl<'a, 'genv, 'tcx> ConstrGen<'a, 'genv, 'tcx> {
         env: &mut TypeEnv,
         args: &[Ty],
         arr_ty: Ty,
+    ) -> Result<Ty> {
+        let arr_ty = self.check_array_elems(rcx, env, args, arr_ty)?;
+        Ok(Ty::array(arr_ty, rty::Const::from_array_len(self.genv.tcx(), args.len())))
+    }
+
+    /// Checks an array of the form `[x; N]`. As for [`ConstrGen::check_mk_array`], the type of the
+    /// elements is generalized from the type of `x`, so they can later be updated independently.
+    pub(crate) fn check_mk_repeat(
+        &mut self,
+        rcx: &mut RefineCtxt,
+        env: &mut TypeEnv,
+        ty: Ty,
+        elem_ty: Ty,
+        len: rty::Const,
+    ) -> Result<Ty> {
+        let elem_ty = self.check_array_elems(rcx, env, &[ty], elem_ty)?;
+        Ok(Ty::array(elem_ty, len))
+    }
+
+    /// Checks that the types of the elements of an array are subtypes of `arr_ty` and returns it
+    /// with its holes inferred.
+    fn check_array_elems(
+        &mut self,
+        rcx: &mut RefineCtxt,
+        env: &mut TypeEnv,
+        args: &[Ty],
+        arr_ty: Ty,
     ) -> Result<Ty> {
         let mut infcx = self.infcx(rcx, ConstrReason::Other);
 
@@ -334,7 +440,7 @@ impl<'a, 'genv, 'tcx> ConstrGen<'a, 'genv, 'tcx> {
         }
         rcx.replace_evars(&infcx.solve()?);
 
-        Ok(Ty::array(arr_ty, rty::Const::from_array_len(self.genv.tcx(), args.len())))
+        Ok(arr_ty)
     }
 
     pub(crate) fn infcx(
@@ -479,6 +585,11 @@ impl<'a, 'genv, 'tcx> InferCtxt<'a, 'genv, 'tcx> {
     }
 
     pub(crate) fn subtyping(&mut self, rcx: &mut RefineCtxt, ty1: &Ty, ty2: &Ty) -> Result {
+        // Subtyping is reflexive, so there's nothing to check if both types are syntactically
+        // equal. This is common after substitution and saves generating trivial constraints.
+        if ty1 == ty2 {
+            return Ok(());
+        }
         let rcx = &mut rcx.branch();
 
         // We *fully* unpack the rhs before continuing to be able to prove goals like this
@@ -608,12 +719,77 @@ impl<'a, 'genv, 'tcx> InferCtxt<'a, 'genv, 'tcx> {
                 }
                 Ok(())
             }
+            (BaseTy::FnPtr(sig1), BaseTy::FnPtr(sig2)) => self.fn_sig_subtyping(rcx, sig1, sig2),
             _ => {
                 panic!("unexpected base types: `{:?}` and `{:?}`", bty1, bty2,);
             }
         }
     }
 
+    /// A function with signature `sig1` can be used where a function with signature `sig2` is
+    /// expected if the arguments of `sig2` are subtypes of the arguments of `sig1` and the output
+    /// of `sig1` is a subtype of the output of `sig2`. The refinement parameters of `sig2` are
+    /// universally quantified while the ones of `sig1` are inferred. Type constraints in either
+    /// signature are not supported and reported as an error.
+    fn fn_sig_subtyping(
+        &mut self,
+        rcx: &mut RefineCtxt,
+        sig1: &PolyFnSig,
+        sig2: &PolyFnSig,
+    ) -> Result {
+        if has_type_constraints(sig1) || has_type_constraints(sig2) {
+            return Err(CheckerErrKind::FnPtrTypeConstraint);
+        }
+        let rcx = &mut rcx.branch();
+        let region_infcx = self.region_infcx;
+        let span = self.tag.src_span;
+        let mut fresh_region = |br: rty::BoundRegion| {
+            let re = region_infcx.next_region_var(BoundRegion(
+                span,
+                br.kind,
+                BoundRegionConversionTime::HigherRankedType,
+            ));
+            rty::ReVar(re.as_var())
+        };
+
+        let sig2 = sig2.replace_bound_vars(&mut fresh_region, |sort, _, kind| {
+            rcx.define_bound_reft_var(sort, kind)
+        });
+        for constr in sig2.requires() {
+            rcx.assume_pred(expect_pred_constraint(constr));
+        }
+
+        self.push_scope(rcx);
+        let sig1 = sig1.replace_bound_vars(&mut fresh_region, |sort, mode, _| {
+            self.fresh_infer_var(sort, mode)
+        });
+        for (arg2, arg1) in iter::zip(sig2.args(), sig1.args()) {
+            self.subtyping(rcx, arg2, arg1)?;
+        }
+        for constr in sig1.requires() {
+            self.check_pred(rcx, expect_pred_constraint(constr));
+        }
+
+        let output1 = sig1
+            .output()
+            .replace_bound_refts_with(|sort, _, kind| rcx.define_bound_reft_var(sort, kind));
+        for constr in &output1.ensures {
+            rcx.assume_pred(expect_pred_constraint(constr));
+        }
+
+        self.push_scope(rcx);
+        let output2 = sig2
+            .output()
+            .replace_bound_refts_with(|sort, mode, _| self.fresh_infer_var(sort, mode));
+        self.subtyping(rcx, &output1.ret, &output2.ret)?;
+        for constr in &output2.ensures {
+            self.check_pred(rcx, expect_pred_constraint(constr));
+        }
+        self.pop_scope();
+        self.pop_scope();
+        Ok(())
+    }
+
     fn project_bty(&mut self, self_ty: &Ty, def_id: DefId) -> Result<Ty> {
         let args = List::singleton(GenericArg::Ty(self_ty.clone()));
         let alias_ty = rty::AliasTy::new(def_id, args, List::empty());
@@ -744,6 +920,23 @@ impl Obligations {
     }
 }
 
+/// Whether the signature has type constraints, i.e., strong references in its arguments. These are
+/// not supported in the signature of function pointers.
+fn has_type_constraints(sig: &PolyFnSig) -> bool {
+    let sig = sig.as_ref().skip_binder();
+    sig.requires()
+        .iter()
+        .chain(sig.output().as_ref().skip_binder().ensures.iter())
+        .any(|constr| matches!(constr, Constraint::Type(..)))
+}
+
+fn expect_pred_constraint(constr: &Constraint) -> &Expr {
+    match constr {
+        Constraint::Pred(pred) => pred,
+        Constraint::Type(..) => bug!("unexpected type constraint in function pointer `{constr:?}`"),
+    }
+}
+
 fn mk_generator_obligations(
     genv: GlobalEnv,
     generator_did: &DefId,
@@ -774,10 +967,31 @@ fn mk_obligations(
     args: &[GenericArg],
     refine_args: &[Expr],
 ) -> Result<List<rty::Clause>> {
-    Ok(genv
-        .predicates_of(did)?
+    let predicates = genv.predicates_of(did)?;
+    let parent = predicates.as_ref().skip_binder().parent;
+    let mut obligs = predicates
         .predicates()
-        .instantiate(args, refine_args))
+        .instantiate(args, refine_args)
+        .to_vec();
+
+    // A closure stored in the receiver of a method is bounded in the parent, e.g., by the bound
+    // `F: FnMut(I::Item) -> B` in `impl<B, I, F> Iterator for Map<I, F>`. Checking the closure at
+    // each call relates the output of the method (`B`) to the output of the closure.
+    if let Some(parent) = parent {
+        let parent_obligs = genv
+            .predicates_of(parent)?
+            .instantiate(genv, args, refine_args)?;
+        obligs.extend(parent_obligs.into_iter().filter(is_closure_bound));
+    }
+    Ok(List::from_vec(obligs))
+}
+
+fn is_closure_bound(clause: &rty::Clause) -> bool {
+    if let rty::ClauseKind::FnTrait(pred) = clause.kind() {
+        matches!(pred.self_ty.as_bty_skipping_existentials(), Some(BaseTy::Closure(..)))
+    } else {
+        false
+    }
 }
 
 impl<F> KVarGen for F
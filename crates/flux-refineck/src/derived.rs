@@ -0,0 +1,258 @@
+//! Built-in specifications for the methods of derived traits.
+//!
+//! The methods generated by `#[derive(PartialEq)]` compare two values field by field. Their default
+//! signature doesn't say anything about the returned boolean, so an equality test between two
+//! values of a refined struct doesn't tell us anything about their indices. However, if the index
+//! of a struct is *structural*, i.e., every field is an integer or a boolean indexed by one of the
+//! refinement parameters of the struct and every parameter indexes at least one field, then two
+//! values are equal exactly when their indices are. For such structs we give `eq` the signature
+//!
+//! ```ignore
+//! fn(&S[@a], &S[@b]) -> bool[a.f0 == b.f0 && ... && a.fn == b.fn]
+//! ```
+//!
+//! and `ne` the negation, so equality tests refine subsequent branches.
+//!
+//! Similarly, `#[derive(PartialOrd)]` on a *newtype*, i.e., a structural struct with a single
+//! integer field, orders values as their indices. We give the comparison methods of `PartialOrd`
+//! (`lt`, `le`, `gt` and `ge`) a signature relating the result with the order of the indices, e.g.,
+//! `fn(&S[@a], &S[@b]) -> bool[a.f0 < b.f0]` for `lt`. The prelude indexes `Ordering` by `-1`,
+//! `0` or `1`, so we also give `Ord::cmp` the signature
+//!
+//! ```ignore
+//! fn(&S[@a], &S[@b]) -> Ordering[if a.f0 < b.f0 { -1 } else if a.f0 == b.f0 { 0 } else { 1 }]
+//! ```
+//!
+//! and `PartialOrd::partial_cmp` the same ordering wrapped in an `Option`.
+//!
+//! Only `eq`, `cmp` and `partial_cmp` are generated by the derives. The rest of the methods are
+//! default methods of the traits, in which case we look for a derived implementation for the type
+//! of the receiver.
+use flux_common::bug;
+use flux_middle::{
+    global_env::GlobalEnv,
+    intern::List,
+    queries::QueryResult,
+    rty::{
+        AdtDef, BaseTy, BinOp, Binder, BoundReft, BoundReftKind, BoundVariableKind, EarlyBinder,
+        Expr, ExprKind, FieldProj, FnOutput, FnSig, GenericArg, InferMode, Opaqueness, PolyFnSig,
+        Sort, Ty, TyKind, Var, FIRST_VARIANT, INNERMOST,
+    },
+};
+use rustc_hir::{def_id::DefId, LangItem};
+use rustc_index::bit_set::BitSet;
+use rustc_middle::ty::{GenericArgsRef, TyCtxt};
+use rustc_span::sym;
+
+/// Returns the built-in signature for a call to `def_id` with `args` if it is a method of a derived
+/// trait we know how to specify.
+pub(crate) fn fn_sig<'tcx>(
+    genv: GlobalEnv<'_, 'tcx>,
+    def_id: DefId,
+    args: GenericArgsRef<'tcx>,
+) -> QueryResult<Option<EarlyBinder<PolyFnSig>>> {
+    let tcx = genv.tcx();
+    let (trait_id, self_ty) = if let Some(impl_id) = tcx.impl_of_method(def_id) {
+        let Some(trait_id) = tcx.trait_id_of_impl(impl_id) else { return Ok(None) };
+        (trait_id, tcx.type_of(impl_id).instantiate_identity())
+    } else if let Some(trait_id) = tcx.trait_of_item(def_id) {
+        (trait_id, args.type_at(0))
+    } else {
+        return Ok(None);
+    };
+    let Some(rustc_adt_def) = self_ty.ty_adt_def() else { return Ok(None) };
+    let adt_did = rustc_adt_def.did();
+
+    let lang_items = tcx.lang_items();
+    let name = tcx.item_name(def_id);
+    let (rel, adt_def) = if Some(trait_id) == lang_items.eq_trait() {
+        let rel = match name {
+            sym::eq => Rel::Eq,
+            sym::ne => Rel::Ne,
+            _ => return Ok(None),
+        };
+        let Some(adt_def) = structural_adt(genv, adt_did)? else { return Ok(None) };
+        (rel, adt_def)
+    } else if Some(trait_id) == lang_items.partial_ord_trait() {
+        let rel = match name {
+            sym::lt => Rel::Cmp(BinOp::Lt(Sort::Int)),
+            sym::le => Rel::Cmp(BinOp::Le(Sort::Int)),
+            sym::gt => Rel::Cmp(BinOp::Gt(Sort::Int)),
+            sym::ge => Rel::Cmp(BinOp::Ge(Sort::Int)),
+            sym::partial_cmp => {
+                let Some(ordering) = ordering_adt(genv)? else { return Ok(None) };
+                let option = genv.adt_def(tcx.require_lang_item(LangItem::Option, None))?;
+                Rel::PartialOrdering(option, ordering)
+            }
+            _ => return Ok(None),
+        };
+        let Some(adt_def) = newtype_adt(genv, adt_did)? else { return Ok(None) };
+        (rel, adt_def)
+    } else if Some(trait_id) == tcx.get_diagnostic_item(sym::Ord) {
+        if name != sym::cmp {
+            return Ok(None);
+        }
+        let Some(ordering) = ordering_adt(genv)? else { return Ok(None) };
+        let Some(adt_def) = newtype_adt(genv, adt_did)? else { return Ok(None) };
+        (Rel::Ordering(ordering), adt_def)
+    } else {
+        return Ok(None);
+    };
+    if !has_derived_impl(tcx, trait_id, adt_did) {
+        return Ok(None);
+    }
+    Ok(Some(rel_sig(genv, def_id, rel, &adt_def)?))
+}
+
+/// The relation between the indices of the arguments a derived method returns
+enum Rel {
+    Eq,
+    Ne,
+    Cmp(BinOp),
+    /// The ordering of the indices, i.e., an `Ordering` as given by its [`AdtDef`]
+    Ordering(AdtDef),
+    /// The ordering of the indices wrapped in an `Option` as given by the [`AdtDef`]s of `Option`
+    /// and `Ordering`
+    PartialOrdering(AdtDef, AdtDef),
+}
+
+/// Builds the signature `fn(&S[@a], &S[@b]) -> bool[rel(a, b)]` (or the corresponding ordering)
+/// reusing the regions of the signature of `def_id`
+fn rel_sig(
+    genv: GlobalEnv,
+    def_id: DefId,
+    rel: Rel,
+    adt_def: &AdtDef,
+) -> QueryResult<EarlyBinder<PolyFnSig>> {
+    let fn_sig = genv.fn_sig(def_id)?.skip_binder();
+    let nvars = fn_sig.vars().len() as u32;
+    let a = Expr::late_bvar(INNERMOST, nvars, BoundReftKind::Annon);
+    let b = Expr::late_bvar(INNERMOST, nvars + 1, BoundReftKind::Annon);
+
+    let inputs = fn_sig
+        .as_ref()
+        .skip_binder()
+        .args()
+        .iter()
+        .zip([&a, &b])
+        .map(|(arg, idx)| {
+            let Some(BaseTy::Ref(re, _, mutbl)) = arg.as_bty_skipping_existentials() else {
+                bug!("unexpected argument of derived method `{arg:?}`")
+            };
+            let bty = BaseTy::adt(adt_def.clone(), List::empty());
+            Ty::mk_ref(*re, Ty::indexed(bty, idx.clone()), *mutbl)
+        })
+        .collect::<Vec<_>>();
+
+    let proj = |e: &Expr, field| e.proj_and_reduce(FieldProj::Adt { def_id: adt_def.did(), field });
+    let fields = 0..adt_def.sort_def().fields() as u32;
+    let ordering = |ordering_def: &AdtDef| {
+        let (a, b) = (proj(&a, 0), proj(&b, 0));
+        let idx = Expr::ite(
+            Expr::lt(a.clone(), b.clone()),
+            -1,
+            Expr::ite(Expr::eq(a, b), 0, 1, None),
+            None,
+        );
+        Ty::indexed(BaseTy::adt(ordering_def.clone(), List::empty()), idx)
+    };
+    let ret = match rel {
+        Rel::Eq => {
+            Ty::indexed(BaseTy::Bool, Expr::and(fields.map(|f| Expr::eq(proj(&a, f), proj(&b, f)))))
+        }
+        Rel::Ne => {
+            let eq = Expr::and(fields.map(|f| Expr::eq(proj(&a, f), proj(&b, f))));
+            Ty::indexed(BaseTy::Bool, eq.not())
+        }
+        Rel::Cmp(op) => {
+            Ty::indexed(BaseTy::Bool, Expr::binary_op(op, proj(&a, 0), proj(&b, 0), None))
+        }
+        Rel::Ordering(ordering_def) => ordering(&ordering_def),
+        Rel::PartialOrdering(option_def, ordering_def) => {
+            // The ordering is under the binder of the existential for the `Option`
+            let args = vec![GenericArg::Ty(ordering(&ordering_def).shift_in_escaping(1))];
+            BaseTy::adt(option_def, args).into_ty()
+        }
+    };
+
+    // The index of the returned value is under the binder of the output
+    let ret = ret.shift_in_escaping(1);
+    let output = Binder::new(FnOutput::new(ret, vec![]), List::empty());
+
+    let var = BoundVariableKind::Refine(adt_def.sort(&[]), InferMode::EVar, BoundReftKind::Annon);
+    let vars = fn_sig
+        .vars()
+        .iter()
+        .cloned()
+        .chain([var.clone(), var])
+        .collect();
+    Ok(EarlyBinder(PolyFnSig::new(FnSig::new(vec![], inputs, output), vars)))
+}
+
+/// Whether there's a derived implementation of `trait_id` for the adt `adt_did`
+fn has_derived_impl(tcx: TyCtxt, trait_id: DefId, adt_did: DefId) -> bool {
+    tcx.all_impls(trait_id).any(|impl_id| {
+        tcx.has_attr(impl_id, sym::automatically_derived)
+            && tcx
+                .type_of(impl_id)
+                .instantiate_identity()
+                .ty_adt_def()
+                .map(|adt| adt.did())
+                == Some(adt_did)
+    })
+}
+
+/// Returns the [`AdtDef`] for `adt_did` if it is a non-generic struct with a structural index
+fn structural_adt(genv: GlobalEnv, adt_did: DefId) -> QueryResult<Option<AdtDef>> {
+    let adt_def = genv.adt_def(adt_did)?;
+    if !adt_def.is_struct() || genv.tcx().generics_of(adt_did).count() > 0 {
+        return Ok(None);
+    }
+    let Opaqueness::Transparent(variant) = genv.variant_sig(adt_did, FIRST_VARIANT)? else {
+        return Ok(None);
+    };
+    let variant = variant.skip_binder();
+    let mut indexed = BitSet::new_empty(variant.vars().len());
+    for field in variant.as_ref().skip_binder().fields.iter() {
+        if let TyKind::Indexed(BaseTy::Int(_) | BaseTy::Uint(_) | BaseTy::Bool, idx) = field.kind()
+            && let ExprKind::Var(Var::LateBound(INNERMOST, BoundReft { index, .. })) = idx.kind()
+        {
+            indexed.insert(*index as usize);
+        } else {
+            return Ok(None);
+        }
+    }
+    if indexed.count() != variant.vars().len() {
+        return Ok(None);
+    }
+    Ok(Some(adt_def))
+}
+
+/// Returns the [`AdtDef`] for `Ordering` if it is indexed by an integer as in the prelude
+fn ordering_adt(genv: GlobalEnv) -> QueryResult<Option<AdtDef>> {
+    let Some(def_id) = genv.tcx().lang_items().get(LangItem::OrderingEnum) else {
+        return Ok(None);
+    };
+    let adt_def = genv.adt_def(def_id)?;
+    if let [Sort::Int] = &adt_def.sort_def().sorts(&[])[..] {
+        Ok(Some(adt_def))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns the [`AdtDef`] for `adt_did` if it is a structural struct with a single integer field
+fn newtype_adt(genv: GlobalEnv, adt_did: DefId) -> QueryResult<Option<AdtDef>> {
+    let Some(adt_def) = structural_adt(genv, adt_did)? else { return Ok(None) };
+    let Opaqueness::Transparent(variant) = genv.variant_sig(adt_did, FIRST_VARIANT)? else {
+        return Ok(None);
+    };
+    let variant = variant.skip_binder();
+    if let [field] = &variant.as_ref().skip_binder().fields[..]
+        && let TyKind::Indexed(BaseTy::Int(_) | BaseTy::Uint(_), _) = field.kind()
+    {
+        Ok(Some(adt_def))
+    } else {
+        Ok(None)
+    }
+}
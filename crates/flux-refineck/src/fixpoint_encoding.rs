@@ -1,6 +1,6 @@
 //! Encoding of the refinement tree into a fixpoint constraint.
 
-use std::{hash::Hash, iter, ops::ControlFlow};
+use std::{collections::VecDeque, hash::Hash, io, iter, ops::ControlFlow, thread, time::Duration};
 
 use flux_common::{
     bug,
@@ -11,7 +11,7 @@ use flux_common::{
     span_bug,
 };
 use flux_config as config;
-use flux_fixpoint::FixpointResult;
+use flux_fixpoint::{FixpointResult, KVarBind, Response, TaskPool};
 use flux_middle::{
     fhir::SpecFuncKind,
     global_env::GlobalEnv,
@@ -28,12 +28,13 @@ use rustc_data_structures::{
     fx::FxIndexMap,
     unord::{UnordMap, UnordSet},
 };
+use rustc_hash::FxHashSet;
 use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_index::newtype_index;
-use rustc_span::Span;
+use rustc_span::{Span, Symbol};
 use rustc_type_ir::DebruijnIndex;
 
-use crate::{refine_tree::Scope, CheckerConfig};
+use crate::{qualifier_mining, CheckerConfig};
 
 newtype_index! {
     #[debug_format = "TagIdx({})"]
@@ -43,6 +44,31 @@ newtype_index! {
 #[derive(Default)]
 pub struct KVarStore {
     kvars: IndexVec<rty::KVid, KVarDecl>,
+    /// The kvars at the entry of loops whose solution is reported, see [`config::show_invariants`]
+    loop_heads: Vec<LoopHead>,
+    /// The kvar standing for the postcondition of the function being checked if we are suggesting
+    /// a signature for it, see [`config::suggest_sigs`]
+    sig_template: Option<SigTemplate>,
+}
+
+struct LoopHead {
+    kvid: rty::KVid,
+    span: Span,
+    /// The name of each argument of the kvar in the source
+    arg_names: Vec<String>,
+}
+
+/// A signature with a kvar in place of the predicate refining the returned value
+pub(crate) struct SigTemplate {
+    pub kvid: rty::KVid,
+    pub span: Span,
+    /// The name of each argument of the kvar, i.e., the returned value followed by the arguments
+    /// of the function
+    pub arg_names: Vec<String>,
+    /// The arguments of the signature, e.g., `a: i32, b: bool`
+    pub inputs: String,
+    /// The returned type without refinements, e.g., `i32`
+    pub output: String,
 }
 
 #[derive(Clone)]
@@ -135,7 +161,7 @@ pub mod fixpoint {
         UIFRel(BinRel),
         /// Interpreted theory function. This can be an arbitrary string, thus we are assuming the
         /// name is different than the display implementation for the other variants.
-        Itf(Symbol),
+        Itf(&'static str),
     }
 
     impl From<GlobalVar> for Var {
@@ -183,7 +209,6 @@ pub mod fixpoint {
         type Tag = super::TagIdx;
     }
     pub use fixpoint_generated::*;
-    use rustc_span::Symbol;
 }
 
 type ConstMap<'tcx> = FxIndexMap<Key<'tcx>, ConstInfo>;
@@ -194,6 +219,7 @@ enum Key<'tcx> {
     Const(DefId),
     Alias(rustc_middle::ty::TraitRef<'tcx>),
     Lambda(Lambda),
+    ConstGeneric(rustc_middle::ty::ParamConst),
 }
 
 pub struct FixpointCtxt<'genv, 'tcx, T: Eq + Hash> {
@@ -206,11 +232,328 @@ pub struct FixpointCtxt<'genv, 'tcx, T: Eq + Hash> {
     env: Env,
     tags: IndexVec<TagIdx, T>,
     tags_inv: UnordMap<T, TagIdx>,
+    explanations: IndexVec<TagIdx, Explanation>,
+    /// Spanned hypotheses in scope together with their free variables, see [`Self::with_facts`]
+    facts_in_scope: Vec<(Span, FxHashSet<rty::Name>)>,
+    /// Variables in scope that were bound in the surface syntax together with their surface name
+    surface_names: Vec<(rty::Name, Symbol)>,
     /// [`DefId`] of the item being checked. This could be a function/method or an adt when checking
     /// invariants.
     def_id: LocalDefId,
 }
 
+/// A fixpoint query ready to be solved. Solving it doesn't require the [`GlobalEnv`], so the
+/// queries of different items can be solved concurrently (see [`QuerySolver`]).
+pub struct FixpointQuery<Tag> {
+    key: String,
+    span: Span,
+    /// `None` if the constraint is trivially true
+    task: Option<fixpoint::Task>,
+    /// The timeout of the task. It's kept apart because the task is moved to the thread solving it.
+    timeout: Option<Duration>,
+    tags: IndexVec<TagIdx, Tag>,
+    explanations: IndexVec<TagIdx, Explanation>,
+    /// The kvars encoding the invariant of each loop head, see [`config::show_invariants`]
+    loop_heads: Vec<LoopHeadKVars>,
+    /// The signature whose postcondition is inferred, see [`config::suggest_sigs`]
+    sig_template: Option<SigTemplateKVars>,
+}
+
+struct LoopHeadKVars {
+    span: Span,
+    /// Each fixpoint kvar encoding the invariant together with the names of its parameters
+    kvars: Vec<(fixpoint::KVid, Vec<String>)>,
+}
+
+struct SigTemplateKVars {
+    span: Span,
+    inputs: String,
+    output: String,
+    /// The name of the returned value in the postcondition
+    nu: String,
+    kvars: Vec<(fixpoint::KVid, Vec<String>)>,
+}
+
+/// A loop invariant inferred by fixpoint
+#[derive(Debug)]
+pub struct InferredInvariant {
+    pub span: Span,
+    pub pred: String,
+}
+
+/// A signature for a function without one, with the postcondition inferred by fixpoint
+#[derive(Debug)]
+pub struct SuggestedSig {
+    pub span: Span,
+    pub sig: String,
+}
+
+/// The result of solving a [`FixpointQuery`]
+pub struct QueryOutcome<Tag> {
+    pub errors: QueryErrors<Tag>,
+    /// Empty unless [`config::show_invariants`] is set
+    pub invariants: Vec<InferredInvariant>,
+    /// `None` unless [`config::suggest_sigs`] is set and the function is a candidate for it
+    pub suggested_sig: Option<SuggestedSig>,
+}
+
+/// The solver didn't finish solving a [`FixpointQuery`] within its timeout
+#[derive(Clone, Copy, Debug)]
+pub struct Timeout {
+    pub span: Span,
+    pub secs: u64,
+}
+
+/// A constraint that failed to be proved
+#[derive(Debug)]
+pub struct QueryError<Tag> {
+    pub tag: Tag,
+    /// Spans of the hypotheses in scope that mention a variable of the failing constraint, e.g.,
+    /// branch conditions and preconditions of the function being checked. Errors point at them to
+    /// show which facts were too weak to prove the constraint.
+    pub facts: Vec<Span>,
+    pub pred: Option<FailedPred>,
+}
+
+/// The predicate of a failing constraint. We keep the surface name of the variables it mentions
+/// (if any) so the predicate can be printed back in terms of the source, e.g., to suggest adding
+/// it as a precondition.
+#[derive(Clone, Debug)]
+pub struct FailedPred {
+    pub expr: rty::Expr,
+    pub surface_names: Vec<(rty::Name, Symbol)>,
+}
+
+/// What we know about the constraints with a given tag to explain why they fail, see [`QueryError`]
+#[derive(Clone, Default)]
+struct Explanation {
+    facts: Vec<Span>,
+    pred: Option<FailedPred>,
+}
+
+/// The errors found when solving a [`FixpointQuery`]
+pub type QueryErrors<Tag> = Result<Vec<QueryError<Tag>>, Timeout>;
+
+/// Statistics about solving a [`FixpointQuery`]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct SolveStats {
+    pub kvars: usize,
+    /// Number of constraints reported by fixpoint, `0` if the result was cached
+    pub constraints: usize,
+    pub time: Duration,
+}
+
+impl<Tag: Hash + Eq + Copy> FixpointQuery<Tag> {
+    pub fn solve(self, cache: &QueryCache) -> QueryOutcome<Tag> {
+        let response = self
+            .task
+            .as_ref()
+            .map(|task| task.check_with_cache(self.key.clone(), cache));
+        self.outcome(response)
+    }
+
+    fn outcome(&self, response: Option<io::Result<Response<TagIdx>>>) -> QueryOutcome<Tag> {
+        let (result, solution) = match response {
+            Some(Ok(Response { result, solution })) => (Some(Ok(result)), solution),
+            Some(Err(err)) => (Some(Err(err)), vec![]),
+            None => (None, vec![]),
+        };
+        QueryOutcome {
+            errors: self.errors(result),
+            invariants: self.invariants(&solution),
+            suggested_sig: self.suggested_sig(&solution),
+        }
+    }
+
+    /// Maps the solution of the kvars back to the loop heads they were generated for. Fixpoint
+    /// names the parameters of a kvar with a suffix `##i` for the `i`-th parameter (e.g.,
+    /// `lq_karg$nnf_arg$k0##1`) and we rename them to the name of the corresponding variable in
+    /// the source.
+    fn invariants(&self, solution: &[KVarBind]) -> Vec<InferredInvariant> {
+        if solution.is_empty() {
+            return vec![];
+        }
+        self.loop_heads
+            .iter()
+            .filter_map(|loop_head| {
+                let pred = solved_pred(solution, &loop_head.kvars)?;
+                Some(InferredInvariant { span: loop_head.span, pred })
+            })
+            .collect()
+    }
+
+    /// Fills the postcondition of the signature template with the solution of its kvars. Nothing
+    /// is suggested if the solution is trivial because it'd be the same as the default signature.
+    fn suggested_sig(&self, solution: &[KVarBind]) -> Option<SuggestedSig> {
+        let template = self.sig_template.as_ref()?;
+        let pred = solved_pred(solution, &template.kvars)?;
+        let SigTemplateKVars { span, inputs, output, nu, .. } = template;
+        let pred = fixpoint_to_surface(&pred);
+        Some(SuggestedSig { span: *span, sig: format!("fn({inputs}) -> {output}{{{nu}: {pred}}}") })
+    }
+
+    fn errors(&self, result: Option<io::Result<FixpointResult<TagIdx>>>) -> QueryErrors<Tag> {
+        let span = self.span;
+        match result {
+            None | Some(Ok(FixpointResult::Safe(_))) => Ok(vec![]),
+            Some(Ok(FixpointResult::Unsafe(_, errors))) => {
+                Ok(errors
+                    .into_iter()
+                    .map(|err| err.tag)
+                    .unique()
+                    .map(|idx| {
+                        let Explanation { facts, pred } = self.explanations[idx].clone();
+                        QueryError { tag: self.tags[idx], facts, pred }
+                    })
+                    .collect_vec())
+            }
+            Some(Ok(FixpointResult::Crash(err))) => span_bug!(span, "fixpoint crash: {err:?}"),
+            Some(Err(err)) if err.kind() == io::ErrorKind::TimedOut => {
+                Err(Timeout { span, secs: self.timeout.map_or(0, |timeout| timeout.as_secs()) })
+            }
+            Some(Err(err)) => span_bug!(span, "failed to run fixpoint: {err:?}"),
+        }
+    }
+}
+
+/// Solves fixpoint queries on a [`TaskPool`] while more queries are being generated. Outcomes are
+/// reported in the order the queries were submitted, as soon as a query and all the ones before it
+/// are solved, so errors can be reported deterministically while other queries are still being
+/// solved.
+pub struct QuerySolver<Tag> {
+    pool: TaskPool<fixpoint::FixpointTypes>,
+    /// The queries that haven't been reported yet in the order they were submitted, together with
+    /// their outcome once they are solved
+    pending: VecDeque<PendingQuery<Tag>>,
+    /// Number of queries reported so far, i.e., the index of the first query in `pending`
+    reported: usize,
+    /// The index of the query each task in the pool belongs to
+    owners: Vec<usize>,
+}
+
+struct PendingQuery<Tag> {
+    query: FixpointQuery<Tag>,
+    kvars: usize,
+    solved: Option<(QueryOutcome<Tag>, SolveStats)>,
+}
+
+impl<Tag: Hash + Eq + Copy> QuerySolver<Tag> {
+    pub fn new<'scope, 'env>(
+        scope: &'scope thread::Scope<'scope, 'env>,
+        cache: &'env QueryCache,
+    ) -> Self {
+        QuerySolver {
+            pool: TaskPool::new(scope, cache),
+            pending: VecDeque::new(),
+            reported: 0,
+            owners: vec![],
+        }
+    }
+
+    /// Starts solving `query` in the background and returns its index. Queries are numbered in the
+    /// order they are submitted.
+    pub fn submit(&mut self, mut query: FixpointQuery<Tag>) -> usize {
+        let idx = self.reported + self.pending.len();
+        let kvars = query.task.as_ref().map_or(0, |task| task.kvars.len());
+        let solved = match query.task.take() {
+            Some(task) => {
+                self.pool.submit(query.key.clone(), task);
+                self.owners.push(idx);
+                None
+            }
+            None => Some((query.outcome(None), SolveStats::default())),
+        };
+        self.pending
+            .push_back(PendingQuery { query, kvars, solved });
+        idx
+    }
+
+    /// Calls `on_outcome` with the index of each query solved since the last call, the outcome of
+    /// solving it and statistics about solving it. If `wait` is true, this blocks until all the
+    /// submitted queries are solved.
+    pub fn poll(
+        &mut self,
+        wait: bool,
+        mut on_outcome: impl FnMut(usize, QueryOutcome<Tag>, SolveStats),
+    ) {
+        let QuerySolver { pool, pending, reported, owners } = self;
+        pool.poll(wait, |task_idx, response, time| {
+            let constraints = match &response {
+                Ok(Response {
+                    result: FixpointResult::Safe(stats) | FixpointResult::Unsafe(stats, _),
+                    ..
+                }) => stats.num_cstr as usize,
+                _ => 0,
+            };
+            let pending = &mut pending[owners[task_idx] - *reported];
+            let outcome = pending.query.outcome(Some(response));
+            let stats = SolveStats { kvars: pending.kvars, constraints, time };
+            pending.solved = Some((outcome, stats));
+        });
+        while let Some((outcome, stats)) = pending.front_mut().and_then(|p| p.solved.take()) {
+            pending.pop_front();
+            on_outcome(*reported, outcome, stats);
+            *reported += 1;
+        }
+    }
+}
+
+/// The conjunction of the solution of `kvars` with their parameters renamed, or `None` if it is
+/// trivially true.
+fn solved_pred(solution: &[KVarBind], kvars: &[(fixpoint::KVid, Vec<String>)]) -> Option<String> {
+    let preds = kvars
+        .iter()
+        .filter_map(|(kvid, names)| {
+            let bind = solution
+                .iter()
+                .find(|bind| bind.kvar.trim_start_matches('$') == kvid.to_string())?;
+            Some(rename_kvar_params(&bind.val, names))
+        })
+        .filter(|pred| pred != "true")
+        .collect_vec();
+    if preds.is_empty() {
+        return None;
+    }
+    Some(preds.join(" && "))
+}
+
+/// Fixpoint writes equality as `=`, but the surface syntax requires `==`
+fn fixpoint_to_surface(pred: &str) -> String {
+    let mut surface = String::with_capacity(pred.len());
+    let mut prev = ' ';
+    let mut chars = pred.chars().peekable();
+    while let Some(c) = chars.next() {
+        let next = chars.peek().copied().unwrap_or(' ');
+        if c == '=' && !matches!(prev, '<' | '>' | '!' | '=') && !matches!(next, '=' | '>') {
+            surface.push_str("==");
+        } else {
+            surface.push(c);
+        }
+        prev = c;
+    }
+    surface
+}
+
+/// Replaces every identifier in `pred` ending with `##i` by `names[i]`
+fn rename_kvar_params(pred: &str, names: &[String]) -> String {
+    let is_ident_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '$' | '#' | '\'');
+    let mut renamed = String::with_capacity(pred.len());
+    let mut rest = pred;
+    while let Some(start) = rest.find(is_ident_char) {
+        renamed.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(|c| !is_ident_char(c)).unwrap_or(rest.len());
+        let ident = &rest[..end];
+        let name = ident
+            .rsplit_once("##")
+            .and_then(|(_, idx)| names.get(idx.parse::<usize>().ok()?));
+        renamed.push_str(name.map_or(ident, String::as_str));
+        rest = &rest[end..];
+    }
+    renamed.push_str(rest);
+    renamed
+}
+
 struct FixpointKVar {
     sorts: Vec<fixpoint::Sort>,
     orig: rty::KVid,
@@ -225,6 +568,26 @@ struct KVarEncodingCtxt {
 }
 
 impl KVarEncodingCtxt {
+    /// The fixpoint kvars encoding `kvid` together with the names of their parameters, or `None`
+    /// if the kvar doesn't appear in the constraint.
+    fn named_kvars(
+        &self,
+        kvid: rty::KVid,
+        arg_names: &[String],
+    ) -> Option<Vec<(fixpoint::KVid, Vec<String>)>> {
+        let kvids = self.map.get(&kvid)?;
+        let kvars = kvids
+            .iter()
+            .map(|kvid| {
+                // The fixpoint kvars encoding a kvar take a suffix of its arguments, see
+                // `KVarEncodingCtxt::encode`
+                let arity = self.kvars[*kvid].sorts.len();
+                (*kvid, arg_names[arg_names.len().saturating_sub(arity)..].to_vec())
+            })
+            .collect();
+        Some(kvars)
+    }
+
     fn encode(&mut self, kvid: rty::KVid, decl: &KVarDecl) -> &[fixpoint::KVid] {
         self.map.entry(kvid).or_insert_with(|| {
             let all_args = decl.sorts.iter().map(sort_to_fixpoint).collect_vec();
@@ -305,7 +668,7 @@ impl Env {
                     span_bug!(dbg_span, "no entry found for late bound var: `{var:?}`")
                 })
             }
-            rty::Var::EarlyParam(_) | rty::Var::EVar(_) => {
+            rty::Var::EarlyParam(_) | rty::Var::EVar(_) | rty::Var::ConstGeneric(_) => {
                 span_bug!(dbg_span, "unexpected var: `{var:?}`")
             }
         }
@@ -377,6 +740,9 @@ where
             kcx: Default::default(),
             tags: IndexVec::new(),
             tags_inv: Default::default(),
+            explanations: IndexVec::new(),
+            facts_in_scope: vec![],
+            surface_names: vec![],
             def_id,
         })
     }
@@ -411,10 +777,17 @@ where
     pub(crate) fn with_name_map<R>(
         &mut self,
         name: rty::Name,
+        surface_name: Option<Symbol>,
         f: impl FnOnce(&mut Self, fixpoint::LocalVar) -> R,
     ) -> R {
         let fresh = self.env.insert_fvar_map(name);
+        if let Some(sym) = surface_name {
+            self.surface_names.push((name, sym));
+        }
         let r = f(self, fresh);
+        if surface_name.is_some() {
+            self.surface_names.pop();
+        }
         self.env.remove_fvar_map(name);
         r
     }
@@ -442,16 +815,56 @@ where
     }
 
     pub fn check(
+        self,
+        cache: &QueryCache,
+        constraint: fixpoint::Constraint,
+        config: &CheckerConfig,
+    ) -> QueryResult<QueryErrors<Tag>> {
+        Ok(self.into_query(constraint, config)?.solve(cache).errors)
+    }
+
+    /// Builds the fixpoint query for the constraint without solving it.
+    pub fn into_query(
         mut self,
-        cache: &mut QueryCache,
         constraint: fixpoint::Constraint,
         config: &CheckerConfig,
-    ) -> QueryResult<Vec<Tag>> {
+    ) -> QueryResult<FixpointQuery<Tag>> {
+        let key = self.genv.tcx().def_path_str(self.def_id);
+        let span = self.def_span();
         if !constraint.is_concrete() {
             // skip checking trivial constraints
-            return Ok(vec![]);
+            return Ok(FixpointQuery {
+                key,
+                span,
+                task: None,
+                timeout: None,
+                tags: self.tags,
+                explanations: self.explanations,
+                loop_heads: vec![],
+                sig_template: None,
+            });
         }
-        let span = self.def_span();
+
+        let loop_heads = self
+            .kvars
+            .loop_heads
+            .iter()
+            .filter_map(|loop_head| {
+                let kvars = self.kcx.named_kvars(loop_head.kvid, &loop_head.arg_names)?;
+                Some(LoopHeadKVars { span: loop_head.span, kvars })
+            })
+            .collect();
+
+        let sig_template = self.kvars.sig_template.as_ref().and_then(|template| {
+            let kvars = self.kcx.named_kvars(template.kvid, &template.arg_names)?;
+            Some(SigTemplateKVars {
+                span: template.span,
+                inputs: template.inputs.clone(),
+                output: template.output.clone(),
+                nu: template.arg_names[0].clone(),
+                kvars,
+            })
+        });
 
         let kvars = self
             .kcx
@@ -469,11 +882,14 @@ where
             }
         }
 
-        let qualifiers = self
+        let mut qualifiers: Vec<_> = self
             .genv
             .qualifiers_for(self.def_id)?
             .map(|qual| self.ecx.qualifier_to_fixpoint(qual))
             .try_collect()?;
+        if config.mine_quals {
+            qualifiers.extend(qualifier_mining::mine(&constraint));
+        }
 
         let mut constants = self
             .ecx
@@ -503,26 +919,33 @@ where
             constraint,
             qualifiers,
             scrape_quals: config.scrape_quals,
+            solver: config.solver,
+            solver_args: config.solver_args,
+            smt_backend: config.smt_backend,
+            timeout: (config.timeout > 0).then(|| Duration::from_secs(config.timeout)),
             data_decls: self.sorts.into_data_decls(),
         };
         if config::dump_constraint() {
             dbg::dump_item_info(self.genv.tcx(), self.def_id, "smt2", &task).unwrap();
         }
-
-        let task_key = self.genv.tcx().def_path_str(self.def_id);
-
-        match task.check_with_cache(task_key, cache) {
-            Ok(FixpointResult::Safe(_)) => Ok(vec![]),
-            Ok(FixpointResult::Unsafe(_, errors)) => {
-                Ok(errors
-                    .into_iter()
-                    .map(|err| self.tags[err.tag])
-                    .unique()
-                    .collect_vec())
-            }
-            Ok(FixpointResult::Crash(err)) => span_bug!(span, "fixpoint crash: {err:?}"),
-            Err(err) => span_bug!(span, "failed to run fixpoint: {err:?}"),
+        let emit = config::emit();
+        if emit.fixpoint {
+            dbg::emit_for_item(self.genv.tcx(), self.def_id, "fq", &task.to_string()).unwrap();
+        }
+        if emit.smtlib {
+            dbg::emit_for_item(self.genv.tcx(), self.def_id, "smt2", &task.to_smtlib()).unwrap();
         }
+
+        Ok(FixpointQuery {
+            key,
+            span,
+            timeout: task.timeout,
+            task: Some(task),
+            tags: self.tags,
+            explanations: self.explanations,
+            loop_heads,
+            sig_template,
+        })
     }
 
     pub fn tag_idx(&mut self, tag: Tag) -> TagIdx
@@ -531,11 +954,58 @@ where
     {
         *self.tags_inv.entry(tag).or_insert_with(|| {
             let idx = self.tags.push(tag);
+            self.explanations.push(Explanation::default());
             self.comments.push(format!("Tag {idx}: {tag:?}"));
             idx
         })
     }
 
+    /// Like [`Self::tag_idx`] but also records what's needed to explain why `pred` may fail: the
+    /// hypotheses in scope relevant to prove it (i.e., the ones sharing a free variable with it)
+    /// and the surface names of its variables.
+    pub(crate) fn tag_idx_with_explanation(&mut self, tag: Tag, pred: &rty::Expr) -> TagIdx
+    where
+        Tag: std::fmt::Debug,
+    {
+        let idx = self.tag_idx(tag);
+        let fvars = pred.fvars();
+        let explanation = &mut self.explanations[idx];
+        for (span, vars) in &self.facts_in_scope {
+            if !vars.is_disjoint(&fvars) && !explanation.facts.contains(span) {
+                explanation.facts.push(*span);
+            }
+        }
+        if explanation.pred.is_none() {
+            let surface_names = self
+                .surface_names
+                .iter()
+                .filter(|(name, _)| fvars.contains(name))
+                .copied()
+                .collect();
+            explanation.pred = Some(FailedPred { expr: pred.clone(), surface_names });
+        }
+        idx
+    }
+
+    /// Runs `f` with the conjuncts of `pred` that have a span in scope as hypotheses. If the span
+    /// of a conjunct comes from the expansion of a function in a `flux::defs`, we use the span of
+    /// the call site.
+    pub(crate) fn with_facts<R>(&mut self, pred: &rty::Expr, f: impl FnOnce(&mut Self) -> R) -> R {
+        fn go(pred: &rty::Expr, facts: &mut Vec<(Span, FxHashSet<rty::Name>)>) {
+            if let rty::ExprKind::BinaryOp(rty::BinOp::And, e1, e2) = pred.kind() {
+                go(e1, facts);
+                go(e2, facts);
+            } else if let Some(espan) = pred.span() {
+                facts.push((espan.base().unwrap_or(espan.span()), pred.fvars()));
+            }
+        }
+        let n = self.facts_in_scope.len();
+        go(pred, &mut self.facts_in_scope);
+        let r = f(self);
+        self.facts_in_scope.truncate(n);
+        r
+    }
+
     pub fn pred_to_fixpoint(&mut self, pred: &rty::Expr) -> QueryResult<(Bindings, PredSpans)> {
         let mut bindings = vec![];
         let mut preds = vec![];
@@ -658,7 +1128,15 @@ fn fixpoint_const_map<'tcx>(
 
 impl KVarStore {
     pub fn new() -> Self {
-        Self { kvars: IndexVec::new() }
+        Self { kvars: IndexVec::new(), loop_heads: vec![], sig_template: None }
+    }
+
+    pub(crate) fn add_loop_head(&mut self, kvid: rty::KVid, span: Span, arg_names: Vec<String>) {
+        self.loop_heads.push(LoopHead { kvid, span, arg_names });
+    }
+
+    pub(crate) fn set_sig_template(&mut self, template: SigTemplate) {
+        self.sig_template = Some(template);
     }
 
     fn get(&self, kvid: rty::KVid) -> &KVarDecl {
@@ -677,7 +1155,7 @@ impl KVarStore {
     pub fn fresh(
         &mut self,
         binders: &[List<rty::Sort>],
-        scope: &Scope,
+        scope: impl IntoIterator<Item = (rty::Name, rty::Sort)>,
         encoding: KVarEncoding,
     ) -> rty::Expr {
         if binders.is_empty() {
@@ -693,7 +1171,7 @@ impl KVarStore {
                 })
             }),
             scope
-                .iter()
+                .into_iter()
                 .map(|(name, sort)| (rty::Var::Free(name), sort)),
         );
         self.fresh_inner(binders.last().unwrap().len(), args, encoding)
@@ -781,6 +1259,17 @@ fn tuple_sort_name(arity: usize) -> String {
     format!("Tuple{arity}")
 }
 
+/// Returns fixpoint's name for an interpreted theory function. We don't store the [`Symbol`] in
+/// [`fixpoint::Var`] because symbols can only be resolved in the thread running the compiler and
+/// constraints are encoded in the threads solving them.
+fn theory_func_name(fixpoint_name: Symbol) -> &'static str {
+    flux_middle::theory_funcs()
+        .iter()
+        .find(|itf| itf.fixpoint_name == fixpoint_name)
+        .map(|itf| itf.fixpoint_name.as_str())
+        .unwrap_or_else(|| bug!("unknown theory function `{fixpoint_name}`"))
+}
+
 fn func_sort_to_fixpoint(fsort: &rty::PolyFuncSort) -> fixpoint::Sort {
     let params = fsort.params();
     let fsort = fsort.skip_binders();
@@ -800,6 +1289,9 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
 
     fn expr_to_fixpoint(&mut self, expr: &rty::Expr, env: &Env) -> QueryResult<fixpoint::Expr> {
         let e = match expr.kind() {
+            rty::ExprKind::Var(rty::Var::ConstGeneric(param)) => {
+                fixpoint::Expr::Var(self.register_const_for_param_const(*param).into())
+            }
             rty::ExprKind::Var(var) => fixpoint::Expr::Var(env.get_var(var, self.dbg_span).into()),
             rty::ExprKind::Constant(c) => fixpoint::Expr::Constant(*c),
             rty::ExprKind::BinaryOp(op, e1, e2) => self.bin_op_to_fixpoint(op, e1, e2, env)?,
@@ -1037,7 +1529,9 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
     fn func_to_fixpoint(&mut self, func: &rty::Expr, env: &Env) -> fixpoint::Var {
         match func.kind() {
             rty::ExprKind::Var(var) => env.get_var(var, self.dbg_span).into(),
-            rty::ExprKind::GlobalFunc(_, SpecFuncKind::Thy(sym)) => fixpoint::Var::Itf(*sym),
+            rty::ExprKind::GlobalFunc(_, SpecFuncKind::Thy(sym)) => {
+                fixpoint::Var::Itf(theory_func_name(*sym))
+            }
             rty::ExprKind::GlobalFunc(sym, SpecFuncKind::Uif) => {
                 let cinfo = self.const_map.get(&Key::Uif(*sym)).unwrap_or_else(|| {
                     span_bug!(
@@ -1064,7 +1558,9 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
         bindings: &mut Vec<(fixpoint::LocalVar, fixpoint::Sort, fixpoint::Expr)>,
     ) -> QueryResult<fixpoint::LocalVar> {
         match arg.kind() {
-            rty::ExprKind::Var(var) => Ok(env.get_var(var, self.dbg_span)),
+            rty::ExprKind::Var(var) if !matches!(var, rty::Var::ConstGeneric(_)) => {
+                Ok(env.get_var(var, self.dbg_span))
+            }
             _ => {
                 let fresh = env.fresh_name();
                 let pred = fixpoint::Expr::eq(
@@ -1097,6 +1593,22 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
             .name
     }
 
+    /// The value of a const generic parameter is unknown inside the body of the function, so we
+    /// encode it with an uninterpreted integer constant.
+    fn register_const_for_param_const(
+        &mut self,
+        param: rustc_middle::ty::ParamConst,
+    ) -> fixpoint::GlobalVar {
+        self.const_map
+            .entry(Key::ConstGeneric(param))
+            .or_insert_with(|| {
+                let orig = format!("{param:?}");
+                let name = self.global_var_gen.fresh();
+                ConstInfo { name, orig, sort: fixpoint::Sort::Int, val: None }
+            })
+            .name
+    }
+
     /// We encode lambdas with uninterpreted constant. Two syntactically equal lambdas will be encoded
     /// with the same constant.
     fn register_const_for_lambda(&mut self, lam: &rty::Lambda) -> fixpoint::GlobalVar {
@@ -2,27 +2,37 @@
 //! extracted from the compiler or some additional analysis.
 mod fold_unfold;
 mod points_to;
+mod stmt_annots;
 
 use std::{fmt, io, iter};
 
 use flux_common::dbg;
 use flux_config as config;
 use flux_middle::{
+    fhir::Intrinsic,
     global_env::GlobalEnv,
     queries::QueryResult,
+    rty::Expr,
     rustc::{
         lowering,
-        mir::{BasicBlock, Body, Place},
+        mir::{
+            BasicBlock, BasicBlockData, Body, Operand, Place, Rvalue, StatementKind,
+            TerminatorKind, START_BLOCK,
+        },
     },
 };
+use itertools::Itertools;
 use rustc_data_structures::unord::UnordMap;
 use rustc_hash::{FxHashMap, FxHashSet};
-use rustc_hir::def_id::LocalDefId;
+use rustc_hir::{def::DefKind, def_id::LocalDefId};
 use rustc_middle::{mir::Location, ty::TyCtxt};
+use rustc_span::Span;
 
 pub(crate) struct GhostStatements {
     at_location: LocationMap,
     at_edge: EdgeMap,
+    /// Locations of calls to functions that can be inlined (see [`compute_ghost_statements`])
+    inlinable_calls: FxHashSet<Location>,
 }
 
 type LocationMap = FxHashMap<Location, Vec<GhostStatement>>;
@@ -33,6 +43,16 @@ pub(crate) enum GhostStatement {
     Unfold(Place),
     Unblock(Place),
     PtrToBorrow(Place),
+    /// Packs the type of a place, inserted for calls to the `pack` intrinsic
+    Pack(Place),
+    /// Unpacks the type of a place, inserted for calls to the `unpack` intrinsic
+    Unpack(Place),
+    /// Checks a condition holds, inserted for `#[flux::assert(..)]` annotations on statements. The
+    /// locals in the condition stand for the index of their types. The span is the one of the
+    /// annotation.
+    Assert(Expr, Span),
+    /// Assumes a condition holds, inserted for `#[flux::assume(..)]` annotations on statements
+    Assume(Expr, Span),
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -41,26 +61,76 @@ pub(crate) enum Point {
     Edge(BasicBlock, BasicBlock),
 }
 
+/// Computes the ghost statements for all the bodies checked together with `def_id`. These are the
+/// body of `def_id`, its nested closures and generators, and the bodies of the functions whose
+/// calls can be inlined.
+///
+/// A call can be inlined if the callee is a local non-generic function marked with
+/// `#[flux::inline]`. Whether a call is actually inlined is decided when checking it, because
+/// a function inlined at several call sites is checked once per call site (see
+/// `Checker::check_inlined_call`). The ghost statements of a body are the same for all of them.
 pub(crate) fn compute_ghost_statements(
     genv: GlobalEnv,
     def_id: LocalDefId,
 ) -> QueryResult<UnordMap<LocalDefId, GhostStatements>> {
     let mut data = UnordMap::default();
-    for def_id in all_nested_bodies(genv.tcx(), def_id) {
-        data.insert(def_id, GhostStatements::new(genv, def_id)?);
+    let mut worklist = vec![def_id];
+    while let Some(def_id) = worklist.pop() {
+        for def_id in all_nested_bodies(genv.tcx(), def_id) {
+            if data.contains_key(&def_id) {
+                continue;
+            }
+            let mut stmts = GhostStatements::new(genv, def_id)?;
+            for (location, callee) in inline_candidates(genv, def_id)? {
+                stmts.inlinable_calls.insert(location);
+                worklist.push(callee);
+            }
+            data.insert(def_id, stmts);
+        }
     }
     Ok(data)
 }
 
+/// Returns the calls in the body of `def_id` to functions that can be inlined
+fn inline_candidates(
+    genv: GlobalEnv,
+    def_id: LocalDefId,
+) -> QueryResult<Vec<(Location, LocalDefId)>> {
+    let tcx = genv.tcx();
+    let body = genv.mir(def_id)?;
+    let mut candidates = vec![];
+    for (bb, data) in body.basic_blocks.iter_enumerated() {
+        if let Some(terminator) = &data.terminator
+            && let TerminatorKind::Call { resolved_call: (callee, _), .. } = &terminator.kind
+            && let Some(callee) = callee.as_local()
+            && matches!(tcx.def_kind(callee), DefKind::Fn | DefKind::AssocFn)
+            && tcx.is_mir_available(callee)
+            && !tcx.generics_of(callee).requires_monomorphization(tcx)
+            && genv.is_inline(callee)?
+            && !genv.is_trusted(callee)?
+        {
+            let location = Location { block: bb, statement_index: data.statements.len() };
+            candidates.push((location, callee));
+        }
+    }
+    Ok(candidates)
+}
+
 impl GhostStatements {
     fn new(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<Self> {
         let body = genv.mir(def_id)?;
 
-        let mut stmts = Self { at_location: LocationMap::default(), at_edge: EdgeMap::default() };
+        let mut stmts = Self {
+            at_location: LocationMap::default(),
+            at_edge: EdgeMap::default(),
+            inlinable_calls: FxHashSet::default(),
+        };
 
         fold_unfold::add_ghost_statements(&mut stmts, genv, &body)?;
         points_to::add_ghost_statements(&mut stmts, genv, body.rustc_body(), def_id)?;
         stmts.add_unblocks(&body);
+        stmts.add_packs(genv, &body);
+        stmt_annots::add_ghost_statements(&mut stmts, genv, &body, genv.stmt_annots(def_id))?;
 
         if config::dump_mir() {
             let mut writer =
@@ -70,14 +140,56 @@ impl GhostStatements {
         Ok(stmts)
     }
 
+    /// Inserts an [`GhostStatement::Unblock`] for the borrowed place at every location where a
+    /// borrow goes out of scope.
+    ///
+    /// If a borrow goes out of scope at the beginning of a join point (e.g., the header of a loop
+    /// for a borrow that only lives during an iteration), we unblock in every incoming edge instead.
+    /// This way, the environments are joined with the unblocked (strongly updatable) types.
     fn add_unblocks(&mut self, body: &Body) {
         for (location, borrows) in body.calculate_borrows_out_of_scope_at_location() {
-            let stmts = borrows.into_iter().map(|bidx| {
-                let borrow = body.borrow_data(bidx);
-                let place = lowering::lower_place(&borrow.borrowed_place).unwrap();
-                GhostStatement::Unblock(place)
-            });
-            self.at_location.entry(location).or_default().extend(stmts);
+            let places = borrows
+                .into_iter()
+                .map(|bidx| {
+                    let borrow = body.borrow_data(bidx);
+                    lowering::lower_place(&borrow.borrowed_place).unwrap()
+                })
+                .collect_vec();
+            let stmts = || places.iter().cloned().map(GhostStatement::Unblock);
+
+            let bb = location.block;
+            if location.statement_index == 0 && bb != START_BLOCK && body.is_join_point(bb) {
+                for pred in &body.rustc_body().basic_blocks.predecessors()[bb] {
+                    self.extend_at(Point::Edge(*pred, bb), stmts());
+                }
+            } else {
+                self.extend_at(Point::Location(location), stmts());
+            }
+        }
+    }
+
+    /// Inserts a [`GhostStatement::Pack`] or [`GhostStatement::Unpack`] before every call to the
+    /// `pack` or `unpack` intrinsic. The argument of the call must be a borrow of the place to pack
+    /// or unpack taken in the same basic block, which is what `flux_rs::pack!` and
+    /// `flux_rs::unpack!` expand to. Calls with any other argument are ignored.
+    fn add_packs(&mut self, genv: GlobalEnv, body: &Body) {
+        for (bb, data) in body.basic_blocks.iter_enumerated() {
+            let Some(terminator) = &data.terminator else { continue };
+            let TerminatorKind::Call { args, resolved_call: (callee, _), .. } = &terminator.kind
+            else {
+                continue;
+            };
+            let mk_stmt = match genv.intrinsic(*callee) {
+                Some(Intrinsic::Pack) => GhostStatement::Pack,
+                Some(Intrinsic::Unpack) => GhostStatement::Unpack,
+                _ => continue,
+            };
+            if let [Operand::Move(arg) | Operand::Copy(arg)] = &args[..]
+                && let Some(place) = borrowed_place(data, arg)
+            {
+                let location = Location { block: bb, statement_index: data.statements.len() };
+                self.insert_at(Point::Location(location), mk_stmt(place));
+            }
         }
     }
 
@@ -118,6 +230,11 @@ impl GhostStatements {
         }
     }
 
+    /// Whether the call at `location` is to a function that can be inlined
+    pub(crate) fn is_inlinable_call(&self, location: Location) -> bool {
+        self.inlinable_calls.contains(&location)
+    }
+
     pub(crate) fn write_mir<'tcx, W: io::Write>(
         &self,
         tcx: TyCtxt<'tcx>,
@@ -168,6 +285,19 @@ impl StatementsAt<'_> {
     }
 }
 
+/// Returns the place borrowed by the last assignment to `arg` in `data`
+fn borrowed_place(data: &BasicBlockData, arg: &Place) -> Option<Place> {
+    data.statements.iter().rev().find_map(|stmt| {
+        if let StatementKind::Assign(lhs, Rvalue::Ref(_, _, place)) = &stmt.kind
+            && lhs == arg
+        {
+            Some(place.clone())
+        } else {
+            None
+        }
+    })
+}
+
 fn all_nested_bodies(tcx: TyCtxt, def_id: LocalDefId) -> impl Iterator<Item = LocalDefId> {
     use rustc_hir as hir;
     struct ClosureFinder<'hir> {
@@ -205,6 +335,10 @@ impl fmt::Debug for GhostStatement {
             GhostStatement::Unfold(place) => write!(f, "unfold({place:?})"),
             GhostStatement::Unblock(place) => write!(f, "unblock({place:?})"),
             GhostStatement::PtrToBorrow(place) => write!(f, "ptr_to_borrow({place:?})"),
+            GhostStatement::Pack(place) => write!(f, "pack({place:?})"),
+            GhostStatement::Unpack(place) => write!(f, "unpack({place:?})"),
+            GhostStatement::Assert(pred, _) => write!(f, "assert({pred:?})"),
+            GhostStatement::Assume(pred, _) => write!(f, "assume({pred:?})"),
         }
     }
 }
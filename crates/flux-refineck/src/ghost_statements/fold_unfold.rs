@@ -218,7 +218,10 @@ impl<'a, 'genv, 'tcx, M: Mode> FoldUnfoldAnalysis<'a, 'genv, 'tcx, M> {
         match &stmt.kind {
             StatementKind::Assign(place, rvalue) => {
                 match rvalue {
-                    Rvalue::Use(op) | Rvalue::Cast(_, op, _) | Rvalue::UnaryOp(_, op) => {
+                    Rvalue::Use(op)
+                    | Rvalue::Cast(_, op, _)
+                    | Rvalue::UnaryOp(_, op)
+                    | Rvalue::Repeat(op, ..) => {
                         self.operand(op, env)?;
                     }
                     Rvalue::Ref(.., place) => {
@@ -277,6 +280,16 @@ impl<'a, 'genv, 'tcx, M: Mode> FoldUnfoldAnalysis<'a, 'genv, 'tcx, M> {
                     self.goto(bb, *target, env)?;
                 }
             }
+            TerminatorKind::CallIndirect { func, args, destination, target, .. } => {
+                self.operand(func, &mut env)?;
+                for arg in args {
+                    self.operand(arg, &mut env)?;
+                }
+                M::projection(self, &mut env, destination, ProjKind::Other)?;
+                if let Some(target) = target {
+                    self.goto(bb, *target, env)?;
+                }
+            }
             TerminatorKind::SwitchInt { discr, targets } => {
                 let is_match = match discr {
                     Operand::Copy(place) | Operand::Move(place) => {
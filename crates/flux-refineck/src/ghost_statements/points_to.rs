@@ -17,12 +17,18 @@
 //! `r` could pointn to either `x` or `y`. Thus, we use the result of the analysis to insert a ghost
 //! statement at the end of each branch to convert the pointers to a borrow `&mut T` for a type `T` that
 //! needs to be inferred.
+//!
+//! The analysis tracks the path a pointer points to and not just its root location. This way, a
+//! reborrow of a field through a pointer, e.g., `r2 = &mut (*r1).f`, is known to point to the same
+//! path as a borrow of the field of the original location. If the paths differ at a join point, the
+//! reborrow is converted to a borrow (blocking the field) and when the reborrow ends the field is
+//! unblocked, so `r1` can be strongly updated again.
 use std::{collections::VecDeque, fmt, iter, ops::Range};
 
 use flux_middle::{
     global_env::GlobalEnv,
     queries::QueryResult,
-    rty::{self, Loc},
+    rty::{self, Loc, Path},
     rustc::mir::FieldIdx,
 };
 use rustc_data_structures::stack::ensure_sufficient_stack;
@@ -132,12 +138,23 @@ impl<'a> PointsToAnalysis<'a> {
         }
     }
 
-    fn handle_ref(&self, place: &mir::Place, state: &State) -> FlatSet<Loc> {
-        match &place.projection[..] {
-            [] => FlatSet::Elem(Loc::Local(place.local)),
-            [mir::PlaceElem::Deref] => state.get(place.local.into(), self.map),
-            _ => FlatSet::Top,
+    fn handle_ref(&self, place: &mir::Place, state: &State) -> FlatSet<Path> {
+        let (base, projection) = match &place.projection[..] {
+            [mir::PlaceElem::Deref, projection @ ..] => {
+                (state.get(place.local.into(), self.map), projection)
+            }
+            projection => (FlatSet::Elem(Path::from(Loc::Local(place.local))), projection),
+        };
+        let path = match base {
+            FlatSet::Elem(path) => path,
+            base => return base,
+        };
+        let mut fields = path.projection().to_vec();
+        for elem in projection {
+            let mir::PlaceElem::Field(f, _) = elem else { return FlatSet::Top };
+            fields.push(*f);
         }
+        FlatSet::Elem(Path::new(path.loc, fields.as_slice()))
     }
 
     fn handle_operand(&self, operand: &mir::Operand) -> Option<PlaceIndex> {
@@ -208,8 +225,8 @@ impl<'a, 'tcx> rustc_mir_dataflow::AnalysisDomain<'tcx> for PointsToAnalysis<'a>
             let fn_sig = fn_sig.as_ref().skip_binder().as_ref().skip_binder();
             for (local, ty) in iter::zip(body.args_iter(), fn_sig.args()) {
                 if let rty::TyKind::Ptr(_, path) = ty.kind() {
-                    let loc = FlatSet::Elem(path.to_loc().unwrap());
-                    state.flood_with(mir::PlaceRef { local, projection: &[] }, self.map, loc);
+                    let path = FlatSet::Elem(path.clone());
+                    state.flood_with(mir::PlaceRef { local, projection: &[] }, self.map, path);
                 } else {
                     state.flood(mir::PlaceRef { local, projection: &[] }, self.map);
                 }
@@ -263,7 +280,7 @@ struct CollectPointerToBorrows<'a> {
     map: &'a Map,
     tracked_places: FxHashMap<PlaceIndex, flux_middle::rustc::mir::Place>,
     stmts: &'a mut GhostStatements,
-    before_state: Vec<(PlaceIndex, FlatSet<Loc>)>,
+    before_state: Vec<(PlaceIndex, FlatSet<Path>)>,
 }
 
 impl<'a> CollectPointerToBorrows<'a> {
@@ -668,7 +685,7 @@ rustc_index::newtype_index!(
 
 /// Used as the result for r-value.
 enum PlaceOrValue {
-    Value(FlatSet<Loc>),
+    Value(FlatSet<Path>),
     Place(PlaceIndex),
 }
 
@@ -688,7 +705,7 @@ impl PlaceOrValue {
 /// Flooding means assigning a value (by default `⊤`) to all tracked projections of a given place.
 #[derive(PartialEq, Eq, Debug)]
 struct State {
-    values: IndexVec<ValueIndex, FlatSet<Loc>>,
+    values: IndexVec<ValueIndex, FlatSet<Path>>,
 }
 
 impl Clone for State {
@@ -712,9 +729,9 @@ impl State {
         self.flood_with(place, map, FlatSet::TOP);
     }
 
-    fn flood_with(&mut self, place: mir::PlaceRef<'_>, map: &Map, value: FlatSet<Loc>) {
+    fn flood_with(&mut self, place: mir::PlaceRef<'_>, map: &Map, value: FlatSet<Path>) {
         map.for_each_aliasing_place(place, &mut |vi| {
-            self.values[vi] = value;
+            self.values[vi] = value.clone();
         });
     }
 
@@ -750,7 +767,7 @@ impl State {
         // already been performed.
         if let Some(target_value) = map.places[target].value_index {
             if let Some(source_value) = map.places[source].value_index {
-                self.values[target_value] = self.values[source_value];
+                self.values[target_value] = self.values[source_value].clone();
             }
         }
         for target_child in map.children(target) {
@@ -766,26 +783,26 @@ impl State {
     /// This does nothing if the place is not tracked.
     ///
     /// The target place must have been flooded before calling this method.
-    fn insert_value_idx(&mut self, target: PlaceIndex, value: FlatSet<Loc>, map: &Map) {
+    fn insert_value_idx(&mut self, target: PlaceIndex, value: FlatSet<Path>, map: &Map) {
         if let Some(value_index) = map.places[target].value_index {
             self.values[value_index] = value;
         }
     }
 
     /// Retrieve the value stored for a place, or ⊤ if it is not tracked.
-    fn get(&self, place: mir::PlaceRef<'_>, map: &Map) -> FlatSet<Loc> {
+    fn get(&self, place: mir::PlaceRef<'_>, map: &Map) -> FlatSet<Path> {
         map.find(place)
             .map_or(FlatSet::TOP, |place| self.get_idx(place, map))
     }
 
     /// Retrieve the value stored for a place index, or ⊤ if it is not tracked.
-    fn get_idx(&self, place: PlaceIndex, map: &Map) -> FlatSet<Loc> {
+    fn get_idx(&self, place: PlaceIndex, map: &Map) -> FlatSet<Path> {
         self.get_tracked_idx(place, map).unwrap_or(FlatSet::Top)
     }
 
     /// Retrieve the value stored for a place index if tracked
-    fn get_tracked_idx(&self, place: PlaceIndex, map: &Map) -> Option<FlatSet<Loc>> {
-        map.places[place].value_index.map(|v| self.values[v])
+    fn get_tracked_idx(&self, place: PlaceIndex, map: &Map) -> Option<FlatSet<Path>> {
+        map.places[place].value_index.map(|v| self.values[v].clone())
     }
 }
 
@@ -0,0 +1,259 @@
+//! Ghost statements for the `#[flux::assert(..)]` and `#[flux::assume(..)]` annotations on
+//! statements. The condition of an annotation is checked (or assumed) right before the first mir
+//! location of the annotated statement. Variables in the condition are resolved to the locals they
+//! name at that location and are replaced with the index of their types when the statement is
+//! checked.
+use flux_middle::{
+    global_env::GlobalEnv,
+    queries::{QueryErr, QueryResult},
+    rty::{BinOp, Constant, ESpan, Expr, Sort, UnOp},
+    rustc::{
+        mir::{Body, Local, Location},
+        ty::{Mutability, Ty, TyKind},
+    },
+};
+use flux_syntax::surface::{self, StmtAnnotKind};
+use rustc_errors::Diagnostic;
+use rustc_middle::mir::{SourceScope, VarDebugInfoContents};
+use rustc_span::{symbol::kw, Span};
+
+use super::{GhostStatement, GhostStatements, Point};
+
+pub(super) fn add_ghost_statements<'tcx>(
+    stmts: &mut GhostStatements,
+    genv: GlobalEnv<'_, 'tcx>,
+    body: &Body<'tcx>,
+    annots: &[surface::StmtAnnot],
+) -> QueryResult {
+    for annot in annots {
+        let Some((location, scope)) = first_location_in(body, annot.stmt_span) else {
+            return Err(emit_err(genv, errors::AnnotNeverChecked { span: annot.span }));
+        };
+        let conv = Conv { genv, body, scope };
+        let (pred, sort) = conv.conv_expr(&annot.pred)?;
+        conv.check_sort(&annot.pred, &Sort::Bool, &sort)?;
+        let stmt = match annot.kind {
+            StmtAnnotKind::Assert => GhostStatement::Assert(pred, annot.span),
+            StmtAnnotKind::Assume => GhostStatement::Assume(pred, annot.span),
+        };
+        stmts.insert_at(Point::Location(location), stmt);
+    }
+    Ok(())
+}
+
+/// Returns the first location in reverse postorder whose span is inside `span` together with its
+/// source scope
+fn first_location_in(body: &Body, span: Span) -> Option<(Location, SourceScope)> {
+    body.rustc_body()
+        .basic_blocks
+        .reverse_postorder()
+        .iter()
+        .find_map(|&bb| {
+            let data = &body.basic_blocks[bb];
+            if data.is_cleanup {
+                return None;
+            }
+            let terminator = data
+                .terminator
+                .iter()
+                .map(|terminator| terminator.source_info);
+            data.statements
+                .iter()
+                .map(|stmt| stmt.source_info)
+                .chain(terminator)
+                .enumerate()
+                .find(|(_, source_info)| span.contains(source_info.span))
+                .map(|(statement_index, source_info)| {
+                    (Location { block: bb, statement_index }, source_info.scope)
+                })
+        })
+}
+
+struct Conv<'a, 'genv, 'tcx> {
+    genv: GlobalEnv<'genv, 'tcx>,
+    body: &'a Body<'tcx>,
+    scope: SourceScope,
+}
+
+impl<'genv> Conv<'_, 'genv, '_> {
+    fn conv_expr(&self, expr: &surface::Expr) -> QueryResult<(Expr, Sort)> {
+        let espan = Some(ESpan::new(expr.span));
+        match &expr.kind {
+            surface::ExprKind::Path(surface::PathExpr { segments, .. }) if segments.len() == 1 => {
+                let ident = segments[0];
+                let Some(local) = self.resolve(ident) else {
+                    return Err(self.emit_err(errors::UnresolvedVar { span: ident.span, ident }));
+                };
+                let Some(sort) = sort_of(&self.body.local_decls[local].ty) else {
+                    return Err(self.emit_err(errors::UnsupportedVar { span: ident.span, ident }));
+                };
+                Ok((Expr::local(local, espan), sort))
+            }
+            surface::ExprKind::Literal(lit) => {
+                let (c, sort) = match lit.kind {
+                    surface::LitKind::Integer if lit.suffix.is_none() => {
+                        let Ok(n) = lit.symbol.as_str().parse::<i128>() else {
+                            return Err(self.emit_err(errors::UnsupportedExpr { span: expr.span }));
+                        };
+                        (Constant::from(n), Sort::Int)
+                    }
+                    surface::LitKind::Bool => (Constant::from(lit.symbol == kw::True), Sort::Bool),
+                    _ => return Err(self.emit_err(errors::UnsupportedExpr { span: expr.span })),
+                };
+                Ok((Expr::constant_at(c, espan), sort))
+            }
+            surface::ExprKind::BinaryOp(op, box [e1, e2]) => {
+                let (conv1, sort1) = self.conv_expr(e1)?;
+                let (conv2, sort2) = self.conv_expr(e2)?;
+                let (op, operand_sort, sort) = match op {
+                    surface::BinOp::Iff => (BinOp::Iff, Sort::Bool, Sort::Bool),
+                    surface::BinOp::Imp => (BinOp::Imp, Sort::Bool, Sort::Bool),
+                    surface::BinOp::Or => (BinOp::Or, Sort::Bool, Sort::Bool),
+                    surface::BinOp::And => (BinOp::And, Sort::Bool, Sort::Bool),
+                    surface::BinOp::Eq => (BinOp::Eq, sort1.clone(), Sort::Bool),
+                    surface::BinOp::Ne => (BinOp::Ne, sort1.clone(), Sort::Bool),
+                    surface::BinOp::Gt => (BinOp::Gt(Sort::Int), Sort::Int, Sort::Bool),
+                    surface::BinOp::Ge => (BinOp::Ge(Sort::Int), Sort::Int, Sort::Bool),
+                    surface::BinOp::Lt => (BinOp::Lt(Sort::Int), Sort::Int, Sort::Bool),
+                    surface::BinOp::Le => (BinOp::Le(Sort::Int), Sort::Int, Sort::Bool),
+                    surface::BinOp::Add => (BinOp::Add, Sort::Int, Sort::Int),
+                    surface::BinOp::Sub => (BinOp::Sub, Sort::Int, Sort::Int),
+                    surface::BinOp::Mul => (BinOp::Mul, Sort::Int, Sort::Int),
+                    surface::BinOp::Div => (BinOp::Div, Sort::Int, Sort::Int),
+                    surface::BinOp::Mod => (BinOp::Mod, Sort::Int, Sort::Int),
+                };
+                self.check_sort(e1, &operand_sort, &sort1)?;
+                self.check_sort(e2, &operand_sort, &sort2)?;
+                Ok((Expr::binary_op(op, conv1, conv2, espan), sort))
+            }
+            surface::ExprKind::UnaryOp(op, e) => {
+                let (op, sort) = match op {
+                    surface::UnOp::Not => (UnOp::Not, Sort::Bool),
+                    surface::UnOp::Neg => (UnOp::Neg, Sort::Int),
+                };
+                let (conv, found) = self.conv_expr(e)?;
+                self.check_sort(e, &sort, &found)?;
+                Ok((Expr::unary_op(op, conv, espan), sort))
+            }
+            surface::ExprKind::IfThenElse(box [p, e1, e2]) => {
+                let (p_conv, p_sort) = self.conv_expr(p)?;
+                self.check_sort(p, &Sort::Bool, &p_sort)?;
+                let (e1_conv, sort) = self.conv_expr(e1)?;
+                let (e2_conv, e2_sort) = self.conv_expr(e2)?;
+                self.check_sort(e2, &sort, &e2_sort)?;
+                Ok((Expr::ite(p_conv, e1_conv, e2_conv, espan), sort))
+            }
+            _ => Err(self.emit_err(errors::UnsupportedExpr { span: expr.span })),
+        }
+    }
+
+    fn check_sort(&self, expr: &surface::Expr, expected: &Sort, found: &Sort) -> QueryResult {
+        if expected == found {
+            Ok(())
+        } else {
+            Err(self.emit_err(errors::MismatchedSort::new(expr, expected, found)))
+        }
+    }
+
+    /// Resolves `ident` to the local it names in the source scope of the annotation. Inner scopes
+    /// shadow outer ones and every `let` introduces a new scope, so the first variable with the
+    /// name found walking up the scope tree is the one in scope.
+    fn resolve(&self, ident: surface::Ident) -> Option<Local> {
+        let body = self.body.rustc_body();
+        let mut scope = Some(self.scope);
+        while let Some(s) = scope {
+            let local = body.var_debug_info.iter().find_map(|info| {
+                if info.name == ident.name
+                    && info.source_info.scope == s
+                    && let VarDebugInfoContents::Place(place) = info.value
+                    && place.projection.is_empty()
+                {
+                    Some(place.local)
+                } else {
+                    None
+                }
+            });
+            if local.is_some() {
+                return local;
+            }
+            scope = body.source_scopes[s].parent_scope;
+        }
+        None
+    }
+
+    fn emit_err(&self, err: impl Diagnostic<'genv>) -> QueryErr {
+        emit_err(self.genv, err)
+    }
+}
+
+fn emit_err<'genv>(genv: GlobalEnv<'genv, '_>, err: impl Diagnostic<'genv>) -> QueryErr {
+    QueryErr::Emitted(genv.sess().emit_err(err))
+}
+
+/// The sort of the index of a variable of type `ty`. Only variables of integer or boolean type, or
+/// shared references to them, can be used in an annotation.
+fn sort_of(ty: &Ty) -> Option<Sort> {
+    match ty.kind() {
+        TyKind::Int(_) | TyKind::Uint(_) => Some(Sort::Int),
+        TyKind::Bool => Some(Sort::Bool),
+        TyKind::Ref(_, ty, Mutability::Not) => sort_of(ty),
+        _ => None,
+    }
+}
+
+mod errors {
+    use flux_errors::E0999;
+    use flux_macros::Diagnostic;
+    use flux_middle::rty::Sort;
+    use flux_syntax::surface;
+    use rustc_span::{symbol::Ident, Span};
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_annot_never_checked, code = E0999)]
+    #[note]
+    pub(super) struct AnnotNeverChecked {
+        #[primary_span]
+        pub span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_annot_unresolved_var, code = E0999)]
+    pub(super) struct UnresolvedVar {
+        #[primary_span]
+        pub span: Span,
+        pub ident: Ident,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_annot_unsupported_var, code = E0999)]
+    #[note]
+    pub(super) struct UnsupportedVar {
+        #[primary_span]
+        pub span: Span,
+        pub ident: Ident,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_annot_unsupported_expr, code = E0999)]
+    #[note]
+    pub(super) struct UnsupportedExpr {
+        #[primary_span]
+        pub span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_annot_mismatched_sort, code = E0999)]
+    pub(super) struct MismatchedSort {
+        #[primary_span]
+        #[label]
+        span: Span,
+        expected: String,
+        found: String,
+    }
+
+    impl MismatchedSort {
+        pub(super) fn new(expr: &surface::Expr, expected: &Sort, found: &Sort) -> Self {
+            Self { span: expr.span, expected: format!("{expected:?}"), found: format!("{found:?}") }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Holes are code paths that inevitably end in a call to `todo!()` or `unimplemented!()`. We don't
+//! check them, i.e., a hole behaves like a `trusted` expression. This way, partially implemented
+//! code can still be checked.
+use flux_middle::rustc::mir::{BasicBlock, Body, TerminatorKind};
+use rustc_index::bit_set::BitSet;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::{sym, Span};
+
+pub(crate) struct Holes {
+    /// Blocks from which every path ends in a hole
+    blocks: BitSet<BasicBlock>,
+    /// Spans of the calls to `todo!()` and `unimplemented!()`
+    spans: Vec<Span>,
+}
+
+impl Holes {
+    pub(crate) fn new(tcx: TyCtxt, body: &Body) -> Self {
+        let mut blocks = BitSet::new_empty(body.basic_blocks.len());
+        let mut spans = vec![];
+        // Successors are visited before their predecessors except for back edges, thus a block
+        // that only reaches a hole through a loop is conservatively not considered a hole.
+        for &bb in body.rustc_body().basic_blocks.postorder() {
+            let Some(terminator) = &body.basic_blocks[bb].terminator else { continue };
+            let span = terminator.source_info.span;
+            if let TerminatorKind::Call { target: None, .. } = terminator.kind
+                && is_hole_macro(tcx, span)
+            {
+                blocks.insert(bb);
+                spans.push(span);
+                continue;
+            }
+            let successors = terminator.successors();
+            if !successors.is_empty() && successors.iter().all(|succ| blocks.contains(*succ)) {
+                blocks.insert(bb);
+            }
+        }
+        spans.sort();
+        Self { blocks, spans }
+    }
+
+    /// Whether every path starting from `bb` ends in a hole
+    pub(crate) fn is_hole(&self, bb: BasicBlock) -> bool {
+        self.blocks.contains(bb)
+    }
+
+    pub(crate) fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
+
+/// Whether `span` comes from the expansion of `todo!` or `unimplemented!`
+fn is_hole_macro(tcx: TyCtxt, span: Span) -> bool {
+    span.macro_backtrace().any(|expn_data| {
+        expn_data
+            .macro_def_id
+            .and_then(|def_id| tcx.get_diagnostic_name(def_id))
+            .is_some_and(|name| matches!(name, sym::todo_macro | sym::unimplemented_macro))
+    })
+}
@@ -0,0 +1,166 @@
+//! Refined types at a position in the source, enabled with `-Zflux-hover=path:line:column` (see
+//! [`config::hover`]).
+//!
+//! Flux doesn't implement a language server. Errors show up in editors through rust-analyzer's
+//! check command (see the guide), and this is the building block for hover: an editor extension
+//! can run flux with the position under the cursor and show the note we report. We pick the
+//! innermost statement or terminator whose span contains the position and report the refined types
+//! of the variables in scope right before it, printed in surface syntax, together with the
+//! predicates assumed at that point. The note is a regular diagnostic, so it is also reported in
+//! the json output consumed by editors.
+use std::{fmt::Write, path::Path};
+
+use flux_config as config;
+use flux_middle::{
+    global_env::GlobalEnv,
+    pretty::{PrettyCx, WithCx},
+    rty::{Expr, ExprKind, PtrKind, Ty, TyKind, Var},
+    rustc::mir::Body,
+};
+use itertools::Itertools;
+use rustc_hir::def_id::LocalDefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::{BytePos, FileName, Span};
+
+use crate::{refine_tree::RefineCtxt, type_env::TypeEnv};
+
+pub(crate) struct Hover {
+    /// The (empty) span at the position
+    pos: Span,
+    /// The innermost span containing the position found so far and the environment before it
+    found: Option<(Span, String)>,
+}
+
+impl Hover {
+    /// Returns a hover for `def_id` if its body contains the position in [`config::hover`]
+    pub(crate) fn new(tcx: TyCtxt, def_id: LocalDefId) -> Option<Hover> {
+        let pos = resolve_position(tcx, config::hover()?)?;
+        let body_span = tcx.hir().span_with_body(tcx.local_def_id_to_hir_id(def_id));
+        if !body_span.contains(pos) {
+            return None;
+        }
+        Some(Hover { pos, found: None })
+    }
+
+    /// Records the environment before the statement or terminator at `span`. Statements sharing
+    /// the same span are reported with the environment before the first one.
+    pub(crate) fn record(
+        &mut self,
+        tcx: TyCtxt,
+        body: &Body,
+        span: Span,
+        rcx: &RefineCtxt,
+        env: &TypeEnv,
+    ) {
+        if !span.contains(self.pos) {
+            return;
+        }
+        if let Some((found, _)) = &self.found
+            && (*found == span || !found.contains(span))
+        {
+            return;
+        }
+        self.found = Some((span, render(tcx, body, rcx, env)));
+    }
+
+    pub(crate) fn report(self, genv: GlobalEnv) {
+        if let Some((span, env)) = self.found {
+            genv.sess().emit_note(errors::Hover { span, env });
+        }
+    }
+}
+
+/// Renders the types of the variables of `body` in `env` followed by the predicates assumed in
+/// `rcx`, e.g., `x: i32[x], y: i32[x + 1]` and `x > 0`. Refinement variables are printed with the
+/// name they have in the signature or, if they don't have one, with the name of the variable they
+/// index. Temporaries and uninitialized variables are left out.
+fn render(tcx: TyCtxt, body: &Body, rcx: &RefineCtxt, env: &TypeEnv) -> String {
+    let local_names = body.local_names();
+    let vars = env
+        .local_types()
+        .filter(|(_, ty)| !ty.is_uninit())
+        .filter_map(|(local, ty)| Some((local, *local_names.get(&local)?, ty)))
+        .sorted_by_key(|(local, ..)| *local)
+        .map(|(_, name, ty)| (name, ty))
+        .collect_vec();
+
+    let (mut surface_names, preds) = rcx.assumptions();
+    for (name, ty) in &vars {
+        if let TyKind::Indexed(_, idx) = ty.kind()
+            && let ExprKind::Var(Var::Free(fvar)) = idx.kind()
+            && !surface_names.iter().any(|(named, _)| named == fvar)
+        {
+            surface_names.push((*fvar, *name));
+        }
+    }
+
+    let cx = PrettyCx::surface(tcx);
+    cx.with_fvar_names(surface_names, || {
+        let mut s = vars
+            .iter()
+            .format_with(", ", |(name, ty), f| {
+                f(&format_args!("{name}: {}", render_ty(&cx, env, ty)))
+            })
+            .to_string();
+        if !preds.is_empty() {
+            write!(s, "\nassuming {:?}", WithCx::new(&cx, &Expr::and(preds))).unwrap();
+        }
+        s
+    })
+}
+
+/// Pointers to a location in the environment are printed as references to the type of the
+/// location, as they would be written in a signature, e.g., `&strg i32[x]`.
+fn render_ty(cx: &PrettyCx, env: &TypeEnv, ty: &Ty) -> String {
+    match ty.kind() {
+        TyKind::Ptr(PtrKind::Mut(_), path) => {
+            format!("&strg {}", render_ty(cx, env, &env.get(path)))
+        }
+        TyKind::Ptr(PtrKind::Shr(_), path) => format!("&{}", render_ty(cx, env, &env.get(path))),
+        _ => format!("{:?}", WithCx::new(cx, ty)),
+    }
+}
+
+/// Converts a position written as `path:line:column` (both 1-based) into a span
+fn resolve_position(tcx: TyCtxt, position: &str) -> Option<Span> {
+    // The path may contain colons, so we split from the right
+    let mut parts = position.rsplitn(3, ':');
+    let column: usize = parts.next()?.parse().ok()?;
+    let line: usize = parts.next()?.parse().ok()?;
+    let path = Path::new(parts.next()?);
+    let canonical = path.canonicalize().ok();
+
+    let source_map = tcx.sess.source_map();
+    let files = source_map.files();
+    let file = files.iter().find(|file| {
+        let FileName::Real(name) = &file.name else { return false };
+        let Some(local_path) = name.local_path() else { return false };
+        local_path.ends_with(path)
+            || canonical.is_some() && local_path.canonicalize().ok() == canonical
+    })?;
+
+    if line == 0 || line > file.count_lines() {
+        return None;
+    }
+    let text = file.get_line(line - 1)?;
+    let offset = text
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(text.len(), |(offset, _)| offset);
+    let pos = file.line_bounds(line - 1).start + BytePos(offset as u32);
+    Some(Span::with_root_ctxt(pos, pos))
+}
+
+mod errors {
+    use flux_macros::Diagnostic;
+    use rustc_span::Span;
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_hover)]
+    #[note]
+    pub(super) struct Hover {
+        #[primary_span]
+        pub span: Span,
+        pub env: String,
+    }
+}
@@ -0,0 +1,114 @@
+//! Fingerprints used to skip checking functions that haven't changed since the last run.
+//!
+//! The fingerprint of a function combines the (lowered) MIR of every body checked together with
+//! it, i.e., the function itself, its nested closures and coroutines, and the functions whose calls
+//! are inlined, with a fingerprint of the crate. The crate fingerprint conservatively accounts for
+//! every spec a function may depend on: the signatures of all local functions, the definitions of
+//! all local types, spec functions and qualifiers, the hashes of upstream crates (which cover their
+//! exported specs), and the configuration of the checker. Values are hashed through their [`Debug`]
+//! representation, which doesn't include spans, so moving code around doesn't invalidate the
+//! fingerprints.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use flux_middle::{fhir::Ignored, global_env::GlobalEnv, queries::QueryResult};
+use itertools::Itertools;
+use rustc_hir::def::DefKind;
+use rustc_span::def_id::LocalDefId;
+
+use crate::{ghost_statements::compute_ghost_statements, CheckerConfig};
+
+pub struct Fingerprinter {
+    crate_hash: u64,
+}
+
+impl Fingerprinter {
+    pub fn new(genv: GlobalEnv, config: &CheckerConfig) -> QueryResult<Self> {
+        let tcx = genv.tcx();
+        let mut hasher = DefaultHasher::new();
+
+        for cnum in tcx.crates(()) {
+            tcx.crate_name(*cnum).as_str().hash(&mut hasher);
+            tcx.crate_hash(*cnum).hash(&mut hasher);
+        }
+
+        for local_id in tcx.iter_local_def_id() {
+            if genv.ignored(local_id) == Ignored::Yes {
+                continue;
+            }
+            let def_id = local_id.to_def_id();
+            match tcx.def_kind(local_id) {
+                DefKind::Fn | DefKind::AssocFn => {
+                    hash_debug(&mut hasher, &genv.fn_sig(def_id)?);
+                }
+                DefKind::Enum | DefKind::Struct => {
+                    hash_debug(&mut hasher, &genv.adt_def(def_id)?);
+                    hash_debug(&mut hasher, &genv.variants_of(def_id)?);
+                }
+                DefKind::TyAlias { .. } => {
+                    hash_debug(&mut hasher, &genv.type_of(def_id)?);
+                }
+                DefKind::Trait | DefKind::Impl { .. } => {
+                    hash_debug(&mut hasher, &genv.predicates_of(def_id)?);
+                    hash_debug(&mut hasher, &genv.assoc_refinements_of(def_id)?);
+                }
+                _ => {}
+            }
+        }
+
+        // Flux items are stored in a map so we sort them to get a deterministic order
+        let defns = genv.spec_func_defns()?;
+        let mut flux_items = vec![];
+        for func in genv.map().spec_funcs() {
+            flux_items.push(format!("{:?}", genv.func_decl(func.name)?));
+            flux_items.push(format!("{:?}", defns.func_defn(&func.name)));
+        }
+        for qualifier in genv.local_qualifiers()? {
+            flux_items.push(format!("{qualifier:?}"));
+        }
+        flux_items.sort();
+        flux_items.hash(&mut hasher);
+
+        hash_debug(&mut hasher, config);
+
+        Ok(Fingerprinter { crate_hash: hasher.finish() })
+    }
+
+    /// The fingerprint of the function `def_id`. Two runs checking a function with the same
+    /// fingerprint are guaranteed to produce the same result.
+    pub fn fingerprint(&self, genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<u64> {
+        let tcx = genv.tcx();
+        let ghost_stmts = compute_ghost_statements(genv, def_id)?;
+        let bodies = tcx.with_stable_hashing_context(|hcx| {
+            ghost_stmts
+                .to_sorted(&hcx, false)
+                .into_iter()
+                .map(|(def_id, _)| *def_id)
+                .collect_vec()
+        });
+
+        let mut hasher = DefaultHasher::new();
+        self.crate_hash.hash(&mut hasher);
+        for def_id in bodies {
+            let body = genv.mir(def_id)?;
+            tcx.def_path_str(def_id).hash(&mut hasher);
+            hash_debug(&mut hasher, &body);
+            for local_decl in &body.local_decls {
+                hash_debug(&mut hasher, &local_decl.ty);
+            }
+        }
+        hash_debug(&mut hasher, &genv.unpack_policy(def_id));
+        hash_debug(&mut hasher, &genv.opts(def_id));
+        hash_debug(&mut hasher, &genv.map().is_trusted(def_id)?);
+        hash_debug(&mut hasher, &genv.map().should_fail(def_id)?);
+        Ok(hasher.finish())
+    }
+}
+
+fn hash_debug(hasher: &mut impl Hasher, value: &impl fmt::Debug) {
+    format!("{value:?}").hash(hasher);
+}
@@ -9,66 +9,84 @@ use crate::{
     constraint_gen::{ConstrReason, Tag},
     fixpoint_encoding::{FixpointCtxt, KVarStore},
     refine_tree::RefineTree,
+    report_errors,
+    stats::ObligationStats,
     CheckerConfig,
 };
 
 pub fn check_invariants(
     genv: GlobalEnv,
-    cache: &mut QueryCache,
+    cache: &QueryCache,
+    stats: &mut ObligationStats,
     def_id: LocalDefId,
     invariants: &[fhir::Expr],
     adt_def: &rty::AdtDef,
     checker_config: CheckerConfig,
 ) -> Result<(), ErrorGuaranteed> {
+    let mut cx = InvariantCtxt { genv, cache, stats, def_id, adt_def, checker_config };
     adt_def
         .invariants()
         .iter()
         .enumerate()
         .try_for_each_exhaust(|(idx, invariant)| {
             let span = invariants[idx].span;
-            check_invariant(genv, cache, def_id, adt_def, span, invariant, checker_config)
+            cx.check_invariant(span, invariant)
         })
 }
 
-fn check_invariant(
-    genv: GlobalEnv,
-    cache: &mut QueryCache,
+/// State shared by the checks of all the invariants of an adt
+struct InvariantCtxt<'a, 'genv, 'tcx> {
+    genv: GlobalEnv<'genv, 'tcx>,
+    cache: &'a QueryCache,
+    stats: &'a mut ObligationStats,
     def_id: LocalDefId,
-    adt_def: &rty::AdtDef,
-    span: Span,
-    invariant: &rty::Invariant,
+    adt_def: &'a rty::AdtDef,
     checker_config: CheckerConfig,
-) -> Result<(), ErrorGuaranteed> {
-    let mut refine_tree = RefineTree::new();
+}
+
+impl InvariantCtxt<'_, '_, '_> {
+    fn check_invariant(
+        &mut self,
+        span: Span,
+        invariant: &rty::Invariant,
+    ) -> Result<(), ErrorGuaranteed> {
+        let genv = self.genv;
+        let def_id = self.def_id;
+        let mut refine_tree = RefineTree::new();
 
-    for variant_idx in adt_def.variants().indices() {
-        let mut rcx = refine_tree.refine_ctxt_at_root();
+        for variant_idx in self.adt_def.variants().indices() {
+            let mut rcx = refine_tree.refine_ctxt_at_root();
 
-        let variant = genv
-            .variant_sig(adt_def.did(), variant_idx)
-            .emit(&genv)?
-            .expect("cannot check opaque structs")
-            .instantiate_identity(&[])
-            .replace_bound_refts_with(|sort, _, _| rcx.define_vars(sort));
+            let variant = genv
+                .variant_sig(self.adt_def.did(), variant_idx)
+                .emit(&genv)?
+                .expect("cannot check opaque structs")
+                .instantiate_identity(&[])
+                .replace_bound_refts_with(|sort, _, kind| rcx.define_bound_reft_var(sort, kind));
 
-        for ty in variant.fields() {
-            let ty = rcx.unpack(ty);
-            rcx.assume_invariants(&ty, checker_config.check_overflow);
+            for ty in variant.fields() {
+                let ty = rcx.unpack(ty);
+                rcx.assume_invariants(&ty, self.checker_config.check_overflow);
+            }
+            let pred = invariant.apply(&variant.idx);
+            rcx.check_pred(&pred, Tag::new(ConstrReason::Other, DUMMY_SP));
+        }
+        self.stats
+            .insert(genv.tcx().def_path_str(def_id), refine_tree.obligations());
+        let mut fcx = FixpointCtxt::new(genv, def_id, KVarStore::default()).emit(&genv)?;
+        if config::dump_constraint() {
+            dbg::dump_item_info(genv.tcx(), def_id, "fluxc", &refine_tree).unwrap();
         }
-        let pred = invariant.apply(&variant.idx);
-        rcx.check_pred(&pred, Tag::new(ConstrReason::Other, DUMMY_SP));
-    }
-    let mut fcx = FixpointCtxt::new(genv, def_id, KVarStore::default()).emit(&genv)?;
-    if config::dump_constraint() {
-        dbg::dump_item_info(genv.tcx(), def_id, "fluxc", &refine_tree).unwrap();
-    }
 
-    let cstr = refine_tree.into_fixpoint(&mut fcx).emit(&genv)?;
-    let errors = fcx.check(cache, cstr, &checker_config).emit(&genv)?;
-    if errors.is_empty() {
-        Ok(())
-    } else {
-        Err(genv.sess().emit_err(errors::Invalid { span }))
+        let cstr = refine_tree.into_fixpoint(&mut fcx).emit(&genv)?;
+        let errors = fcx
+            .check(self.cache, cstr, &self.checker_config)
+            .emit(&genv)?;
+        match errors {
+            Ok(errors) if errors.is_empty() => Ok(()),
+            Ok(_) => Err(genv.sess().emit_err(errors::Invalid { span })),
+            Err(timeout) => report_errors(genv, Err(timeout)),
+        }
     }
 }
 
@@ -25,112 +25,395 @@ extern crate rustc_middle;
 extern crate rustc_mir_dataflow;
 extern crate rustc_span;
 extern crate rustc_type_ir;
+extern crate serde_json;
 
+mod auto_spec;
 mod checker;
 mod constraint_gen;
+mod derived;
 mod fixpoint_encoding;
 mod ghost_statements;
+mod holes;
+mod hover;
+pub mod incremental;
 pub mod invariants;
+mod liveness;
+mod qualifier_mining;
 mod queue;
 mod refine_tree;
+mod sig_suggestion;
 mod sigs;
+pub mod stats;
+mod trace;
 mod type_env;
 
+use std::io::Write as _;
+
 use checker::Checker;
 pub use checker::CheckerConfig;
-use constraint_gen::{ConstrReason, Tag};
-use flux_common::{cache::QueryCache, dbg, result::ResultExt as _};
-use flux_config as config;
+pub use constraint_gen::ConstrReason;
+use constraint_gen::Tag;
+use flux_common::{
+    cache::QueryCache,
+    dbg,
+    result::ResultExt as _,
+    timings::{self, Phase},
+};
+use flux_config::{self as config, RefineTreeFormat};
+use flux_errors::{EFLUX0001, EFLUX0002};
 use flux_macros::fluent_messages;
 use flux_middle::{
+    fhir,
     global_env::GlobalEnv,
-    rty::{self, ESpan},
+    pretty,
+    queries::Providers,
+    rty::{self, fold::TypeVisitable},
 };
 use itertools::Itertools;
+use rustc_data_structures::unord::UnordMap;
 use rustc_errors::ErrorGuaranteed;
-use rustc_hir::def_id::LocalDefId;
+use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_span::Span;
-
-use crate::{checker::errors::ResultExt as _, ghost_statements::compute_ghost_statements};
+use stats::ObligationStats;
+
+use crate::{
+    checker::{errors::ResultExt as _, ShapeResult},
+    fixpoint_encoding::{
+        FailedPred, FixpointQuery, InferredInvariant, QueryError, QueryErrors, QuerySolver,
+        SuggestedSig, Timeout,
+    },
+    ghost_statements::{compute_ghost_statements, GhostStatements},
+    holes::Holes,
+};
 
 fluent_messages! { "../locales/en-US.ftl" }
 
+pub fn provide(providers: &mut Providers) {
+    providers.fn_summary = auto_spec::summary;
+}
+
+/// The fixpoint query of a function, see [`fn_query`].
+pub type FnQuery = FixpointQuery<Tag>;
+
+/// Solves the fixpoint queries of functions in the background, see [`QuerySolver`].
+pub type FnSolver = QuerySolver<Tag>;
+
 pub fn check_fn(
     genv: GlobalEnv,
-    cache: &mut QueryCache,
+    cache: &QueryCache,
+    stats: &mut ObligationStats,
     def_id: LocalDefId,
     config: CheckerConfig,
 ) -> Result<(), ErrorGuaranteed> {
+    match fn_query(genv, stats, def_id, config)? {
+        Some(query) => {
+            let outcome = query.solve(cache);
+            report_invariants(genv, &outcome.invariants);
+            report_suggested_sig(genv, outcome.suggested_sig.as_ref());
+            report_fn_errors(genv, def_id, outcome.errors)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Checks the function `def_id` up to the point of generating its fixpoint query, which can be
+/// solved later (possibly in parallel with the queries of other functions). Returns `None` if the
+/// function doesn't need to be checked.
+pub fn fn_query(
+    genv: GlobalEnv,
+    stats: &mut ObligationStats,
+    def_id: LocalDefId,
+    config: CheckerConfig,
+) -> Result<Option<FnQuery>, ErrorGuaranteed> {
     dbg::check_fn_span!(genv.tcx(), def_id).in_scope(|| {
         let span = genv.tcx().def_span(def_id);
 
         if genv.map().is_trusted(def_id).with_span(span).emit(&genv)? {
-            return Ok(());
+            return Ok(None);
         }
 
         // HACK(nilehmann) this will ignore any code generated by a macro. This is
         // a temporary workaround to allow `#[derive(PartialEq, Eq)]` and should be
         // removed.
         if genv.tcx().def_span(def_id).ctxt() > rustc_span::SyntaxContext::root() {
-            return Ok(());
+            return Ok(None);
         }
-        let ghost_stmts = compute_ghost_statements(genv, def_id)
-            .with_span(span)
-            .emit(&genv)?;
 
-        // PHASE 1: infer shape of `TypeEnv` at the entry of join points
-        let shape_result =
-            Checker::run_in_shape_mode(genv, def_id, &ghost_stmts, config).emit(&genv)?;
-        tracing::info!("check_fn::shape");
+        let opts = genv.opts(def_id);
+        let config = CheckerConfig {
+            unpack: genv.unpack_policy(def_id).unwrap_or(config.unpack),
+            solver: opts.solver.unwrap_or(config.solver),
+            timeout: opts.timeout.unwrap_or(config.timeout),
+            ..config
+        };
 
-        // PHASE 2: generate refinement tree constraint
         let (mut refine_tree, kvars) =
-            Checker::run_in_refine_mode(genv, def_id, &ghost_stmts, shape_result, config)
-                .emit(&genv)?;
-        tracing::info!("check_fn::refine");
-
-        // PHASE 3: invoke fixpoint on the constraint
-        refine_tree.simplify();
-        if config::dump_constraint() {
-            dbg::dump_item_info(genv.tcx(), def_id, "fluxc", &refine_tree).unwrap();
-        }
-        let mut fcx = fixpoint_encoding::FixpointCtxt::new(genv, def_id, kvars).emit(&genv)?;
-        fcx.collect_sorts(&refine_tree);
-        let cstr = refine_tree.into_fixpoint(&mut fcx).emit(&genv)?;
-        let errors = fcx.check(cache, cstr, &config).emit(&genv)?;
+            timings::time(Phase::RefineCheck, genv.tcx(), def_id, || {
+                if config.warn_holes {
+                    let body = genv.mir(def_id).with_span(span).emit(&genv)?;
+                    for span in Holes::new(genv.tcx(), &body).spans() {
+                        genv.sess().emit_warn(errors::HoleWarning { span: *span });
+                    }
+                }
 
+                let ghost_stmts = compute_ghost_statements(genv, def_id)
+                    .with_span(span)
+                    .emit(&genv)?;
+
+                // PHASE 1: infer shape of `TypeEnv` at the entry of join points. If there are no
+                // join points there's nothing to infer, so we skip straight to phase 2.
+                let shape_result = if has_join_points(genv, &ghost_stmts) {
+                    let shape_result =
+                        Checker::run_in_shape_mode(genv, def_id, &ghost_stmts, config)
+                            .emit(&genv)?;
+                    tracing::info!("check_fn::shape");
+                    shape_result
+                } else {
+                    ShapeResult::default()
+                };
+
+                // PHASE 2: generate refinement tree constraint
+                let result =
+                    Checker::run_in_refine_mode(genv, def_id, &ghost_stmts, shape_result, config)
+                        .emit(&genv)?;
+                tracing::info!("check_fn::refine");
+                Ok::<_, ErrorGuaranteed>(result)
+            })?;
+
+        // PHASE 3: encode the constraint as a fixpoint query
+        let query = timings::time(Phase::Encode, genv.tcx(), def_id, || {
+            refine_tree.simplify();
+            stats.insert(genv.tcx().def_path_str(def_id), refine_tree.obligations());
+            let dump_refine_tree = config::dump_refine_tree();
+            if config::dump_constraint() || dump_refine_tree == Some(RefineTreeFormat::Text) {
+                dbg::dump_item_info(genv.tcx(), def_id, "fluxc", &refine_tree).unwrap();
+            }
+            if dump_refine_tree == Some(RefineTreeFormat::Dot) {
+                let mut writer =
+                    dbg::writer_for_item(genv.tcx(), def_id.to_def_id(), "dot").unwrap();
+                write!(writer, "{}", refine_tree.to_dot(genv.tcx())).unwrap();
+            }
+            let mut fcx = fixpoint_encoding::FixpointCtxt::new(genv, def_id, kvars).emit(&genv)?;
+            fcx.collect_sorts(&refine_tree);
+            let cstr = refine_tree.into_fixpoint(&mut fcx).emit(&genv)?;
+            fcx.into_query(cstr, &config).emit(&genv)
+        })?;
         tracing::info!("check_fn::fixpoint");
-        if errors.is_empty() {
-            Ok(())
+
+        Ok(Some(query))
+    })
+}
+
+/// Whether any of the bodies checked together with a function (see [`compute_ghost_statements`])
+/// has a join point. If we fail to get the body of one of them, we conservatively return `true`
+/// and let the checker report the error.
+fn has_join_points(genv: GlobalEnv, ghost_stmts: &UnordMap<LocalDefId, GhostStatements>) -> bool {
+    ghost_stmts.items().any(|(def_id, _)| {
+        genv.mir(*def_id)
+            .map_or(true, |body| body.basic_blocks.indices().any(|bb| body.is_join_point(bb)))
+    })
+}
+
+fn call_error(
+    genv: GlobalEnv,
+    span: Span,
+    tag: Tag,
+    facts: Vec<Span>,
+    pred: Option<FailedPred>,
+) -> ErrorGuaranteed {
+    let failed_pred = pred.map(|pred| failed_pred_to_string(genv, &pred));
+    genv.sess().emit_err_with_code(
+        errors::RefineError::call(
+            span,
+            tag.dst_span,
+            facts,
+            blame(genv, tag),
+            signature_help(genv, tag),
+        )
+        .with_failed_pred(failed_pred),
+        EFLUX0001,
+    )
+}
+
+fn ret_error(
+    genv: GlobalEnv,
+    span: Span,
+    tag: Tag,
+    facts: Vec<Span>,
+    pred: Option<FailedPred>,
+) -> ErrorGuaranteed {
+    let failed_pred = pred.as_ref().map(|pred| failed_pred_to_string(genv, pred));
+    let suggestion = pred.and_then(|pred| precondition_suggestion(genv, tag, &pred));
+    genv.sess().emit_err_with_code(
+        errors::RefineError::ret(
+            span,
+            tag.dst_span,
+            facts,
+            blame(genv, tag),
+            signature_help(genv, tag),
+        )
+        .with_failed_pred(failed_pred)
+        .with_suggestion(suggestion),
+        EFLUX0002,
+    )
+}
+
+/// Reports a failed fold pointing at the declaration of the field whose type doesn't hold. When
+/// folding nested places, inner places are folded first, so this is the innermost failing field.
+fn fold_error(genv: GlobalEnv, span: Span, field: DefId) -> ErrorGuaranteed {
+    let tcx = genv.tcx();
+    let field_note = errors::FoldFieldNote {
+        span: tcx.def_span(field),
+        field: tcx.item_name(field).to_string(),
+        adt: tcx.def_path_str(tcx.parent(field)),
+    };
+    genv.sess().emit_err(errors::FoldError { span, field_note })
+}
+
+/// Prints the predicate that failed using the names the user gave to its variables. Variables
+/// without a surface name are printed with their internal name.
+fn failed_pred_to_string(genv: GlobalEnv, pred: &FailedPred) -> String {
+    let cx = pretty::PrettyCx::surface(genv.tcx());
+    cx.with_fvar_names(pred.surface_names.iter().copied(), || {
+        format!("{:?}", pretty::WithCx::new(&cx, &pred.expr))
+    })
+}
+
+/// When a postcondition fails because of a predicate that only mentions one argument of the
+/// function, assuming the predicate as a precondition is enough to prove the postcondition. In that
+/// case, we suggest refining the argument in the signature, e.g., if the predicate is `x > 0` and
+/// the argument is declared as `x: i32`, we suggest `x: i32{x > 0}`.
+fn precondition_suggestion(
+    genv: GlobalEnv,
+    tag: Tag,
+    pred: &FailedPred,
+) -> Option<errors::PreconditionSuggestion> {
+    let def_id = tag.required_by?.as_local()?;
+    let fvars = pred.expr.fvars();
+    let [name] = fvars.iter().collect_vec()[..] else { return None };
+    let (_, sym) = pred.surface_names.iter().find(|(n, _)| n == name)?;
+
+    // We can only extend arguments declared as `x: T`, where `T` is a base type
+    let decl = genv.map().node(def_id).ok()?.fn_sig()?.decl;
+    if decl.lifted {
+        return None;
+    }
+    let bty = decl.args.iter().find_map(|arg| {
+        if let fhir::TyKind::Indexed(bty, idx) = &arg.kind
+            && idx.is_colon_param().is_some()
+            && let fhir::RefineArgKind::Expr(expr) = &idx.kind
+            && let fhir::ExprKind::Var(path, _) = &expr.kind
+            && let [ident] = path.segments
+            && ident.name == *sym
+        {
+            Some(bty)
         } else {
-            report_errors(genv, errors)
+            None
         }
+    })?;
+
+    let cx = pretty::PrettyCx::surface(genv.tcx());
+    let pred = cx
+        .with_fvar_names([(*name, *sym)], || format!("{:?}", pretty::WithCx::new(&cx, &pred.expr)));
+    let ty = genv
+        .tcx()
+        .sess
+        .source_map()
+        .span_to_snippet(bty.span)
+        .ok()?;
+    let code = format!("{ty}{{{pred}}}");
+    Some(errors::PreconditionSuggestion { span: bty.span, sugg: format!("{sym}: {code}"), code })
+}
+
+/// Shows the signature of the item whose condition failed in surface syntax, so it reads like what
+/// the user wrote in `#[flux::sig]`.
+fn signature_help(genv: GlobalEnv, tag: Tag) -> Option<errors::SignatureHelp> {
+    let def_id = tag.required_by?;
+    let fn_sig = genv.fn_sig(def_id).ok()?;
+    Some(errors::SignatureHelp {
+        def: genv.tcx().def_path_str(def_id),
+        sig: pretty::to_surface_string(genv.tcx(), &fn_sig.skip_binder()),
+    })
+}
+
+/// Explains which conjunct of which item's condition failed, e.g., ``cannot prove `i < len`
+/// required by `RVec::get` at rvec.rs:42``. The conjunct is taken from the source, so there's
+/// nothing to report if the condition's span is unknown or its source is not available. A conjunct
+/// coming from the body of a spec function is blamed on the call to the function in the condition.
+fn blame(genv: GlobalEnv, tag: Tag) -> Option<errors::BlameNote> {
+    let dst_span = tag.dst_span?;
+    let dst_span = dst_span.base().unwrap_or(dst_span.span());
+    let source_map = genv.tcx().sess.source_map();
+    let pred = source_map.span_to_snippet(dst_span).ok()?;
+    let loc = source_map.lookup_char_pos(dst_span.lo());
+    Some(errors::BlameNote {
+        pred: pred.split_whitespace().join(" "),
+        def: genv.tcx().def_path_str(tag.required_by?),
+        loc: format!("{}:{}", loc.file.name.prefer_local(), loc.line),
     })
 }
 
-fn call_error(genv: GlobalEnv, span: Span, dst_span: Option<ESpan>) -> ErrorGuaranteed {
-    genv.sess()
-        .emit_err(errors::RefineError::call(span, dst_span))
+/// Reports the loop invariants inferred by fixpoint, see [`config::show_invariants`].
+pub fn report_invariants(genv: GlobalEnv, invariants: &[InferredInvariant]) {
+    for invariant in invariants {
+        genv.sess().emit_note(errors::InferredInvariant {
+            span: invariant.span,
+            pred: invariant.pred.clone(),
+        });
+    }
+}
+
+/// Reports the signature inferred for a function without one, see [`config::suggest_sigs`].
+pub fn report_suggested_sig(genv: GlobalEnv, suggested_sig: Option<&SuggestedSig>) {
+    if let Some(suggested_sig) = suggested_sig {
+        genv.sess().emit_note(errors::SuggestedSig {
+            span: suggested_sig.span,
+            sig: suggested_sig.sig.clone(),
+        });
+    }
 }
 
-fn ret_error(genv: GlobalEnv, span: Span, dst_span: Option<ESpan>) -> ErrorGuaranteed {
-    genv.sess()
-        .emit_err(errors::RefineError::ret(span, dst_span))
+/// Reports the errors found by solving the fixpoint query of the function `def_id`. If the function
+/// is marked with `#[flux::should_fail]`, the errors are expected, and instead we report an error if
+/// the function verifies. A timeout is always reported, it doesn't count as a failure.
+pub fn report_fn_errors(
+    genv: GlobalEnv,
+    def_id: LocalDefId,
+    errors: QueryErrors<Tag>,
+) -> Result<(), ErrorGuaranteed> {
+    let span = genv.tcx().def_span(def_id);
+    if !genv.map().should_fail(def_id).with_span(span).emit(&genv)? {
+        return report_errors(genv, errors);
+    }
+    match errors {
+        Ok(errors) if errors.is_empty() => {
+            Err(genv.sess().emit_err(errors::ShouldFailError { span }))
+        }
+        Ok(_) => Ok(()),
+        Err(_) => report_errors(genv, errors),
+    }
 }
 
-fn report_errors(genv: GlobalEnv, errors: Vec<Tag>) -> Result<(), ErrorGuaranteed> {
+/// Reports the errors found by solving the fixpoint query of a function.
+pub fn report_errors(genv: GlobalEnv, errors: QueryErrors<Tag>) -> Result<(), ErrorGuaranteed> {
+    let errors = match errors {
+        Ok(errors) => errors,
+        Err(Timeout { span, secs }) => {
+            return Err(genv.sess().emit_err(errors::TimeoutError { span, secs }));
+        }
+    };
     let mut e = None;
-    for err in errors {
+    for QueryError { tag: err, facts, pred } in errors {
         let span = err.src_span;
         e = Some(match err.reason {
-            ConstrReason::Call => call_error(genv, span, err.dst_span),
+            ConstrReason::Call => call_error(genv, span, err, facts, pred),
             ConstrReason::Assign => genv.sess().emit_err(errors::AssignError { span }),
-            ConstrReason::Ret => ret_error(genv, span, err.dst_span),
+            ConstrReason::Ret => ret_error(genv, span, err, facts, pred),
             ConstrReason::Div => genv.sess().emit_err(errors::DivError { span }),
             ConstrReason::Rem => genv.sess().emit_err(errors::RemError { span }),
             ConstrReason::Goto(_) => genv.sess().emit_err(errors::GotoError { span }),
             ConstrReason::Assert(msg) => genv.sess().emit_err(errors::AssertError { span, msg }),
-            ConstrReason::Fold => genv.sess().emit_err(errors::FoldError { span }),
+            ConstrReason::Fold(field) => fold_error(genv, span, field),
             ConstrReason::Overflow => genv.sess().emit_err(errors::OverflowError { span }),
             ConstrReason::Other => genv.sess().emit_err(errors::UnknownError { span }),
         });
@@ -144,20 +427,22 @@ fn report_errors(genv: GlobalEnv, errors: Vec<Tag>) -> Result<(), ErrorGuarantee
 }
 
 mod errors {
-    use flux_errors::E0999;
+    use flux_errors::{
+        E0999, EFLUX0003, EFLUX0004, EFLUX0006, EFLUX0007, EFLUX0008, EFLUX0009, EFLUX0010,
+    };
     use flux_macros::{Diagnostic, Subdiagnostic};
     use flux_middle::rty::ESpan;
     use rustc_span::Span;
 
     #[derive(Diagnostic)]
-    #[diag(refineck_goto_error, code = E0999)]
+    #[diag(refineck_goto_error, code = EFLUX0006)]
     pub struct GotoError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_assign_error, code = E0999)]
+    #[diag(refineck_assign_error, code = EFLUX0007)]
     pub struct AssignError {
         #[primary_span]
         pub span: Span,
@@ -177,56 +462,191 @@ mod errors {
         pub span: Span,
     }
 
+    #[derive(Subdiagnostic)]
+    #[label(refineck_fact_label)]
+    pub(crate) struct FactLabel {
+        #[primary_span]
+        pub span: Span,
+    }
+
+    #[derive(Subdiagnostic)]
+    #[suggestion(
+        refineck_precondition_suggestion,
+        code = "{code}",
+        applicability = "maybe-incorrect"
+    )]
+    pub(crate) struct PreconditionSuggestion {
+        #[primary_span]
+        pub span: Span,
+        pub code: String,
+        pub sugg: String,
+    }
+
+    #[derive(Subdiagnostic)]
+    #[note(refineck_blame_note)]
+    pub(crate) struct BlameNote {
+        pub pred: String,
+        pub def: String,
+        pub loc: String,
+    }
+
+    #[derive(Subdiagnostic)]
+    #[help(refineck_signature_help)]
+    pub(crate) struct SignatureHelp {
+        pub def: String,
+        pub sig: String,
+    }
+
     #[derive(Diagnostic)]
-    #[diag(refineck_refine_error, code = E0999)]
+    #[diag(refineck_refine_error)]
     pub struct RefineError {
         #[primary_span]
         #[label]
         pub span: Span,
         cond: &'static str,
+        has_pred: bool,
+        pred: String,
         #[subdiagnostic]
         span_note: Option<ConditionSpanNote>,
         #[subdiagnostic]
         call_span_note: Option<CallSpanNote>,
+        #[subdiagnostic]
+        fact_labels: Vec<FactLabel>,
+        #[subdiagnostic]
+        blame_note: Option<BlameNote>,
+        #[subdiagnostic]
+        signature_help: Option<SignatureHelp>,
+        #[subdiagnostic]
+        suggestion: Option<PreconditionSuggestion>,
     }
 
     impl RefineError {
-        pub fn call(span: Span, espan: Option<ESpan>) -> Self {
-            RefineError::new("precondition", span, espan)
+        pub fn call(
+            span: Span,
+            espan: Option<ESpan>,
+            facts: Vec<Span>,
+            blame_note: Option<BlameNote>,
+            signature_help: Option<SignatureHelp>,
+        ) -> Self {
+            RefineError::new("precondition", span, espan, facts, blame_note, signature_help)
         }
 
-        pub fn ret(span: Span, espan: Option<ESpan>) -> Self {
-            RefineError::new("postcondition", span, espan)
+        pub fn ret(
+            span: Span,
+            espan: Option<ESpan>,
+            facts: Vec<Span>,
+            blame_note: Option<BlameNote>,
+            signature_help: Option<SignatureHelp>,
+        ) -> Self {
+            RefineError::new("postcondition", span, espan, facts, blame_note, signature_help)
         }
 
-        fn new(cond: &'static str, span: Span, espan: Option<ESpan>) -> RefineError {
-            match espan {
-                Some(dst_span) => {
-                    let span_note = Some(ConditionSpanNote { span: dst_span.span() });
-                    let call_span_note = dst_span.base().map(|span| CallSpanNote { span });
-                    RefineError { span, cond, span_note, call_span_note }
-                }
-                None => RefineError { span, cond, span_note: None, call_span_note: None },
+        fn new(
+            cond: &'static str,
+            span: Span,
+            espan: Option<ESpan>,
+            facts: Vec<Span>,
+            blame_note: Option<BlameNote>,
+            signature_help: Option<SignatureHelp>,
+        ) -> RefineError {
+            let span_note = espan.map(|dst_span| ConditionSpanNote { span: dst_span.span() });
+            let call_span_note = espan
+                .and_then(|dst_span| dst_span.base())
+                .map(|span| CallSpanNote { span });
+            // Skip facts that would overlap with the spans we are already pointing at
+            let fact_labels = facts
+                .into_iter()
+                .filter(|fact| {
+                    !fact.overlaps(span)
+                        && span_note
+                            .as_ref()
+                            .map_or(true, |note| !fact.overlaps(note.span))
+                })
+                .map(|span| FactLabel { span })
+                .collect();
+            RefineError {
+                span,
+                cond,
+                has_pred: false,
+                pred: String::new(),
+                span_note,
+                call_span_note,
+                fact_labels,
+                blame_note,
+                signature_help,
+                suggestion: None,
+            }
+        }
+
+        pub fn with_failed_pred(self, pred: Option<String>) -> Self {
+            match pred {
+                Some(pred) => RefineError { has_pred: true, pred, ..self },
+                None => self,
             }
         }
+
+        pub fn with_suggestion(self, suggestion: Option<PreconditionSuggestion>) -> Self {
+            RefineError { suggestion, ..self }
+        }
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_div_error, code = E0999)]
+    #[diag(refineck_timeout_error, code = E0999)]
+    #[help]
+    pub struct TimeoutError {
+        #[primary_span]
+        pub span: Span,
+        pub secs: u64,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_hole_warning)]
+    #[note]
+    pub struct HoleWarning {
+        #[primary_span]
+        pub span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_inferred_invariant)]
+    pub struct InferredInvariant {
+        #[primary_span]
+        pub span: Span,
+        pub pred: String,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_suggested_sig)]
+    pub struct SuggestedSig {
+        #[primary_span]
+        pub span: Span,
+        pub sig: String,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_should_fail_error)]
+    #[note]
+    pub struct ShouldFailError {
+        #[primary_span]
+        pub span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_div_error, code = EFLUX0008)]
     pub struct DivError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_rem_error, code = E0999)]
+    #[diag(refineck_rem_error, code = EFLUX0009)]
     pub struct RemError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_assert_error, code = E0999)]
+    #[diag(refineck_assert_error, code = EFLUX0010)]
     pub struct AssertError {
         #[primary_span]
         pub span: Span,
@@ -234,14 +654,25 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_fold_error, code = E0999)]
+    #[diag(refineck_fold_error, code = EFLUX0003)]
     pub struct FoldError {
         #[primary_span]
         pub span: Span,
+        #[subdiagnostic]
+        pub field_note: FoldFieldNote,
+    }
+
+    #[derive(Subdiagnostic)]
+    #[note(refineck_fold_field_note)]
+    pub struct FoldFieldNote {
+        #[primary_span]
+        pub span: Span,
+        pub field: String,
+        pub adt: String,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_overflow_error, code = E0999)]
+    #[diag(refineck_overflow_error, code = EFLUX0004)]
     pub struct OverflowError {
         #[primary_span]
         pub span: Span,
@@ -0,0 +1,51 @@
+//! Liveness of locals used to restrict the scope of the kvars generated at join points.
+//!
+//! A kvar at the entry of a basic block is parameterized by every variable in scope, which makes
+//! their arity grow linearly with the length of the function. However, a variable that is not
+//! mentioned by the type of any live location cannot be related to anything checked after the join
+//! point, so there's no need to pass it to the kvar.
+use flux_middle::{
+    rty::Name,
+    rustc::mir::{BasicBlock, Body, Local},
+};
+use rustc_hash::FxHashSet;
+use rustc_index::{bit_set::BitSet, IndexVec};
+use rustc_middle::ty::TyCtxt;
+use rustc_mir_dataflow::{impls::MaybeLiveLocals, Analysis};
+
+use crate::refine_tree::Scope;
+
+pub(crate) struct Liveness {
+    /// Locals that may be used after the entry of each basic block
+    live_at_entry: IndexVec<BasicBlock, BitSet<Local>>,
+    /// Variables in scope at the entry of the function. They are always live because the output
+    /// of the function may mention them.
+    params: FxHashSet<Name>,
+}
+
+impl Liveness {
+    pub(crate) fn new(tcx: TyCtxt, body: &Body, params: &Scope) -> Self {
+        let mut cursor = MaybeLiveLocals
+            .into_engine(tcx, body.rustc_body())
+            .iterate_to_fixpoint()
+            .into_results_cursor(body.rustc_body());
+        let live_at_entry = body
+            .basic_blocks
+            .indices()
+            .map(|bb| {
+                cursor.seek_to_block_start(bb);
+                cursor.get().clone()
+            })
+            .collect();
+        let params = params.iter().map(|(name, _)| name).collect();
+        Self { live_at_entry, params }
+    }
+
+    pub(crate) fn live_at_entry(&self, bb: BasicBlock) -> &BitSet<Local> {
+        &self.live_at_entry[bb]
+    }
+
+    pub(crate) fn params(&self) -> &FxHashSet<Name> {
+        &self.params
+    }
+}
@@ -0,0 +1,162 @@
+//! Candidate qualifiers mined from the constraint of a function (enabled with `mine_quals`).
+//!
+//! The predicates a programmer writes in specs, branch conditions and asserts are often exactly
+//! the building blocks of the loop invariants fixpoint has to infer. All of them end up in the
+//! encoded constraint, either as hypotheses in binders or as heads, so we harvest the atomic
+//! relations appearing there and abstract their free variables into qualifier parameters, e.g.,
+//! the guard `a3 < a5 + 1` becomes the qualifier `(qualif Mined0 ((a0 int) (a1 int)) (a0 < a1 + 1))`.
+//! Constants and functions are left untouched since they mean the same in every scope.
+
+use flux_common::index::IndexVec;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+
+use crate::fixpoint_encoding::fixpoint::{self, LocalVar};
+
+/// Atoms with more free variables than this are skipped. Fixpoint instantiates a qualifier with
+/// every combination of variables in scope, so qualifiers with many parameters are expensive.
+const MAX_PARAMS: usize = 3;
+
+pub(crate) fn mine(constraint: &fixpoint::Constraint) -> Vec<fixpoint::Qualifier> {
+    let mut miner = Miner { scope: FxHashMap::default(), quals: FxIndexMap::default() };
+    miner.constraint(constraint);
+    miner
+        .quals
+        .into_values()
+        .enumerate()
+        .map(|(i, (args, body))| fixpoint::Qualifier { name: format!("Mined{i}"), args, body })
+        .collect()
+}
+
+struct Miner {
+    /// Sorts of the variables bound in the constraint
+    scope: FxHashMap<LocalVar, fixpoint::Sort>,
+    /// Qualifiers found so far, keyed by their textual representation to remove duplicates
+    quals: FxIndexMap<String, (Vec<(fixpoint::Var, fixpoint::Sort)>, fixpoint::Expr)>,
+}
+
+impl Miner {
+    fn constraint(&mut self, cstr: &fixpoint::Constraint) {
+        match cstr {
+            fixpoint::Constraint::Conj(cstrs) => {
+                for cstr in cstrs {
+                    self.constraint(cstr);
+                }
+            }
+            fixpoint::Constraint::ForAll(bind, head) => {
+                if let fixpoint::Var::Local(var) = bind.name {
+                    self.scope.insert(var, bind.sort.clone());
+                }
+                self.pred(&bind.pred);
+                self.constraint(head);
+            }
+            fixpoint::Constraint::Pred(pred, _) => self.pred(pred),
+        }
+    }
+
+    fn pred(&mut self, pred: &fixpoint::Pred) {
+        match pred {
+            fixpoint::Pred::And(preds) => {
+                for pred in preds {
+                    self.pred(pred);
+                }
+            }
+            fixpoint::Pred::KVar(..) => {}
+            fixpoint::Pred::Expr(e) => self.expr(e),
+        }
+    }
+
+    /// Looks for atoms in the boolean structure of `e`
+    fn expr(&mut self, e: &fixpoint::Expr) {
+        match e {
+            fixpoint::Expr::And(exprs) | fixpoint::Expr::Or(exprs) => {
+                for e in exprs {
+                    self.expr(e);
+                }
+            }
+            fixpoint::Expr::Not(e) => self.expr(e),
+            fixpoint::Expr::Imp(box [e1, e2]) | fixpoint::Expr::Iff(box [e1, e2]) => {
+                self.expr(e1);
+                self.expr(e2);
+            }
+            fixpoint::Expr::Atom(..) => {
+                let mut abs = Abstractor { scope: &self.scope, params: IndexVec::new() };
+                if let Some(body) = abs.expr(e)
+                    && !abs.params.is_empty()
+                    && abs.params.len() <= MAX_PARAMS
+                {
+                    let args = abs
+                        .params
+                        .into_iter_enumerated()
+                        .map(|(param, (_, sort))| (fixpoint::Var::Local(param), sort))
+                        .collect::<Vec<_>>();
+                    let qual = fixpoint::Qualifier { name: String::new(), args, body };
+                    self.quals
+                        .entry(qual.to_string())
+                        .or_insert((qual.args, qual.body));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Replaces the free variables of an expression with qualifier parameters
+struct Abstractor<'a> {
+    scope: &'a FxHashMap<LocalVar, fixpoint::Sort>,
+    /// The variable abstracted by each parameter, in order of first occurrence
+    params: IndexVec<LocalVar, (LocalVar, fixpoint::Sort)>,
+}
+
+impl Abstractor<'_> {
+    /// Returns `None` if the expression mentions a variable whose sort cannot be used in a
+    /// qualifier parameter.
+    fn expr(&mut self, e: &fixpoint::Expr) -> Option<fixpoint::Expr> {
+        let e = match e {
+            fixpoint::Expr::Constant(c) => fixpoint::Expr::Constant(*c),
+            fixpoint::Expr::Var(var) => fixpoint::Expr::Var(self.var(*var)?),
+            fixpoint::Expr::App(func, args) => {
+                fixpoint::Expr::App(self.var(*func)?, self.exprs(args)?)
+            }
+            fixpoint::Expr::Neg(e) => fixpoint::Expr::Neg(Box::new(self.expr(e)?)),
+            fixpoint::Expr::BinaryOp(op, box [e1, e2]) => {
+                fixpoint::Expr::BinaryOp(*op, Box::new([self.expr(e1)?, self.expr(e2)?]))
+            }
+            fixpoint::Expr::IfThenElse(box [p, e1, e2]) => {
+                fixpoint::Expr::IfThenElse(Box::new([
+                    self.expr(p)?,
+                    self.expr(e1)?,
+                    self.expr(e2)?,
+                ]))
+            }
+            fixpoint::Expr::And(exprs) => fixpoint::Expr::And(self.exprs(exprs)?),
+            fixpoint::Expr::Or(exprs) => fixpoint::Expr::Or(self.exprs(exprs)?),
+            fixpoint::Expr::Not(e) => fixpoint::Expr::Not(Box::new(self.expr(e)?)),
+            fixpoint::Expr::Imp(box [e1, e2]) => {
+                fixpoint::Expr::Imp(Box::new([self.expr(e1)?, self.expr(e2)?]))
+            }
+            fixpoint::Expr::Iff(box [e1, e2]) => {
+                fixpoint::Expr::Iff(Box::new([self.expr(e1)?, self.expr(e2)?]))
+            }
+            fixpoint::Expr::Atom(rel, box [e1, e2]) => {
+                fixpoint::Expr::Atom(*rel, Box::new([self.expr(e1)?, self.expr(e2)?]))
+            }
+        };
+        Some(e)
+    }
+
+    fn exprs(&mut self, exprs: &[fixpoint::Expr]) -> Option<Vec<fixpoint::Expr>> {
+        exprs.iter().map(|e| self.expr(e)).collect()
+    }
+
+    fn var(&mut self, var: fixpoint::Var) -> Option<fixpoint::Var> {
+        let fixpoint::Var::Local(var) = var else { return Some(var) };
+        if let Some(param) = self.params.iter().position(|(v, _)| *v == var) {
+            return Some(fixpoint::Var::Local(LocalVar::from_usize(param)));
+        }
+        let sort = self.scope.get(&var)?;
+        if !matches!(sort, fixpoint::Sort::Int | fixpoint::Sort::Bool | fixpoint::Sort::Real) {
+            return None;
+        }
+        Some(fixpoint::Var::Local(self.params.push((var, sort.clone()))))
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
     cell::RefCell,
+    iter,
     ops::ControlFlow,
     rc::{Rc, Weak},
 };
@@ -8,6 +9,7 @@ use flux_common::{
     index::{IndexGen, IndexVec},
     iter::IterExt,
 };
+use flux_config::UnpackPolicy;
 use flux_middle::{
     queries::QueryResult,
     rty::{
@@ -17,14 +19,19 @@ use flux_middle::{
             TypeFoldable, TypeFolder, TypeSuperFoldable, TypeSuperVisitable, TypeVisitable,
             TypeVisitor,
         },
-        BaseTy, Expr, GenericArg, Mutability, Name, Sort, Ty, TyKind,
+        BaseTy, BoundReftKind, Expr, ExprKind, GenericArg, Mutability, Name, Sort, SortCtor, Ty,
+        TyKind,
     },
 };
 use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Symbol;
 
 use crate::{
     constraint_gen::Tag,
     fixpoint_encoding::{fixpoint, sort_to_fixpoint, stitch, FixpointCtxt},
+    stats::ObligationCounts,
 };
 
 /// A *refine*ment *tree* tracks the "tree-like structure" of refinement variables and predicates
@@ -91,6 +98,16 @@ pub(crate) struct Snapshot {
     ptr: WeakNodePtr,
 }
 
+/// A position in a [refinement tree] used to print the nodes added below it afterwards, e.g., the
+/// constraints generated by a single statement.
+///
+/// [refinement tree]: RefineTree
+pub(crate) struct Mark {
+    ptr: NodePtr,
+    /// Number of children the node had when the mark was taken
+    nchildren: usize,
+}
+
 /// A ist of refinement variables and their sorts.
 #[derive(PartialEq, Eq)]
 pub(crate) struct Scope {
@@ -114,7 +131,9 @@ struct WeakNodePtr(Weak<RefCell<Node>>);
 enum NodeKind {
     Conj,
     Comment(String),
-    ForAll(Name, Sort),
+    /// A universally quantified variable. We keep the name of the variable in the surface syntax
+    /// (if any) to print it back when reporting.
+    ForAll(Name, Sort, Option<Symbol>),
     Guard(Expr),
     Head(Expr, Tag),
     True,
@@ -127,8 +146,20 @@ impl RefineTree {
         RefineTree { root }
     }
 
+    /// Simplifies the tree before encoding it. Besides simplifying predicates, this removes
+    /// structure that doesn't affect the meaning of the constraint: nodes without obligations,
+    /// bindings for variables that are never mentioned, guards that are trivially true, and nested
+    /// conjunctions. Consecutive guards are merged into a single one. This breaks the invariant on
+    /// [`Node::nbindings`], so the tree cannot be extended afterwards.
     pub(crate) fn simplify(&mut self) {
-        self.root.borrow_mut().simplify();
+        self.root.simplify();
+    }
+
+    /// Counts the obligations (heads) in the tree classified by their tag
+    pub(crate) fn obligations(&self) -> ObligationCounts {
+        let mut counts = ObligationCounts::default();
+        self.root.borrow().count_obligations(&mut counts);
+        counts
     }
 
     pub(crate) fn into_fixpoint(
@@ -145,6 +176,11 @@ impl RefineTree {
     pub(crate) fn refine_ctxt_at_root(&mut self) -> RefineCtxt {
         RefineCtxt { ptr: NodePtr(Rc::clone(&self.root)), tree: self }
     }
+
+    /// Renders the tree as a graphviz graph (see [`dot`])
+    pub(crate) fn to_dot(&self, tcx: TyCtxt) -> String {
+        dot::render(tcx, &self.root)
+    }
 }
 
 impl<'a> RefineSubtree<'a> {
@@ -188,6 +224,34 @@ impl<'rcx> RefineCtxt<'rcx> {
         self.snapshot().scope().unwrap()
     }
 
+    pub(crate) fn mark(&self) -> Mark {
+        Mark { ptr: NodePtr::clone(&self.ptr), nchildren: self.ptr.borrow().children.len() }
+    }
+
+    /// Returns the surface names of the variables in scope that have one together with the
+    /// predicates assumed so far, outermost first. Assumed kvars are skipped because they are only
+    /// meaningful after the constraint is solved.
+    pub(crate) fn assumptions(&self) -> (Vec<(Name, Symbol)>, Vec<Expr>) {
+        let mut surface_names = vec![];
+        let mut preds = vec![];
+        for ptr in ParentsIter::new(NodePtr::clone(&self.ptr)) {
+            match &ptr.borrow().kind {
+                NodeKind::ForAll(name, _, Some(surface_name)) => {
+                    surface_names.push((*name, *surface_name));
+                }
+                NodeKind::Guard(pred) => {
+                    let pred = pred.simplify();
+                    if !pred.is_trivially_true() && !matches!(pred.kind(), ExprKind::KVar(_)) {
+                        preds.push(pred);
+                    }
+                }
+                _ => {}
+            }
+        }
+        preds.reverse();
+        (surface_names, preds)
+    }
+
     #[allow(dead_code)]
     #[must_use]
     #[allow(dead_code)]
@@ -199,11 +263,54 @@ impl<'rcx> RefineCtxt<'rcx> {
     /// Defines a fresh refinement variable with the given `sort`. It returns the freshly generated
     /// name for the variable.
     pub(crate) fn define_var(&mut self, sort: &Sort) -> Name {
+        self.define_var_with_surface_name(sort, None)
+    }
+
+    fn define_var_with_surface_name(&mut self, sort: &Sort, surface_name: Option<Symbol>) -> Name {
         let fresh = self.ptr.name_gen().fresh();
-        self.ptr = self.ptr.push_node(NodeKind::ForAll(fresh, sort.clone()));
+        self.ptr = self
+            .ptr
+            .push_node(NodeKind::ForAll(fresh, sort.clone(), surface_name));
         fresh
     }
 
+    /// Defines fresh variables to replace a bound refinement variable of the given `sort`. If the
+    /// variable was named in the surface syntax the fresh variables remember the name to be used
+    /// when printing. When the sort is destructed (see [`RefineCtxt::define_vars`]) each variable
+    /// is named after the field it stands for, e.g., `r.lo` for the field `lo` of a variable `r`.
+    pub(crate) fn define_bound_reft_var(&mut self, sort: &Sort, kind: BoundReftKind) -> Expr {
+        match kind {
+            BoundReftKind::Named(name) => self.define_vars_with_surface_name(sort, name),
+            BoundReftKind::Annon => self.define_vars(sort),
+        }
+    }
+
+    fn define_vars_with_surface_name(&mut self, sort: &Sort, surface_name: Symbol) -> Expr {
+        match sort {
+            Sort::Tuple(sorts) => {
+                let flds = sorts
+                    .iter()
+                    .enumerate()
+                    .map(|(i, sort)| {
+                        let name = Symbol::intern(&format!("{surface_name}.{i}"));
+                        self.define_vars_with_surface_name(sort, name)
+                    })
+                    .collect();
+                Expr::tuple(flds)
+            }
+            Sort::App(SortCtor::Adt(adt_sort_def), args) => {
+                let flds = iter::zip(adt_sort_def.field_names(), adt_sort_def.sorts(args).iter())
+                    .map(|(fld, sort)| {
+                        let name = Symbol::intern(&format!("{surface_name}.{fld}"));
+                        self.define_vars_with_surface_name(sort, name)
+                    })
+                    .collect();
+                Expr::adt(adt_sort_def.did(), flds)
+            }
+            _ => Expr::fvar(self.define_var_with_surface_name(sort, Some(surface_name))),
+        }
+    }
+
     /// Given a [`sort`] that may contain aggregate sorts ([tuple] or [adt]), it destructs the sort
     /// recursively, generating multiple fresh variables and returning an "eta-expanded" expression
     /// of fresh variables. This is in contrast to generating a single fresh variable of aggregate
@@ -231,9 +338,13 @@ impl<'rcx> RefineCtxt<'rcx> {
     }
 
     pub(crate) fn check_impl(&mut self, pred1: impl Into<Expr>, pred2: impl Into<Expr>, tag: Tag) {
+        let (pred1, pred2) = (pred1.into(), pred2.into());
+        if pred1 == pred2 || pred2.is_trivially_true() {
+            return;
+        }
         self.ptr
-            .push_node(NodeKind::Guard(pred1.into()))
-            .push_node(NodeKind::Head(pred2.into(), tag));
+            .push_node(NodeKind::Guard(pred1))
+            .push_node(NodeKind::Head(pred2, tag));
     }
 
     pub(crate) fn unpack(&mut self, ty: &Ty) -> Ty {
@@ -287,9 +398,9 @@ enum AssumeInvariants {
 pub(crate) struct Unpacker<'a, 'rcx> {
     rcx: &'a mut RefineCtxt<'rcx>,
     in_mut_ref: bool,
-    unpack_inside_mut_ref: bool,
     shallow: bool,
     unpack_exists: bool,
+    unpack_in_mut_ref: bool,
     assume_invariants: AssumeInvariants,
 }
 
@@ -298,9 +409,9 @@ impl<'a, 'rcx> Unpacker<'a, 'rcx> {
         Self {
             rcx,
             in_mut_ref: false,
-            unpack_inside_mut_ref: false,
             shallow: false,
             unpack_exists: true,
+            unpack_in_mut_ref: false,
             assume_invariants: AssumeInvariants::No,
         }
     }
@@ -310,11 +421,6 @@ impl<'a, 'rcx> Unpacker<'a, 'rcx> {
         self
     }
 
-    pub(crate) fn unpack_inside_mut_ref(mut self, unpack_inside_mut_ref: bool) -> Self {
-        self.unpack_inside_mut_ref = unpack_inside_mut_ref;
-        self
-    }
-
     pub(crate) fn shallow(mut self, shallow: bool) -> Self {
         self.shallow = shallow;
         self
@@ -325,6 +431,24 @@ impl<'a, 'rcx> Unpacker<'a, 'rcx> {
         self
     }
 
+    /// Whether to also unpack existentials under mutable references. The referent type becomes
+    /// more specific, so this is only done when explicitly requested (see [`GhostStatement::Unpack`]).
+    ///
+    /// [`GhostStatement::Unpack`]: crate::ghost_statements::GhostStatement::Unpack
+    pub(crate) fn unpack_in_mut_ref(mut self, unpack_in_mut_ref: bool) -> Self {
+        self.unpack_in_mut_ref = unpack_in_mut_ref;
+        self
+    }
+
+    /// Configures how deep to unpack according to `policy`
+    pub(crate) fn policy(self, policy: UnpackPolicy) -> Self {
+        match policy {
+            UnpackPolicy::Shallow => self.shallow(true),
+            UnpackPolicy::Default => self,
+            UnpackPolicy::Deep => self.unpack_in_mut_ref(true),
+        }
+    }
+
     pub(crate) fn unpack(mut self, ty: &Ty) -> Ty {
         ty.fold_with(&mut self)
     }
@@ -335,13 +459,14 @@ impl TypeFolder for Unpacker<'_, '_> {
         match ty.kind() {
             TyKind::Indexed(bty, idx) => Ty::indexed(bty.fold_with(self), idx.clone()),
             TyKind::Exists(bound_ty) if self.unpack_exists => {
-                // HACK(nilehmann) In general we shouldn't unpack through mutable references because
-                // that makes referent type too specific. We only have this as a workaround to infer
-                // parameters under mutable references and it should be removed once we implement
-                // opening of mutable references. See also `ConstrGen::check_fn_call`.
-                if !self.in_mut_ref || self.unpack_inside_mut_ref {
+                // We don't unpack through mutable references because that would make the referent
+                // type too specific. Mutable references are instead opened temporarily when passed
+                // to a function. See `ConstrGen::check_fn_call`.
+                if !self.in_mut_ref || self.unpack_in_mut_ref {
                     let bound_ty = bound_ty
-                        .replace_bound_refts_with(|sort, _, _| self.rcx.define_vars(sort))
+                        .replace_bound_refts_with(|sort, _, kind| {
+                            self.rcx.define_bound_reft_var(sort, kind)
+                        })
                         .fold_with(self);
                     if let AssumeInvariants::Yes { check_overflow } = self.assume_invariants {
                         self.rcx.assume_invariants(&bound_ty, check_overflow);
@@ -395,7 +520,7 @@ impl Snapshot {
         let bindings = parents
             .filter_map(|node| {
                 let node = node.borrow();
-                if let NodeKind::ForAll(_, sort) = &node.kind {
+                if let NodeKind::ForAll(_, sort, _) = &node.kind {
                     Some(sort.clone())
                 } else {
                     None
@@ -462,6 +587,104 @@ impl NodePtr {
     fn next_name_idx(&self) -> usize {
         self.borrow().nbindings + usize::from(self.borrow().is_forall())
     }
+
+    /// Simplifies the subtree rooted at this node and returns the free variables mentioned in it.
+    ///
+    /// A node that should be removed from its parent is turned into [`NodeKind::True`], and a node
+    /// whose children should be moved to its parent is turned into [`NodeKind::Conj`].
+    fn simplify(&self) -> FxHashSet<Name> {
+        let mut fvars = FxHashSet::default();
+        let children = std::mem::take(&mut self.borrow_mut().children);
+        for child in children {
+            fvars.extend(child.simplify());
+            match child.borrow().kind {
+                NodeKind::True => {}
+                NodeKind::Conj => {
+                    let grandchildren = std::mem::take(&mut child.borrow_mut().children);
+                    self.adopt(grandchildren);
+                }
+                _ => self.adopt([child.clone()]),
+            }
+        }
+
+        let mut guard = self.borrow_mut();
+        let node = &mut *guard;
+        match &node.kind {
+            NodeKind::Head(pred, tag) => {
+                let pred = pred.simplify();
+                if pred.is_trivially_true() {
+                    node.kind = NodeKind::True;
+                } else {
+                    fvars.extend(pred.fvars());
+                    node.kind = NodeKind::Head(pred, *tag);
+                }
+            }
+            NodeKind::True => {}
+            NodeKind::Guard(pred) => {
+                let pred = pred.simplify();
+                node.children
+                    .extract_if(|child| {
+                        matches!(&child.borrow().kind, NodeKind::Head(head, _) if *head == pred)
+                    })
+                    .for_each(drop);
+                if pred.is_trivially_false() {
+                    node.kind = NodeKind::True;
+                } else if pred.is_trivially_true() {
+                    node.kind = NodeKind::Conj;
+                } else {
+                    fvars.extend(pred.fvars());
+                    node.kind = NodeKind::Guard(pred);
+                }
+            }
+            NodeKind::ForAll(name, ..) => {
+                if !fvars.remove(name) {
+                    node.kind = NodeKind::Conj;
+                }
+            }
+            NodeKind::Comment(_) | NodeKind::Conj => {}
+        }
+        if !node.is_leaf() && node.children.is_empty() {
+            node.kind = NodeKind::True;
+        }
+        let is_guard = matches!(node.kind, NodeKind::Guard(_));
+        drop(guard);
+        if is_guard {
+            self.merge_guards();
+        }
+        fvars
+    }
+
+    /// Merges a guard with its only child if it's also a guard, i.e., `p => (q => c)` becomes
+    /// `p && q => c`.
+    fn merge_guards(&self) {
+        let child = {
+            let node = self.borrow();
+            match &node.children[..] {
+                [child] if matches!(child.borrow().kind, NodeKind::Guard(_)) => child.clone(),
+                _ => return,
+            }
+        };
+        let mut child = child.borrow_mut();
+        let NodeKind::Guard(inner) = &child.kind else { unreachable!() };
+        let mut node = self.borrow_mut();
+        if let NodeKind::Guard(outer) = &mut node.kind {
+            *outer = Expr::and([outer.clone(), inner.clone()]);
+        }
+        node.children = std::mem::take(&mut child.children);
+        drop(node);
+        drop(child);
+        for grandchild in &self.borrow().children {
+            grandchild.borrow_mut().parent = Some(NodePtr::downgrade(self));
+        }
+    }
+
+    /// Pushes `children` at the end of this node's children making this node their parent
+    fn adopt(&self, children: impl IntoIterator<Item = NodePtr>) {
+        for child in children {
+            child.borrow_mut().parent = Some(NodePtr::downgrade(self));
+            self.borrow_mut().children.push(child);
+        }
+    }
 }
 
 impl WeakNodePtr {
@@ -487,38 +710,12 @@ impl std::ops::Deref for NodePtr {
 }
 
 impl Node {
-    fn simplify(&mut self) {
-        for child in &self.children {
-            child.borrow_mut().simplify();
+    fn count_obligations(&self, counts: &mut ObligationCounts) {
+        if let NodeKind::Head(_, tag) = &self.kind {
+            counts.record(tag.reason);
         }
-
-        match &mut self.kind {
-            NodeKind::Head(pred, tag) => {
-                let pred = pred.simplify();
-                if pred.is_trivially_true() {
-                    self.kind = NodeKind::True;
-                } else {
-                    self.kind = NodeKind::Head(pred, *tag);
-                }
-            }
-            NodeKind::True => {}
-            NodeKind::Guard(pred) => {
-                *pred = pred.simplify();
-                self.children
-                    .extract_if(|child| {
-                        matches!(child.borrow().kind, NodeKind::True)
-                            || matches!(&child.borrow().kind, NodeKind::Head(head, _) if head == pred)
-                    })
-                    .for_each(drop);
-            }
-            NodeKind::Comment(_) | NodeKind::Conj | NodeKind::ForAll(..) => {
-                self.children
-                    .extract_if(|child| matches!(&child.borrow().kind, NodeKind::True))
-                    .for_each(drop);
-            }
-        }
-        if !self.is_leaf() && self.children.is_empty() {
-            self.kind = NodeKind::True;
+        for child in &self.children {
+            child.borrow().count_obligations(counts);
         }
     }
 
@@ -541,11 +738,11 @@ impl Node {
 
     fn to_fixpoint(&self, cx: &mut FixpointCtxt<Tag>) -> QueryResult<Option<fixpoint::Constraint>> {
         let cstr = match &self.kind {
-            NodeKind::Comment(_) | NodeKind::Conj | NodeKind::ForAll(_, Sort::Loc) => {
+            NodeKind::Comment(_) | NodeKind::Conj | NodeKind::ForAll(_, Sort::Loc, _) => {
                 children_to_fixpoint(cx, &self.children)?
             }
-            NodeKind::ForAll(name, sort) => {
-                cx.with_name_map(*name, |cx, fresh| -> QueryResult<_> {
+            NodeKind::ForAll(name, sort, surface_name) => {
+                cx.with_name_map(*name, *surface_name, |cx, fresh| -> QueryResult<_> {
                     let Some(children) = children_to_fixpoint(cx, &self.children)? else {
                         return Ok(None);
                     };
@@ -561,9 +758,10 @@ impl Node {
             }
             NodeKind::Guard(pred) => {
                 let (bindings, preds) = cx.pred_to_fixpoint(pred)?;
-                let preds = preds.into_iter().map(|(pred, _)| pred).collect_vec();
-                let pred = fixpoint::Pred::And(preds);
-                let Some(children) = children_to_fixpoint(cx, &self.children)? else {
+                let preds = preds.into_iter().map(|(fpred, _)| fpred).collect_vec();
+                let Some(children) =
+                    cx.with_facts(pred, |cx| children_to_fixpoint(cx, &self.children))?
+                else {
                     return Ok(None);
                 };
                 Some(stitch(
@@ -572,7 +770,7 @@ impl Node {
                         fixpoint::Bind {
                             name: fixpoint::Var::Underscore,
                             sort: fixpoint::Sort::Int,
-                            pred,
+                            pred: fixpoint::Pred::And(preds),
                         },
                         Box::new(children),
                     ),
@@ -582,8 +780,9 @@ impl Node {
                 let (bindings, preds) = cx.pred_to_fixpoint(pred)?;
                 let cstr = preds
                     .into_iter()
-                    .map(|(pred, span)| {
-                        fixpoint::Constraint::Pred(pred, Some(cx.tag_idx(tag.with_dst(span))))
+                    .map(|(fpred, span)| {
+                        let tag_idx = cx.tag_idx_with_explanation(tag.with_dst(span), pred);
+                        fixpoint::Constraint::Pred(fpred, Some(tag_idx))
                     })
                     .collect_vec();
                 Some(stitch(bindings, fixpoint::Constraint::Conj(cstr)))
@@ -659,7 +858,7 @@ impl TypeVisitable for NodePtr {
         match &node.kind {
             NodeKind::Conj | NodeKind::Comment(_) | NodeKind::True => {}
             NodeKind::Guard(pred) | NodeKind::Head(pred, _) => pred.visit_with(visitor)?,
-            NodeKind::ForAll(_, sort) => sort.visit_with(visitor)?,
+            NodeKind::ForAll(_, sort, _) => sort.visit_with(visitor)?,
         }
         for child in &node.children {
             child.visit_with(visitor)?;
@@ -679,11 +878,13 @@ mod pretty {
 
     use super::*;
 
-    fn bindings_chain(ptr: &NodePtr) -> (Vec<(Name, Sort)>, Vec<NodePtr>) {
-        fn go(ptr: &NodePtr, mut bindings: Vec<(Name, Sort)>) -> (Vec<(Name, Sort)>, Vec<NodePtr>) {
+    type Bindings = Vec<(Name, Sort, Option<Symbol>)>;
+
+    fn bindings_chain(ptr: &NodePtr) -> (Bindings, Vec<NodePtr>) {
+        fn go(ptr: &NodePtr, mut bindings: Bindings) -> (Bindings, Vec<NodePtr>) {
             let node = ptr.borrow();
-            if let NodeKind::ForAll(name, sort) = &node.kind {
-                bindings.push((*name, sort.clone()));
+            if let NodeKind::ForAll(name, sort, surface_name) = &node.kind {
+                bindings.push((*name, sort.clone(), *surface_name));
                 if let [child] = &node.children[..] {
                     go(child, bindings)
                 } else {
@@ -758,22 +959,28 @@ mod pretty {
                     let nodes = flatten_conjs(slice::from_ref(self));
                     w!("{:?}", join!("\n", nodes))
                 }
-                NodeKind::ForAll(name, sort) => {
+                NodeKind::ForAll(name, sort, surface_name) => {
                     let (bindings, children) = if cx.bindings_chain {
                         bindings_chain(self)
                     } else {
-                        (vec![(*name, sort.clone())], node.children.clone())
+                        (vec![(*name, sort.clone(), *surface_name)], node.children.clone())
                     };
 
-                    w!(
-                        "∀ {}.",
-                        ^bindings
-                            .into_iter()
-                            .format_with(", ", |(name, sort), f| {
-                                f(&format_args_cx!("{:?}: {:?}", ^name, sort))
-                            })
-                    )?;
-                    fmt_children(&children, cx, f)
+                    let surface_names = bindings
+                        .iter()
+                        .filter_map(|(name, _, surface_name)| Some((*name, (*surface_name)?)))
+                        .collect_vec();
+                    cx.with_fvar_names(surface_names, || {
+                        w!(
+                            "∀ {}.",
+                            ^bindings
+                                .iter()
+                                .format_with(", ", |(name, sort, _), f| {
+                                    f(&format_args_cx!("{:?}: {:?}", Expr::fvar(*name), sort))
+                                })
+                        )?;
+                        fmt_children(&children, cx, f)
+                    })
                 }
                 NodeKind::Guard(pred) => {
                     let (preds, children) = if cx.preds_chain {
@@ -826,31 +1033,43 @@ mod pretty {
         fn fmt(&self, cx: &PrettyCx, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             define_scoped!(cx, f);
             let parents = ParentsIter::new(NodePtr::clone(&self.ptr)).collect_vec();
-            write!(
-                f,
-                "{{{}}}",
-                parents
-                    .into_iter()
-                    .rev()
-                    .filter(|ptr| {
-                        let node = ptr.borrow();
-                        match &node.kind {
-                            NodeKind::ForAll(..) => true,
-                            NodeKind::Guard(e) => !e.simplify().is_trivially_true(),
-                            _ => false,
-                        }
-                    })
-                    .format_with(", ", |n, f| {
-                        let n = n.borrow();
-                        match &n.kind {
-                            NodeKind::ForAll(name, sort) => {
-                                f(&format_args_cx!("{:?}: {:?}", ^name, sort))
+            let surface_names = parents
+                .iter()
+                .filter_map(|ptr| {
+                    if let NodeKind::ForAll(name, _, Some(surface_name)) = &ptr.borrow().kind {
+                        Some((*name, *surface_name))
+                    } else {
+                        None
+                    }
+                })
+                .collect_vec();
+            cx.with_fvar_names(surface_names, || {
+                write!(
+                    f,
+                    "{{{}}}",
+                    parents
+                        .into_iter()
+                        .rev()
+                        .filter(|ptr| {
+                            let node = ptr.borrow();
+                            match &node.kind {
+                                NodeKind::ForAll(..) => true,
+                                NodeKind::Guard(e) => !e.simplify().is_trivially_true(),
+                                _ => false,
                             }
-                            NodeKind::Guard(pred) => f(&format_args_cx!("{:?}", pred)),
-                            _ => unreachable!(),
-                        }
-                    })
-            )
+                        })
+                        .format_with(", ", |n, f| {
+                            let n = n.borrow();
+                            match &n.kind {
+                                NodeKind::ForAll(name, sort, _) => {
+                                    f(&format_args_cx!("{:?}: {:?}", Expr::fvar(*name), sort))
+                                }
+                                NodeKind::Guard(pred) => f(&format_args_cx!("{:?}", pred)),
+                                _ => unreachable!(),
+                            }
+                        })
+                )
+            })
         }
     }
 
@@ -869,10 +1088,93 @@ mod pretty {
         }
     }
 
+    impl Pretty for Mark {
+        fn fmt(&self, cx: &PrettyCx, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            define_scoped!(cx, f);
+            let node = self.ptr.borrow();
+            w!("{:?}", join!("\n", &node.children[self.nchildren..]))
+        }
+    }
+
     impl_debug_with_default_cx!(
         RefineTree => "refine_tree",
         RefineSubtree<'_> => "refine_subtree",
         RefineCtxt<'_> => "refine_ctxt",
         Scope,
+        Mark => "refine_tree",
     );
 }
+
+/// Rendering of the tree in graphviz's dot format, enabled with `-Zflux-dump-refine-tree=dot`.
+///
+/// Each node of the tree becomes a node in the graph: binders are drawn as ellipses, guards as
+/// boxes and heads as notes labeled with the reason and source location of the obligation. A head
+/// is implied by the bindings and guards in the path from the root to it, so following the edges
+/// backwards from an unprovable obligation shows everything that was known when checking it.
+mod dot {
+    use std::fmt::Write;
+
+    use super::*;
+
+    pub(super) fn render(tcx: TyCtxt, root: &NodePtr) -> String {
+        let mut dot = Dot { tcx, text: String::new(), next_id: 0 };
+        writeln!(dot.text, "digraph refine_tree {{").unwrap();
+        writeln!(dot.text, "  node [fontname=\"monospace\"];").unwrap();
+        dot.node(root);
+        writeln!(dot.text, "}}").unwrap();
+        dot.text
+    }
+
+    struct Dot<'tcx> {
+        tcx: TyCtxt<'tcx>,
+        text: String,
+        next_id: usize,
+    }
+
+    impl Dot<'_> {
+        /// Writes the subtree rooted at `ptr` and returns the id of its node
+        fn node(&mut self, ptr: &NodePtr) -> usize {
+            let id = self.next_id;
+            self.next_id += 1;
+
+            let node = ptr.borrow();
+            let (label, attrs) = match &node.kind {
+                NodeKind::Conj => ("∧".to_string(), "shape=point"),
+                NodeKind::Comment(comment) => (format!("@ {comment}"), "shape=plaintext"),
+                NodeKind::ForAll(name, sort, surface_name) => {
+                    let name = Expr::fvar(*name);
+                    let label = match surface_name {
+                        Some(surface_name) => format!("∀ {name:?} ({surface_name}): {sort:?}"),
+                        None => format!("∀ {name:?}: {sort:?}"),
+                    };
+                    (label, "shape=ellipse")
+                }
+                NodeKind::Guard(pred) => (format!("{pred:?} =>"), "shape=box"),
+                NodeKind::Head(pred, tag) => {
+                    let span = self
+                        .tcx
+                        .sess
+                        .source_map()
+                        .span_to_diagnostic_string(tag.src_span);
+                    (format!("{pred:?}\n{:?} at {span}", tag.reason), "shape=note, color=red")
+                }
+                NodeKind::True => ("true".to_string(), "shape=plaintext"),
+            };
+            writeln!(self.text, "  n{id} [label=\"{}\", {attrs}];", escape(&label)).unwrap();
+
+            for child in &node.children {
+                let child_id = self.node(child);
+                writeln!(self.text, "  n{id} -> n{child_id};").unwrap();
+            }
+            id
+        }
+    }
+
+    /// Escapes a label so it can be written inside a double quoted string
+    fn escape(label: &str) -> String {
+        label
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    }
+}
@@ -0,0 +1,111 @@
+//! Signatures suggested for private functions without one (enabled with [`config::suggest_sigs`]).
+//!
+//! To help bootstrapping specs on existing code, we check the candidates of [`auto_spec`] (private
+//! functions without a signature whose arguments and return value are integers or booleans) against
+//! a *template* whose postcondition is a kvar over the returned value and the arguments, e.g., for
+//!
+//! ```ignore
+//! fn max(a: i32, b: i32) -> i32 {
+//!     if a > b { a } else { b }
+//! }
+//! ```
+//!
+//! we check the body against `fn(i32[@a], i32[@b]) -> {v. i32[v] | $k(v, a, b)}`. The kvar only
+//! appears in heads, so fixpoint infers the strongest conjunction of qualifiers that holds for the
+//! returned value, which we print back as an attribute the user can paste, e.g.,
+//! `#[flux::sig(fn(a: i32, b: i32) -> i32{v: v >= a && v >= b})]`. The template doesn't have
+//! preconditions, so the function is still checked exactly as with its default signature.
+//!
+//! [`auto_spec`]: crate::auto_spec
+use flux_config as config;
+use flux_middle::{
+    global_env::GlobalEnv,
+    intern::List,
+    queries::QueryResult,
+    rty::{
+        Binder, BoundReftKind, BoundVariableKind, EarlyBinder, Expr, ExprKind, FnOutput, FnSig,
+        InferMode, PolyFnSig, Ty, INNERMOST,
+    },
+    rustc::mir::RETURN_PLACE,
+};
+use itertools::Itertools;
+use rustc_hir::def_id::LocalDefId;
+use rustc_span::symbol::kw;
+
+use crate::{
+    auto_spec::{is_candidate, scalar_bty},
+    fixpoint_encoding::{KVarEncoding, KVarStore, SigTemplate},
+};
+
+/// Returns the template to check `def_id` with if we are suggesting a signature for it. The kvar of
+/// the postcondition is generated in `kvars` and registered so its solution can be reported.
+pub(crate) fn template(
+    genv: GlobalEnv,
+    def_id: LocalDefId,
+    kvars: &mut KVarStore,
+) -> QueryResult<Option<EarlyBinder<PolyFnSig>>> {
+    if !config::suggest_sigs() || !is_candidate(genv, def_id)? {
+        return Ok(None);
+    }
+    let tcx = genv.tcx();
+    let body = genv.mir(def_id)?;
+
+    let mut vars = vec![];
+    let mut sorts = vec![];
+    let mut inputs = vec![];
+    for (idx, local) in body.args_iter().enumerate() {
+        let Some((bty, sort)) = scalar_bty(&body.local_decls[local].ty) else { return Ok(None) };
+        let idx = Expr::late_bvar(INNERMOST, idx as u32, BoundReftKind::Annon);
+        vars.push(BoundVariableKind::Refine(sort.clone(), InferMode::EVar, BoundReftKind::Annon));
+        sorts.push(sort);
+        inputs.push(Ty::indexed(bty, idx));
+    }
+    let Some((ret_bty, ret_sort)) = scalar_bty(&body.local_decls[RETURN_PLACE].ty) else {
+        return Ok(None);
+    };
+
+    // The kvar is under the binder of the signature, the (empty) binder of the output, and the
+    // binder of the existential for the returned value.
+    let pred = kvars.fresh(
+        &[List::from_vec(sorts), List::empty(), List::singleton(ret_sort)],
+        [],
+        KVarEncoding::Conj,
+    );
+    let ExprKind::KVar(kvar) = pred.kind() else { unreachable!() };
+    let kvid = kvar.kvid;
+    let output = FnOutput::new(Ty::exists_with_constr(ret_bty, pred), vec![]);
+    let fn_sig = PolyFnSig::new(
+        FnSig::new(vec![], inputs, Binder::new(output, List::empty())),
+        List::from_vec(vars),
+    );
+
+    // Arguments bound to a pattern don't have a name, so we make one up
+    let arg_names = tcx
+        .fn_arg_names(def_id)
+        .iter()
+        .enumerate()
+        .map(|(i, ident)| {
+            if matches!(ident.name, kw::Empty | kw::Underscore) {
+                format!("arg{i}")
+            } else {
+                ident.to_string()
+            }
+        })
+        .collect_vec();
+    let nu = if arg_names.iter().any(|name| name == "v") { "ret" } else { "v" };
+    let rust_sig = tcx.fn_sig(def_id).instantiate_identity().skip_binder();
+    let inputs = arg_names
+        .iter()
+        .zip(rust_sig.inputs())
+        .map(|(name, ty)| format!("{name}: {ty}"))
+        .join(", ");
+
+    kvars.set_sig_template(SigTemplate {
+        kvid,
+        span: tcx.def_span(def_id),
+        arg_names: std::iter::once(nu.to_string()).chain(arg_names).collect(),
+        inputs,
+        output: rust_sig.output().to_string(),
+    });
+    Ok(Some(EarlyBinder(fn_sig)))
+}
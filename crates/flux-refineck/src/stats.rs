@@ -0,0 +1,240 @@
+//! Statistics about the obligations generated while checking a crate.
+//!
+//! Every head predicate in a [refinement tree] is an *obligation* that has to be proven by fixpoint.
+//! We classify obligations by the [reason] in their tag and keep per-category counts for every
+//! checked item. The counts are reported in a summary at the end of the crate (see
+//! [`config::summary`]) and saved as json in the log directory.
+//!
+//! Independently, we can record how long it takes to check each item and the size of its
+//! constraint (see [`config::stats`]) to find the items that dominate the time to check a crate.
+//!
+//! [refinement tree]: crate::refine_tree::RefineTree
+//! [reason]: crate::constraint_gen::ConstrReason
+use std::{collections::BTreeMap, fmt, fs, io, time::Duration};
+
+use flux_config as config;
+use serde_json::{Map, Value};
+
+use crate::constraint_gen::ConstrReason;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum ObligationCategory {
+    Call,
+    Assign,
+    Ret,
+    Fold,
+    Assert,
+    Div,
+    Rem,
+    Goto,
+    Overflow,
+    Other,
+}
+
+/// Number of obligations per [`ObligationCategory`]
+#[derive(Default, Clone, Debug)]
+pub struct ObligationCounts {
+    counts: BTreeMap<ObligationCategory, usize>,
+}
+
+/// Obligation counts for all the items checked in a crate
+#[derive(Default)]
+pub struct ObligationStats {
+    items: BTreeMap<String, ObligationCounts>,
+}
+
+impl ObligationCategory {
+    pub const ALL: [ObligationCategory; 10] = [
+        ObligationCategory::Call,
+        ObligationCategory::Assign,
+        ObligationCategory::Ret,
+        ObligationCategory::Fold,
+        ObligationCategory::Assert,
+        ObligationCategory::Div,
+        ObligationCategory::Rem,
+        ObligationCategory::Goto,
+        ObligationCategory::Overflow,
+        ObligationCategory::Other,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ObligationCategory::Call => "call",
+            ObligationCategory::Assign => "assign",
+            ObligationCategory::Ret => "ret",
+            ObligationCategory::Fold => "fold",
+            ObligationCategory::Assert => "assert",
+            ObligationCategory::Div => "div",
+            ObligationCategory::Rem => "rem",
+            ObligationCategory::Goto => "goto",
+            ObligationCategory::Overflow => "overflow",
+            ObligationCategory::Other => "other",
+        }
+    }
+}
+
+impl From<ConstrReason> for ObligationCategory {
+    fn from(reason: ConstrReason) -> Self {
+        match reason {
+            ConstrReason::Call => ObligationCategory::Call,
+            ConstrReason::Assign => ObligationCategory::Assign,
+            ConstrReason::Ret => ObligationCategory::Ret,
+            ConstrReason::Fold(_) => ObligationCategory::Fold,
+            ConstrReason::Assert(_) => ObligationCategory::Assert,
+            ConstrReason::Div => ObligationCategory::Div,
+            ConstrReason::Rem => ObligationCategory::Rem,
+            ConstrReason::Goto(_) => ObligationCategory::Goto,
+            ConstrReason::Overflow => ObligationCategory::Overflow,
+            ConstrReason::Other => ObligationCategory::Other,
+        }
+    }
+}
+
+impl ObligationCounts {
+    pub(crate) fn record(&mut self, reason: ConstrReason) {
+        *self.counts.entry(reason.into()).or_default() += 1;
+    }
+
+    pub fn get(&self, category: ObligationCategory) -> usize {
+        self.counts.get(&category).copied().unwrap_or_default()
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    fn add(&mut self, other: &ObligationCounts) {
+        for (category, count) in &other.counts {
+            *self.counts.entry(*category).or_default() += count;
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut map: Map<String, Value> = ObligationCategory::ALL
+            .into_iter()
+            .map(|category| (category.name().to_string(), Value::from(self.get(category))))
+            .collect();
+        map.insert("total".to_string(), Value::from(self.total()));
+        Value::Object(map)
+    }
+}
+
+impl ObligationStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn insert(&mut self, def_path: String, counts: ObligationCounts) {
+        self.items.entry(def_path).or_default().add(&counts);
+    }
+
+    pub fn totals(&self) -> ObligationCounts {
+        let mut totals = ObligationCounts::default();
+        for counts in self.items.values() {
+            totals.add(counts);
+        }
+        totals
+    }
+
+    pub fn to_json(&self) -> Value {
+        let items = self
+            .items
+            .iter()
+            .map(|(def_path, counts)| (def_path.clone(), counts.to_json()))
+            .collect();
+        let mut map = Map::new();
+        map.insert("totals".to_string(), self.totals().to_json());
+        map.insert("items".to_string(), Value::Object(items));
+        Value::Object(map)
+    }
+
+    /// Save the stats as json in `FLUX_LOG_DIR/obligations.json`
+    pub fn save(&self) -> io::Result<()> {
+        fs::create_dir_all(config::log_dir())?;
+        let file = fs::File::create(config::log_dir().join("obligations.json"))?;
+        serde_json::to_writer_pretty(file, &self.to_json())?;
+        Ok(())
+    }
+}
+
+/// Time spent checking an item and the size of its constraint
+#[derive(Default, Clone, Copy, Debug)]
+pub struct ItemStats {
+    /// Time spent generating the constraint, i.e., everything but solving it
+    pub check_time: Duration,
+    /// Time spent solving the constraint with fixpoint (or directly with the SMT solver)
+    pub solve_time: Duration,
+    pub kvars: usize,
+    pub constraints: usize,
+}
+
+/// [`ItemStats`] for all the items checked in a crate
+#[derive(Default)]
+pub struct CheckStats {
+    items: BTreeMap<String, ItemStats>,
+}
+
+impl ItemStats {
+    pub fn wall_time(&self) -> Duration {
+        self.check_time + self.solve_time
+    }
+
+    fn to_json(self, def_path: &str) -> Value {
+        let mut map = Map::new();
+        map.insert("item".to_string(), Value::from(def_path));
+        map.insert("wall_time_ms".to_string(), millis(self.wall_time()));
+        map.insert("check_time_ms".to_string(), millis(self.check_time));
+        map.insert("solve_time_ms".to_string(), millis(self.solve_time));
+        map.insert("kvars".to_string(), Value::from(self.kvars));
+        map.insert("constraints".to_string(), Value::from(self.constraints));
+        Value::Object(map)
+    }
+}
+
+fn millis(duration: Duration) -> Value {
+    Value::from(duration.as_secs_f64() * 1000.0)
+}
+
+impl CheckStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, def_path: String, stats: ItemStats) {
+        self.items.insert(def_path, stats);
+    }
+
+    /// The stats of every item, slowest first
+    pub fn to_json(&self) -> Value {
+        let mut items = self.items.iter().collect::<Vec<_>>();
+        items.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.wall_time()));
+        let items = items
+            .into_iter()
+            .map(|(def_path, stats)| stats.to_json(def_path))
+            .collect();
+        let total = self.items.values().map(ItemStats::wall_time).sum();
+        let mut map = Map::new();
+        map.insert("wall_time_ms".to_string(), millis(total));
+        map.insert("items".to_string(), Value::Array(items));
+        Value::Object(map)
+    }
+
+    /// Save the stats as json in `FLUX_LOG_DIR/stats.json`
+    pub fn save(&self) -> io::Result<()> {
+        fs::create_dir_all(config::log_dir())?;
+        let file = fs::File::create(config::log_dir().join("stats.json"))?;
+        serde_json::to_writer_pretty(file, &self.to_json())?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for ObligationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let totals = self.totals();
+        writeln!(f, "obligations: {} in {} items", totals.total(), self.items.len())?;
+        for category in ObligationCategory::ALL {
+            writeln!(f, "  {:<10}{:>8}", category.name(), totals.get(category))?;
+        }
+        Ok(())
+    }
+}
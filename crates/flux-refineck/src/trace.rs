@@ -0,0 +1,84 @@
+//! Step-by-step trace of the refinement checker, enabled with `-Zflux-trace=name` (see
+//! [`config::trace`]).
+//!
+//! For every function whose path contains the filter, we write a human readable log with the type
+//! environment before and after each statement and terminator together with the constraints they
+//! generated. The log is meant to follow the checker through a single problematic function, so in
+//! contrast to `dump_checker_trace` it's written in the same format used by `dump_constraint`,
+//! and the names of the variables in the environments match the ones in the constraints.
+use std::{
+    fmt,
+    io::{self, Write as _},
+};
+
+use flux_common::dbg;
+use flux_config as config;
+use flux_middle::rustc::mir::Location;
+use rustc_hir::def_id::LocalDefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+
+use crate::{
+    refine_tree::{Mark, RefineCtxt},
+    type_env::TypeEnv,
+};
+
+pub(crate) struct Tracer {
+    writer: Box<dyn io::Write>,
+}
+
+/// A statement or terminator being traced
+pub(crate) struct Step {
+    env_before: String,
+    env_after: String,
+    mark: Mark,
+}
+
+impl Tracer {
+    /// Returns a tracer for `def_id` if its path matches the filter in [`config::trace`]
+    pub(crate) fn new(tcx: TyCtxt, def_id: LocalDefId) -> Option<Tracer> {
+        let filter = config::trace()?;
+        let def_path = tcx.def_path_str(def_id);
+        if !def_path.contains(filter) {
+            return None;
+        }
+        let mut writer = dbg::writer_for_item(tcx, def_id.to_def_id(), "trace").unwrap();
+        writeln!(writer, "// {def_path}").unwrap();
+        Some(Tracer { writer: Box::new(writer) })
+    }
+
+    pub(crate) fn basic_block_start(&mut self, bb: impl fmt::Debug, env: &TypeEnv) {
+        writeln!(self.writer, "\n{bb:?}:\n  env: {env:?}").unwrap();
+    }
+
+    pub(crate) fn step(
+        &mut self,
+        tcx: TyCtxt,
+        step: Step,
+        location: Location,
+        what: impl fmt::Debug,
+        span: Span,
+    ) {
+        let span = tcx.sess.source_map().span_to_diagnostic_string(span);
+        writeln!(self.writer, "\n  {location:?}: {what:?} @ {span}").unwrap();
+        writeln!(self.writer, "    before: {}", step.env_before).unwrap();
+        writeln!(self.writer, "    after:  {}", step.env_after).unwrap();
+        let constraints = format!("{:?}", step.mark);
+        if !constraints.is_empty() {
+            writeln!(self.writer, "    constraints:").unwrap();
+            for line in constraints.lines() {
+                writeln!(self.writer, "      {line}").unwrap();
+            }
+        }
+    }
+}
+
+impl Step {
+    pub(crate) fn start(rcx: &RefineCtxt, env: &TypeEnv) -> Step {
+        Step { env_before: format!("{env:?}"), env_after: String::new(), mark: rcx.mark() }
+    }
+
+    pub(crate) fn end(self, env: &TypeEnv) -> Step {
+        Step { env_after: format!("{env:?}"), ..self }
+    }
+}
@@ -9,15 +9,18 @@ use flux_middle::{
     rty::{
         canonicalize::ShallowHoister,
         evars::EVarSol,
-        fold::{FallibleTypeFolder, TypeFoldable, TypeVisitable, TypeVisitor},
+        fold::{FallibleTypeFolder, TypeFoldable, TypeSuperVisitable, TypeVisitable, TypeVisitor},
         subst::RegionSubst,
-        BaseTy, Binder, BoundReftKind, Expr, ExprKind, GenericArg, HoleKind, Lambda, Mutability,
-        Path, PtrKind, Region, SortCtor, SubsetTy, Ty, TyKind, INNERMOST,
+        BaseTy, Binder, BoundReftKind, Constraint, Expr, ExprKind, FnOutput, FnSig, GenericArg,
+        HoleKind, KVid, Lambda, Mutability, Name, Path, PolyFnSig, PtrKind, Region, SortCtor,
+        SubsetTy, Ty, TyKind, INNERMOST,
     },
     rustc::mir::{BasicBlock, Local, LocalDecls, Place, PlaceElem},
 };
 use itertools::{izip, Itertools};
+use rustc_hash::{FxHashMap, FxHashSet};
 use rustc_middle::ty::TyCtxt;
+use rustc_span::Symbol;
 
 use self::place_ty::{LocKind, PlacesTree};
 use super::rty::{Loc, Sort};
@@ -25,6 +28,7 @@ use crate::{
     checker::errors::CheckerErrKind,
     constraint_gen::{ConstrGen, ConstrReason},
     fixpoint_encoding::{KVarEncoding, KVarStore},
+    liveness::Liveness,
     refine_tree::{RefineCtxt, Scope},
     rty::VariantIdx,
     CheckerConfig,
@@ -41,6 +45,8 @@ pub struct TypeEnv<'a> {
 pub struct BasicBlockEnvShape {
     scope: Scope,
     bindings: PlacesTree,
+    /// Variables in the scope that are passed to the kvars generated for the block
+    live_names: FxHashSet<Name>,
 }
 
 pub struct BasicBlockEnv {
@@ -74,8 +80,13 @@ impl TypeEnv<'_> {
             .insert(local.into(), Place::new(local, vec![]), LocKind::Local, Ty::uninit());
     }
 
-    pub(crate) fn into_infer(self, scope: Scope) -> Result<BasicBlockEnvShape> {
-        BasicBlockEnvShape::new(scope, self)
+    pub(crate) fn into_infer(
+        self,
+        scope: Scope,
+        liveness: &Liveness,
+        bb: BasicBlock,
+    ) -> Result<BasicBlockEnvShape> {
+        BasicBlockEnvShape::new(scope, self, liveness, bb)
     }
 
     pub(crate) fn lookup_place(
@@ -91,6 +102,17 @@ impl TypeEnv<'_> {
         self.bindings.get(path)
     }
 
+    /// The types of the locals in the environment, in no particular order
+    pub(crate) fn local_types(&self) -> impl Iterator<Item = (Local, &Ty)> {
+        self.bindings.iter().filter_map(|(loc, binding)| {
+            if let Loc::Local(local) = loc {
+                Some((*local, &binding.ty))
+            } else {
+                None
+            }
+        })
+    }
+
     pub fn update_path(&mut self, path: &Path, new_ty: Ty) {
         self.bindings.lookup(path).update(new_ty);
     }
@@ -142,7 +164,49 @@ impl TypeEnv<'_> {
         let TyKind::Ptr(PtrKind::Mut(re), path) = ptr_lookup.ty.kind() else {
             tracked_span_bug!("ptr_to_borrow called on non mutable pointer type")
         };
+        let (re, path) = (*re, path.clone());
+
+        // place: &mut new_ty
+        let ty = self.ptr_to_ref(rcx, gen, re, &path)?;
+        self.bindings.lookup(place).update(ty);
 
+        Ok(())
+    }
+
+    /// Moves the referent of a mutable reference `&mut T` reached through `place` into a fresh
+    /// location, so a pointer to it can be passed to a function expecting a strong reference. The
+    /// location must be removed with [`TypeEnv::close_weak_loc`] after the call.
+    pub(crate) fn open_weak_loc(&mut self, rcx: &mut RefineCtxt, place: Place, ty: &Ty) -> Path {
+        let loc = Loc::from(rcx.define_var(&Sort::Loc));
+        let ty = rcx.unpack(ty);
+        self.bindings.insert(loc, place, LocKind::Universal, ty);
+        Path::from(loc)
+    }
+
+    /// Removes a location created by [`TypeEnv::open_weak_loc`] checking that the type left in it
+    /// by the callee is a subtype of the type `T` of the original reference.
+    pub(crate) fn close_weak_loc(
+        &mut self,
+        rcx: &mut RefineCtxt,
+        gen: &mut ConstrGen,
+        path: &Path,
+        ty: &Ty,
+    ) -> Result {
+        let new_ty = self.bindings.lookup(path).fold(rcx, gen)?;
+        gen.subtyping(rcx, &new_ty, ty, ConstrReason::Call);
+        self.bindings.remove(&path.loc);
+        Ok(())
+    }
+
+    /// Converts a pointer `ptr(mut, path)` into a borrow `&mut T` blocking `path` with a type `T`
+    /// that needs to be inferred. See [`TypeEnv::ptr_to_borrow`].
+    pub(crate) fn ptr_to_ref(
+        &mut self,
+        rcx: &mut RefineCtxt,
+        gen: &mut ConstrGen,
+        re: Region,
+        path: &Path,
+    ) -> Result<Ty> {
         // path: old_ty
         let old_ty = self.bindings.lookup(path).fold(rcx, gen)?;
 
@@ -155,15 +219,12 @@ impl TypeEnv<'_> {
         });
         infcx.subtyping(rcx, &old_ty, &new_ty)?;
 
-        // path: new_ty, place: &mut new_ty
+        // path: new_ty
         self.bindings.lookup(path).block_with(new_ty.clone());
-        self.bindings
-            .lookup(place)
-            .update(Ty::mk_ref(*re, new_ty, Mutability::Mut));
 
         rcx.replace_evars(&infcx.solve().unwrap());
 
-        Ok(())
+        Ok(Ty::mk_ref(re, new_ty, Mutability::Mut))
     }
 
     pub(crate) fn assign(
@@ -200,9 +261,62 @@ impl TypeEnv<'_> {
         }
     }
 
-    pub(crate) fn unpack(&mut self, rcx: &mut RefineCtxt, check_overflow: bool) {
-        self.bindings
-            .fmap_mut(|ty| rcx.unpacker().assume_invariants(check_overflow).unpack(ty));
+    pub(crate) fn unpack(&mut self, rcx: &mut RefineCtxt, config: CheckerConfig) {
+        self.bindings.fmap_mut(|ty| {
+            rcx.unpacker()
+                .policy(config.unpack)
+                .assume_invariants(config.check_overflow)
+                .unpack(ty)
+        });
+    }
+
+    /// Packs the type of an owned `place` into a type with refinements that need to be inferred,
+    /// i.e., the current indices of `place` are forgotten. For example, given `x: i32[a]`, packing
+    /// `x` produces `x: i32{v: $k(v)}` together with the constraint `i32[a] <: i32{v: $k(v)}`.
+    pub(crate) fn pack(
+        &mut self,
+        rcx: &mut RefineCtxt,
+        gen: &mut ConstrGen,
+        place: &Place,
+    ) -> Result {
+        let result = self.bindings.lookup_unfolding(gen.genv, rcx, place)?;
+        if !result.is_strg {
+            return Err(CheckerErrKind::InvalidPack);
+        }
+        let old_ty = result.ty.clone();
+
+        let mut infcx = gen.infcx(rcx, ConstrReason::Other);
+        let new_ty = old_ty.with_holes().replace_holes(|sorts, kind| {
+            debug_assert_eq!(kind, HoleKind::Pred);
+            infcx.fresh_kvar(sorts, KVarEncoding::Conj)
+        });
+        infcx.subtyping(rcx, &old_ty, &new_ty)?;
+        rcx.replace_evars(&infcx.solve().unwrap());
+
+        self.bindings.lookup(place).update(new_ty);
+        Ok(())
+    }
+
+    /// Unpacks the type of an owned `place` including existentials under mutable references,
+    /// which are otherwise kept packed.
+    pub(crate) fn unpack_place(
+        &mut self,
+        genv: GlobalEnv,
+        rcx: &mut RefineCtxt,
+        place: &Place,
+        check_overflow: bool,
+    ) -> Result {
+        let result = self.bindings.lookup_unfolding(genv, rcx, place)?;
+        if !result.is_strg {
+            return Err(CheckerErrKind::InvalidPack);
+        }
+        let ty = rcx
+            .unpacker()
+            .assume_invariants(check_overflow)
+            .unpack_in_mut_ref(true)
+            .unpack(&result.ty);
+        result.update(ty);
+        Ok(())
     }
 
     pub(crate) fn unblock(&mut self, rcx: &mut RefineCtxt, place: &Place, check_overflow: bool) {
@@ -295,10 +409,55 @@ impl BasicBlockEnvShape {
         TypeEnv { bindings: self.bindings.clone(), local_decls }
     }
 
-    fn new(scope: Scope, env: TypeEnv) -> Result<BasicBlockEnvShape> {
+    fn new(
+        scope: Scope,
+        env: TypeEnv,
+        liveness: &Liveness,
+        bb: BasicBlock,
+    ) -> Result<BasicBlockEnvShape> {
         let mut bindings = env.bindings;
         bindings.fmap_mut(|ty| BasicBlockEnvShape::pack_ty(&scope, ty));
-        Ok(BasicBlockEnvShape { scope, bindings })
+        let live_names = BasicBlockEnvShape::live_names(&bindings, liveness, bb);
+        Ok(BasicBlockEnvShape { scope, bindings, live_names })
+    }
+
+    /// Collects the variables mentioned by the types of the locations that can be accessed after
+    /// the entry of `bb`, i.e., live locals, locations that are not locals, and locals pointed to
+    /// by them. The types are already packed, so all the variables are in scope. Joining only
+    /// replaces indices with holes, so the set computed for the first environment reaching the
+    /// block is also valid for the joined environment.
+    fn live_names(bindings: &PlacesTree, liveness: &Liveness, bb: BasicBlock) -> FxHashSet<Name> {
+        struct PtrLocs(Vec<Loc>);
+
+        impl TypeVisitor for PtrLocs {
+            fn visit_ty(&mut self, ty: &Ty) -> ControlFlow<Self::BreakTy> {
+                if let TyKind::Ptr(_, path) = ty.kind() {
+                    self.0.push(path.loc);
+                }
+                ty.super_visit_with(self)
+            }
+        }
+
+        let live_locals = liveness.live_at_entry(bb);
+        let bindings: FxHashMap<_, _> = bindings.iter().collect();
+        let mut worklist = bindings
+            .keys()
+            .filter(|loc| !matches!(loc, Loc::Local(local) if !live_locals.contains(*local)))
+            .map(|loc| **loc)
+            .collect_vec();
+        let mut visited = FxHashSet::default();
+        let mut names = liveness.params().clone();
+        while let Some(loc) = worklist.pop() {
+            if !visited.insert(loc) {
+                continue;
+            }
+            let Some(binding) = bindings.get(&loc) else { continue };
+            names.extend(binding.ty.fvars());
+            let mut ptr_locs = PtrLocs(vec![]);
+            binding.ty.visit_with(&mut ptr_locs);
+            worklist.extend(ptr_locs.0);
+        }
+        names
     }
 
     fn pack_ty(scope: &Scope, ty: &Ty) -> Ty {
@@ -363,7 +522,8 @@ impl BasicBlockEnvShape {
             | BaseTy::Char
             | BaseTy::Never
             | BaseTy::Closure(_, _)
-            | BaseTy::Coroutine(..) => bty.clone(),
+            | BaseTy::Coroutine(..)
+            | BaseTy::FnPtr(_) => bty.clone(),
         }
     }
 
@@ -413,13 +573,13 @@ impl BasicBlockEnvShape {
             (_, TyKind::Constr(_, ty2)) => self.join_ty(ty1, ty2),
             (TyKind::Indexed(bty1, idx1), TyKind::Indexed(bty2, idx2)) => {
                 let bty = self.join_bty(bty1, bty2);
-                let mut sorts = vec![];
-                let idx = self.join_idx(idx1, idx2, &bty.sort(), &mut sorts);
-                if sorts.is_empty() {
+                let mut vars = JoinVars::default();
+                let idx = self.join_idx(idx1, idx2, &bty.sort(), &mut vars);
+                if vars.sorts.is_empty() {
                     Ty::indexed(bty, idx)
                 } else {
                     let ty = Ty::constr(Expr::hole(HoleKind::Pred), Ty::indexed(bty, idx));
-                    Ty::exists(Binder::with_sorts(ty, &sorts))
+                    Ty::exists(Binder::with_sorts(ty, &vars.sorts))
                 }
             }
             (TyKind::Ptr(rk1, path1), TyKind::Ptr(rk2, path2)) => {
@@ -454,13 +614,13 @@ impl BasicBlockEnvShape {
         }
     }
 
-    fn join_idx(&self, e1: &Expr, e2: &Expr, sort: &Sort, bound_sorts: &mut Vec<Sort>) -> Expr {
+    fn join_idx(&self, e1: &Expr, e2: &Expr, sort: &Sort, vars: &mut JoinVars) -> Expr {
         match (e1.kind(), e2.kind(), sort) {
             (ExprKind::Aggregate(_, es1), ExprKind::Aggregate(_, es2), Sort::Tuple(sorts)) => {
                 debug_assert_eq3!(es1.len(), es2.len(), sorts.len());
                 Expr::tuple(
                     izip!(es1, es2, sorts)
-                        .map(|(e1, e2, sort)| self.join_idx(e1, e2, sort, bound_sorts))
+                        .map(|(e1, e2, sort)| self.join_idx(e1, e2, sort, vars))
                         .collect(),
                 )
             }
@@ -475,7 +635,7 @@ impl BasicBlockEnvShape {
                 Expr::adt(
                     sort_def.did(),
                     izip!(flds1, flds2, &sorts)
-                        .map(|(f1, f2, sort)| self.join_idx(f1, f2, sort, bound_sorts))
+                        .map(|(f1, f2, sort)| self.join_idx(f1, f2, sort, vars))
                         .collect(),
                 )
             }
@@ -493,8 +653,7 @@ impl BasicBlockEnvShape {
                         fsort.output().clone(),
                     ))
                 } else {
-                    bound_sorts.push(sort.clone());
-                    Expr::late_bvar(INNERMOST, (bound_sorts.len() - 1) as u32, BoundReftKind::Annon)
+                    vars.generalize(e1, e2, sort)
                 }
             }
         }
@@ -524,6 +683,9 @@ impl BasicBlockEnvShape {
                 debug_assert_eq!(len1, len2);
                 BaseTy::Array(self.join_ty(ty1, ty2), len1.clone())
             }
+            (BaseTy::FnPtr(sig1), BaseTy::FnPtr(sig2)) if sig1 != sig2 => {
+                BaseTy::FnPtr(generalize_fn_output(sig1))
+            }
             _ => {
                 debug_assert_eq!(bty1, bty2);
                 bty1.clone()
@@ -574,7 +736,13 @@ impl BasicBlockEnvShape {
 
         let outter_sorts = vars.to_sort_list();
 
-        let kvar = kvar_store.fresh(&[outter_sorts.clone()], &self.scope, KVarEncoding::Conj);
+        let scope = self
+            .scope
+            .iter()
+            .filter(|(name, _)| self.live_names.contains(name))
+            .collect_vec();
+
+        let kvar = kvar_store.fresh(&[outter_sorts.clone()], scope.clone(), KVarEncoding::Conj);
         constrs.push(kvar);
 
         // Replace remaning holes by fresh kvars
@@ -583,7 +751,7 @@ impl BasicBlockEnvShape {
             let sorts = std::iter::once(outter_sorts.clone())
                 .chain(sorts.iter().cloned())
                 .collect_vec();
-            kvar_store.fresh(&sorts, &self.scope, KVarEncoding::Conj)
+            kvar_store.fresh(&sorts, scope.clone(), KVarEncoding::Conj)
         };
         bindings.fmap_mut(|binding| binding.replace_holes(&mut kvar_gen));
 
@@ -593,6 +761,54 @@ impl BasicBlockEnvShape {
     }
 }
 
+/// Generalizes a function pointer whose signature differs on both sides of a join. The arguments and
+/// requires clauses are taken from the first signature, the signature on the other side is checked
+/// against them when jumping to the block. The output is replaced by one with holes, which are later
+/// inferred as kvars weak enough to be implied by both outputs.
+fn generalize_fn_output(sig: &PolyFnSig) -> PolyFnSig {
+    sig.clone().map(|sig| {
+        let output = sig.output().clone().map(|output| {
+            let ensures = output
+                .ensures
+                .iter()
+                .map(|constr| {
+                    match constr {
+                        Constraint::Type(path, ty, local) => {
+                            Constraint::Type(path.clone(), ty.with_holes(), *local)
+                        }
+                        Constraint::Pred(_) => Constraint::Pred(Expr::hole(HoleKind::Pred)),
+                    }
+                })
+                .collect_vec();
+            FnOutput::new(output.ret.with_holes(), ensures)
+        });
+        FnSig::new(sig.requires().clone(), sig.args(), output)
+    })
+}
+
+/// The variables introduced to generalize the parts of an index that differ on both sides of a
+/// join. A pair of expressions is always generalized to the same variable, so an equality between
+/// two parts of the index that holds on both sides is preserved, e.g., joining `(a, a)` with
+/// `(b, b)` produces `(v, v)` instead of `(v0, v1)`.
+#[derive(Default)]
+struct JoinVars {
+    sorts: Vec<Sort>,
+    vars: FxHashMap<(Expr, Expr), u32>,
+}
+
+impl JoinVars {
+    fn generalize(&mut self, e1: &Expr, e2: &Expr, sort: &Sort) -> Expr {
+        let idx = *self
+            .vars
+            .entry((e1.clone(), e2.clone()))
+            .or_insert_with(|| {
+                self.sorts.push(sort.clone());
+                (self.sorts.len() - 1) as u32
+            });
+        Expr::late_bvar(INNERMOST, idx, BoundReftKind::Annon)
+    }
+}
+
 impl TypeVisitable for BasicBlockEnvData {
     fn visit_with<V: TypeVisitor>(&self, _visitor: &mut V) -> ControlFlow<V::BreakTy> {
         unimplemented!()
@@ -619,7 +835,7 @@ impl BasicBlockEnv {
     ) -> TypeEnv<'a> {
         let data = self
             .data
-            .replace_bound_refts_with(|sort, _, _| rcx.define_vars(sort));
+            .replace_bound_refts_with(|sort, _, kind| rcx.define_bound_reft_var(sort, kind));
         for constr in &data.constrs {
             rcx.assume_pred(constr);
         }
@@ -629,6 +845,37 @@ impl BasicBlockEnv {
     pub(crate) fn scope(&self) -> &Scope {
         &self.scope
     }
+
+    /// Returns the kvar relating all the variables at the entry of the block (see
+    /// [`BasicBlockEnvShape::into_bb_env`]) together with a name for each of its arguments. An
+    /// argument that is the index of a local is named after the local, e.g., `i` for `i: i32[a0]`.
+    pub(crate) fn invariant_kvar(
+        &self,
+        local_names: &FxHashMap<Local, Symbol>,
+    ) -> Option<(KVid, Vec<String>)> {
+        let data = self.data.as_ref().skip_binder();
+        let ExprKind::KVar(kvar) = data.constrs.last()?.kind() else { return None };
+        let mut names = FxHashMap::default();
+        for (loc, binding) in data.bindings.iter() {
+            if let Loc::Local(local) = loc
+                && let Some(name) = local_names.get(local)
+                && let TyKind::Indexed(_, idx) = binding.ty.kind()
+            {
+                names.entry(idx.clone()).or_insert_with(|| name.to_string());
+            }
+        }
+        let args = kvar
+            .args
+            .iter()
+            .map(|arg| {
+                names
+                    .get(arg)
+                    .cloned()
+                    .unwrap_or_else(|| format!("{arg:?}"))
+            })
+            .collect();
+        Some((kvar.kvid, args))
+    }
 }
 
 mod pretty {
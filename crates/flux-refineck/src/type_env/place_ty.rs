@@ -265,7 +265,7 @@ impl PlacesTree {
         self.loc_to_place.insert(loc, place);
     }
 
-    fn remove(&mut self, loc: &Loc) -> Binding {
+    pub(crate) fn remove(&mut self, loc: &Loc) -> Binding {
         self.map.remove(loc).unwrap()
     }
 
@@ -815,7 +815,7 @@ fn downcast_enum(
         .variant_sig(adt.did(), variant_idx)?
         .expect("enums cannot be opaque")
         .instantiate(args, &[])
-        .replace_bound_refts_with(|sort, _, _| rcx.define_vars(sort));
+        .replace_bound_refts_with(|sort, _, kind| rcx.define_bound_reft_var(sort, kind));
 
     let (.., exprs1) = idx1.expect_adt();
     let (.., exprs2) = variant_def.idx.expect_adt();
@@ -832,6 +832,13 @@ fn downcast_enum(
     Ok(variant_def.fields.to_vec())
 }
 
+/// Folds `ty` back into a type without downcasts or pointers to boxes. Folding is done bottom-up:
+/// fields of a downcast are folded first (following boxes into the [`PlacesTree`]), so nested
+/// structures like `Option<Box<Node>>` are reassembled one level at a time. For strong places,
+/// reassembling a downcast checks the fields against the variant's signature, generating
+/// [`ConstrReason::Fold`] obligations.
+///
+/// [`ConstrReason::Fold`]: crate::constraint_gen::ConstrReason::Fold
 fn fold(
     bindings: &mut PlacesTree,
     rcx: &mut RefineCtxt,
@@ -869,8 +876,7 @@ fn fold(
                 let ty = if partially_moved {
                     Ty::uninit()
                 } else {
-                    gen.check_constructor(rcx, variant_sig, args, &fields)
-                        .unwrap_or_else(|err| tracked_span_bug!("{err:?}"))
+                    gen.check_constructor(rcx, variant_sig, *variant_idx, args, &fields)?
                 };
 
                 Ok(ty)
@@ -57,6 +57,7 @@ pub enum Token {
     Check,
     If,
     Else,
+    Match,
     PathSep,
     Qualifier,
     Sort,
@@ -65,6 +66,7 @@ pub enum Token {
     BitVec,
     As,
     Refine,
+    Pub,
 }
 
 pub(crate) struct Cursor<'t> {
@@ -161,8 +163,10 @@ impl<'t> Cursor<'t> {
             TokenKind::Ident(symb, _) if symb == kw::Type => Token::Type,
             TokenKind::Ident(symb, _) if symb == kw::If => Token::If,
             TokenKind::Ident(symb, _) if symb == kw::Else => Token::Else,
+            TokenKind::Ident(symb, _) if symb == kw::Match => Token::Match,
             TokenKind::Ident(symb, _) if symb == kw::Async => Token::Async,
             TokenKind::Ident(symb, _) if symb == kw::As => Token::As,
+            TokenKind::Ident(symb, _) if symb == kw::Pub => Token::Pub,
             TokenKind::Ident(symb, _) if symb == kw::Underscore => Token::Underscore,
             TokenKind::Ident(symb, _) => Token::Ident(symb),
             TokenKind::BinOp(BinOpToken::Or) => Token::Caret,
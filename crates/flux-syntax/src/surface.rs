@@ -41,6 +41,8 @@ pub struct Qualifier {
     pub expr: Expr,
     pub span: Span,
     pub global: bool,
+    /// Whether the qualifier is meant to be used by other crates
+    pub public: bool,
 }
 
 /// A global function definition. It can be either an uninterpreted function or a *syntactic abstraction*,
@@ -53,6 +55,8 @@ pub struct SpecFunc {
     pub output: Sort,
     /// Body of the function. If not present this definition corresponds to an uninterpreted function.
     pub body: Option<Expr>,
+    /// Whether the function is meant to be used by other crates
+    pub public: bool,
 }
 
 #[derive(Debug)]
@@ -114,6 +118,9 @@ pub struct EnumDef {
     pub node_id: NodeId,
     /// Whether the enum is an extern spec for some [DefId]
     pub extern_id: Option<DefId>,
+    /// Whether the enum is marked with `#[flux::reflect]`, i.e., its variants can be used in
+    /// refinements and each variant is indexed by its position in the enum.
+    pub reflected: bool,
 }
 
 impl EnumDef {
@@ -226,11 +233,33 @@ pub struct TraitAssocReft {
 pub struct FnSpec {
     pub fn_sig: Option<FnSig>,
     pub trusted: bool,
+    /// Whether calls to this function should be checked by inlining its body, i.e.,
+    /// `#[flux::inline]`
+    pub inline: bool,
+    /// Whether the function is expected to fail verification, i.e., `#[flux::should_fail]`
+    pub should_fail: bool,
     pub qual_names: Option<QualNames>,
     /// Whether this function is an extern spec for some [DefId]
     pub extern_id: Option<DefId>,
 }
 
+/// A `#[flux::assert(..)]` or `#[flux::assume(..)]` attribute on a statement
+#[derive(Debug)]
+pub struct StmtAnnot {
+    pub kind: StmtAnnotKind,
+    pub pred: Expr,
+    /// The span of the attribute
+    pub span: Span,
+    /// The span of the annotated statement
+    pub stmt_span: Span,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum StmtAnnotKind {
+    Assert,
+    Assume,
+}
+
 #[derive(Debug)]
 pub struct FnSig {
     pub asyncness: Async,
@@ -340,11 +369,21 @@ pub enum TyKind {
     Constr(Expr, Box<Ty>),
     Tuple(Vec<Ty>),
     Array(Box<Ty>, ArrayLen),
+    /// A function pointer type `fn(T1, ..., Tn) -> T`
+    BareFn(Box<BareFnTy>),
     /// The `NodeId` is used to resolve the type to a corresponding `OpaqueTy`
     ImplTrait(NodeId, GenericBounds),
     Hole,
 }
 
+/// The signature of a function pointer type. Only the argument and return types can be refined,
+/// i.e., there are no refinement parameters, `requires` or `ensures` clauses.
+#[derive(Debug)]
+pub struct BareFnTy {
+    pub inputs: Vec<Ty>,
+    pub output: FnRetTy,
+}
+
 impl Ty {
     pub fn is_refined(&self) -> bool {
         struct IsRefinedVisitor {
@@ -452,6 +491,16 @@ pub enum ExprKind {
     App(Ident, Vec<Expr>),
     Alias(AliasReft, Vec<Expr>),
     IfThenElse(Box<[Expr; 3]>),
+    /// A `match` over the variants of a reflected enum. It is desugared to a chain of
+    /// if-then-else.
+    Match(Box<Expr>, Vec<MatchArm>),
+}
+
+#[derive(Debug)]
+pub struct MatchArm {
+    pub pat: PathExpr,
+    pub body: Expr,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
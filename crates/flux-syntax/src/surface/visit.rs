@@ -439,6 +439,10 @@ pub fn walk_ty<V: Visitor>(vis: &mut V, ty: &Ty) {
             vis.visit_array_len(len);
             vis.visit_ty(ty);
         }
+        TyKind::BareFn(bare_fn) => {
+            walk_list!(vis, visit_ty, &bare_fn.inputs);
+            vis.visit_fn_ret_ty(&bare_fn.output);
+        }
         TyKind::ImplTrait(_node_id, trait_ref) => {
             walk_list!(vis, visit_trait_ref, trait_ref);
         }
@@ -501,6 +505,13 @@ pub fn walk_expr<V: Visitor>(vis: &mut V, expr: &Expr) {
         ExprKind::IfThenElse(box exprs) => {
             walk_list!(vis, visit_expr, exprs);
         }
+        ExprKind::Match(scrutinee, arms) => {
+            vis.visit_expr(scrutinee);
+            for arm in arms {
+                vis.vist_path_expr(&arm.pat);
+                vis.visit_expr(&arm.body);
+            }
+        }
     }
 }
 
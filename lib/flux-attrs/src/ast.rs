@@ -663,7 +663,7 @@ impl Parse for Items {
     }
 }
 
-const FLUX_ATTRS: &[&str] = &["opaque", "invariant", "trusted", "generics", "assoc"];
+const FLUX_ATTRS: &[&str] = &["opaque", "reflect", "invariant", "trusted", "generics", "assoc"];
 
 impl Parse for Item {
     fn parse(input: ParseStream) -> Result<Self> {
@@ -1433,7 +1433,7 @@ impl Parse for Signature {
     }
 }
 
-fn parse_requires(input: ParseStream) -> Result<Option<Requires>> {
+pub(crate) fn parse_requires(input: ParseStream) -> Result<Option<Requires>> {
     if !input.peek(kw::requires) {
         return Ok(None);
     }
@@ -1451,7 +1451,7 @@ fn parse_requires(input: ParseStream) -> Result<Option<Requires>> {
     Ok(Some(Requires { requires_token, constraint }))
 }
 
-fn parse_ensures(input: ParseStream) -> Result<Option<Ensures>> {
+pub(crate) fn parse_ensures(input: ParseStream) -> Result<Option<Ensures>> {
     if input.peek(kw::ensures) {
         Ok(Some(Ensures {
             ensures_token: input.parse()?,
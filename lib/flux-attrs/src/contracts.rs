@@ -0,0 +1,446 @@
+//! Runtime checks generated from signatures, enabled with the `contracts` feature of `flux-rs`.
+//!
+//! Without flux, `#[flux_rs::sig]` is normally erased. With the feature enabled, the parts of the
+//! signature that can be evaluated at runtime are turned into `debug_assert!`s, so the same specs
+//! double as contracts when testing code that isn't verified, e.g.,
+//!
+//! ```ignore
+//! #[flux_rs::sig(fn(x: i32{x > 0}) -> i32{v: v > x})]
+//! fn inc(x: i32) -> i32 {
+//!     x + 1
+//! }
+//! ```
+//!
+//! asserts that `x > 0` before running the body and that the returned value is greater than `x`
+//! after. Only refinements of integers and booleans can be checked, because their index is the
+//! value itself while the index of any other type is only known to flux. A predicate that mentions
+//! anything else (e.g., the index of a vector, a field or a function) is skipped, and so are
+//! implications, which aren't Rust expressions. Integers are compared as `i128` so they can be
+//! mixed as in flux.
+use std::collections::HashSet;
+
+use proc_macro2::{Spacing, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    Ident, Result, Token,
+};
+
+use crate::ast::{
+    parse_ensures, parse_requires, BaseType, Constraint, Expr, FnArg, Pat, PathArguments, Type,
+};
+
+/// Adds runtime checks for the signature `attr` to the function `item`. The function is returned
+/// unchanged if the signature can't be parsed or doesn't match it, in which case flux reports the
+/// error when checking the crate.
+pub fn contracts(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let (Ok(spec), Ok(item_fn)) =
+        (syn::parse2::<FnSpec>(attr), syn::parse2::<syn::ItemFn>(item.clone()))
+    else {
+        return item;
+    };
+    let sig = &item_fn.sig;
+    // The body is wrapped in a closure to check the postcondition, which isn't possible in a const
+    // or async function
+    if sig.constness.is_some() || sig.asyncness.is_some() || spec.inputs.len() != sig.inputs.len() {
+        return item;
+    }
+
+    let mut contract = Contract::default();
+    for (i, (input, param)) in spec.inputs.iter().zip(&sig.inputs).enumerate() {
+        let syn::FnArg::Typed(param) = param else { continue };
+        let syn::Pat::Ident(pat) = &*param.pat else { continue };
+        let Some(ty) = &input.ty else { continue };
+        let value = format_ident!("__flux_arg{i}");
+        if let Some(scalar) = contract.refinements(ty, value.to_token_stream(), false) {
+            if let Some(name) = &input.name {
+                let value = value.to_token_stream();
+                contract.params.push(Binding { name: name.clone(), value });
+            }
+            contract.captures.push((value, pat.ident.clone(), scalar));
+        }
+        if let Some(pred) = &input.pred {
+            contract.check(pred.clone(), vec![], false);
+        }
+    }
+    if let Some(requires) = spec.requires {
+        contract.check(requires, vec![], false);
+    }
+    // The return value is only checked for scalars, otherwise the type of the closure wrapping the
+    // body may not be expressible, e.g., if it mentions an elided lifetime.
+    let ret_ty = match sig.output.clone() {
+        syn::ReturnType::Default => Some(quote!(())),
+        syn::ReturnType::Type(_, ty) => {
+            let value = match rust_scalar(&ty) {
+                Some(Scalar::Int) => quote!((__flux_ret as i128)),
+                Some(Scalar::Bool) => quote!(__flux_ret),
+                None => return contract.expand(item_fn, None).unwrap_or(item),
+            };
+            if let Some(output) = &spec.output {
+                contract.refinements(output, value, true);
+            }
+            Some(ty.to_token_stream())
+        }
+    };
+    if ret_ty.is_some() {
+        for constraint in spec.ensures {
+            if let Constraint::Expr(expr) = constraint {
+                contract.check(expr, vec![], true);
+            }
+        }
+    }
+    contract.expand(item_fn, ret_ty).unwrap_or(item)
+}
+
+/// A signature as written in `#[flux_rs::sig]`, keeping only the parts that can be checked
+struct FnSpec {
+    inputs: Vec<Input>,
+    output: Option<Type>,
+    requires: Option<Expr>,
+    ensures: Vec<Constraint>,
+}
+
+/// An argument of a signature, e.g., `x: i32{x > 0}` or just `i32[@n]`
+struct Input {
+    name: Option<Ident>,
+    /// `None` for a strong reference, e.g., `x: &strg i32`
+    ty: Option<Type>,
+    pred: Option<Expr>,
+}
+
+#[derive(Clone, Copy)]
+enum Scalar {
+    Int,
+    Bool,
+}
+
+/// A refinement variable and the expression computing its value at runtime
+struct Binding {
+    name: Ident,
+    value: TokenStream,
+}
+
+struct Check {
+    pred: Expr,
+    /// Variables bound only for this predicate, e.g., `v` in `i32{v: v > 0}`
+    locals: Vec<Binding>,
+    post: bool,
+}
+
+#[derive(Default)]
+struct Contract {
+    /// Variables bound by the arguments, e.g., `x` in `x: i32` or `n` in `i32[@n]`
+    params: Vec<Binding>,
+    checks: Vec<Check>,
+    /// Arguments whose value is saved before running the body, with the name of the parameter
+    captures: Vec<(Ident, Ident, Scalar)>,
+}
+
+impl Contract {
+    /// Collects the variables bound by a type and the predicates it asserts about `value`. Returns
+    /// the kind of scalar if the type is an integer or a boolean.
+    fn refinements(&mut self, ty: &Type, value: TokenStream, post: bool) -> Option<Scalar> {
+        match ty {
+            Type::Base(bty) => scalar(bty),
+            Type::Indexed(indexed) => {
+                let scalar = scalar(&indexed.bty)?;
+                match binder(&indexed.expr) {
+                    Some(name) => {
+                        if !post {
+                            self.params.push(Binding { name, value });
+                        }
+                    }
+                    None => {
+                        let name = format_ident!("__flux_value");
+                        let expr = &indexed.expr;
+                        self.check(quote!(#name == (#expr)), vec![Binding { name, value }], post);
+                    }
+                }
+                Some(scalar)
+            }
+            Type::Exists(exists) => {
+                let scalar = scalar(&exists.bty)?;
+                let name = exists.ident.clone();
+                self.check(exists.expr.clone(), vec![Binding { name, value }], post);
+                Some(scalar)
+            }
+            Type::Constraint(constraint) => {
+                let scalar = self.refinements(&constraint.ty, value, post);
+                self.check(constraint.pred.clone(), vec![], post);
+                scalar
+            }
+            Type::GeneralExists(exists) => {
+                let Type::Indexed(indexed) = &*exists.ty else { return None };
+                let scalar = scalar(&indexed.bty)?;
+                let name = syn::parse2::<Ident>(indexed.expr.clone()).ok()?;
+                if let Some(pred) = &exists.pred {
+                    self.check(pred.clone(), vec![Binding { name, value }], post);
+                }
+                Some(scalar)
+            }
+            _ => None,
+        }
+    }
+
+    fn check(&mut self, pred: Expr, locals: Vec<Binding>, post: bool) {
+        self.checks.push(Check { pred, locals, post });
+    }
+
+    /// Returns `None` if none of the predicates can be checked
+    fn expand(&self, item_fn: syn::ItemFn, ret_ty: Option<TokenStream>) -> Option<TokenStream> {
+        let fn_name = &item_fn.sig.ident;
+        let mut used = HashSet::new();
+        let mut pre = vec![];
+        let mut post = vec![];
+        for check in &self.checks {
+            let Some(tokens) = self.expand_check(check, &fn_name.to_string(), &mut used) else {
+                continue;
+            };
+            if check.post {
+                post.push(tokens);
+            } else {
+                pre.push(tokens);
+            }
+        }
+        if pre.is_empty() && post.is_empty() {
+            return None;
+        }
+
+        let captures = self
+            .captures
+            .iter()
+            .filter(|(value, ..)| used.contains(&value.to_string()))
+            .map(|(value, param, scalar)| {
+                match scalar {
+                    Scalar::Int => quote!(let #value = #param as i128;),
+                    Scalar::Bool => quote!(let #value: bool = #param;),
+                }
+            });
+        let syn::ItemFn { attrs, vis, sig, block } = item_fn;
+        let body = match ret_ty {
+            Some(ret_ty) if !post.is_empty() => {
+                quote! {
+                    #(#captures)*
+                    #(#pre)*
+                    #[allow(clippy::redundant_closure_call)]
+                    let __flux_ret: #ret_ty = (|| -> #ret_ty #block)();
+                    #(#post)*
+                    __flux_ret
+                }
+            }
+            _ => {
+                quote! {
+                    #(#captures)*
+                    #(#pre)*
+                    #block
+                }
+            }
+        };
+        Some(quote! {
+            #(#attrs)*
+            #vis #sig {
+                #body
+            }
+        })
+    }
+
+    /// A block asserting the predicate of `check`, or `None` if it mentions a variable whose value
+    /// isn't known at runtime
+    fn expand_check(
+        &self,
+        check: &Check,
+        fn_name: &str,
+        used: &mut HashSet<String>,
+    ) -> Option<TokenStream> {
+        if has_implication(check.pred.clone()) {
+            return None;
+        }
+        let mut idents = vec![];
+        collect_idents(check.pred.clone(), &mut idents);
+        let mut bindings: Vec<&Binding> = vec![];
+        for ident in idents {
+            if ["true", "false", "if", "else"].contains(&&*ident.to_string())
+                || bindings.iter().any(|binding| binding.name == ident)
+            {
+                continue;
+            }
+            let binding = check
+                .locals
+                .iter()
+                .rev()
+                .chain(self.params.iter().rev())
+                .find(|binding| binding.name == ident && binding.name != "self")?;
+            bindings.push(binding);
+        }
+        for binding in &bindings {
+            used.insert(binding.value.to_string());
+        }
+
+        let pred = &check.pred;
+        let kind = if check.post { "postcondition" } else { "precondition" };
+        let msg = format!("{kind} of `{fn_name}` does not hold: `{pred}`");
+        let names = bindings.iter().map(|binding| &binding.name);
+        let values = bindings.iter().map(|binding| &binding.value);
+        Some(quote! {
+            {
+                #(let #names = #values;)*
+                ::core::debug_assert!(#pred, "{}", #msg);
+            }
+        })
+    }
+}
+
+fn scalar(bty: &BaseType) -> Option<Scalar> {
+    let BaseType::Path(path) = bty else { return None };
+    if path.segments.len() != 1 {
+        return None;
+    }
+    let segment = &path.segments[0];
+    if !matches!(segment.arguments, PathArguments::None) {
+        return None;
+    }
+    scalar_named(&segment.ident)
+}
+
+fn rust_scalar(ty: &syn::Type) -> Option<Scalar> {
+    let syn::Type::Path(path) = ty else { return None };
+    scalar_named(path.path.get_ident()?)
+}
+
+fn scalar_named(ident: &Ident) -> Option<Scalar> {
+    match &*ident.to_string() {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => Some(Scalar::Int),
+        "bool" => Some(Scalar::Bool),
+        _ => None,
+    }
+}
+
+/// The name bound by an index of the form `@n`
+fn binder(expr: &Expr) -> Option<Ident> {
+    let mut tokens = expr.clone().into_iter();
+    let (Some(TokenTree::Punct(punct)), Some(TokenTree::Ident(ident)), None) =
+        (tokens.next(), tokens.next(), tokens.next())
+    else {
+        return None;
+    };
+    (punct.as_char() == '@').then_some(ident)
+}
+
+fn collect_idents(tokens: TokenStream, idents: &mut Vec<Ident>) {
+    for tt in tokens {
+        match tt {
+            TokenTree::Ident(ident) => idents.push(ident),
+            TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+    }
+}
+
+/// Whether the expression contains `=>` (or `<=>`)
+fn has_implication(tokens: TokenStream) -> bool {
+    let mut prev_eq = false;
+    for tt in tokens {
+        match tt {
+            TokenTree::Punct(punct) => {
+                if prev_eq && punct.as_char() == '>' {
+                    return true;
+                }
+                prev_eq = punct.as_char() == '=' && punct.spacing() == Spacing::Joint;
+            }
+            TokenTree::Group(group) => {
+                if has_implication(group.stream()) {
+                    return true;
+                }
+                prev_eq = false;
+            }
+            _ => prev_eq = false,
+        }
+    }
+    false
+}
+
+impl Parse for FnSpec {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // Generics and refinement parameters, e.g., `for<n: int>` or `fn<T>`, aren't needed
+        if input.peek(Token![for]) {
+            input.parse::<Token![for]>()?;
+            skip_angle_brackets(input)?;
+        }
+        input.parse::<Token![fn]>()?;
+        if input.peek(Token![<]) {
+            skip_angle_brackets(input)?;
+        }
+        let content;
+        parenthesized!(content in input);
+        let inputs = content
+            .parse_terminated(Input::parse, Token![,])?
+            .into_iter()
+            .collect();
+        let output = if input.peek(Token![->]) {
+            input.parse::<Token![->]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let requires = parse_requires(input)?.map(|requires| requires.constraint);
+        let ensures = parse_ensures(input)?
+            .map(|ensures| ensures.constraints.into_iter().collect())
+            .unwrap_or_default();
+        // Ignore the rest, e.g., a where clause
+        input.parse::<TokenStream>()?;
+        Ok(FnSpec { inputs, output, requires, ensures })
+    }
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let named = input.peek(Token![mut])
+            || (input.peek(Ident) || input.peek(Token![self]) || input.peek(Token![_]))
+                && input.peek2(Token![:])
+                && !input.peek2(Token![::]);
+        if !named {
+            return Ok(Input { name: None, ty: Some(input.parse()?), pred: None });
+        }
+        match input.parse::<FnArg>()? {
+            FnArg::StrgRef(strg_ref) => {
+                Ok(Input { name: pat_name(strg_ref.pat), ty: None, pred: None })
+            }
+            FnArg::Typed(pat_type) => {
+                Ok(Input {
+                    name: pat_name(pat_type.pat),
+                    ty: Some(pat_type.ty),
+                    pred: pat_type.pred.map(|pred| pred.pred),
+                })
+            }
+        }
+    }
+}
+
+fn pat_name(pat: Pat) -> Option<Ident> {
+    match pat {
+        Pat::Ident(pat) => Some(pat.ident),
+        Pat::Wild(_) => None,
+    }
+}
+
+/// Skips a list in angle brackets, e.g., `<n: int, f: int -> int>`
+fn skip_angle_brackets(input: ParseStream) -> Result<()> {
+    input.parse::<Token![<]>()?;
+    let mut depth = 1;
+    while depth > 0 {
+        if input.peek(Token![->]) {
+            input.parse::<Token![->]>()?;
+            continue;
+        }
+        if let TokenTree::Punct(punct) = input.parse::<TokenTree>()? {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,408 @@
+//! Printing of specs for documentation (see [`crate::spec_doc`]).
+//!
+//! Specs are parsed with the [`ast`] and printed back the way a person would write them, e.g.,
+//! `fn(x: i32{x > 0}) -> i32[x + 1]`. Printing the token stream directly would instead put spaces
+//! around every token, e.g., `fn (x : i32 { x > 0 }) -> i32 [x + 1]`. Refinement expressions are
+//! kept as tokens by the [`ast`], so they are printed with [`tokens_to_string`]. A spec that
+//! doesn't parse is printed with [`tokens_to_string`] as a whole, flux will report the error when
+//! checking the crate.
+//!
+//! [`ast`]: crate::ast
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use syn::{
+    parenthesized,
+    parse::{discouraged::Speculative, Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    Result, Token,
+};
+
+use crate::ast::{
+    parse_ensures, parse_requires, BaseSort, BaseType, Constraint, Ensures, FnArg, FuncSortInput,
+    GenericArgument, Pat, Path, PathArguments, RefinedByParam, Requires, ReturnType, Sort,
+    SortArguments, Type,
+};
+
+/// Prints the spec in a `sig` attribute, e.g., `fn(x: i32{x > 0}) -> i32`
+pub(crate) fn sig_to_string(spec: &TokenStream) -> String {
+    match FnSpec::parse.parse2(spec.clone()) {
+        Ok(fn_spec) => fn_spec_to_string(&fn_spec),
+        Err(_) => tokens_to_string(spec),
+    }
+}
+
+/// Prints the spec in a `refined_by` attribute, e.g., `n: int, b: bool`
+pub(crate) fn refined_by_to_string(spec: &TokenStream) -> String {
+    let parser = Punctuated::<RefinedByParam, Token![,]>::parse_terminated;
+    match parser.parse2(spec.clone()) {
+        Ok(params) => {
+            params
+                .iter()
+                .map(|param| format!("{}: {}", param.ident, sort_to_string(&param.sort)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+        Err(_) => tokens_to_string(spec),
+    }
+}
+
+/// The signature in a `sig` attribute. Unlike [`crate::ast::Signature`], the function doesn't have
+/// a name and arguments may be written without one.
+struct FnSpec {
+    /// The tokens between the angle brackets of the generics, printed as they are written
+    generics: Option<TokenStream>,
+    inputs: Vec<FnSpecArg>,
+    output: ReturnType,
+    requires: Option<Requires>,
+    ensures: Option<Ensures>,
+    /// Anything after the ensures clause, e.g., a where clause, printed as it is written
+    rest: TokenStream,
+}
+
+enum FnSpecArg {
+    Named(FnArg),
+    Unnamed(Type),
+}
+
+impl Parse for FnSpec {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let _: Token![fn] = input.parse()?;
+        let generics = if input.peek(Token![<]) { Some(parse_generics(input)?) } else { None };
+        let content;
+        parenthesized!(content in input);
+        let mut inputs = vec![];
+        while !content.is_empty() {
+            let ahead = content.fork();
+            let arg = match ahead.parse::<FnArg>() {
+                Ok(arg) if ahead.is_empty() || ahead.peek(Token![,]) => {
+                    content.advance_to(&ahead);
+                    FnSpecArg::Named(arg)
+                }
+                _ => FnSpecArg::Unnamed(content.parse()?),
+            };
+            inputs.push(arg);
+            if !content.is_empty() {
+                let _: Token![,] = content.parse()?;
+            }
+        }
+        Ok(FnSpec {
+            generics,
+            inputs,
+            output: input.parse()?,
+            requires: parse_requires(input)?,
+            ensures: parse_ensures(input)?,
+            rest: input.parse()?,
+        })
+    }
+}
+
+/// Parses `<`, the tokens up to the matching `>`, and the `>`, e.g., `<T, U>`. It returns the
+/// tokens in between.
+fn parse_generics(input: ParseStream) -> Result<TokenStream> {
+    let _: Token![<] = input.parse()?;
+    let mut tokens = TokenStream::new();
+    let mut depth = 0;
+    loop {
+        let tt: TokenTree = input.parse()?;
+        if let TokenTree::Punct(punct) = &tt {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' if depth == 0 => return Ok(tokens),
+                '>' => depth -= 1,
+                _ => {}
+            }
+        }
+        tokens.extend([tt]);
+    }
+}
+
+fn fn_spec_to_string(fn_spec: &FnSpec) -> String {
+    let mut s = String::from("fn");
+    if let Some(generics) = &fn_spec.generics {
+        s.push_str(&format!("<{}>", tokens_to_string(generics)));
+    }
+    let inputs = fn_spec
+        .inputs
+        .iter()
+        .map(|arg| {
+            match arg {
+                FnSpecArg::Named(arg) => fn_arg_to_string(arg),
+                FnSpecArg::Unnamed(ty) => type_to_string(ty),
+            }
+        })
+        .collect::<Vec<_>>();
+    s.push_str(&format!("({})", inputs.join(", ")));
+    if let ReturnType::Type(_, ty) = &fn_spec.output {
+        s.push_str(&format!(" -> {}", type_to_string(ty)));
+    }
+    if let Some(requires) = &fn_spec.requires {
+        s.push_str(&format!(" requires {}", tokens_to_string(&requires.constraint)));
+    }
+    if let Some(ensures) = &fn_spec.ensures {
+        let constraints = ensures
+            .constraints
+            .iter()
+            .map(|constraint| {
+                match constraint {
+                    Constraint::Type { ident, ty, .. } => {
+                        format!("{ident}: {}", type_to_string(ty))
+                    }
+                    Constraint::Expr(expr) => tokens_to_string(expr),
+                }
+            })
+            .collect::<Vec<_>>();
+        s.push_str(&format!(" ensures {}", constraints.join(", ")));
+    }
+    if !fn_spec.rest.is_empty() {
+        s.push(' ');
+        s.push_str(&tokens_to_string(&fn_spec.rest));
+    }
+    s
+}
+
+fn fn_arg_to_string(arg: &FnArg) -> String {
+    match arg {
+        FnArg::StrgRef(arg) => {
+            format!("{}: &strg {}", pat_to_string(&arg.pat), type_to_string(&arg.ty))
+        }
+        FnArg::Typed(arg) => {
+            let mut s = format!("{}: {}", pat_to_string(&arg.pat), type_to_string(&arg.ty));
+            if let Some(pred) = &arg.pred {
+                s.push_str(&format!("{{{}}}", tokens_to_string(&pred.pred)));
+            }
+            s
+        }
+    }
+}
+
+fn pat_to_string(pat: &Pat) -> String {
+    match pat {
+        Pat::Ident(pat) if pat.mutability.is_some() => format!("mut {}", pat.ident),
+        Pat::Ident(pat) => pat.ident.to_string(),
+        Pat::Wild(_) => "_".to_string(),
+    }
+}
+
+fn type_to_string(ty: &Type) -> String {
+    match ty {
+        Type::Base(bty) => base_type_to_string(bty),
+        Type::Indexed(ty) => {
+            format!("{}[{}]", base_type_to_string(&ty.bty), tokens_to_string(&ty.expr))
+        }
+        Type::Exists(ty) => {
+            format!(
+                "{}{{{}: {}}}",
+                base_type_to_string(&ty.bty),
+                ty.ident,
+                tokens_to_string(&ty.expr)
+            )
+        }
+        Type::GeneralExists(ty) => {
+            let params = ty
+                .params
+                .iter()
+                .map(|param| {
+                    match &param.sort {
+                        Some(sort) => format!("{}: {sort}", param.ident),
+                        None => param.ident.to_string(),
+                    }
+                })
+                .collect::<Vec<_>>();
+            let mut s = format!("{{{}. {}", params.join(", "), type_to_string(&ty.ty));
+            if let Some(pred) = &ty.pred {
+                s.push_str(&format!(" | {}", tokens_to_string(pred)));
+            }
+            s.push('}');
+            s
+        }
+        Type::Reference(ty) => {
+            let mut s = String::from("&");
+            if let Some(lifetime) = &ty.lifetime {
+                s.push_str(&format!("{lifetime} "));
+            }
+            if ty.mutability.is_some() {
+                s.push_str("mut ");
+            }
+            s.push_str(&type_to_string(&ty.elem));
+            s
+        }
+        Type::Constraint(ty) => {
+            format!("{{{} | {}}}", type_to_string(&ty.ty), tokens_to_string(&ty.pred))
+        }
+        Type::Array(ty) => format!("[{}; {}]", type_to_string(&ty.ty), tokens_to_string(&ty.len)),
+        Type::Tuple(ty) => {
+            let elems = ty.elems.iter().map(type_to_string).collect::<Vec<_>>();
+            if elems.len() == 1 {
+                format!("({},)", elems[0])
+            } else {
+                format!("({})", elems.join(", "))
+            }
+        }
+        Type::Ptr(ty) => tokens_to_string(&quote::ToTokens::to_token_stream(ty)),
+    }
+}
+
+fn base_type_to_string(bty: &BaseType) -> String {
+    match bty {
+        BaseType::Path(path) => path_to_string(path),
+        BaseType::Slice(slice) => format!("[{}]", type_to_string(&slice.ty)),
+    }
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| {
+            match &segment.arguments {
+                PathArguments::None => segment.ident.to_string(),
+                PathArguments::AngleBracketed(args) => {
+                    let args = args
+                        .args
+                        .iter()
+                        .map(|arg| {
+                            match arg {
+                                GenericArgument::Type(ty) => type_to_string(ty),
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    format!("{}<{}>", segment.ident, args.join(", "))
+                }
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn sort_to_string(sort: &Sort) -> String {
+    match sort {
+        Sort::BaseSort(sort) => base_sort_to_string(sort),
+        Sort::Func { input: FuncSortInput::Single(input), output, .. } => {
+            format!("{} -> {}", base_sort_to_string(input), base_sort_to_string(output))
+        }
+        Sort::Func { input: FuncSortInput::Parenthesized { inputs, .. }, output, .. } => {
+            let inputs = inputs.iter().map(base_sort_to_string).collect::<Vec<_>>();
+            format!("({}) -> {}", inputs.join(", "), base_sort_to_string(output))
+        }
+    }
+}
+
+fn base_sort_to_string(sort: &BaseSort) -> String {
+    match sort {
+        BaseSort::BitVec(sort) => format!("bitvec<{}>", sort.lit),
+        BaseSort::App(ident, SortArguments::None) => ident.to_string(),
+        BaseSort::App(ident, SortArguments::AngleBracketed(args)) => {
+            let args = args
+                .args
+                .iter()
+                .map(base_sort_to_string)
+                .collect::<Vec<_>>();
+            format!("{ident}<{}>", args.join(", "))
+        }
+    }
+}
+
+/// What was printed last, which determines whether the next token is preceded by a space
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Last {
+    /// Nothing, or an opening delimiter
+    Start,
+    /// An identifier, a literal or a closing delimiter
+    Word,
+    /// An operator or separator, already followed by a space if it needs one
+    Op,
+    /// A prefix operator, e.g., the `-` in `-x` or the `@` in `@n`, which is never followed by a
+    /// space
+    Prefix,
+}
+
+/// Prints a sequence of tokens the way an expression is usually written: binary operators are
+/// surrounded by spaces, separators are followed by one, and there are no spaces around paths,
+/// field accesses, calls, or prefix operators, e.g., `f(x.0, -y) + a::b`.
+pub(crate) fn tokens_to_string(tokens: &TokenStream) -> String {
+    let mut s = String::new();
+    print_tokens(tokens.clone(), &mut s, Last::Start);
+    s
+}
+
+fn print_tokens(tokens: TokenStream, s: &mut String, mut last: Last) -> Last {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(tt) = tokens.next() {
+        last = match tt {
+            TokenTree::Ident(ident) => {
+                if last == Last::Word {
+                    s.push(' ');
+                }
+                s.push_str(&ident.to_string());
+                Last::Word
+            }
+            TokenTree::Literal(lit) => {
+                if last == Last::Word {
+                    s.push(' ');
+                }
+                s.push_str(&lit.to_string());
+                Last::Word
+            }
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                s.push_str(open);
+                let inner = print_tokens(group.stream(), s, Last::Start);
+                s.push_str(close);
+                if group.delimiter() == Delimiter::None {
+                    inner
+                } else {
+                    Last::Word
+                }
+            }
+            TokenTree::Punct(punct) => {
+                let mut op = String::from(punct.as_char());
+                let mut spacing = punct.spacing();
+                while spacing == Spacing::Joint {
+                    let Some(TokenTree::Punct(next)) = tokens.peek() else { break };
+                    op.push(next.as_char());
+                    spacing = next.spacing();
+                    tokens.next();
+                }
+                print_op(&op, s, last)
+            }
+        };
+    }
+    last
+}
+
+fn print_op(op: &str, s: &mut String, last: Last) -> Last {
+    let is_prefix = matches!(last, Last::Start | Last::Op | Last::Prefix);
+    match op {
+        "::" | "." | ".." => {
+            s.push_str(op);
+            Last::Prefix
+        }
+        "," | ";" | ":" => {
+            s.push_str(op);
+            s.push(' ');
+            Last::Op
+        }
+        "@" | "#" | "$" | "'" => {
+            if last == Last::Word {
+                s.push(' ');
+            }
+            s.push_str(op);
+            Last::Prefix
+        }
+        "!" | "-" | "*" | "&" if is_prefix => {
+            s.push_str(op);
+            Last::Prefix
+        }
+        _ => {
+            if last != Last::Start && last != Last::Op {
+                s.push(' ');
+            }
+            s.push_str(op);
+            s.push(' ');
+            Last::Op
+        }
+    }
+}
@@ -448,7 +448,9 @@ fn create_dummy_enum(
     Ok(dummy_enum_with_attrs.to_token_stream())
 }
 
-/// Create a dummy struct with a single unnamed field that is the external struct.
+/// Create a dummy struct whose last field is the external struct. A struct without fields is
+/// opaque. Otherwise, its fields must be the same as the fields of the external struct and they
+/// are kept in the dummy struct before the external one.
 ///
 /// Example:
 ///
@@ -463,38 +465,64 @@ fn create_dummy_enum(
 /// #[allow(unused, dead_code)]
 /// #[flux::refined_by(n: int)]
 /// struct FluxExternStructVec<T>(std::vec::Vec<T>);
+///
+/// #[extern_spec(geometry)]
+/// #[flux::refined_by(x: int, y: int)]
+/// struct Point {
+///     #[flux::field(u32[x])]
+///     x: u32,
+///     #[flux::field(u32[y])]
+///     y: u32,
+/// }
+///
+/// =>
+///
+/// #[flux::extern_spec]
+/// #[allow(unused, dead_code)]
+/// #[flux::refined_by(x: int, y: int)]
+/// struct FluxExternStructPoint {
+///     #[flux::field(u32[x])]
+///     x: u32,
+///     #[flux::field(u32[y])]
+///     y: u32,
+///     __flux_extern_struct: geometry::Point,
+/// }
 /// ```
 fn create_dummy_struct(
     mod_path: Option<syn::Path>,
     item_struct: syn::ItemStruct,
 ) -> syn::Result<TokenStream> {
     let item_struct_span = item_struct.span();
-    let fields_span = item_struct.fields.span();
-    let syn::Fields::Unit = item_struct.fields else {
-        return Err(syn::Error::new(
-            fields_span,
-            "invalid extern spec: extern specs on structs cannot have fields, i.e. they must look like struct Vec<T>;",
-        ));
-    };
     let mut dummy_struct = item_struct.clone();
     let ident = item_struct.ident;
     let mut generics = item_struct.generics;
     strip_generics_eq_default(&mut generics.params);
 
     dummy_struct.ident = format_ident!("__FluxExternStruct{}", ident);
-    dummy_struct.semi_token = None;
 
-    let dummy_field: syn::FieldsUnnamed = if let Some(mod_path) = mod_path {
-        parse_quote_spanned! {item_struct_span =>
-                              ( #mod_path :: #ident #generics )
-        }
+    let extern_ty: syn::Type = if let Some(mod_path) = mod_path {
+        parse_quote_spanned! {item_struct_span => #mod_path :: #ident #generics }
     } else {
-        parse_quote_spanned! {item_struct_span =>
-                              ( #ident #generics )
-        }
+        parse_quote_spanned! {item_struct_span => #ident #generics }
     };
 
-    dummy_struct.fields = syn::Fields::Unnamed(dummy_field);
+    match &mut dummy_struct.fields {
+        syn::Fields::Unit => {
+            dummy_struct.semi_token = None;
+            dummy_struct.fields =
+                syn::Fields::Unnamed(parse_quote_spanned! {item_struct_span => ( #extern_ty ) });
+        }
+        syn::Fields::Named(fields) => {
+            fields
+                .named
+                .push(parse_quote_spanned! {item_struct_span => __flux_extern_struct: #extern_ty });
+        }
+        syn::Fields::Unnamed(fields) => {
+            fields
+                .unnamed
+                .push(parse_quote_spanned! {item_struct_span => #extern_ty });
+        }
+    }
     let dummy_struct_with_attrs: syn::ItemStruct = parse_quote_spanned! { item_struct_span =>
                                                                           #[flux::extern_spec]
                                                                           #[allow(unused, dead_code)]
@@ -503,7 +531,8 @@ fn create_dummy_struct(
     Ok(dummy_struct_with_attrs.to_token_stream())
 }
 
-/// Create a dummy trait with a single super-trait that is the external trait
+/// Create a dummy trait with a single super-trait that is the external trait. Methods are turned
+/// into provided methods calling the corresponding method of the external trait.
 ///
 /// Example:
 ///
@@ -511,7 +540,10 @@ fn create_dummy_struct(
 /// #[extern_spec(std::vec)]
 /// #[flux::generics(Self as base)]
 /// #[flux::assoc(fn f(self: Self) -> bool)]
-/// trait MyTrait {}
+/// trait MyTrait {
+///     #[flux::sig(fn(&Self{v: Self::f(v)}) -> bool[true])]
+///     fn method(&self) -> bool;
+/// }
 ///
 /// =>
 ///
@@ -519,7 +551,13 @@ fn create_dummy_struct(
 /// #[allow(unused, dead_code)]
 /// #[flux::generics(Self as base)]
 /// #[flux::assoc(fn f(self: Self) -> bool)]
-/// trait __FluxExternTraitMyTrait: MyTrait {}
+/// trait __FluxExternTraitMyTrait: MyTrait {
+///     #[flux::extern_spec]
+///     #[flux::sig(fn(&Self{v: Self::f(v)}) -> bool[true])]
+///     fn __flux_extern_spec_method(&self) -> bool {
+///         <Self as std::vec::MyTrait>::method::<>(self)
+///     }
+/// }
 /// ```
 fn create_dummy_trait(
     mod_path: Option<syn::Path>,
@@ -532,18 +570,38 @@ fn create_dummy_trait(
             "invalid extern spec: extern specs on traits cannot have supertraits",
         ));
     }
-    if !item_trait.items.is_empty() {
-        return Err(syn::Error::new(
-            item_trait_span,
-            "invalid extern spec: extern specs on traits cannot have items",
-        ));
-    }
 
     let mut dummy_trait = item_trait.clone();
     let ident = item_trait.ident;
     let mut generics = item_trait.generics;
     strip_generics_eq_default(&mut generics.params);
 
+    let trait_path: syn::Path = if let Some(mod_path) = &mod_path {
+        parse_quote_spanned!(item_trait_span=> #mod_path :: #ident #generics)
+    } else {
+        parse_quote_spanned!(item_trait_span=> #ident #generics)
+    };
+    let self_ty: syn::Type = parse_quote_spanned!(item_trait_span=> Self);
+    dummy_trait.items = item_trait
+        .items
+        .into_iter()
+        .map(|item| {
+            match item {
+                syn::TraitItem::Fn(syn::TraitItemFn { attrs, sig, default: None, .. }) => {
+                    let mut extern_fn = ExternFn { attrs, sig, block: None };
+                    extern_fn.prepare(&None, Some(&self_ty), &Some(trait_path.clone()), true);
+                    Ok(syn::TraitItem::Verbatim(extern_fn.into_token_stream()))
+                }
+                _ => {
+                    Err(syn::Error::new(
+                        item.span(),
+                        "invalid extern spec: extern specs on traits can only have methods without a body",
+                    ))
+                }
+            }
+        })
+        .collect::<syn::Result<_>>()?;
+
     dummy_trait.ident = format_ident!("__FluxExternTrait{}", ident);
     dummy_trait.auto_token = None;
 
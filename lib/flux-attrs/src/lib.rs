@@ -1,7 +1,10 @@
 mod ast;
+mod contracts;
+mod doc;
 mod extern_spec;
 
-use proc_macro2::{Ident, TokenStream, TokenTree};
+pub use contracts::contracts;
+use proc_macro2::{Delimiter, Group, Ident, TokenStream, TokenTree};
 use quote::{quote, quote_spanned, ToTokens};
 use syn::{parse_quote, spanned::Spanned, Attribute, ItemEnum, ItemStruct};
 
@@ -46,7 +49,133 @@ pub fn refined_by(attr: TokenStream, item: TokenStream) -> TokenStream {
             #item
         }
     } else {
-        item.to_token_stream()
+        spec_doc(&refined_by_doc(&attr), item.to_token_stream())
+    }
+}
+
+/// Adds the signature in `#[flux_rs::sig(..)]` to the documentation of the item, see [`spec_doc`]
+pub fn sig_doc(attr: TokenStream, item: TokenStream) -> TokenStream {
+    spec_doc(&sig_doc_text(&attr), item)
+}
+
+fn sig_doc_text(spec: &TokenStream) -> String {
+    format!("**Flux signature:** `{}`", doc::sig_to_string(spec))
+}
+
+fn refined_by_doc(spec: &TokenStream) -> String {
+    format!("**Refined by:** `{}`", doc::refined_by_to_string(spec))
+}
+
+/// Adds the spec of an item to its documentation, e.g., `#[flux_rs::sig(fn(i32{v: v > 0}))]` is
+/// shown by rustdoc as a paragraph ``Flux signature: `fn(i32{v: v > 0})` ``. The spec is printed
+/// as it would be written by hand (see [`doc`]). The doc attributes are only emitted when building
+/// documentation, and they go after the docs of the item, so the summary shown in module listings
+/// doesn't change.
+fn spec_doc(text: &str, item: TokenStream) -> TokenStream {
+    let mut tokens = item.into_iter().peekable();
+    let mut attrs = TokenStream::new();
+    // An outer attribute is a `#` followed by a bracketed group
+    while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+        if punct.as_char() != '#' {
+            break;
+        }
+        attrs.extend(tokens.next());
+        attrs.extend(tokens.next());
+    }
+    let rest: TokenStream = tokens.collect();
+    let docs = doc_attrs(text);
+    quote! {
+        #attrs
+        #docs
+        #rest
+    }
+}
+
+fn doc_attrs(text: &str) -> TokenStream {
+    quote! {
+        #[cfg_attr(doc, doc = "")]
+        #[cfg_attr(doc, doc = #text)]
+    }
+}
+
+/// Documents the specs written with tool attributes, e.g., `#[flux::sig(..)]` or
+/// `#[cfg_attr(flux, flux::refined_by(..))]`, in every item nested in `item` the same way as
+/// [`spec_doc`]. Tool attributes are inert, so they can't add documentation themselves. The tokens
+/// of `item` are kept as they are.
+pub fn doc_specs(item: TokenStream) -> TokenStream {
+    let mut tokens = item.into_iter().peekable();
+    let mut result = TokenStream::new();
+    while let Some(tt) = tokens.next() {
+        match tt {
+            TokenTree::Punct(punct) if punct.as_char() == '#' && is_attr_group(tokens.peek()) => {
+                // A sequence of outer attributes. The docs go after all of them.
+                let mut docs = vec![];
+                result.extend([TokenTree::Punct(punct)]);
+                while let Some(TokenTree::Group(group)) = tokens.next() {
+                    docs.extend(tool_attr_docs(group.stream()));
+                    result.extend([TokenTree::Group(group)]);
+                    match tokens.peek() {
+                        Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => {
+                            result.extend(tokens.next());
+                        }
+                        _ => break,
+                    }
+                    if !is_attr_group(tokens.peek()) {
+                        break;
+                    }
+                }
+                for text in docs {
+                    result.extend(doc_attrs(&text));
+                }
+            }
+            TokenTree::Group(group) => {
+                let mut new_group = Group::new(group.delimiter(), doc_specs(group.stream()));
+                new_group.set_span(group.span());
+                result.extend([TokenTree::Group(new_group)]);
+            }
+            tt => result.extend([tt]),
+        }
+    }
+    result
+}
+
+fn is_attr_group(tt: Option<&TokenTree>) -> bool {
+    matches!(tt, Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket)
+}
+
+/// The documentation for the content of an attribute if it is a `sig` or `refined_by` tool
+/// attribute, e.g., `flux::sig(..)`, or a `cfg_attr` containing them.
+fn tool_attr_docs(attr: TokenStream) -> Vec<String> {
+    let tokens = attr.into_iter().collect::<Vec<_>>();
+    match &tokens[..] {
+        [TokenTree::Ident(tool), TokenTree::Punct(colon1), TokenTree::Punct(colon2), TokenTree::Ident(name), TokenTree::Group(args)]
+            if tool == "flux"
+                && colon1.as_char() == ':'
+                && colon2.as_char() == ':'
+                && args.delimiter() == Delimiter::Parenthesis =>
+        {
+            if name == "sig" {
+                vec![sig_doc_text(&args.stream())]
+            } else if name == "refined_by" {
+                vec![refined_by_doc(&args.stream())]
+            } else {
+                vec![]
+            }
+        }
+        [TokenTree::Ident(cfg_attr), TokenTree::Group(args)] if cfg_attr == "cfg_attr" => {
+            // The first argument is the predicate, the rest are attributes
+            let mut attrs = vec![TokenStream::new()];
+            for tt in args.stream() {
+                match tt {
+                    TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                        attrs.push(TokenStream::new());
+                    }
+                    tt => attrs.last_mut().unwrap().extend([tt]),
+                }
+            }
+            attrs.into_iter().skip(1).flat_map(tool_attr_docs).collect()
+        }
+        _ => vec![],
     }
 }
 
@@ -99,6 +228,77 @@ pub fn flux(tokens: TokenStream) -> TokenStream {
         .map_or_else(|err| err.to_compile_error(), ToTokens::into_token_stream)
 }
 
+/// Expands `assume!(cond)` into a call to a function declared as the `assume` intrinsic. The
+/// condition is evaluated (outside of the `unsafe` block) with or without flux.
+pub fn assume(tokens: TokenStream) -> TokenStream {
+    if cfg!(flux_sysroot) {
+        quote! {
+            {
+                #[flux_tool::intrinsic(assume)]
+                unsafe fn __flux_assume(_: bool) {}
+                let cond: bool = #tokens;
+                unsafe { __flux_assume(cond) }
+            }
+        }
+    } else {
+        eval_cond(tokens)
+    }
+}
+
+/// Expands `flux_assert!(cond)` into a call to a function declared as the `assert` intrinsic. The
+/// condition is checked statically, so there's no runtime check.
+pub fn flux_assert(tokens: TokenStream) -> TokenStream {
+    if cfg!(flux_sysroot) {
+        quote! {
+            {
+                #[flux_tool::intrinsic(assert)]
+                fn __flux_assert(_: bool) {}
+                __flux_assert(#tokens)
+            }
+        }
+    } else {
+        eval_cond(tokens)
+    }
+}
+
+fn eval_cond(tokens: TokenStream) -> TokenStream {
+    quote! {
+        {
+            let _: bool = #tokens;
+        }
+    }
+}
+
+/// Expands `pack!(place)` into a call to a function declared as the `pack` intrinsic
+pub fn pack(tokens: TokenStream) -> TokenStream {
+    ghost_op(quote!(pack), tokens)
+}
+
+/// Expands `unpack!(place)` into a call to a function declared as the `unpack` intrinsic
+pub fn unpack(tokens: TokenStream) -> TokenStream {
+    ghost_op(quote!(unpack), tokens)
+}
+
+/// The intrinsic is called with a borrow of the place, which is how the checker finds it. Without
+/// flux the place is only borrowed.
+fn ghost_op(intrinsic: TokenStream, tokens: TokenStream) -> TokenStream {
+    if cfg!(flux_sysroot) {
+        quote! {
+            {
+                #[flux_tool::intrinsic(#intrinsic)]
+                fn __flux_ghost_op<T: ?Sized>(_: &T) {}
+                __flux_ghost_op(&#tokens)
+            }
+        }
+    } else {
+        quote! {
+            {
+                let _ = &#tokens;
+            }
+        }
+    }
+}
+
 pub fn defs(tokens: TokenStream) -> TokenStream {
     quote! {
         mod flux_defs {
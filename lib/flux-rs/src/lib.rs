@@ -1,3 +1,11 @@
+//! Attributes and macros to write flux specs.
+//!
+//! The attributes, e.g., `#[flux_rs::sig(..)]` or `#[flux_rs::refined_by(..)]`, are proc-macros,
+//! so a crate using them compiles with any toolchain, including stable, without registering the
+//! `flux` tool. During normal compilation they are erased (or turned into documentation), and when
+//! the crate is checked with `cargo flux` they expand to the corresponding tool attributes, e.g.,
+//! `#[flux_tool::sig(..)]`, which are the ones flux reads. See `lib/README.md` for how the two
+//! modes are toggled.
 #[cfg(not(flux_sysroot))]
 use attr_dummy as attr_impl;
 #[cfg(flux_sysroot)]
@@ -39,11 +47,41 @@ pub fn opaque(attr: TokenStream, tokens: TokenStream) -> TokenStream {
     attr_impl::opaque(attr, tokens)
 }
 
+#[proc_macro_attribute]
+pub fn reflect(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::reflect(attr, tokens)
+}
+
 #[proc_macro_attribute]
 pub fn trusted(attr: TokenStream, tokens: TokenStream) -> TokenStream {
     attr_impl::trusted(attr, tokens)
 }
 
+#[proc_macro_attribute]
+pub fn should_fail(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::should_fail(attr, tokens)
+}
+
+#[proc_macro_attribute]
+pub fn inline(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::inline(attr, tokens)
+}
+
+#[proc_macro_attribute]
+pub fn unpack_policy(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::unpack_policy(attr, tokens)
+}
+
+#[proc_macro_attribute]
+pub fn solver(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::solver(attr, tokens)
+}
+
+#[proc_macro_attribute]
+pub fn opts(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::opts(attr, tokens)
+}
+
 #[proc_macro_attribute]
 pub fn generics(attr: TokenStream, tokens: TokenStream) -> TokenStream {
     attr_impl::generics(attr, tokens)
@@ -54,11 +92,36 @@ pub fn assoc(attr: TokenStream, tokens: TokenStream) -> TokenStream {
     attr_impl::assoc(attr, tokens)
 }
 
+#[proc_macro_attribute]
+pub fn doc_specs(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::doc_specs(attr, tokens)
+}
+
 #[proc_macro]
 pub fn flux(tokens: TokenStream) -> TokenStream {
     flux_attrs::flux(tokens.into()).into()
 }
 
+#[proc_macro]
+pub fn assume(tokens: TokenStream) -> TokenStream {
+    flux_attrs::assume(tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn flux_assert(tokens: TokenStream) -> TokenStream {
+    flux_attrs::flux_assert(tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn pack(tokens: TokenStream) -> TokenStream {
+    flux_attrs::pack(tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn unpack(tokens: TokenStream) -> TokenStream {
+    flux_attrs::unpack(tokens.into()).into()
+}
+
 #[proc_macro]
 pub fn defs(tokens: TokenStream) -> TokenStream {
     attr_impl::defs(tokens)
@@ -90,6 +153,10 @@ mod attr_sysroot {
         flux_attrs::defs(tokens.into()).into()
     }
 
+    pub fn doc_specs(_attr: TokenStream, item: TokenStream) -> TokenStream {
+        item
+    }
+
     macro_rules! flux_tool_attrs {
         ($($name:ident),+ $(,)?) => {
             $(
@@ -101,7 +168,22 @@ mod attr_sysroot {
     }
 
     flux_tool_attrs!(
-        alias, sig, qualifiers, constant, invariant, opaque, trusted, generics, assoc, ignore
+        alias,
+        sig,
+        qualifiers,
+        constant,
+        invariant,
+        opaque,
+        reflect,
+        trusted,
+        should_fail,
+        inline,
+        unpack_policy,
+        solver,
+        opts,
+        generics,
+        assoc,
+        ignore
     );
 }
 
@@ -121,6 +203,19 @@ mod attr_dummy {
         TokenStream::new()
     }
 
+    pub fn sig(attr: TokenStream, item: TokenStream) -> TokenStream {
+        let item = if cfg!(feature = "contracts") {
+            flux_attrs::contracts(attr.clone().into(), item.into())
+        } else {
+            item.into()
+        };
+        flux_attrs::sig_doc(attr.into(), item).into()
+    }
+
+    pub fn doc_specs(_attr: TokenStream, item: TokenStream) -> TokenStream {
+        flux_attrs::doc_specs(item.into()).into()
+    }
+
     macro_rules! no_op {
         ($($name:ident),+ $(,)?) => {
             $(
@@ -131,5 +226,21 @@ mod attr_dummy {
         };
     }
 
-    no_op!(alias, sig, qualifiers, invariant, constant, opaque, trusted, generics, assoc, ignore);
+    no_op!(
+        alias,
+        qualifiers,
+        invariant,
+        constant,
+        opaque,
+        reflect,
+        trusted,
+        should_fail,
+        inline,
+        unpack_policy,
+        solver,
+        opts,
+        generics,
+        assoc,
+        ignore
+    );
 }
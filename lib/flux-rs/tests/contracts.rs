@@ -0,0 +1,50 @@
+//! Tests for the runtime checks generated with the `contracts` feature, which is enabled for the
+//! tests by the dev-dependency of the crate on itself.
+
+#[flux_rs::sig(fn(x: i32{x > 0}) -> i32{v: v > x})]
+fn inc(x: i32) -> i32 {
+    x + 1
+}
+
+#[flux_rs::sig(fn(x: i32{x > 0}) -> i32{v: v > x})]
+fn bad_inc(x: i32) -> i32 {
+    x - 1
+}
+
+#[flux_rs::sig(fn(x: i32[@n], bool[@b]) -> i32[n] requires b => n > 0)]
+fn implication(x: i32, _b: bool) -> i32 {
+    x
+}
+
+#[allow(clippy::ptr_arg)]
+#[flux_rs::sig(fn(&Vec<i32>[@n], i: usize{i < n}) -> i32)]
+fn first(v: &Vec<i32>, _i: usize) -> i32 {
+    v[0]
+}
+
+#[test]
+fn valid_call() {
+    assert_eq!(inc(1), 2);
+}
+
+#[test]
+#[should_panic(expected = "precondition of `inc` does not hold")]
+fn violated_precondition() {
+    inc(0);
+}
+
+#[test]
+#[should_panic(expected = "postcondition of `bad_inc` does not hold")]
+fn violated_postcondition() {
+    bad_inc(1);
+}
+
+#[test]
+fn implications_are_skipped() {
+    assert_eq!(implication(0, true), 0);
+}
+
+#[test]
+fn unknown_indices_are_skipped() {
+    assert_eq!(first(&vec![1], 5), 1);
+}
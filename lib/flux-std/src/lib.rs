@@ -0,0 +1,21 @@
+//! Refined wrappers and specifications for commonly used types in the standard library.
+//!
+//! The types in this crate are thin wrappers around their standard library counterparts whose
+//! methods are annotated with trusted Flux signatures. They are meant to be used as a starting
+//! point by projects verified with Flux, so the same specs do not need to be rewritten for every
+//! project.
+//!
+//! During normal compilation all the annotations are no-ops and the wrappers behave exactly like
+//! the types they wrap.
+
+pub mod option;
+pub mod range;
+pub mod result;
+pub mod rmap;
+pub mod rstring;
+pub mod rvec;
+
+pub use range::{RRange, RRangeIter};
+pub use rmap::RMap;
+pub use rstring::RString;
+pub use rvec::{RVec, RVecIter};
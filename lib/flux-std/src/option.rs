@@ -0,0 +1,39 @@
+//! Refinements for [`Option`] and helpers that use them.
+//!
+//! An `Option<T>` is refined by a boolean that is `true` iff the value is a `Some`.
+
+use flux_rs::{extern_spec, sig};
+
+#[extern_spec]
+#[flux::refined_by(b: bool)]
+enum Option<T> {
+    #[flux::variant(Option<T>[false])]
+    None,
+    #[flux::variant({T} -> Option<T>[true])]
+    Some(T),
+}
+
+#[sig(fn(&Option<T>[@b]) -> bool[b])]
+pub fn is_some<T>(opt: &Option<T>) -> bool {
+    match opt {
+        Some(_) => true,
+        None => false,
+    }
+}
+
+#[sig(fn(&Option<T>[@b]) -> bool[!b])]
+pub fn is_none<T>(opt: &Option<T>) -> bool {
+    match opt {
+        Some(_) => false,
+        None => true,
+    }
+}
+
+/// Unwraps an option that is statically known to be a `Some`.
+#[sig(fn(Option<T>[true]) -> T)]
+pub fn unwrap<T>(opt: Option<T>) -> T {
+    match opt {
+        Some(v) => v,
+        None => unreachable!(),
+    }
+}
@@ -0,0 +1,66 @@
+//! Half-open ranges whose iterators yield indices known to be within bounds.
+
+use flux_rs::{opaque, refined_by, sig, trusted};
+
+/// The half-open range `lo..hi`.
+#[opaque]
+#[refined_by(lo: int, hi: int)]
+pub struct RRange {
+    lo: usize,
+    hi: usize,
+}
+
+impl RRange {
+    #[trusted]
+    #[sig(fn(lo: usize, hi: usize) -> RRange[lo, hi])]
+    pub fn new(lo: usize, hi: usize) -> RRange {
+        RRange { lo, hi }
+    }
+
+    #[trusted]
+    #[sig(fn(&RRange[@lo, @hi]) -> usize[if lo < hi { hi - lo } else { 0 }])]
+    pub fn len(&self) -> usize {
+        self.hi.saturating_sub(self.lo)
+    }
+
+    #[trusted]
+    #[sig(fn(&RRange[@lo, @hi]) -> bool[hi <= lo])]
+    pub fn is_empty(&self) -> bool {
+        self.hi <= self.lo
+    }
+}
+
+/// An iterator over the elements of an [`RRange`].
+#[opaque]
+#[refined_by(lo: int, hi: int)]
+pub struct RRangeIter {
+    cur: usize,
+    hi: usize,
+}
+
+impl IntoIterator for RRange {
+    type Item = usize;
+    type IntoIter = RRangeIter;
+
+    #[trusted]
+    #[sig(fn(RRange[@lo, @hi]) -> RRangeIter[lo, hi])]
+    fn into_iter(self) -> RRangeIter {
+        RRangeIter { cur: self.lo, hi: self.hi }
+    }
+}
+
+impl Iterator for RRangeIter {
+    type Item = usize;
+
+    #[trusted]
+    #[sig(fn(&mut RRangeIter[@lo, @hi]) -> Option<usize{v: lo <= v && v < hi}>)]
+    fn next(&mut self) -> Option<usize> {
+        if self.cur < self.hi {
+            let cur = self.cur;
+            self.cur += 1;
+            Some(cur)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Refinements for [`Result`] and helpers that use them.
+//!
+//! A `Result<T, E>` is refined by a boolean that is `true` iff the value is an `Ok`.
+
+use flux_rs::{extern_spec, sig};
+
+#[extern_spec]
+#[flux::refined_by(ok: bool)]
+enum Result<T, E> {
+    #[flux::variant({T} -> Result<T, E>[true])]
+    Ok(T),
+    #[flux::variant({E} -> Result<T, E>[false])]
+    Err(E),
+}
+
+#[sig(fn(&Result<T, E>[@ok]) -> bool[ok])]
+pub fn is_ok<T, E>(res: &Result<T, E>) -> bool {
+    match res {
+        Ok(_) => true,
+        Err(_) => false,
+    }
+}
+
+#[sig(fn(&Result<T, E>[@ok]) -> bool[!ok])]
+pub fn is_err<T, E>(res: &Result<T, E>) -> bool {
+    match res {
+        Ok(_) => false,
+        Err(_) => true,
+    }
+}
+
+/// Unwraps a result that is statically known to be an `Ok`.
+#[sig(fn(Result<T, E>[true]) -> T)]
+pub fn unwrap<T, E>(res: Result<T, E>) -> T {
+    match res {
+        Ok(v) => v,
+        Err(_) => unreachable!(),
+    }
+}
+
+/// Unwraps a result that is statically known to be an `Err`.
+#[sig(fn(Result<T, E>[false]) -> E)]
+pub fn unwrap_err<T, E>(res: Result<T, E>) -> E {
+    match res {
+        Ok(_) => unreachable!(),
+        Err(e) => e,
+    }
+}
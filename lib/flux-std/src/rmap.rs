@@ -0,0 +1,68 @@
+//! A hash map indexed by the map it represents.
+
+use std::{collections::HashMap, hash::Hash};
+
+use flux_rs::{defs, generics, opaque, refined_by, sig, trusted};
+
+defs! {
+    fn map_set<K, V>(m: Map<K, V>, k: K, v: V) -> Map<K, V> { map_store(m, k, v) }
+    fn map_get<K, V>(m: Map<K, V>, k: K) -> V { map_select(m, k) }
+}
+
+/// A wrapper around [`HashMap`] refined by the map from keys to values it stores.
+#[opaque]
+#[refined_by(vals: Map<K, V>)]
+pub struct RMap<K, V> {
+    inner: HashMap<K, V>,
+}
+
+#[generics(K as base, V as base)]
+impl<K, V> RMap<K, V> {
+    #[trusted]
+    pub fn new() -> Self {
+        Self { inner: HashMap::new() }
+    }
+
+    #[trusted]
+    #[sig(fn(self: &strg RMap<K, V>[@m], k: K, v: V) ensures self: RMap<K, V>[map_set(m.vals, k, v)])]
+    pub fn set(&mut self, k: K, v: V)
+    where
+        K: Eq + Hash,
+    {
+        self.inner.insert(k, v);
+    }
+
+    #[trusted]
+    #[sig(fn(&RMap<K, V>[@m], &K[@k]) -> Option<&V[map_get(m.vals, k)]>)]
+    pub fn get(&self, k: &K) -> Option<&V>
+    where
+        K: Eq + Hash,
+    {
+        self.inner.get(k)
+    }
+
+    #[trusted]
+    pub fn contains_key(&self, k: &K) -> bool
+    where
+        K: Eq + Hash,
+    {
+        self.inner.contains_key(k)
+    }
+
+    #[trusted]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[trusted]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl<K, V> Default for RMap<K, V> {
+    #[trusted]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,83 @@
+//! A string indexed by its length in bytes.
+
+use flux_rs::{invariant, opaque, refined_by, sig, trusted};
+
+/// A wrapper around [`String`] refined by its length in bytes.
+#[opaque]
+#[refined_by(len: int)]
+#[invariant(0 <= len)]
+pub struct RString {
+    inner: String,
+}
+
+impl RString {
+    #[trusted]
+    #[sig(fn() -> RString[0])]
+    pub fn new() -> Self {
+        Self { inner: String::new() }
+    }
+
+    #[trusted]
+    #[sig(fn(&RString[@n]) -> usize[n])]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[trusted]
+    #[sig(fn(&RString[@n]) -> bool[n == 0])]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    #[trusted]
+    #[sig(fn(self: &strg RString[@n], char) ensures self: RString{m: n < m})]
+    pub fn push(&mut self, c: char) {
+        self.inner.push(c);
+    }
+
+    #[trusted]
+    #[sig(fn(self: &strg RString[@n], other: &RString[@m]) ensures self: RString[n + m])]
+    pub fn push_rstring(&mut self, other: &RString) {
+        self.inner.push_str(&other.inner);
+    }
+
+    #[trusted]
+    #[sig(fn(self: &strg RString[@n], &str) ensures self: RString{m: n <= m})]
+    pub fn push_str(&mut self, s: &str) {
+        self.inner.push_str(s);
+    }
+
+    #[trusted]
+    pub fn as_str(&self) -> &str {
+        self.inner.as_str()
+    }
+
+    /// Consumes the wrapper returning the underlying [`String`].
+    #[trusted]
+    pub fn into_inner(self) -> String {
+        self.inner
+    }
+}
+
+impl Default for RString {
+    #[trusted]
+    #[sig(fn() -> RString[0])]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for RString {
+    #[trusted]
+    fn from(s: &str) -> Self {
+        Self { inner: String::from(s) }
+    }
+}
+
+impl Clone for RString {
+    #[trusted]
+    #[sig(fn(&RString[@n]) -> RString[n])]
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
@@ -0,0 +1,187 @@
+//! A vector indexed by its length.
+
+use flux_rs::{invariant, opaque, refined_by, sig, trusted};
+
+/// Creates an [`RVec`] containing the arguments. It supports the same forms as [`vec!`].
+#[macro_export]
+macro_rules! rvec {
+    () => { $crate::RVec::new() };
+    ($($e:expr),+$(,)?) => {{
+        let mut res = $crate::RVec::new();
+        $( res.push($e); )*
+        res
+    }};
+    ($elem:expr; $n:expr) => {{
+        $crate::RVec::from_elem_n($elem, $n)
+    }}
+}
+
+/// A wrapper around [`Vec`] refined by its length.
+#[opaque]
+#[refined_by(len: int)]
+#[invariant(0 <= len)]
+pub struct RVec<T> {
+    inner: Vec<T>,
+}
+
+impl<T> RVec<T> {
+    #[trusted]
+    #[sig(fn() -> RVec<T>[0])]
+    pub fn new() -> Self {
+        Self { inner: Vec::new() }
+    }
+
+    #[trusted]
+    #[sig(fn(self: &strg RVec<T>[@n], T) ensures self: RVec<T>[n + 1])]
+    pub fn push(&mut self, item: T) {
+        self.inner.push(item);
+    }
+
+    #[trusted]
+    #[sig(fn(&RVec<T>[@n]) -> usize[n])]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[trusted]
+    #[sig(fn(&RVec<T>[@n]) -> bool[n == 0])]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    #[trusted]
+    #[sig(fn(&RVec<T>[@n], i: usize{i < n}) -> &T)]
+    pub fn get(&self, i: usize) -> &T {
+        &self.inner[i]
+    }
+
+    #[trusted]
+    #[sig(fn(&mut RVec<T>[@n], i: usize{i < n}) -> &mut T)]
+    pub fn get_mut(&mut self, i: usize) -> &mut T {
+        &mut self.inner[i]
+    }
+
+    #[trusted]
+    #[sig(fn(self: &strg RVec<T>[@n]) -> T requires n > 0 ensures self: RVec<T>[n - 1])]
+    pub fn pop(&mut self) -> T {
+        self.inner.pop().unwrap()
+    }
+
+    #[trusted]
+    #[sig(fn(&mut RVec<T>[@n], a: usize{a < n}, b: usize{b < n}))]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.inner.swap(a, b);
+    }
+
+    #[trusted]
+    #[sig(fn(&RVec<T>[@n]) -> &[T][n])]
+    pub fn as_slice(&self) -> &[T] {
+        self.inner.as_slice()
+    }
+
+    #[trusted]
+    #[sig(fn(&mut RVec<T>[@n]) -> &mut [T][n])]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.inner.as_mut_slice()
+    }
+
+    #[trusted]
+    #[sig(fn(T, n: usize) -> RVec<T>[n])]
+    pub fn from_elem_n(elem: T, n: usize) -> Self
+    where
+        T: Clone,
+    {
+        Self { inner: vec![elem; n] }
+    }
+
+    #[trusted]
+    #[sig(fn(self: &strg RVec<T>[@n], other: &[T][@m]) ensures self: RVec<T>[n + m])]
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Clone,
+    {
+        self.inner.extend_from_slice(other);
+    }
+
+    #[trusted]
+    #[sig(fn(&RVec<T>[@n], F) -> RVec<U>[n])]
+    pub fn map<U, F>(&self, f: F) -> RVec<U>
+    where
+        F: Fn(&T) -> U,
+    {
+        RVec { inner: self.inner.iter().map(f).collect() }
+    }
+
+    #[trusted]
+    pub fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &T) -> B,
+    {
+        self.inner.iter().fold(init, f)
+    }
+
+    /// Consumes the wrapper returning the underlying [`Vec`].
+    #[trusted]
+    pub fn into_inner(self) -> Vec<T> {
+        self.inner
+    }
+}
+
+impl<T> Default for RVec<T> {
+    #[trusted]
+    #[sig(fn() -> RVec<T>[0])]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for RVec<T> {
+    #[trusted]
+    #[sig(fn(&RVec<T>[@n]) -> RVec<T>[n])]
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<T> std::ops::Index<usize> for RVec<T> {
+    type Output = T;
+
+    #[trusted]
+    #[sig(fn(&RVec<T>[@n], usize{v: v < n}) -> &T)]
+    fn index(&self, index: usize) -> &T {
+        &self.inner[index]
+    }
+}
+
+impl<T> std::ops::IndexMut<usize> for RVec<T> {
+    #[trusted]
+    #[sig(fn(&mut RVec<T>[@n], usize{v: v < n}) -> &mut T)]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.inner[index]
+    }
+}
+
+/// An owning iterator over the elements of an [`RVec`].
+#[opaque]
+pub struct RVecIter<T> {
+    inner: std::vec::IntoIter<T>,
+}
+
+impl<T> IntoIterator for RVec<T> {
+    type Item = T;
+    type IntoIter = RVecIter<T>;
+
+    #[trusted]
+    fn into_iter(self) -> RVecIter<T> {
+        RVecIter { inner: self.inner.into_iter() }
+    }
+}
+
+impl<T> Iterator for RVecIter<T> {
+    type Item = T;
+
+    #[trusted]
+    fn next(&mut self) -> Option<T> {
+        self.inner.next()
+    }
+}
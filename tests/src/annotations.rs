@@ -0,0 +1,328 @@
+//! Expected diagnostics annotated in the source of a test and the diagnostics actually reported.
+//!
+//! Annotations follow the syntax of rustc's compiletest. A comment `//~ KIND msg` expects a
+//! diagnostic of kind `KIND` (`ERROR`, `WARN`, `NOTE` or `HELP`) whose message contains `msg` on
+//! the same line. The annotation can refer to a previous line with `//~^` (one `^` per line), or to
+//! the line of the previous annotation with `//~|`. On top of that, the kind can be followed by
+//! the error code of the diagnostic in brackets and by the column where its primary span starts,
+//! e.g., `//~ ERROR[EFLUX0002]@5 refinement type`.
+//!
+//! Errors and warnings must always be annotated. Notes and helps only have to be annotated if the
+//! test has at least one annotation of the same kind. Span labels are reported as notes.
+use std::{fmt, path::Path, str::FromStr};
+
+use serde::Deserialize;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kind {
+    Error,
+    Warn,
+    Note,
+    Help,
+}
+
+/// A diagnostic expected by an annotation
+#[derive(Debug)]
+pub struct Annotation {
+    pub line: usize,
+    pub kind: Kind,
+    pub code: Option<String>,
+    pub column: Option<usize>,
+    pub msg: String,
+}
+
+/// A diagnostic (or a part of one) reported by the compiler on the test file
+#[derive(Debug)]
+pub struct Reported {
+    pub line: usize,
+    pub column: usize,
+    pub kind: Option<Kind>,
+    pub code: Option<String>,
+    pub msg: String,
+}
+
+const TAG: &str = "//~";
+
+/// Parses the annotations in the source of a test
+pub fn parse(source: &str) -> Result<Vec<Annotation>, String> {
+    let mut annotations = vec![];
+    let mut last_line = None;
+    for (idx, line) in source.lines().enumerate() {
+        let Some(start) = line.find(TAG) else { continue };
+        let line_num = idx + 1;
+        let rest = &line[start + TAG.len()..];
+        let (line_num, rest) = if let Some(rest) = rest.strip_prefix('|') {
+            let Some(last_line) = last_line else {
+                return Err(format!("line {line_num}: `//~|` without a preceding annotation"));
+            };
+            (last_line, rest)
+        } else {
+            let adjust = rest.chars().take_while(|c| *c == '^').count();
+            if adjust >= line_num {
+                return Err(format!("line {line_num}: `//~^` points before the start of the file"));
+            }
+            let target = line_num - adjust;
+            last_line = Some(target);
+            (target, &rest[adjust..])
+        };
+        let rest = rest.trim_start();
+        let (header, msg) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let (kind, code, column) =
+            parse_header(header).map_err(|err| format!("line {line_num}: {err}"))?;
+        annotations.push(Annotation {
+            line: line_num,
+            kind,
+            code,
+            column,
+            msg: msg.trim().to_string(),
+        });
+    }
+    Ok(annotations)
+}
+
+/// Parses the part of an annotation before the message, e.g., `ERROR[EFLUX0002]@5`
+fn parse_header(header: &str) -> Result<(Kind, Option<String>, Option<usize>), String> {
+    let (header, column) = match header.split_once('@') {
+        Some((header, column)) => {
+            let column = column
+                .parse()
+                .map_err(|_| format!("invalid column `{column}`"))?;
+            (header, Some(column))
+        }
+        None => (header, None),
+    };
+    let (kind, code) = match header.split_once('[') {
+        Some((kind, code)) => {
+            let Some(code) = code.strip_suffix(']') else {
+                return Err(format!("unclosed error code in `{header}`"));
+            };
+            (kind, Some(code.to_string()))
+        }
+        None => (header, None),
+    };
+    let kind = kind.trim_end_matches(':').parse()?;
+    Ok((kind, code, column))
+}
+
+/// Collects the diagnostics reported on the test file `file` from the json output of rustc
+pub fn from_json(stderr: &str, file: &Path) -> Result<Vec<Reported>, String> {
+    let mut reported = vec![];
+    for line in stderr.lines().filter(|line| line.starts_with('{')) {
+        let diagnostic: JsonDiagnostic = serde_json::from_str(line)
+            .map_err(|err| format!("failed to parse diagnostic `{line}`: {err}"))?;
+        push_reported(&mut reported, &diagnostic, None, &[], file);
+    }
+    Ok(reported)
+}
+
+/// Flattens `diagnostic` and its children. Children without a span in the test file inherit the
+/// spans of their parent, and so does their error code.
+fn push_reported(
+    reported: &mut Vec<Reported>,
+    diagnostic: &JsonDiagnostic,
+    parent_code: Option<&str>,
+    default_spans: &[&JsonSpan],
+    file: &Path,
+) {
+    let spans_in_file = diagnostic
+        .spans
+        .iter()
+        .filter(|span| Path::new(&span.file_name) == file)
+        .collect::<Vec<_>>();
+    let primary_spans = spans_in_file
+        .iter()
+        .copied()
+        .filter(|span| span.is_primary)
+        .take(1)
+        .collect::<Vec<_>>();
+    let primary_spans = if primary_spans.is_empty() { default_spans } else { &primary_spans };
+    let code = diagnostic.code().or(parent_code);
+    let kind = diagnostic.level.parse().ok();
+
+    for (i, line) in diagnostic.message.lines().enumerate() {
+        for span in primary_spans {
+            reported.push(Reported {
+                line: span.line_start,
+                column: span.column_start,
+                // Only the first line of a message has a kind
+                kind: if i == 0 { kind } else { None },
+                code: code.map(str::to_string),
+                msg: line.to_string(),
+            });
+        }
+    }
+    for span in primary_spans {
+        let mut expansion = &span.expansion;
+        while let Some(exp) = expansion {
+            if Path::new(&exp.span.file_name) == file {
+                reported.push(Reported {
+                    line: exp.span.line_start,
+                    column: exp.span.column_start,
+                    kind: Some(Kind::Note),
+                    code: None,
+                    msg: format!("in this expansion of {}", exp.macro_decl_name),
+                });
+            }
+            expansion = &exp.span.expansion;
+        }
+    }
+    for span in &spans_in_file {
+        if let Some(label) = &span.label {
+            reported.push(Reported {
+                line: span.line_start,
+                column: span.column_start,
+                kind: Some(Kind::Note),
+                code: code.map(str::to_string),
+                msg: label.clone(),
+            });
+        }
+    }
+    for child in &diagnostic.children {
+        push_reported(reported, child, code, primary_spans, file);
+    }
+}
+
+/// Matches annotations against the reported diagnostics and returns a description of every
+/// annotation that wasn't matched and every reported diagnostic that wasn't expected.
+pub fn check(annotations: &[Annotation], reported: &[Reported]) -> Vec<String> {
+    let expect_note = annotations.iter().any(|a| a.kind == Kind::Note);
+    let expect_help = annotations.iter().any(|a| a.kind == Kind::Help);
+
+    let mut found = vec![false; annotations.len()];
+    let mut mismatches = vec![];
+    for actual in reported {
+        let matched = annotations
+            .iter()
+            .enumerate()
+            .position(|(i, expected)| !found[i] && expected.matches(actual));
+        match matched {
+            Some(i) => found[i] = true,
+            None => {
+                let unexpected = match actual.kind {
+                    Some(Kind::Error | Kind::Warn) => true,
+                    Some(Kind::Note) => expect_note,
+                    Some(Kind::Help) => expect_help,
+                    None => false,
+                };
+                if unexpected {
+                    mismatches.push(format!("unexpected {actual}"));
+                }
+            }
+        }
+    }
+    for (expected, found) in annotations.iter().zip(found) {
+        if !found {
+            mismatches.push(format!("expected {expected} not found"));
+        }
+    }
+    mismatches
+}
+
+impl Annotation {
+    fn matches(&self, actual: &Reported) -> bool {
+        self.line == actual.line
+            && Some(self.kind) == actual.kind
+            && self.column.map_or(true, |column| column == actual.column)
+            && self
+                .code
+                .as_ref()
+                .map_or(true, |code| Some(code) == actual.code.as_ref())
+            && actual.msg.contains(&self.msg)
+    }
+}
+
+impl FromStr for Kind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Levels in the json output can have a suffix, e.g., `error: internal compiler error`
+        match s.split(':').next().unwrap().to_uppercase().as_str() {
+            "ERROR" => Ok(Kind::Error),
+            "WARN" | "WARNING" => Ok(Kind::Warn),
+            "NOTE" => Ok(Kind::Note),
+            "HELP" => Ok(Kind::Help),
+            _ => Err(format!("unknown diagnostic kind `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::Error => write!(f, "ERROR"),
+            Kind::Warn => write!(f, "WARN"),
+            Kind::Note => write!(f, "NOTE"),
+            Kind::Help => write!(f, "HELP"),
+        }
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {}", self.kind, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ", column {column}")?;
+        }
+        if let Some(code) = &self.code {
+            write!(f, " with code {code}")?;
+        }
+        write!(f, ": `{}`", self.msg)
+    }
+}
+
+impl fmt::Display for Reported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(kind) = self.kind {
+            write!(f, "{kind} ")?;
+        }
+        write!(f, "at {}:{}", self.line, self.column)?;
+        if let Some(code) = &self.code {
+            write!(f, " [{code}]")?;
+        }
+        write!(f, ": `{}`", self.msg)
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonDiagnostic {
+    message: String,
+    code: Option<JsonCode>,
+    level: String,
+    spans: Vec<JsonSpan>,
+    children: Vec<JsonDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct JsonCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct JsonSpan {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    is_primary: bool,
+    label: Option<String>,
+    expansion: Option<Box<JsonExpansion>>,
+}
+
+#[derive(Deserialize)]
+struct JsonExpansion {
+    span: JsonSpan,
+    macro_decl_name: String,
+}
+
+impl JsonDiagnostic {
+    /// The error code of the diagnostic. Flux removes its own error codes and adds a help pointing
+    /// to their explanation instead, so we recover them from there.
+    fn code(&self) -> Option<&str> {
+        if let Some(code) = &self.code {
+            return Some(&code.code);
+        }
+        self.children.iter().find_map(|child| {
+            let (_, rest) = child.message.split_once("cargo flux --explain ")?;
+            rest.split(|c: char| !c.is_ascii_alphanumeric()).next()
+        })
+    }
+}
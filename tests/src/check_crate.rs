@@ -0,0 +1,39 @@
+//! Runner that checks a library crate with flux, e.g., `lib/flux-std`, so the specs it ships are
+//! verified along with the rest of the tests. Unlike the tests in `tests/pos`, the crate is
+//! compiled as a whole from its root file.
+use std::{path::Path, process::Command};
+
+use crate::runner::{self, Config};
+
+/// Checks the crate rooted at `root` and panics if flux reports any error. The crate is skipped if
+/// there are filters and none of them matches `name`.
+pub fn run(config: &Config, name: &str, root: &Path) {
+    if !config.filters.is_empty() && !config.filters.iter().any(|filter| name.contains(filter)) {
+        return;
+    }
+    match check(config, name, root) {
+        Ok(()) => println!("test [check-crate] {name} ... ok"),
+        Err(err) => {
+            println!("test [check-crate] {name} ... FAILED");
+            panic!("flux failed to check `{name}`:\n{err}");
+        }
+    }
+}
+
+fn check(config: &Config, name: &str, root: &Path) -> Result<(), String> {
+    let out_dir = runner::out_dir(Path::new(name))?;
+    let output = Command::new(&config.flux_path)
+        .arg(root)
+        .arg("--crate-name")
+        .arg(name.replace('-', "_"))
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .args(&config.rustc_flags)
+        .output()
+        .map_err(|err| format!("failed to run flux: {err}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+}
@@ -0,0 +1,79 @@
+//! Runner for tests that are expected to fail, i.e., the tests in `tests/neg`.
+//!
+//! Every test is compiled with flux and must fail with exactly the diagnostics annotated in its
+//! source (see [`annotations`]). Diagnostics on the spec file of a test, e.g., `foo.flux` for
+//! `foo.rs`, are annotated in the spec file. We don't use compiletest for these because its
+//! annotations can only match the kind and message of a diagnostic. Tests in a directory with a
+//! `flux.toml` are compiled with `CARGO_MANIFEST_DIR` pointing to it, so the settings of the crate
+//! are read from it as if it were the directory of a package.
+//!
+//! [`annotations`]: crate::annotations
+use std::{fs, iter, path::Path, process::Command};
+
+use crate::{
+    annotations,
+    runner::{self, Config},
+};
+
+/// Runs all the tests in `config.src_base` and panics if any of them fails
+pub fn run_tests(config: &Config) {
+    runner::run_tests("compile-fail", config, run_test);
+}
+
+fn run_test(config: &Config, test: &Path) -> Result<(), String> {
+    let mut files = vec![test.to_path_buf()];
+    let spec_file = test.with_extension("flux");
+    if spec_file.exists() {
+        files.push(spec_file);
+    }
+    let annotations = files
+        .iter()
+        .map(|file| {
+            let source = fs::read_to_string(file).map_err(|err| err.to_string())?;
+            annotations::parse(&source).map_err(|err| format!("{}: {err}", file.display()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let out_dir = runner::out_dir(test)?;
+    let mut cmd = Command::new(&config.flux_path);
+    if let Some(dir) = test.parent().filter(|dir| dir.join("flux.toml").exists()) {
+        cmd.env("CARGO_MANIFEST_DIR", dir);
+    }
+    let output = cmd
+        .arg(test)
+        .arg("--error-format=json")
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .args(&config.rustc_flags)
+        .output()
+        .map_err(|err| format!("failed to run flux: {err}"))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if stderr.contains("internal compiler error") {
+        return Err(format!("flux crashed:\n{stderr}"));
+    }
+    if output.status.success() {
+        return Err("test compiled successfully".to_string());
+    }
+    if !annotations
+        .iter()
+        .flatten()
+        .any(|annotation| annotation.kind == annotations::Kind::Error)
+    {
+        return Err("test has no `//~ ERROR` annotations".to_string());
+    }
+    let mut mismatches = vec![];
+    for (file, annotations) in iter::zip(&files, &annotations) {
+        let reported = annotations::from_json(&stderr, file)?;
+        mismatches.extend(
+            annotations::check(annotations, &reported)
+                .into_iter()
+                .map(|mismatch| format!("{}: {mismatch}", file.display())),
+        );
+    }
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches.join("\n"))
+    }
+}
@@ -1,6 +1,12 @@
 #![feature(register_tool)]
 use std::path::PathBuf;
 
+pub mod annotations;
+pub mod check_crate;
+pub mod compile_fail;
+pub mod runner;
+pub mod snapshot;
+
 // CODESYNC(sysroot-env) we must use the same env var in flux-bin
 pub const FLUX_SYSROOT: &str = "FLUX_SYSROOT";
 
@@ -0,0 +1,105 @@
+//! Shared infrastructure for the test modes we run without compiletest.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+pub struct Config {
+    pub flux_path: PathBuf,
+    pub src_base: PathBuf,
+    pub rustc_flags: Vec<String>,
+    /// Only run tests whose path contains one of the filters. Run all tests if empty.
+    pub filters: Vec<String>,
+    /// Overwrite the expected output of tests instead of comparing against it
+    pub bless: bool,
+}
+
+/// Runs `run_test` on every test in `config.src_base` in parallel and panics if any of them fails
+pub(crate) fn run_tests(
+    mode: &str,
+    config: &Config,
+    run_test: impl Fn(&Config, &Path) -> Result<(), String> + Sync,
+) {
+    let mut tests = vec![];
+    collect_tests(&config.src_base, &mut tests);
+    let total = tests.len();
+    tests.retain(|test| {
+        let name = test_name(config, test);
+        config.filters.is_empty() || config.filters.iter().any(|filter| name.contains(filter))
+    });
+    tests.sort();
+
+    println!("\nrunning {} tests", tests.len());
+    let next = AtomicUsize::new(0);
+    let failures = Mutex::new(vec![]);
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                while let Some(test) = tests.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let name = test_name(config, test);
+                    match run_test(config, test) {
+                        Ok(()) => println!("test [{mode}] {name} ... ok"),
+                        Err(err) => {
+                            println!("test [{mode}] {name} ... FAILED");
+                            failures.lock().unwrap().push((name, err));
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    let mut failures = failures.into_inner().unwrap();
+    failures.sort();
+    for (name, err) in &failures {
+        println!("\n---- [{mode}] {name} ----\n{err}");
+    }
+    println!(
+        "\ntest result: {}. {} passed; {} failed; {} filtered out\n",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        tests.len() - failures.len(),
+        failures.len(),
+        total - tests.len(),
+    );
+    if !failures.is_empty() {
+        panic!("{} {mode} tests failed", failures.len());
+    }
+}
+
+/// A fresh directory for the output of compiling `test`
+pub(crate) fn out_dir(test: &Path) -> Result<PathBuf, String> {
+    let out_dir = std::env::temp_dir()
+        .join("flux-tests")
+        .join(test.with_extension(""));
+    if out_dir.exists() {
+        fs::remove_dir_all(&out_dir).map_err(|err| err.to_string())?;
+    }
+    fs::create_dir_all(&out_dir).map_err(|err| err.to_string())?;
+    Ok(out_dir)
+}
+
+fn collect_tests(dir: &Path, tests: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_tests(&path, tests);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            tests.push(path);
+        }
+    }
+}
+
+/// The name of a test as printed in the output, e.g., `neg/surface/impl_trait00.rs`
+fn test_name(config: &Config, test: &Path) -> String {
+    let base = config.src_base.parent().unwrap_or(&config.src_base);
+    test.strip_prefix(base)
+        .unwrap_or(test)
+        .display()
+        .to_string()
+}
@@ -0,0 +1,111 @@
+//! Snapshot tests of the fixpoint constraints generated for a test, i.e., the tests in
+//! `tests/snapshots`.
+//!
+//! Every test must verify, and the constraints of all its functions (as emitted with
+//! `FLUX_EMIT=fixpoint`) are compared against the ones checked in next to it, e.g., the snapshot
+//! of `foo.rs` is `foo.fq`. A change in the way constraints are generated shows up as a diff of the
+//! snapshot, even if all the tests still pass. Run the tests with `--bless` to update the snapshots
+//! after checking the diff makes sense.
+//!
+//! Constraints are normalized before comparing them. Comments are removed because they include the
+//! spans of the tags, which change whenever the source of a test is edited.
+use std::{fmt::Write as _, fs, path::Path, process::Command};
+
+use crate::runner::{self, Config};
+
+/// Runs all the tests in `config.src_base` and panics if any of them fails
+pub fn run_tests(config: &Config) {
+    runner::run_tests("snapshot", config, run_test);
+}
+
+fn run_test(config: &Config, test: &Path) -> Result<(), String> {
+    let out_dir = runner::out_dir(test)?;
+    let emit_dir = out_dir.join("emit");
+    let output = Command::new(&config.flux_path)
+        .arg(test)
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .args(&config.rustc_flags)
+        .env("FLUX_EMIT", "fixpoint")
+        .env("FLUX_EMIT_DIR", &emit_dir)
+        .env("FLUX_CACHE", "false")
+        .output()
+        .map_err(|err| format!("failed to run flux: {err}"))?;
+    if !output.status.success() {
+        return Err(format!("test failed to verify:\n{}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let actual = snapshot(&emit_dir)?;
+    let snapshot_path = test.with_extension("fq");
+    if config.bless {
+        return fs::write(&snapshot_path, actual).map_err(|err| err.to_string());
+    }
+    let Ok(expected) = fs::read_to_string(&snapshot_path) else {
+        return Err(format!(
+            "snapshot `{}` not found, run the tests with `--bless` to create it",
+            snapshot_path.display()
+        ));
+    };
+    match first_difference(&expected, &actual) {
+        None => Ok(()),
+        Some((line, expected, actual)) => {
+            Err(format!(
+                "constraints differ from snapshot `{}` at line {line}\n\
+                 expected: {expected}\n\
+                 actual:   {actual}\n\
+                 run the tests with `--bless` to update the snapshot",
+                snapshot_path.display()
+            ))
+        }
+    }
+}
+
+/// The normalized constraints in `emit_dir`, sorted by the name of their items
+fn snapshot(emit_dir: &Path) -> Result<String, String> {
+    let mut files = vec![];
+    if emit_dir.exists() {
+        for entry in fs::read_dir(emit_dir).map_err(|err| err.to_string())? {
+            let path = entry.map_err(|err| err.to_string())?.path();
+            if path.extension().is_some_and(|ext| ext == "fq") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+
+    let mut snapshot = String::new();
+    for file in files {
+        let contents = fs::read_to_string(&file).map_err(|err| err.to_string())?;
+        // The name of the file is `{crate}.{item}.fq`
+        let name = file.file_stem().unwrap().to_string_lossy();
+        let item = name.split_once('.').map_or(&*name, |(_, item)| item);
+        writeln!(snapshot, "// {item}").unwrap();
+        writeln!(snapshot, "{}", normalize(&contents)).unwrap();
+    }
+    Ok(snapshot)
+}
+
+/// Removes comments, trailing whitespace and blank lines
+fn normalize(constraint: &str) -> String {
+    constraint
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty() && !line.trim_start().starts_with("//"))
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
+fn first_difference<'a>(expected: &'a str, actual: &'a str) -> Option<(usize, &'a str, &'a str)> {
+    let mut expected = expected.lines();
+    let mut actual = actual.lines();
+    let mut line = 1;
+    loop {
+        match (expected.next(), actual.next()) {
+            (None, None) => return None,
+            (e, a) if e == a => line += 1,
+            (e, a) => {
+                return Some((line, e.unwrap_or("<end of file>"), a.unwrap_or("<end of file>")))
+            }
+        }
+    }
+}
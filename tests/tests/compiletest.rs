@@ -5,7 +5,9 @@ use std::{env, path::PathBuf};
 
 use compiletest_rs::{common::Mode, Config};
 use itertools::Itertools;
-use tests::{find_flux_path, rustc_flags, FLUX_SYSROOT};
+use tests::{
+    check_crate, compile_fail, find_flux_path, runner, rustc_flags, snapshot, FLUX_SYSROOT,
+};
 
 fn config() -> Config {
     let bless = env::args().any(|arg| arg == "--bless");
@@ -30,11 +32,39 @@ fn test_runner(_: &[&()]) {
         compiletest_rs::run_tests(&config);
     }
 
-    let path: PathBuf = ["tests", "neg"].iter().collect();
+    // Worked examples, see `book/src/examples.md`
+    let path: PathBuf = ["tests", "examples"].iter().collect();
     if path.exists() {
-        config.mode = Mode::CompileFail;
+        config.mode = Mode::Ui;
         config.src_base = path;
         compiletest_rs::run_tests(&config);
     }
+
     config.clean_rmeta();
+
+    let runner_config = |src_base| {
+        runner::Config {
+            flux_path: config.rustc_path.clone(),
+            src_base,
+            rustc_flags: rustc_flags(),
+            filters: config.filters.clone(),
+            bless: config.bless,
+        }
+    };
+
+    let path: PathBuf = ["tests", "neg"].iter().collect();
+    if path.exists() {
+        compile_fail::run_tests(&runner_config(path));
+    }
+
+    // Constraints generated for each test, see `tests/src/snapshot.rs`. The directory must exist
+    // so that snapshot tests aren't silently skipped if it's moved.
+    let path: PathBuf = ["tests", "snapshots"].iter().collect();
+    assert!(path.exists(), "snapshot tests not found in `{}`", path.display());
+    snapshot::run_tests(&runner_config(path));
+
+    // The specs shipped in `lib/flux-std` are only checked if the crate is compiled with flux
+    let path: PathBuf = ["..", "lib", "flux-std", "src", "lib.rs"].iter().collect();
+    assert!(path.exists(), "flux-std not found in `{}`", path.display());
+    check_crate::run(&runner_config(path.clone()), "flux-std", &path);
 }
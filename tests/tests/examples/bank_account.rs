@@ -0,0 +1,73 @@
+//! A bank account whose state is tracked in its type.
+//!
+//! Features: structs refined by several indices, strong references (`&strg`) to update the
+//! indices of a value in place, and preconditions on methods.
+
+/// An account with a `balance` that is `open` until it is closed
+#[flux::refined_by(balance: int, open: bool)]
+pub struct Account {
+    #[flux::field(u32[balance])]
+    balance: u32,
+    #[flux::field(bool[open])]
+    open: bool,
+}
+
+impl Account {
+    #[flux::sig(fn() -> Account[0, true])]
+    pub fn open() -> Account {
+        Account { balance: 0, open: true }
+    }
+
+    #[flux::sig(fn(&Account[@b, @o]) -> u32[b])]
+    pub fn balance(&self) -> u32 {
+        self.balance
+    }
+
+    #[flux::sig(fn(&Account[@b, @o]) -> bool[o])]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Money can only be deposited in an open account
+    #[flux::sig(fn(self: &strg Account[@b, true], amount: u32) ensures self: Account[b + amount, true])]
+    pub fn deposit(&mut self, amount: u32) {
+        self.balance += amount;
+    }
+
+    /// Withdrawing more than the balance is rejected statically
+    #[flux::sig(fn(self: &strg Account[@b, true], amount: u32{amount <= b}) ensures self: Account[b - amount, true])]
+    pub fn withdraw(&mut self, amount: u32) {
+        self.balance -= amount;
+    }
+
+    /// Only an open account with no money left can be closed
+    #[flux::sig(fn(Account[0, true]) -> Account[0, false])]
+    pub fn close(self) -> Account {
+        Account { balance: self.balance, open: false }
+    }
+}
+
+/// Moves `amount` from `src` to `dst`
+#[flux::sig(
+    fn(src: &strg Account[@a, true], dst: &strg Account[@b, true], amount: u32{amount <= a})
+    ensures src: Account[a - amount, true], dst: Account[b + amount, true]
+)]
+pub fn transfer(src: &mut Account, dst: &mut Account, amount: u32) {
+    src.withdraw(amount);
+    dst.deposit(amount);
+}
+
+#[flux::sig(fn() -> u32[30])]
+pub fn client() -> u32 {
+    let mut checking = Account::open();
+    let mut savings = Account::open();
+    checking.deposit(50);
+    transfer(&mut checking, &mut savings, 20);
+    checking.withdraw(30);
+    let checking = checking.close();
+    assert(!checking.is_open());
+    savings.balance()
+}
+
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_: bool) {}
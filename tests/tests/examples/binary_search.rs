@@ -0,0 +1,47 @@
+//! Binary search over a vector of integers.
+//!
+//! Features: refined vectors (`RVec<T>[n]`), index bounds checking, and loop invariants inferred
+//! by the checker.
+#![allow(unused_attributes)]
+
+#[path = "../lib/rvec.rs"]
+pub mod rvec;
+use rvec::RVec;
+
+/// Returns the position of `k` in the sorted vector `items` or its length if `k` is not found.
+/// All accesses to `items` are in bounds, and so is the returned position unless it is `n`.
+#[flux::sig(fn(i32, &RVec<i32>[@n]) -> usize{v: v <= n})]
+pub fn binary_search(k: i32, items: &RVec<i32>) -> usize {
+    let size = items.len();
+    if size == 0 {
+        return size;
+    }
+
+    let mut low: usize = 0;
+    let mut high: usize = size - 1;
+
+    while low <= high {
+        // `(low + high) / 2` would also be in bounds, but it may overflow
+        let middle = low + ((high - low) / 2);
+        let current = items[middle];
+        if current == k {
+            return middle;
+        }
+        if current > k {
+            if middle == 0 {
+                return size;
+            }
+            high = middle - 1;
+        }
+        if current < k {
+            low = middle + 1;
+        }
+    }
+    size
+}
+
+#[flux::sig(fn(&RVec<i32>[@n], i32) -> bool)]
+pub fn contains(items: &RVec<i32>, k: i32) -> bool {
+    let i = binary_search(k, items);
+    i < items.len() && items[i] == k
+}
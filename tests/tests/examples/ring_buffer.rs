@@ -0,0 +1,95 @@
+//! A fixed-capacity ring buffer of integers.
+//!
+//! Features: struct fields refined by other fields, structs with several indices, method
+//! preconditions with `requires`, and in-place updates through `&strg` references.
+#![allow(unused_attributes)]
+
+#[path = "../lib/rvec.rs"]
+pub mod rvec;
+use rvec::RVec;
+
+/// A buffer with capacity `cap` holding `len` elements. The elements are stored in `data` starting
+/// at position `head` and wrapping around the end.
+#[flux::refined_by(cap: int, len: int)]
+pub struct RingBuffer {
+    #[flux::field({RVec<i32>[cap] | cap > 0})]
+    data: RVec<i32>,
+    #[flux::field(usize{v: v < cap})]
+    head: usize,
+    #[flux::field({usize[len] | len <= cap})]
+    len: usize,
+}
+
+impl RingBuffer {
+    #[flux::sig(fn(cap: usize{cap > 0}) -> RingBuffer[cap, 0])]
+    pub fn new(cap: usize) -> RingBuffer {
+        RingBuffer { data: RVec::from_elem_n(0, cap), head: 0, len: 0 }
+    }
+
+    #[flux::sig(fn(&RingBuffer[@c, @n]) -> usize[n])]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[flux::sig(fn(&RingBuffer[@c, @n]) -> bool[n == 0])]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[flux::sig(fn(&RingBuffer[@c, @n]) -> bool[n == c])]
+    pub fn is_full(&self) -> bool {
+        self.len == self.data.len()
+    }
+
+    /// Pushes `x` at the back. The buffer must not be full.
+    #[flux::sig(
+        fn(self: &strg RingBuffer[@c, @n], x: i32)
+        requires n < c
+        ensures self: RingBuffer[c, n + 1]
+    )]
+    pub fn push(&mut self, x: i32) {
+        let cap = self.data.len();
+        // `head < cap` and `len < cap` so subtracting `cap` once is enough to wrap around
+        let mut tail = self.head + self.len;
+        if tail >= cap {
+            tail -= cap;
+        }
+        self.data[tail] = x;
+        self.len += 1;
+    }
+
+    /// Pops the element at the front. The buffer must not be empty.
+    #[flux::sig(
+        fn(self: &strg RingBuffer[@c, @n]) -> i32
+        requires n > 0
+        ensures self: RingBuffer[c, n - 1]
+    )]
+    pub fn pop(&mut self) -> i32 {
+        let x = self.data[self.head];
+        let mut head = self.head + 1;
+        if head == self.data.len() {
+            head = 0;
+        }
+        self.head = head;
+        self.len -= 1;
+        x
+    }
+}
+
+/// Adds up the elements in the buffer
+pub fn sum(mut buf: RingBuffer) -> i32 {
+    let mut sum = 0;
+    while !buf.is_empty() {
+        sum += buf.pop();
+    }
+    sum
+}
+
+pub fn client() -> i32 {
+    let mut buf = RingBuffer::new(2);
+    buf.push(1);
+    buf.push(2);
+    let x = buf.pop();
+    buf.push(3);
+    x + sum(buf)
+}
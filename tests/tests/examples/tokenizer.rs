@@ -0,0 +1,76 @@
+//! A tokenizer splitting a string of bytes into words and numbers.
+//!
+//! Features: slices refined by their length, functions relating their output to their inputs,
+//! and loops over indices of a slice.
+
+/// Returns the first position at or after `i` with a non-whitespace byte, or `n`
+#[flux::sig(fn(s: &[u8][@n], i: usize{i <= n}) -> usize{v: i <= v && v <= n})]
+pub fn skip_whitespace(s: &[u8], i: usize) -> usize {
+    let mut i = i;
+    while i < s.len() && is_whitespace(s[i]) {
+        i += 1;
+    }
+    i
+}
+
+/// Returns the end of the token starting at `i`. A token is a maximal run of alphanumeric bytes,
+/// or a single byte of any other kind. The token is never empty, so the tokenizer always makes
+/// progress.
+#[flux::sig(fn(s: &[u8][@n], i: usize{i < n}) -> usize{v: i < v && v <= n})]
+pub fn token_end(s: &[u8], i: usize) -> usize {
+    if !is_alphanumeric(s[i]) {
+        return i + 1;
+    }
+    let mut j = i + 1;
+    while j < s.len() && is_alphanumeric(s[j]) {
+        j += 1;
+    }
+    j
+}
+
+/// Counts the tokens in `s`
+#[flux::sig(fn(s: &[u8][@n]) -> usize{v: v <= n})]
+pub fn count_tokens(s: &[u8]) -> usize {
+    let mut count = 0;
+    let mut i = skip_whitespace(s, 0);
+    while i < s.len() {
+        i = token_end(s, i);
+        // Every token has at least one byte, so there are at most as many tokens as bytes
+        count += 1;
+        i = skip_whitespace(s, i);
+    }
+    count
+}
+
+/// Returns the numeric value of the token `s[start..end]` if it is a number
+#[flux::sig(fn(s: &[u8][@n], start: usize, end: usize{start <= end && end <= n}) -> Option<u32>)]
+pub fn parse_number(s: &[u8], start: usize, end: usize) -> Option<u32> {
+    if start == end {
+        return None;
+    }
+    let mut value: u32 = 0;
+    let mut i = start;
+    while i < end {
+        let b = s[i];
+        if !is_digit(b) {
+            return None;
+        }
+        value = value.wrapping_mul(10).wrapping_add((b - b'0') as u32);
+        i += 1;
+    }
+    Some(value)
+}
+
+#[flux::sig(fn(u8[@b]) -> bool[b == 32 || b == 9 || b == 10 || b == 13])]
+fn is_whitespace(b: u8) -> bool {
+    b == b' ' || b == b'\t' || b == b'\n' || b == b'\r'
+}
+
+#[flux::sig(fn(u8[@b]) -> bool[48 <= b && b <= 57])]
+fn is_digit(b: u8) -> bool {
+    b'0' <= b && b <= b'9'
+}
+
+fn is_alphanumeric(b: u8) -> bool {
+    is_digit(b) || (b'a' <= b && b <= b'z') || (b'A' <= b && b <= b'Z')
+}
@@ -36,7 +36,7 @@ impl<K, V> RMap<K, V> {
     }
 
     #[flux::trusted]
-    #[flux::sig(fn(&RMap<K, V>[@m], &K[@k]) -> Option<&V[map_get(m.vals, k)]>)]
+    #[flux::sig(fn(&RMap<K, V>[@m], &K[@k]) -> Option<&V[map_get(m.vals, k)]>[set_is_in(k, m.keys)])]
     pub fn get(&self, k: &K) -> Option<&V>
     where
         K: Eq + Hash,
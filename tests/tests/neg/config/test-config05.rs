@@ -0,0 +1,3 @@
+#![flux::cfg(unstable_features = "smt-backend,teleportation")] //~ ERROR invalid flux configuration: incorrect type in value for setting `unstable_features`, expected UnstableFeatures
+
+pub fn test() {}
@@ -0,0 +1,3 @@
+#![flux::cfg(solver = "mathsat")] //~ ERROR invalid flux configuration: incorrect type in value for setting `solver`, expected SmtSolver
+
+pub fn test() {}
@@ -0,0 +1,11 @@
+#[flux::opts(timeout = "soon")] //~ ERROR invalid flux configuration: incorrect type in value for setting `timeout`, expected u64
+pub fn test00() {}
+
+#[flux::opts(fuel = 10)] //~ ERROR invalid flux configuration: invalid option `fuel`
+pub fn test01() {}
+
+#[flux::opts(solver = "yices")] //~ ERROR invalid flux configuration: incorrect type in value for setting `solver`, expected SmtSolver
+pub fn test02() {}
+
+#[flux::opts(check_overflow = true)] //~ ERROR invalid flux configuration: `check_overflow` can only be set for the whole crate with `flux::cfg`
+pub fn test03() {}
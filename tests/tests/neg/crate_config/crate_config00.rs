@@ -0,0 +1,6 @@
+// `check_overflow` is enabled in the `flux.toml` next to this file
+
+#[flux::sig(fn(a: i32) -> i32[-a])]
+pub fn neg_overflow_i32(a: i32) -> i32 {
+    -a //~ ERROR overflow
+}
@@ -1,6 +1,6 @@
 #![flux::defs {
     fn set_add(x: int, s: Set<int>) -> Set<int> { set_union(set_singleton(x), s) }
-    fn set_is_empty(s: Set<int>) -> bool { s == set_empty(0) }
+    fn set_is_empty(s: Set<int>) -> bool { s == set_empty(0) } //~ WARN spec function `set_is_empty` is never used
     fn set_emp() -> Set<int> { set_empty(0) }
 }]
 
@@ -0,0 +1,28 @@
+// The rust signature gained an argument but the refined signature wasn't updated
+#[flux::sig(fn(x: i32) -> i32)] //~ ERROR argument count mismatch
+pub fn added_arg(x: i32, y: i32) -> i32 {
+    x + y
+}
+
+// The rust signature lost an argument
+#[flux::sig(fn(x: i32, y: i32, z: i32) -> i32)] //~ ERROR argument count mismatch
+pub fn removed_arg(x: i32, y: i32) -> i32 {
+    x + y
+}
+
+// The type of the second argument changed
+#[flux::sig(fn(x: i32, y: i32) -> i32)] //~ ERROR invalid refinement annotation
+pub fn changed_arg(x: i32, y: i64) -> i32 {
+    x
+}
+
+// Both arguments changed, each one is reported
+#[flux::sig(fn(x: i32, y: i32))] //~ ERROR invalid refinement annotation
+//~^ ERROR invalid refinement annotation
+pub fn changed_args(x: u32, y: u32) {}
+
+// The return type changed
+#[flux::sig(fn(x: i32) -> i32{v: v > x})] //~ ERROR invalid refinement annotation
+pub fn changed_ret(x: i32) -> i64 {
+    x as i64 + 1
+}
@@ -0,0 +1,16 @@
+// Annotations can match the error code and column of a diagnostic
+
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+pub fn test00(x: i32) -> i32 {
+    x //~ ERROR[EFLUX0002]@5 refinement type
+}
+
+#[flux::sig(fn(i32{v: v > 0}))]
+pub fn assert_pos(_x: i32) {}
+
+pub fn test01(x: i32) {
+    let y = 0;
+    assert_pos(x); //~ ERROR[EFLUX0001]@5
+    assert_pos(y);
+    //~^ ERROR[EFLUX0001]@5 refinement type
+}
@@ -0,0 +1,13 @@
+#[flux::defs {
+    fn is_pos(x: int) -> bool { x > 0 }
+}]
+mod m {}
+
+#[flux::qualifiers(Pos)] //~ ERROR cannot find qualifier `Pos`
+#[flux::sig(fn(i32))]
+pub fn test00(_x: i32) {}
+
+#[flux::defs {
+    fn is_pos(x: int) -> bool { x >= 0 } //~ ERROR the name `is_pos` is defined multiple times
+}]
+mod n {}
@@ -0,0 +1,26 @@
+#![flux::cfg(unstable_features = "match-exprs")]
+
+#[flux::reflect]
+pub enum Ordering {
+    Less,
+    Equal,
+    Greater,
+}
+
+#[flux::sig(fn(o: Ordering) -> i32{v: match o { Ordering::Less => v < 0, Ordering::Greater => v > 0 }})] //~ ERROR non-exhaustive `match` in refinement
+pub fn test00(o: Ordering) -> i32 {
+    match o {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+#[flux::sig(fn(o: Ordering) -> i32{v: match o { Ordering::Less => v < 0, Ordering::Less => v == 0, Ordering::Greater => v > 0 }})] //~ ERROR invalid pattern in refinement `match`
+pub fn test01(o: Ordering) -> i32 {
+    match o {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
@@ -0,0 +1,11 @@
+// Errors have a distinct code for each kind of failure
+
+#[flux::sig(fn(x: &mut i32{v: v > 0}))]
+pub fn test00(x: &mut i32) {
+    *x = 0; //~ ERROR[EFLUX0007] assignment might be unsafe
+}
+
+#[flux::sig(fn(&[i32][@n], usize) -> i32)]
+pub fn test01(s: &[i32], i: usize) -> i32 {
+    s[i] //~ ERROR[EFLUX0010] assertion might fail
+}
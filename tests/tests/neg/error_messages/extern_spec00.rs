@@ -0,0 +1,21 @@
+use flux_rs::extern_spec;
+
+#[extern_spec(std::cmp)]
+#[flux::sig(fn(i32[@a], i32[@b]) -> i32{v: v >= a && v >= b})]
+fn max(a: i32, b: i32) -> i32;
+
+#[flux::extern_spec]
+#[flux::sig(fn(i32[@a], i32[@b]) -> i32{v: v >= a})]
+fn max2(a: i32, b: i32) -> i32 { //~ ERROR duplicate extern spec
+    std::cmp::max(a, b)
+}
+
+pub fn local(x: i32) -> i32 {
+    x
+}
+
+#[flux::extern_spec]
+#[flux::sig(fn(i32[@a]) -> i32[a])]
+fn local2(x: i32) -> i32 { //~ ERROR cannot declare an extern spec
+    local(x)
+}
@@ -0,0 +1,42 @@
+use flux_rs::extern_spec;
+
+mod external {
+    pub struct Pair {
+        pub fst: i32,
+        pub snd: i32,
+    }
+
+    pub struct Point {
+        pub x: u32,
+        pub y: u32,
+    }
+
+    pub struct Wrapper<T> {
+        pub inner: T,
+    }
+}
+
+use external::{Pair, Point, Wrapper};
+
+#[extern_spec(external)]
+#[flux::refined_by(fst: int)]
+struct Pair { //~ ERROR extern spec for `external::Pair` has 1 fields
+    #[flux::field(i32[fst])]
+    fst: i32,
+}
+
+#[extern_spec(external)]
+#[flux::refined_by(x: int, y: int)]
+struct Point {
+    #[flux::field(u32[y])]
+    y: u32, //~ ERROR field doesn't match
+    #[flux::field(u32[x])]
+    x: u32,
+}
+
+#[extern_spec(external)]
+#[flux::refined_by(n: int)]
+struct Wrapper<T> {
+    #[flux::field(i32[n])]
+    inner: i32, //~ ERROR field doesn't match
+}
@@ -0,0 +1,11 @@
+#[flux::intrinsic(assume)]
+fn not_unsafe(_: bool) {} //~ ERROR the `assume` intrinsic must be declared as `unsafe fn(bool)`
+
+#[flux::intrinsic(assume)]
+unsafe fn not_bool(_: i32) {} //~ ERROR the `assume` intrinsic must be declared as `unsafe fn(bool)`
+
+#[flux::intrinsic(pack)]
+fn not_ref<T>(_: T) {} //~ ERROR the `pack` intrinsic must be declared as `fn(&T)`
+
+#[flux::intrinsic(assert)]
+unsafe fn unsafe_assert(_: bool) {} //~ ERROR the `assert` intrinsic must be declared as `fn(bool)`
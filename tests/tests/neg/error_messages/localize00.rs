@@ -4,9 +4,10 @@
 pub fn pre(_n: i32) {}
 
 pub fn test(n: i32) {
-    if n < 15 {
+    if n < 15 { //~ NOTE assuming this condition holds
         pre(n); //~ ERROR refinement type
                 //~| NOTE a precondition cannot be proved
+                //~| NOTE cannot prove `10 <= v` required by `pre`
     }
 }
 
@@ -18,14 +19,17 @@ pub fn pre2(_n: i32, _m: i32) {}
 pub fn test2(n: i32) {
     pre2(n, n + n); //~ ERROR refinement type
                     //~| NOTE a precondition cannot be proved
+                    //~| NOTE required by `pre2`
 }
 
 // ------ Test 3 -------------------------------------------------
 
 #[flux::sig(fn(n:i32{0 <= n}) -> i32{v: 100 <= v && v <= 200})] //~ NOTE this is the condition
+                                                                 //~| NOTE assuming this condition holds
 pub fn post(n: i32) -> i32 {
     n + 100 //~ ERROR refinement type
             //~| NOTE a postcondition cannot be proved
+            //~| NOTE cannot prove `v <= 200` required by `post`
 }
 
 // ------ Test 4 -------------------------------------------------
@@ -35,4 +39,5 @@ pub fn floo(_n: i32) {}
 pub fn test_floo() {
     floo(1000); //~ ERROR refinement type
                 //~| NOTE a precondition cannot be proved
+                //~| NOTE cannot prove `n < 400` required by `floo`
 }
@@ -19,10 +19,12 @@ fn assertp(_x: i32) {}
 fn test() {
     assertp(12); //~ ERROR refinement type
                  //~| NOTE a precondition cannot be proved
+                 //~| NOTE cannot prove `chunky(x)` required by `assertp`
 }
 
 #[flux::sig(fn() -> i32[inc1(0)])] //~ NOTE inside this call
 fn moo() -> i32 {
     2 //~ ERROR refinement type
       //~| NOTE a postcondition cannot be proved
+      //~| NOTE cannot prove `inc1(0)` required by `moo`
 }
@@ -0,0 +1,15 @@
+#![allow(unused)]
+
+#[path = "../../lib/rvec.rs"]
+mod rvec;
+use rvec::RVec;
+
+#[flux::sig(fn(&RVec<i32>[@n], usize{v: v <= n}) -> i32)] //~ NOTE assuming this condition holds
+fn get(vec: &RVec<i32>, i: usize) -> i32 {
+    *vec.get(i) //~ ERROR refinement type
+                //~| NOTE a precondition cannot be proved
+                //~| NOTE this is the condition
+                //~| NOTE cannot prove `i < n` required by
+                //~| HELP has signature `fn(
+                //~| HELP cargo flux --explain EFLUX0001
+}
@@ -0,0 +1,11 @@
+#[flux::sig(fn(i32{v: v > 0}))] //~ NOTE this is the condition
+pub fn pos(_n: i32) {}
+
+pub fn test(n: i32) {
+    if n < 0 { //~ NOTE assuming this condition holds
+        return;
+    }
+    pos(n); //~ ERROR refinement type
+            //~| NOTE a precondition cannot be proved
+            //~| NOTE cannot prove `v > 0` required by `pos`
+}
@@ -0,0 +1,24 @@
+#[flux::sig(fn(i32) -> i32{v: v >= 0})]
+pub fn abs(x: i32) -> i32 {
+    if x < 0 { -x } else { x }
+}
+
+// The spec of `abs` is strong enough to prove this
+#[flux::should_fail]
+#[flux::sig(fn(x: i32) -> i32{v: v >= 0})]
+pub fn abs_is_nonnegative(x: i32) -> i32 { //~ ERROR function was expected to fail verification
+    abs(x)
+}
+
+// Expected failures are not reported
+#[flux::should_fail]
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+pub fn abs_is_positive(x: i32) -> i32 {
+    abs(x)
+}
+
+// Errors in functions not marked with `#[flux::should_fail]` are still reported
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+pub fn abs_is_positive2(x: i32) -> i32 {
+    abs(x) //~ ERROR refinement type
+}
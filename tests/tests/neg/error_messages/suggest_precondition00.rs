@@ -0,0 +1,14 @@
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})] //~ HELP consider adding `x: i32{x > 0}`
+pub fn test00(x: i32) -> i32 {
+    x //~ ERROR refinement type
+      //~| HELP has signature
+      //~| HELP cargo flux --explain EFLUX0002
+}
+
+// The postcondition depends on two arguments so there's nothing to suggest
+#[flux::sig(fn(x: i32, y: i32) -> i32{v: v > 0})]
+pub fn test01(x: i32, y: i32) -> i32 {
+    x + y //~ ERROR refinement type
+          //~| HELP has signature
+          //~| HELP cargo flux --explain EFLUX0002
+}
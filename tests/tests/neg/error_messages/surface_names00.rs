@@ -0,0 +1,31 @@
+#[flux::sig(fn(n: i32{n > 0}))]
+pub fn assert_pos(_n: i32) {}
+
+#[flux::sig(fn(x: i32))]
+pub fn test00(x: i32) {
+    assert_pos(x); //~ ERROR cannot prove `x > 0`
+}
+
+#[flux::sig(fn(a: i32, b: i32) -> i32{v: v > a})]
+pub fn test01(a: i32, b: i32) -> i32 {
+    b //~ ERROR cannot prove `b > a`
+}
+
+#[flux::refined_by(lo: int, hi: int)]
+pub struct Range {
+    #[flux::field(i32[lo])]
+    lo: i32,
+    #[flux::field(i32[hi])]
+    hi: i32,
+}
+
+#[flux::sig(fn(r: Range) -> i32{v: v >= r.lo})]
+pub fn test02(r: Range) -> i32 {
+    r.hi //~ ERROR cannot prove `r.hi >= 
+}
+
+// The unnamed argument is printed with its internal name
+#[flux::sig(fn(x: i32, i32) -> i32{v: v > x})]
+pub fn test03(_x: i32, y: i32) -> i32 {
+    y //~ ERROR refinement type error: cannot prove
+}
@@ -0,0 +1,2 @@
+#[flux::unpack_policy(sideways)] //~ ERROR invalid flux attribute
+pub fn test00() {}
@@ -0,0 +1,13 @@
+#[flux::reflect]
+pub enum Ordering {
+    Less,
+    Greater,
+}
+
+#[flux::sig(fn(o: Ordering) -> i32{v: match o { Ordering::Less => v < 0, Ordering::Greater => v > 0 }})] //~ ERROR use of unstable feature `match-exprs`
+pub fn sign(o: Ordering) -> i32 {
+    match o {
+        Ordering::Less => -1,
+        Ordering::Greater => 1,
+    }
+}
@@ -0,0 +1,24 @@
+#![flux::defs {
+    local qualifier Unused(x: int) { x > 0 } //~ WARN qualifier `Unused` is never used
+    local qualifier Used(x: int, y: int) { x < y }
+    pub local qualifier Exported(x: int) { x > 0 }
+    qualifier Global(x: int) { x > 0 }
+
+    fn unused(x: int) -> bool { x > 0 } //~ WARN spec function `unused` is never used
+    fn used(x: int) -> bool { helper(x) }
+    fn helper(x: int) -> bool { x > 0 }
+    pub fn exported(x: int) -> bool { x > 0 }
+}]
+
+#[allow(dead_code)]
+#[flux::alias(type Pos = i32{v: v > 0})] //~ WARN type alias `Pos` is never used
+type Pos = i32;
+
+#[flux::alias(type Nat = i32{v: v >= 0})]
+type Nat = i32;
+
+#[flux::qualifiers(Used)]
+#[flux::sig(fn(x: i32{used(x)}) -> Nat)]
+pub fn test00(x: i32) -> i32 {
+    x - 2 //~ ERROR refinement type
+}
@@ -0,0 +1,36 @@
+#[derive(PartialEq, Eq)]
+#[flux::refined_by(x: int, y: int)]
+pub struct Point {
+    #[flux::field(i32[x])]
+    x: i32,
+    #[flux::field(i32[y])]
+    y: i32,
+}
+
+#[flux::sig(fn(&Point[@p], &Point[@q]) -> bool[p.x == q.x])]
+pub fn same(p: &Point, q: &Point) -> bool {
+    p == q //~ ERROR refinement type
+}
+
+#[flux::sig(fn(&Point[@p], &Point{q: q.x > 0}) -> i32{v: v > 0})]
+pub fn refine_branch(p: &Point, q: &Point) -> i32 {
+    if p != q {
+        p.x //~ ERROR refinement type
+    } else {
+        1
+    }
+}
+
+// The index doesn't determine the value so we don't use the built-in spec
+#[derive(PartialEq)]
+#[flux::refined_by(x: int)]
+pub struct Partial {
+    #[flux::field(i32[x])]
+    x: i32,
+    y: i32,
+}
+
+#[flux::sig(fn(&Partial[@p], &Partial[@q]) -> bool[p.x == q.x])]
+pub fn partial(p: &Partial, q: &Partial) -> bool {
+    p == q //~ ERROR refinement type
+}
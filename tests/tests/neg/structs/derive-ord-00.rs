@@ -0,0 +1,45 @@
+use std::cmp::Ordering;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[flux::refined_by(k: int)]
+pub struct Key {
+    #[flux::field(u32[k])]
+    k: u32,
+}
+
+#[flux::sig(fn(&Key[@a], &Key[@b]) -> bool[a.k <= b.k])]
+pub fn lt(a: &Key, b: &Key) -> bool {
+    a < b //~ ERROR refinement type
+}
+
+#[flux::sig(fn(Key[@a], Key[@b]) -> Key{v: v.k >= a.k && v.k >= b.k})]
+pub fn min_key(a: Key, b: Key) -> Key {
+    if a < b {
+        a //~ ERROR refinement type
+    } else {
+        b
+    }
+}
+
+// Pairs are ordered lexicographically so we don't use the built-in spec
+#[derive(PartialEq, PartialOrd)]
+#[flux::refined_by(x: int, y: int)]
+pub struct Pair {
+    #[flux::field(i32[x])]
+    x: i32,
+    #[flux::field(i32[y])]
+    y: i32,
+}
+
+#[flux::sig(fn(&Pair[@a], &Pair[@b]) -> bool[a.x < b.x || (a.x == b.x && a.y < b.y)])]
+pub fn lt_pair(a: &Pair, b: &Pair) -> bool {
+    a < b //~ ERROR refinement type
+}
+
+#[flux::sig(fn(Key[@a], Key[@b]) -> Key{v: v.k <= a.k && v.k <= b.k})]
+pub fn min_key_cmp(a: Key, b: Key) -> Key {
+    match a.cmp(&b) {
+        Ordering::Less => a,
+        Ordering::Equal | Ordering::Greater => a, //~ ERROR refinement type
+    }
+}
@@ -0,0 +1,22 @@
+pub struct Handler {
+    #[flux::field(fn(i32{v: v > 0}) -> i32{v: v > 0})]
+    pub f: fn(i32) -> i32,
+}
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v >= x - 1})]
+fn decr(x: i32) -> i32 {
+    x - 1
+}
+
+#[flux::sig(fn(x: i32{x > 10}) -> i32{v: v > 0})]
+fn big(x: i32) -> i32 {
+    x
+}
+
+pub fn test00() -> Handler {
+    Handler { f: decr } //~ ERROR refinement type
+}
+
+pub fn test01() -> Handler {
+    Handler { f: big } //~ ERROR refinement type
+}
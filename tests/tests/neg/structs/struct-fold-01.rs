@@ -0,0 +1,21 @@
+pub struct Inner {
+    #[flux::field(i32{v : v > 0})]
+    a: i32,
+    #[flux::field(i32)]
+    b: i32,
+}
+
+pub struct Outer {
+    #[flux::field(Inner)]
+    inner: Inner,
+    #[flux::field(Option<Box<Inner>>)]
+    next: Option<Box<Inner>>,
+}
+
+pub fn baz(o: Outer) {}
+
+pub fn foo() {
+    let mut x = Outer { inner: Inner { a: 1, b: 0 }, next: None };
+    x.inner.a = 0;
+    baz(x); //~ ERROR type invariant may not hold
+}
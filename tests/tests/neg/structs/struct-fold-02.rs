@@ -0,0 +1,21 @@
+pub struct Inner {
+    #[flux::field(i32{v : v > 0})]
+    a: i32, //~ NOTE field `a` of `Inner` may not have its declared type
+    #[flux::field(i32)]
+    b: i32,
+}
+
+pub struct Outer {
+    #[flux::field(Box<Inner>)]
+    inner: Box<Inner>,
+    #[flux::field(i32)]
+    c: i32,
+}
+
+pub fn baz(o: Outer) {}
+
+pub fn foo(mut x: Outer) {
+    x.inner.a = 0;
+    x.c = 1;
+    baz(x); //~ ERROR type invariant may not hold
+}
@@ -0,0 +1,27 @@
+#[flux::alias(type Nat = i32{v: 0 <= v})]
+type Nat = i32;
+
+#[flux::alias(type Lb(n: int) = i32{v: n <= v})]
+type Lb = i32;
+
+#[flux::sig(fn(x: Option<Nat>) -> Nat)]
+pub fn test0(x: Option<Nat>) -> Nat {
+    match x {
+        Some(n) => n - 1, //~ ERROR refinement type
+        None => 0,
+    }
+}
+
+#[flux::sig(fn(x: &mut Lb(10)))]
+pub fn test1(x: &mut Lb) {
+    *x -= 1; //~ ERROR assignment might be unsafe
+}
+
+// The rust signature can use the type the alias stands for
+#[flux::sig(fn(x: Option<Nat>) -> Option<Nat>)]
+pub fn test2(x: Option<i32>) -> Option<i32> {
+    match x {
+        Some(n) => Some(n - 1), //~ ERROR refinement type
+        None => None,
+    }
+}
@@ -0,0 +1,16 @@
+use flux_rs::assume;
+
+#[flux::sig(fn(i32{v: v > 0}))]
+pub fn assert_pos(_x: i32) {}
+
+pub fn test00(x: i32) {
+    assert_pos(x); //~ ERROR refinement type
+    assume!(x > 0);
+}
+
+pub fn test01(x: i32, b: bool) {
+    if b {
+        assume!(x > 0);
+    }
+    assert_pos(x); //~ ERROR refinement type
+}
@@ -0,0 +1,24 @@
+#![flux::cfg(auto_specs = true)]
+
+fn max(a: i32, b: i32) -> i32 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+// Public functions are not summarized
+pub fn incr(x: u32) -> u32 {
+    x + 1
+}
+
+#[flux::sig(fn(i32[@x], i32[@y]) -> i32{v: v > x})]
+pub fn test00(x: i32, y: i32) -> i32 {
+    max(x, y) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(u32[@x]) -> u32[x + 1])]
+pub fn test01(x: u32) -> u32 {
+    incr(x) //~ ERROR refinement type
+}
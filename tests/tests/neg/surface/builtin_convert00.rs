@@ -0,0 +1,40 @@
+// Conversions between integer types are refined without any extern specs in the crate
+
+#[flux::sig(fn(usize{v: v <= 256}) -> u8)]
+pub fn test_try_from(x: usize) -> u8 {
+    u8::try_from(x).unwrap() //~ ERROR refinement type
+}
+
+#[flux::sig(fn(i32) -> u32)]
+pub fn test_try_into(x: i32) -> u32 {
+    let y: Result<u32, _> = x.try_into();
+    y.unwrap() //~ ERROR refinement type
+}
+
+#[flux::sig(fn(u8[@n]) -> u64[n + 1])]
+pub fn test_from(x: u8) -> u64 {
+    u64::from(x) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(i32[@n]) -> i64{v: v > n})]
+pub fn test_into(x: i32) -> i64 {
+    x.into() //~ ERROR refinement type
+}
+
+#[flux::refined_by(n: int)]
+pub struct Pos {
+    #[flux::field(i32[n])]
+    val: i32,
+}
+
+impl From<Pos> for i32 {
+    #[flux::sig(fn(Pos[@n]) -> i32[n])]
+    fn from(p: Pos) -> i32 {
+        p.val
+    }
+}
+
+#[flux::sig(fn(Pos[@n]) -> i32[n + 1])]
+pub fn test_user_into(p: Pos) -> i32 {
+    p.into() //~ ERROR refinement type
+}
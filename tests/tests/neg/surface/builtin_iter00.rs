@@ -0,0 +1,20 @@
+// Iterators over vectors are indexed by the number of elements left without any extern specs in
+// the crate
+
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_b: bool) {}
+
+#[flux::sig(fn(Vec<i32{v: v >= 0}>))]
+pub fn test_for(v: Vec<i32>) {
+    for x in v {
+        assert(x > 0); //~ ERROR refinement type
+    }
+}
+
+#[flux::sig(fn(Vec<i32>[1]) -> i32)]
+pub fn test_next(v: Vec<i32>) -> i32 {
+    let mut it = v.into_iter();
+    let a = it.next().unwrap();
+    let b = it.next().unwrap(); //~ ERROR refinement type
+    a + b
+}
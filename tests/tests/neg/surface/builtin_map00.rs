@@ -0,0 +1,33 @@
+// Maps are indexed by the set of their keys without any extern specs in the crate
+
+use std::collections::{BTreeMap, HashMap};
+
+pub fn test_get(m: &HashMap<i32, i32>, k: i32) -> i32 {
+    *m.get(&k).unwrap() //~ ERROR refinement type
+}
+
+pub fn test_new(k: i32) -> i32 {
+    let m: HashMap<i32, i32> = HashMap::new();
+    *m.get(&k).unwrap() //~ ERROR refinement type
+}
+
+pub fn test_insert(k: i32) -> i32 {
+    let mut m = HashMap::new();
+    m.insert(k, 1);
+    *m.get(&(k + 1)).unwrap() //~ ERROR refinement type
+}
+
+pub fn test_remove(k: i32) -> i32 {
+    let mut m = HashMap::new();
+    m.insert(k, 1);
+    m.remove(&k);
+    *m.get(&k).unwrap() //~ ERROR refinement type
+}
+
+pub fn test_btree(m: &BTreeMap<i32, i32>, k: i32) -> i32 {
+    if m.contains_key(&k) {
+        0
+    } else {
+        *m.get(&k).unwrap() //~ ERROR refinement type
+    }
+}
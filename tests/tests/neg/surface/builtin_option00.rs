@@ -0,0 +1,28 @@
+// Option is indexed by whether it is a `Some` without any extern specs in the crate
+
+pub fn test_unwrap(x: Option<i32>) -> i32 {
+    x.unwrap() //~ ERROR refinement type
+}
+
+pub fn test_unwrap_none() -> i32 {
+    let x: Option<i32> = None;
+    x.unwrap() //~ ERROR refinement type
+}
+
+pub fn test_is_some(x: Option<i32>) -> i32 {
+    if x.is_some() {
+        0
+    } else {
+        x.unwrap() //~ ERROR refinement type
+    }
+}
+
+#[flux::sig(fn(Option<i32{v: v > 0}>) -> i32{v: v > 0})]
+pub fn test_unwrap_or(x: Option<i32>) -> i32 {
+    x.unwrap_or(0) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(Option<i32>) -> Option<i32>[true])]
+pub fn test_map(x: Option<i32>) -> Option<i32> {
+    x.map(|n| n + 1) //~ ERROR refinement type
+}
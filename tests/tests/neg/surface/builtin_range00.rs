@@ -0,0 +1,32 @@
+// Ranges are indexed by their bounds without any extern specs in the crate
+
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_b: bool) {}
+
+#[flux::sig(fn(n: usize))]
+pub fn test_for(n: usize) {
+    for i in 0..n {
+        assert(i + 1 < n); //~ ERROR refinement type
+    }
+}
+
+#[flux::sig(fn(lo: i32, hi: i32))]
+pub fn test_for_inclusive(lo: i32, hi: i32) {
+    for i in lo..=hi {
+        assert(i < hi); //~ ERROR refinement type
+    }
+}
+
+#[flux::sig(fn(&Vec<i32>[@n], usize[n]) -> i32)]
+pub fn test_index(v: &Vec<i32>, n: usize) -> i32 {
+    let mut sum = 0;
+    for i in 0..=n {
+        sum += v[i]; //~ ERROR refinement type
+    }
+    sum
+}
+
+pub fn test_next() -> i32 {
+    let mut r = 0..0;
+    r.next().unwrap() //~ ERROR refinement type
+}
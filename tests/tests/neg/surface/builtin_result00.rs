@@ -0,0 +1,29 @@
+// Result is indexed by whether it is an `Ok` without any extern specs in the crate
+
+pub fn test_unwrap(x: Result<i32, ()>) -> i32 {
+    x.unwrap() //~ ERROR refinement type
+}
+
+pub fn test_expect(x: Result<i32, ()>) -> i32 {
+    if x.is_ok() {
+        0
+    } else {
+        x.expect("not ok") //~ ERROR refinement type
+    }
+}
+
+#[flux::sig(fn(Result<i32, ()>) -> Result<i32, ()>[true])]
+pub fn test_map(x: Result<i32, ()>) -> Result<i32, ()> {
+    x.map(|n| n + 1) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(Result<i32, ()>) -> Option<i32>[true])]
+pub fn test_ok(x: Result<i32, ()>) -> Option<i32> {
+    x.ok() //~ ERROR refinement type
+}
+
+#[flux::sig(fn(Result<i32, ()>) -> Result<i32, ()>[true])]
+pub fn test_question_mark(x: Result<i32, ()>) -> Result<i32, ()> {
+    let n = x?; //~ ERROR refinement type
+    Ok(n)
+}
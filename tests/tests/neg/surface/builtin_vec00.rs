@@ -0,0 +1,21 @@
+// Vec is indexed by its length without any extern specs in the crate
+
+pub fn test_index(v: &Vec<i32>) -> i32 {
+    v[0] //~ ERROR refinement type
+}
+
+pub fn test_index_push() -> i32 {
+    let mut v = Vec::new();
+    v.push(1);
+    v[1] //~ ERROR refinement type
+}
+
+pub fn test_set(v: &mut Vec<i32>, i: usize) {
+    v[i] = 0; //~ ERROR refinement type
+}
+
+#[flux::sig(fn(Vec<i32>[@n]) -> Vec<i32>[n - 1])]
+pub fn test_pop(mut v: Vec<i32>) -> Vec<i32> {
+    v.pop();
+    v //~ ERROR refinement type
+}
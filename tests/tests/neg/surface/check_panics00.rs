@@ -0,0 +1,23 @@
+#![flux::cfg(check_panics = true)]
+
+pub fn assert_pos(x: i32) -> i32 {
+    assert!(x > 0); //~ ERROR assertion might fail
+    x
+}
+
+#[flux::sig(fn(b: bool) -> i32)]
+pub fn live_branch(b: bool) -> i32 {
+    if b {
+        1
+    } else {
+        unreachable!() //~ ERROR assertion might fail
+    }
+}
+
+#[flux::sig(fn(x: i32{x >= -1}) -> i32)]
+pub fn live_panic(x: i32) -> i32 {
+    if x < 0 {
+        panic!("negative"); //~ ERROR assertion might fail
+    }
+    x
+}
@@ -0,0 +1,50 @@
+// Indices survive deref coercions through the refined signatures of `Deref` and `DerefMut`
+
+use std::{ops::Deref, rc::Rc};
+
+#[flux::sig(fn(&[i32][@n]) -> i32 requires n > 0)]
+fn head(s: &[i32]) -> i32 {
+    s[0]
+}
+
+#[flux::sig(fn(&Vec<i32>[@n]) -> i32)]
+pub fn test_coerce(v: &Vec<i32>) -> i32 {
+    head(v) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(&Vec<i32>[@n]) -> usize[n + 1])]
+pub fn test_slice_len(v: &Vec<i32>) -> usize {
+    let s: &[i32] = v;
+    s.len() //~ ERROR refinement type
+}
+
+#[flux::sig(fn(&mut Vec<i32>[@n]) requires n > 0)]
+pub fn test_deref_mut(v: &mut Vec<i32>) {
+    let s: &mut [i32] = v;
+    s[1] = 0; //~ ERROR assertion might fail
+}
+
+#[flux::refined_by(len: int)]
+pub struct Wrapper {
+    #[flux::field(Vec<i32>[len])]
+    inner: Vec<i32>,
+}
+
+impl Deref for Wrapper {
+    type Target = Vec<i32>;
+
+    #[flux::sig(fn(&Wrapper[@n]) -> &Vec<i32>[n])]
+    fn deref(&self) -> &Vec<i32> {
+        &self.inner
+    }
+}
+
+#[flux::sig(fn(&Wrapper[@n]) -> usize{v: v > n})]
+pub fn test_auto_deref(w: &Wrapper) -> usize {
+    w.len() //~ ERROR refinement type
+}
+
+#[flux::sig(fn(Rc<i32>) -> i32{v: v > 0})]
+pub fn test_rc(x: Rc<i32>) -> i32 {
+    *x //~ ERROR refinement type
+}
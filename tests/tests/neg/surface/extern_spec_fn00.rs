@@ -0,0 +1,10 @@
+use flux_rs::extern_spec;
+
+#[extern_spec(std::cmp)]
+#[flux::sig(fn(i32[@a], i32[@b]) -> i32{v: v >= a && v >= b})]
+fn max(a: i32, b: i32) -> i32;
+
+#[flux::sig(fn(i32) -> i32{v: v > 0})]
+pub fn test00(x: i32) -> i32 {
+    std::cmp::max(x, 0) //~ ERROR refinement type
+}
@@ -0,0 +1,31 @@
+use flux_rs::extern_spec;
+
+// the "existing" trait
+pub trait MyTrait {
+    fn get(&self) -> i32;
+}
+
+// the "extern" spec
+#[extern_spec]
+trait MyTrait {
+    #[flux::sig(fn(&Self) -> i32{v: v >= 0})]
+    fn get(&self) -> i32;
+}
+
+pub struct S;
+
+impl MyTrait for S {
+    fn get(&self) -> i32 {
+        1
+    }
+}
+
+#[flux::sig(fn(&T) -> i32{v: v > 0})]
+pub fn generic<T: MyTrait>(x: &T) -> i32 {
+    x.get() //~ ERROR refinement type
+}
+
+#[flux::sig(fn(&S) -> i32{v: v > 0})]
+pub fn concrete(x: &S) -> i32 {
+    x.get() //~ ERROR refinement type
+}
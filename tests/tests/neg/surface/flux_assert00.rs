@@ -0,0 +1,13 @@
+use flux_rs::flux_assert;
+
+#[flux::sig(fn(x: i32{v: v > 0}))]
+pub fn test00(x: i32) {
+    let y = x - 5;
+    flux_assert!(y > 0); //~ ERROR assertion might fail
+}
+
+pub fn test01(x: i32, y: i32) {
+    if x <= y {
+        flux_assert!(y - x > 0); //~ ERROR assertion might fail
+    }
+}
@@ -0,0 +1,10 @@
+#[flux_rs::sig(fn(x: i32{x > 0}) -> i32{v: v > x})]
+pub fn dec(x: i32) -> i32 {
+    x - 1 //~ ERROR refinement type
+}
+
+#[flux_rs::should_fail]
+#[flux_rs::sig(fn(x: i32) -> i32{v: v > 0})]
+pub fn id(x: i32) -> i32 {
+    x
+}
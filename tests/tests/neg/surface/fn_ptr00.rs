@@ -0,0 +1,31 @@
+pub struct Handler {
+    #[flux::field(fn(i32{v: v > 0}) -> i32{v: v > 0})]
+    pub f: fn(i32) -> i32,
+}
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > x})]
+fn incr(x: i32) -> i32 {
+    x + 1
+}
+
+#[flux::sig(fn(f: fn(i32{v: v > 0}) -> i32{v: v > 0}) -> i32)]
+pub fn apply(f: fn(i32) -> i32) -> i32 {
+    f(0) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(&Handler) -> i32{v: v > 1})]
+pub fn call_field(h: &Handler) -> i32 {
+    (h.f)(5) //~ ERROR refinement type
+}
+
+#[flux::sig(fn() -> i32{v: v > 2})]
+pub fn call_reified() -> i32 {
+    let f: fn(i32) -> i32 = incr;
+    f(1) //~ ERROR refinement type
+}
+
+const INCR: fn(i32) -> i32 = incr;
+
+pub fn call_const() -> i32 {
+    INCR(1) //~ ERROR function pointer
+}
@@ -0,0 +1,6 @@
+// `Vec::push` takes a strong reference in the built-in specs
+
+pub fn reify_strg(v: &mut Vec<i32>) {
+    let f: fn(&mut Vec<i32>, i32) = Vec::push; //~ ERROR function pointers
+    f(v, 0);
+}
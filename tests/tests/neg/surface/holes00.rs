@@ -0,0 +1,21 @@
+#![flux::cfg(warn_holes = true)]
+
+#[flux::sig(fn(i32{v: v > 0}))]
+pub fn assert_pos(_x: i32) {}
+
+pub fn todo_branch(x: i32) {
+    if x > 0 {
+        assert_pos(x);
+    } else {
+        assert_pos(x);
+        todo!() //~ WARN this code path is not checked
+    }
+}
+
+// Only the paths that inevitably end in a hole are not checked
+pub fn partial(x: i32, b: bool) {
+    assert_pos(x); //~ ERROR refinement type
+    if b {
+        todo!() //~ WARN this code path is not checked
+    }
+}
@@ -0,0 +1,35 @@
+// Items inside an ignored module can be included back with `#[flux::ignore(no)]`
+#[flux::ignore]
+mod legacy {
+    use crate::Counter;
+
+    // bad refinement, but no error since we are ignoring this function
+    #[flux::sig(fn(i32, i32))]
+    pub fn malformed(_: i32) {}
+
+    pub fn ignored_fun() {}
+
+    #[flux::ignore(no)]
+    #[flux::sig(fn(bool[true]))]
+    pub fn assert(_: bool) {}
+
+    #[flux::ignore(no)]
+    pub fn test1() {
+        assert(20 < 10); //~ ERROR refinement type error
+    }
+
+    #[flux::ignore(no)]
+    pub fn test2() {
+        ignored_fun(); //~ ERROR use of ignored function
+    }
+
+    #[flux::ignore(no)]
+    impl Counter {
+        #[flux::sig(fn(&Counter, i32{v: v > 0}))]
+        pub fn bump(&self, n: i32) {
+            assert(n > 1); //~ ERROR refinement type error
+        }
+    }
+}
+
+pub struct Counter;
@@ -0,0 +1,50 @@
+// Indexing a user type goes through the refined signatures of its `Index` and `IndexMut` impls
+
+use std::ops::{Index, IndexMut};
+
+#[flux::refined_by(len: int)]
+pub struct Buf {
+    #[flux::field(Vec<i32>[len])]
+    data: Vec<i32>,
+}
+
+impl Buf {
+    #[flux::sig(fn(&Buf[@n]) -> usize[n])]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+impl Index<usize> for Buf {
+    type Output = i32;
+
+    #[flux::sig(fn(&Buf[@n], usize{v: v < n}) -> &i32)]
+    fn index(&self, i: usize) -> &i32 {
+        &self.data[i]
+    }
+}
+
+impl IndexMut<usize> for Buf {
+    #[flux::sig(fn(&mut Buf[@n], usize{v: v < n}) -> &mut i32)]
+    fn index_mut(&mut self, i: usize) -> &mut i32 {
+        &mut self.data[i]
+    }
+}
+
+pub fn first(buf: &Buf) -> i32 {
+    buf[0] //~ ERROR refinement type
+}
+
+#[flux::sig(fn(&mut Buf))]
+pub fn clear(buf: &mut Buf) {
+    let mut i = 0;
+    while i <= buf.len() {
+        buf[i] = 0; //~ ERROR refinement type
+        i += 1;
+    }
+}
+
+#[flux::sig(fn(&Buf[@n], usize{v: v <= n}) -> i32)]
+pub fn get_via_trait(buf: &Buf, i: usize) -> i32 {
+    *Index::index(buf, i) //~ ERROR refinement type
+}
@@ -0,0 +1,28 @@
+#[flux::inline]
+fn max(a: i32, b: i32) -> i32 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+#[flux::inline]
+fn sum(n: i32) -> i32 {
+    if n <= 0 {
+        0
+    } else {
+        n + sum(n - 1)
+    }
+}
+
+#[flux::sig(fn(i32[@x], i32[@y]) -> i32{v: v > x})]
+pub fn test00(x: i32, y: i32) -> i32 {
+    max(x, y) //~ ERROR refinement type
+}
+
+// The recursive call to `sum` uses its (unrefined) signature
+#[flux::sig(fn(i32) -> i32{v: v >= 0})]
+pub fn test01(n: i32) -> i32 {
+    sum(n) //~ ERROR refinement type
+}
@@ -0,0 +1,23 @@
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_b: bool) {}
+
+#[flux::sig(fn(&Vec<i32{v: v >= 0}>))]
+pub fn test_map(v: &Vec<i32>) {
+    for x in v.iter().map(|x| x - 1) {
+        assert(x >= 0); //~ ERROR refinement type
+    }
+}
+
+#[flux::sig(fn(&Vec<i32{v: v >= 0}>))]
+pub fn test_enumerate(v: &Vec<i32>) {
+    for (_, x) in v.iter().enumerate() {
+        assert(*x > 0); //~ ERROR refinement type
+    }
+}
+
+#[flux::sig(fn(&Vec<i32{v: v > 0}>, &Vec<i32>))]
+pub fn test_zip(v1: &Vec<i32>, v2: &Vec<i32>) {
+    for (x, y) in v1.iter().zip(v2.iter()) {
+        assert(*x > *y); //~ ERROR refinement type
+    }
+}
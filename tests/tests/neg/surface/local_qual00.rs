@@ -1,5 +1,5 @@
 #![flux::defs {
-    local qualifier MyQ1(x: int, y: int, z: int) { x + y <= z + 10 }
+    local qualifier MyQ1(x: int, y: int, z: int) { x + y <= z + 10 } //~ WARN qualifier `MyQ1` is never used
 }]
 
 #[path = "../../lib/rvec.rs"]
@@ -0,0 +1,22 @@
+#[flux::sig(fn(x: &mut i32[@n]) ensures x: i32[n + 1])]
+fn incr(x: &mut i32) {
+    *x += 2 //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: &mut i32[@n]) ensures x: i32[n + 1])]
+fn incr_ok(x: &mut i32) {
+    *x += 1;
+}
+
+#[flux::sig(fn() -> i32[1])]
+fn test00() -> i32 {
+    let mut x = 0;
+    incr_ok(&mut x);
+    incr_ok(&mut x);
+    x //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: &mut i32{v: v < 0}))]
+fn test01(x: &mut i32) {
+    incr_ok(x); //~ ERROR refinement type
+}
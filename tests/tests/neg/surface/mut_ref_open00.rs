@@ -0,0 +1,34 @@
+#[path = "../../lib/rvec.rs"]
+mod rvec;
+use rvec::RVec;
+
+#[flux::sig(fn(&mut RVec<i32>))]
+pub fn test00(vec: &mut RVec<i32>) {
+    *vec.get_mut(0) = 1; //~ ERROR refinement type
+}
+
+#[flux::sig(fn(&mut i32[@n]) -> i32[n + 1])]
+fn incr(x: &mut i32) -> i32 {
+    *x + 1
+}
+
+#[flux::sig(fn(&mut i32{v: v > 0}) -> i32{v: v > 1})]
+pub fn test01(x: &mut i32) -> i32 {
+    let y = incr(x);
+    *x = 0; //~ ERROR refinement type
+    y
+}
+
+#[flux::sig(fn(&mut RVec<i32>[@n]))]
+fn clear(vec: &mut RVec<i32>) {
+    if vec.len() > 0 {
+        *vec.get_mut(0) = 0;
+    }
+}
+
+// `clear` can write any `i32` to the vector so its elements cannot be assumed to be positive after
+// the call.
+#[flux::sig(fn(&mut RVec<i32{v: v > 0}>))]
+pub fn test02(vec: &mut RVec<i32>) {
+    clear(vec); //~ ERROR refinement type
+}
@@ -0,0 +1,19 @@
+// `flux::opts` can be attached to modules, impls and functions
+
+#[flux::opts(timeout = 10)]
+mod checked {
+    #[flux::opts(solver = "cvc5")]
+    pub fn add(x: i32, y: i32) -> i32 {
+        x + y
+    }
+
+    pub struct S;
+
+    #[flux::opts(timeout = 60)]
+    impl S {
+        #[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+        pub fn id(x: i32) -> i32 {
+            x //~ ERROR refinement type
+        }
+    }
+}
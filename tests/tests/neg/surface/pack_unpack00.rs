@@ -0,0 +1,23 @@
+use flux_rs::{pack, unpack};
+
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_: bool) {}
+
+#[flux::sig(fn(x: i32{v: v > 0}) -> i32[x])]
+pub fn test00(x: i32) -> i32 {
+    let y = x;
+    pack!(y);
+    y //~ ERROR refinement type
+}
+
+#[flux::sig(fn(r: &mut i32{v: v > 0}))]
+pub fn test01(r: &mut i32) {
+    let a = *r;
+    let b = *r;
+    assert(a == b); //~ ERROR refinement type
+}
+
+#[flux::sig(fn(r: &mut i32{v: v > 0}))]
+pub fn test02(r: &mut i32) {
+    unpack!(*r); //~ ERROR only places owned by the function can be packed or unpacked
+}
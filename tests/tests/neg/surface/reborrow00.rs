@@ -0,0 +1,8 @@
+#[flux::sig(fn(bool) -> i32[5])]
+pub fn test00(b: bool) -> i32 {
+    let mut p = (0, 0);
+    let r1 = &mut p;
+    let r2 = if b { &mut (*r1).0 } else { &mut (*r1).1 };
+    *r2 = 5;
+    p.0 //~ ERROR refinement type
+}
@@ -0,0 +1,17 @@
+#![flux::cfg(unstable_features = "match-exprs")]
+
+#[flux::reflect]
+pub enum Ordering {
+    Less,
+    Equal,
+    Greater,
+}
+
+#[flux::sig(fn(o: Ordering, x: i32, y: i32) -> i32{v: match o { Ordering::Less => v == x, Ordering::Equal => v == x, Ordering::Greater => v == y }})]
+pub fn pick(o: Ordering, x: i32, y: i32) -> i32 {
+    match o {
+        Ordering::Less => x,
+        Ordering::Equal => y, //~ ERROR refinement type
+        Ordering::Greater => y,
+    }
+}
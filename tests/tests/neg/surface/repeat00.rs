@@ -0,0 +1,10 @@
+#[flux::sig(fn() -> [i32{v: v > 0}; 4])]
+pub fn zeros() -> [i32; 4] {
+    [0; 4] //~ ERROR refinement type
+}
+
+#[flux::sig(fn(i32, usize{v: v <= 8}) -> i32)]
+pub fn read(n: i32, i: usize) -> i32 {
+    let arr = [n; 8];
+    arr[i] //~ ERROR assertion might fail
+}
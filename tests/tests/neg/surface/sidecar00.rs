@@ -0,0 +1,11 @@
+// The specs of the items in this file are in `sidecar00.flux`
+
+pub mod generated {
+    pub fn dec(x: i32) -> i32 {
+        x - 1 //~ ERROR refinement type
+    }
+}
+
+pub fn test00() -> i32 {
+    generated::dec(0) //~ ERROR refinement type
+}
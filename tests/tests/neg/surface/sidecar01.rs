@@ -0,0 +1,19 @@
+// The specs of the items in this file are in `sidecar01.flux`
+
+pub mod generated {
+    pub struct Wrapper<T> {
+        pub inner: T,
+    }
+
+    impl Wrapper<i32> {
+        pub fn get(&self) -> i32 {
+            self.inner
+        }
+    }
+
+    impl Wrapper<bool> {
+        pub fn get(&self) -> bool {
+            self.inner
+        }
+    }
+}
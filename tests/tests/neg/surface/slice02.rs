@@ -0,0 +1,4 @@
+#[flux::sig(fn(&mut [i32]{n: n > 0}))]
+pub fn second(arr: &mut [i32]) {
+    arr[1] = 1; //~ ERROR assertion might fail
+}
@@ -0,0 +1,31 @@
+#[flux::sig(fn(i32{v: v > 0}))]
+pub fn assert_pos(_x: i32) {}
+
+#[flux::sig(fn(x: i32{v: v > 0}))]
+pub fn test00(x: i32) {
+    #[flux::assert(x > 5)] //~ ERROR assertion might fail
+    let y = x - 5;
+    assert_pos(y);
+}
+
+pub fn test01(x: i32, y: i32) {
+    #[flux::assume(y > 0)]
+    assert_pos(x); //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: i32{v: v > 0}))]
+pub fn test02(x: i32) {
+    let x = x - x;
+    #[flux::assert(x > 0)] //~ ERROR assertion might fail
+    assert_pos(1);
+}
+
+pub fn test03(x: i32) {
+    #[flux::assert(z > 0)] //~ ERROR cannot find value `z`
+    assert_pos(x);
+}
+
+pub fn test04(x: i32) {
+    #[flux::assert(x + 1)] //~ ERROR mismatched sorts
+    assert_pos(x);
+}
@@ -0,0 +1,14 @@
+#[flux::sig(fn() -> i32[4])]
+pub fn test00() -> i32 {
+    1 + 2 //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: v < x})]
+pub fn test01(x: i32) -> i32 {
+    x //~ ERROR refinement type
+}
+
+#[flux::sig(fn() -> i32{v: 100000000000000000000000000000000000000 * 10 < 0})]
+pub fn test02() -> i32 {
+    0 //~ ERROR refinement type
+}
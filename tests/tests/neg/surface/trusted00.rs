@@ -0,0 +1,39 @@
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_: bool) {}
+
+// A shallow trusted function skips its body, but items nested in it are still checked
+#[flux::trusted(reason = "inner functions are checked")]
+pub fn shallow() {
+    assert(1 > 2);
+
+    fn inner() {
+        assert(1 > 2); //~ ERROR refinement type error
+    }
+    inner();
+}
+
+// A deep trusted function also skips the items nested in it
+#[flux::trusted(deep, reason = "legacy code")]
+pub fn deep() {
+    assert(1 > 2);
+
+    fn inner() {
+        assert(1 > 2);
+    }
+
+    struct S;
+
+    impl S {
+        fn method(&self) {
+            assert(1 > 2);
+        }
+    }
+
+    inner();
+    S.method();
+}
+
+#[flux::trusted(shallow)]
+pub fn explicit_shallow() {
+    assert(1 > 2);
+}
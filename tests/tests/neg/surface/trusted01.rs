@@ -0,0 +1,2 @@
+#[flux::trusted(sometimes)] //~ ERROR invalid flux attribute
+pub fn bad_arg() {}
@@ -0,0 +1,16 @@
+#[flux::sig(fn(x: i32) -> (i32{v: v > 0}, bool[true]))]
+pub fn tuple00(x: i32) -> (i32, bool) {
+    (x, true) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(i32[@n]) -> i32[n])]
+pub fn tuple01(x: i32) -> i32 {
+    let t = ((x, 1), 2);
+    (t.0).1 //~ ERROR refinement type
+}
+
+#[flux::sig(fn(vs: Option<i32{v: 0 <= v}>) -> Option<i32{v: 5 <= v}>)]
+pub fn closure00(vs: Option<i32>) -> Option<i32> {
+    let t = (2, 2);
+    vs.map(|x| x + t.0 + t.1) //~ ERROR refinement type
+}
@@ -0,0 +1,16 @@
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_: bool) {}
+
+#[flux::unpack_policy(deep)]
+#[flux::sig(fn(r: &mut i32{v: v > 0}))]
+pub fn test00(r: &mut i32) {
+    *r = 1; //~ ERROR assignment
+}
+
+#[flux::unpack_policy(shallow)]
+#[flux::sig(fn(r: &mut i32{v: v > 0}))]
+pub fn test01(r: &mut i32) {
+    let a = *r;
+    let b = *r;
+    assert(a == b); //~ ERROR refinement type
+}
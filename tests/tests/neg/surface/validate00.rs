@@ -0,0 +1,40 @@
+#[derive(Debug)]
+pub struct Error;
+
+#[flux::refined_by(n: int)]
+#[flux::invariant(n > 0)]
+pub struct Pos {
+    #[flux::field({i32[n] | n > 0})]
+    n: i32,
+}
+
+impl Pos {
+    // The validation is missing
+    #[flux::sig(fn(x: i32) -> Result<Pos[x], Error>)]
+    pub fn new(x: i32) -> Result<Pos, Error> {
+        Ok(Pos { n: x }) //~ ERROR refinement type
+    }
+
+    // The validation is too weak
+    #[flux::sig(fn(x: i32) -> Result<Pos[x], Error>)]
+    pub fn new_weak(x: i32) -> Result<Pos, Error> {
+        if x >= 0 {
+            Ok(Pos { n: x }) //~ ERROR refinement type
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+#[flux::sig(fn(i32{v: v > 0}) -> i32)]
+pub fn needs_pos(x: i32) -> i32 {
+    x
+}
+
+// The error branch doesn't carry the invariant
+pub fn with_match(x: i32) -> i32 {
+    match Pos::new(x) {
+        Ok(_) => 0,
+        Err(_) => needs_pos(x), //~ ERROR refinement type
+    }
+}
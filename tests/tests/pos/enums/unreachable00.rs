@@ -0,0 +1,38 @@
+#[flux::refined_by(b: bool)]
+pub enum Opt {
+    #[flux::variant(Opt[true])]
+    Some,
+    #[flux::variant(Opt[false])]
+    None,
+}
+
+// The `otherwise` branch of an exhaustive match is unreachable and doesn't
+// contribute to the join point after the match.
+#[flux::sig(fn(Opt) -> i32{v: v > 0})]
+pub fn exhaustive(x: Opt) -> i32 {
+    let r = match x {
+        Opt::Some => 1,
+        Opt::None => 2,
+    };
+    r
+}
+
+// A branch ending in a call to a diverging function doesn't contribute to the
+// join point either.
+#[flux::sig(fn(i32) -> i32{v: v > 0})]
+pub fn diverging(x: i32) -> i32 {
+    let r = if x > 0 { x } else { unsafe { std::hint::unreachable_unchecked() } };
+    r
+}
+
+// All the switches of a nested match jump to the same unreachable `otherwise` block, which is
+// thus a join point. None of the jumps contribute to it.
+#[flux::sig(fn(Opt[@a], Opt[@b]) -> bool[a == b])]
+pub fn same(x: Opt, y: Opt) -> bool {
+    match (x, y) {
+        (Opt::Some, Opt::Some) => true,
+        (Opt::Some, Opt::None) => false,
+        (Opt::None, Opt::Some) => false,
+        (Opt::None, Opt::None) => true,
+    }
+}
@@ -1,6 +1,7 @@
 // We need this right now to not deal with the enum `Some`
 
 #[flux::trusted]
+#[flux::sig(fn(T) -> Option<T>[true])]
 fn some<T>(x: T) -> Option<T> {
     Option::Some(x)
 }
@@ -0,0 +1,27 @@
+#[derive(PartialEq, Eq)]
+#[flux::refined_by(x: int, y: int)]
+pub struct Point {
+    #[flux::field(i32[x])]
+    x: i32,
+    #[flux::field(i32[y])]
+    y: i32,
+}
+
+#[flux::sig(fn(&Point[@p], &Point[@q]) -> bool[p.x == q.x && p.y == q.y])]
+pub fn same(p: &Point, q: &Point) -> bool {
+    p == q
+}
+
+#[flux::sig(fn(&Point[@p], &Point[@q]) -> bool[p.x != q.x || p.y != q.y])]
+pub fn different(p: &Point, q: &Point) -> bool {
+    p != q
+}
+
+#[flux::sig(fn(&Point[@p], &Point{q: q.x > 0}) -> i32{v: v > 0})]
+pub fn refine_branch(p: &Point, q: &Point) -> i32 {
+    if p == q {
+        p.x
+    } else {
+        1
+    }
+}
@@ -0,0 +1,40 @@
+use std::cmp::Ordering;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[flux::refined_by(k: int)]
+pub struct Key {
+    #[flux::field(u32[k])]
+    k: u32,
+}
+
+#[flux::sig(fn(&Key[@a], &Key[@b]) -> bool[a.k < b.k])]
+pub fn lt(a: &Key, b: &Key) -> bool {
+    a < b
+}
+
+#[flux::sig(fn(&Key[@a], &Key[@b]) -> bool[a.k >= b.k])]
+pub fn ge(a: &Key, b: &Key) -> bool {
+    a >= b
+}
+
+#[flux::sig(fn(Key[@a], Key[@b]) -> Key{v: v.k >= a.k && v.k >= b.k})]
+pub fn max_key(a: Key, b: Key) -> Key {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+#[flux::sig(fn(Key[@a], Key[@b]) -> Key{v: v.k <= a.k && v.k <= b.k})]
+pub fn min_key(a: Key, b: Key) -> Key {
+    match a.cmp(&b) {
+        Ordering::Less | Ordering::Equal => a,
+        Ordering::Greater => b,
+    }
+}
+
+#[flux::sig(fn(&Key[@a], &Key[@b]) -> bool{v: v => a.k == b.k})]
+pub fn same(a: &Key, b: &Key) -> bool {
+    matches!(a.partial_cmp(b), Some(Ordering::Equal))
+}
@@ -0,0 +1,26 @@
+pub struct Handler {
+    #[flux::field(fn(i32{v: v > 0}) -> i32{v: v > 0})]
+    pub f: fn(i32) -> i32,
+}
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > x})]
+fn incr(x: i32) -> i32 {
+    x + 1
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+fn clamp(x: i32) -> i32 {
+    if x > 0 {
+        x
+    } else {
+        1
+    }
+}
+
+pub fn test00() -> Handler {
+    Handler { f: incr }
+}
+
+pub fn test01() -> Handler {
+    Handler { f: clamp }
+}
@@ -0,0 +1,29 @@
+pub struct Inner {
+    #[flux::field(i32{v : v > 0})]
+    a: i32,
+    #[flux::field(i32)]
+    b: i32,
+}
+
+pub struct Outer {
+    #[flux::field(Inner)]
+    inner: Inner,
+    #[flux::field(Option<Box<Inner>>)]
+    next: Option<Box<Inner>>,
+}
+
+pub fn baz(o: Outer) {}
+
+pub fn foo() {
+    let mut x = Outer { inner: Inner { a: 1, b: 0 }, next: None };
+    x.inner.a = 2;
+    baz(x);
+}
+
+pub fn bar(mut x: Outer) {
+    if let Some(next) = &mut x.next {
+        next.a = x.inner.a;
+    }
+    x.inner.b = 0;
+    baz(x);
+}
@@ -0,0 +1,47 @@
+#[flux::alias(type Nat = i32{v: 0 <= v})]
+type Nat = i32;
+
+#[flux::alias(type Lb(n: int) = i32{v: n <= v})]
+type Lb = i32;
+
+#[flux::sig(fn(x: Option<Nat>) -> Nat)]
+pub fn test0(x: Option<Nat>) -> Nat {
+    match x {
+        Some(n) => n,
+        None => 0,
+    }
+}
+
+#[flux::sig(fn(x: &mut Lb(10)))]
+pub fn test1(x: &mut Lb) {
+    *x += 1;
+}
+
+#[flux::sig(fn(p: (Nat, Lb(5))) -> Lb(5))]
+pub fn test2(p: (Nat, Lb)) -> Lb {
+    p.0 + p.1
+}
+
+#[flux::sig(fn(xs: [Nat; 2]) -> Nat)]
+pub fn test3(xs: [Nat; 2]) -> Nat {
+    xs[0] + xs[1]
+}
+
+// The rust signature can use the type the alias stands for
+#[flux::sig(fn(x: Option<Nat>, y: &mut Lb(10)) -> (Nat, Lb(10)))]
+pub fn test4(x: Option<i32>, y: &mut i32) -> (i32, i32) {
+    *y += 1;
+    let n = match x {
+        Some(n) => n,
+        None => 0,
+    };
+    (n, *y)
+}
+
+#[flux::sig(fn(n: i32, x: Option<Lb(n)>) -> Lb(n))]
+pub fn test5(n: i32, x: Option<Lb>) -> Lb {
+    match x {
+        Some(m) => m,
+        None => n,
+    }
+}
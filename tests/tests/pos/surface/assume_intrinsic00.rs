@@ -0,0 +1,24 @@
+use flux_rs::assume;
+
+#[flux::sig(fn(i32{v: v > 0}))]
+pub fn assert_pos(_x: i32) {}
+
+#[flux::intrinsic(assume)]
+unsafe fn my_assume(_: bool) {}
+
+pub fn test00(x: i32) {
+    unsafe { my_assume(x > 0) };
+    assert_pos(x);
+}
+
+pub fn test01(x: i32, y: i32) {
+    assume!(x < y);
+    assert_pos(y - x);
+}
+
+pub fn test02(x: i32, b: bool) {
+    if b {
+        assume!(x > 0);
+        assert_pos(x);
+    }
+}
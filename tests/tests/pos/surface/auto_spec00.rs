@@ -0,0 +1,33 @@
+#![flux::cfg(auto_specs = true)]
+
+fn max(a: i32, b: i32) -> i32 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+fn incr(x: u32) -> u32 {
+    x + 1
+}
+
+fn is_pos(x: i32) -> bool {
+    x > 0
+}
+
+#[flux::sig(fn(i32[@x], i32[@y]) -> i32{v: v >= x && v >= y})]
+pub fn test00(x: i32, y: i32) -> i32 {
+    let a = max(x, y);
+    max(a, y)
+}
+
+#[flux::sig(fn(u32[@x]) -> u32[x + 2])]
+pub fn test01(x: u32) -> u32 {
+    incr(incr(x))
+}
+
+#[flux::sig(fn(i32{v: v > 0}) -> bool[true])]
+pub fn test02(x: i32) -> bool {
+    is_pos(x)
+}
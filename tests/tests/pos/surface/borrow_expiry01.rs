@@ -0,0 +1,28 @@
+#[flux::sig(fn(&mut i32{v: v >= 0}))]
+fn bump(x: &mut i32) {
+    *x += 1;
+}
+
+#[flux::sig(fn(n: usize) -> i32{v: v >= 0})]
+pub fn test00(n: usize) -> i32 {
+    let mut x = 0;
+    let mut i = 0;
+    while i < n {
+        let r = &mut x;
+        i += 1;
+        bump(r);
+    }
+    x
+}
+
+#[flux::sig(fn(n: usize) -> i32{v: v >= 0})]
+pub fn test01(n: usize) -> i32 {
+    let mut x = 0;
+    let mut i = 0;
+    while i < n {
+        bump(&mut x);
+        x += 1;
+        i += 1;
+    }
+    x
+}
@@ -0,0 +1,34 @@
+// Iterators over vectors are indexed by the number of elements left without any extern specs in
+// the crate
+
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_b: bool) {}
+
+#[flux::sig(fn(Vec<i32{v: v > 0}>))]
+pub fn test_for(v: Vec<i32>) {
+    for x in v {
+        assert(x > 0);
+    }
+}
+
+#[flux::sig(fn(&Vec<i32{v: v > 0}>))]
+pub fn test_for_ref(v: &Vec<i32>) {
+    for x in v {
+        assert(*x > 0);
+    }
+}
+
+#[flux::sig(fn(Vec<i32>[2]) -> i32)]
+pub fn test_next(v: Vec<i32>) -> i32 {
+    let mut it = v.into_iter();
+    let a = it.next().unwrap();
+    let b = it.next().unwrap();
+    assert(it.next().is_none());
+    a + b
+}
+
+#[flux::sig(fn(&Vec<i32>[@n]) -> usize[n])]
+pub fn test_size_hint(v: &Vec<i32>) -> usize {
+    let it = v.into_iter();
+    it.size_hint().0
+}
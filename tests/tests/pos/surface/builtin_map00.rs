@@ -0,0 +1,52 @@
+// Maps are indexed by the set of their keys without any extern specs in the crate
+
+use std::collections::{BTreeMap, HashMap};
+
+pub fn test_contains_key(m: &HashMap<i32, i32>, k: i32) -> i32 {
+    if m.contains_key(&k) {
+        *m.get(&k).unwrap()
+    } else {
+        0
+    }
+}
+
+pub fn test_insert(k: i32) -> i32 {
+    let mut m = HashMap::new();
+    m.insert(k, 1);
+    m.insert(k + 1, 2);
+    *m.get(&k).unwrap() + *m.get(&(k + 1)).unwrap()
+}
+
+pub fn test_insert_twice(k: i32) -> i32 {
+    let mut m = HashMap::new();
+    m.insert(k, 1);
+    m.insert(k, 2).unwrap()
+}
+
+#[flux::sig(fn(&HashMap<i32, i32>[@m], i32[@k]) -> bool[true] requires set_is_in(k, m.keys))]
+pub fn test_sig(m: &HashMap<i32, i32>, k: i32) -> bool {
+    m.contains_key(&k)
+}
+
+pub fn test_remove(k: i32) -> i32 {
+    let mut m = HashMap::new();
+    m.insert(k, 1);
+    m.insert(k + 1, 2);
+    let v = m.remove(&k).unwrap();
+    v + *m.get(&(k + 1)).unwrap()
+}
+
+pub fn test_btree(k: i32) -> i32 {
+    let mut m = BTreeMap::new();
+    m.insert(k, 1);
+    if m.contains_key(&0) {
+        *m.get(&k).unwrap() + *m.get(&0).unwrap()
+    } else {
+        m.remove(&k).unwrap()
+    }
+}
+
+pub fn test_insert_mut_ref(m: &mut HashMap<i32, i32>, k: i32) -> Option<i32> {
+    m.insert(k, 1);
+    m.remove(&k)
+}
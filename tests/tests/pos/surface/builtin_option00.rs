@@ -0,0 +1,42 @@
+// Option is indexed by whether it is a `Some` without any extern specs in the crate
+
+#[flux::sig(fn(Option<i32>[true]) -> i32)]
+pub fn test_unwrap(x: Option<i32>) -> i32 {
+    x.unwrap()
+}
+
+pub fn test_unwrap_some() -> i32 {
+    let x = Some(1);
+    x.unwrap()
+}
+
+pub fn test_is_some(x: Option<i32>) -> i32 {
+    if x.is_some() {
+        x.unwrap()
+    } else {
+        0
+    }
+}
+
+pub fn test_is_none(x: Option<i32>) -> i32 {
+    if x.is_none() {
+        0
+    } else {
+        x.unwrap()
+    }
+}
+
+#[flux::sig(fn(Option<i32{v: v > 0}>) -> i32{v: v > 0})]
+pub fn test_unwrap_or(x: Option<i32>) -> i32 {
+    x.unwrap_or(1)
+}
+
+#[flux::sig(fn(Option<i32>[@b]) -> Option<i32>[b])]
+pub fn test_map(x: Option<i32>) -> Option<i32> {
+    x.map(|n| n + 1)
+}
+
+#[flux::sig(fn(Option<i32>[true]) -> i32)]
+pub fn test_map_unwrap(x: Option<i32>) -> i32 {
+    x.map(|n| n + 1).unwrap()
+}
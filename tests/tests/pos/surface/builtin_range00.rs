@@ -0,0 +1,41 @@
+// Ranges are indexed by their bounds without any extern specs in the crate
+
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_b: bool) {}
+
+#[flux::sig(fn(n: usize))]
+pub fn test_for(n: usize) {
+    for i in 0..n {
+        assert(i < n);
+    }
+}
+
+#[flux::sig(fn(lo: i32, hi: i32))]
+pub fn test_for_inclusive(lo: i32, hi: i32) {
+    for i in lo..=hi {
+        assert(lo <= i && i <= hi);
+    }
+}
+
+#[flux::sig(fn(&Vec<i32>[@n], usize[n]) -> i32)]
+pub fn test_index(v: &Vec<i32>, n: usize) -> i32 {
+    let mut sum = 0;
+    for i in 0..n {
+        sum += v[i];
+    }
+    sum
+}
+
+pub fn test_next() -> i32 {
+    let mut r = 0..1;
+    let x = r.next().unwrap();
+    assert(x == 0);
+    x
+}
+
+#[flux::sig(fn(usize{v: v > 0}) -> usize)]
+pub fn test_fields(n: usize) -> usize {
+    let r = 0..n;
+    assert(r.start < r.end);
+    r.end
+}
@@ -0,0 +1,43 @@
+// Result is indexed by whether it is an `Ok` without any extern specs in the crate
+
+#[flux::sig(fn(Result<i32, ()>[true]) -> i32)]
+pub fn test_unwrap(x: Result<i32, ()>) -> i32 {
+    x.unwrap()
+}
+
+pub fn test_is_ok(x: Result<i32, ()>) -> i32 {
+    if x.is_ok() {
+        x.expect("checked")
+    } else {
+        0
+    }
+}
+
+pub fn test_is_err(x: Result<i32, i32>) -> i32 {
+    if x.is_err() {
+        x.unwrap_err()
+    } else {
+        x.unwrap()
+    }
+}
+
+#[flux::sig(fn(Result<i32, ()>[@ok]) -> Result<i32, ()>[ok])]
+pub fn test_map(x: Result<i32, ()>) -> Result<i32, ()> {
+    x.map(|n| n + 1)
+}
+
+#[flux::sig(fn(Result<i32, i32>[@ok]) -> Result<i32, bool>[ok])]
+pub fn test_map_err(x: Result<i32, i32>) -> Result<i32, bool> {
+    x.map_err(|n| n > 0)
+}
+
+#[flux::sig(fn(Result<i32, ()>[@ok]) -> Option<i32>[ok])]
+pub fn test_ok(x: Result<i32, ()>) -> Option<i32> {
+    x.ok()
+}
+
+#[flux::sig(fn(Result<i32, ()>[@ok]) -> Result<i32, ()>[ok])]
+pub fn test_question_mark(x: Result<i32, ()>) -> Result<i32, ()> {
+    let n = x?;
+    Ok(n)
+}
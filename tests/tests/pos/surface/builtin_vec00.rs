@@ -0,0 +1,65 @@
+// Vec is indexed by its length without any extern specs in the crate
+
+#[flux::sig(fn() -> Vec<i32>[3])]
+pub fn test_push() -> Vec<i32> {
+    let mut v = Vec::new();
+    v.push(1);
+    v.push(2);
+    v.push(3);
+    v
+}
+
+#[flux::sig(fn(&Vec<i32>[@n]) -> i32 requires n > 0)]
+pub fn test_first(v: &Vec<i32>) -> i32 {
+    v[0]
+}
+
+pub fn test_index() -> i32 {
+    let v = test_push();
+    v[0] + v[1] + v[2]
+}
+
+#[flux::sig(fn(&mut Vec<i32>[@n], usize{i: i < n}))]
+pub fn test_set(v: &mut Vec<i32>, i: usize) {
+    v[i] = 0;
+}
+
+pub fn test_sum(v: &Vec<i32>) -> i32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < v.len() {
+        sum += v[i];
+        i += 1;
+    }
+    sum
+}
+
+#[flux::sig(fn(usize) -> Vec<i32>[1])]
+pub fn test_with_capacity(n: usize) -> Vec<i32> {
+    let mut v = Vec::with_capacity(n);
+    v.push(0);
+    v
+}
+
+#[flux::sig(fn() -> bool[true])]
+pub fn test_is_empty() -> bool {
+    let v: Vec<i32> = Vec::new();
+    v.is_empty()
+}
+
+#[flux::sig(fn(Vec<i32>[@n]) -> Vec<i32>[n - 1] requires n > 0)]
+pub fn test_pop(mut v: Vec<i32>) -> Vec<i32> {
+    v.pop();
+    v
+}
+
+pub fn test_push_mut_ref(v: &mut Vec<i32>) {
+    v.push(1);
+    v.pop();
+}
+
+pub fn test_push_through_helper() -> Vec<i32> {
+    let mut v = Vec::new();
+    test_push_mut_ref(&mut v);
+    v
+}
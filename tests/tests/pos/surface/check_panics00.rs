@@ -0,0 +1,24 @@
+#![flux::cfg(check_panics = true)]
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32)]
+pub fn assert_pos(x: i32) -> i32 {
+    assert!(x > 0);
+    x
+}
+
+#[flux::sig(fn(b: bool[true]) -> i32)]
+pub fn dead_branch(b: bool) -> i32 {
+    if b {
+        1
+    } else {
+        unreachable!()
+    }
+}
+
+#[flux::sig(fn(x: i32{x >= 0}) -> i32)]
+pub fn dead_panic(x: i32) -> i32 {
+    if x < 0 {
+        panic!("negative");
+    }
+    x
+}
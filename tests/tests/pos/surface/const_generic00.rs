@@ -0,0 +1,32 @@
+pub struct Buf<const N: usize> {
+    data: [i32; N],
+}
+
+impl<const N: usize> Buf<N> {
+    pub fn new() -> Self {
+        Buf { data: [0; N] }
+    }
+
+    pub fn data(&self) -> &[i32; N] {
+        &self.data
+    }
+}
+
+pub trait Len {
+    type Elem;
+
+    fn elem(&self) -> Self::Elem;
+}
+
+impl<const N: usize> Len for [i32; N] {
+    type Elem = i32;
+
+    fn elem(&self) -> i32 {
+        0
+    }
+}
+
+pub fn test00() -> i32 {
+    let buf: Buf<4> = Buf::new();
+    buf.data().elem()
+}
@@ -0,0 +1,26 @@
+#[flux::sig(fn(&[i32{v: v >= 0}; N]) -> i32{v: v >= 0})]
+fn sum<const N: usize>(a: &[i32; N]) -> i32 {
+    let mut s = 0;
+    let mut i = 0;
+    while i < a.len() {
+        s += a[i];
+        i += 1;
+    }
+    s
+}
+
+#[flux::sig(fn() -> i32{v: v >= 0})]
+pub fn test00() -> i32 {
+    let a = [1; 8];
+    sum(&a)
+}
+
+fn first<const N: usize>(a: &[i32; N]) -> Option<i32> {
+    let s: &[i32] = a;
+    if s.len() > 0 { Some(s[0]) } else { None }
+}
+
+pub fn test01() -> Option<i32> {
+    let a = [1, 2, 3];
+    first(&a)
+}
@@ -0,0 +1,30 @@
+// Both arrays have length `N`, so checking the bounds of `a` is enough to index into `b`.
+fn dot<const N: usize>(a: &[i32; N], b: &[i32; N]) -> i32 {
+    let mut s = 0;
+    let mut i = 0;
+    while i < a.len() {
+        s += a[i] * b[i];
+        i += 1;
+    }
+    s
+}
+
+pub fn test00() -> i32 {
+    let a = [1, 2, 3];
+    let b = [4, 5, 6];
+    dot(&a, &b)
+}
+
+fn copy<const N: usize>(src: &[i32; N], dst: &mut [i32; N]) {
+    let mut i = 0;
+    while i < src.len() {
+        dst[i] = src[i];
+        i += 1;
+    }
+}
+
+pub fn test01() {
+    let src = [1, 2, 3];
+    let mut dst = [0; 3];
+    copy(&src, &mut dst);
+}
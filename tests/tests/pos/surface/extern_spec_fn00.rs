@@ -0,0 +1,22 @@
+use flux_rs::extern_spec;
+
+#[extern_spec(std::mem)]
+#[flux::sig(fn(a: &strg i32[@x], b: &strg i32[@y]) ensures a: i32[y], b: i32[x])]
+fn swap(a: &mut i32, b: &mut i32);
+
+#[extern_spec(std::cmp)]
+#[flux::sig(fn(i32[@a], i32[@b]) -> i32{v: v >= a && v >= b})]
+fn max(a: i32, b: i32) -> i32;
+
+#[flux::sig(fn() -> i32[1])]
+pub fn test00() -> i32 {
+    let mut x = 0;
+    let mut y = 1;
+    std::mem::swap(&mut x, &mut y);
+    x
+}
+
+#[flux::sig(fn(i32) -> i32{v: v >= 0})]
+pub fn test01(x: i32) -> i32 {
+    std::cmp::max(x, 0)
+}
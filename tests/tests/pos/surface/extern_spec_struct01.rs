@@ -0,0 +1,35 @@
+use flux_rs::extern_spec;
+
+// the "external" struct
+mod external {
+    pub struct Pair {
+        pub fst: i32,
+        pub snd: i32,
+    }
+}
+
+use external::Pair;
+
+#[extern_spec(external)]
+#[flux::refined_by(fst: int, snd: int)]
+struct Pair {
+    #[flux::field(i32[fst])]
+    fst: i32,
+    #[flux::field(i32[snd])]
+    snd: i32,
+}
+
+#[flux::sig(fn() -> Pair[1, 2])]
+pub fn make() -> Pair {
+    Pair { fst: 1, snd: 2 }
+}
+
+#[flux::sig(fn(Pair[@a, @b]) -> i32[a + b])]
+pub fn sum(p: Pair) -> i32 {
+    p.fst + p.snd
+}
+
+#[flux::sig(fn(&Pair[@a, @b]) -> i32[b])]
+pub fn snd(p: &Pair) -> i32 {
+    p.snd
+}
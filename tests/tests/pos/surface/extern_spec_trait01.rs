@@ -0,0 +1,45 @@
+use flux_rs::extern_spec;
+
+// the "existing" trait
+pub trait MyTrait {
+    fn get(&self) -> i32;
+}
+
+// the "extern" spec
+#[extern_spec]
+trait MyTrait {
+    #[flux::sig(fn(&Self) -> i32{v: v > 0})]
+    fn get(&self) -> i32;
+}
+
+pub struct S;
+
+impl MyTrait for S {
+    fn get(&self) -> i32 {
+        1
+    }
+}
+
+pub struct R;
+
+impl MyTrait for R {
+    #[flux::sig(fn(&R) -> i32[2])]
+    fn get(&self) -> i32 {
+        2
+    }
+}
+
+#[flux::sig(fn(&T) -> i32{v: v > 0})]
+pub fn generic<T: MyTrait>(x: &T) -> i32 {
+    x.get()
+}
+
+#[flux::sig(fn(&S) -> i32{v: v > 0})]
+pub fn concrete(x: &S) -> i32 {
+    x.get()
+}
+
+#[flux::sig(fn(&R) -> i32[2])]
+pub fn concrete_with_sig(x: &R) -> i32 {
+    x.get()
+}
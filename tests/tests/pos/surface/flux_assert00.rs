@@ -0,0 +1,17 @@
+use flux_rs::flux_assert;
+
+#[flux::sig(fn(i32{v: v > 0}))]
+pub fn assert_pos(_x: i32) {}
+
+#[flux::sig(fn(x: i32{v: v > 10}))]
+pub fn test00(x: i32) {
+    let y = x - 5;
+    flux_assert!(y > 0);
+    assert_pos(y);
+}
+
+pub fn test01(x: i32, y: i32) {
+    if x < y {
+        flux_assert!(y - x > 0);
+    }
+}
@@ -0,0 +1,35 @@
+#[flux::defs {
+    fn is_even(x: int) -> bool { x % 2 == 0 }
+    local qualifier Sum(x: int, y: int, z: int) { x + y <= z }
+}]
+mod arith {
+    #[flux::sig(fn(x: i32{is_even(x)}) -> i32{v: is_even(v)})]
+    pub fn id(x: i32) -> i32 {
+        x
+    }
+}
+
+// items declared in a module can be used anywhere in the crate
+mod client {
+    #[flux::sig(fn() -> i32{v: is_even(v)})]
+    pub fn four() -> i32 {
+        crate::arith::id(4)
+    }
+
+    #[flux::qualifiers(Sum)]
+    #[flux::sig(fn(n: i32{n >= 0}) -> i32{v: v >= n})]
+    pub fn count(n: i32) -> i32 {
+        let mut i = 0;
+        let mut res = n;
+        while i < n {
+            res += 1;
+            i += 1;
+        }
+        res
+    }
+}
+
+#[flux::sig(fn() -> i32{v: is_even(v)})]
+pub fn zero() -> i32 {
+    0
+}
@@ -0,0 +1,28 @@
+// Specs written with the attribute macros of `flux-rs` are checked like tool attributes
+
+#[flux_rs::refined_by(lo: int, hi: int)]
+#[flux_rs::invariant(lo <= hi)]
+pub struct Range {
+    #[field(i32[lo])]
+    lo: i32,
+    #[field(i32[hi])]
+    hi: i32,
+}
+
+impl Range {
+    #[flux_rs::sig(fn(lo: i32, hi: i32{lo <= hi}) -> Range[lo, hi])]
+    pub fn new(lo: i32, hi: i32) -> Range {
+        Range { lo, hi }
+    }
+
+    #[flux_rs::sig(fn(&Range[@lo, @hi]) -> i32{v: v >= 0 && v == hi - lo})]
+    pub fn len(&self) -> i32 {
+        self.hi - self.lo
+    }
+}
+
+#[flux_rs::trusted]
+#[flux_rs::sig(fn(x: i32) -> i32{v: v > x})]
+pub fn inc(x: i32) -> i32 {
+    x + 1
+}
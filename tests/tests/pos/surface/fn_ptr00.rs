@@ -0,0 +1,40 @@
+pub struct Handler {
+    #[flux::field(fn(i32{v: v > 0}) -> i32{v: v > 0})]
+    pub f: fn(i32) -> i32,
+}
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > x})]
+fn incr(x: i32) -> i32 {
+    x + 1
+}
+
+#[flux::sig(fn(f: fn(i32{v: v > 0}) -> i32{v: v > 0}) -> i32{v: v > 0})]
+pub fn apply(f: fn(i32) -> i32) -> i32 {
+    f(1)
+}
+
+#[flux::sig(fn(&Handler) -> i32{v: v > 0})]
+pub fn call_field(h: &Handler) -> i32 {
+    (h.f)(5)
+}
+
+#[flux::sig(fn() -> i32{v: v > 1})]
+pub fn call_reified() -> i32 {
+    let f: fn(i32) -> i32 = incr;
+    f(1)
+}
+
+pub fn test() -> i32 {
+    apply(incr)
+}
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 0})]
+fn positive(x: i32) -> i32 {
+    x
+}
+
+#[flux::sig(fn(bool) -> i32{v: v > 0})]
+pub fn join_different_sigs(b: bool) -> i32 {
+    let f: fn(i32) -> i32 = if b { incr } else { positive };
+    f(1)
+}
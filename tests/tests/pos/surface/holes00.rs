@@ -0,0 +1,26 @@
+#[flux::sig(fn(i32{v: v > 0}))]
+pub fn assert_pos(_x: i32) {}
+
+pub fn todo_branch(x: i32) {
+    if x > 0 {
+        assert_pos(x);
+    } else {
+        assert_pos(x);
+        todo!()
+    }
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: v > x})]
+pub fn unimplemented_fn(x: i32) -> i32 {
+    assert_pos(x);
+    unimplemented!("later")
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+pub fn todo_after_match(x: i32) -> i32 {
+    let y = match x {
+        0 => todo!(),
+        _ => 1,
+    };
+    y
+}
@@ -0,0 +1,47 @@
+#[flux::inline]
+fn max(a: i32, b: i32) -> i32 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+#[flux::inline]
+fn pos_part(x: i32) -> i32 {
+    max(x, 0)
+}
+
+#[flux::inline]
+fn incr(x: &mut i32) {
+    *x += 1;
+}
+
+#[flux::sig(fn(i32[@x], i32[@y]) -> i32{v: v >= x && v >= y})]
+pub fn test00(x: i32, y: i32) -> i32 {
+    max(x, y)
+}
+
+#[flux::sig(fn(i32[@x]) -> i32{v: v >= x && v >= 0})]
+pub fn test01(x: i32) -> i32 {
+    pos_part(x)
+}
+
+#[flux::sig(fn(i32[@x]) -> i32{v: v > x})]
+pub fn test02(x: i32) -> i32 {
+    let mut y = x;
+    incr(&mut y);
+    y
+}
+
+#[flux::sig(fn(i32[@x], i32[@y], i32[@z]) -> i32{v: v >= x && v >= y && v >= z})]
+pub fn test03(x: i32, y: i32, z: i32) -> i32 {
+    max(max(x, y), z)
+}
+
+#[flux::sig(fn(i32[@x], i32[@y]) -> i32{v: v >= x + y})]
+pub fn test04(x: i32, y: i32) -> i32 {
+    let a = pos_part(x);
+    let b = pos_part(y);
+    max(a + b, x + y)
+}
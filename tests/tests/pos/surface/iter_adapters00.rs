@@ -0,0 +1,45 @@
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_b: bool) {}
+
+#[flux::sig(fn(&Vec<i32{v: v >= 0}>))]
+pub fn test_map(v: &Vec<i32>) {
+    for x in v.iter().map(|x| x + 1) {
+        assert(x > 0);
+    }
+}
+
+#[flux::sig(fn(Vec<i32{v: v >= 0}>))]
+pub fn test_map_next(v: Vec<i32>) {
+    let mut it = v.into_iter().map(|x| x + 1);
+    if let Some(x) = it.next() {
+        assert(x > 0);
+    }
+}
+
+#[flux::sig(fn(&Vec<i32{v: v > 0}>))]
+pub fn test_filter(v: &Vec<i32>) {
+    for x in v.iter().filter(|x| **x > 10) {
+        assert(*x > 0);
+    }
+}
+
+#[flux::sig(fn(&Vec<i32{v: v > 0}>))]
+pub fn test_enumerate(v: &Vec<i32>) {
+    for (_, x) in v.iter().enumerate() {
+        assert(*x > 0);
+    }
+}
+
+#[flux::sig(fn(&Vec<i32{v: v > 0}>, &Vec<i32{v: v < 0}>))]
+pub fn test_zip(v1: &Vec<i32>, v2: &Vec<i32>) {
+    for (x, y) in v1.iter().zip(v2.iter()) {
+        assert(*x > *y);
+    }
+}
+
+#[flux::sig(fn(&Vec<i32{v: v > 0}>))]
+pub fn test_take(v: &Vec<i32>) {
+    for x in v.iter().take(3) {
+        assert(*x > 0);
+    }
+}
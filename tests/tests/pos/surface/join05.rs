@@ -0,0 +1,24 @@
+#[flux::refined_by(a: int, b: int)]
+pub struct Pair {
+    #[flux::field(i32[a])]
+    left: i32,
+    #[flux::field(i32[b])]
+    right: i32,
+}
+
+#[flux::sig(fn(i32, i32, bool) -> Pair{v: v.a == v.b})]
+fn test00(x: i32, y: i32, b: bool) -> Pair {
+    let p = if b { Pair { left: x, right: x } } else { Pair { left: y, right: y } };
+    p
+}
+
+#[flux::sig(fn(n: i32) -> Pair{v: v.a == v.b})]
+fn test01(n: i32) -> Pair {
+    let mut p = Pair { left: 0, right: 0 };
+    let mut i = 0;
+    while i < n {
+        p = Pair { left: i, right: i };
+        i += 1;
+    }
+    p
+}
@@ -0,0 +1,40 @@
+#![flux::cfg(mine_quals = true)]
+
+// test that the qualifier needed for the loop invariant below is mined from the
+// postcondition, without declaring it with `flux::qualifiers` (cf. local_qual00.rs)
+
+#[path = "../../lib/rvec.rs"]
+pub mod rvec;
+
+use rvec::RVec;
+
+#[flux::refined_by(x: int, y:int)]
+pub struct Pair {
+    #[flux::field(i32[x])]
+    pub x: i32,
+    #[flux::field(i32[y])]
+    pub y: i32,
+}
+
+#[flux::sig(fn (a: i32) -> RVec<Pair{v : v.x + v.y <= a + 10 }>)]
+pub fn mk_pairs_with_bound(a: i32) -> RVec<Pair> {
+    let mut i = 0;
+    let mut res = RVec::new();
+    while i < a {
+        let p = Pair { x: i + 10, y: a - i };
+        res.push(p);
+        i += 1;
+    }
+    return res;
+}
+
+#[flux::sig(fn(lo: usize, hi: usize{lo <= hi}) -> usize[hi - lo])]
+pub fn count(lo: usize, hi: usize) -> usize {
+    let mut i = lo;
+    let mut res = 0;
+    while i < hi {
+        res += 1;
+        i += 1;
+    }
+    res
+}
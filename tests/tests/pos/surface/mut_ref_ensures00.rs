@@ -0,0 +1,22 @@
+#[flux::sig(fn(x: &mut i32[@n]) ensures x: i32[n + 1])]
+fn incr(x: &mut i32) {
+    *x += 1;
+}
+
+#[flux::sig(fn() -> i32[2])]
+fn test00() -> i32 {
+    let mut x = 0;
+    incr(&mut x);
+    incr(&mut x);
+    x
+}
+
+#[flux::sig(fn(x: &mut i32{v: v >= 0}) ensures x: i32{v: v > 0})]
+fn test01(x: &mut i32) {
+    incr(x);
+}
+
+#[flux::sig(fn(x: &mut i32{v: v >= 0}))]
+fn test02(x: &mut i32) {
+    incr(x);
+}
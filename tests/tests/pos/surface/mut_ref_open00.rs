@@ -0,0 +1,32 @@
+#[path = "../../lib/rvec.rs"]
+mod rvec;
+use rvec::RVec;
+
+#[flux::sig(fn(&mut RVec<i32>{v: v > 0}))]
+pub fn test00(vec: &mut RVec<i32>) {
+    *vec.get_mut(0) = 1;
+}
+
+#[flux::sig(fn(&mut i32[@n]) -> i32[n + 1])]
+fn incr(x: &mut i32) -> i32 {
+    *x + 1
+}
+
+#[flux::sig(fn(&mut i32{v: v > 0}) -> i32{v: v > 1})]
+pub fn test01(x: &mut i32) -> i32 {
+    let y = incr(x);
+    *x = 3;
+    y
+}
+
+#[flux::sig(fn(&mut RVec<i32{v: v > 0}>[@n]))]
+fn reset(vec: &mut RVec<i32>) {
+    if vec.len() > 0 {
+        *vec.get_mut(0) = 1;
+    }
+}
+
+#[flux::sig(fn(&mut RVec<i32{v: v > 0}>))]
+pub fn test02(vec: &mut RVec<i32>) {
+    reset(vec);
+}
@@ -0,0 +1,34 @@
+#[flux::refined_by(a: int, b: int)]
+pub struct Inner {
+    #[flux::field(i32[a])]
+    a: i32,
+    #[flux::field(i32[b])]
+    b: i32,
+}
+
+#[flux::refined_by(x: int, y: int, z: int)]
+pub struct Outer {
+    #[flux::field(Inner[x, y])]
+    inner: Inner,
+    #[flux::field(i32[z])]
+    z: i32,
+}
+
+#[flux::sig(fn(Outer[@x, @y, @z]) -> Outer[x, 42, z])]
+pub fn test00(mut o: Outer) -> Outer {
+    o.inner.b = 42;
+    o
+}
+
+#[flux::sig(fn(o: &strg Outer[@x, @y, @z]) ensures o: Outer[x, y + 1, z])]
+pub fn test01(o: &mut Outer) {
+    o.inner.b += 1;
+}
+
+#[flux::sig(fn(Outer[@x, @y, @z]) -> Outer[y, x, z])]
+pub fn test02(mut o: Outer) -> Outer {
+    let tmp = o.inner.a;
+    o.inner.a = o.inner.b;
+    o.inner.b = tmp;
+    o
+}
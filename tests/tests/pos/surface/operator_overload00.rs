@@ -0,0 +1,76 @@
+// Operators on user types go through the refined signatures of the impls of the operator traits
+
+use std::ops::{Add, Sub};
+
+#[flux::refined_by(n: int)]
+#[flux::invariant(n >= 0)]
+#[derive(Clone, Copy)]
+pub struct Nat {
+    #[flux::field(u32[n])]
+    val: u32,
+}
+
+impl Nat {
+    #[flux::sig(fn(u32[@n]) -> Nat[n])]
+    pub fn new(val: u32) -> Nat {
+        Nat { val }
+    }
+}
+
+impl Add for Nat {
+    type Output = Nat;
+
+    #[flux::sig(fn(Nat[@a], Nat[@b]) -> Nat[a + b])]
+    fn add(self, other: Nat) -> Nat {
+        Nat { val: self.val + other.val }
+    }
+}
+
+impl Sub for Nat {
+    type Output = Nat;
+
+    #[flux::sig(fn(Nat[@a], Nat{b: b <= a}) -> Nat[a - b])]
+    fn sub(self, other: Nat) -> Nat {
+        Nat { val: self.val - other.val }
+    }
+}
+
+impl PartialEq for Nat {
+    #[flux::sig(fn(&Nat[@a], &Nat[@b]) -> bool[a == b])]
+    fn eq(&self, other: &Nat) -> bool {
+        self.val == other.val
+    }
+
+    #[flux::sig(fn(&Nat[@a], &Nat[@b]) -> bool[a != b])]
+    fn ne(&self, other: &Nat) -> bool {
+        self.val != other.val
+    }
+}
+
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_: bool) {}
+
+#[flux::sig(fn(Nat[@a], Nat[@b]) -> Nat[a + b])]
+pub fn test_add(a: Nat, b: Nat) -> Nat {
+    a + b
+}
+
+#[flux::sig(fn(Nat[@a], Nat[@b]) -> Nat[a])]
+pub fn test_sub(a: Nat, b: Nat) -> Nat {
+    a + b - b
+}
+
+pub fn test_eq(a: Nat) {
+    let b = a + Nat::new(1);
+    assert(a != b);
+    assert(b == a + Nat::new(1));
+}
+
+#[flux::sig(fn(Nat[@a], Nat[@b]) -> Nat)]
+pub fn test_sub_guarded(a: Nat, b: Nat) -> Nat {
+    if a == b {
+        a - b
+    } else {
+        a
+    }
+}
@@ -0,0 +1,20 @@
+use flux_rs::{pack, unpack};
+
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_: bool) {}
+
+#[flux::sig(fn(x: i32{v: v > 0}) -> i32{v: v > 0})]
+pub fn test00(x: i32) -> i32 {
+    let y = x;
+    pack!(y);
+    y
+}
+
+#[flux::sig(fn(r: &mut i32{v: v > 0}))]
+pub fn test01(r: &mut i32) {
+    unpack!(r);
+    let a = *r;
+    let b = *r;
+    assert(a == b);
+    assert(a > 0);
+}
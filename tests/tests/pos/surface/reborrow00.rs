@@ -0,0 +1,30 @@
+#[flux::sig(fn(bool) -> i32[7])]
+pub fn test00(b: bool) -> i32 {
+    let mut p = (0, 0);
+    let r1 = &mut p;
+    let r2 = if b { &mut (*r1).0 } else { &mut (*r1).1 };
+    *r2 = 5;
+    (*r1).0 = 7;
+    p.0
+}
+
+#[flux::sig(fn(bool) -> i32{v: v >= 0})]
+pub fn test01(b: bool) -> i32 {
+    let mut p = (0, 0);
+    let r1 = &mut p;
+    let r2 = if b { &mut (*r1).0 } else { &mut (*r1).1 };
+    *r2 = 5;
+    p.0 + p.1
+}
+
+pub struct S {
+    a: i32,
+    b: i32,
+}
+
+#[flux::sig(fn(s: &strg S, bool) ensures s: S)]
+pub fn test02(s: &mut S, b: bool) {
+    let r = if b { &mut s.a } else { &mut s.b };
+    *r = 1;
+    s.b = 3;
+}
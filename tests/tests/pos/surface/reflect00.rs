@@ -0,0 +1,34 @@
+#![flux::cfg(unstable_features = "match-exprs")]
+
+#[flux::reflect]
+pub enum Ordering {
+    Less,
+    Equal,
+    Greater,
+}
+
+#[flux::sig(fn(x: i32, y: i32) -> Ordering[if x < y { Ordering::Less } else if x == y { Ordering::Equal } else { Ordering::Greater }])]
+pub fn cmp(x: i32, y: i32) -> Ordering {
+    if x < y {
+        Ordering::Less
+    } else if x == y {
+        Ordering::Equal
+    } else {
+        Ordering::Greater
+    }
+}
+
+#[flux::sig(fn(o: Ordering, x: i32, y: i32) -> i32{v: match o { Ordering::Less => v == x, Ordering::Equal => v == x, Ordering::Greater => v == y }})]
+pub fn pick(o: Ordering, x: i32, y: i32) -> i32 {
+    match o {
+        Ordering::Less => x,
+        Ordering::Equal => x,
+        Ordering::Greater => y,
+    }
+}
+
+#[flux::sig(fn(x: i32, y: i32) -> i32{v: v >= x && v >= y})]
+pub fn max(x: i32, y: i32) -> i32 {
+    let o = cmp(x, y);
+    pick(o, y, x)
+}
@@ -0,0 +1,22 @@
+#[flux::sig(fn() -> [i32{v: v >= 0}; 4])]
+pub fn zeros() -> [i32; 4] {
+    [0; 4]
+}
+
+#[flux::sig(fn(i32{v: v > 0}) -> [i32{v: v > 0}; 3])]
+pub fn fill(x: i32) -> [i32; 3] {
+    [x; 3]
+}
+
+#[flux::sig(fn(i32[@n]) -> i32[n])]
+pub fn read(n: i32) -> i32 {
+    let arr = [n; 8];
+    arr[7]
+}
+
+#[flux::sig(fn() -> [i32{v: v >= 0}; 4])]
+pub fn update() -> [i32; 4] {
+    let mut arr = [0; 4];
+    arr[1] = 3;
+    arr
+}
@@ -0,0 +1,13 @@
+#[flux::sig(fn(i32{v: v > 0}))]
+pub fn assert_pos(_x: i32) {}
+
+#[flux::should_fail]
+pub fn call_with_zero() {
+    assert_pos(0);
+}
+
+#[flux::should_fail]
+#[flux::sig(fn(x: i32) -> i32{v: v > x})]
+pub fn inc(x: i32) -> i32 {
+    x
+}
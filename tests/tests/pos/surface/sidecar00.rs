@@ -0,0 +1,50 @@
+// The specs of the items in this file are in `sidecar00.flux`
+
+pub mod generated {
+    pub struct Counter {
+        pub count: i32,
+    }
+
+    impl Counter {
+        pub fn new() -> Counter {
+            Counter { count: 0 }
+        }
+
+        pub fn incr(&mut self) {
+            self.count += 1;
+        }
+    }
+
+    pub fn inc(x: i32) -> i32 {
+        x + 1
+    }
+
+    pub trait Get {
+        fn get(&self) -> i32;
+    }
+
+    impl Get for Counter {
+        fn get(&self) -> i32 {
+            self.count
+        }
+    }
+}
+
+#[flux::sig(fn() -> i32{v: v > 1})]
+pub fn test00() -> i32 {
+    generated::inc(generated::inc(1))
+}
+
+#[flux::sig(fn() -> i32[2])]
+pub fn test01() -> i32 {
+    let mut counter = generated::Counter::new();
+    counter.incr();
+    counter.incr();
+    counter.count
+}
+
+#[flux::sig(fn() -> i32[0])]
+pub fn test02() -> i32 {
+    use generated::Get;
+    generated::Counter::new().get()
+}
@@ -0,0 +1,14 @@
+#[flux::sig(fn(&mut [i32]{n: n > 0}))]
+pub fn first(arr: &mut [i32]) {
+    arr[0] = 1;
+}
+
+#[flux::sig(fn(&mut [i32][@n], usize{v: v < n}))]
+pub fn set(arr: &mut [i32], i: usize) {
+    arr[i] = 0;
+}
+
+#[flux::sig(fn(&mut [i32; 3]))]
+pub fn last(arr: &mut [i32; 3]) {
+    arr[2] = 0;
+}
@@ -0,0 +1,7 @@
+#![flux::cfg(solver = "z3")]
+
+#[flux::solver(z3)]
+#[flux::sig(fn(x: i32{v: v > 0}) -> i32{v: v > 1})]
+pub fn test00(x: i32) -> i32 {
+    x + 1
+}
@@ -0,0 +1,24 @@
+#[flux::sig(fn(i32{v: v > 0}))]
+pub fn assert_pos(_x: i32) {}
+
+#[flux::sig(fn(x: i32{v: v > 10}))]
+pub fn test00(x: i32) {
+    #[flux::assert(x - 5 > 0)]
+    let y = x - 5;
+    assert_pos(y);
+}
+
+pub fn test01(x: i32) {
+    #[flux::assume(x > 0)]
+    assert_pos(x);
+}
+
+#[flux::sig(fn(x: i32{v: v > 0}))]
+pub fn test02(x: i32) {
+    let x = x + 1;
+    let r = &x;
+    #[flux::assert(r > 1 && (if x > 2 { r > 2 } else { true }))]
+    {
+        assert_pos(x - 1);
+    }
+}
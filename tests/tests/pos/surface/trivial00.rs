@@ -0,0 +1,20 @@
+#[flux::sig(fn() -> i32[3])]
+pub fn test00() -> i32 {
+    1 + 2
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: v <= x})]
+pub fn test01(x: i32) -> i32 {
+    x
+}
+
+#[flux::sig(fn() -> i32{v: v == -2 * 3})]
+pub fn test02() -> i32 {
+    -6
+}
+
+// Folding the product overflows an `i128` so the obligation is left to the solver
+#[flux::sig(fn() -> i32{v: 100000000000000000000000000000000000000 * 10 > 0})]
+pub fn test03() -> i32 {
+    0
+}
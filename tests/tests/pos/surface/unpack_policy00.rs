@@ -0,0 +1,23 @@
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_: bool) {}
+
+#[flux::unpack_policy(deep)]
+#[flux::sig(fn(r: &mut i32{v: v > 0}))]
+pub fn test00(r: &mut i32) {
+    let a = *r;
+    let b = *r;
+    assert(a == b);
+    assert(a > 0);
+}
+
+#[flux::unpack_policy(shallow)]
+#[flux::sig(fn(x: i32{v: v > 0}) -> i32{v: v > 1})]
+pub fn test01(x: i32) -> i32 {
+    x + 1
+}
+
+#[flux::unpack_policy(default)]
+#[flux::sig(fn(r: &mut i32{v: v > 0}))]
+pub fn test02(r: &mut i32) {
+    *r = 1;
+}
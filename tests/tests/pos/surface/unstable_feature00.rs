@@ -0,0 +1,6 @@
+// compile-flags: -Zflux-unstable-features=smt-backend
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 1})]
+pub fn inc(x: i32) -> i32 {
+    x + 1
+}
@@ -0,0 +1,60 @@
+// The "parse, don't validate" pattern: a constructor that validates its input
+// and returns a `Result` whose `Ok` payload carries the invariant.
+
+#[derive(Debug)]
+pub struct Error;
+
+#[derive(Debug)]
+pub enum AppError {
+    Validation(Error),
+}
+
+#[flux::refined_by(n: int)]
+#[flux::invariant(n > 0)]
+pub struct Pos {
+    #[flux::field({i32[n] | n > 0})]
+    n: i32,
+}
+
+impl Pos {
+    #[flux::sig(fn(x: i32) -> Result<Pos[x], Error>)]
+    pub fn new(x: i32) -> Result<Pos, Error> {
+        if x > 0 {
+            Ok(Pos { n: x })
+        } else {
+            Err(Error)
+        }
+    }
+
+    #[flux::sig(fn(&Pos[@n]) -> i32[n])]
+    pub fn get(&self) -> i32 {
+        self.n
+    }
+}
+
+#[flux::sig(fn(i32{v: v > 0}) -> i32)]
+pub fn needs_pos(x: i32) -> i32 {
+    x
+}
+
+// `?` propagates the error and keeps the refinement of the `Ok` payload
+#[flux::sig(fn(x: i32) -> Result<i32[x], Error>)]
+pub fn with_try(x: i32) -> Result<i32, Error> {
+    let p = Pos::new(x)?;
+    needs_pos(p.get());
+    Ok(p.get())
+}
+
+// `map_err` only changes the error so the payload is still positive
+pub fn with_map_err(x: i32) -> Result<i32, AppError> {
+    let p = Pos::new(x).map_err(|e| AppError::Validation(e))?;
+    Ok(needs_pos(p.get()))
+}
+
+// A match on the result recovers the invariant in the `Ok` branch
+pub fn with_match(x: i32) -> i32 {
+    match Pos::new(x) {
+        Ok(p) => needs_pos(p.get()),
+        Err(_) => 0,
+    }
+}
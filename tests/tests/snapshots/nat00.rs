@@ -0,0 +1,4 @@
+#[flux::sig(fn(i32{v: v > 0}) -> i32{v: v >= 0})]
+pub fn nat(x: i32) -> i32 {
+    x
+}
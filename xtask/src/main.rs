@@ -30,11 +30,16 @@ xflags::xflags! {
         cmd install {
             /// Build the flux-driver binary in debug mode (with the 'dev' profile) instead of release mode
             optional --debug
+            /// Path of the fixpoint executable to bundle with the installation. If not given, the
+            /// executable found in `$PATH` is bundled.
+            optional --fixpoint path: PathBuf
         }
         /// Uninstall flux binaries and libraries
         cmd uninstall { }
         /// Generate precompiled libraries
         cmd build-sysroot { }
+        /// Compile the crates with the built-in specs. Requires the flux binaries to be built.
+        cmd build-prelude { }
         /// Build the documentation
         cmd doc {
             optional -o,--open
@@ -64,6 +69,10 @@ fn main() -> anyhow::Result<()> {
         XtaskCmd::Install(args) => install(&sh, &args),
         XtaskCmd::Doc(args) => doc(sh, args),
         XtaskCmd::BuildSysroot(_) => build_sysroot(&sh),
+        XtaskCmd::BuildPrelude(_) => {
+            let flux_path = find_flux_path();
+            build_prelude(&sh, &flux_path, flux_path.parent().unwrap())
+        }
         XtaskCmd::Uninstall(_) => uninstall(&sh),
         XtaskCmd::Expand(args) => expand(&sh, args),
     }
@@ -72,6 +81,8 @@ fn main() -> anyhow::Result<()> {
 fn prepare(sh: &Shell) -> Result<(), anyhow::Error> {
     build_sysroot(sh)?;
     cmd!(sh, "cargo build").run()?;
+    let flux_path = find_flux_path();
+    build_prelude(sh, &flux_path, flux_path.parent().unwrap())?;
     Ok(())
 }
 
@@ -121,6 +132,8 @@ fn install(sh: &Shell, args: &Install) -> anyhow::Result<()> {
     cmd!(sh, "cargo install --path crates/flux-bin --force").run()?;
     install_driver(sh, args)?;
     install_libs(sh, args)?;
+    install_fixpoint(args)?;
+    build_prelude(sh, Path::new("rustc-flux"), &default_sysroot_dir())?;
 
     Ok(())
 }
@@ -151,6 +164,28 @@ fn install_libs(sh: &Shell, args: &Install) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Copies the fixpoint executable to the sysroot so flux doesn't depend on it being in `$PATH`
+fn install_fixpoint(args: &Install) -> anyhow::Result<()> {
+    let Some(src) = args.fixpoint.clone().or_else(|| find_in_path("fixpoint")) else {
+        println!("warning: fixpoint executable not found, it must be in `$PATH` when running flux");
+        return Ok(());
+    };
+    let mut dst = default_sysroot_dir().join("fixpoint");
+    if cfg!(target_os = "windows") {
+        dst.set_extension("exe");
+    }
+    println!("$ cp {} {}", src.display(), dst.display());
+    std::fs::copy(src, dst)?;
+    Ok(())
+}
+
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let name = if cfg!(target_os = "windows") { format!("{name}.exe") } else { name.to_string() };
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(&name))
+        .find(|path| path.is_file())
+}
+
 fn uninstall(sh: &Shell) -> anyhow::Result<()> {
     cmd!(sh, "cargo uninstall -p flux-bin").run()?;
     println!("$ rm -rf ~/.flux");
@@ -185,6 +220,20 @@ fn build_sysroot(sh: &Shell) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Checks the crates with the built-in specs saving their metadata in `sysroot`, where the driver
+/// looks for it. The crates are built in order because the ones for `std` use the specs for `core`.
+fn build_prelude(sh: &Shell, rustc_flux: &Path, sysroot: &Path) -> anyhow::Result<()> {
+    // CODESYNC(prelude-crates) must match the crates in `flux-driver/src/prelude.rs`
+    let _env = sh.push_env(FLUX_SYSROOT, sysroot);
+    let flags = ["--crate-type=rlib", "--edition=2021", "--emit=metadata"];
+    for (crate_name, root) in [("flux_prelude_core", "core.rs"), ("flux_prelude_std", "std.rs")] {
+        let root = Path::new("crates/flux-driver/prelude").join(root);
+        cmd!(sh, "{rustc_flux} {flags...} --crate-name={crate_name} --out-dir {sysroot} {root}")
+            .run()?;
+    }
+    Ok(())
+}
+
 impl Install {
     fn is_release(&self) -> bool {
         !self.debug